@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use async_trait::async_trait;
+use await_tree::InstrumentAwait;
+use bytes::Bytes;
+
+use crate::ops::*;
+use crate::raw::oio::PageOperation;
+use crate::raw::oio::WriteOperation;
+use crate::raw::*;
+use crate::*;
+
+/// Add an instrument await-tree for actor-based applications to the underlying services.
+///
+/// # AwaitTree
+///
+/// await-tree allows developers to dump this execution tree at runtime,
+/// with the span of each Future annotated by instrument_await.
+/// Read more about [await-tree](https://docs.rs/await-tree/latest/await_tree/)
+///
+/// Every operation will be labeled as `opendal::{operation} {path}`, for
+/// example `opendal::read abc/def`, so that we can see which operation and
+/// path a task is blocked on.
+///
+/// # Notes
+///
+/// - Only async operations will be instrumented, blocking operations will
+///   be passed to underlying services directly.
+/// - Futures returned by writers and pagers will be instrumented too.
+/// - Readers are poll-based without a future to attach, so reading data
+///   will be accounted to the span of the caller instead.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::AwaitTreeLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(AwaitTreeLayer)
+///     .finish();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwaitTreeLayer;
+
+impl<A: Accessor> Layer<A> for AwaitTreeLayer {
+    type LayeredAccessor = AwaitTreeAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        AwaitTreeAccessor { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AwaitTreeAccessor<A: Accessor> {
+    inner: A,
+}
+
+/// Build the span label for given operation and path.
+fn span(op: impl Into<&'static str>, path: &str) -> String {
+    format!("opendal::{} {}", op.into(), path)
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for AwaitTreeAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = AwaitTreeWrapper<A::Writer>;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = AwaitTreeWrapper<A::Pager>;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.inner
+            .create_dir(path, args)
+            .instrument_await(span(Operation::CreateDir, path))
+            .await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner
+            .read(path, args)
+            .instrument_await(span(Operation::Read, path))
+            .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner
+            .write(path, args)
+            .instrument_await(span(Operation::Write, path))
+            .await
+            .map(|(rp, w)| (rp, AwaitTreeWrapper::new(w, path)))
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inner
+            .copy(from, to, args)
+            .instrument_await(format!("opendal::{} {} {}", Operation::Copy, from, to))
+            .await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inner
+            .rename(from, to, args)
+            .instrument_await(format!("opendal::{} {} {}", Operation::Rename, from, to))
+            .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner
+            .stat(path, args)
+            .instrument_await(span(Operation::Stat, path))
+            .await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.inner
+            .delete(path, args)
+            .instrument_await(span(Operation::Delete, path))
+            .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner
+            .list(path, args)
+            .instrument_await(span(Operation::List, path))
+            .await
+            .map(|(rp, p)| (rp, AwaitTreeWrapper::new(p, path)))
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner
+            .scan(path, args)
+            .instrument_await(span(Operation::Scan, path))
+            .await
+            .map(|(rp, p)| (rp, AwaitTreeWrapper::new(p, path)))
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.inner
+            .batch(args)
+            .instrument_await(format!("opendal::{}", Operation::Batch))
+            .await
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.inner
+            .presign(path, args)
+            .instrument_await(span(Operation::Presign, path))
+            .await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+pub struct AwaitTreeWrapper<R> {
+    inner: R,
+    path: String,
+}
+
+impl<R> AwaitTreeWrapper<R> {
+    fn new(inner: R, path: &str) -> Self {
+        Self {
+            inner,
+            path: path.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl<R: oio::Write> oio::Write for AwaitTreeWrapper<R> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.inner
+            .write(bs)
+            .instrument_await(span(WriteOperation::Write, &self.path))
+            .await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.inner
+            .append(bs)
+            .instrument_await(span(WriteOperation::Append, &self.path))
+            .await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner
+            .abort()
+            .instrument_await(span(WriteOperation::Abort, &self.path))
+            .await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner
+            .close()
+            .instrument_await(span(WriteOperation::Close, &self.path))
+            .await
+    }
+}
+
+#[async_trait]
+impl<R: oio::Page> oio::Page for AwaitTreeWrapper<R> {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        self.inner
+            .next()
+            .instrument_await(span(PageOperation::Next, &self.path))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use await_tree::Config;
+    use await_tree::Registry;
+
+    use super::*;
+    use crate::services;
+
+    #[tokio::test]
+    async fn test_await_tree() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(AwaitTreeLayer)
+            .finish();
+
+        let mut registry = Registry::new(Config::default());
+        let root = registry.register(0, "root");
+
+        root.instrument(async {
+            op.write("test", "Hello, World!").await.unwrap();
+            let tree = await_tree::current_tree().unwrap().to_string();
+            assert!(tree.starts_with("root"), "{tree}");
+        })
+        .await;
+    }
+}
@@ -0,0 +1,315 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::SeekFrom;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::runtime::Handle;
+
+use crate::ops::*;
+use crate::raw::oio::ReadExt;
+use crate::raw::*;
+use crate::*;
+
+/// Add blocking API support for non-blocking services.
+///
+/// Most services only implement async operations. BlockingLayer will
+/// implement the blocking operations by running the async ones on given
+/// tokio runtime, so that [`BlockingOperator`] can be used with them.
+///
+/// # Notes
+///
+/// - BlockingLayer requires a tokio runtime. Use [`BlockingLayer::create`]
+///   to capture the runtime of current context, or [`BlockingLayer::new`]
+///   to specify it explicitly.
+/// - Blocking operations MUST NOT be called inside an async context (for
+///   example, inside `async fn` running on tokio runtime), which will
+///   dead lock the runtime. Tokio will detect this and panic with `Cannot
+///   start a runtime from within a runtime`. Use `tokio::task::spawn_blocking`
+///   or a plain thread to call blocking operations instead.
+/// - Services that already support blocking operations don't need this
+///   layer. BlockingLayer always implements blocking operations via the
+///   async ones.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use anyhow::Result;
+/// use opendal::layers::BlockingLayer;
+/// use opendal::services;
+/// use opendal::BlockingOperator;
+/// use opendal::Operator;
+///
+/// # fn main() -> Result<()> {
+/// let runtime = tokio::runtime::Runtime::new()?;
+/// // Enter the runtime so that BlockingLayer can capture it.
+/// let _guard = runtime.enter();
+///
+/// let op: BlockingOperator = Operator::new(services::S3::default())?
+///     .layer(BlockingLayer::create()?)
+///     .finish()
+///     .blocking();
+///
+/// let _ = op.read("test")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockingLayer {
+    handle: Handle,
+}
+
+impl BlockingLayer {
+    /// Create a new BlockingLayer with given tokio runtime handle.
+    pub fn new(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    /// Create a new BlockingLayer with the tokio runtime of current context.
+    ///
+    /// Returns an error if there is no tokio runtime in current context.
+    pub fn create() -> Result<Self> {
+        let handle = Handle::try_current().map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "BlockingLayer must be created inside a tokio runtime context",
+            )
+            .set_source(err)
+        })?;
+
+        Ok(Self { handle })
+    }
+}
+
+impl<A: Accessor> Layer<A> for BlockingLayer {
+    type LayeredAccessor = BlockingAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        BlockingAccessor {
+            inner,
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockingAccessor<A: Accessor> {
+    inner: A,
+
+    handle: Handle,
+}
+
+impl<A: Accessor> BlockingAccessor<A> {
+    fn wrap<I>(&self, inner: I) -> BlockingWrapper<I> {
+        BlockingWrapper::new(inner, self.handle.clone())
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for BlockingAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = BlockingWrapper<A::Reader>;
+    type Writer = A::Writer;
+    type BlockingWriter = BlockingWrapper<A::Writer>;
+    type Pager = A::Pager;
+    type BlockingPager = BlockingWrapper<A::Pager>;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    fn metadata(&self) -> AccessorInfo {
+        let mut meta = self.inner.info();
+        let cap = meta.capabilities() | AccessorCapability::Blocking;
+        meta.set_capabilities(cap);
+        meta
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner.write(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.handle.block_on(self.inner.create_dir(path, args))
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.handle
+            .block_on(self.inner.read(path, args))
+            .map(|(rp, r)| (rp, self.wrap(r)))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.handle
+            .block_on(self.inner.write(path, args))
+            .map(|(rp, w)| (rp, self.wrap(w)))
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.handle.block_on(self.inner.copy(from, to, args))
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.handle.block_on(self.inner.rename(from, to, args))
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.handle.block_on(self.inner.stat(path, args))
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.handle.block_on(self.inner.delete(path, args))
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.handle
+            .block_on(self.inner.list(path, args))
+            .map(|(rp, p)| (rp, self.wrap(p)))
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.handle
+            .block_on(self.inner.scan(path, args))
+            .map(|(rp, p)| (rp, self.wrap(p)))
+    }
+}
+
+/// BlockingWrapper converts async reader, writer and pager into their
+/// blocking counterparts.
+pub struct BlockingWrapper<I> {
+    inner: I,
+
+    handle: Handle,
+}
+
+impl<I> BlockingWrapper<I> {
+    fn new(inner: I, handle: Handle) -> Self {
+        Self { inner, handle }
+    }
+}
+
+impl<I: oio::Read + 'static> oio::BlockingRead for BlockingWrapper<I> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.handle.block_on(self.inner.read(buf))
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.handle.block_on(self.inner.seek(pos))
+    }
+
+    fn next(&mut self) -> Option<Result<Bytes>> {
+        self.handle.block_on(self.inner.next())
+    }
+}
+
+impl<I: oio::Write + 'static> oio::BlockingWrite for BlockingWrapper<I> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.handle.block_on(self.inner.write(bs))
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.handle.block_on(self.inner.append(bs))
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.handle.block_on(self.inner.abort())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.handle.block_on(self.inner.close())
+    }
+}
+
+impl<I: oio::Page> oio::BlockingPage for BlockingWrapper<I> {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        self.handle.block_on(self.inner.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::services;
+
+    static RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    });
+
+    fn create_blocking_layer() -> Result<BlockingLayer> {
+        let _guard = RUNTIME.enter();
+        BlockingLayer::create()
+    }
+
+    #[test]
+    fn test_blocking_layer_in_blocking_context() {
+        // No runtime in current context, create must fail.
+        let layer = BlockingLayer::create();
+        assert!(layer.is_err());
+
+        let layer = create_blocking_layer();
+        assert!(layer.is_ok())
+    }
+
+    #[test]
+    fn test_blocking_operations() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(create_blocking_layer().unwrap())
+            .finish()
+            .blocking();
+
+        op.write("test", "Hello, World!").unwrap();
+        assert_eq!(op.stat("test").unwrap().content_length(), 13);
+        assert_eq!(op.read("test").unwrap(), b"Hello, World!");
+        assert_eq!(op.range_read("test", 0..5).unwrap(), b"Hello");
+        op.delete("test").unwrap();
+        assert!(op.stat("test").is_err());
+    }
+
+    #[test]
+    fn test_blocking_in_async_context() {
+        let layer = create_blocking_layer().unwrap();
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(layer)
+            .finish();
+
+        let res = RUNTIME.block_on(RUNTIME.spawn(async move {
+            let _ = op.blocking().stat("test");
+        }));
+        assert!(res.expect_err("must panic").is_panic());
+    }
+}
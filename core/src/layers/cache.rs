@@ -0,0 +1,608 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io;
+use std::mem;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::warn;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Add read-through cache backed by another operator.
+///
+/// # Notes
+///
+/// - `read` will try the cache operator first and fall back to the
+///   underlying services on miss. Whole objects fetched from underlying
+///   services will be written into the cache in background once they have
+///   been read to the end.
+/// - Ranged reads will be served by cache if the object has been cached,
+///   but won't fill the cache on miss.
+/// - Conditional reads (with `if_match` or `if_none_match`) always bypass
+///   the cache.
+/// - `write`, `delete`, `copy` and `rename` through this layer will
+///   invalidate related objects in cache. Writes are invalidated after
+///   the writer has been closed or aborted, fills of reads that started
+///   before the invalidation will be dropped.
+/// - Errors from cache operator will be logged and ignored, reads will
+///   never fail just because the cache is not available.
+///
+/// Objects will be buffered in memory before writing into cache, please
+/// make sure they can fit in memory.
+///
+/// Filling cache in background requires a tokio runtime, the cache will
+/// not be filled if there is no runtime available.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::CacheLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let cache = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .finish();
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(CacheLayer::new(cache))
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheLayer {
+    cache: Operator,
+}
+
+impl CacheLayer {
+    /// Create a new `CacheLayer` with given cache operator.
+    pub fn new(cache: Operator) -> Self {
+        Self { cache }
+    }
+}
+
+impl<A: Accessor> Layer<A> for CacheLayer {
+    type LayeredAccessor = CacheAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        CacheAccessor {
+            inner,
+            cache: Arc::new(Cache::new(self.cache.clone())),
+        }
+    }
+}
+
+/// Number of generation slots, paths are hashed into these slots so that
+/// memory usage is bounded.
+const GENERATION_SLOTS: usize = 256;
+
+/// Cache wraps the cache operator with generations of paths.
+///
+/// Generation of a path is bumped before every invalidation, fills will be
+/// dropped if the generation has been changed since the read started.
+#[derive(Debug)]
+struct Cache {
+    op: Operator,
+    generations: Vec<AtomicU64>,
+    /// Count of fills that are still running in background.
+    filling: AtomicUsize,
+}
+
+impl Cache {
+    fn new(op: Operator) -> Self {
+        Self {
+            op,
+            generations: (0..GENERATION_SLOTS).map(|_| AtomicU64::new(0)).collect(),
+            filling: AtomicUsize::new(0),
+        }
+    }
+
+    fn slot(&self, path: &str) -> &AtomicU64 {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        &self.generations[hasher.finish() as usize % GENERATION_SLOTS]
+    }
+
+    fn generation(&self, path: &str) -> u64 {
+        self.slot(path).load(Ordering::SeqCst)
+    }
+
+    /// Remove given path from cache.
+    ///
+    /// Failure of invalidation will be logged only.
+    async fn invalidate(&self, path: &str) {
+        self.slot(path).fetch_add(1, Ordering::SeqCst);
+
+        if let Err(err) = self.op.inner().delete(path, OpDelete::new()).await {
+            warn!("cache: invalidate {path} failed: {err:?}");
+        }
+    }
+
+    fn blocking_invalidate(&self, path: &str) {
+        self.slot(path).fetch_add(1, Ordering::SeqCst);
+
+        if !self.op.info().can_blocking() {
+            return;
+        }
+
+        if let Err(err) = self.op.inner().blocking_delete(path, OpDelete::new()) {
+            warn!("cache: invalidate {path} failed: {err:?}");
+        }
+    }
+
+    /// Write content read at given generation into cache.
+    async fn fill(&self, path: &str, generation: u64, bs: Vec<u8>) {
+        // Path has been invalidated since the read started.
+        if self.generation(path) != generation {
+            return;
+        }
+
+        if let Err(err) = self.op.write(path, bs).await {
+            warn!("cache: fill {path} failed: {err:?}");
+            return;
+        }
+
+        // Path could be invalidated while we are writing, remove the
+        // stale content in this case.
+        if self.generation(path) != generation {
+            if let Err(err) = self.op.inner().delete(path, OpDelete::new()).await {
+                warn!("cache: remove stale {path} failed: {err:?}");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheAccessor<A: Accessor> {
+    inner: A,
+    cache: Arc<Cache>,
+}
+
+impl<A: Accessor> CacheAccessor<A> {
+    fn wrap<W>(&self, path: &str, w: W) -> CacheWriter<W> {
+        CacheWriter {
+            inner: w,
+            path: path.to_string(),
+            cache: self.cache.clone(),
+        }
+    }
+
+    /// Wait for all background fills finished.
+    #[cfg(test)]
+    async fn wait_fills(&self) {
+        while self.cache.filling.load(Ordering::SeqCst) > 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for CacheAccessor<A> {
+    type Inner = A;
+    type Reader = oio::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = CacheWriter<A::Writer>;
+    type BlockingWriter = CacheWriter<A::BlockingWriter>;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        if args.if_match().is_some() || args.if_none_match().is_some() {
+            return self
+                .inner
+                .read(path, args)
+                .await
+                .map(|(rp, r)| (rp, Box::new(r) as oio::Reader));
+        }
+
+        match self.cache.op.inner().read(path, args.clone()).await {
+            Ok(v) => return Ok(v),
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => warn!("cache: read {path} from cache failed: {err:?}"),
+        }
+
+        let fill = args.range().is_full();
+        // Take the generation before reading so that writes committed
+        // during this read will drop the fill.
+        let generation = self.cache.generation(path);
+        let (rp, r) = self.inner.read(path, args).await?;
+
+        let r: oio::Reader = if fill {
+            let size = rp.metadata().content_length_raw();
+            Box::new(CacheFillReader::new(
+                r,
+                self.cache.clone(),
+                path,
+                generation,
+                size,
+            ))
+        } else {
+            Box::new(r)
+        };
+        Ok((rp, r))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.inner
+            .write(path, args)
+            .await
+            .map(|(rp, w)| (rp, self.wrap(path, w)))
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let rp = self.inner.copy(from, to, args).await?;
+        self.cache.invalidate(to).await;
+        Ok(rp)
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let rp = self.inner.rename(from, to, args).await?;
+        self.cache.invalidate(from).await;
+        self.cache.invalidate(to).await;
+        Ok(rp)
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let rp = self.inner.delete(path, args).await?;
+        self.cache.invalidate(path).await;
+        Ok(rp)
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        let paths: Vec<String> = args
+            .operation()
+            .iter()
+            .map(|(path, _)| path.to_string())
+            .collect();
+
+        let rp = self.inner.batch(args).await?;
+        for path in paths {
+            self.cache.invalidate(&path).await;
+        }
+        Ok(rp)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner
+            .blocking_write(path, args)
+            .map(|(rp, w)| (rp, self.wrap(path, w)))
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let rp = self.inner.blocking_copy(from, to, args)?;
+        self.cache.blocking_invalidate(to);
+        Ok(rp)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let rp = self.inner.blocking_rename(from, to, args)?;
+        self.cache.blocking_invalidate(from);
+        self.cache.blocking_invalidate(to);
+        Ok(rp)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let rp = self.inner.blocking_delete(path, args)?;
+        self.cache.blocking_invalidate(path);
+        Ok(rp)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+/// CacheWriter invalidates the path after closed or aborted, so that the
+/// old content will be kept in cache until new content is committed.
+pub struct CacheWriter<W> {
+    inner: W,
+    path: String,
+    cache: Arc<Cache>,
+}
+
+#[async_trait]
+impl<W: oio::Write> oio::Write for CacheWriter<W> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.write(bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.append(bs).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let result = self.inner.abort().await;
+        self.cache.invalidate(&self.path).await;
+        result
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let result = self.inner.close().await;
+        self.cache.invalidate(&self.path).await;
+        result
+    }
+}
+
+impl<W: oio::BlockingWrite> oio::BlockingWrite for CacheWriter<W> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.write(bs)
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.append(bs)
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        let result = self.inner.abort();
+        self.cache.blocking_invalidate(&self.path);
+        result
+    }
+
+    fn close(&mut self) -> Result<()> {
+        let result = self.inner.close();
+        self.cache.blocking_invalidate(&self.path);
+        result
+    }
+}
+
+/// CacheFillReader will buffer all bytes read from inner, and write them
+/// into cache once reaching the end.
+pub struct CacheFillReader<R> {
+    inner: R,
+
+    cache: Arc<Cache>,
+    path: String,
+    /// The generation of path while this read started.
+    generation: u64,
+    /// The expected size of this object if known.
+    size: Option<u64>,
+    /// Set to `None` if cache filling has been canceled.
+    buf: Option<Vec<Bytes>>,
+    read: u64,
+}
+
+impl<R> CacheFillReader<R> {
+    fn new(inner: R, cache: Arc<Cache>, path: &str, generation: u64, size: Option<u64>) -> Self {
+        Self {
+            inner,
+            cache,
+            path: path.to_string(),
+            generation,
+            size,
+            buf: Some(vec![]),
+            read: 0,
+        }
+    }
+
+    fn push(&mut self, bs: Bytes) {
+        self.read += bs.len() as u64;
+        if let Some(buf) = self.buf.as_mut() {
+            buf.push(bs)
+        }
+
+        // Users could stop reading once got enough bytes, so we can't
+        // wait for EOF here.
+        if Some(self.read) == self.size {
+            self.fill()
+        }
+    }
+
+    /// Write all buffered bytes into cache in background.
+    fn fill(&mut self) {
+        let buf = match self.buf.take() {
+            Some(buf) => buf,
+            None => return,
+        };
+        // Don't fill cache with truncated content.
+        if matches!(self.size, Some(size) if size != self.read) {
+            return;
+        }
+
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+
+        let cache = self.cache.clone();
+        let path = mem::take(&mut self.path);
+        let generation = self.generation;
+        let bs = buf.concat();
+        cache.filling.fetch_add(1, Ordering::SeqCst);
+        handle.spawn(async move {
+            cache.fill(&path, generation, bs).await;
+            cache.filling.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+impl<R: oio::Read> oio::Read for CacheFillReader<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        match self.inner.poll_read(cx, buf) {
+            Poll::Ready(Ok(0)) if !buf.is_empty() => {
+                self.fill();
+                Poll::Ready(Ok(0))
+            }
+            Poll::Ready(Ok(n)) => {
+                self.push(Bytes::copy_from_slice(&buf[..n]));
+                Poll::Ready(Ok(n))
+            }
+            Poll::Ready(Err(err)) => {
+                self.buf = None;
+                Poll::Ready(Err(err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        // Seeking breaks the continuity of bytes, give up filling cache.
+        self.buf = None;
+        self.inner.poll_seek(cx, pos)
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        match self.inner.poll_next(cx) {
+            Poll::Ready(Some(Ok(bs))) => {
+                self.push(bs.clone());
+                Poll::Ready(Some(Ok(bs)))
+            }
+            Poll::Ready(Some(Err(err))) => {
+                self.buf = None;
+                Poll::Ready(Some(Err(err)))
+            }
+            Poll::Ready(None) => {
+                self.fill();
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services;
+    use crate::Builder;
+    use crate::OperatorBuilder;
+
+    fn new_test_op(
+        cache: &Operator,
+    ) -> (
+        CacheAccessor<<services::Memory as Builder>::Accessor>,
+        Operator,
+    ) {
+        let inner = services::Memory::default().build().unwrap();
+        let acc = CacheLayer::new(cache.clone()).layer(inner);
+        let op = OperatorBuilder::new(acc.clone()).finish();
+        (acc, op)
+    }
+
+    #[tokio::test]
+    async fn test_read_through() {
+        let cache = Operator::new(services::Memory::default()).unwrap().finish();
+        let (acc, op) = new_test_op(&cache);
+
+        op.write("test", "Hello, World!").await.unwrap();
+        assert!(cache.stat("test").await.is_err());
+
+        // Ranged reads should not fill cache.
+        let bs = op.range_read("test", 0..5).await.unwrap();
+        assert_eq!(bs, b"Hello");
+        acc.wait_fills().await;
+        assert!(cache.stat("test").await.is_err());
+
+        let bs = op.read("test").await.unwrap();
+        assert_eq!(bs, b"Hello, World!");
+        acc.wait_fills().await;
+        assert_eq!(cache.read("test").await.unwrap(), b"Hello, World!");
+
+        // Ranged reads should be served by cache.
+        cache.write("test", "Hello, Cache!").await.unwrap();
+        let bs = op.range_read("test", 7..).await.unwrap();
+        assert_eq!(bs, b"Cache!");
+
+        // Write should invalidate cache.
+        op.write("test", "Hello, OpenDAL!").await.unwrap();
+        assert!(cache.stat("test").await.is_err());
+        assert_eq!(op.read("test").await.unwrap(), b"Hello, OpenDAL!");
+
+        // Delete should invalidate cache.
+        acc.wait_fills().await;
+        assert_eq!(cache.read("test").await.unwrap(), b"Hello, OpenDAL!");
+        op.delete("test").await.unwrap();
+        assert!(cache.stat("test").await.is_err());
+        assert!(op.read("test").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_after_close() {
+        let cache = Operator::new(services::Memory::default()).unwrap().finish();
+        let (acc, op) = new_test_op(&cache);
+
+        op.write("test", "Hello, World!").await.unwrap();
+        assert_eq!(op.read("test").await.unwrap(), b"Hello, World!");
+        acc.wait_fills().await;
+
+        // Cache should be kept until the writer has been closed.
+        let mut w = op.writer("test").await.unwrap();
+        w.append("Hello, OpenDAL!").await.unwrap();
+        assert_eq!(cache.read("test").await.unwrap(), b"Hello, World!");
+
+        w.close().await.unwrap();
+        assert!(cache.stat("test").await.is_err());
+        assert_eq!(op.read("test").await.unwrap(), b"Hello, OpenDAL!");
+    }
+
+    #[tokio::test]
+    async fn test_drop_stale_fill() {
+        let cache = Operator::new(services::Memory::default()).unwrap().finish();
+        let (acc, op) = new_test_op(&cache);
+
+        op.write("test", "Hello, World!").await.unwrap();
+
+        // Start reading the old content.
+        let mut r = op.reader("test").await.unwrap();
+
+        // Commit new content while the read is in flight.
+        op.write("test", "Hello, OpenDAL!").await.unwrap();
+
+        let mut bs = Vec::new();
+        futures::AsyncReadExt::read_to_end(&mut r, &mut bs)
+            .await
+            .unwrap();
+        assert_eq!(bs, b"Hello, World!");
+
+        // The stale content must not land in cache.
+        acc.wait_fills().await;
+        assert!(cache.stat("test").await.is_err());
+    }
+}
@@ -15,13 +15,17 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
 use std::io;
+use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
+use std::thread;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::FutureExt;
+use parking_lot::Mutex;
 use rand::prelude::*;
 use rand::rngs::StdRng;
 
@@ -41,10 +45,25 @@ use crate::*;
 /// For example: If we specify an error rate of 0.5, there is a 50% chance
 /// of an EOF error for every read operation.
 ///
+/// ChaosLayer supports the following kinds of chaos:
+///
+/// - Errors while reading from readers, controlled by the error ratio
+///   passed to [`ChaosLayer::new`].
+/// - Errors returned by operations, controlled by
+///   [`ChaosLayer::with_operation_error_ratio`].
+/// - Latency injected before every operation, controlled by
+///   [`ChaosLayer::with_latency`].
+/// - Readers truncated after given bytes, controlled by
+///   [`ChaosLayer::with_truncate`].
+///
+/// Injected errors are temporary by default so that they could be retried,
+/// use [`ChaosLayer::with_permanent_error`] to inject permanent errors
+/// instead. Use [`ChaosLayer::with_seed`] to make the chaos reproducible.
+///
 /// # Note
 ///
-/// For now, ChaosLayer only injects read operations. More operations may
-/// be added in the future.
+/// ChaosLayer is designed for tests only and it's gated behind the
+/// `layers-chaos` feature. Please never enable it in production.
 ///
 /// # Examples
 ///
@@ -60,9 +79,33 @@ use crate::*;
 ///     .layer(ChaosLayer::new(0.1))
 ///     .finish();
 /// ```
+///
+/// Inject errors for stat and make it reproducible:
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::ChaosLayer;
+/// use opendal::raw::Operation;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(
+///         ChaosLayer::new(0.0)
+///             .with_operation_error_ratio(Operation::Stat, 0.5)
+///             .with_seed(42),
+///     )
+///     .finish();
+/// ```
 #[derive(Debug, Clone)]
 pub struct ChaosLayer {
     error_ratio: f64,
+    operation_error_ratio: HashMap<Operation, f64>,
+    permanent: bool,
+    latency: Option<Duration>,
+    truncate: Option<u64>,
+    seed: Option<u64>,
 }
 
 impl ChaosLayer {
@@ -76,7 +119,56 @@ impl ChaosLayer {
             (0.0..=1.0).contains(&error_ratio),
             "error_ratio must between 0.0 and 1.0"
         );
-        Self { error_ratio }
+        Self {
+            error_ratio,
+            operation_error_ratio: HashMap::new(),
+            permanent: false,
+            latency: None,
+            truncate: None,
+            seed: None,
+        }
+    }
+
+    /// Set error ratio for given operation.
+    ///
+    /// The operation will fail before calling underlying services.
+    ///
+    /// # Panics
+    ///
+    /// Input error_ratio must in [0.0..=1.0]
+    pub fn with_operation_error_ratio(mut self, op: Operation, error_ratio: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&error_ratio),
+            "error_ratio must between 0.0 and 1.0"
+        );
+        self.operation_error_ratio.insert(op, error_ratio);
+        self
+    }
+
+    /// Inject permanent errors instead of temporary errors.
+    pub fn with_permanent_error(mut self) -> Self {
+        self.permanent = true;
+        self
+    }
+
+    /// Inject latency before every operation.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Truncate readers after given bytes.
+    ///
+    /// Readers will return EOF once `size` bytes have been read.
+    pub fn with_truncate(mut self, size: u64) -> Self {
+        self.truncate = Some(size);
+        self
+    }
+
+    /// Set the seed of random generator to make chaos reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
     }
 }
 
@@ -84,10 +176,15 @@ impl<A: Accessor> Layer<A> for ChaosLayer {
     type LayeredAccessor = ChaosAccessor<A>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
         ChaosAccessor {
             inner,
-            rng: StdRng::from_entropy(),
-            error_ratio: self.error_ratio,
+            rng: Arc::new(Mutex::new(rng)),
+            config: Arc::new(self.clone()),
         }
     }
 }
@@ -95,9 +192,57 @@ impl<A: Accessor> Layer<A> for ChaosLayer {
 #[derive(Debug)]
 pub struct ChaosAccessor<A> {
     inner: A,
-    rng: StdRng,
+    rng: Arc<Mutex<StdRng>>,
 
-    error_ratio: f64,
+    config: Arc<ChaosLayer>,
+}
+
+impl<A> ChaosAccessor<A> {
+    /// Roll the dice for given operation, returns error if unlucky.
+    fn roll(&self, op: Operation) -> Result<()> {
+        let ratio = match self.config.operation_error_ratio.get(&op) {
+            Some(ratio) => *ratio,
+            None => return Ok(()),
+        };
+
+        if self.rng.lock().gen_bool(ratio) {
+            Err(new_chaos_error(op, self.config.permanent))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn chaos(&self, op: Operation) -> Result<()> {
+        if let Some(latency) = self.config.latency {
+            tokio::time::sleep(latency).await;
+        }
+        self.roll(op)
+    }
+
+    fn blocking_chaos(&self, op: Operation) -> Result<()> {
+        if let Some(latency) = self.config.latency {
+            thread::sleep(latency);
+        }
+        self.roll(op)
+    }
+
+    fn new_reader<R>(&self, inner: R) -> ChaosReader<R> {
+        // Every reader has its own rng seeded from accessor's rng, so that
+        // the chaos is still reproducible.
+        let rng = StdRng::seed_from_u64(self.rng.lock().gen());
+
+        ChaosReader::new(inner, rng, self.config.clone())
+    }
+}
+
+fn new_chaos_error(op: impl Into<&'static str>, permanent: bool) -> Error {
+    let err = Error::new(ErrorKind::Unexpected, "I am your chaos!").with_operation(op);
+
+    if permanent {
+        err.set_permanent()
+    } else {
+        err.set_temporary()
+    }
 }
 
 #[async_trait]
@@ -114,40 +259,108 @@ impl<A: Accessor> LayeredAccessor for ChaosAccessor<A> {
         &self.inner
     }
 
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.chaos(Operation::CreateDir).await?;
+        self.inner.create_dir(path, args).await
+    }
+
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.chaos(Operation::Read).await?;
         self.inner
             .read(path, args)
-            .map(|v| v.map(|(rp, r)| (rp, ChaosReader::new(r, self.rng.clone(), self.error_ratio))))
             .await
-    }
-
-    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
-        self.inner
-            .blocking_read(path, args)
-            .map(|(rp, r)| (rp, ChaosReader::new(r, self.rng.clone(), self.error_ratio)))
+            .map(|(rp, r)| (rp, self.new_reader(r)))
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.chaos(Operation::Write).await?;
         self.inner.write(path, args).await
     }
 
-    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        self.inner.blocking_write(path, args)
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.chaos(Operation::Copy).await?;
+        self.inner.copy(from, to, args).await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.chaos(Operation::Rename).await?;
+        self.inner.rename(from, to, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.chaos(Operation::Stat).await?;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.chaos(Operation::Delete).await?;
+        self.inner.delete(path, args).await
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.chaos(Operation::List).await?;
         self.inner.list(path, args).await
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.chaos(Operation::Scan).await?;
         self.inner.scan(path, args).await
     }
 
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.chaos(Operation::Batch).await?;
+        self.inner.batch(args).await
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.chaos(Operation::Presign).await?;
+        self.inner.presign(path, args).await
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.blocking_chaos(Operation::BlockingCreateDir)?;
+        self.inner.blocking_create_dir(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.blocking_chaos(Operation::BlockingRead)?;
+        self.inner
+            .blocking_read(path, args)
+            .map(|(rp, r)| (rp, self.new_reader(r)))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.blocking_chaos(Operation::BlockingWrite)?;
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.blocking_chaos(Operation::BlockingCopy)?;
+        self.inner.blocking_copy(from, to, args)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.blocking_chaos(Operation::BlockingMove)?;
+        self.inner.blocking_rename(from, to, args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.blocking_chaos(Operation::BlockingStat)?;
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.blocking_chaos(Operation::BlockingDelete)?;
+        self.inner.blocking_delete(path, args)
+    }
+
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.blocking_chaos(Operation::BlockingList)?;
         self.inner.blocking_list(path, args)
     }
 
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.blocking_chaos(Operation::BlockingScan)?;
         self.inner.blocking_scan(path, args)
     }
 }
@@ -157,80 +370,284 @@ pub struct ChaosReader<R> {
     inner: R,
     rng: StdRng,
 
-    error_ratio: f64,
+    config: Arc<ChaosLayer>,
+    /// Bytes that have been read since last seek, used for truncating.
+    read: u64,
+    /// The dice rolled for current read, kept until the inner reader
+    /// returns ready so that re-polls will not roll again.
+    lucky: Option<bool>,
 }
 
 impl<R> ChaosReader<R> {
-    fn new(inner: R, rng: StdRng, error_ratio: f64) -> Self {
+    fn new(inner: R, rng: StdRng, config: Arc<ChaosLayer>) -> Self {
         Self {
             inner,
             rng,
-            error_ratio,
+            config,
+            read: 0,
+            lucky: None,
         }
     }
 
     /// If I feel lucky, we can return the correct response. Otherwise,
     /// we need to generate an error.
+    ///
+    /// The dice will only be rolled once for every logical read, call
+    /// `done` after it's finished.
     fn i_feel_lucky(&mut self) -> bool {
-        let point = self.rng.gen_range(0..=100);
-        point >= (self.error_ratio * 100.0) as i32
+        match self.lucky {
+            Some(lucky) => lucky,
+            None => {
+                let lucky = !self.rng.gen_bool(self.config.error_ratio);
+                self.lucky = Some(lucky);
+                lucky
+            }
+        }
+    }
+
+    /// Mark current read as finished, next read will roll the dice again.
+    fn done<T>(&mut self, v: T) -> T {
+        self.lucky = None;
+        v
+    }
+
+    fn unexpected_eof(&self) -> Error {
+        new_chaos_error("chaos", self.config.permanent)
+    }
+
+    /// Returns the max bytes could be read before truncated.
+    fn remaining(&self) -> Option<u64> {
+        self.config
+            .truncate
+            .map(|size| size.saturating_sub(self.read))
+    }
+
+    /// Truncate given buf to make sure we will not read more bytes than
+    /// expected.
+    fn truncate_buf<'a>(&self, buf: &'a mut [u8]) -> &'a mut [u8] {
+        match self.remaining() {
+            Some(remaining) if (remaining as usize) < buf.len() => &mut buf[..remaining as usize],
+            _ => buf,
+        }
     }
 
-    fn unexpected_eof() -> Error {
-        Error::new(ErrorKind::Unexpected, "I am your chaos!")
-            .with_operation("chaos")
-            .set_temporary()
+    fn truncate_bytes(&self, bs: Bytes) -> Bytes {
+        match self.remaining() {
+            Some(remaining) if (remaining as usize) < bs.len() => bs.slice(..remaining as usize),
+            _ => bs,
+        }
     }
 }
 
 impl<R: oio::Read> oio::Read for ChaosReader<R> {
     fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
-        if self.i_feel_lucky() {
-            self.inner.poll_read(cx, buf)
-        } else {
-            Poll::Ready(Err(Self::unexpected_eof()))
+        if !self.i_feel_lucky() {
+            return Poll::Ready(self.done(Err(self.unexpected_eof())));
         }
+        if self.remaining() == Some(0) {
+            return Poll::Ready(self.done(Ok(0)));
+        }
+
+        let buf = self.truncate_buf(buf);
+        let n = futures::ready!(self.inner.poll_read(cx, buf));
+        let n = self.done(n)?;
+        self.read += n as u64;
+        Poll::Ready(Ok(n))
     }
 
     fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
-        if self.i_feel_lucky() {
-            self.inner.poll_seek(cx, pos)
-        } else {
-            Poll::Ready(Err(Self::unexpected_eof()))
+        if !self.i_feel_lucky() {
+            return Poll::Ready(self.done(Err(self.unexpected_eof())));
         }
+
+        let n = futures::ready!(self.inner.poll_seek(cx, pos));
+        let n = self.done(n)?;
+        self.read = 0;
+        Poll::Ready(Ok(n))
     }
 
     fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
-        if self.i_feel_lucky() {
-            self.inner.poll_next(cx)
-        } else {
-            Poll::Ready(Some(Err(Self::unexpected_eof())))
+        if !self.i_feel_lucky() {
+            return Poll::Ready(self.done(Some(Err(self.unexpected_eof()))));
+        }
+        if self.remaining() == Some(0) {
+            return Poll::Ready(self.done(None));
+        }
+
+        let res = futures::ready!(self.inner.poll_next(cx));
+        match self.done(res) {
+            Some(Ok(bs)) => {
+                let bs = self.truncate_bytes(bs);
+                self.read += bs.len() as u64;
+                Poll::Ready(Some(Ok(bs)))
+            }
+            v => Poll::Ready(v),
         }
     }
 }
 
 impl<R: oio::BlockingRead> oio::BlockingRead for ChaosReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        if self.i_feel_lucky() {
-            self.inner.read(buf)
-        } else {
-            Err(Self::unexpected_eof())
+        if !self.i_feel_lucky() {
+            return self.done(Err(self.unexpected_eof()));
+        }
+        if self.remaining() == Some(0) {
+            return self.done(Ok(0));
         }
+
+        let buf = self.truncate_buf(buf);
+        let n = self.inner.read(buf);
+        let n = self.done(n)?;
+        self.read += n as u64;
+        Ok(n)
     }
 
     fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
-        if self.i_feel_lucky() {
-            self.inner.seek(pos)
-        } else {
-            Err(Self::unexpected_eof())
+        if !self.i_feel_lucky() {
+            return self.done(Err(self.unexpected_eof()));
         }
+
+        let n = self.inner.seek(pos);
+        let n = self.done(n)?;
+        self.read = 0;
+        Ok(n)
     }
 
     fn next(&mut self) -> Option<Result<Bytes>> {
-        if self.i_feel_lucky() {
-            self.inner.next()
-        } else {
-            Some(Err(Self::unexpected_eof()))
+        if !self.i_feel_lucky() {
+            return self.done(Some(Err(self.unexpected_eof())));
+        }
+        if self.remaining() == Some(0) {
+            return self.done(None);
+        }
+
+        let res = self.inner.next();
+        match self.done(res) {
+            Some(Ok(bs)) => {
+                let bs = self.truncate_bytes(bs);
+                self.read += bs.len() as u64;
+                Some(Ok(bs))
+            }
+            v => v,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncReadExt;
+    use futures::AsyncSeekExt;
+
+    use super::*;
+    use crate::services;
+
+    #[tokio::test]
+    async fn test_operation_error() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ChaosLayer::new(0.0).with_operation_error_ratio(Operation::Stat, 1.0))
+            .finish();
+
+        op.write("test", "Hello, World!").await.unwrap();
+        let err = op.stat("test").await.expect_err("stat must fail");
+        assert!(err.is_temporary());
+        assert_eq!(op.read("test").await.unwrap(), b"Hello, World!");
+    }
+
+    #[tokio::test]
+    async fn test_reproducible() {
+        async fn run(seed: u64) -> Vec<bool> {
+            let op = Operator::new(services::Memory::default())
+                .unwrap()
+                .layer(
+                    ChaosLayer::new(0.0)
+                        .with_operation_error_ratio(Operation::Stat, 0.5)
+                        .with_seed(seed),
+                )
+                .finish();
+
+            let mut res = Vec::new();
+            for _ in 0..32 {
+                res.push(op.stat("test").await.is_ok());
+            }
+            res
+        }
+
+        assert_eq!(run(42).await, run(42).await);
+    }
+
+    #[tokio::test]
+    async fn test_truncate() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ChaosLayer::new(0.0).with_truncate(5))
+            .finish();
+
+        op.write("test", "Hello, World!").await.unwrap();
+
+        let mut r = op.reader("test").await.unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.unwrap();
+        assert_eq!(bs, b"Hello");
+
+        // Seek should reset the truncation.
+        r.seek(io::SeekFrom::Start(7)).await.unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.unwrap();
+        assert_eq!(bs, b"World");
+    }
+
+    /// PendingReader returns pending before every read.
+    struct PendingReader {
+        pending: bool,
+    }
+
+    impl oio::Read for PendingReader {
+        fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            buf.fill(0);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_seek(&mut self, _: &mut Context<'_>, _: io::SeekFrom) -> Poll<Result<u64>> {
+            unimplemented!()
+        }
+
+        fn poll_next(&mut self, _: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_roll_once_per_read() {
+        let config = Arc::new(ChaosLayer::new(0.5));
+        let mut r = ChaosReader::new(
+            PendingReader { pending: false },
+            StdRng::seed_from_u64(42),
+            config,
+        );
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut buf = [0; 4];
+
+        for _ in 0..32 {
+            match oio::Read::poll_read(&mut r, &mut cx, &mut buf) {
+                Poll::Pending => {
+                    // The dice must be kept while pending.
+                    let lucky = r.lucky;
+                    assert!(lucky.is_some());
+                    assert!(oio::Read::poll_read(&mut r, &mut cx, &mut buf).is_ready());
+                    assert_eq!(r.lucky, None);
+                }
+                Poll::Ready(res) => {
+                    assert!(res.is_err());
+                    assert_eq!(r.lucky, None);
+                }
+            }
         }
     }
 }
@@ -46,7 +46,20 @@ use crate::*;
 ///
 /// # Internal
 ///
-/// So far CompleteLayer will do two completion:
+/// So far CompleteLayer will do three completion:
+///
+/// ## Stat
+///
+/// Path ends with `/` always means a directory and path without it always
+/// means a file. Some services ignore the trailing slash, CompleteLayer will
+/// return `NotFound` if the returning entry mode is not match with its path.
+/// See [`check_path_mode`] for details.
+///
+/// Services without real directories will return `NotFound` for dirs that
+/// are only implied by the entries under them. If the service has hint
+/// [`AccessorHint::ImplicitDir`], CompleteLayer will list the dir in this
+/// case and return a DIR if it has any entry. Services with real
+/// directories won't pay for this extra list.
 ///
 /// ## Read
 ///
@@ -273,6 +286,54 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
         }
     }
 
+    /// Check if the dir could still exist while stat returns `NotFound`.
+    ///
+    /// Only services without real directories need this check, others
+    /// won't pay for the extra list.
+    fn is_implicit_dir(&self, err: &Error, path: &str) -> bool {
+        err.kind() == ErrorKind::NotFound
+            && path.ends_with('/')
+            && self.meta.hints().contains(AccessorHint::ImplicitDir)
+    }
+
+    /// Check if given dir exists by listing it, returns `Ok(false)` if the
+    /// dir is empty or can't be listed.
+    async fn dir_exists(&self, path: &str) -> Result<bool> {
+        let mut p = match self.complete_list(path, OpList::new().with_limit(1)).await {
+            Ok((_, p)) => p,
+            Err(err) if is_missing_dir(&err) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        loop {
+            match oio::Page::next(&mut p).await {
+                Ok(Some(entries)) if entries.is_empty() => continue,
+                Ok(Some(_)) => return Ok(true),
+                Ok(None) => return Ok(false),
+                Err(err) if is_missing_dir(&err) => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn blocking_dir_exists(&self, path: &str) -> Result<bool> {
+        let mut p = match self.complete_blocking_list(path, OpList::new().with_limit(1)) {
+            Ok((_, p)) => p,
+            Err(err) if is_missing_dir(&err) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        loop {
+            match oio::BlockingPage::next(&mut p) {
+                Ok(Some(entries)) if entries.is_empty() => continue,
+                Ok(Some(_)) => return Ok(true),
+                Ok(None) => return Ok(false),
+                Err(err) if is_missing_dir(&err) => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
     async fn complete_scan(
         &self,
         path: &str,
@@ -324,6 +385,14 @@ impl<A: Accessor> CompleteReaderAccessor<A> {
     }
 }
 
+/// Errors that mean the dir can't be found by listing.
+fn is_missing_dir(err: &Error) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::NotFound | ErrorKind::NotADirectory | ErrorKind::Unsupported
+    )
+}
+
 #[async_trait]
 impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     type Inner = A;
@@ -347,21 +416,43 @@ impl<A: Accessor> LayeredAccessor for CompleteReaderAccessor<A> {
     }
 
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        self.inner.stat(path, args).await.map(|v| {
-            v.map_metadata(|m| {
-                let bit = m.bit();
-                m.with_bit(bit | Metakey::Complete)
-            })
-        })
+        let rp = match self.inner.stat(path, args).await {
+            Ok(rp) => rp,
+            Err(err) if self.is_implicit_dir(&err, path) => {
+                if !self.dir_exists(path).await? {
+                    return Err(err);
+                }
+                RpStat::new(Metadata::new(EntryMode::DIR))
+            }
+            Err(err) => return Err(err),
+        };
+        check_path_mode(path, rp.metadata().mode())
+            .map_err(|err| err.with_operation(Operation::Stat))?;
+
+        Ok(rp.map_metadata(|m| {
+            let bit = m.bit();
+            m.with_bit(bit | Metakey::Complete)
+        }))
     }
 
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        self.inner.blocking_stat(path, args).map(|v| {
-            v.map_metadata(|m| {
-                let bit = m.bit();
-                m.with_bit(bit | Metakey::Complete)
-            })
-        })
+        let rp = match self.inner.blocking_stat(path, args) {
+            Ok(rp) => rp,
+            Err(err) if self.is_implicit_dir(&err, path) => {
+                if !self.blocking_dir_exists(path)? {
+                    return Err(err);
+                }
+                RpStat::new(Metadata::new(EntryMode::DIR))
+            }
+            Err(err) => return Err(err),
+        };
+        check_path_mode(path, rp.metadata().mode())
+            .map_err(|err| err.with_operation(Operation::BlockingStat))?;
+
+        Ok(rp.map_metadata(|m| {
+            let bit = m.bit();
+            m.with_bit(bit | Metakey::Complete)
+        }))
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
@@ -509,3 +600,41 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tests")]
+    #[tokio::test]
+    async fn test_stat_implicit_dir() {
+        use super::*;
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockReply;
+
+        let not_found = || MockReply::err(Error::new(ErrorKind::NotFound, "not found"));
+        let capabilities =
+            AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::List;
+
+        // Services with real dirs don't need to list.
+        let mock = MockAccessor::new().with_capabilities(capabilities);
+        let op = OperatorBuilder::new(mock.clone()).finish();
+        mock.push(Operation::Stat, not_found());
+        assert!(!op.is_exist("dir/").await.unwrap());
+        mock.assert_called_times(Operation::List, 0);
+
+        // Services without real dirs will list to find implied dirs.
+        let mock = MockAccessor::new()
+            .with_capabilities(capabilities)
+            .with_hints(
+                AccessorHint::ReadSeekable
+                    | AccessorHint::ReadStreamable
+                    | AccessorHint::ImplicitDir,
+            );
+        let op = OperatorBuilder::new(mock.clone()).finish();
+        mock.push(Operation::Stat, not_found()).push(
+            Operation::List,
+            MockReply::list(vec![Entry::new("dir/file", Metadata::new(EntryMode::FILE))]),
+        );
+        assert!(op.stat("dir/").await.unwrap().is_dir());
+        mock.assert_called_times(Operation::List, 1);
+    }
+}
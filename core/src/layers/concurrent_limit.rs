@@ -25,6 +25,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::Semaphore;
+use tokio_util::sync::PollSemaphore;
 
 use crate::ops::*;
 use crate::raw::*;
@@ -37,6 +38,16 @@ use crate::*;
 /// Users can control how many concurrent connections could be established
 /// between OpenDAL and underlying storage services.
 ///
+/// Permits of `read` and `write` will be held until the returned reader or
+/// writer finished or dropped. The same goes for `list` and `scan` with
+/// the returned pager. Seeking a finished reader will acquire the permit
+/// again since it could be read again.
+///
+/// By default, all operations share the same permits. Users can set
+/// separate permits for metadata operations like `stat`, `delete` and
+/// `list` via [`ConcurrentLimitLayer::with_metadata_permits`], so that
+/// they won't be starved behind long running reads and writes.
+///
 /// # Examples
 ///
 /// ```
@@ -51,15 +62,43 @@ use crate::*;
 ///     .layer(ConcurrentLimitLayer::new(1024))
 ///     .finish();
 /// ```
+///
+/// Use separate permits for metadata operations:
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::ConcurrentLimitLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(ConcurrentLimitLayer::new(64).with_metadata_permits(256))
+///     .finish();
+/// ```
 #[derive(Clone)]
 pub struct ConcurrentLimitLayer {
     permits: usize,
+    metadata_permits: Option<usize>,
 }
 
 impl ConcurrentLimitLayer {
     /// Create a new ConcurrentLimitLayer will specify permits
     pub fn new(permits: usize) -> Self {
-        Self { permits }
+        Self {
+            permits,
+            metadata_permits: None,
+        }
+    }
+
+    /// Set separate permits for metadata operations.
+    ///
+    /// Metadata operations including `create_dir`, `stat`, `delete`,
+    /// `list`, `scan` and `batch`. If not set, they will share the same
+    /// permits with `read`, `write`, `copy` and `rename`.
+    pub fn with_metadata_permits(mut self, permits: usize) -> Self {
+        self.metadata_permits = Some(permits);
+        self
     }
 }
 
@@ -67,9 +106,16 @@ impl<A: Accessor> Layer<A> for ConcurrentLimitLayer {
     type LayeredAccessor = ConcurrentLimitAccessor<A>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let semaphore = Arc::new(Semaphore::new(self.permits));
+        let metadata_semaphore = match self.metadata_permits {
+            Some(permits) => Arc::new(Semaphore::new(permits)),
+            None => semaphore.clone(),
+        };
+
         ConcurrentLimitAccessor {
             inner,
-            semaphore: Arc::new(Semaphore::new(self.permits)),
+            semaphore,
+            metadata_semaphore,
         }
     }
 }
@@ -77,7 +123,10 @@ impl<A: Accessor> Layer<A> for ConcurrentLimitLayer {
 #[derive(Debug, Clone)]
 pub struct ConcurrentLimitAccessor<A: Accessor> {
     inner: A,
+    /// Semaphore for data operations like read and write.
     semaphore: Arc<Semaphore>,
+    /// Semaphore for metadata operations like stat and list.
+    metadata_semaphore: Arc<Semaphore>,
 }
 
 #[async_trait]
@@ -96,7 +145,7 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
     async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
         let _permit = self
-            .semaphore
+            .metadata_semaphore
             .acquire()
             .await
             .expect("semaphore must be valid");
@@ -115,7 +164,7 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
         self.inner
             .read(path, args)
             .await
-            .map(|(rp, r)| (rp, ConcurrentLimitWrapper::new(r, permit)))
+            .map(|(rp, r)| (rp, ConcurrentLimitWrapper::new(r, &self.semaphore, permit)))
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
@@ -129,22 +178,42 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
         self.inner
             .write(path, args)
             .await
-            .map(|(rp, w)| (rp, ConcurrentLimitWrapper::new(w, permit)))
+            .map(|(rp, w)| (rp, ConcurrentLimitWrapper::new(w, &self.semaphore, permit)))
     }
 
-    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore must be valid");
+
+        self.inner.copy(from, to, args).await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
         let _permit = self
             .semaphore
             .acquire()
             .await
             .expect("semaphore must be valid");
 
+        self.inner.rename(from, to, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let _permit = self
+            .metadata_semaphore
+            .acquire()
+            .await
+            .expect("semaphore must be valid");
+
         self.inner.stat(path, args).await
     }
 
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
         let _permit = self
-            .semaphore
+            .metadata_semaphore
             .acquire()
             .await
             .expect("semaphore must be valid");
@@ -154,35 +223,39 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         let permit = self
-            .semaphore
+            .metadata_semaphore
             .clone()
             .acquire_owned()
             .await
             .expect("semaphore must be valid");
 
-        self.inner
-            .list(path, args)
-            .await
-            .map(|(rp, s)| (rp, ConcurrentLimitWrapper::new(s, permit)))
+        self.inner.list(path, args).await.map(|(rp, s)| {
+            (
+                rp,
+                ConcurrentLimitWrapper::new(s, &self.metadata_semaphore, permit),
+            )
+        })
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
         let permit = self
-            .semaphore
+            .metadata_semaphore
             .clone()
             .acquire_owned()
             .await
             .expect("semaphore must be valid");
 
-        self.inner
-            .scan(path, args)
-            .await
-            .map(|(rp, s)| (rp, ConcurrentLimitWrapper::new(s, permit)))
+        self.inner.scan(path, args).await.map(|(rp, s)| {
+            (
+                rp,
+                ConcurrentLimitWrapper::new(s, &self.metadata_semaphore, permit),
+            )
+        })
     }
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
         let _permit = self
-            .semaphore
+            .metadata_semaphore
             .acquire()
             .await
             .expect("semaphore must be valid");
@@ -192,7 +265,7 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
     fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
         let _permit = self
-            .semaphore
+            .metadata_semaphore
             .try_acquire()
             .expect("semaphore must be valid");
 
@@ -208,7 +281,7 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
         self.inner
             .blocking_read(path, args)
-            .map(|(rp, r)| (rp, ConcurrentLimitWrapper::new(r, permit)))
+            .map(|(rp, r)| (rp, ConcurrentLimitWrapper::new(r, &self.semaphore, permit)))
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
@@ -220,21 +293,39 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
         self.inner
             .blocking_write(path, args)
-            .map(|(rp, w)| (rp, ConcurrentLimitWrapper::new(w, permit)))
+            .map(|(rp, w)| (rp, ConcurrentLimitWrapper::new(w, &self.semaphore, permit)))
     }
 
-    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let _permit = self
+            .semaphore
+            .try_acquire()
+            .expect("semaphore must be valid");
+
+        self.inner.blocking_copy(from, to, args)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
         let _permit = self
             .semaphore
             .try_acquire()
             .expect("semaphore must be valid");
 
+        self.inner.blocking_rename(from, to, args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let _permit = self
+            .metadata_semaphore
+            .try_acquire()
+            .expect("semaphore must be valid");
+
         self.inner.blocking_stat(path, args)
     }
 
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
         let _permit = self
-            .semaphore
+            .metadata_semaphore
             .try_acquire()
             .expect("semaphore must be valid");
 
@@ -243,70 +334,136 @@ impl<A: Accessor> LayeredAccessor for ConcurrentLimitAccessor<A> {
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
         let permit = self
-            .semaphore
+            .metadata_semaphore
             .clone()
             .try_acquire_owned()
             .expect("semaphore must be valid");
 
-        self.inner
-            .blocking_list(path, args)
-            .map(|(rp, it)| (rp, ConcurrentLimitWrapper::new(it, permit)))
+        self.inner.blocking_list(path, args).map(|(rp, it)| {
+            (
+                rp,
+                ConcurrentLimitWrapper::new(it, &self.metadata_semaphore, permit),
+            )
+        })
     }
 
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
         let permit = self
-            .semaphore
+            .metadata_semaphore
             .clone()
             .try_acquire_owned()
             .expect("semaphore must be valid");
 
-        self.inner
-            .blocking_scan(path, args)
-            .map(|(rp, it)| (rp, ConcurrentLimitWrapper::new(it, permit)))
+        self.inner.blocking_scan(path, args).map(|(rp, it)| {
+            (
+                rp,
+                ConcurrentLimitWrapper::new(it, &self.metadata_semaphore, permit),
+            )
+        })
     }
 }
 
 pub struct ConcurrentLimitWrapper<R> {
     inner: R,
 
-    // Hold on this permit until this reader has been dropped.
-    _permit: OwnedSemaphorePermit,
+    semaphore: PollSemaphore,
+    // Hold on this permit until this reader has been finished or dropped.
+    permit: Option<OwnedSemaphorePermit>,
 }
 
 impl<R> ConcurrentLimitWrapper<R> {
-    fn new(inner: R, permit: OwnedSemaphorePermit) -> Self {
+    fn new(inner: R, semaphore: &Arc<Semaphore>, permit: OwnedSemaphorePermit) -> Self {
         Self {
             inner,
-            _permit: permit,
+            semaphore: PollSemaphore::new(semaphore.clone()),
+            permit: Some(permit),
         }
     }
+
+    /// Release the permit as soon as inner has been finished so that other
+    /// requests don't need to wait for this wrapper to be dropped.
+    fn release(&mut self) {
+        self.permit = None;
+    }
+
+    /// Acquire the permit again if it has been released.
+    fn poll_acquire(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.permit.is_none() {
+            let permit = futures::ready!(self.semaphore.poll_acquire(cx));
+            self.permit = Some(permit.expect("semaphore must be valid"));
+        }
+        Poll::Ready(())
+    }
+
+    /// Try to acquire the permit again if it has been released.
+    ///
+    /// Blocking operations can't wait for the permit, return a temporary
+    /// error instead so that users can retry later.
+    fn blocking_acquire(&mut self) -> Result<()> {
+        if self.permit.is_none() {
+            let permit = self
+                .semaphore
+                .clone_inner()
+                .try_acquire_owned()
+                .map_err(|err| {
+                    Error::new(ErrorKind::RateLimited, "no permit available")
+                        .with_operation("ConcurrentLimitLayer::acquire")
+                        .set_temporary()
+                        .set_source(err)
+                })?;
+            self.permit = Some(permit);
+        }
+        Ok(())
+    }
 }
 
 impl<R: oio::Read> oio::Read for ConcurrentLimitWrapper<R> {
     fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
-        self.inner.poll_read(cx, buf)
+        let res = self.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(0)) = res {
+            if !buf.is_empty() {
+                self.release();
+            }
+        }
+        res
     }
 
     fn poll_seek(&mut self, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        futures::ready!(self.poll_acquire(cx));
         self.inner.poll_seek(cx, pos)
     }
 
     fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
-        self.inner.poll_next(cx)
+        let res = self.inner.poll_next(cx);
+        if let Poll::Ready(None) = res {
+            self.release();
+        }
+        res
     }
 }
 
 impl<R: oio::BlockingRead> oio::BlockingRead for ConcurrentLimitWrapper<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.inner.read(buf)
+        let res = self.inner.read(buf);
+        if let Ok(0) = res {
+            if !buf.is_empty() {
+                self.release();
+            }
+        }
+        res
     }
 
     fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.blocking_acquire()?;
         self.inner.seek(pos)
     }
 
     fn next(&mut self) -> Option<Result<Bytes>> {
-        self.inner.next()
+        let res = self.inner.next();
+        if res.is_none() {
+            self.release();
+        }
+        res
     }
 }
 
@@ -321,11 +478,15 @@ impl<R: oio::Write> oio::Write for ConcurrentLimitWrapper<R> {
     }
 
     async fn abort(&mut self) -> Result<()> {
-        self.inner.abort().await
+        self.inner.abort().await?;
+        self.release();
+        Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
-        self.inner.close().await
+        self.inner.close().await?;
+        self.release();
+        Ok(())
     }
 }
 
@@ -338,20 +499,145 @@ impl<R: oio::BlockingWrite> oio::BlockingWrite for ConcurrentLimitWrapper<R> {
         self.inner.append(bs)
     }
 
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()?;
+        self.release();
+        Ok(())
+    }
+
     fn close(&mut self) -> Result<()> {
-        self.inner.close()
+        self.inner.close()?;
+        self.release();
+        Ok(())
     }
 }
 
 #[async_trait]
 impl<R: oio::Page> oio::Page for ConcurrentLimitWrapper<R> {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        self.inner.next().await
+        let res = self.inner.next().await;
+        if let Ok(None) = res {
+            self.release();
+        }
+        res
     }
 }
 
 impl<R: oio::BlockingPage> oio::BlockingPage for ConcurrentLimitWrapper<R> {
     fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        self.inner.next()
+        let res = self.inner.next();
+        if let Ok(None) = res {
+            self.release();
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::io::Read;
+    use std::io::Seek;
+    use std::time::Duration;
+
+    use futures::AsyncReadExt;
+    use futures::AsyncSeekExt;
+
+    use super::*;
+    use crate::services;
+
+    #[tokio::test]
+    async fn test_metadata_permits() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ConcurrentLimitLayer::new(1).with_metadata_permits(1))
+            .finish();
+        op.write("test", "Hello, World!").await.unwrap();
+
+        // Hold the only data permit.
+        let _r = op.reader("test").await.unwrap();
+
+        let meta = tokio::time::timeout(Duration::from_secs(1), op.stat("test"))
+            .await
+            .expect("stat must not be blocked by reader")
+            .unwrap();
+        assert_eq!(meta.content_length(), 13);
+    }
+
+    #[tokio::test]
+    async fn test_release_permit_after_finished() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ConcurrentLimitLayer::new(1))
+            .finish();
+        op.write("test", "Hello, World!").await.unwrap();
+
+        let mut r = op.reader("test").await.unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.unwrap();
+        assert_eq!(bs, b"Hello, World!");
+
+        // The permit should have been released while `r` is still alive.
+        let bs = tokio::time::timeout(Duration::from_secs(1), op.read("test"))
+            .await
+            .expect("read must not be blocked by finished reader")
+            .unwrap();
+        assert_eq!(bs, b"Hello, World!");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_permit_after_seek() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ConcurrentLimitLayer::new(1))
+            .finish();
+        op.write("test", "Hello, World!").await.unwrap();
+
+        let mut r = op.reader("test").await.unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.unwrap();
+
+        // Seeking back must wait for the permit held by other readers.
+        let other = op.reader("test").await.unwrap();
+        tokio::time::timeout(Duration::from_millis(100), r.seek(SeekFrom::Start(0)))
+            .await
+            .expect_err("seek must be blocked by other reader");
+
+        drop(other);
+        r.seek(SeekFrom::Start(0)).await.unwrap();
+
+        // The permit is held by `r` again until it has been finished.
+        let res = tokio::time::timeout(Duration::from_millis(100), op.reader("test")).await;
+        assert!(res.is_err(), "reader must be blocked by seeking reader");
+
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await.unwrap();
+        assert_eq!(bs, b"Hello, World!");
+        op.reader("test").await.unwrap();
+    }
+
+    #[test]
+    fn test_blocking_seek_without_permit() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ConcurrentLimitLayer::new(1))
+            .finish()
+            .blocking();
+        op.write("test", "Hello, World!").unwrap();
+
+        let mut r = op.reader("test").unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).unwrap();
+
+        // Seeking back must not panic while the permit is held by others.
+        let other = op.reader("test").unwrap();
+        let err = r.seek(SeekFrom::Start(0)).expect_err("seek must fail");
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+
+        drop(other);
+        r.seek(SeekFrom::Start(0)).unwrap();
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).unwrap();
+        assert_eq!(bs, b"Hello, World!");
     }
 }
@@ -0,0 +1,589 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+use async_trait::async_trait;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// CorrectnessCheckLayer will check whether the args of operations are
+/// supported by the underlying services.
+///
+/// # Notes
+///
+/// Services will silently ignore the args they don't support, for example,
+/// passing `if_none_match` to a service that doesn't support it will read
+/// the content anyway. CorrectnessCheckLayer will cross-reference the args
+/// against [`AccessorCapability`] and return an [`ErrorKind::Unsupported`]
+/// error which carries the exact argument and service instead.
+///
+/// This layer is applied by [`OperatorBuilder::finish`] by default, use
+/// [`OperatorBuilder::disable_correctness_check`] to opt out.
+pub struct CorrectnessCheckLayer;
+
+impl<A: Accessor> Layer<A> for CorrectnessCheckLayer {
+    type LayeredAccessor = CorrectnessAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let meta = inner.info();
+        CorrectnessAccessor { meta, inner }
+    }
+}
+
+pub struct CorrectnessAccessor<A: Accessor> {
+    meta: AccessorInfo,
+    inner: A,
+}
+
+impl<A: Accessor> Debug for CorrectnessAccessor<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<A: Accessor> CorrectnessAccessor<A> {
+    /// Check given args, returns the first argument that is set but not
+    /// supported.
+    fn check<'a>(
+        &self,
+        op: impl Into<&'static str>,
+        args: impl IntoIterator<Item = (&'a str, bool, AccessorCapability)>,
+    ) -> Result<()> {
+        let capabilities = self.meta.capabilities();
+
+        for (name, is_set, capability) in args {
+            if is_set && !capabilities.contains(capability) {
+                return Err(new_unsupported_error(self.meta.scheme(), op.into(), name));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_read(&self, op: impl Into<&'static str>, args: &OpRead) -> Result<()> {
+        use AccessorCapability::*;
+
+        self.check(
+            op,
+            [
+                ("if_match", args.if_match().is_some(), ReadWithIfMatch),
+                (
+                    "if_none_match",
+                    args.if_none_match().is_some(),
+                    ReadWithIfNoneMatch,
+                ),
+                (
+                    "override_cache_control",
+                    args.override_cache_control().is_some(),
+                    ReadWithOverrideCacheControl,
+                ),
+                (
+                    "override_content_disposition",
+                    args.override_content_disposition().is_some(),
+                    ReadWithOverrideContentDisposition,
+                ),
+                ("version", args.version().is_some(), ReadWithVersion),
+            ],
+        )
+    }
+
+    fn check_stat(&self, op: impl Into<&'static str>, args: &OpStat) -> Result<()> {
+        use AccessorCapability::*;
+
+        self.check(
+            op,
+            [
+                ("if_match", args.if_match().is_some(), StatWithIfMatch),
+                (
+                    "if_none_match",
+                    args.if_none_match().is_some(),
+                    StatWithIfNoneMatch,
+                ),
+                ("version", args.version().is_some(), StatWithVersion),
+            ],
+        )
+    }
+
+    fn check_write(&self, op: impl Into<&'static str>, args: &OpWrite) -> Result<()> {
+        use AccessorCapability::*;
+
+        self.check(
+            op,
+            [
+                (
+                    "content_type",
+                    args.content_type().is_some(),
+                    WriteWithContentType,
+                ),
+                (
+                    "content_disposition",
+                    args.content_disposition().is_some(),
+                    WriteWithContentDisposition,
+                ),
+                (
+                    "cache_control",
+                    args.cache_control().is_some(),
+                    WriteWithCacheControl,
+                ),
+                ("if_match", args.if_match().is_some(), WriteWithIfMatch),
+                (
+                    "storage_class",
+                    args.storage_class().is_some(),
+                    WriteWithStorageClass,
+                ),
+                (
+                    "last_modified",
+                    args.last_modified().is_some(),
+                    WriteWithLastModified,
+                ),
+            ],
+        )
+    }
+
+    fn check_delete(&self, op: impl Into<&'static str>, args: &OpDelete) -> Result<()> {
+        self.check(
+            op,
+            [(
+                "version",
+                args.version().is_some(),
+                AccessorCapability::DeleteWithVersion,
+            )],
+        )
+    }
+
+    fn check_list(&self, op: impl Into<&'static str>, args: &OpList) -> Result<()> {
+        use AccessorCapability::*;
+
+        self.check(
+            op,
+            [
+                ("limit", args.limit().is_some(), ListWithLimit),
+                ("versions", args.versions(), ListWithVersions),
+            ],
+        )
+    }
+
+    fn check_limit(&self, op: impl Into<&'static str>, limit: Option<usize>) -> Result<()> {
+        self.check(
+            op,
+            [("limit", limit.is_some(), AccessorCapability::ListWithLimit)],
+        )
+    }
+}
+
+fn new_unsupported_error(scheme: Scheme, op: &'static str, arg: &str) -> Error {
+    Error::new(
+        ErrorKind::Unsupported,
+        &format!("service {scheme} doesn't support operation {op} with arg {arg}"),
+    )
+    .with_operation(op)
+    .with_context("service", scheme)
+    .with_context("argument", arg)
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for CorrectnessAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    fn metadata(&self) -> AccessorInfo {
+        self.meta.clone()
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.check_read(Operation::Read, &args)?;
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.check_write(Operation::Write, &args)?;
+        self.inner.write(path, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.check_stat(Operation::Stat, &args)?;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.check_delete(Operation::Delete, &args)?;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.check_list(Operation::List, &args)?;
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.check_limit(Operation::Scan, args.limit())?;
+        self.inner.scan(path, args).await
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        match args.operation() {
+            PresignOperation::Read(v) => self.check_read(Operation::Presign, v)?,
+            PresignOperation::Stat(v) => self.check_stat(Operation::Presign, v)?,
+            PresignOperation::Write(v) => self.check_write(Operation::Presign, v)?,
+        }
+        self.inner.presign(path, args).await
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        for (_, op) in args.operation() {
+            match op {
+                BatchOperation::Delete(v) => self.check_delete(Operation::Batch, v)?,
+            }
+        }
+        self.inner.batch(args).await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.check_read(Operation::BlockingRead, &args)?;
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.check_write(Operation::BlockingWrite, &args)?;
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.check_stat(Operation::BlockingStat, &args)?;
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.check_delete(Operation::BlockingDelete, &args)?;
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.check_list(Operation::BlockingList, &args)?;
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.check_limit(Operation::BlockingScan, args.limit())?;
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flagset::FlagSet;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockService {
+        capabilities: FlagSet<AccessorCapability>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        type Reader = ();
+        type BlockingReader = ();
+        type Writer = ();
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capabilities(self.capabilities);
+            am
+        }
+
+        async fn read(&self, _: &str, _: OpRead) -> Result<(RpRead, Self::Reader)> {
+            Ok((RpRead::new(0), ()))
+        }
+
+        async fn write(&self, _: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+            Ok((RpWrite::new(), ()))
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<RpStat> {
+            Ok(RpStat::new(Metadata::new(EntryMode::Unknown)))
+        }
+
+        async fn delete(&self, _: &str, _: OpDelete) -> Result<RpDelete> {
+            Ok(RpDelete::default())
+        }
+
+        async fn list(&self, _: &str, _: OpList) -> Result<(RpList, Self::Pager)> {
+            Ok((RpList::default(), ()))
+        }
+    }
+
+    fn new_accessor(
+        capabilities: impl Into<FlagSet<AccessorCapability>>,
+    ) -> CorrectnessAccessor<MockService> {
+        CorrectnessCheckLayer.layer(MockService {
+            capabilities: capabilities.into(),
+        })
+    }
+
+    /// Assert the result is ok or an unsupported error with given arg.
+    fn assert_result<T>(res: Result<T>, unsupported: Option<&str>) {
+        match (res, unsupported) {
+            (Ok(_), None) => {}
+            (Err(err), Some(arg)) => {
+                assert_eq!(err.kind(), ErrorKind::Unsupported);
+                assert!(err.to_string().contains(arg), "{err}");
+            }
+            (Ok(_), Some(arg)) => panic!("expect unsupported error for {arg}"),
+            (Err(err), None) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read() {
+        use AccessorCapability::*;
+
+        let cases: Vec<(FlagSet<AccessorCapability>, OpRead, Option<&str>)> = vec![
+            (Read.into(), OpRead::new(), None),
+            (
+                Read.into(),
+                OpRead::new().with_if_match("etag"),
+                Some("if_match"),
+            ),
+            (
+                Read | ReadWithIfMatch,
+                OpRead::new().with_if_match("etag"),
+                None,
+            ),
+            (
+                Read | ReadWithIfMatch,
+                OpRead::new().with_if_none_match("etag"),
+                Some("if_none_match"),
+            ),
+            (
+                Read | ReadWithIfNoneMatch,
+                OpRead::new().with_if_none_match("etag"),
+                None,
+            ),
+            (
+                Read.into(),
+                OpRead::new().with_override_cache_control("no-cache"),
+                Some("override_cache_control"),
+            ),
+            (
+                Read | ReadWithOverrideCacheControl,
+                OpRead::new().with_override_cache_control("no-cache"),
+                None,
+            ),
+            (
+                Read.into(),
+                OpRead::new().with_override_content_disposition("inline"),
+                Some("override_content_disposition"),
+            ),
+            (
+                Read | ReadWithOverrideContentDisposition,
+                OpRead::new().with_override_content_disposition("inline"),
+                None,
+            ),
+            (
+                Read.into(),
+                OpRead::new().with_version("v1"),
+                Some("version"),
+            ),
+            (
+                Read | ReadWithVersion,
+                OpRead::new().with_version("v1"),
+                None,
+            ),
+        ];
+
+        for (capabilities, args, unsupported) in cases {
+            let acc = new_accessor(capabilities);
+            assert_result(LayeredAccessor::read(&acc, "path", args).await, unsupported);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write() {
+        use AccessorCapability::*;
+
+        let cases: Vec<(FlagSet<AccessorCapability>, OpWrite, Option<&str>)> = vec![
+            (Write.into(), OpWrite::new(), None),
+            (
+                Write.into(),
+                OpWrite::new().with_content_type("text/plain"),
+                Some("content_type"),
+            ),
+            (
+                Write | WriteWithContentType,
+                OpWrite::new().with_content_type("text/plain"),
+                None,
+            ),
+            (
+                Write | WriteWithContentType,
+                OpWrite::new().with_content_disposition("inline"),
+                Some("content_disposition"),
+            ),
+            (
+                Write | WriteWithContentDisposition,
+                OpWrite::new().with_content_disposition("inline"),
+                None,
+            ),
+            (
+                Write.into(),
+                OpWrite::new().with_cache_control("no-cache"),
+                Some("cache_control"),
+            ),
+            (
+                Write | WriteWithCacheControl,
+                OpWrite::new().with_cache_control("no-cache"),
+                None,
+            ),
+            (
+                Write.into(),
+                OpWrite::new().with_if_match("etag"),
+                Some("if_match"),
+            ),
+            (
+                Write | WriteWithIfMatch,
+                OpWrite::new().with_if_match("etag"),
+                None,
+            ),
+            (
+                Write.into(),
+                OpWrite::new().with_storage_class("GLACIER"),
+                Some("storage_class"),
+            ),
+            (
+                Write | WriteWithStorageClass,
+                OpWrite::new().with_storage_class("GLACIER"),
+                None,
+            ),
+        ];
+
+        for (capabilities, args, unsupported) in cases {
+            let acc = new_accessor(capabilities);
+            assert_result(
+                LayeredAccessor::write(&acc, "path", args).await,
+                unsupported,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stat() {
+        use AccessorCapability::*;
+
+        let cases: Vec<(FlagSet<AccessorCapability>, OpStat, Option<&str>)> = vec![
+            (Read.into(), OpStat::new(), None),
+            (
+                Read.into(),
+                OpStat::new().with_if_match("etag"),
+                Some("if_match"),
+            ),
+            (
+                Read | StatWithIfMatch,
+                OpStat::new().with_if_match("etag"),
+                None,
+            ),
+            (
+                Read | StatWithIfMatch,
+                OpStat::new().with_if_none_match("etag"),
+                Some("if_none_match"),
+            ),
+            (
+                Read | StatWithIfNoneMatch,
+                OpStat::new().with_if_none_match("etag"),
+                None,
+            ),
+            (
+                Read.into(),
+                OpStat::new().with_version("v1"),
+                Some("version"),
+            ),
+            (
+                Read | StatWithVersion,
+                OpStat::new().with_version("v1"),
+                None,
+            ),
+        ];
+
+        for (capabilities, args, unsupported) in cases {
+            let acc = new_accessor(capabilities);
+            assert_result(LayeredAccessor::stat(&acc, "path", args).await, unsupported);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        use AccessorCapability::*;
+
+        let cases: Vec<(FlagSet<AccessorCapability>, OpList, Option<&str>)> = vec![
+            (List.into(), OpList::new(), None),
+            (List.into(), OpList::new().with_limit(10), Some("limit")),
+            (List | ListWithLimit, OpList::new().with_limit(10), None),
+            (List.into(), OpList::new().with_versions(), Some("versions")),
+            (List | ListWithVersions, OpList::new().with_versions(), None),
+        ];
+
+        for (capabilities, args, unsupported) in cases {
+            let acc = new_accessor(capabilities);
+            assert_result(
+                LayeredAccessor::list(&acc, "path/", args).await,
+                unsupported,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        use AccessorCapability::*;
+
+        let cases: Vec<(FlagSet<AccessorCapability>, OpDelete, Option<&str>)> = vec![
+            (Write.into(), OpDelete::new(), None),
+            (
+                Write.into(),
+                OpDelete::new().with_version("v1"),
+                Some("version"),
+            ),
+            (
+                Write | DeleteWithVersion,
+                OpDelete::new().with_version("v1"),
+                None,
+            ),
+        ];
+
+        for (capabilities, args, unsupported) in cases {
+            let acc = new_accessor(capabilities);
+            assert_result(
+                LayeredAccessor::delete(&acc, "path", args).await,
+                unsupported,
+            );
+        }
+    }
+}
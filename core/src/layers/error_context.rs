@@ -41,6 +41,12 @@ use crate::*;
 /// - `service`: The [`Scheme`] of underlying service.
 /// - `operation`: The [`Operation`] of this operation
 /// - `path`: The path of this operation
+/// - `from` and `to`: The source and target path of copy and rename
+/// - `range`: The range of read operation
+/// - `limit`: The limit of list and scan operation if set
+///
+/// This layer is always attached by [`OperatorBuilder`], so services
+/// don't need to add these context by themselves.
 pub struct ErrorContextLayer;
 
 impl<A: Accessor> Layer<A> for ErrorContextLayer {
@@ -138,6 +144,30 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
             .await
     }
 
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inner
+            .copy(from, to, args)
+            .map_err(|err| {
+                err.with_operation(Operation::Copy)
+                    .with_context("service", self.meta.scheme())
+                    .with_context("from", from)
+                    .with_context("to", to)
+            })
+            .await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inner
+            .rename(from, to, args)
+            .map_err(|err| {
+                err.with_operation(Operation::Rename)
+                    .with_context("service", self.meta.scheme())
+                    .with_context("from", from)
+                    .with_context("to", to)
+            })
+            .await
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         self.inner
             .stat(path, args)
@@ -161,6 +191,8 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let limit = args.limit();
+
         self.inner
             .list(path, args)
             .map_ok(|(rp, os)| {
@@ -177,11 +209,17 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
                 err.with_operation(Operation::List)
                     .with_context("service", self.meta.scheme())
                     .with_context("path", path)
+                    .map(|err| match limit {
+                        Some(limit) => err.with_context("limit", limit.to_string()),
+                        None => err,
+                    })
             })
             .await
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        let limit = args.limit();
+
         self.inner
             .scan(path, args)
             .map_ok(|(rp, os)| {
@@ -198,6 +236,10 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
                 err.with_operation(Operation::Scan)
                     .with_context("service", self.meta.scheme())
                     .with_context("path", path)
+                    .map(|err| match limit {
+                        Some(limit) => err.with_context("limit", limit.to_string()),
+                        None => err,
+                    })
             })
             .await
     }
@@ -245,6 +287,8 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        let br = args.range();
+
         self.inner
             .blocking_read(path, args)
             .map(|(rp, os)| {
@@ -261,6 +305,7 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
                 err.with_operation(Operation::BlockingRead)
                     .with_context("service", self.meta.scheme())
                     .with_context("path", path)
+                    .with_context("range", br.to_string())
             })
     }
 
@@ -284,6 +329,24 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
             })
     }
 
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.inner.blocking_copy(from, to, args).map_err(|err| {
+            err.with_operation(Operation::BlockingCopy)
+                .with_context("service", self.meta.scheme())
+                .with_context("from", from)
+                .with_context("to", to)
+        })
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.inner.blocking_rename(from, to, args).map_err(|err| {
+            err.with_operation(Operation::BlockingMove)
+                .with_context("service", self.meta.scheme())
+                .with_context("from", from)
+                .with_context("to", to)
+        })
+    }
+
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         self.inner.blocking_stat(path, args).map_err(|err| {
             err.with_operation(Operation::BlockingStat)
@@ -301,6 +364,8 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        let limit = args.limit();
+
         self.inner
             .blocking_list(path, args)
             .map(|(rp, os)| {
@@ -317,10 +382,16 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
                 err.with_operation(Operation::BlockingList)
                     .with_context("service", self.meta.scheme())
                     .with_context("path", path)
+                    .map(|err| match limit {
+                        Some(limit) => err.with_context("limit", limit.to_string()),
+                        None => err,
+                    })
             })
     }
 
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        let limit = args.limit();
+
         self.inner
             .blocking_scan(path, args)
             .map(|(rp, os)| {
@@ -337,6 +408,10 @@ impl<A: Accessor> LayeredAccessor for ErrorContextAccessor<A> {
                 err.with_operation(Operation::BlockingScan)
                     .with_context("service", self.meta.scheme())
                     .with_context("path", path)
+                    .map(|err| match limit {
+                        Some(limit) => err.with_context("limit", limit.to_string()),
+                        None => err,
+                    })
             })
     }
 }
@@ -421,7 +496,7 @@ impl<T: oio::Write> oio::Write for ErrorContextWrapper<T> {
 
     async fn abort(&mut self) -> Result<()> {
         self.inner.abort().await.map_err(|err| {
-            err.with_operation(WriteOperation::Append)
+            err.with_operation(WriteOperation::Abort)
                 .with_context("service", self.scheme)
                 .with_context("path", &self.path)
         })
@@ -453,6 +528,14 @@ impl<T: oio::BlockingWrite> oio::BlockingWrite for ErrorContextWrapper<T> {
         })
     }
 
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort().map_err(|err| {
+            err.with_operation(WriteOperation::BlockingAbort)
+                .with_context("service", self.scheme)
+                .with_context("path", &self.path)
+        })
+    }
+
     fn close(&mut self) -> Result<()> {
         self.inner.close().map_err(|err| {
             err.with_operation(WriteOperation::BlockingClose)
@@ -482,3 +565,28 @@ impl<T: oio::BlockingPage> oio::BlockingPage for ErrorContextWrapper<T> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services;
+
+    #[tokio::test]
+    async fn test_error_context() {
+        let op = Operator::new(services::Memory::default()).unwrap().finish();
+
+        let err = op.stat("not_exist").await.expect_err("stat must fail");
+        let msg = err.to_string();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(msg.contains("service: memory"), "{msg}");
+        assert!(msg.contains("path: not_exist"), "{msg}");
+
+        let err = op
+            .copy("not_exist", "target")
+            .await
+            .expect_err("copy must fail");
+        let msg = err.to_string();
+        assert!(msg.contains("from: not_exist"), "{msg}");
+        assert!(msg.contains("to: target"), "{msg}");
+    }
+}
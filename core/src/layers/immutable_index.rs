@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::mem;
 
@@ -29,6 +29,11 @@ use crate::*;
 ///
 /// Especially useful for services without list capability like HTTP.
 ///
+/// `list` and `scan` will be answered by the index only, while other
+/// operations like `read` and `stat` will be delegated to the underlying
+/// services. Parent directories of inserted keys don't need to be
+/// inserted, they will be synthesized while listing.
+///
 /// # Examples
 ///
 /// ```rust, no_run
@@ -49,13 +54,22 @@ use crate::*;
 /// ```
 #[derive(Default, Debug, Clone)]
 pub struct ImmutableIndexLayer {
-    vec: Vec<String>,
+    set: BTreeSet<String>,
 }
 
 impl ImmutableIndexLayer {
     /// Insert a key into index.
+    ///
+    /// Keys are relative to the root of operator, keys that end with `/`
+    /// will be treated as directories.
     pub fn insert(&mut self, key: String) {
-        self.vec.push(key);
+        let key = normalize_path(&key);
+        // Root is always existing, no need to index it.
+        if key == "/" {
+            return;
+        }
+
+        self.set.insert(key);
     }
 
     /// Insert keys from iter.
@@ -63,7 +77,9 @@ impl ImmutableIndexLayer {
     where
         I: IntoIterator<Item = String>,
     {
-        self.vec.extend(iter);
+        for key in iter {
+            self.insert(key)
+        }
     }
 }
 
@@ -72,7 +88,7 @@ impl<A: Accessor> Layer<A> for ImmutableIndexLayer {
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
         ImmutableIndexAccessor {
-            vec: self.vec.clone(),
+            set: self.set.clone(),
             inner,
         }
     }
@@ -81,22 +97,42 @@ impl<A: Accessor> Layer<A> for ImmutableIndexLayer {
 #[derive(Debug, Clone)]
 pub struct ImmutableIndexAccessor<A: Accessor> {
     inner: A,
-    vec: Vec<String>,
+    set: BTreeSet<String>,
 }
 
 impl<A: Accessor> ImmutableIndexAccessor<A> {
     fn children_flat(&self, path: &str) -> Vec<String> {
-        self.vec
-            .iter()
-            .filter(|v| v.starts_with(path) && v.as_str() != path)
-            .cloned()
-            .collect()
+        let mut res = BTreeSet::new();
+
+        for i in self.set.range(path.to_string()..) {
+            // Keys are sorted, so we can stop at the first key that
+            // doesn't belong to `path`.
+            if !i.starts_with(path) {
+                break;
+            }
+
+            if i == path {
+                continue;
+            }
+
+            // Synthesize all parent dirs between `path` and `i`.
+            for (idx, _) in i[path.len()..].match_indices('/') {
+                let dir_idx = idx + 1 + path.len();
+                if dir_idx != i.len() {
+                    res.insert(i[..dir_idx].to_string());
+                }
+            }
+
+            res.insert(i.to_string());
+        }
+
+        res.into_iter().collect()
     }
 
     fn children_hierarchy(&self, path: &str) -> Vec<String> {
-        let mut res = HashSet::new();
+        let mut res = BTreeSet::new();
 
-        for i in self.vec.iter() {
+        for i in self.set.iter() {
             // `/xyz` should not belong to `/abc`
             if !i.starts_with(path) {
                 continue;
@@ -464,4 +500,50 @@ mod tests {
         assert_eq!(map["dataset/stateful/ontime_2009_200.csv"], EntryMode::FILE);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_scan_with_prefix() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut iil = ImmutableIndexLayer::default();
+        iil.extend_iter(
+            [
+                "/data/2022/12/a.csv",
+                "data/2023/01/a.csv",
+                "data/2023/01/b.csv",
+                "data/2023/02/a.csv",
+                "data/2023/02/a.csv",
+            ]
+            .into_iter()
+            .map(|v| v.to_string()),
+        );
+
+        let op = Operator::new(Http::from_iter(
+            vec![("endpoint".to_string(), "https://xuanwo.io".to_string())].into_iter(),
+        ))?
+        .layer(iil)
+        .finish();
+
+        let info = op.info();
+        assert!(info.can_list());
+        assert!(info.can_scan());
+
+        let mut ds = op.scan("data/2023/").await?;
+        let mut paths = Vec::new();
+        while let Some(entry) = ds.try_next().await? {
+            paths.push(entry.path().to_string());
+        }
+
+        assert_eq!(
+            paths,
+            vec![
+                "data/2023/01/",
+                "data/2023/01/a.csv",
+                "data/2023/01/b.csv",
+                "data/2023/02/",
+                "data/2023/02/a.csv",
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1537,6 +1537,36 @@ impl<W: oio::BlockingWrite> oio::BlockingWrite for LoggingWriter<W> {
         }
     }
 
+    fn abort(&mut self) -> Result<()> {
+        match self.inner.abort() {
+            Ok(_) => {
+                trace!(
+                    target: LOGGING_TARGET,
+                    "service={} operation={} path={} written={} -> abort writer",
+                    self.scheme,
+                    WriteOperation::BlockingAbort,
+                    self.path,
+                    self.written,
+                );
+                Ok(())
+            }
+            Err(err) => {
+                if let Some(lvl) = self.failure_level {
+                    log!(
+                        target: LOGGING_TARGET,
+                        lvl,
+                        "service={} operation={} path={} written={} -> abort writer failed: {err:?}",
+                        self.scheme,
+                        WriteOperation::BlockingAbort,
+                        self.path,
+                        self.written,
+                    )
+                }
+                Err(err)
+            }
+        }
+    }
+
     fn close(&mut self) -> Result<()> {
         match self.inner.close() {
             Ok(_) => Ok(()),
@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Guess the content type from the path extension while writing.
+///
+/// Services like s3 will use `application/octet-stream` as the content type
+/// if it's not specified, which makes browsers download the file instead of
+/// displaying it. MimeGuessLayer will guess the content type from the path
+/// extension via [`mime_guess`](https://docs.rs/mime_guess) if users don't
+/// set it.
+///
+/// # Notes
+///
+/// - Writes with content type already set will be passed through.
+/// - Paths without a known extension will be passed through.
+/// - Services that don't support write with content type will be passed
+///   through.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::MimeGuessLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(MimeGuessLayer::default().with_mapping("parquet", "application/vnd.apache.parquet"))
+///     .finish();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MimeGuessLayer {
+    mappings: HashMap<String, String>,
+}
+
+impl MimeGuessLayer {
+    /// Add a custom mapping from extension to content type.
+    ///
+    /// Custom mappings take precedence over the builtin ones. Extensions
+    /// are matched case-insensitively and should not start with `.`.
+    pub fn with_mapping(mut self, ext: &str, content_type: &str) -> Self {
+        self.mappings
+            .insert(ext.to_lowercase(), content_type.to_string());
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for MimeGuessLayer {
+    type LayeredAccessor = MimeGuessAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let enabled = inner
+            .info()
+            .capabilities()
+            .contains(AccessorCapability::WriteWithContentType);
+
+        MimeGuessAccessor {
+            inner,
+            enabled,
+            mappings: Arc::new(self.mappings.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MimeGuessAccessor<A: Accessor> {
+    inner: A,
+
+    /// Only guess content type if underlying service supports it.
+    enabled: bool,
+    mappings: Arc<HashMap<String, String>>,
+}
+
+impl<A: Accessor> MimeGuessAccessor<A> {
+    fn guess(&self, path: &str) -> Option<String> {
+        let ext = path.rsplit_once('.')?.1;
+        if ext.is_empty() || ext.contains('/') {
+            return None;
+        }
+
+        if let Some(v) = self.mappings.get(&ext.to_lowercase()) {
+            return Some(v.clone());
+        }
+
+        mime_guess::from_ext(ext).first().map(|v| v.to_string())
+    }
+
+    fn complete_args(&self, path: &str, args: OpWrite) -> OpWrite {
+        if !self.enabled || args.content_type().is_some() {
+            return args;
+        }
+
+        match self.guess(path) {
+            Some(content_type) => args.with_content_type(&content_type),
+            None => args,
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for MimeGuessAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = A::Writer;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let args = self.complete_args(path, args);
+        self.inner.write(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        let args = self.complete_args(path, args);
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use flagset::FlagSet;
+
+    use super::*;
+
+    /// MockService records the content type of the last write.
+    #[derive(Debug, Default, Clone)]
+    struct MockService {
+        capability: Option<FlagSet<AccessorCapability>>,
+        content_type: Arc<Mutex<Option<Option<String>>>>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        type Reader = ();
+        type BlockingReader = ();
+        type Writer = ();
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capabilities(self.capability.unwrap_or_else(|| {
+                AccessorCapability::Write | AccessorCapability::WriteWithContentType
+            }));
+            am
+        }
+
+        async fn write(&self, _: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+            *self.content_type.lock().unwrap() = Some(args.content_type().map(|v| v.to_string()));
+            Ok((RpWrite::new(), ()))
+        }
+    }
+
+    async fn guess(
+        srv: MockService,
+        layer: MimeGuessLayer,
+        path: &str,
+        args: OpWrite,
+    ) -> Option<String> {
+        let acc = layer.layer(srv.clone());
+        LayeredAccessor::write(&acc, path, args).await.unwrap();
+        let ct = srv.content_type.lock().unwrap().clone();
+        ct.expect("write must be called")
+    }
+
+    #[tokio::test]
+    async fn test_mime_guess() {
+        let layer = MimeGuessLayer::default().with_mapping("PARQUET", "application/x-parquet");
+
+        let cases = vec![
+            ("index.html", OpWrite::new(), Some("text/html")),
+            ("a/b/c.JPG", OpWrite::new(), Some("image/jpeg")),
+            (
+                "data.parquet",
+                OpWrite::new(),
+                Some("application/x-parquet"),
+            ),
+            ("no_ext", OpWrite::new(), None),
+            ("dir.d/no_ext", OpWrite::new(), None),
+            (
+                "index.html",
+                OpWrite::new().with_content_type("text/plain"),
+                Some("text/plain"),
+            ),
+        ];
+
+        for (path, args, expected) in cases {
+            let ct = guess(MockService::default(), layer.clone(), path, args).await;
+            assert_eq!(ct.as_deref(), expected, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mime_guess_unsupported() {
+        let srv = MockService {
+            capability: Some(AccessorCapability::Write.into()),
+            ..Default::default()
+        };
+
+        let ct = guess(srv, MimeGuessLayer::default(), "index.html", OpWrite::new()).await;
+        assert_eq!(ct, None);
+    }
+}
@@ -34,6 +34,19 @@ use crate::*;
 
 /// Add [minitrace](https://docs.rs/minitrace/) for every operations.
 ///
+/// # Spans
+///
+/// Every operation will be executed inside a span named `opendal::<operation>`
+/// (for example `opendal::read`) which carries the following properties:
+///
+/// - `scheme`: Service name from [`Scheme`]
+/// - `path`: The path of this operation (`from` and `to` for copy and rename)
+///
+/// Readers, writers and pagers returned by operations are covered by a child
+/// span that lives as long as they do. Their polls are recorded as child spans
+/// of it, and the total bytes transferred will be added as the `bytes`
+/// property once they are dropped.
+///
 /// # Examples
 ///
 /// ## Basic Setup
@@ -119,13 +132,26 @@ impl<A: Accessor> Layer<A> for MinitraceLayer {
     type LayeredAccessor = MinitraceAccessor<A>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
-        MinitraceAccessor { inner }
+        let scheme = inner.info().scheme();
+
+        MinitraceAccessor { inner, scheme }
     }
 }
 
 #[derive(Debug)]
 pub struct MinitraceAccessor<A> {
     inner: A,
+    scheme: Scheme,
+}
+
+impl<A> MinitraceAccessor<A> {
+    /// Open a span for given operation under current local parent.
+    fn span(&self, op: Operation, paths: &[(&'static str, &str)]) -> Span {
+        let mut span = Span::enter_with_local_parent(op.into_span_name());
+        span.add_property(|| ("scheme", self.scheme.to_string()));
+        span.add_properties(|| paths.iter().map(|(k, v)| (*k, v.to_string())));
+        span
+    }
 }
 
 #[async_trait]
@@ -142,159 +168,185 @@ impl<A: Accessor> LayeredAccessor for MinitraceAccessor<A> {
         &self.inner
     }
 
-    #[trace("metadata")]
     fn metadata(&self) -> AccessorInfo {
+        let span = self.span(Operation::Info, &[]);
+        let _g = span.set_local_parent();
         self.inner.info()
     }
 
-    #[trace("create", enter_on_poll = true)]
     async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
-        self.inner.create_dir(path, args).await
+        let span = self.span(Operation::CreateDir, &[("path", path)]);
+        self.inner.create_dir(path, args).in_span(span).await
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let span = Span::enter_with_local_parent("read");
+        let span = self.span(Operation::Read, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::reader", &span);
         self.inner
             .read(path, args)
-            .map(|v| v.map(|(rp, r)| (rp, MinitraceWrapper::new(span, r))))
+            .in_span(span)
+            .map(|v| v.map(|(rp, r)| (rp, MinitraceWrapper::new(child, r))))
             .await
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let span = Span::enter_with_local_parent("write");
+        let span = self.span(Operation::Write, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::writer", &span);
         self.inner
             .write(path, args)
-            .map(|v| v.map(|(rp, r)| (rp, MinitraceWrapper::new(span, r))))
+            .in_span(span)
+            .map(|v| v.map(|(rp, r)| (rp, MinitraceWrapper::new(child, r))))
             .await
     }
 
-    #[trace("copy", enter_on_poll = true)]
     async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
-        self.inner().copy(from, to, args).await
+        let span = self.span(Operation::Copy, &[("from", from), ("to", to)]);
+        self.inner().copy(from, to, args).in_span(span).await
     }
 
-    #[trace("rename", enter_on_poll = true)]
     async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
-        self.inner().rename(from, to, args).await
+        let span = self.span(Operation::Rename, &[("from", from), ("to", to)]);
+        self.inner().rename(from, to, args).in_span(span).await
     }
 
-    #[trace("stat", enter_on_poll = true)]
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        self.inner.stat(path, args).await
+        let span = self.span(Operation::Stat, &[("path", path)]);
+        self.inner.stat(path, args).in_span(span).await
     }
 
-    #[trace("delete", enter_on_poll = true)]
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        self.inner.delete(path, args).await
+        let span = self.span(Operation::Delete, &[("path", path)]);
+        self.inner.delete(path, args).in_span(span).await
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        let span = Span::enter_with_local_parent("list");
+        let span = self.span(Operation::List, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::pager", &span);
         self.inner
             .list(path, args)
-            .map(|v| v.map(|(rp, s)| (rp, MinitraceWrapper::new(span, s))))
+            .in_span(span)
+            .map(|v| v.map(|(rp, s)| (rp, MinitraceWrapper::new(child, s))))
             .await
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
-        let span = Span::enter_with_local_parent("scan");
+        let span = self.span(Operation::Scan, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::pager", &span);
         self.inner
             .scan(path, args)
-            .map(|v| v.map(|(rp, s)| (rp, MinitraceWrapper::new(span, s))))
+            .in_span(span)
+            .map(|v| v.map(|(rp, s)| (rp, MinitraceWrapper::new(child, s))))
             .await
     }
 
-    #[trace("presign", enter_on_poll = true)]
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
-        self.inner.presign(path, args).await
+        let span = self.span(Operation::Presign, &[("path", path)]);
+        self.inner.presign(path, args).in_span(span).await
     }
 
-    #[trace("batch", enter_on_poll = true)]
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
-        self.inner.batch(args).await
+        let span = self.span(Operation::Batch, &[]);
+        self.inner.batch(args).in_span(span).await
     }
 
-    #[trace("blocking_create")]
     fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        let span = self.span(Operation::BlockingCreateDir, &[("path", path)]);
+        let _g = span.set_local_parent();
         self.inner.blocking_create_dir(path, args)
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
-        let span = Span::enter_with_local_parent("blocking_read");
-        self.inner.blocking_read(path, args).map(|(rp, r)| {
-            (
-                rp,
-                MinitraceWrapper::new(Span::enter_with_parent("ReadOperation", &span), r),
-            )
-        })
+        let span = self.span(Operation::BlockingRead, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::reader", &span);
+        let _g = span.set_local_parent();
+        self.inner
+            .blocking_read(path, args)
+            .map(|(rp, r)| (rp, MinitraceWrapper::new(child, r)))
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        let span = Span::enter_with_local_parent("blocking_write");
-        self.inner.blocking_write(path, args).map(|(rp, r)| {
-            (
-                rp,
-                MinitraceWrapper::new(Span::enter_with_parent("WriteOperation", &span), r),
-            )
-        })
+        let span = self.span(Operation::BlockingWrite, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::writer", &span);
+        let _g = span.set_local_parent();
+        self.inner
+            .blocking_write(path, args)
+            .map(|(rp, r)| (rp, MinitraceWrapper::new(child, r)))
     }
 
-    #[trace("blocking_copy")]
     fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let span = self.span(Operation::BlockingCopy, &[("from", from), ("to", to)]);
+        let _g = span.set_local_parent();
         self.inner().blocking_copy(from, to, args)
     }
 
-    #[trace("blocking_rename")]
     fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let span = self.span(Operation::BlockingMove, &[("from", from), ("to", to)]);
+        let _g = span.set_local_parent();
         self.inner().blocking_rename(from, to, args)
     }
 
-    #[trace("blocking_stat")]
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let span = self.span(Operation::BlockingStat, &[("path", path)]);
+        let _g = span.set_local_parent();
         self.inner.blocking_stat(path, args)
     }
 
-    #[trace("blocking_delete")]
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let span = self.span(Operation::BlockingDelete, &[("path", path)]);
+        let _g = span.set_local_parent();
         self.inner.blocking_delete(path, args)
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
-        let span = Span::enter_with_local_parent("blocking_list");
-        self.inner.blocking_list(path, args).map(|(rp, it)| {
-            (
-                rp,
-                MinitraceWrapper::new(Span::enter_with_parent("PageOperation", &span), it),
-            )
-        })
+        let span = self.span(Operation::BlockingList, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::pager", &span);
+        let _g = span.set_local_parent();
+        self.inner
+            .blocking_list(path, args)
+            .map(|(rp, it)| (rp, MinitraceWrapper::new(child, it)))
     }
 
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
-        let span = Span::enter_with_local_parent("blocking_scan");
-        self.inner.blocking_scan(path, args).map(|(rp, it)| {
-            (
-                rp,
-                MinitraceWrapper::new(Span::enter_with_parent("PageOperation", &span), it),
-            )
-        })
+        let span = self.span(Operation::BlockingScan, &[("path", path)]);
+        let child = Span::enter_with_parent("opendal::pager", &span);
+        let _g = span.set_local_parent();
+        self.inner
+            .blocking_scan(path, args)
+            .map(|(rp, it)| (rp, MinitraceWrapper::new(child, it)))
     }
 }
 
 pub struct MinitraceWrapper<R> {
     span: Span,
     inner: R,
+    bytes: u64,
 }
 
 impl<R> MinitraceWrapper<R> {
     fn new(span: Span, inner: R) -> Self {
-        Self { span, inner }
+        Self {
+            span,
+            inner,
+            bytes: 0,
+        }
+    }
+}
+
+impl<R> Drop for MinitraceWrapper<R> {
+    fn drop(&mut self) {
+        let bytes = self.bytes;
+        self.span.add_property(|| ("bytes", bytes.to_string()));
     }
 }
 
 impl<R: oio::Read> oio::Read for MinitraceWrapper<R> {
     fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
         let _span = Span::enter_with_parent(ReadOperation::Read.into_static(), &self.span);
-        self.inner.poll_read(cx, buf)
+        let res = self.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = &res {
+            self.bytes += *n as u64;
+        }
+        res
     }
 
     fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
@@ -304,14 +356,20 @@ impl<R: oio::Read> oio::Read for MinitraceWrapper<R> {
 
     fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
         let _span = Span::enter_with_parent(ReadOperation::Next.into_static(), &self.span);
-        self.inner.poll_next(cx)
+        let res = self.inner.poll_next(cx);
+        if let Poll::Ready(Some(Ok(bs))) = &res {
+            self.bytes += bs.len() as u64;
+        }
+        res
     }
 }
 
 impl<R: oio::BlockingRead> oio::BlockingRead for MinitraceWrapper<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         let _span = Span::enter_with_parent(ReadOperation::BlockingRead.into_static(), &self.span);
-        self.inner.read(buf)
+        let n = self.inner.read(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
     }
 
     fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
@@ -321,30 +379,40 @@ impl<R: oio::BlockingRead> oio::BlockingRead for MinitraceWrapper<R> {
 
     fn next(&mut self) -> Option<Result<Bytes>> {
         let _span = Span::enter_with_parent(ReadOperation::BlockingNext.into_static(), &self.span);
-        self.inner.next()
+        let res = self.inner.next();
+        if let Some(Ok(bs)) = &res {
+            self.bytes += bs.len() as u64;
+        }
+        res
     }
 }
 
 #[async_trait]
 impl<R: oio::Write> oio::Write for MinitraceWrapper<R> {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len() as u64;
         self.inner
             .write(bs)
             .in_span(Span::enter_with_parent(
                 WriteOperation::Write.into_static(),
                 &self.span,
             ))
-            .await
+            .await?;
+        self.bytes += size;
+        Ok(())
     }
 
     async fn append(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len() as u64;
         self.inner
             .append(bs)
             .in_span(Span::enter_with_parent(
                 WriteOperation::Append.into_static(),
                 &self.span,
             ))
-            .await
+            .await?;
+        self.bytes += size;
+        Ok(())
     }
 
     async fn abort(&mut self) -> Result<()> {
@@ -372,13 +440,25 @@ impl<R: oio::BlockingWrite> oio::BlockingWrite for MinitraceWrapper<R> {
     fn write(&mut self, bs: Bytes) -> Result<()> {
         let _span =
             Span::enter_with_parent(WriteOperation::BlockingWrite.into_static(), &self.span);
-        self.inner.write(bs)
+        let size = bs.len() as u64;
+        self.inner.write(bs)?;
+        self.bytes += size;
+        Ok(())
     }
 
     fn append(&mut self, bs: Bytes) -> Result<()> {
         let _span =
             Span::enter_with_parent(WriteOperation::BlockingAppend.into_static(), &self.span);
-        self.inner.append(bs)
+        let size = bs.len() as u64;
+        self.inner.append(bs)?;
+        self.bytes += size;
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        let _span =
+            Span::enter_with_parent(WriteOperation::BlockingAbort.into_static(), &self.span);
+        self.inner.abort()
     }
 
     fn close(&mut self) -> Result<()> {
@@ -407,3 +487,56 @@ impl<R: oio::BlockingPage> oio::BlockingPage for MinitraceWrapper<R> {
         self.inner.next()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use minitrace::collector::SpanRecord;
+
+    use super::*;
+    use crate::services;
+    use crate::Operator;
+
+    #[test]
+    fn test_minitrace_spans() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(MinitraceLayer)
+            .finish()
+            .blocking();
+
+        let collector = {
+            let (root, collector) = Span::root("root");
+            let _g = root.set_local_parent();
+
+            op.write("test", "0".repeat(16)).unwrap();
+            let bs = op.read("test").unwrap();
+            assert_eq!(bs.len(), 16);
+
+            collector
+        };
+        let spans: Vec<SpanRecord> = block_on(collector.collect());
+
+        let write = spans
+            .iter()
+            .find(|s| s.event == "opendal::blocking_write")
+            .expect("write span must exist");
+        assert!(write
+            .properties
+            .iter()
+            .any(|(k, v)| *k == "scheme" && v == "memory"));
+        assert!(write
+            .properties
+            .iter()
+            .any(|(k, v)| *k == "path" && v == "test"));
+
+        let reader = spans
+            .iter()
+            .find(|s| s.event == "opendal::reader")
+            .expect("reader span must exist");
+        assert!(reader
+            .properties
+            .iter()
+            .any(|(k, v)| *k == "bytes" && v == "16"));
+    }
+}
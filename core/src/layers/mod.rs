@@ -17,6 +17,19 @@
 
 //! `Layer` is the mechanism to intercept operations.
 
+#[cfg(feature = "layers-await-tree")]
+mod await_tree;
+#[cfg(feature = "layers-await-tree")]
+pub use self::await_tree::AwaitTreeLayer;
+
+#[cfg(feature = "layers-blocking")]
+mod blocking;
+#[cfg(feature = "layers-blocking")]
+pub use blocking::BlockingLayer;
+
+mod cache;
+pub use cache::CacheLayer;
+
 mod concurrent_limit;
 pub use concurrent_limit::ConcurrentLimitLayer;
 
@@ -31,11 +44,19 @@ mod chaos;
 #[cfg(feature = "layers-chaos")]
 pub use chaos::ChaosLayer;
 
+#[cfg(any(feature = "layers-metrics", feature = "layers-prometheus"))]
+mod observe;
+
 #[cfg(feature = "layers-metrics")]
 mod metrics;
 #[cfg(feature = "layers-metrics")]
 pub use self::metrics::MetricsLayer;
 
+#[cfg(feature = "layers-mime-guess")]
+mod mime_guess;
+#[cfg(feature = "layers-mime-guess")]
+pub use self::mime_guess::MimeGuessLayer;
+
 #[cfg(feature = "layers-prometheus")]
 mod prometheus;
 #[cfg(feature = "layers-prometheus")]
@@ -44,6 +65,15 @@ pub use self::prometheus::PrometheusLayer;
 mod retry;
 pub use self::retry::RetryLayer;
 
+mod stat_cache;
+pub use self::stat_cache::StatCacheLayer;
+
+mod throttle;
+pub use self::throttle::ThrottleLayer;
+
+mod timeout;
+pub use self::timeout::TimeoutLayer;
+
 #[cfg(feature = "layers-tracing")]
 mod tracing;
 #[cfg(feature = "layers-tracing")]
@@ -63,6 +93,9 @@ pub(crate) use error_context::ErrorContextLayer;
 mod complete;
 pub(crate) use complete::CompleteLayer;
 
+mod correctness_check;
+pub(crate) use correctness_check::CorrectnessCheckLayer;
+
 #[cfg(feature = "layers-madsim")]
 #[cfg(madsim)]
 mod madsim;
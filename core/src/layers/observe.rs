@@ -0,0 +1,519 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Shared instrumentation points for metrics layers.
+//!
+//! Both [`MetricsLayer`][super::MetricsLayer] and
+//! [`PrometheusLayer`][super::PrometheusLayer] are built on top of
+//! [`ObserveAccessor`] so that they observe the same things at the same
+//! places. Backends only need to implement [`MetricsObserver`].
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// MetricsObserver is the sink of all instrumentation points.
+///
+/// # Notes
+///
+/// - `observe_request` will be called before every operation.
+/// - `observe_duration` will be called once the operation finished. For
+///   read and write, the duration covers the whole lifetime of the returned
+///   reader or writer.
+/// - `observe_bytes` will be called once the returned reader or writer is
+///   dropped with the bytes transferred.
+/// - `observe_error` will be called for every error, including errors
+///   returned by readers and writers.
+pub trait MetricsObserver: Send + Sync + 'static {
+    /// Observe a request of given operation.
+    fn observe_request(&self, op: Operation);
+    /// Observe the duration of given operation.
+    fn observe_duration(&self, op: Operation, dur: Duration);
+    /// Observe bytes transferred by given operation.
+    fn observe_bytes(&self, op: Operation, bytes: u64);
+    /// Observe an error returned by given operation.
+    fn observe_error(&self, op: Operation, kind: ErrorKind);
+}
+
+/// ObserveAccessor calls [`MetricsObserver`] for every operation.
+pub struct ObserveAccessor<A: Accessor, O: MetricsObserver> {
+    inner: A,
+    observer: Arc<O>,
+}
+
+impl<A: Accessor, O: MetricsObserver> ObserveAccessor<A, O> {
+    pub(crate) fn new(inner: A, observer: O) -> Self {
+        Self {
+            inner,
+            observer: Arc::new(observer),
+        }
+    }
+
+    /// Observe a unary operation that has been finished.
+    fn observe<T>(&self, op: Operation, start: Instant, result: Result<T>) -> Result<T> {
+        self.observer.observe_duration(op, start.elapsed());
+
+        result.map_err(|err| {
+            self.observer.observe_error(op, err.kind());
+            err
+        })
+    }
+
+    fn wrap<R>(&self, op: Operation, start: Instant, r: R) -> ObserveWrapper<R, O> {
+        ObserveWrapper {
+            inner: r,
+            op,
+            observer: self.observer.clone(),
+            start,
+            bytes: 0,
+        }
+    }
+}
+
+impl<A: Accessor, O: MetricsObserver> Debug for ObserveAccessor<A, O> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObserveAccessor")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<A: Accessor, O: MetricsObserver> LayeredAccessor for ObserveAccessor<A, O> {
+    type Inner = A;
+    type Reader = ObserveWrapper<A::Reader, O>;
+    type BlockingReader = ObserveWrapper<A::BlockingReader, O>;
+    type Writer = ObserveWrapper<A::Writer, O>;
+    type BlockingWriter = ObserveWrapper<A::BlockingWriter, O>;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    fn metadata(&self) -> AccessorInfo {
+        self.observer.observe_request(Operation::Info);
+
+        let start = Instant::now();
+        let result = self.inner.info();
+        self.observer
+            .observe_duration(Operation::Info, start.elapsed());
+
+        result
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.observer.observe_request(Operation::CreateDir);
+
+        let start = Instant::now();
+        let result = self.inner.create_dir(path, args).await;
+        self.observe(Operation::CreateDir, start, result)
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.observer.observe_request(Operation::Read);
+
+        let start = Instant::now();
+        match self.inner.read(path, args).await {
+            Ok((rp, r)) => Ok((rp, self.wrap(Operation::Read, start, r))),
+            Err(err) => self.observe(Operation::Read, start, Err(err)),
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.observer.observe_request(Operation::Write);
+
+        let start = Instant::now();
+        match self.inner.write(path, args).await {
+            Ok((rp, w)) => Ok((rp, self.wrap(Operation::Write, start, w))),
+            Err(err) => self.observe(Operation::Write, start, Err(err)),
+        }
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.observer.observe_request(Operation::Copy);
+
+        let start = Instant::now();
+        let result = self.inner.copy(from, to, args).await;
+        self.observe(Operation::Copy, start, result)
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.observer.observe_request(Operation::Rename);
+
+        let start = Instant::now();
+        let result = self.inner.rename(from, to, args).await;
+        self.observe(Operation::Rename, start, result)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.observer.observe_request(Operation::Stat);
+
+        let start = Instant::now();
+        let result = self.inner.stat(path, args).await;
+        self.observe(Operation::Stat, start, result)
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.observer.observe_request(Operation::Delete);
+
+        let start = Instant::now();
+        let result = self.inner.delete(path, args).await;
+        self.observe(Operation::Delete, start, result)
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.observer.observe_request(Operation::List);
+
+        let start = Instant::now();
+        let result = self.inner.list(path, args).await;
+        self.observe(Operation::List, start, result)
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.observer.observe_request(Operation::Scan);
+
+        let start = Instant::now();
+        let result = self.inner.scan(path, args).await;
+        self.observe(Operation::Scan, start, result)
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.observer.observe_request(Operation::Batch);
+
+        let start = Instant::now();
+        let result = self.inner.batch(args).await;
+        self.observe(Operation::Batch, start, result)
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.observer.observe_request(Operation::Presign);
+
+        let start = Instant::now();
+        let result = self.inner.presign(path, args).await;
+        self.observe(Operation::Presign, start, result)
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.observer.observe_request(Operation::BlockingCreateDir);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_create_dir(path, args);
+        self.observe(Operation::BlockingCreateDir, start, result)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.observer.observe_request(Operation::BlockingRead);
+
+        let start = Instant::now();
+        match self.inner.blocking_read(path, args) {
+            Ok((rp, r)) => Ok((rp, self.wrap(Operation::BlockingRead, start, r))),
+            Err(err) => self.observe(Operation::BlockingRead, start, Err(err)),
+        }
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.observer.observe_request(Operation::BlockingWrite);
+
+        let start = Instant::now();
+        match self.inner.blocking_write(path, args) {
+            Ok((rp, w)) => Ok((rp, self.wrap(Operation::BlockingWrite, start, w))),
+            Err(err) => self.observe(Operation::BlockingWrite, start, Err(err)),
+        }
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.observer.observe_request(Operation::BlockingCopy);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_copy(from, to, args);
+        self.observe(Operation::BlockingCopy, start, result)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.observer.observe_request(Operation::BlockingMove);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_rename(from, to, args);
+        self.observe(Operation::BlockingMove, start, result)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.observer.observe_request(Operation::BlockingStat);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_stat(path, args);
+        self.observe(Operation::BlockingStat, start, result)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.observer.observe_request(Operation::BlockingDelete);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_delete(path, args);
+        self.observe(Operation::BlockingDelete, start, result)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.observer.observe_request(Operation::BlockingList);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_list(path, args);
+        self.observe(Operation::BlockingList, start, result)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.observer.observe_request(Operation::BlockingScan);
+
+        let start = Instant::now();
+        let result = self.inner.blocking_scan(path, args);
+        self.observe(Operation::BlockingScan, start, result)
+    }
+}
+
+/// ObserveWrapper will report bytes and duration while dropped.
+pub struct ObserveWrapper<R, O: MetricsObserver> {
+    inner: R,
+
+    op: Operation,
+    observer: Arc<O>,
+
+    start: Instant,
+    bytes: u64,
+}
+
+impl<R, O: MetricsObserver> ObserveWrapper<R, O> {
+    fn observe_error(&self, err: Error) -> Error {
+        self.observer.observe_error(self.op, err.kind());
+        err
+    }
+}
+
+impl<R, O: MetricsObserver> Drop for ObserveWrapper<R, O> {
+    fn drop(&mut self) {
+        self.observer.observe_bytes(self.op, self.bytes);
+        self.observer
+            .observe_duration(self.op, self.start.elapsed());
+    }
+}
+
+impl<R: oio::Read, O: MetricsObserver> oio::Read for ObserveWrapper<R, O> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        self.inner.poll_read(cx, buf).map(|res| match res {
+            Ok(n) => {
+                self.bytes += n as u64;
+                Ok(n)
+            }
+            Err(err) => Err(self.observe_error(err)),
+        })
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        self.inner
+            .poll_seek(cx, pos)
+            .map(|res| res.map_err(|err| self.observe_error(err)))
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        self.inner.poll_next(cx).map(|res| match res {
+            Some(Ok(bs)) => {
+                self.bytes += bs.len() as u64;
+                Some(Ok(bs))
+            }
+            Some(Err(err)) => Some(Err(self.observe_error(err))),
+            None => None,
+        })
+    }
+}
+
+impl<R: oio::BlockingRead, O: MetricsObserver> oio::BlockingRead for ObserveWrapper<R, O> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.inner.read(buf) {
+            Ok(n) => {
+                self.bytes += n as u64;
+                Ok(n)
+            }
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
+        self.inner.seek(pos).map_err(|err| self.observe_error(err))
+    }
+
+    fn next(&mut self) -> Option<Result<Bytes>> {
+        match self.inner.next() {
+            Some(Ok(bs)) => {
+                self.bytes += bs.len() as u64;
+                Some(Ok(bs))
+            }
+            Some(Err(err)) => Some(Err(self.observe_error(err))),
+            None => None,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: oio::Write, O: MetricsObserver> oio::Write for ObserveWrapper<R, O> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len();
+        match self.inner.write(bs).await {
+            Ok(_) => {
+                self.bytes += size as u64;
+                Ok(())
+            }
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len();
+        match self.inner.append(bs).await {
+            Ok(_) => {
+                self.bytes += size as u64;
+                Ok(())
+            }
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        match self.inner.abort().await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        match self.inner.close().await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+}
+
+impl<R: oio::BlockingWrite, O: MetricsObserver> oio::BlockingWrite for ObserveWrapper<R, O> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len();
+        match self.inner.write(bs) {
+            Ok(_) => {
+                self.bytes += size as u64;
+                Ok(())
+            }
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        let size = bs.len();
+        match self.inner.append(bs) {
+            Ok(_) => {
+                self.bytes += size as u64;
+                Ok(())
+            }
+            Err(err) => Err(self.observe_error(err)),
+        }
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort().map_err(|err| self.observe_error(err))
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.inner.close().map_err(|err| self.observe_error(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::services;
+
+    #[derive(Default)]
+    struct MockObserver {
+        requests: Mutex<Vec<Operation>>,
+        bytes: Mutex<Vec<(Operation, u64)>>,
+        errors: Mutex<Vec<(Operation, ErrorKind)>>,
+    }
+
+    impl MetricsObserver for Arc<MockObserver> {
+        fn observe_request(&self, op: Operation) {
+            self.requests.lock().unwrap().push(op)
+        }
+
+        fn observe_duration(&self, _: Operation, _: Duration) {}
+
+        fn observe_bytes(&self, op: Operation, bytes: u64) {
+            self.bytes.lock().unwrap().push((op, bytes))
+        }
+
+        fn observe_error(&self, op: Operation, kind: ErrorKind) {
+            self.errors.lock().unwrap().push((op, kind))
+        }
+    }
+
+    struct MockLayer(Arc<MockObserver>);
+
+    impl<A: Accessor> Layer<A> for MockLayer {
+        type LayeredAccessor = ObserveAccessor<A, Arc<MockObserver>>;
+
+        fn layer(&self, inner: A) -> Self::LayeredAccessor {
+            ObserveAccessor::new(inner, self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observe() {
+        let observer = Arc::new(MockObserver::default());
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(MockLayer(observer.clone()))
+            .finish();
+
+        op.write("test", "Hello, World!").await.unwrap();
+        let bs = op.read("test").await.unwrap();
+        assert_eq!(bs.len(), 13);
+        assert!(op.stat("not_exist").await.is_err());
+
+        let requests = observer.requests.lock().unwrap().clone();
+        assert!(requests.contains(&Operation::Write));
+        assert!(requests.contains(&Operation::Read));
+        assert!(requests.contains(&Operation::Stat));
+
+        let bytes = observer.bytes.lock().unwrap().clone();
+        assert!(bytes.contains(&(Operation::Write, 13)));
+        assert!(bytes.contains(&(Operation::Read, 13)));
+
+        let errors = observer.errors.lock().unwrap().clone();
+        assert_eq!(errors, vec![(Operation::Stat, ErrorKind::NotFound)]);
+    }
+}
@@ -15,16 +15,20 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::fmt::Debug;
+use std::fmt::Formatter;
 use std::io;
+use std::sync::Arc;
 use std::task;
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::FutureExt;
 use opentelemetry::global;
-use opentelemetry::global::BoxedSpan;
+use opentelemetry::global::BoxedTracer;
 use opentelemetry::trace::FutureExt as TraceFutureExt;
-use opentelemetry::trace::Span;
+use opentelemetry::trace::SpanKind;
+use opentelemetry::trace::Status;
 use opentelemetry::trace::TraceContextExt;
 use opentelemetry::trace::Tracer;
 use opentelemetry::Context;
@@ -36,10 +40,38 @@ use crate::*;
 
 /// Add [opentelemetry::trace](https://docs.rs/opentelemetry/latest/opentelemetry/trace/index.html) for every operations.
 ///
-/// Examples
+/// # Spans
+///
+/// OtelTraceLayer will create a span with [`SpanKind::Client`] for every
+/// operation, the span name is the name of [`Operation`]. The span will be
+/// created as a child of current context, and the span's context will be
+/// propagated into underlying services.
+///
+/// Every span will carry the following attributes:
+///
+/// - `rpc.system`: always `opendal`
+/// - `rpc.service`: the [`Scheme`] of underlying service
+/// - `rpc.method`: the name of [`Operation`]
+/// - `opendal.name`: the name of underlying service, like bucket name
+/// - `net.peer.name`: the host of underlying service's endpoint, only
+///   available for services that talk to a remote server
+/// - `opendal.path`: the path of this operation, copy and rename will
+///   use `opendal.from` and `opendal.to` instead
+///
+/// Spans of `read`, `write`, `list` and `scan` (and their blocking
+/// versions) will be ended while the returning reader, writer or pager
+/// completed instead of the call returned. Reader and writer spans will
+/// carry an extra `opendal.bytes` attribute for the bytes transferred.
+///
+/// Errors will be recorded as exceptions and the span status will be set
+/// to error.
+///
+/// # Examples
 ///
 /// ## Basic Setup
 ///
+/// Use the tracer `opendal` from global tracer provider.
+///
 /// ```
 /// use anyhow::Result;
 /// use opendal::layers::OtelTraceLayer;
@@ -48,26 +80,176 @@ use crate::*;
 ///
 /// let _ = Operator::new(services::Memory::default())
 ///     .expect("must init")
-///     .layer(OtelTraceLayer)
+///     .layer(OtelTraceLayer::new())
 ///     .finish();
 /// ```
-pub struct OtelTraceLayer;
+///
+/// ## Specify Tracer
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::OtelTraceLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+/// use opentelemetry::global;
+/// use opentelemetry::trace::TracerProvider;
+///
+/// let tracer = global::tracer_provider().tracer("my_app");
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(OtelTraceLayer::with_tracer(tracer))
+///     .finish();
+/// ```
+pub struct OtelTraceLayer<T = BoxedTracer> {
+    tracer: Arc<T>,
+}
 
-impl<A: Accessor> Layer<A> for OtelTraceLayer {
-    type LayeredAccessor = OtelTraceAccessor<A>;
+impl OtelTraceLayer {
+    /// Create a new OtelTraceLayer with the `opendal` tracer from global
+    /// tracer provider.
+    ///
+    /// # Notes
+    ///
+    /// The tracer will be fetched while creating this layer, so please
+    /// make sure global tracer provider has been set before.
+    pub fn new() -> Self {
+        Self::with_tracer(global::tracer("opendal"))
+    }
+}
+
+impl Default for OtelTraceLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> OtelTraceLayer<T> {
+    /// Create a new OtelTraceLayer with given tracer.
+    pub fn with_tracer(tracer: T) -> Self {
+        Self {
+            tracer: Arc::new(tracer),
+        }
+    }
+}
+
+impl<A, T> Layer<A> for OtelTraceLayer<T>
+where
+    A: Accessor,
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    type LayeredAccessor = OtelTraceAccessor<A, T>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
-        OtelTraceAccessor { inner }
+        let info = inner.info();
+
+        OtelTraceAccessor {
+            inner,
+            tracer: self.tracer.clone(),
+            scheme: info.scheme(),
+            name: info.name().to_string(),
+            peer: info.endpoint().map(peer_name),
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct OtelTraceAccessor<A> {
+/// Extract the host from endpoint, fallback to the endpoint itself if
+/// it's not a valid uri.
+fn peer_name(endpoint: &str) -> String {
+    endpoint
+        .parse::<http::Uri>()
+        .ok()
+        .and_then(|uri| uri.host().map(|host| host.to_string()))
+        .unwrap_or_else(|| endpoint.to_string())
+}
+
+pub struct OtelTraceAccessor<A, T> {
     inner: A,
+    tracer: Arc<T>,
+
+    scheme: Scheme,
+    name: String,
+    peer: Option<String>,
+}
+
+impl<A: Debug, T> Debug for OtelTraceAccessor<A, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtelTraceAccessor")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, T> OtelTraceAccessor<A, T>
+where
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    /// Start a new span as the child of current context.
+    fn start(&self, op: Operation, attrs: Vec<KeyValue>) -> Context {
+        let parent = Context::current();
+
+        let mut attributes = vec![
+            KeyValue::new("rpc.system", "opendal"),
+            KeyValue::new("rpc.service", self.scheme.into_static()),
+            KeyValue::new("rpc.method", op.into_static()),
+            KeyValue::new("opendal.name", self.name.clone()),
+        ];
+        if let Some(peer) = &self.peer {
+            attributes.push(KeyValue::new("net.peer.name", peer.clone()));
+        }
+        attributes.extend(attrs);
+
+        let span = self
+            .tracer
+            .span_builder(op.into_static())
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes)
+            .start_with_context(self.tracer.as_ref(), &parent);
+
+        parent.with_span(span)
+    }
+}
+
+/// Record the result into span and end it.
+fn finish<T>(cx: &Context, res: &Result<T>) {
+    if let Err(err) = res {
+        record_error(cx, err);
+    }
+    cx.span().end();
+}
+
+fn record_error(cx: &Context, err: &Error) {
+    let span = cx.span();
+    span.record_error(err);
+    span.set_status(Status::error(err.to_string()));
+}
+
+fn path_attrs(path: &str) -> Vec<KeyValue> {
+    vec![KeyValue::new("opendal.path", path.to_string())]
+}
+
+fn from_to_attrs(from: &str, to: &str) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("opendal.from", from.to_string()),
+        KeyValue::new("opendal.to", to.to_string()),
+    ]
+}
+
+fn range_attrs(path: &str, args: &OpRead) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("opendal.path", path.to_string()),
+        KeyValue::new("opendal.range", args.range().to_string()),
+    ]
 }
 
 #[async_trait]
-impl<A: Accessor> LayeredAccessor for OtelTraceAccessor<A> {
+impl<A, T> LayeredAccessor for OtelTraceAccessor<A, T>
+where
+    A: Accessor,
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
     type Inner = A;
     type Reader = OtelTraceWrapper<A::Reader>;
     type BlockingReader = OtelTraceWrapper<A::BlockingReader>;
@@ -81,219 +263,280 @@ impl<A: Accessor> LayeredAccessor for OtelTraceAccessor<A> {
     }
 
     fn metadata(&self) -> AccessorInfo {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("metadata", |_cx| self.inner.info())
+        let cx = self.start(Operation::Info, vec![]);
+        let _guard = cx.clone().attach();
+        let info = self.inner.info();
+        cx.span().end();
+        info
     }
 
     async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("create");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner.create_dir(path, args).with_context(cx).await
+        let cx = self.start(Operation::CreateDir, path_attrs(path));
+        let res = self
+            .inner
+            .create_dir(path, args)
+            .with_context(cx.clone())
+            .await;
+        finish(&cx, &res);
+        res
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("read");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
+        let cx = self.start(Operation::Read, range_attrs(path, &args));
         self.inner
             .read(path, args)
-            .map(|v| v.map(|(rp, r)| (rp, OtelTraceWrapper::new(span, r))))
+            .with_context(cx.clone())
+            .map(|v| match v {
+                Ok((rp, r)) => Ok((rp, OtelTraceWrapper::new(cx, r))),
+                Err(err) => Err(finish_err(&cx, err)),
+            })
             .await
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("write");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
+        let cx = self.start(Operation::Write, path_attrs(path));
         self.inner
             .write(path, args)
+            .with_context(cx.clone())
+            .map(|v| match v {
+                Ok((rp, w)) => Ok((rp, OtelTraceWrapper::new(cx, w))),
+                Err(err) => Err(finish_err(&cx, err)),
+            })
             .await
-            .map(|(rp, r)| (rp, OtelTraceWrapper::new(span, r)))
     }
 
     async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("copy");
-        span.set_attribute(KeyValue::new("from", from.to_string()));
-        span.set_attribute(KeyValue::new("to", to.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().copy(from, to, args).with_context(cx).await
+        let cx = self.start(Operation::Copy, from_to_attrs(from, to));
+        let res = self
+            .inner
+            .copy(from, to, args)
+            .with_context(cx.clone())
+            .await;
+        finish(&cx, &res);
+        res
     }
 
     async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("rename");
-        span.set_attribute(KeyValue::new("from", from.to_string()));
-        span.set_attribute(KeyValue::new("to", to.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().rename(from, to, args).with_context(cx).await
+        let cx = self.start(Operation::Rename, from_to_attrs(from, to));
+        let res = self
+            .inner
+            .rename(from, to, args)
+            .with_context(cx.clone())
+            .await;
+        finish(&cx, &res);
+        res
     }
 
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("stat");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().stat(path, args).with_context(cx).await
+        let cx = self.start(Operation::Stat, path_attrs(path));
+        let res = self.inner.stat(path, args).with_context(cx.clone()).await;
+        finish(&cx, &res);
+        res
     }
 
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("delete");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().delete(path, args).with_context(cx).await
+        let cx = self.start(Operation::Delete, path_attrs(path));
+        let res = self.inner.delete(path, args).with_context(cx.clone()).await;
+        finish(&cx, &res);
+        res
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("list");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
+        let cx = self.start(Operation::List, path_attrs(path));
         self.inner
             .list(path, args)
-            .map(|v| v.map(|(rp, s)| (rp, OtelTraceWrapper::new(span, s))))
+            .with_context(cx.clone())
+            .map(|v| match v {
+                Ok((rp, p)) => Ok((rp, OtelTraceWrapper::new(cx, p))),
+                Err(err) => Err(finish_err(&cx, err)),
+            })
             .await
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("scan");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
+        let cx = self.start(Operation::Scan, path_attrs(path));
         self.inner
             .scan(path, args)
-            .map(|v| v.map(|(rp, s)| (rp, OtelTraceWrapper::new(span, s))))
+            .with_context(cx.clone())
+            .map(|v| match v {
+                Ok((rp, p)) => Ok((rp, OtelTraceWrapper::new(cx, p))),
+                Err(err) => Err(finish_err(&cx, err)),
+            })
             .await
     }
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("batch");
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().batch(args).with_context(cx).await
+        let cx = self.start(
+            Operation::Batch,
+            vec![KeyValue::new(
+                "opendal.batch_size",
+                args.operation().len() as i64,
+            )],
+        );
+        let res = self.inner.batch(args).with_context(cx.clone()).await;
+        finish(&cx, &res);
+        res
     }
 
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("presign");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        let cx = Context::current_with_span(span);
-        self.inner().presign(path, args).with_context(cx).await
+        let cx = self.start(Operation::Presign, path_attrs(path));
+        let res = self
+            .inner
+            .presign(path, args)
+            .with_context(cx.clone())
+            .await;
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("blocking_create_dir", |cx| {
-            let span = cx.span(); // let mut span = cx.();
-            span.set_attribute(KeyValue::new("path", path.to_string()));
-            span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-            self.inner().blocking_create_dir(path, args)
-        })
+        let cx = self.start(Operation::BlockingCreateDir, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_create_dir(path, args)
+        };
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("blocking_read");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        self.inner
-            .blocking_read(path, args)
-            .map(|(rp, r)| (rp, OtelTraceWrapper::new(span, r)))
+        let cx = self.start(Operation::BlockingRead, range_attrs(path, &args));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_read(path, args)
+        };
+        match res {
+            Ok((rp, r)) => Ok((rp, OtelTraceWrapper::new(cx, r))),
+            Err(err) => Err(finish_err(&cx, err)),
+        }
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("blocking_write");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        self.inner
-            .blocking_write(path, args)
-            .map(|(rp, r)| (rp, OtelTraceWrapper::new(span, r)))
+        let cx = self.start(Operation::BlockingWrite, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_write(path, args)
+        };
+        match res {
+            Ok((rp, w)) => Ok((rp, OtelTraceWrapper::new(cx, w))),
+            Err(err) => Err(finish_err(&cx, err)),
+        }
     }
 
     fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("blocking_copy", |cx| {
-            let span = cx.span();
-            span.set_attribute(KeyValue::new("from", from.to_string()));
-            span.set_attribute(KeyValue::new("to", to.to_string()));
-            span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-            self.inner().blocking_copy(from, to, args)
-        })
+        let cx = self.start(Operation::BlockingCopy, from_to_attrs(from, to));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_copy(from, to, args)
+        };
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("blocking_rename", |cx| {
-            let span = cx.span();
-            span.set_attribute(KeyValue::new("from", from.to_string()));
-            span.set_attribute(KeyValue::new("to", to.to_string()));
-            span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-            self.inner().blocking_rename(from, to, args)
-        })
+        let cx = self.start(Operation::BlockingMove, from_to_attrs(from, to));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_rename(from, to, args)
+        };
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("blocking_stat", |cx| {
-            let span = cx.span();
-            span.set_attribute(KeyValue::new("path", path.to_string()));
-            span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-            self.inner().blocking_stat(path, args)
-        })
+        let cx = self.start(Operation::BlockingStat, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_stat(path, args)
+        };
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        let tracer = global::tracer("opendal");
-        tracer.in_span("blocking_delete", |cx| {
-            let span = cx.span();
-            span.set_attribute(KeyValue::new("path", path.to_string()));
-            span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-            self.inner().blocking_delete(path, args)
-        })
+        let cx = self.start(Operation::BlockingDelete, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_delete(path, args)
+        };
+        finish(&cx, &res);
+        res
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("blocking_list");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        self.inner
-            .blocking_list(path, args)
-            .map(|(rp, it)| (rp, OtelTraceWrapper::new(span, it)))
+        let cx = self.start(Operation::BlockingList, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_list(path, args)
+        };
+        match res {
+            Ok((rp, p)) => Ok((rp, OtelTraceWrapper::new(cx, p))),
+            Err(err) => Err(finish_err(&cx, err)),
+        }
     }
 
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
-        let tracer = global::tracer("opendal");
-        let mut span = tracer.start("blocking_scan");
-        span.set_attribute(KeyValue::new("path", path.to_string()));
-        span.set_attribute(KeyValue::new("args", format!("{:?}", args)));
-        self.inner
-            .blocking_scan(path, args)
-            .map(|(rp, it)| (rp, OtelTraceWrapper::new(span, it)))
+        let cx = self.start(Operation::BlockingScan, path_attrs(path));
+        let res = {
+            let _guard = cx.clone().attach();
+            self.inner.blocking_scan(path, args)
+        };
+        match res {
+            Ok((rp, p)) => Ok((rp, OtelTraceWrapper::new(cx, p))),
+            Err(err) => Err(finish_err(&cx, err)),
+        }
     }
 }
 
+/// Record the error into span, end it and return the error back.
+fn finish_err(cx: &Context, err: Error) -> Error {
+    record_error(cx, &err);
+    cx.span().end();
+    err
+}
+
+/// OtelTraceWrapper will keep the span alive until the wrapped reader,
+/// writer or pager completed.
 pub struct OtelTraceWrapper<R> {
-    _span: BoxedSpan,
+    cx: Context,
     inner: R,
+
+    bytes: u64,
+    ended: bool,
 }
 
 impl<R> OtelTraceWrapper<R> {
-    fn new(_span: BoxedSpan, inner: R) -> Self {
-        Self { _span, inner }
+    fn new(cx: Context, inner: R) -> Self {
+        Self {
+            cx,
+            inner,
+            bytes: 0,
+            ended: false,
+        }
+    }
+
+    fn end(&mut self) {
+        if self.ended {
+            return;
+        }
+        self.ended = true;
+
+        let span = self.cx.span();
+        span.set_attribute(KeyValue::new("opendal.bytes", self.bytes as i64));
+        span.end();
+    }
+
+    fn track<T>(&mut self, res: Result<T>) -> Result<T> {
+        if let Err(err) = &res {
+            record_error(&self.cx, err);
+        }
+        res
+    }
+}
+
+impl<R> Drop for OtelTraceWrapper<R> {
+    fn drop(&mut self) {
+        self.end()
     }
 }
 
@@ -303,7 +546,14 @@ impl<R: oio::Read> oio::Read for OtelTraceWrapper<R> {
         cx: &mut task::Context<'_>,
         buf: &mut [u8],
     ) -> task::Poll<Result<usize>> {
-        self.inner.poll_read(cx, buf)
+        let _guard = self.cx.clone().attach();
+        let res = futures::ready!(self.inner.poll_read(cx, buf));
+        match res {
+            Ok(0) if !buf.is_empty() => self.end(),
+            Ok(n) => self.bytes += n as u64,
+            Err(_) => {}
+        }
+        task::Poll::Ready(self.track(res))
     }
 
     fn poll_seek(
@@ -311,70 +561,178 @@ impl<R: oio::Read> oio::Read for OtelTraceWrapper<R> {
         cx: &mut task::Context<'_>,
         pos: io::SeekFrom,
     ) -> task::Poll<Result<u64>> {
-        self.inner.poll_seek(cx, pos)
+        let _guard = self.cx.clone().attach();
+        let res = futures::ready!(self.inner.poll_seek(cx, pos));
+        task::Poll::Ready(self.track(res))
     }
 
     fn poll_next(&mut self, cx: &mut task::Context<'_>) -> task::Poll<Option<Result<Bytes>>> {
-        self.inner.poll_next(cx)
+        let _guard = self.cx.clone().attach();
+        let res = futures::ready!(self.inner.poll_next(cx));
+        match res {
+            None => {
+                self.end();
+                task::Poll::Ready(None)
+            }
+            Some(Ok(bs)) => {
+                self.bytes += bs.len() as u64;
+                task::Poll::Ready(Some(Ok(bs)))
+            }
+            Some(Err(err)) => task::Poll::Ready(Some(self.track(Err(err)))),
+        }
     }
 }
 
 impl<R: oio::BlockingRead> oio::BlockingRead for OtelTraceWrapper<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.inner.read(buf)
+        let _guard = self.cx.clone().attach();
+        let res = self.inner.read(buf);
+        match res {
+            Ok(0) if !buf.is_empty() => self.end(),
+            Ok(n) => self.bytes += n as u64,
+            Err(_) => {}
+        }
+        self.track(res)
     }
 
-    fn seek(&mut self, pos: std::io::SeekFrom) -> Result<u64> {
-        self.inner.seek(pos)
+    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
+        let _guard = self.cx.clone().attach();
+        let res = self.inner.seek(pos);
+        self.track(res)
     }
 
     fn next(&mut self) -> Option<Result<Bytes>> {
-        self.inner.next()
+        let _guard = self.cx.clone().attach();
+        match self.inner.next() {
+            None => {
+                self.end();
+                None
+            }
+            Some(Ok(bs)) => {
+                self.bytes += bs.len() as u64;
+                Some(Ok(bs))
+            }
+            Some(Err(err)) => Some(self.track(Err(err))),
+        }
     }
 }
 
 #[async_trait]
 impl<R: oio::Write> oio::Write for OtelTraceWrapper<R> {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.inner.write(bs).await
+        let size = bs.len();
+        let res = self.inner.write(bs).with_context(self.cx.clone()).await;
+        if res.is_ok() {
+            self.bytes += size as u64;
+        }
+        self.track(res)
     }
 
     async fn append(&mut self, bs: Bytes) -> Result<()> {
-        self.inner.append(bs).await
+        let size = bs.len();
+        let res = self.inner.append(bs).with_context(self.cx.clone()).await;
+        if res.is_ok() {
+            self.bytes += size as u64;
+        }
+        self.track(res)
     }
 
     async fn abort(&mut self) -> Result<()> {
-        self.inner.abort().await
+        let res = self.inner.abort().with_context(self.cx.clone()).await;
+        let res = self.track(res);
+        self.end();
+        res
     }
 
     async fn close(&mut self) -> Result<()> {
-        self.inner.close().await
+        let res = self.inner.close().with_context(self.cx.clone()).await;
+        let res = self.track(res);
+        self.end();
+        res
     }
 }
 
 impl<R: oio::BlockingWrite> oio::BlockingWrite for OtelTraceWrapper<R> {
     fn write(&mut self, bs: Bytes) -> Result<()> {
-        self.inner.write(bs)
+        let _guard = self.cx.clone().attach();
+        let size = bs.len();
+        let res = self.inner.write(bs);
+        if res.is_ok() {
+            self.bytes += size as u64;
+        }
+        self.track(res)
     }
 
     fn append(&mut self, bs: Bytes) -> Result<()> {
-        self.inner.append(bs)
+        let _guard = self.cx.clone().attach();
+        let size = bs.len();
+        let res = self.inner.append(bs);
+        if res.is_ok() {
+            self.bytes += size as u64;
+        }
+        self.track(res)
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        let res = {
+            let _guard = self.cx.clone().attach();
+            self.inner.abort()
+        };
+        let res = self.track(res);
+        self.end();
+        res
     }
 
     fn close(&mut self) -> Result<()> {
-        self.inner.close()
+        let res = {
+            let _guard = self.cx.clone().attach();
+            self.inner.close()
+        };
+        let res = self.track(res);
+        self.end();
+        res
     }
 }
 
 #[async_trait]
 impl<R: oio::Page> oio::Page for OtelTraceWrapper<R> {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        self.inner.next().await
+        let res = self.inner.next().with_context(self.cx.clone()).await;
+        if let Ok(None) = res {
+            self.end();
+        }
+        self.track(res)
     }
 }
 
 impl<R: oio::BlockingPage> oio::BlockingPage for OtelTraceWrapper<R> {
     fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        self.inner.next()
+        let res = {
+            let _guard = self.cx.clone().attach();
+            self.inner.next()
+        };
+        if let Ok(None) = res {
+            self.end();
+        }
+        self.track(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_name() {
+        let cases = vec![
+            ("https://s3.amazonaws.com", "s3.amazonaws.com"),
+            ("http://127.0.0.1:9000", "127.0.0.1"),
+            ("https://example.com/root/", "example.com"),
+            ("not a uri", "not a uri"),
+        ];
+
+        for (endpoint, expected) in cases {
+            assert_eq!(peer_name(endpoint), expected, "{endpoint}");
+        }
     }
 }
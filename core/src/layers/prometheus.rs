@@ -16,17 +16,8 @@
 // under the License.
 
 use std::fmt::Debug;
-use std::fmt::Formatter;
-use std::io;
-use std::sync::Arc;
-use std::task::Context;
-use std::task::Poll;
+use std::time::Duration;
 
-use async_trait::async_trait;
-use bytes::Bytes;
-use futures::FutureExt;
-use futures::TryFutureExt;
-use log::debug;
 use prometheus::core::AtomicU64;
 use prometheus::core::GenericCounterVec;
 use prometheus::exponential_buckets;
@@ -36,10 +27,12 @@ use prometheus::register_int_counter_vec_with_registry;
 use prometheus::HistogramVec;
 use prometheus::Registry;
 
-use crate::ops::*;
+use super::observe::MetricsObserver;
+use super::observe::ObserveAccessor;
 use crate::raw::Accessor;
 use crate::raw::*;
 use crate::*;
+
 /// Add [prometheus](https://docs.rs/prometheus) for every operations.
 ///
 /// # Examples
@@ -99,19 +92,21 @@ impl PrometheusLayer {
 }
 
 impl<A: Accessor> Layer<A> for PrometheusLayer {
-    type LayeredAccessor = PrometheusAccessor<A>;
+    type LayeredAccessor = ObserveAccessor<A, PrometheusObserver>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
         let meta = inner.info();
-        let scheme = meta.scheme();
 
-        PrometheusAccessor {
+        ObserveAccessor::new(
             inner,
-            stats: Arc::new(PrometheusMetrics::new(self.registry.clone())),
-            scheme: scheme.to_string(),
-        }
+            PrometheusObserver {
+                stats: PrometheusMetrics::new(self.registry.clone()),
+                scheme: meta.scheme().into_static(),
+            },
+        )
     }
 }
+
 /// [`PrometheusMetrics`] provide the performance and IO metrics.
 #[derive(Debug)]
 pub struct PrometheusMetrics {
@@ -121,6 +116,8 @@ pub struct PrometheusMetrics {
     pub requests_duration_seconds: HistogramVec,
     /// Size of the specific metrics.
     pub bytes_total: HistogramVec,
+    /// Total times of the specific operation failed.
+    pub errors_total: GenericCounterVec<AtomicU64>,
 }
 
 impl PrometheusMetrics {
@@ -152,643 +149,56 @@ impl PrometheusMetrics {
             register_histogram_vec_with_registry!(opts, &["scheme", "operation"], registry)
                 .unwrap();
 
+        let errors_total = register_int_counter_vec_with_registry!(
+            "errors_total",
+            "Total times of specific operation failed",
+            &["scheme", "operation", "error"],
+            registry
+        )
+        .unwrap();
+
         Self {
             requests_total,
             requests_duration_seconds,
             bytes_total,
+            errors_total,
         }
     }
-
-    /// error handling is the cold path, so we will not init error counters
-    /// in advance.
-    #[inline]
-    fn increment_errors_total(&self, op: Operation, kind: ErrorKind) {
-        debug!(
-            "Prometheus statistics metrics error, operation {} error {}",
-            op.into_static(),
-            kind.into_static()
-        );
-    }
 }
 
-#[derive(Clone)]
-pub struct PrometheusAccessor<A: Accessor> {
-    inner: A,
-    stats: Arc<PrometheusMetrics>,
-    scheme: String,
-}
-
-impl<A: Accessor> Debug for PrometheusAccessor<A> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("PrometheusAccessor")
-            .field("inner", &self.inner)
-            .finish_non_exhaustive()
-    }
+/// PrometheusObserver reports observed metrics into [`PrometheusMetrics`].
+#[derive(Debug)]
+pub struct PrometheusObserver {
+    stats: PrometheusMetrics,
+    scheme: &'static str,
 }
 
-#[async_trait]
-impl<A: Accessor> LayeredAccessor for PrometheusAccessor<A> {
-    type Inner = A;
-    type Reader = PrometheusMetricWrapper<A::Reader>;
-    type BlockingReader = PrometheusMetricWrapper<A::BlockingReader>;
-    type Writer = PrometheusMetricWrapper<A::Writer>;
-    type BlockingWriter = PrometheusMetricWrapper<A::BlockingWriter>;
-    type Pager = A::Pager;
-    type BlockingPager = A::BlockingPager;
-
-    fn inner(&self) -> &Self::Inner {
-        &self.inner
-    }
-
-    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::CreateDir.into_static()])
-            .start_timer();
-        let create_res = self.inner.create_dir(path, args).await;
-
-        timer.observe_duration();
-        create_res.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::CreateDir, e.kind());
-            e
-        })
-    }
-
-    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Read.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Read.into_static()])
-            .start_timer();
-
-        let read_res = self
-            .inner
-            .read(path, args)
-            .map(|v| {
-                v.map(|(rp, r)| {
-                    self.stats
-                        .bytes_total
-                        .with_label_values(&[&self.scheme, Operation::Read.into_static()])
-                        .observe(rp.metadata().content_length() as f64);
-                    (
-                        rp,
-                        PrometheusMetricWrapper::new(
-                            r,
-                            Operation::Read,
-                            self.stats.clone(),
-                            &self.scheme,
-                        ),
-                    )
-                })
-            })
-            .await;
-        timer.observe_duration();
-        read_res.map_err(|e| {
-            self.stats.increment_errors_total(Operation::Read, e.kind());
-            e
-        })
-    }
-
-    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Write.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Write.into_static()])
-            .start_timer();
-
-        let write_res = self
-            .inner
-            .write(path, args)
-            .map(|v| {
-                v.map(|(rp, r)| {
-                    (
-                        rp,
-                        PrometheusMetricWrapper::new(
-                            r,
-                            Operation::Write,
-                            self.stats.clone(),
-                            &self.scheme,
-                        ),
-                    )
-                })
-            })
-            .await;
-        timer.observe_duration();
-        write_res.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::Write, e.kind());
-            e
-        })
-    }
-
-    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Stat.into_static()])
-            .inc();
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Stat.into_static()])
-            .start_timer();
-
-        let stat_res = self
-            .inner
-            .stat(path, args)
-            .inspect_err(|e| {
-                self.stats.increment_errors_total(Operation::Stat, e.kind());
-            })
-            .await;
-        timer.observe_duration();
-        stat_res.map_err(|e| {
-            self.stats.increment_errors_total(Operation::Stat, e.kind());
-            e
-        })
-    }
-
-    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Stat.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Stat.into_static()])
-            .start_timer();
-
-        let delete_res = self.inner.delete(path, args).await;
-        timer.observe_duration();
-        delete_res.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::Delete, e.kind());
-            e
-        })
-    }
-
-    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::List.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::List.into_static()])
-            .start_timer();
-
-        let list_res = self.inner.list(path, args).await;
-
-        timer.observe_duration();
-        list_res.map_err(|e| {
-            self.stats.increment_errors_total(Operation::List, e.kind());
-            e
-        })
-    }
-
-    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Scan.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Scan.into_static()])
-            .start_timer();
-
-        let scan_res = self.inner.scan(path, args).await;
-        timer.observe_duration();
-        scan_res.map_err(|e| {
-            self.stats.increment_errors_total(Operation::Scan, e.kind());
-            e
-        })
-    }
-
-    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Batch.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Batch.into_static()])
-            .start_timer();
-        let result = self.inner.batch(args).await;
-
-        timer.observe_duration();
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::Batch, e.kind());
-            e
-        })
-    }
-
-    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::Presign.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::Presign.into_static()])
-            .start_timer();
-        let result = self.inner.presign(path, args).await;
-        timer.observe_duration();
-
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::Presign, e.kind());
-            e
-        })
-    }
-
-    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingCreateDir.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingCreateDir.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_create_dir(path, args);
-
-        timer.observe_duration();
-
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingCreateDir, e.kind());
-            e
-        })
-    }
-
-    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingRead.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme])
-            .start_timer();
-        let result = self.inner.blocking_read(path, args).map(|(rp, r)| {
-            self.stats
-                .bytes_total
-                .with_label_values(&[&self.scheme, Operation::BlockingRead.into_static()])
-                .observe(rp.metadata().content_length() as f64);
-            (
-                rp,
-                PrometheusMetricWrapper::new(
-                    r,
-                    Operation::BlockingRead,
-                    self.stats.clone(),
-                    &self.scheme,
-                ),
-            )
-        });
-        timer.observe_duration();
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingRead, e.kind());
-            e
-        })
-    }
-
-    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+impl MetricsObserver for PrometheusObserver {
+    fn observe_request(&self, op: Operation) {
         self.stats
             .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingWrite.into_static()])
+            .with_label_values(&[self.scheme, op.into_static()])
             .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingWrite.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_write(path, args).map(|(rp, r)| {
-            (
-                rp,
-                PrometheusMetricWrapper::new(
-                    r,
-                    Operation::BlockingWrite,
-                    self.stats.clone(),
-                    &self.scheme,
-                ),
-            )
-        });
-        timer.observe_duration();
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingWrite, e.kind());
-            e
-        })
     }
 
-    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+    fn observe_duration(&self, op: Operation, dur: Duration) {
         self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingStat.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
             .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingStat.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_stat(path, args);
-        timer.observe_duration();
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingStat, e.kind());
-            e
-        })
+            .with_label_values(&[self.scheme, op.into_static()])
+            .observe(dur.as_secs_f64());
     }
 
-    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+    fn observe_bytes(&self, op: Operation, bytes: u64) {
         self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingDelete.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingDelete.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_delete(path, args);
-        timer.observe_duration();
-
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingDelete, e.kind());
-            e
-        })
-    }
-
-    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
-        self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingList.into_static()])
-            .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingList.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_list(path, args);
-        timer.observe_duration();
-
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingList, e.kind());
-            e
-        })
+            .bytes_total
+            .with_label_values(&[self.scheme, op.into_static()])
+            .observe(bytes as f64);
     }
 
-    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+    fn observe_error(&self, op: Operation, kind: ErrorKind) {
         self.stats
-            .requests_total
-            .with_label_values(&[&self.scheme, Operation::BlockingScan.into_static()])
+            .errors_total
+            .with_label_values(&[self.scheme, op.into_static(), kind.into_static()])
             .inc();
-
-        let timer = self
-            .stats
-            .requests_duration_seconds
-            .with_label_values(&[&self.scheme, Operation::BlockingScan.into_static()])
-            .start_timer();
-        let result = self.inner.blocking_scan(path, args);
-        timer.observe_duration();
-        result.map_err(|e| {
-            self.stats
-                .increment_errors_total(Operation::BlockingScan, e.kind());
-            e
-        })
-    }
-}
-
-pub struct PrometheusMetricWrapper<R> {
-    inner: R,
-
-    op: Operation,
-    stats: Arc<PrometheusMetrics>,
-    scheme: String,
-}
-
-impl<R> PrometheusMetricWrapper<R> {
-    fn new(inner: R, op: Operation, stats: Arc<PrometheusMetrics>, scheme: &String) -> Self {
-        Self {
-            inner,
-            op,
-            stats,
-            scheme: scheme.to_string(),
-        }
-    }
-}
-
-impl<R: oio::Read> oio::Read for PrometheusMetricWrapper<R> {
-    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
-        self.inner.poll_read(cx, buf).map(|res| match res {
-            Ok(bytes) => {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::Read.into_static()])
-                    .observe(bytes as f64);
-                Ok(bytes)
-            }
-            Err(e) => {
-                self.stats.increment_errors_total(self.op, e.kind());
-                Err(e)
-            }
-        })
-    }
-
-    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
-        self.inner.poll_seek(cx, pos).map(|res| match res {
-            Ok(n) => Ok(n),
-            Err(e) => {
-                self.stats.increment_errors_total(self.op, e.kind());
-                Err(e)
-            }
-        })
-    }
-
-    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
-        self.inner.poll_next(cx).map(|res| match res {
-            Some(Ok(bytes)) => {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::Read.into_static()])
-                    .observe(bytes.len() as f64);
-                Some(Ok(bytes))
-            }
-            Some(Err(e)) => {
-                self.stats.increment_errors_total(self.op, e.kind());
-                Some(Err(e))
-            }
-            None => None,
-        })
-    }
-}
-
-impl<R: oio::BlockingRead> oio::BlockingRead for PrometheusMetricWrapper<R> {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.inner
-            .read(buf)
-            .map(|n| {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::BlockingRead.into_static()])
-                    .observe(n as f64);
-                n
-            })
-            .map_err(|e| {
-                self.stats.increment_errors_total(self.op, e.kind());
-                e
-            })
-    }
-
-    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
-        self.inner.seek(pos).map_err(|err| {
-            self.stats.increment_errors_total(self.op, err.kind());
-            err
-        })
-    }
-
-    fn next(&mut self) -> Option<Result<Bytes>> {
-        self.inner.next().map(|res| match res {
-            Ok(bytes) => {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::BlockingRead.into_static()])
-                    .observe(bytes.len() as f64);
-                Ok(bytes)
-            }
-            Err(e) => {
-                self.stats.increment_errors_total(self.op, e.kind());
-                Err(e)
-            }
-        })
-    }
-}
-
-#[async_trait]
-impl<R: oio::Write> oio::Write for PrometheusMetricWrapper<R> {
-    async fn write(&mut self, bs: Bytes) -> Result<()> {
-        let size = bs.len();
-        self.inner
-            .write(bs)
-            .await
-            .map(|_| {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::Write.into_static()])
-                    .observe(size as f64)
-            })
-            .map_err(|err| {
-                self.stats.increment_errors_total(self.op, err.kind());
-                err
-            })
-    }
-
-    async fn append(&mut self, bs: Bytes) -> Result<()> {
-        let size = bs.len();
-        self.inner
-            .append(bs)
-            .await
-            .map(|_| {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::Write.into_static()])
-                    .observe(size as f64)
-            })
-            .map_err(|err| {
-                self.stats.increment_errors_total(self.op, err.kind());
-                err
-            })
-    }
-
-    async fn abort(&mut self) -> Result<()> {
-        self.inner.abort().await.map_err(|err| {
-            self.stats.increment_errors_total(self.op, err.kind());
-            err
-        })
-    }
-
-    async fn close(&mut self) -> Result<()> {
-        self.inner.close().await.map_err(|err| {
-            self.stats.increment_errors_total(self.op, err.kind());
-            err
-        })
-    }
-}
-
-impl<R: oio::BlockingWrite> oio::BlockingWrite for PrometheusMetricWrapper<R> {
-    fn write(&mut self, bs: Bytes) -> Result<()> {
-        let size = bs.len();
-        self.inner
-            .write(bs)
-            .map(|_| {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::BlockingWrite.into_static()])
-                    .observe(size as f64)
-            })
-            .map_err(|err| {
-                self.stats.increment_errors_total(self.op, err.kind());
-                err
-            })
-    }
-
-    fn append(&mut self, bs: Bytes) -> Result<()> {
-        let size = bs.len();
-        self.inner
-            .append(bs)
-            .map(|_| {
-                self.stats
-                    .bytes_total
-                    .with_label_values(&[&self.scheme, Operation::BlockingWrite.into_static()])
-                    .observe(size as f64)
-            })
-            .map_err(|err| {
-                self.stats.increment_errors_total(self.op, err.kind());
-                err
-            })
-    }
-
-    fn close(&mut self) -> Result<()> {
-        self.inner.close().map_err(|err| {
-            self.stats.increment_errors_total(self.op, err.kind());
-            err
-        })
     }
 }
@@ -744,6 +744,20 @@ impl<R: oio::BlockingWrite> oio::BlockingWrite for RetryWrapper<R> {
             .map_err(|e| e.set_persistent())
     }
 
+    fn abort(&mut self) -> Result<()> {
+        { || self.inner.abort() }
+            .retry(&self.builder)
+            .when(|e| e.is_temporary())
+            .notify(move |err, dur| {
+                warn!(
+                target: "opendal::service",
+                "operation={} -> pager retry after {}s: error={:?}",
+               WriteOperation::BlockingAbort, dur.as_secs_f64(), err)
+            })
+            .call()
+            .map_err(|e| e.set_persistent())
+    }
+
     fn close(&mut self) -> Result<()> {
         { || self.inner.close() }
             .retry(&self.builder)
@@ -1095,4 +1109,61 @@ mod tests {
         op.remove(paths).await.expect("batch must succeed");
         assert_eq!(*builder.attempt.lock().unwrap(), 5);
     }
+
+    #[cfg(feature = "tests")]
+    #[tokio::test]
+    async fn test_retry_stat() {
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockReply;
+
+        let _ = env_logger::try_init();
+
+        let mock = MockAccessor::new();
+        mock.push(
+            Operation::Stat,
+            MockReply::err(Error::new(ErrorKind::Unexpected, "retryable").set_temporary()),
+        )
+        .push(
+            Operation::Stat,
+            MockReply::err(Error::new(ErrorKind::RateLimited, "retryable").set_temporary())
+                .with_delay(Duration::from_millis(10)),
+        )
+        .push(
+            Operation::Stat,
+            MockReply::stat(Metadata::new(EntryMode::FILE).with_content_length(13)),
+        );
+
+        let op = OperatorBuilder::new(mock.clone())
+            .layer(RetryLayer::new().with_min_delay(Duration::from_millis(1)))
+            .finish();
+
+        let meta = op.stat("hello").await.expect("stat must succeed");
+        assert_eq!(meta.content_length(), 13);
+        mock.assert_called(Operation::Stat, "hello");
+        mock.assert_called_times(Operation::Stat, 3);
+    }
+
+    #[cfg(feature = "tests")]
+    #[tokio::test]
+    async fn test_retry_permanent_error() {
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockReply;
+
+        let _ = env_logger::try_init();
+
+        let mock = MockAccessor::new();
+        mock.push(
+            Operation::Delete,
+            MockReply::err(Error::new(ErrorKind::PermissionDenied, "permanent")),
+        );
+
+        let op = OperatorBuilder::new(mock.clone())
+            .layer(RetryLayer::new().with_min_delay(Duration::from_millis(1)))
+            .finish();
+
+        let err = op.delete("hello").await.expect_err("delete must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        // Permanent errors should not be retried.
+        mock.assert_called_times(Operation::Delete, 1);
+    }
 }
@@ -0,0 +1,771 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Add an in-memory cache for the metadata returned by `stat`.
+///
+/// Unlike [`CacheLayer`][crate::layers::CacheLayer] which caches content,
+/// `StatCacheLayer` only caches [`Metadata`] per path, which is useful for
+/// applications that check the existence of the same paths again and again.
+///
+/// # Notes
+///
+/// - Only `stat` without `if_match`, `if_none_match` and `version` will be
+///   cached.
+/// - Cached entries of a path and its parents will be invalidated by
+///   `create_dir`, `write`, `copy`, `rename`, `delete` and `batch` issued
+///   through the same operator. Changes made by others will only be seen
+///   after the entry expired.
+/// - `NotFound` will only be cached if `with_not_found_ttl` is set.
+/// - If revalidate is enabled and the service supports
+///   `StatWithIfNoneMatch`, expired entries with etag will be revalidated
+///   via `stat` with `if_none_match` instead of being dropped.
+/// - Stat results will be dropped if the path has been invalidated while
+///   the stat is running, so that stale metadata won't be cached.
+/// - While the cache is full, the oldest inserted entry will be evicted.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::StatCacheLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(
+///         StatCacheLayer::new()
+///             .with_ttl(Duration::from_secs(30))
+///             .with_not_found_ttl(Duration::from_secs(5))
+///             .with_capacity(4096)
+///             .with_revalidate(true),
+///     )
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatCacheLayer {
+    ttl: Duration,
+    not_found_ttl: Option<Duration>,
+    capacity: usize,
+    revalidate: bool,
+}
+
+impl Default for StatCacheLayer {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            not_found_ttl: None,
+            capacity: 1024,
+            revalidate: false,
+        }
+    }
+}
+
+impl StatCacheLayer {
+    /// Create a new stat cache layer which caches at most 1024 entries for
+    /// 60 seconds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time to live of cached metadata.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Enable caching `NotFound` with given time to live, which is usually
+    /// shorter than `ttl`.
+    pub fn with_not_found_ttl(mut self, ttl: Duration) -> Self {
+        self.not_found_ttl = Some(ttl);
+        self
+    }
+
+    /// Set the max count of cached entries.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Revalidate expired entries via `stat` with `if_none_match` if the
+    /// service supports it.
+    pub fn with_revalidate(mut self, revalidate: bool) -> Self {
+        self.revalidate = revalidate;
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for StatCacheLayer {
+    type LayeredAccessor = StatCacheAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        let revalidate = self.revalidate
+            && inner
+                .info()
+                .capabilities()
+                .contains(AccessorCapability::StatWithIfNoneMatch);
+
+        StatCacheAccessor {
+            inner,
+            cache: Arc::new(StatCache::new(self.ttl, self.not_found_ttl, self.capacity)),
+            revalidate,
+        }
+    }
+}
+
+/// Number of generation slots, paths are hashed into these slots so that
+/// memory usage is bounded.
+const GENERATION_SLOTS: usize = 256;
+
+/// Cached value of a path, `None` means `NotFound`.
+struct CacheEntry {
+    meta: Option<Metadata>,
+    /// Sequence of this entry in [`CacheState::order`].
+    seq: u64,
+    expires_at: Instant,
+}
+
+/// Lookup result of the cache.
+enum Lookup {
+    Hit(Option<Metadata>),
+    /// Entry has expired but could be revalidated with its etag.
+    Stale(Metadata),
+    Miss,
+}
+
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Paths in insertion order, so that the oldest entry could be evicted
+    /// without scanning all entries. Records of removed or replaced entries
+    /// are skipped while evicting.
+    order: VecDeque<(String, u64)>,
+    /// Sequence of the next inserted entry.
+    seq: u64,
+    /// Generations of paths, bumped before every invalidation.
+    generations: Vec<u64>,
+    /// Generation of all paths, bumped while invalidating a whole dir.
+    dir_generation: u64,
+}
+
+impl CacheState {
+    fn slot(&mut self, path: &str) -> &mut u64 {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        &mut self.generations[hasher.finish() as usize % GENERATION_SLOTS]
+    }
+
+    /// Both generations only increase, so their sum changes as long as
+    /// any of them has been bumped.
+    fn generation(&mut self, path: &str) -> u64 {
+        *self.slot(path) + self.dir_generation
+    }
+
+    fn remove(&mut self, path: &str) {
+        *self.slot(path) += 1;
+        self.entries.remove(path);
+    }
+
+    /// Evict the oldest entries until there is room for a new one.
+    fn evict(&mut self, capacity: usize) {
+        while self.entries.len() >= capacity {
+            let (path, seq) = match self.order.pop_front() {
+                Some(v) => v,
+                None => break,
+            };
+            if self.entries.get(&path).map(|v| v.seq) == Some(seq) {
+                self.entries.remove(&path);
+            }
+        }
+    }
+}
+
+/// StatCache stores metadata of paths with generations.
+///
+/// Generation of a path is bumped before every invalidation, stat results
+/// will be dropped if the generation has been changed since the stat
+/// started.
+struct StatCache {
+    ttl: Duration,
+    not_found_ttl: Option<Duration>,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl StatCache {
+    fn new(ttl: Duration, not_found_ttl: Option<Duration>, capacity: usize) -> Self {
+        Self {
+            ttl,
+            not_found_ttl,
+            capacity,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+                seq: 0,
+                generations: vec![0; GENERATION_SLOTS],
+                dir_generation: 0,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // The cache is always consistent, it's safe to ignore poison.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn generation(&self, path: &str) -> u64 {
+        self.lock().generation(path)
+    }
+
+    fn get(&self, path: &str) -> Lookup {
+        let mut state = self.lock();
+        match state.entries.get(path) {
+            None => return Lookup::Miss,
+            Some(entry) if entry.expires_at > Instant::now() => {
+                return Lookup::Hit(entry.meta.clone())
+            }
+            Some(_) => {}
+        }
+
+        match state.entries.remove(path).and_then(|v| v.meta) {
+            Some(meta) if meta.etag().is_some() => Lookup::Stale(meta),
+            _ => Lookup::Miss,
+        }
+    }
+
+    /// Insert metadata got at given generation into cache.
+    fn insert(&self, path: &str, generation: u64, meta: Option<Metadata>) {
+        let ttl = match &meta {
+            Some(_) => self.ttl,
+            None => match self.not_found_ttl {
+                Some(ttl) => ttl,
+                None => return,
+            },
+        };
+        if self.capacity == 0 || ttl.is_zero() {
+            return;
+        }
+
+        let mut state = self.lock();
+        // Path has been invalidated since the stat started.
+        if state.generation(path) != generation {
+            return;
+        }
+
+        if !state.entries.contains_key(path) {
+            state.evict(self.capacity);
+        }
+        state.seq += 1;
+        let seq = state.seq;
+        state.order.push_back((path.to_string(), seq));
+        state.entries.insert(
+            path.to_string(),
+            CacheEntry {
+                meta,
+                seq,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+
+        // Drop records of removed entries so that order won't grow forever.
+        if state.order.len() > self.capacity * 2 {
+            let CacheState { entries, order, .. } = &mut *state;
+            order.retain(|(path, seq)| entries.get(path).map(|v| v.seq) == Some(*seq));
+        }
+    }
+
+    /// Invalidate the path and all its parents.
+    fn invalidate(&self, path: &str) {
+        let mut state = self.lock();
+        state.remove(path);
+
+        let mut path = path;
+        while path != "/" {
+            path = get_parent(path);
+            state.remove(path);
+        }
+    }
+
+    /// Remove cached entries of the path and its parents.
+    fn invalidate_dir(&self, path: &str) {
+        self.invalidate(path);
+        // Files under this dir may be changed too.
+        if path.ends_with('/') {
+            let mut state = self.lock();
+            state.dir_generation += 1;
+            state.entries.retain(|k, _| !k.starts_with(path));
+        }
+    }
+}
+
+/// Only plain stat could be served from cache.
+fn is_cacheable(args: &OpStat) -> bool {
+    args.if_match().is_none() && args.if_none_match().is_none() && args.version().is_none()
+}
+
+pub struct StatCacheAccessor<A: Accessor> {
+    inner: A,
+    cache: Arc<StatCache>,
+    revalidate: bool,
+}
+
+impl<A: Accessor> Debug for StatCacheAccessor<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatCacheAccessor")
+            .field("inner", &self.inner)
+            .field("revalidate", &self.revalidate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Accessor> StatCacheAccessor<A> {
+    /// Store the result of stat started at given generation into cache.
+    fn store(&self, path: &str, generation: u64, result: Result<RpStat>) -> Result<RpStat> {
+        match result {
+            Ok(rp) => {
+                self.cache
+                    .insert(path, generation, Some(rp.clone().into_metadata()));
+                Ok(rp)
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.cache.insert(path, generation, None);
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn cached(path: &str, meta: Option<Metadata>) -> Result<RpStat> {
+        match meta {
+            Some(meta) => Ok(RpStat::new(meta)),
+            None => Err(
+                Error::new(ErrorKind::NotFound, "path not found in stat cache")
+                    .with_operation(Operation::Stat)
+                    .with_context("path", path),
+            ),
+        }
+    }
+
+    fn wrap<W>(&self, path: &str, w: W) -> StatCacheWriter<W> {
+        StatCacheWriter {
+            inner: w,
+            path: path.to_string(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for StatCacheAccessor<A> {
+    type Inner = A;
+    type Reader = A::Reader;
+    type BlockingReader = A::BlockingReader;
+    type Writer = StatCacheWriter<A::Writer>;
+    type BlockingWriter = StatCacheWriter<A::BlockingWriter>;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.cache.invalidate(path);
+        self.inner.create_dir(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.cache.invalidate(path);
+        self.inner
+            .write(path, args)
+            .await
+            .map(|(rp, w)| (rp, self.wrap(path, w)))
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let result = self.inner.copy(from, to, args).await;
+        self.cache.invalidate(to);
+        result
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let result = self.inner.rename(from, to, args).await;
+        self.cache.invalidate_dir(from);
+        self.cache.invalidate(to);
+        result
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        if !is_cacheable(&args) {
+            return self.inner.stat(path, args).await;
+        }
+
+        // Take the generation before stat so that invalidations during this
+        // stat will drop the result.
+        let generation = self.cache.generation(path);
+        match self.cache.get(path) {
+            Lookup::Hit(meta) => Self::cached(path, meta),
+            Lookup::Stale(meta) if self.revalidate => {
+                let etag = meta.etag().unwrap_or_default();
+                match self
+                    .inner
+                    .stat(path, OpStat::new().with_if_none_match(etag))
+                    .await
+                {
+                    // Not modified, keep using the cached metadata.
+                    Err(err) if err.kind() == ErrorKind::PreconditionFailed => {
+                        self.cache.insert(path, generation, Some(meta.clone()));
+                        Ok(RpStat::new(meta))
+                    }
+                    result => self.store(path, generation, result),
+                }
+            }
+            Lookup::Stale(_) | Lookup::Miss => {
+                let result = self.inner.stat(path, args).await;
+                self.store(path, generation, result)
+            }
+        }
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let result = self.inner.delete(path, args).await;
+        self.cache.invalidate_dir(path);
+        result
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.inner.scan(path, args).await
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        let paths: Vec<_> = args.operation().iter().map(|(p, _)| p.clone()).collect();
+        let result = self.inner.batch(args).await;
+        for path in paths {
+            self.cache.invalidate_dir(&path);
+        }
+        result
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.cache.invalidate(path);
+        self.inner.blocking_create_dir(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.cache.invalidate(path);
+        self.inner
+            .blocking_write(path, args)
+            .map(|(rp, w)| (rp, self.wrap(path, w)))
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let result = self.inner.blocking_copy(from, to, args);
+        self.cache.invalidate(to);
+        result
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let result = self.inner.blocking_rename(from, to, args);
+        self.cache.invalidate_dir(from);
+        self.cache.invalidate(to);
+        result
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        if !is_cacheable(&args) {
+            return self.inner.blocking_stat(path, args);
+        }
+
+        // Take the generation before stat so that invalidations during this
+        // stat will drop the result.
+        let generation = self.cache.generation(path);
+        match self.cache.get(path) {
+            Lookup::Hit(meta) => Self::cached(path, meta),
+            Lookup::Stale(meta) if self.revalidate => {
+                let etag = meta.etag().unwrap_or_default();
+                match self
+                    .inner
+                    .blocking_stat(path, OpStat::new().with_if_none_match(etag))
+                {
+                    // Not modified, keep using the cached metadata.
+                    Err(err) if err.kind() == ErrorKind::PreconditionFailed => {
+                        self.cache.insert(path, generation, Some(meta.clone()));
+                        Ok(RpStat::new(meta))
+                    }
+                    result => self.store(path, generation, result),
+                }
+            }
+            Lookup::Stale(_) | Lookup::Miss => {
+                let result = self.inner.blocking_stat(path, args);
+                self.store(path, generation, result)
+            }
+        }
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let result = self.inner.blocking_delete(path, args);
+        self.cache.invalidate_dir(path);
+        result
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+/// StatCacheWriter invalidates the path again after closed, so that stat
+/// during writing will not be cached.
+pub struct StatCacheWriter<W> {
+    inner: W,
+    path: String,
+    cache: Arc<StatCache>,
+}
+
+#[async_trait]
+impl<W: oio::Write> oio::Write for StatCacheWriter<W> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.write(bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.append(bs).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let result = self.inner.abort().await;
+        self.cache.invalidate(&self.path);
+        result
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let result = self.inner.close().await;
+        self.cache.invalidate(&self.path);
+        result
+    }
+}
+
+impl<W: oio::BlockingWrite> oio::BlockingWrite for StatCacheWriter<W> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.write(bs)
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.inner.append(bs)
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        let result = self.inner.abort();
+        self.cache.invalidate(&self.path);
+        result
+    }
+
+    fn close(&mut self) -> Result<()> {
+        let result = self.inner.close();
+        self.cache.invalidate(&self.path);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_cache(not_found_ttl: Option<Duration>, capacity: usize) -> StatCache {
+        StatCache::new(Duration::from_secs(60), not_found_ttl, capacity)
+    }
+
+    fn insert(cache: &StatCache, path: &str, meta: Option<Metadata>) {
+        cache.insert(path, cache.generation(path), meta)
+    }
+
+    #[test]
+    fn test_cache_not_found() {
+        let cache = new_cache(None, 16);
+        insert(&cache, "a", None);
+        assert!(matches!(cache.get("a"), Lookup::Miss));
+
+        let cache = new_cache(Some(Duration::from_secs(5)), 16);
+        insert(&cache, "a", None);
+        assert!(matches!(cache.get("a"), Lookup::Hit(None)));
+    }
+
+    #[test]
+    fn test_cache_stale() {
+        let cache = new_cache(None, 16);
+        let meta = Metadata::new(EntryMode::FILE).with_etag("\"etag\"".to_string());
+        cache.lock().entries.insert(
+            "a".to_string(),
+            CacheEntry {
+                meta: Some(meta),
+                seq: 0,
+                expires_at: Instant::now(),
+            },
+        );
+
+        assert!(matches!(cache.get("a"), Lookup::Stale(_)));
+        // Stale entry has been taken.
+        assert!(matches!(cache.get("a"), Lookup::Miss));
+    }
+
+    #[test]
+    fn test_cache_invalidate() {
+        let cache = new_cache(Some(Duration::from_secs(5)), 16);
+        for path in ["/", "a/", "a/b/", "a/b/c", "a/d"] {
+            insert(&cache, path, None);
+        }
+
+        cache.invalidate("a/b/c");
+        for path in ["/", "a/", "a/b/", "a/b/c"] {
+            assert!(matches!(cache.get(path), Lookup::Miss), "{path}");
+        }
+        assert!(matches!(cache.get("a/d"), Lookup::Hit(None)));
+    }
+
+    #[test]
+    fn test_drop_stale_fill() {
+        let cache = new_cache(Some(Duration::from_secs(5)), 16);
+        let meta = Metadata::new(EntryMode::FILE);
+
+        // Stat started before the path has been invalidated.
+        let generation = cache.generation("a");
+        cache.invalidate("a");
+        cache.insert("a", generation, Some(meta.clone()));
+        assert!(matches!(cache.get("a"), Lookup::Miss));
+
+        // Stat started before the parent dir has been removed.
+        let generation = cache.generation("d/f");
+        cache.invalidate_dir("d/");
+        cache.insert("d/f", generation, Some(meta.clone()));
+        assert!(matches!(cache.get("d/f"), Lookup::Miss));
+
+        insert(&cache, "a", Some(meta));
+        assert!(matches!(cache.get("a"), Lookup::Hit(Some(_))));
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let cache = new_cache(Some(Duration::from_secs(5)), 2);
+        insert(&cache, "a", None);
+        insert(&cache, "b", None);
+        // Replace doesn't need to evict.
+        insert(&cache, "a", None);
+        insert(&cache, "c", None);
+
+        assert_eq!(cache.lock().entries.len(), 2);
+        assert!(matches!(cache.get("b"), Lookup::Miss));
+        assert!(matches!(cache.get("a"), Lookup::Hit(None)));
+        assert!(matches!(cache.get("c"), Lookup::Hit(None)));
+
+        // Records of replaced entries will be dropped.
+        for _ in 0..10 {
+            insert(&cache, "c", None);
+        }
+        assert!(cache.lock().order.len() <= 4);
+    }
+
+    #[cfg(feature = "tests")]
+    #[tokio::test]
+    async fn test_stat_cache_layer() {
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockArgs;
+        use crate::raw::tests::MockReply;
+
+        let mock = MockAccessor::new().with_capabilities(
+            AccessorCapability::Read
+                | AccessorCapability::Write
+                | AccessorCapability::StatWithIfNoneMatch,
+        );
+        let op = OperatorBuilder::new(mock.clone())
+            .layer(
+                StatCacheLayer::new()
+                    .with_ttl(Duration::from_millis(50))
+                    .with_not_found_ttl(Duration::from_secs(60))
+                    .with_revalidate(true),
+            )
+            .finish();
+
+        // NotFound should be cached.
+        mock.push(
+            Operation::Stat,
+            MockReply::err(Error::new(ErrorKind::NotFound, "not found")),
+        );
+        assert!(!op.is_exist("hello").await.unwrap());
+        assert!(!op.is_exist("hello").await.unwrap());
+        mock.assert_called_times(Operation::Stat, 1);
+
+        // Write should invalidate the cache.
+        mock.push(Operation::Write, MockReply::ok()).push(
+            Operation::Stat,
+            MockReply::stat(Metadata::new(EntryMode::FILE).with_etag("\"etag\"".to_string())),
+        );
+        op.write("hello", "Hello, World!").await.unwrap();
+        assert!(op.is_exist("hello").await.unwrap());
+        assert!(op.is_exist("hello").await.unwrap());
+        mock.assert_called_times(Operation::Stat, 2);
+
+        // Expired entry should be revalidated with etag.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        mock.push(
+            Operation::Stat,
+            MockReply::err(Error::new(ErrorKind::PreconditionFailed, "not modified")),
+        );
+        let meta = op.stat("hello").await.unwrap();
+        assert_eq!(meta.etag(), Some("\"etag\""));
+        mock.assert_called_times(Operation::Stat, 3);
+        mock.assert_called_with(
+            Operation::Stat,
+            "hello",
+            |args| matches!(args, MockArgs::Stat(op) if op.if_none_match() == Some("\"etag\"")),
+        );
+    }
+}
@@ -0,0 +1,506 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Add a bandwidth rate limiter to the underlying services.
+///
+/// # Throttle
+///
+/// There are several algorithm when it come to rate limiting techniques.
+/// This throttle layer uses a token bucket: the bucket will be refilled
+/// with `bandwidth` bytes per second and hold at most `burst` bytes.
+/// Reads and writes consume tokens for every byte transferred and will
+/// be delayed if the bucket has been drained.
+///
+/// All operations on the same operator share one bucket, so the aggregate
+/// throughput respects the limit.
+///
+/// Users can also limit the count of requests per second via
+/// [`ThrottleLayer::with_requests_per_second`], which is useful for
+/// metadata heavy workloads.
+///
+/// # Examples
+///
+/// This example limits bandwidth to 10 KiB/s and burst size to 10 MiB.
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::ThrottleLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(ThrottleLayer::new(10 * 1024).with_burst(10 * 1024 * 1024))
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThrottleLayer {
+    bandwidth: u64,
+    burst: u64,
+    requests_per_second: Option<u64>,
+}
+
+impl ThrottleLayer {
+    /// Create a new `ThrottleLayer` with given bandwidth in bytes per second.
+    ///
+    /// Burst size will be the same as bandwidth by default.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if bandwidth is zero.
+    pub fn new(bandwidth: u64) -> Self {
+        assert!(bandwidth > 0, "bandwidth must be larger than zero");
+
+        Self {
+            bandwidth,
+            burst: bandwidth,
+            requests_per_second: None,
+        }
+    }
+
+    /// Set the max bytes that could be transferred without delay.
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// Limit the count of requests per second.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if requests_per_second is zero.
+    pub fn with_requests_per_second(mut self, requests_per_second: u64) -> Self {
+        assert!(
+            requests_per_second > 0,
+            "requests_per_second must be larger than zero"
+        );
+
+        self.requests_per_second = Some(requests_per_second);
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for ThrottleLayer {
+    type LayeredAccessor = ThrottleAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        ThrottleAccessor {
+            inner,
+            bytes: Arc::new(TokenBucket::new(self.bandwidth, self.burst)),
+            requests: self
+                .requests_per_second
+                .map(|v| Arc::new(TokenBucket::new(v, v))),
+        }
+    }
+}
+
+/// TokenBucket is a simple token bucket that allows going into debt.
+///
+/// Consuming more tokens than the bucket holds will succeed, but the
+/// caller should wait for the returned duration before next consuming.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens refilled per second.
+    rate: f64,
+    /// The max tokens that bucket can hold.
+    capacity: f64,
+    /// Current tokens and the last refilled time.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, capacity: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            capacity: capacity as f64,
+            state: Mutex::new((capacity as f64, Instant::now())),
+        }
+    }
+
+    /// Consume tokens and returns the duration to wait.
+    fn consume(&self, n: u64) -> Option<Duration> {
+        let mut state = self.state.lock();
+        let (tokens, last) = &mut *state;
+
+        let now = Instant::now();
+        let refilled = now.saturating_duration_since(*last).as_secs_f64() * self.rate;
+        *tokens = (*tokens + refilled).min(self.capacity) - n as f64;
+        *last = now;
+
+        if *tokens >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-*tokens / self.rate))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrottleAccessor<A: Accessor> {
+    inner: A,
+
+    bytes: Arc<TokenBucket>,
+    requests: Option<Arc<TokenBucket>>,
+}
+
+impl<A: Accessor> ThrottleAccessor<A> {
+    async fn throttle_request(&self) {
+        if let Some(dur) = self.requests.as_ref().and_then(|v| v.consume(1)) {
+            tokio::time::sleep(dur).await
+        }
+    }
+
+    fn blocking_throttle_request(&self) {
+        if let Some(dur) = self.requests.as_ref().and_then(|v| v.consume(1)) {
+            thread::sleep(dur)
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for ThrottleAccessor<A> {
+    type Inner = A;
+    type Reader = ThrottleWrapper<A::Reader>;
+    type BlockingReader = ThrottleWrapper<A::BlockingReader>;
+    type Writer = ThrottleWrapper<A::Writer>;
+    type BlockingWriter = ThrottleWrapper<A::BlockingWriter>;
+    type Pager = A::Pager;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.throttle_request().await;
+        self.inner.create_dir(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        self.throttle_request().await;
+        self.inner
+            .read(path, args)
+            .await
+            .map(|(rp, r)| (rp, ThrottleWrapper::new(r, self.bytes.clone())))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.throttle_request().await;
+        self.inner
+            .write(path, args)
+            .await
+            .map(|(rp, w)| (rp, ThrottleWrapper::new(w, self.bytes.clone())))
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.throttle_request().await;
+        self.inner.copy(from, to, args).await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.throttle_request().await;
+        self.inner.rename(from, to, args).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.throttle_request().await;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.throttle_request().await;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        self.throttle_request().await;
+        self.inner.list(path, args).await
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        self.throttle_request().await;
+        self.inner.scan(path, args).await
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        self.throttle_request().await;
+        self.inner.batch(args).await
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.blocking_throttle_request();
+        self.inner.blocking_create_dir(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.blocking_throttle_request();
+        self.inner
+            .blocking_read(path, args)
+            .map(|(rp, r)| (rp, ThrottleWrapper::new(r, self.bytes.clone())))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.blocking_throttle_request();
+        self.inner
+            .blocking_write(path, args)
+            .map(|(rp, w)| (rp, ThrottleWrapper::new(w, self.bytes.clone())))
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        self.blocking_throttle_request();
+        self.inner.blocking_copy(from, to, args)
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        self.blocking_throttle_request();
+        self.inner.blocking_rename(from, to, args)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.blocking_throttle_request();
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.blocking_throttle_request();
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.blocking_throttle_request();
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.blocking_throttle_request();
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+pub struct ThrottleWrapper<R> {
+    inner: R,
+
+    bucket: Arc<TokenBucket>,
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<R> ThrottleWrapper<R> {
+    fn new(inner: R, bucket: Arc<TokenBucket>) -> Self {
+        Self {
+            inner,
+            bucket,
+            sleep: None,
+        }
+    }
+
+    /// Wait for the delay of previous consuming.
+    fn poll_delay(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if let Some(sleep) = self.sleep.as_mut() {
+            futures::ready!(sleep.as_mut().poll(cx));
+            self.sleep = None;
+        }
+        Poll::Ready(())
+    }
+
+    /// Consume tokens for bytes that have been read.
+    ///
+    /// The bytes will be returned to caller directly, and the delay will be
+    /// applied to the next poll.
+    fn consume(&mut self, n: usize) {
+        if let Some(dur) = self.bucket.consume(n as u64) {
+            self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
+        }
+    }
+}
+
+impl<R: oio::Read> oio::Read for ThrottleWrapper<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        futures::ready!(self.poll_delay(cx));
+
+        let n = futures::ready!(self.inner.poll_read(cx, buf))?;
+        self.consume(n);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        self.inner.poll_seek(cx, pos)
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        futures::ready!(self.poll_delay(cx));
+
+        match futures::ready!(self.inner.poll_next(cx)) {
+            Some(Ok(bs)) => {
+                self.consume(bs.len());
+                Poll::Ready(Some(Ok(bs)))
+            }
+            v => Poll::Ready(v),
+        }
+    }
+}
+
+impl<R: oio::BlockingRead> oio::BlockingRead for ThrottleWrapper<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(dur) = self.bucket.consume(n as u64) {
+            thread::sleep(dur)
+        }
+        Ok(n)
+    }
+
+    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+
+    fn next(&mut self) -> Option<Result<Bytes>> {
+        let res = self.inner.next();
+        if let Some(Ok(bs)) = &res {
+            if let Some(dur) = self.bucket.consume(bs.len() as u64) {
+                thread::sleep(dur)
+            }
+        }
+        res
+    }
+}
+
+#[async_trait]
+impl<R: oio::Write> oio::Write for ThrottleWrapper<R> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        if let Some(dur) = self.bucket.consume(bs.len() as u64) {
+            tokio::time::sleep(dur).await
+        }
+        self.inner.write(bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        if let Some(dur) = self.bucket.consume(bs.len() as u64) {
+            tokio::time::sleep(dur).await
+        }
+        self.inner.append(bs).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await
+    }
+}
+
+impl<R: oio::BlockingWrite> oio::BlockingWrite for ThrottleWrapper<R> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        if let Some(dur) = self.bucket.consume(bs.len() as u64) {
+            thread::sleep(dur)
+        }
+        self.inner.write(bs)
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        if let Some(dur) = self.bucket.consume(bs.len() as u64) {
+            thread::sleep(dur)
+        }
+        self.inner.append(bs)
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.inner.close()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncReadExt;
+
+    use super::*;
+    use crate::services;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_bandwidth() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ThrottleLayer::new(1024))
+            .finish();
+
+        let content = vec![1; 11 * 1024];
+        op.write("test", content.clone()).await.unwrap();
+        // Wait for the bucket to be refilled.
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        // 1 KiB for burst and 10 KiB should take 10 seconds.
+        let start = Instant::now();
+        let mut r = op.reader("test").await.unwrap();
+        let mut buf = [0; 512];
+        let mut bs = Vec::new();
+        loop {
+            let n = r.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            bs.extend_from_slice(&buf[..n]);
+        }
+        let elapsed = start.elapsed();
+
+        assert_eq!(bs, content);
+        assert!(
+            elapsed >= Duration::from_secs(9) && elapsed <= Duration::from_secs(11),
+            "elapsed {elapsed:?} is not in expected range"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_requests() {
+        let op = Operator::new(services::Memory::default())
+            .unwrap()
+            .layer(ThrottleLayer::new(u64::MAX).with_requests_per_second(10))
+            .finish();
+
+        // 10 requests for burst and 100 requests should take 10 seconds.
+        let start = Instant::now();
+        for _ in 0..110 {
+            let _ = op.stat("test").await;
+        }
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_secs(9) && elapsed <= Duration::from_secs(11),
+            "elapsed {elapsed:?} is not in expected range"
+        );
+    }
+}
@@ -0,0 +1,571 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::task::waker_ref;
+use futures::task::ArcWake;
+use futures::task::AtomicWaker;
+
+use crate::ops::*;
+use crate::raw::oio::PageOperation;
+use crate::raw::oio::ReadOperation;
+use crate::raw::oio::WriteOperation;
+use crate::raw::*;
+use crate::*;
+
+/// Add timeout for every operations.
+///
+/// # Notes
+///
+/// TimeoutLayer has two kinds of timeout:
+///
+/// - `timeout`: The timeout for unary operations like `stat`, `delete`,
+///   `create_dir` and fetching the next page of `list`.
+/// - `io_timeout`: The idle timeout for streaming reads and writes. The
+///   reader or writer will fail if no bytes moved in `io_timeout`, the
+///   total time of transferring is not bounded.
+///
+/// Writers hand the whole buffer to underlying services at once, so a
+/// write is treated as idle while underlying I/O has not woken it up. A
+/// large write will keep going as long as the connection makes progress.
+///
+/// Both of them are disabled by default. Errors returned by timeout are
+/// temporary so [`RetryLayer`][crate::layers::RetryLayer] can retry them
+/// if it's added after `TimeoutLayer`.
+///
+/// Blocking operations are not covered by this layer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::TimeoutLayer;
+/// use opendal::services;
+/// use opendal::Operator;
+///
+/// let _ = Operator::new(services::Memory::default())
+///     .expect("must init")
+///     .layer(
+///         TimeoutLayer::new()
+///             .with_timeout(Duration::from_secs(10))
+///             .with_io_timeout(Duration::from_secs(3)),
+///     )
+///     .finish();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TimeoutLayer {
+    timeout: Option<Duration>,
+    io_timeout: Option<Duration>,
+}
+
+impl TimeoutLayer {
+    /// Create a new timeout layer without any timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set timeout for unary operations.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set idle timeout for streaming reads and writes.
+    ///
+    /// Reader and writer will return an error if no bytes moved during
+    /// this duration.
+    pub fn with_io_timeout(mut self, timeout: Duration) -> Self {
+        self.io_timeout = Some(timeout);
+        self
+    }
+}
+
+impl<A: Accessor> Layer<A> for TimeoutLayer {
+    type LayeredAccessor = TimeoutAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccessor {
+        TimeoutAccessor {
+            inner,
+            timeout: self.timeout,
+            io_timeout: self.io_timeout,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeoutAccessor<A: Accessor> {
+    inner: A,
+
+    timeout: Option<Duration>,
+    io_timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl<A: Accessor> LayeredAccessor for TimeoutAccessor<A> {
+    type Inner = A;
+    type Reader = TimeoutWrapper<A::Reader>;
+    type BlockingReader = A::BlockingReader;
+    type Writer = TimeoutWrapper<A::Writer>;
+    type BlockingWriter = A::BlockingWriter;
+    type Pager = TimeoutWrapper<A::Pager>;
+    type BlockingPager = A::BlockingPager;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        with_timeout(
+            self.timeout,
+            Operation::CreateDir,
+            self.inner.create_dir(path, args),
+        )
+        .await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        with_timeout(self.timeout, Operation::Read, self.inner.read(path, args))
+            .await
+            .map(|(rp, r)| (rp, TimeoutWrapper::new(r, self.io_timeout)))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        with_timeout(self.timeout, Operation::Write, self.inner.write(path, args))
+            .await
+            .map(|(rp, w)| (rp, TimeoutWrapper::new(w, self.io_timeout)))
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        with_timeout(
+            self.timeout,
+            Operation::Copy,
+            self.inner.copy(from, to, args),
+        )
+        .await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        with_timeout(
+            self.timeout,
+            Operation::Rename,
+            self.inner.rename(from, to, args),
+        )
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        with_timeout(self.timeout, Operation::Stat, self.inner.stat(path, args)).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        with_timeout(
+            self.timeout,
+            Operation::Delete,
+            self.inner.delete(path, args),
+        )
+        .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        with_timeout(self.timeout, Operation::List, self.inner.list(path, args))
+            .await
+            .map(|(rp, p)| (rp, TimeoutWrapper::new(p, self.timeout)))
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        with_timeout(self.timeout, Operation::Scan, self.inner.scan(path, args))
+            .await
+            .map(|(rp, p)| (rp, TimeoutWrapper::new(p, self.timeout)))
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        with_timeout(self.timeout, Operation::Batch, self.inner.batch(args)).await
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        with_timeout(
+            self.timeout,
+            Operation::Presign,
+            self.inner.presign(path, args),
+        )
+        .await
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        self.inner.blocking_write(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        self.inner.blocking_list(path, args)
+    }
+
+    fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        self.inner.blocking_scan(path, args)
+    }
+}
+
+/// Run given future with timeout.
+async fn with_timeout<F: Future<Output = Result<T>>, T>(
+    timeout: Option<Duration>,
+    op: impl Into<&'static str>,
+    fut: F,
+) -> Result<T> {
+    match timeout {
+        None => fut.await,
+        Some(dur) => tokio::time::timeout(dur, fut)
+            .await
+            .map_err(|_| new_timeout_error(op, dur))?,
+    }
+}
+
+/// Run given future with idle timeout.
+///
+/// Unlike [`with_timeout`], the timer will be restarted every time the
+/// future has been woken up by underlying I/O resources.
+async fn with_idle_timeout<F: Future<Output = Result<T>> + Unpin, T>(
+    timeout: Option<Duration>,
+    op: impl Into<&'static str>,
+    fut: F,
+) -> Result<T> {
+    match timeout {
+        None => fut.await,
+        Some(dur) => IdleTimeout::new(fut, op.into(), dur).await,
+    }
+}
+
+fn new_timeout_error(op: impl Into<&'static str>, dur: Duration) -> Error {
+    Error::new(ErrorKind::Unexpected, "operation timeout")
+        .with_operation(op)
+        .with_context("timeout", format!("{dur:?}"))
+        .set_temporary()
+}
+
+/// IdleTimeout fails the inner future if it has not been woken up in
+/// `timeout`.
+struct IdleTimeout<F> {
+    inner: F,
+    op: &'static str,
+
+    timeout: Duration,
+    sleep: Pin<Box<tokio::time::Sleep>>,
+    activity: Arc<Activity>,
+}
+
+impl<F> IdleTimeout<F> {
+    fn new(inner: F, op: &'static str, timeout: Duration) -> Self {
+        Self {
+            inner,
+            op,
+            timeout,
+            sleep: Box::pin(tokio::time::sleep(timeout)),
+            activity: Arc::default(),
+        }
+    }
+}
+
+/// Activity records whether the inner future has been woken up since
+/// last poll, and forwards the wake up to the outer task.
+#[derive(Default)]
+struct Activity {
+    woken: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl ArcWake for Activity {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::Release);
+        arc_self.waker.wake();
+    }
+}
+
+impl<F: Future<Output = Result<T>> + Unpin, T> Future for IdleTimeout<F> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        this.activity.waker.register(cx.waker());
+        // Inner has made progress since last poll, restart the timer.
+        if this.activity.woken.swap(false, Ordering::AcqRel) {
+            let deadline = tokio::time::Instant::now() + this.timeout;
+            this.sleep.as_mut().reset(deadline);
+        }
+
+        let waker = waker_ref(&this.activity);
+        let mut inner_cx = Context::from_waker(&waker);
+        if let Poll::Ready(v) = Pin::new(&mut this.inner).poll(&mut inner_cx) {
+            return Poll::Ready(v);
+        }
+
+        match this.sleep.as_mut().poll(cx) {
+            Poll::Ready(_) => Poll::Ready(Err(new_timeout_error(this.op, this.timeout))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pub struct TimeoutWrapper<R> {
+    inner: R,
+
+    timeout: Option<Duration>,
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<R> TimeoutWrapper<R> {
+    fn new(inner: R, timeout: Option<Duration>) -> Self {
+        Self {
+            inner,
+            timeout,
+            sleep: None,
+        }
+    }
+
+    /// Poll the idle timer while inner returns pending.
+    ///
+    /// The timer will be started at the first pending and reset once inner
+    /// returns ready.
+    fn poll_idle(&mut self, cx: &mut Context<'_>, op: ReadOperation) -> Poll<Error> {
+        let dur = match self.timeout {
+            Some(dur) => dur,
+            None => return Poll::Pending,
+        };
+
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(dur)));
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(_) => {
+                self.sleep = None;
+                Poll::Ready(new_timeout_error(op, dur))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<R: oio::Read> oio::Read for TimeoutWrapper<R> {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        match self.inner.poll_read(cx, buf) {
+            Poll::Pending => self.poll_idle(cx, ReadOperation::Read).map(Err),
+            Poll::Ready(v) => {
+                self.sleep = None;
+                Poll::Ready(v)
+            }
+        }
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        match self.inner.poll_seek(cx, pos) {
+            Poll::Pending => self.poll_idle(cx, ReadOperation::Seek).map(Err),
+            Poll::Ready(v) => {
+                self.sleep = None;
+                Poll::Ready(v)
+            }
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        match self.inner.poll_next(cx) {
+            Poll::Pending => self
+                .poll_idle(cx, ReadOperation::Next)
+                .map(|err| Some(Err(err))),
+            Poll::Ready(v) => {
+                self.sleep = None;
+                Poll::Ready(v)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<R: oio::Write> oio::Write for TimeoutWrapper<R> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        with_idle_timeout(self.timeout, WriteOperation::Write, self.inner.write(bs)).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        with_idle_timeout(self.timeout, WriteOperation::Append, self.inner.append(bs)).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        with_idle_timeout(self.timeout, WriteOperation::Abort, self.inner.abort()).await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        with_idle_timeout(self.timeout, WriteOperation::Close, self.inner.close()).await
+    }
+}
+
+#[async_trait]
+impl<P: oio::Page> oio::Page for TimeoutWrapper<P> {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        with_timeout(self.timeout, PageOperation::Next, self.inner.next()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::SeekFrom;
+    use std::task::Context;
+    use std::task::Poll;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use futures::AsyncReadExt;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    struct MockService;
+
+    #[async_trait]
+    impl Accessor for MockService {
+        type Reader = MockReader;
+        type BlockingReader = ();
+        type Writer = MockWriter;
+        type BlockingWriter = ();
+        type Pager = ();
+        type BlockingPager = ();
+
+        fn info(&self) -> AccessorInfo {
+            let mut am = AccessorInfo::default();
+            am.set_capabilities(AccessorCapability::Read | AccessorCapability::Write);
+
+            am
+        }
+
+        /// This function will build a reader that always return pending.
+        async fn read(&self, _: &str, _: OpRead) -> Result<(RpRead, Self::Reader)> {
+            Ok((RpRead::new(10), MockReader))
+        }
+
+        async fn write(&self, _: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+            Ok((RpWrite::new(), MockWriter))
+        }
+
+        /// This function will never return.
+        async fn stat(&self, _: &str, _: OpStat) -> Result<RpStat> {
+            tokio::time::sleep(Duration::from_secs(u64::MAX)).await;
+
+            unreachable!()
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct MockReader;
+
+    impl oio::Read for MockReader {
+        fn poll_read(&mut self, _: &mut Context<'_>, _: &mut [u8]) -> Poll<Result<usize>> {
+            Poll::Pending
+        }
+
+        fn poll_seek(&mut self, _: &mut Context<'_>, _: SeekFrom) -> Poll<Result<u64>> {
+            Poll::Pending
+        }
+
+        fn poll_next(&mut self, _: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+            Poll::Pending
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct MockWriter;
+
+    #[async_trait]
+    impl oio::Write for MockWriter {
+        /// This function will keep making progress for 100ms.
+        async fn write(&mut self, _: Bytes) -> Result<()> {
+            for _ in 0..10 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(())
+        }
+
+        /// This function will never return.
+        async fn append(&mut self, _: Bytes) -> Result<()> {
+            tokio::time::sleep(Duration::from_secs(u64::MAX)).await;
+
+            unreachable!()
+        }
+
+        async fn abort(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operation_timeout() {
+        let op = OperatorBuilder::new(MockService)
+            .layer(TimeoutLayer::new().with_timeout(Duration::from_millis(10)))
+            .finish();
+
+        let err = op.stat("test").await.expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+    }
+
+    #[tokio::test]
+    async fn test_io_timeout() {
+        let op = OperatorBuilder::new(MockService)
+            .layer(TimeoutLayer::new().with_io_timeout(Duration::from_millis(10)))
+            .finish();
+
+        let mut reader = op.range_reader("test", 0..10).await.unwrap();
+        let mut buf = vec![0; 10];
+        let err = reader.read(&mut buf).await.expect_err("must timeout");
+        assert!(err.to_string().contains("operation timeout"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_io_timeout_write() {
+        let op = OperatorBuilder::new(MockService)
+            .layer(TimeoutLayer::new().with_io_timeout(Duration::from_millis(50)))
+            .finish();
+
+        // Write takes longer than io timeout but keeps making progress.
+        op.write("test", "Hello, World!")
+            .await
+            .expect("progressing write must not timeout");
+
+        let mut w = op.writer("test").await.unwrap();
+        let err = w.append("Hello, World!").await.expect_err("must timeout");
+        assert!(err.to_string().contains("operation timeout"));
+        assert!(err.is_temporary());
+    }
+}
@@ -31,6 +31,19 @@ use crate::*;
 
 /// Add [tracing](https://docs.rs/tracing/) for every operations.
 ///
+/// # Spans
+///
+/// Every operation will be executed inside a `debug` span which carries
+/// the following fields:
+///
+/// - `scheme`: Service name from [`Scheme`]
+/// - `operation`: Operation name from [`Operation`]
+/// - `path`: The path of this operation (`from` and `to` for copy and rename)
+///
+/// Errors returned by operations will be recorded in the span. Readers,
+/// writers and pagers returned by operations are instrumented too, their
+/// polls will be executed inside child spans of the operation's span.
+///
 /// # Examples
 ///
 /// ## Basic Setup
@@ -122,13 +135,16 @@ impl<A: Accessor> Layer<A> for TracingLayer {
     type LayeredAccessor = TracingAccessor<A>;
 
     fn layer(&self, inner: A) -> Self::LayeredAccessor {
-        TracingAccessor { inner }
+        let scheme = inner.info().scheme();
+
+        TracingAccessor { inner, scheme }
     }
 }
 
 #[derive(Debug)]
 pub struct TracingAccessor<A> {
     inner: A,
+    scheme: Scheme,
 }
 
 #[async_trait]
@@ -145,17 +161,31 @@ impl<A: Accessor> LayeredAccessor for TracingAccessor<A> {
         &self.inner
     }
 
-    #[tracing::instrument(level = "debug")]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Info)
+    )]
     fn metadata(&self) -> AccessorInfo {
         self.inner.info()
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::CreateDir),
+        err
+    )]
     async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
         self.inner.create_dir(path, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Read),
+        err
+    )]
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
         self.inner
             .read(path, args)
@@ -163,7 +193,12 @@ impl<A: Accessor> LayeredAccessor for TracingAccessor<A> {
             .await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Write),
+        err
+    )]
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         self.inner
             .write(path, args)
@@ -171,27 +206,52 @@ impl<A: Accessor> LayeredAccessor for TracingAccessor<A> {
             .map(|(rp, r)| (rp, TracingWrapper::new(Span::current(), r)))
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Copy),
+        err
+    )]
     async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
         self.inner().copy(from, to, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Rename),
+        err
+    )]
     async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
         self.inner().rename(from, to, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Stat),
+        err
+    )]
     async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         self.inner.stat(path, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Delete),
+        err
+    )]
     async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
         self.inner.delete(path, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::List),
+        err
+    )]
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         self.inner
             .list(path, args)
@@ -199,7 +259,12 @@ impl<A: Accessor> LayeredAccessor for TracingAccessor<A> {
             .await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Scan),
+        err
+    )]
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
         self.inner
             .scan(path, args)
@@ -207,63 +272,118 @@ impl<A: Accessor> LayeredAccessor for TracingAccessor<A> {
             .await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Presign),
+        err
+    )]
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         self.inner.presign(path, args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::Batch),
+        err
+    )]
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
         self.inner.batch(args).await
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingCreateDir),
+        err
+    )]
     fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
         self.inner.blocking_create_dir(path, args)
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingRead),
+        err
+    )]
     fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
         self.inner
             .blocking_read(path, args)
             .map(|(rp, r)| (rp, TracingWrapper::new(Span::current(), r)))
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingWrite),
+        err
+    )]
     fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
         self.inner
             .blocking_write(path, args)
             .map(|(rp, r)| (rp, TracingWrapper::new(Span::current(), r)))
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingCopy),
+        err
+    )]
     fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
         self.inner().blocking_copy(from, to, args)
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingMove),
+        err
+    )]
     fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
         self.inner().blocking_rename(from, to, args)
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingStat),
+        err
+    )]
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         self.inner.blocking_stat(path, args)
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingDelete),
+        err
+    )]
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
         self.inner.blocking_delete(path, args)
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingList),
+        err
+    )]
     fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
         self.inner
             .blocking_list(path, args)
             .map(|(rp, it)| (rp, TracingWrapper::new(Span::current(), it)))
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
+    #[tracing::instrument(
+        level = "debug",
+        skip(self),
+        fields(scheme = %self.scheme, operation = %Operation::BlockingScan),
+        err
+    )]
     fn blocking_scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
         self.inner
             .blocking_scan(path, args)
@@ -386,6 +506,14 @@ impl<R: oio::BlockingWrite> oio::BlockingWrite for TracingWrapper<R> {
         self.inner.append(bs)
     }
 
+    #[tracing::instrument(
+        parent = &self.span,
+        level = "trace",
+        skip_all)]
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()
+    }
+
     #[tracing::instrument(
         parent = &self.span,
         level = "trace",
@@ -394,6 +394,7 @@ impl Accessor for () {
             scheme: Scheme::Custom("dummy"),
             root: "".to_string(),
             name: "dummy".to_string(),
+            endpoint: None,
             max_batch_operations: None,
             capabilities: None.into(),
             hints: None.into(),
@@ -507,6 +508,7 @@ pub struct AccessorInfo {
     scheme: Scheme,
     root: String,
     name: String,
+    endpoint: Option<String>,
     /// limit of batch operation
     /// only meaningful when accessor supports batch operation
     max_batch_operations: Option<usize>,
@@ -555,6 +557,23 @@ impl AccessorInfo {
         self
     }
 
+    /// Endpoint of backend, only available for backends that talk to a
+    /// remote server.
+    ///
+    /// For example:
+    ///
+    /// - endpoint for `s3` => `https://s3.amazonaws.com`
+    /// - endpoint for `webdav` => `http://127.0.0.1:8080`
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// Set endpoint of this backend.
+    pub fn set_endpoint(&mut self, endpoint: &str) -> &mut Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
     /// backend's number limitation of operations in a single batch.
     ///
     /// # Note
@@ -617,6 +636,42 @@ flags! {
         Blocking,
         /// Add this capability if service supports `batch`
         Batch,
+        /// Add this capability if service supports `read` with `if_match`
+        ReadWithIfMatch,
+        /// Add this capability if service supports `read` with `if_none_match`
+        ReadWithIfNoneMatch,
+        /// Add this capability if service supports `read` with `override_cache_control`
+        ReadWithOverrideCacheControl,
+        /// Add this capability if service supports `read` with `override_content_disposition`
+        ReadWithOverrideContentDisposition,
+        /// Add this capability if service supports `stat` with `if_match`
+        StatWithIfMatch,
+        /// Add this capability if service supports `stat` with `if_none_match`
+        StatWithIfNoneMatch,
+        /// Add this capability if service supports `write` with `content_type`
+        WriteWithContentType,
+        /// Add this capability if service supports `write` with `content_disposition`
+        WriteWithContentDisposition,
+        /// Add this capability if service supports `write` with `cache_control`
+        WriteWithCacheControl,
+        /// Add this capability if service supports `write` with `if_match`
+        WriteWithIfMatch,
+        /// Add this capability if service supports `list` and `scan` with `limit`
+        ListWithLimit,
+        /// Add this capability if service supports `read` with `version`
+        ReadWithVersion,
+        /// Add this capability if service supports `stat` with `version`
+        StatWithVersion,
+        /// Add this capability if service supports `delete` with `version`
+        DeleteWithVersion,
+        /// Add this capability if service supports `list` with `versions`
+        ListWithVersions,
+        /// Add this capability if service supports `write` with `storage_class`
+        WriteWithStorageClass,
+        /// Add this capability if service supports `write` with `last_modified`
+        WriteWithLastModified,
+        /// Add this capability if service supports `append` via writer
+        Append,
     }
 }
 
@@ -635,5 +690,16 @@ flags! {
         ///
         /// It's better to use stream to reading data.
         ReadStreamable,
+        /// Read whole content means the underlying service always loads the
+        /// whole content, and range reads are served by slicing it.
+        ///
+        /// Splitting a read into concurrent range reads is wasteful.
+        ReadWholeContent,
+        /// Implicit dir means the underlying service doesn't have real
+        /// directories, a dir could exist only because there are entries
+        /// under it.
+        ///
+        /// Stat on such dirs needs a list to check whether they exist.
+        ImplicitDir,
     }
 }
@@ -50,6 +50,22 @@ pub trait Adapter: Send + Sync + Debug + Unpin + 'static {
         .with_operation("kv::Adapter::blocking_get"))
     }
 
+    /// Get the length of the value of a key.
+    ///
+    /// - return `Ok(None)` if this key is not exist.
+    ///
+    /// The default implementation fetches the whole value via `get`.
+    /// Services that could tell the length in a cheaper way (via metadata
+    /// for example) should override it to make `stat` cheaper.
+    async fn get_length(&self, path: &str) -> Result<Option<u64>> {
+        Ok(self.get(path).await?.map(|bs| bs.len() as u64))
+    }
+
+    /// The blocking version of get_length.
+    fn blocking_get_length(&self, path: &str) -> Result<Option<u64>> {
+        Ok(self.blocking_get(path)?.map(|bs| bs.len() as u64))
+    }
+
     /// Set a key into service.
     async fn set(&self, path: &str, value: &[u8]) -> Result<()>;
 
@@ -83,6 +99,12 @@ pub trait Adapter: Send + Sync + Debug + Unpin + 'static {
     }
 
     /// Scan a key prefix to get all keys that start with this key.
+    ///
+    /// - `path` is the absolute key prefix, empty means all keys.
+    /// - returned keys are absolute keys, root will be handled by backend.
+    ///
+    /// Services that implement scan should advertise [`AccessorCapability::Scan`],
+    /// then list will also be supported via scan.
     async fn scan(&self, path: &str) -> Result<Vec<String>> {
         let _ = path;
 
@@ -15,11 +15,16 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
 
+use super::chunk::is_part_key;
+use super::chunk::part_key;
+use super::chunk::Manifest;
+use super::chunk::MANIFEST_LEN;
 use super::Adapter;
 use crate::ops::*;
 use crate::raw::*;
@@ -30,6 +35,7 @@ use crate::*;
 pub struct Backend<S: Adapter> {
     kv: Arc<S>,
     root: String,
+    chunk_size: Option<usize>,
 }
 
 impl<S> Backend<S>
@@ -41,6 +47,7 @@ where
         Self {
             kv: Arc::new(kv),
             root: "/".to_string(),
+            chunk_size: None,
         }
     }
 
@@ -49,6 +56,22 @@ where
         self.root = normalize_root(root);
         self
     }
+
+    /// Configure chunk size within this backend.
+    ///
+    /// Values larger than chunk size will be split into `key//part/N` keys
+    /// with a manifest stored at `key`. Chunked values will be assembled
+    /// while reading no matter chunk size is configured or not.
+    ///
+    /// Keys of chunks will always be hidden from scan.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = if chunk_size == 0 {
+            None
+        } else {
+            Some(chunk_size)
+        };
+        self
+    }
 }
 
 #[async_trait]
@@ -62,8 +85,17 @@ impl<S: Adapter> Accessor for Backend<S> {
 
     fn info(&self) -> AccessorInfo {
         let mut am: AccessorInfo = self.kv.metadata().into();
-        am.set_root(&self.root)
-            .set_hints(AccessorHint::ReadStreamable | AccessorHint::ReadSeekable);
+        // List is emulated via scan, so services that support scan
+        // could be listed in hierarchy too.
+        if am.capabilities().contains(AccessorCapability::Scan) {
+            am.set_capabilities(am.capabilities() | AccessorCapability::List);
+        }
+        am.set_root(&self.root).set_hints(
+            AccessorHint::ReadStreamable
+                | AccessorHint::ReadSeekable
+                | AccessorHint::ReadWholeContent
+                | AccessorHint::ImplicitDir,
+        );
 
         am
     }
@@ -89,7 +121,17 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
+        let bs = match Manifest::decode(&bs) {
+            Some(m) => {
+                let (start, end, first, last) = m.plan(args.range());
+                let mut buf = Vec::with_capacity((end - start) as usize);
+                for idx in first..last {
+                    buf.extend(self.get_part(&p, idx).await?);
+                }
+                self.trim_chunks(buf, &m, start, end, first)
+            }
+            None => self.apply_range(bs, args.range()),
+        };
 
         let length = bs.len();
         Ok((RpRead::new(length as u64), oio::Cursor::from(bs)))
@@ -103,32 +145,60 @@ impl<S: Adapter> Accessor for Backend<S> {
             None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
         };
 
-        let bs = self.apply_range(bs, args.range());
+        let bs = match Manifest::decode(&bs) {
+            Some(m) => {
+                let (start, end, first, last) = m.plan(args.range());
+                let mut buf = Vec::with_capacity((end - start) as usize);
+                for idx in first..last {
+                    buf.extend(self.blocking_get_part(&p, idx)?);
+                }
+                self.trim_chunks(buf, &m, start, end, first)
+            }
+            None => self.apply_range(bs, args.range()),
+        };
         Ok((RpRead::new(bs.len() as u64), oio::Cursor::from(bs)))
     }
 
     async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         let p = build_abs_path(&self.root, path);
 
-        Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p)))
+        Ok((
+            RpWrite::new(),
+            KvWriter::new(self.kv.clone(), p, self.chunk_size),
+        ))
     }
 
     fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
         let p = build_abs_path(&self.root, path);
 
-        Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p)))
+        Ok((
+            RpWrite::new(),
+            KvWriter::new(self.kv.clone(), p, self.chunk_size),
+        ))
     }
 
     async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
         let p = build_abs_path(&self.root, path);
 
-        if p.is_empty() || p.ends_with('/') {
+        if path == "/" {
             Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
+        } else if p.ends_with('/') {
+            match self.kv.get_length(&p).await? {
+                Some(_) => Ok(RpStat::new(Metadata::new(EntryMode::DIR))),
+                None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+            }
         } else {
-            let bs = self.kv.get(&p).await?;
-            match bs {
-                Some(bs) => Ok(RpStat::new(
-                    Metadata::new(EntryMode::FILE).with_content_length(bs.len() as u64),
+            let length = match self.kv.get_length(&p).await? {
+                // Value with the same length of manifest could be chunked,
+                // fetch it to get the real length.
+                Some(length) if length == MANIFEST_LEN as u64 => {
+                    self.kv.get(&p).await?.map(|bs| content_length(&bs))
+                }
+                v => v,
+            };
+            match length {
+                Some(length) => Ok(RpStat::new(
+                    Metadata::new(EntryMode::FILE).with_content_length(length),
                 )),
                 None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
             }
@@ -138,13 +208,18 @@ impl<S: Adapter> Accessor for Backend<S> {
     fn blocking_stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
         let p = build_abs_path(&self.root, path);
 
-        if p.is_empty() || p.ends_with('/') {
+        if path == "/" {
             Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
+        } else if p.ends_with('/') {
+            match self.kv.blocking_get(&p)? {
+                Some(_) => Ok(RpStat::new(Metadata::new(EntryMode::DIR))),
+                None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+            }
         } else {
             let bs = self.kv.blocking_get(&p)?;
             match bs {
                 Some(bs) => Ok(RpStat::new(
-                    Metadata::new(EntryMode::FILE).with_content_length(bs.len() as u64),
+                    Metadata::new(EntryMode::FILE).with_content_length(content_length(&bs)),
                 )),
                 None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
             }
@@ -154,6 +229,11 @@ impl<S: Adapter> Accessor for Backend<S> {
     async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
         let p = build_abs_path(&self.root, path);
 
+        if let Some(m) = load_manifest(self.kv.as_ref(), &p).await? {
+            for idx in 0..m.chunks {
+                self.kv.delete(&part_key(&p, idx)).await?;
+            }
+        }
         self.kv.delete(&p).await?;
         Ok(RpDelete::default())
     }
@@ -161,14 +241,35 @@ impl<S: Adapter> Accessor for Backend<S> {
     fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
         let p = build_abs_path(&self.root, path);
 
+        if let Some(m) = blocking_load_manifest(self.kv.as_ref(), &p)? {
+            for idx in 0..m.chunks {
+                self.kv.blocking_delete(&part_key(&p, idx))?;
+            }
+        }
         self.kv.blocking_delete(&p)?;
         Ok(RpDelete::default())
     }
 
+    async fn list(&self, path: &str, _: OpList) -> Result<(RpList, Self::Pager)> {
+        let p = build_abs_path(&self.root, path);
+        let res = self.kv.scan(&p).await?;
+        let pager = KvPager::with_hierarchy(&self.root, path, self.filter_parts(res));
+
+        Ok((RpList::default(), pager))
+    }
+
+    fn blocking_list(&self, path: &str, _: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        let p = build_abs_path(&self.root, path);
+        let res = self.kv.blocking_scan(&p)?;
+        let pager = KvPager::with_hierarchy(&self.root, path, self.filter_parts(res));
+
+        Ok((RpList::default(), pager))
+    }
+
     async fn scan(&self, path: &str, _: OpScan) -> Result<(RpScan, Self::Pager)> {
         let p = build_abs_path(&self.root, path);
         let res = self.kv.scan(&p).await?;
-        let pager = KvPager::new(&self.root, res);
+        let pager = KvPager::new(&self.root, self.filter_parts(res));
 
         Ok((RpScan::default(), pager))
     }
@@ -176,7 +277,7 @@ impl<S: Adapter> Accessor for Backend<S> {
     fn blocking_scan(&self, path: &str, _: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
         let p = build_abs_path(&self.root, path);
         let res = self.kv.blocking_scan(&p)?;
-        let pager = KvPager::new(&self.root, res);
+        let pager = KvPager::new(&self.root, self.filter_parts(res));
 
         Ok((RpScan::default(), pager))
     }
@@ -200,10 +301,83 @@ where
             (None, None) => bs,
         }
     }
+
+    /// Trim the assembled chunks into `[start, end)` of the whole value.
+    fn trim_chunks(
+        &self,
+        mut bs: Vec<u8>,
+        m: &Manifest,
+        start: u64,
+        end: u64,
+        first: u64,
+    ) -> Vec<u8> {
+        // Nothing to read, for example, reading at or after the end.
+        if start >= end {
+            return Vec::new();
+        }
+
+        let offset = first * m.chunk_size;
+        bs.truncate((end - offset) as usize);
+        bs.split_off((start - offset) as usize)
+    }
+
+    /// Hide chunks of values from scan result.
+    fn filter_parts(&self, keys: Vec<String>) -> Vec<String> {
+        keys.into_iter().filter(|k| !is_part_key(k)).collect()
+    }
+
+    async fn get_part(&self, path: &str, idx: u64) -> Result<Vec<u8>> {
+        self.kv.get(&part_key(path, idx)).await?.ok_or_else(|| {
+            Error::new(ErrorKind::Unexpected, "chunk of value is missing")
+                .with_context("path", path)
+                .with_context("chunk", idx.to_string())
+        })
+    }
+
+    fn blocking_get_part(&self, path: &str, idx: u64) -> Result<Vec<u8>> {
+        self.kv.blocking_get(&part_key(path, idx))?.ok_or_else(|| {
+            Error::new(ErrorKind::Unexpected, "chunk of value is missing")
+                .with_context("path", path)
+                .with_context("chunk", idx.to_string())
+        })
+    }
+}
+
+/// Get content length of value, respect the size in manifest if it's chunked.
+fn content_length(bs: &[u8]) -> u64 {
+    match Manifest::decode(bs) {
+        Some(m) => m.size,
+        None => bs.len() as u64,
+    }
+}
+
+/// Load the manifest of a chunked value.
+///
+/// Only values with the same length of manifest could be chunked, so we
+/// check the length first to avoid fetching large values.
+async fn load_manifest<S: Adapter>(kv: &S, path: &str) -> Result<Option<Manifest>> {
+    match kv.get_length(path).await? {
+        Some(length) if length == MANIFEST_LEN as u64 => {
+            Ok(kv.get(path).await?.and_then(|bs| Manifest::decode(&bs)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The blocking version of load_manifest.
+fn blocking_load_manifest<S: Adapter>(kv: &S, path: &str) -> Result<Option<Manifest>> {
+    match kv.blocking_get_length(path)? {
+        Some(length) if length == MANIFEST_LEN as u64 => {
+            Ok(kv.blocking_get(path)?.and_then(|bs| Manifest::decode(&bs)))
+        }
+        _ => Ok(None),
+    }
 }
 
 pub struct KvPager {
     root: String,
+    /// The relative dir to list, only set while listing in hierarchy.
+    dir: Option<String>,
     inner: Option<Vec<String>>,
 }
 
@@ -211,23 +385,63 @@ impl KvPager {
     fn new(root: &str, inner: Vec<String>) -> Self {
         Self {
             root: root.to_string(),
+            dir: None,
+            inner: Some(inner),
+        }
+    }
+
+    /// Create a pager that only returns the first-level children of
+    /// `dir`.
+    ///
+    /// Keys deeper than that will be folded into their parent dir, so that
+    /// services without real dirs can be listed like a file system.
+    fn with_hierarchy(root: &str, dir: &str, inner: Vec<String>) -> Self {
+        // Root dir is represented as `/`, but relative keys never start with it.
+        let dir = if dir == "/" { "" } else { dir };
+
+        Self {
+            root: root.to_string(),
+            dir: Some(dir.to_string()),
             inner: Some(inner),
         }
     }
 
     fn inner_next_page(&mut self) -> Option<Vec<oio::Entry>> {
+        let mut visited = HashSet::new();
         let res = self
             .inner
             .take()?
             .into_iter()
-            .map(|v| {
-                let mode = if v.ends_with('/') {
+            .filter_map(|v| {
+                // Keys like `root/` itself is the root dir, skip them.
+                let mut path = build_rel_path(&self.root, &v);
+                if path.is_empty() {
+                    return None;
+                }
+
+                if let Some(dir) = &self.dir {
+                    // The dir marker itself should not be listed.
+                    let child = path.strip_prefix(dir.as_str())?;
+                    if child.is_empty() {
+                        return None;
+                    }
+                    // Fold nested keys into the first-level dir.
+                    if let Some(idx) = child.find('/') {
+                        path = format!("{dir}{}", &child[..=idx]);
+                    }
+                    // Implied dirs could show up many times, only return once.
+                    if !visited.insert(path.clone()) {
+                        return None;
+                    }
+                }
+
+                let mode = if path.ends_with('/') {
                     EntryMode::DIR
                 } else {
                     EntryMode::FILE
                 };
 
-                oio::Entry::new(&build_rel_path(&self.root, &v), Metadata::new(mode))
+                Some(oio::Entry::new(&path, Metadata::new(mode)))
             })
             .collect();
 
@@ -251,16 +465,18 @@ impl oio::BlockingPage for KvPager {
 pub struct KvWriter<S> {
     kv: Arc<S>,
     path: String,
+    chunk_size: Option<usize>,
 
     /// TODO: if kv supports append, we can use them directly.
     buf: Option<Vec<u8>>,
 }
 
 impl<S> KvWriter<S> {
-    fn new(kv: Arc<S>, path: String) -> Self {
+    fn new(kv: Arc<S>, path: String, chunk_size: Option<usize>) -> Self {
         KvWriter {
             kv,
             path,
+            chunk_size,
             buf: None,
         }
     }
@@ -283,6 +499,13 @@ impl<S: Adapter> oio::Write for KvWriter<S> {
     }
 
     async fn append(&mut self, bs: Bytes) -> Result<()> {
+        // Value could be chunked while closing, so we can't append it
+        // to service directly.
+        if self.chunk_size.is_some() {
+            self.extend_buf(bs);
+            return Ok(());
+        }
+
         if let Err(e) = self.kv.append(&self.path, bs.to_vec().as_slice()).await {
             if e.kind() == ErrorKind::Unsupported {
                 self.extend_buf(bs);
@@ -294,17 +517,45 @@ impl<S: Adapter> oio::Write for KvWriter<S> {
     }
 
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        self.buf = None;
+
+        Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
-        if let Some(buf) = self.buf.as_deref() {
+        let buf = match self.buf.as_deref() {
+            Some(buf) => buf,
+            None => return Ok(()),
+        };
+        let chunk_size = match self.chunk_size {
+            Some(v) => v,
+            None => return self.kv.set(&self.path, buf).await,
+        };
+
+        let old = load_manifest(self.kv.as_ref(), &self.path).await?;
+
+        let mut chunks = 0;
+        if buf.len() > chunk_size {
+            for (idx, chunk) in buf.chunks(chunk_size).enumerate() {
+                self.kv
+                    .set(&part_key(&self.path, idx as u64), chunk)
+                    .await?;
+            }
+            // Write manifest at last so that readers won't see partial value.
+            let m = Manifest::new(buf.len() as u64, chunk_size as u64);
+            self.kv.set(&self.path, &m.encode()).await?;
+            chunks = m.chunks;
+        } else {
             self.kv.set(&self.path, buf).await?;
         }
 
+        // Cleanup chunks that are not used anymore.
+        if let Some(old) = old {
+            for idx in chunks..old.chunks {
+                self.kv.delete(&part_key(&self.path, idx)).await?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -322,11 +573,340 @@ impl<S: Adapter> oio::BlockingWrite for KvWriter<S> {
         Ok(())
     }
 
+    fn abort(&mut self) -> Result<()> {
+        self.buf = None;
+
+        Ok(())
+    }
+
     fn close(&mut self) -> Result<()> {
-        if let Some(buf) = self.buf.as_deref() {
+        let buf = match self.buf.as_deref() {
+            Some(buf) => buf,
+            None => return Ok(()),
+        };
+        let chunk_size = match self.chunk_size {
+            Some(v) => v,
+            None => return self.kv.blocking_set(&self.path, buf),
+        };
+
+        let old = blocking_load_manifest(self.kv.as_ref(), &self.path)?;
+
+        let mut chunks = 0;
+        if buf.len() > chunk_size {
+            for (idx, chunk) in buf.chunks(chunk_size).enumerate() {
+                self.kv
+                    .blocking_set(&part_key(&self.path, idx as u64), chunk)?;
+            }
+            // Write manifest at last so that readers won't see partial value.
+            let m = Manifest::new(buf.len() as u64, chunk_size as u64);
+            self.kv.blocking_set(&self.path, &m.encode())?;
+            chunks = m.chunks;
+        } else {
             self.kv.blocking_set(&self.path, buf)?;
         }
 
+        // Cleanup chunks that are not used anymore.
+        if let Some(old) = old {
+            for idx in chunks..old.chunks {
+                self.kv.blocking_delete(&part_key(&self.path, idx))?;
+            }
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+
+    use oio::BlockingRead;
+    use oio::BlockingWrite;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockAdapter {
+        inner: Mutex<BTreeMap<String, Vec<u8>>>,
+        /// Count of values fetched via `get`.
+        gets: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Adapter for MockAdapter {
+        fn metadata(&self) -> super::super::Metadata {
+            super::super::Metadata::new(
+                Scheme::Custom("mock"),
+                "mock",
+                AccessorCapability::Read | AccessorCapability::Write,
+            )
+        }
+
+        async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+            self.blocking_get(path)
+        }
+
+        fn blocking_get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+            self.gets.fetch_add(1, Ordering::SeqCst);
+            Ok(self.inner.lock().unwrap().get(path).cloned())
+        }
+
+        async fn get_length(&self, path: &str) -> Result<Option<u64>> {
+            self.blocking_get_length(path)
+        }
+
+        fn blocking_get_length(&self, path: &str) -> Result<Option<u64>> {
+            Ok(self
+                .inner
+                .lock()
+                .unwrap()
+                .get(path)
+                .map(|bs| bs.len() as u64))
+        }
+
+        async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+            self.blocking_set(path, value)
+        }
+
+        fn blocking_set(&self, path: &str, value: &[u8]) -> Result<()> {
+            self.inner
+                .lock()
+                .unwrap()
+                .insert(path.to_string(), value.to_vec());
+            Ok(())
+        }
+
+        async fn delete(&self, path: &str) -> Result<()> {
+            self.blocking_delete(path)
+        }
+
+        fn blocking_delete(&self, path: &str) -> Result<()> {
+            self.inner.lock().unwrap().remove(path);
+            Ok(())
+        }
+
+        fn blocking_scan(&self, path: &str) -> Result<Vec<String>> {
+            Ok(self
+                .inner
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|k| k.starts_with(path))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn blocking_read_all(b: &Backend<MockAdapter>, path: &str, br: BytesRange) -> Vec<u8> {
+        let (_, mut r) = b
+            .blocking_read(path, OpRead::new().with_range(br))
+            .expect("read must succeed");
+        let mut bs = Vec::new();
+        while let Some(chunk) = r.next() {
+            bs.extend_from_slice(&chunk.expect("read must succeed"));
+        }
+        bs
+    }
+
+    #[test]
+    fn test_chunked_value() {
+        let b = Backend::new(MockAdapter::default()).with_chunk_size(4);
+        let content: Vec<u8> = (0..10).collect();
+
+        let (_, mut w) = b.blocking_write("file", OpWrite::new()).unwrap();
+        w.write(Bytes::from(content.clone())).unwrap();
+        w.close().unwrap();
+
+        let keys = b.kv.blocking_scan("").unwrap();
+        assert_eq!(
+            keys,
+            vec!["file", "file//part/0", "file//part/1", "file//part/2"]
+        );
+        assert_eq!(b.filter_parts(keys), vec!["file"]);
+
+        let meta = b
+            .blocking_stat("file", OpStat::new())
+            .unwrap()
+            .into_metadata();
+        assert_eq!(meta.content_length(), 10);
+
+        assert_eq!(
+            blocking_read_all(&b, "file", BytesRange::new(None, None)),
+            content
+        );
+        assert_eq!(
+            blocking_read_all(&b, "file", BytesRange::new(Some(3), Some(6))),
+            content[3..9]
+        );
+        assert_eq!(
+            blocking_read_all(&b, "file", BytesRange::new(None, Some(3))),
+            content[7..]
+        );
+        // Read at or after the end should return nothing.
+        assert!(blocking_read_all(&b, "file", BytesRange::new(Some(10), None)).is_empty());
+        assert!(blocking_read_all(&b, "file", BytesRange::new(Some(12), Some(2))).is_empty());
+
+        // Overwrite with a small value should cleanup all chunks.
+        let (_, mut w) = b.blocking_write("file", OpWrite::new()).unwrap();
+        w.write(Bytes::from("abc")).unwrap();
+        w.close().unwrap();
+        assert_eq!(b.kv.blocking_scan("").unwrap(), vec!["file"]);
+        assert_eq!(
+            blocking_read_all(&b, "file", BytesRange::new(None, None)),
+            b"abc"
+        );
+
+        // Delete should remove all chunks.
+        let (_, mut w) = b.blocking_write("file", OpWrite::new()).unwrap();
+        w.write(Bytes::from(content)).unwrap();
+        w.close().unwrap();
+        b.blocking_delete("file", OpDelete::new()).unwrap();
+        assert!(b.kv.blocking_scan("").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stat_chunked_value() {
+        let b = Backend::new(MockAdapter::default()).with_chunk_size(4);
+
+        let (_, mut w) = b.write("file", OpWrite::new()).await.unwrap();
+        oio::Write::write(&mut w, Bytes::from(vec![0; 10]))
+            .await
+            .unwrap();
+        oio::Write::close(&mut w).await.unwrap();
+        let meta = b.stat("file", OpStat::new()).await.unwrap().into_metadata();
+        assert_eq!(meta.content_length(), 10);
+
+        // Normal value with the same length of manifest.
+        let (_, mut w) = b.write("other", OpWrite::new()).await.unwrap();
+        oio::Write::write(&mut w, Bytes::from(vec![0; MANIFEST_LEN]))
+            .await
+            .unwrap();
+        oio::Write::close(&mut w).await.unwrap();
+        let meta = b
+            .stat("other", OpStat::new())
+            .await
+            .unwrap()
+            .into_metadata();
+        assert_eq!(meta.content_length(), MANIFEST_LEN as u64);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_unchunked_value() {
+        let b = Backend::new(MockAdapter::default()).with_chunk_size(4);
+        b.kv.blocking_set("file", &[0; 1024]).unwrap();
+
+        // Overwriting a large unchunked value must not fetch it.
+        let (_, mut w) = b.write("file", OpWrite::new()).await.unwrap();
+        oio::Write::write(&mut w, Bytes::from("0123456789"))
+            .await
+            .unwrap();
+        oio::Write::close(&mut w).await.unwrap();
+        assert_eq!(b.kv.gets.load(Ordering::SeqCst), 0);
+
+        b.kv.blocking_set("file", &[0; 1024]).unwrap();
+        let (_, mut w) = b.blocking_write("file", OpWrite::new()).unwrap();
+        w.write(Bytes::from("0123456789")).unwrap();
+        w.close().unwrap();
+        assert_eq!(b.kv.gets.load(Ordering::SeqCst), 0);
+
+        // Overwriting a chunked value still cleanups its chunks.
+        let (_, mut w) = b.blocking_write("file", OpWrite::new()).unwrap();
+        w.write(Bytes::from("abc")).unwrap();
+        w.close().unwrap();
+        assert_eq!(b.kv.gets.load(Ordering::SeqCst), 1);
+        assert_eq!(b.kv.blocking_scan("").unwrap(), vec!["file"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked_value_at_end() {
+        let b = Backend::new(MockAdapter::default()).with_chunk_size(4);
+
+        let (_, mut w) = b.write("file", OpWrite::new()).await.unwrap();
+        oio::Write::write(&mut w, Bytes::from("0123456789"))
+            .await
+            .unwrap();
+        oio::Write::close(&mut w).await.unwrap();
+
+        let (rp, _) = b
+            .read(
+                "file",
+                OpRead::new().with_range(BytesRange::new(Some(10), None)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rp.metadata().content_length(), 0);
+    }
+
+    #[test]
+    fn test_user_keys_not_hidden() {
+        let b = Backend::new(MockAdapter::default()).with_chunk_size(4);
+
+        // Keys looks like chunks but written by users.
+        for path in ["file.part.0", "dir/part/1"] {
+            let (_, mut w) = b.blocking_write(path, OpWrite::new()).unwrap();
+            w.write(Bytes::from("abc")).unwrap();
+            w.close().unwrap();
+        }
+
+        let keys = b.kv.blocking_scan("").unwrap();
+        assert_eq!(b.filter_parts(keys), vec!["dir/part/1", "file.part.0"]);
+    }
+
+    #[test]
+    fn test_kv_pager_with_root() {
+        let mut pager = KvPager::new(
+            "/root/",
+            vec![
+                "root/".to_string(),
+                "root/dir/".to_string(),
+                "root/dir/file".to_string(),
+            ],
+        );
+
+        let entries = pager.inner_next_page().expect("must have page");
+        assert_eq!(
+            entries,
+            vec![
+                oio::Entry::new("dir/", Metadata::new(EntryMode::DIR)),
+                oio::Entry::new("dir/file", Metadata::new(EntryMode::FILE)),
+            ]
+        );
+        assert!(pager.inner_next_page().is_none());
+    }
+
+    #[test]
+    fn test_kv_pager_with_hierarchy() {
+        let keys = vec![
+            "root/dir/a/b/file".to_string(),
+            "root/dir/".to_string(),
+            "root/dir/a/".to_string(),
+            "root/dir/file".to_string(),
+            "root/dir/c/file".to_string(),
+            "root/other".to_string(),
+        ];
+
+        let mut pager = KvPager::with_hierarchy("/root/", "dir/", keys.clone());
+        let entries = pager.inner_next_page().expect("must have page");
+        assert_eq!(
+            entries,
+            vec![
+                oio::Entry::new("dir/a/", Metadata::new(EntryMode::DIR)),
+                oio::Entry::new("dir/file", Metadata::new(EntryMode::FILE)),
+                oio::Entry::new("dir/c/", Metadata::new(EntryMode::DIR)),
+            ]
+        );
+
+        let mut pager = KvPager::with_hierarchy("/root/", "/", keys);
+        let entries = pager.inner_next_page().expect("must have page");
+        assert_eq!(
+            entries,
+            vec![
+                oio::Entry::new("dir/", Metadata::new(EntryMode::DIR)),
+                oio::Entry::new("other", Metadata::new(EntryMode::FILE)),
+            ]
+        );
+    }
+}
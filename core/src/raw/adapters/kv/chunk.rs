@@ -0,0 +1,174 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Chunked values support for kv adapter.
+//!
+//! Values larger than the configured chunk size will be stored as:
+//!
+//! - `key//part/0`, `key//part/1`, ...: the chunks of value.
+//! - `key`: a small manifest that records the chunk count and total length.
+//!
+//! Values that are not chunked are stored as is, so that existing data
+//! keeps working.
+//!
+//! Normalized paths never contain `//`, so chunk keys will not collide
+//! with keys written by users.
+
+use crate::raw::*;
+
+/// Magic prefix of manifest so that we can tell it from normal values.
+const MANIFEST_MAGIC: &[u8] = b"\0opendal:kv:chunked\0";
+/// Length of encoded manifest: magic + chunks + size + chunk_size.
+pub const MANIFEST_LEN: usize = MANIFEST_MAGIC.len() + 8 * 3;
+
+/// Manifest of a chunked value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manifest {
+    /// Count of chunks.
+    pub chunks: u64,
+    /// Total length of the value.
+    pub size: u64,
+    /// Size of every chunk, the last chunk could be smaller.
+    pub chunk_size: u64,
+}
+
+impl Manifest {
+    pub fn new(size: u64, chunk_size: u64) -> Self {
+        Self {
+            chunks: (size + chunk_size - 1) / chunk_size,
+            size,
+            chunk_size,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bs = Vec::with_capacity(MANIFEST_LEN);
+        bs.extend_from_slice(MANIFEST_MAGIC);
+        bs.extend_from_slice(&self.chunks.to_le_bytes());
+        bs.extend_from_slice(&self.size.to_le_bytes());
+        bs.extend_from_slice(&self.chunk_size.to_le_bytes());
+        bs
+    }
+
+    /// Decode manifest from value, returns `None` if this value is not a
+    /// manifest.
+    pub fn decode(bs: &[u8]) -> Option<Self> {
+        if bs.len() != MANIFEST_LEN || !bs.starts_with(MANIFEST_MAGIC) {
+            return None;
+        }
+
+        let read_u64 = |idx: usize| {
+            let start = MANIFEST_MAGIC.len() + idx * 8;
+            let mut buf = [0; 8];
+            buf.copy_from_slice(&bs[start..start + 8]);
+            u64::from_le_bytes(buf)
+        };
+
+        let m = Self {
+            chunks: read_u64(0),
+            size: read_u64(1),
+            chunk_size: read_u64(2),
+        };
+        if m.chunk_size == 0 {
+            return None;
+        }
+        Some(m)
+    }
+
+    /// Calculate the range of value and the chunks that need to be fetched.
+    ///
+    /// Returns `(start, end, first_chunk, last_chunk)`, both `end` and
+    /// `last_chunk` are exclusive.
+    pub fn plan(&self, br: BytesRange) -> (u64, u64, u64, u64) {
+        let total = self.size;
+        let (start, end) = match (br.offset(), br.size()) {
+            (Some(offset), Some(size)) => (offset.min(total), (offset + size).min(total)),
+            (Some(offset), None) => (offset.min(total), total),
+            (None, Some(size)) => (total.saturating_sub(size), total),
+            (None, None) => (0, total),
+        };
+
+        if start >= end {
+            return (start, start, 0, 0);
+        }
+
+        (
+            start,
+            end,
+            start / self.chunk_size,
+            (end - 1) / self.chunk_size + 1,
+        )
+    }
+}
+
+/// Separator between the key of value and the index of chunk.
+const PART_SEPARATOR: &str = "//part/";
+
+/// Build the key of given chunk.
+pub fn part_key(path: &str, idx: u64) -> String {
+    format!("{path}{PART_SEPARATOR}{idx}")
+}
+
+/// Check if given key is a chunk of other value.
+pub fn is_part_key(key: &str) -> bool {
+    match key.rsplit_once(PART_SEPARATOR) {
+        Some((_, idx)) => !idx.is_empty() && idx.bytes().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_encode_decode() {
+        let m = Manifest::new(10, 3);
+        assert_eq!(m.chunks, 4);
+
+        let bs = m.encode();
+        assert_eq!(Manifest::decode(&bs), Some(m));
+
+        assert_eq!(Manifest::decode(b"hello, world"), None);
+        assert_eq!(Manifest::decode(&bs[..bs.len() - 1]), None);
+    }
+
+    #[test]
+    fn test_manifest_plan() {
+        let m = Manifest::new(10, 3);
+
+        assert_eq!(m.plan(BytesRange::new(None, None)), (0, 10, 0, 4));
+        assert_eq!(m.plan(BytesRange::new(Some(3), Some(3))), (3, 6, 1, 2));
+        assert_eq!(m.plan(BytesRange::new(Some(4), Some(4))), (4, 8, 1, 3));
+        assert_eq!(m.plan(BytesRange::new(Some(8), None)), (8, 10, 2, 4));
+        assert_eq!(m.plan(BytesRange::new(None, Some(2))), (8, 10, 2, 4));
+        assert_eq!(m.plan(BytesRange::new(Some(10), None)), (10, 10, 0, 0));
+        assert_eq!(m.plan(BytesRange::new(Some(20), None)), (10, 10, 0, 0));
+    }
+
+    #[test]
+    fn test_is_part_key() {
+        assert!(is_part_key("dir/file//part/0"));
+        assert!(is_part_key("file//part/12"));
+        assert!(!is_part_key("file//part/"));
+        assert!(!is_part_key("file//part/x"));
+        assert!(!is_part_key("file"));
+        // Keys written by users should never be treated as chunks.
+        assert!(!is_part_key("file.part.0"));
+        assert!(!is_part_key("dir/part/0"));
+    }
+}
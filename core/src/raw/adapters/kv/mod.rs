@@ -31,3 +31,5 @@ pub use api::Metadata;
 
 mod backend;
 pub use backend::Backend;
+
+mod chunk;
@@ -44,5 +44,8 @@
 //! # Available Adapters
 //!
 //! - [`kv::Adapter`]: Adapter for Key Value Services like in-memory map, `redis`.
+//! - [`typed_kv::Adapter`]: Adapter for Key Value Services that can store
+//!   values with their metadata like in-memory map.
 
 pub mod kv;
+pub mod typed_kv;
@@ -0,0 +1,142 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::raw::adapters::kv;
+use crate::EntryMode;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Metadata;
+use crate::Result;
+
+/// Adapter is the typed adapter to underlying kv services.
+///
+/// By implement this trait, any kv service can work as an OpenDAL Service.
+#[async_trait]
+pub trait Adapter: Send + Sync + Debug + Unpin + 'static {
+    /// Return the medata of this key value accessor.
+    fn metadata(&self) -> kv::Metadata;
+
+    /// Get a value from service.
+    ///
+    /// - return `Ok(None)` if this key is not exist.
+    async fn get(&self, path: &str) -> Result<Option<Value>>;
+
+    /// The blocking version of get.
+    fn blocking_get(&self, path: &str) -> Result<Option<Value>> {
+        let _ = path;
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "typed kv adapter doesn't support this operation",
+        )
+        .with_operation("typed_kv::Adapter::blocking_get"))
+    }
+
+    /// Set a value into service.
+    async fn set(&self, path: &str, value: Value) -> Result<()>;
+
+    /// The blocking version of set.
+    fn blocking_set(&self, path: &str, value: Value) -> Result<()> {
+        let _ = (path, value);
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "typed kv adapter doesn't support this operation",
+        )
+        .with_operation("typed_kv::Adapter::blocking_set"))
+    }
+
+    /// Delete a key from service.
+    ///
+    /// - return `Ok(())` even if this key is not exist.
+    async fn delete(&self, path: &str) -> Result<()>;
+
+    /// Delete a key from service in blocking way.
+    ///
+    /// - return `Ok(())` even if this key is not exist.
+    fn blocking_delete(&self, path: &str) -> Result<()> {
+        let _ = path;
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "typed kv adapter doesn't support this operation",
+        )
+        .with_operation("typed_kv::Adapter::blocking_delete"))
+    }
+
+    /// Scan a key prefix to get all keys that start with this key and
+    /// their metadata.
+    async fn scan(&self, path: &str) -> Result<Vec<(String, Metadata)>> {
+        let _ = path;
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "typed kv adapter doesn't support this operation",
+        )
+        .with_operation("typed_kv::Adapter::scan"))
+    }
+
+    /// Scan a key prefix to get all keys that start with this key and
+    /// their metadata in blocking way.
+    fn blocking_scan(&self, path: &str) -> Result<Vec<(String, Metadata)>> {
+        let _ = path;
+
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "typed kv adapter doesn't support this operation",
+        )
+        .with_operation("typed_kv::Adapter::blocking_scan"))
+    }
+}
+
+/// Value is the typed value stored in typed kv adapter.
+///
+/// It's cheap to clone so that adapters can store it directly.
+#[derive(Debug, Clone)]
+pub struct Value {
+    /// Metadata of this value.
+    pub metadata: Metadata,
+    /// The corresponding content of this value.
+    pub value: Bytes,
+}
+
+impl Value {
+    /// Create a new value of dir.
+    pub fn new_dir() -> Self {
+        Self {
+            metadata: Metadata::new(EntryMode::DIR).with_content_length(0),
+            value: Bytes::new(),
+        }
+    }
+
+    /// Create a new value of file with given metadata and content.
+    ///
+    /// The content length of metadata will be set to the length of content.
+    pub fn new_file(metadata: Metadata, value: Bytes) -> Self {
+        Self {
+            metadata: metadata
+                .with_mode(EntryMode::FILE)
+                .with_content_length(value.len() as u64),
+            value,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+
+use super::Adapter;
+use super::Value;
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Backend of typed kv service.
+#[derive(Debug, Clone)]
+pub struct Backend<S: Adapter> {
+    kv: Arc<S>,
+    root: String,
+}
+
+impl<S> Backend<S>
+where
+    S: Adapter,
+{
+    /// Create a new typed kv backend.
+    pub fn new(kv: S) -> Self {
+        Self {
+            kv: Arc::new(kv),
+            root: "/".to_string(),
+        }
+    }
+
+    /// Configure root within this backend.
+    pub fn with_root(mut self, root: &str) -> Self {
+        self.root = normalize_root(root);
+        self
+    }
+}
+
+#[async_trait]
+impl<S: Adapter> Accessor for Backend<S> {
+    type Reader = oio::Cursor;
+    type BlockingReader = oio::Cursor;
+    type Writer = KvWriter<S>;
+    type BlockingWriter = KvWriter<S>;
+    type Pager = KvPager;
+    type BlockingPager = KvPager;
+
+    fn info(&self) -> AccessorInfo {
+        let mut am: AccessorInfo = self.kv.metadata().into();
+        am.set_root(&self.root).set_hints(
+            AccessorHint::ReadStreamable | AccessorHint::ReadSeekable | AccessorHint::ImplicitDir,
+        );
+
+        am
+    }
+
+    async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        let p = build_abs_path(&self.root, path);
+        self.kv.set(&p, Value::new_dir()).await?;
+        Ok(RpCreate::default())
+    }
+
+    fn blocking_create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        let p = build_abs_path(&self.root, path);
+        self.kv.blocking_set(&p, Value::new_dir())?;
+
+        Ok(RpCreate::default())
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let p = build_abs_path(&self.root, path);
+
+        let value = match self.kv.get(&p).await? {
+            Some(value) => value,
+            None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+        };
+
+        Ok(self.apply_range(value, args.range()))
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        let p = build_abs_path(&self.root, path);
+
+        let value = match self.kv.blocking_get(&p)? {
+            Some(value) => value,
+            None => return Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+        };
+
+        Ok(self.apply_range(value, args.range()))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let p = build_abs_path(&self.root, path);
+
+        Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p, args)))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        let p = build_abs_path(&self.root, path);
+
+        Ok((RpWrite::new(), KvWriter::new(self.kv.clone(), p, args)))
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        let p = build_abs_path(&self.root, path);
+
+        if path == "/" {
+            Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
+        } else {
+            match self.kv.get(&p).await? {
+                Some(value) => Ok(RpStat::new(value.metadata)),
+                None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+            }
+        }
+    }
+
+    fn blocking_stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        let p = build_abs_path(&self.root, path);
+
+        if path == "/" {
+            Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
+        } else {
+            match self.kv.blocking_get(&p)? {
+                Some(value) => Ok(RpStat::new(value.metadata)),
+                None => Err(Error::new(ErrorKind::NotFound, "kv doesn't have this path")),
+            }
+        }
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        let p = build_abs_path(&self.root, path);
+
+        self.kv.delete(&p).await?;
+        Ok(RpDelete::default())
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        let p = build_abs_path(&self.root, path);
+
+        self.kv.blocking_delete(&p)?;
+        Ok(RpDelete::default())
+    }
+
+    async fn scan(&self, path: &str, _: OpScan) -> Result<(RpScan, Self::Pager)> {
+        let p = build_abs_path(&self.root, path);
+        let res = self.kv.scan(&p).await?;
+        let pager = KvPager::new(&self.root, res);
+
+        Ok((RpScan::default(), pager))
+    }
+
+    fn blocking_scan(&self, path: &str, _: OpScan) -> Result<(RpScan, Self::BlockingPager)> {
+        let p = build_abs_path(&self.root, path);
+        let res = self.kv.blocking_scan(&p)?;
+        let pager = KvPager::new(&self.root, res);
+
+        Ok((RpScan::default(), pager))
+    }
+}
+
+impl<S> Backend<S>
+where
+    S: Adapter,
+{
+    fn apply_range(&self, value: Value, br: BytesRange) -> (RpRead, oio::Cursor) {
+        let bs = value.value;
+        let bs = match (br.offset(), br.size()) {
+            (Some(offset), Some(size)) => {
+                let offset = (offset as usize).min(bs.len());
+                let end = offset.saturating_add(size as usize).min(bs.len());
+                bs.slice(offset..end)
+            }
+            (Some(offset), None) => bs.slice((offset as usize).min(bs.len())..),
+            (None, Some(size)) => bs.slice(bs.len().saturating_sub(size as usize)..),
+            (None, None) => bs,
+        };
+
+        let meta = value.metadata.with_content_length(bs.len() as u64);
+        (RpRead::with_metadata(meta), oio::Cursor::from(bs))
+    }
+}
+
+pub struct KvPager {
+    root: String,
+    inner: Option<Vec<(String, Metadata)>>,
+}
+
+impl KvPager {
+    fn new(root: &str, inner: Vec<(String, Metadata)>) -> Self {
+        Self {
+            root: root.to_string(),
+            inner: Some(inner),
+        }
+    }
+
+    fn inner_next_page(&mut self) -> Option<Vec<oio::Entry>> {
+        let res = self
+            .inner
+            .take()?
+            .into_iter()
+            .map(|(path, meta)| oio::Entry::new(&build_rel_path(&self.root, &path), meta))
+            .collect();
+
+        Some(res)
+    }
+}
+
+#[async_trait]
+impl oio::Page for KvPager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        Ok(self.inner_next_page())
+    }
+}
+
+impl oio::BlockingPage for KvPager {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        Ok(self.inner_next_page())
+    }
+}
+
+pub struct KvWriter<S> {
+    kv: Arc<S>,
+    path: String,
+    op: OpWrite,
+
+    buf: Option<Vec<u8>>,
+}
+
+impl<S> KvWriter<S> {
+    fn new(kv: Arc<S>, path: String, op: OpWrite) -> Self {
+        KvWriter {
+            kv,
+            path,
+            op,
+            buf: None,
+        }
+    }
+
+    fn extend_buf(&mut self, bs: Bytes) {
+        if let Some(buf) = self.buf.as_mut() {
+            buf.extend(bs);
+        } else {
+            self.buf = Some(bs.into())
+        }
+    }
+
+    /// Build the value with metadata from `OpWrite`, returns `None` if
+    /// nothing has been written.
+    fn build(&mut self) -> Option<Value> {
+        let buf = self.buf.take()?;
+
+        let mut meta = Metadata::new(EntryMode::FILE);
+        if let Some(v) = self.op.content_type() {
+            meta.set_content_type(v);
+        }
+        if let Some(v) = self.op.content_disposition() {
+            meta.set_content_disposition(v);
+        }
+        meta.set_last_modified(Utc::now());
+
+        Some(Value::new_file(meta, Bytes::from(buf)))
+    }
+}
+
+#[async_trait]
+impl<S: Adapter> oio::Write for KvWriter<S> {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.buf = Some(bs.into());
+
+        Ok(())
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.extend_buf(bs);
+
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.buf = None;
+
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if let Some(value) = self.build() {
+            self.kv.set(&self.path, value).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: Adapter> oio::BlockingWrite for KvWriter<S> {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.buf = Some(bs.into());
+
+        Ok(())
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.extend_buf(bs);
+
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.buf = None;
+
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some(value) = self.build() {
+            self.kv.blocking_set(&self.path, value)?;
+        }
+
+        Ok(())
+    }
+}
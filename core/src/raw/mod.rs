@@ -43,6 +43,7 @@ mod path;
 pub use path::build_abs_path;
 pub use path::build_rel_path;
 pub use path::build_rooted_abs_path;
+pub use path::check_path_mode;
 pub use path::get_basename;
 pub use path::get_parent;
 pub use path::normalize_path;
@@ -69,3 +70,6 @@ pub use chrono_util::*;
 
 // Expose as a pub mod to avoid confusing.
 pub mod adapters;
+
+#[cfg(feature = "tests")]
+pub mod tests;
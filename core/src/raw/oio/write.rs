@@ -39,6 +39,8 @@ pub enum WriteOperation {
     BlockingWrite,
     /// Operation for [`BlockingWrite::append`]
     BlockingAppend,
+    /// Operation for [`BlockingWrite::abort`]
+    BlockingAbort,
     /// Operation for [`BlockingWrite::close`]
     BlockingClose,
 }
@@ -67,6 +69,7 @@ impl From<WriteOperation> for &'static str {
             Close => "Writer::close",
             BlockingWrite => "BlockingWriter::write",
             BlockingAppend => "BlockingWriter::append",
+            BlockingAbort => "BlockingWriter::abort",
             BlockingClose => "BlockingWriter::close",
         }
     }
@@ -163,6 +166,9 @@ pub trait BlockingWrite: Send + Sync + 'static {
     /// Append content at tailing.
     fn append(&mut self, bs: Bytes) -> Result<()>;
 
+    /// Abort the pending writer.
+    fn abort(&mut self) -> Result<()>;
+
     /// Close the writer and make sure all data has been flushed.
     fn close(&mut self) -> Result<()>;
 }
@@ -183,6 +189,13 @@ impl BlockingWrite for () {
         ))
     }
 
+    fn abort(&mut self) -> Result<()> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "output writer doesn't support abort",
+        ))
+    }
+
     fn close(&mut self) -> Result<()> {
         Err(Error::new(
             ErrorKind::Unsupported,
@@ -202,6 +215,10 @@ impl<T: BlockingWrite + ?Sized> BlockingWrite for Box<T> {
         (**self).append(bs)
     }
 
+    fn abort(&mut self) -> Result<()> {
+        (**self).abort()
+    }
+
     fn close(&mut self) -> Result<()> {
         (**self).close()
     }
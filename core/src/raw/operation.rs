@@ -74,6 +74,37 @@ impl Operation {
     }
 }
 
+impl Operation {
+    /// Name of the span opened by tracing layers for this operation,
+    /// for example `opendal::read`.
+    #[cfg(feature = "layers-minitrace")]
+    pub(crate) fn into_span_name(self) -> &'static str {
+        match self {
+            Operation::Info => "opendal::metadata",
+            Operation::CreateDir => "opendal::create_dir",
+            Operation::Read => "opendal::read",
+            Operation::Write => "opendal::write",
+            Operation::Copy => "opendal::copy",
+            Operation::Rename => "opendal::rename",
+            Operation::Stat => "opendal::stat",
+            Operation::Delete => "opendal::delete",
+            Operation::List => "opendal::list",
+            Operation::Scan => "opendal::scan",
+            Operation::Presign => "opendal::presign",
+            Operation::Batch => "opendal::batch",
+            Operation::BlockingCreateDir => "opendal::blocking_create_dir",
+            Operation::BlockingRead => "opendal::blocking_read",
+            Operation::BlockingWrite => "opendal::blocking_write",
+            Operation::BlockingCopy => "opendal::blocking_copy",
+            Operation::BlockingMove => "opendal::blocking_rename",
+            Operation::BlockingStat => "opendal::blocking_stat",
+            Operation::BlockingDelete => "opendal::blocking_delete",
+            Operation::BlockingList => "opendal::blocking_list",
+            Operation::BlockingScan => "opendal::blocking_scan",
+        }
+    }
+}
+
 impl Display for Operation {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.into_static())
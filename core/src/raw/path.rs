@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::EntryMode;
+use crate::*;
 
 /// build_abs_path will build an absolute path with root.
 ///
@@ -222,6 +222,26 @@ pub fn validate_path(path: &str, mode: EntryMode) -> bool {
     }
 }
 
+/// Check given path is match with the entry mode returned by services.
+///
+/// OpenDAL requires that path ends with `/` is always a directory and path
+/// without it is always a file. Services that ignore the trailing slash may
+/// return a file for `abc/` or a directory for `abc`, we will treat them as
+/// not found.
+///
+/// Entries with [`EntryMode::Unknown`] are always allowed.
+pub fn check_path_mode(path: &str, mode: EntryMode) -> Result<()> {
+    if mode == EntryMode::Unknown || validate_path(path, mode) {
+        return Ok(());
+    }
+
+    Err(
+        Error::new(ErrorKind::NotFound, "entry mode is not match with its path")
+            .with_context("path", path)
+            .with_context("mode", mode.to_string()),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +398,24 @@ mod tests {
             assert_eq!(actual, expect, "{name}")
         }
     }
+
+    #[test]
+    fn test_check_path_mode() {
+        let cases = vec![
+            ("file", "abc", EntryMode::FILE, true),
+            ("dir", "abc/", EntryMode::DIR, true),
+            ("root", "/", EntryMode::DIR, true),
+            ("file with slash", "abc/", EntryMode::FILE, false),
+            ("dir without slash", "abc", EntryMode::DIR, false),
+            ("unknown", "abc", EntryMode::Unknown, true),
+        ];
+
+        for (name, path, mode, expect) in cases {
+            let res = check_path_mode(path, mode);
+            assert_eq!(res.is_ok(), expect, "{name}");
+            if let Err(err) = res {
+                assert_eq!(err.kind(), ErrorKind::NotFound, "{name}");
+            }
+        }
+    }
 }
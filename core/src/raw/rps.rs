@@ -15,7 +15,13 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::BTreeMap;
+
 use http::Request;
+use serde::ser::Error as _;
+use serde::ser::SerializeStruct;
+use serde::Serialize;
+use serde::Serializer;
 
 use crate::*;
 
@@ -85,18 +91,69 @@ impl PresignedRequest {
     pub fn header(&self) -> &http::HeaderMap {
         &self.headers
     }
+
+    /// Convert into a [`http::Request`] with given body.
+    pub fn into_request<B>(self, body: B) -> Request<B> {
+        let mut req = Request::new(body);
+        *req.method_mut() = self.method;
+        *req.uri_mut() = self.uri;
+        *req.headers_mut() = self.headers;
+        req
+    }
+
+    /// Build a [`reqwest::RequestBuilder`] with given client, users can
+    /// set body and send it directly.
+    pub fn to_reqwest(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .request(self.method.clone(), self.uri.to_string())
+            .headers(self.headers.clone())
+    }
+
+    /// Check that all header values are valid utf-8 strings, so that this
+    /// request can be serialized and sent by other http stacks.
+    pub(crate) fn check_headers(&self) -> Result<()> {
+        for (k, v) in self.headers.iter() {
+            v.to_str().map_err(|err| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "presigned header value is not valid utf-8 string",
+                )
+                .with_context("header", k.as_str())
+                .set_source(err)
+            })?;
+        }
+        Ok(())
+    }
 }
 
 impl<T: Default> From<PresignedRequest> for Request<T> {
     fn from(v: PresignedRequest) -> Self {
-        let mut builder = Request::builder().method(v.method).uri(v.uri);
+        v.into_request(T::default())
+    }
+}
 
-        let headers = builder.headers_mut().expect("header map must be valid");
-        headers.extend(v.headers);
+/// Serialize into `{"method": "GET", "uri": "...", "headers": {...}}`.
+///
+/// Multiple values of the same header will be joined by `, `.
+impl Serialize for PresignedRequest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut headers: BTreeMap<&str, String> = BTreeMap::new();
+        for (k, v) in self.headers.iter() {
+            let v = v.to_str().map_err(S::Error::custom)?;
+            headers
+                .entry(k.as_str())
+                .and_modify(|s| {
+                    s.push_str(", ");
+                    s.push_str(v);
+                })
+                .or_insert_with(|| v.to_string());
+        }
 
-        builder
-            .body(T::default())
-            .expect("request must build succeed")
+        let mut s = serializer.serialize_struct("PresignedRequest", 3)?;
+        s.serialize_field("method", self.method.as_str())?;
+        s.serialize_field("uri", &self.uri.to_string())?;
+        s.serialize_field("headers", &headers)?;
+        s.end()
     }
 }
 
@@ -176,6 +233,11 @@ impl RpStat {
         RpStat { meta }
     }
 
+    /// Get a ref of metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.meta
+    }
+
     /// Operate on inner metadata.
     pub fn map_metadata(mut self, f: impl FnOnce(Metadata) -> Metadata) -> Self {
         self.meta = f(self.meta);
@@ -258,4 +320,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_presigned_request_serialize() -> Result<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse()?);
+        headers.append("x-amz-meta-tag", "a".parse()?);
+        headers.append("x-amz-meta-tag", "b".parse()?);
+        let pr = PresignedRequest::new(
+            Method::PUT,
+            Uri::from_static("https://opendal.apache.org/path/to/file?X-Amz-Signature=abc"),
+            headers,
+        );
+        pr.check_headers()?;
+
+        let v = serde_json::to_value(&pr)?;
+        assert_eq!(
+            v,
+            serde_json::json!({
+                "method": "PUT",
+                "uri": "https://opendal.apache.org/path/to/file?X-Amz-Signature=abc",
+                "headers": {
+                    "content-type": "application/json",
+                    "x-amz-meta-tag": "a, b",
+                },
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_presigned_request_invalid_header() -> Result<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-invalid", http::HeaderValue::from_bytes(&[0xfa])?);
+        let pr = PresignedRequest::new(
+            Method::GET,
+            Uri::from_static("https://opendal.apache.org/path/to/file"),
+            headers,
+        );
+
+        let err = pr.check_headers().expect_err("must fail");
+        assert!(err.to_string().contains("x-invalid"));
+        assert!(serde_json::to_string(&pr).is_err());
+
+        Ok(())
+    }
 }
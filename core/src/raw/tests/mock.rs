@@ -0,0 +1,692 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use flagset::FlagSet;
+
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// MockAccessor is an [`Accessor`] whose behavior is scripted by tests,
+/// which is useful to test layers without a real service.
+///
+/// Replies are pushed per [`Operation`] via [`MockAccessor::push`] and
+/// consumed in order, every call will be recorded so that tests can assert
+/// on them later.
+///
+/// # Notes
+///
+/// - Calls without a scripted reply will fail with [`ErrorKind::Unexpected`].
+/// - Replies that don't match the operation will fail with
+///   [`ErrorKind::Unexpected`] too, for example reply a `stat` with
+///   [`MockReply::read`].
+/// - Blocking operations share the same replies with their async
+///   versions, but are recorded with blocking [`Operation`] like
+///   [`Operation::BlockingStat`].
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::RetryLayer;
+/// use opendal::raw::tests::MockAccessor;
+/// use opendal::raw::tests::MockReply;
+/// use opendal::raw::Operation;
+/// use opendal::EntryMode;
+/// use opendal::Error;
+/// use opendal::ErrorKind;
+/// use opendal::Metadata;
+/// use opendal::OperatorBuilder;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mock = MockAccessor::new();
+///     mock.push(
+///         Operation::Stat,
+///         MockReply::err(Error::new(ErrorKind::Unexpected, "retryable").set_temporary()),
+///     )
+///     .push(
+///         Operation::Stat,
+///         MockReply::stat(Metadata::new(EntryMode::FILE).with_content_length(13)),
+///     );
+///
+///     let op = OperatorBuilder::new(mock.clone())
+///         .layer(RetryLayer::new())
+///         .finish();
+///     let meta = op.stat("hello").await?;
+///     assert_eq!(meta.content_length(), 13);
+///
+///     mock.assert_called_times(Operation::Stat, 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MockAccessor {
+    info: AccessorInfo,
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    replies: HashMap<Operation, VecDeque<MockReply>>,
+    calls: Vec<MockCall>,
+    written: HashMap<String, Vec<u8>>,
+    aborted: HashSet<String>,
+}
+
+impl Debug for MockAccessor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockAccessor")
+            .field("info", &self.info)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for MockAccessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockAccessor {
+    /// Create a new mock accessor which supports read, write, copy,
+    /// rename, list, presign, batch and blocking, and whose readers are
+    /// seekable and streamable.
+    pub fn new() -> Self {
+        let mut info = AccessorInfo::default();
+        info.set_scheme(Scheme::Custom("mock"))
+            .set_root("/")
+            .set_name("mock")
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Copy
+                    | AccessorCapability::Rename
+                    | AccessorCapability::List
+                    | AccessorCapability::Presign
+                    | AccessorCapability::Batch
+                    | AccessorCapability::Blocking,
+            )
+            .set_hints(AccessorHint::ReadSeekable | AccessorHint::ReadStreamable);
+
+        Self {
+            info,
+            state: Arc::default(),
+        }
+    }
+
+    /// Replace the capabilities of this mock accessor.
+    pub fn with_capabilities(
+        mut self,
+        capabilities: impl Into<FlagSet<AccessorCapability>>,
+    ) -> Self {
+        self.info.set_capabilities(capabilities);
+        self
+    }
+
+    /// Replace the hints of this mock accessor.
+    pub fn with_hints(mut self, hints: impl Into<FlagSet<AccessorHint>>) -> Self {
+        self.info.set_hints(hints);
+        self
+    }
+
+    /// Set the scheme reported by this mock accessor.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.info.set_scheme(scheme);
+        self
+    }
+
+    /// Push a reply for the next call of given operation.
+    ///
+    /// Blocking operations should use their async versions like
+    /// [`Operation::Stat`] for [`Operation::BlockingStat`].
+    pub fn push(&self, op: Operation, reply: MockReply) -> &Self {
+        self.lock().replies.entry(op).or_default().push_back(reply);
+        self
+    }
+
+    /// Get all recorded calls in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Get the count of calls of given operation.
+    pub fn count(&self, op: Operation) -> usize {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|c| c.operation == op)
+            .count()
+    }
+
+    /// Get the content written into given path by closed writers.
+    pub fn written(&self, path: &str) -> Option<Bytes> {
+        self.lock()
+            .written
+            .get(path)
+            .map(|bs| Bytes::from(bs.clone()))
+    }
+
+    /// Check whether a writer on given path has been aborted.
+    pub fn aborted(&self, path: &str) -> bool {
+        self.lock().aborted.contains(path)
+    }
+
+    /// Assert that given operation has been called on given path.
+    pub fn assert_called(&self, op: Operation, path: &str) {
+        self.assert_called_with(op, path, |_| true)
+    }
+
+    /// Assert that given operation has been called on given path with
+    /// args that `f` returns `true`.
+    ///
+    /// ```
+    /// # use opendal::raw::tests::MockAccessor;
+    /// # use opendal::raw::tests::MockArgs;
+    /// # use opendal::raw::Operation;
+    /// # fn test(mock: MockAccessor) {
+    /// mock.assert_called_with(Operation::Read, "hello", |args| {
+    ///     matches!(args, MockArgs::Read(op) if op.if_match() == Some("etag"))
+    /// });
+    /// # }
+    /// ```
+    pub fn assert_called_with(&self, op: Operation, path: &str, f: impl Fn(&MockArgs) -> bool) {
+        let calls = self.calls();
+        assert!(
+            calls
+                .iter()
+                .any(|c| c.operation == op && c.path == path && f(&c.args)),
+            "operation {op} on path {path} with expected args is not called, recorded calls: {calls:#?}"
+        );
+    }
+
+    /// Assert that given operation has been called exactly `n` times.
+    pub fn assert_called_times(&self, op: Operation, n: usize) {
+        let actual = self.count(op);
+        assert_eq!(
+            actual,
+            n,
+            "operation {op} is expected to be called {n} times, recorded calls: {:#?}",
+            self.calls()
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        // Don't poison other assertions if a test panics with lock held.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Record the call and pop the reply of given operation.
+    fn call(&self, op: Operation, reply_op: Operation, path: &str, args: MockArgs) -> MockReply {
+        let mut state = self.lock();
+        state.calls.push(MockCall {
+            operation: op,
+            path: path.to_string(),
+            args,
+        });
+
+        state
+            .replies
+            .get_mut(&reply_op)
+            .and_then(|v| v.pop_front())
+            .unwrap_or_else(|| {
+                MockReply::err(
+                    Error::new(ErrorKind::Unexpected, "mock reply is not set")
+                        .with_operation(op)
+                        .with_context("path", path),
+                )
+            })
+    }
+
+    async fn reply(&self, op: Operation, path: &str, args: MockArgs) -> Result<MockValue> {
+        let reply = self.call(op, op, path, args);
+        if let Some(delay) = reply.delay {
+            tokio::time::sleep(delay).await;
+        }
+        reply.value
+    }
+
+    fn blocking_reply(
+        &self,
+        op: Operation,
+        reply_op: Operation,
+        path: &str,
+        args: MockArgs,
+    ) -> Result<MockValue> {
+        let reply = self.call(op, reply_op, path, args);
+        if let Some(delay) = reply.delay {
+            thread::sleep(delay);
+        }
+        reply.value
+    }
+}
+
+/// MockReply is the scripted reply of a call to [`MockAccessor`].
+pub struct MockReply {
+    value: Result<MockValue>,
+    delay: Option<Duration>,
+}
+
+enum MockValue {
+    Empty,
+    Read(Bytes),
+    Stat(Metadata),
+    List(Vec<oio::Entry>),
+    Presign(PresignedRequest),
+    Batch(Vec<(String, Result<BatchedReply>)>),
+}
+
+impl MockValue {
+    fn name(&self) -> &'static str {
+        match self {
+            MockValue::Empty => "ok",
+            MockValue::Read(_) => "read",
+            MockValue::Stat(_) => "stat",
+            MockValue::List(_) => "list",
+            MockValue::Presign(_) => "presign",
+            MockValue::Batch(_) => "batch",
+        }
+    }
+}
+
+impl MockReply {
+    /// Reply with success for `create_dir`, `write`, `copy`, `rename`,
+    /// `delete` and `batch`.
+    ///
+    /// Batch replied with `ok` will succeed on every path.
+    pub fn ok() -> Self {
+        Self::new(Ok(MockValue::Empty))
+    }
+
+    /// Reply `read` with given content.
+    pub fn read(bs: impl Into<Bytes>) -> Self {
+        Self::new(Ok(MockValue::Read(bs.into())))
+    }
+
+    /// Reply `stat` with given metadata.
+    pub fn stat(meta: Metadata) -> Self {
+        Self::new(Ok(MockValue::Stat(meta)))
+    }
+
+    /// Reply `list` with given entries in a single page.
+    pub fn list(entries: Vec<oio::Entry>) -> Self {
+        Self::new(Ok(MockValue::List(entries)))
+    }
+
+    /// Reply `presign` with given request.
+    pub fn presign(req: PresignedRequest) -> Self {
+        Self::new(Ok(MockValue::Presign(req)))
+    }
+
+    /// Reply `batch` with given results of every path.
+    pub fn batch(results: Vec<(String, Result<BatchedReply>)>) -> Self {
+        Self::new(Ok(MockValue::Batch(results)))
+    }
+
+    /// Reply any operation with given error.
+    pub fn err(err: Error) -> Self {
+        Self::new(Err(err))
+    }
+
+    /// Delay the reply for given duration.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    fn new(value: Result<MockValue>) -> Self {
+        Self { value, delay: None }
+    }
+}
+
+/// MockCall is a call recorded by [`MockAccessor`].
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    /// Operation of this call.
+    pub operation: Operation,
+    /// Path of this call, `from` for copy and rename, empty for batch.
+    pub path: String,
+    /// Args of this call.
+    pub args: MockArgs,
+}
+
+/// MockArgs is the args of a call recorded by [`MockAccessor`].
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum MockArgs {
+    CreateDir(OpCreate),
+    Read(OpRead),
+    Write(OpWrite),
+    Copy { to: String, args: OpCopy },
+    Rename { to: String, args: OpRename },
+    Stat(OpStat),
+    Delete(OpDelete),
+    List(OpList),
+    Presign(OpPresign),
+    Batch(OpBatch),
+}
+
+/// Build the error for replies that don't match the operation.
+fn mismatch(op: Operation, path: &str, value: MockValue) -> Error {
+    Error::new(ErrorKind::Unexpected, "mock reply doesn't match operation")
+        .with_operation(op)
+        .with_context("path", path)
+        .with_context("reply", value.name())
+}
+
+#[async_trait]
+impl Accessor for MockAccessor {
+    type Reader = oio::Cursor;
+    type BlockingReader = oio::Cursor;
+    type Writer = MockWriter;
+    type BlockingWriter = MockWriter;
+    type Pager = MockPager;
+    type BlockingPager = MockPager;
+
+    fn info(&self) -> AccessorInfo {
+        self.info.clone()
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        let op = Operation::CreateDir;
+        match self.reply(op, path, MockArgs::CreateDir(args)).await? {
+            MockValue::Empty => Ok(RpCreate::default()),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let op = Operation::Read;
+        match self.reply(op, path, MockArgs::Read(args)).await? {
+            MockValue::Read(bs) => Ok((RpRead::new(bs.len() as u64), bs.into())),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let op = Operation::Write;
+        match self.reply(op, path, MockArgs::Write(args)).await? {
+            MockValue::Empty => Ok((RpWrite::new(), MockWriter::new(self, path))),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let op = Operation::Copy;
+        let args = MockArgs::Copy {
+            to: to.to_string(),
+            args,
+        };
+        match self.reply(op, from, args).await? {
+            MockValue::Empty => Ok(RpCopy::new()),
+            v => Err(mismatch(op, from, v)),
+        }
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let op = Operation::Rename;
+        let args = MockArgs::Rename {
+            to: to.to_string(),
+            args,
+        };
+        match self.reply(op, from, args).await? {
+            MockValue::Empty => Ok(RpRename::new()),
+            v => Err(mismatch(op, from, v)),
+        }
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let op = Operation::Stat;
+        match self.reply(op, path, MockArgs::Stat(args)).await? {
+            MockValue::Stat(meta) => Ok(RpStat::new(meta)),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let op = Operation::Delete;
+        match self.reply(op, path, MockArgs::Delete(args)).await? {
+            MockValue::Empty => Ok(RpDelete::default()),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let op = Operation::List;
+        match self.reply(op, path, MockArgs::List(args)).await? {
+            MockValue::List(entries) => Ok((RpList::default(), MockPager::new(entries))),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        let op = Operation::Presign;
+        match self.reply(op, path, MockArgs::Presign(args)).await? {
+            MockValue::Presign(req) => Ok(RpPresign::new(req)),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
+        let op = Operation::Batch;
+        let paths: Vec<_> = args.operation().iter().map(|(p, _)| p.clone()).collect();
+        match self.reply(op, "", MockArgs::Batch(args)).await? {
+            MockValue::Empty => Ok(RpBatch::new(
+                paths
+                    .into_iter()
+                    .map(|p| (p, Ok(RpDelete::default().into())))
+                    .collect(),
+            )),
+            MockValue::Batch(results) => Ok(RpBatch::new(results)),
+            v => Err(mismatch(op, "", v)),
+        }
+    }
+
+    fn blocking_create_dir(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        let op = Operation::BlockingCreateDir;
+        let args = MockArgs::CreateDir(args);
+        match self.blocking_reply(op, Operation::CreateDir, path, args)? {
+            MockValue::Empty => Ok(RpCreate::default()),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::BlockingReader)> {
+        let op = Operation::BlockingRead;
+        match self.blocking_reply(op, Operation::Read, path, MockArgs::Read(args))? {
+            MockValue::Read(bs) => Ok((RpRead::new(bs.len() as u64), bs.into())),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        let op = Operation::BlockingWrite;
+        match self.blocking_reply(op, Operation::Write, path, MockArgs::Write(args))? {
+            MockValue::Empty => Ok((RpWrite::new(), MockWriter::new(self, path))),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    fn blocking_copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        let op = Operation::BlockingCopy;
+        let args = MockArgs::Copy {
+            to: to.to_string(),
+            args,
+        };
+        match self.blocking_reply(op, Operation::Copy, from, args)? {
+            MockValue::Empty => Ok(RpCopy::new()),
+            v => Err(mismatch(op, from, v)),
+        }
+    }
+
+    fn blocking_rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        let op = Operation::BlockingMove;
+        let args = MockArgs::Rename {
+            to: to.to_string(),
+            args,
+        };
+        match self.blocking_reply(op, Operation::Rename, from, args)? {
+            MockValue::Empty => Ok(RpRename::new()),
+            v => Err(mismatch(op, from, v)),
+        }
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        let op = Operation::BlockingStat;
+        match self.blocking_reply(op, Operation::Stat, path, MockArgs::Stat(args))? {
+            MockValue::Stat(meta) => Ok(RpStat::new(meta)),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let op = Operation::BlockingDelete;
+        let args = MockArgs::Delete(args);
+        match self.blocking_reply(op, Operation::Delete, path, args)? {
+            MockValue::Empty => Ok(RpDelete::default()),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, Self::BlockingPager)> {
+        let op = Operation::BlockingList;
+        match self.blocking_reply(op, Operation::List, path, MockArgs::List(args))? {
+            MockValue::List(entries) => Ok((RpList::default(), MockPager::new(entries))),
+            v => Err(mismatch(op, path, v)),
+        }
+    }
+}
+
+/// MockWriter buffers all written content, and stores it into
+/// [`MockAccessor`] while closed.
+pub struct MockWriter {
+    state: Arc<Mutex<MockState>>,
+    path: String,
+    buf: Vec<u8>,
+}
+
+impl MockWriter {
+    fn new(acc: &MockAccessor, path: &str) -> Self {
+        Self {
+            state: acc.state.clone(),
+            path: path.to_string(),
+            buf: Vec::new(),
+        }
+    }
+
+    fn store(&mut self) {
+        let buf = std::mem::take(&mut self.buf);
+        self.state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .written
+            .insert(self.path.clone(), buf);
+    }
+
+    fn mark_aborted(&mut self) {
+        self.buf.clear();
+        self.state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .aborted
+            .insert(self.path.clone());
+    }
+}
+
+#[async_trait]
+impl oio::Write for MockWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.buf.extend_from_slice(&bs);
+        Ok(())
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.buf.extend_from_slice(&bs);
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.mark_aborted();
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.store();
+        Ok(())
+    }
+}
+
+impl oio::BlockingWrite for MockWriter {
+    fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.buf.extend_from_slice(&bs);
+        Ok(())
+    }
+
+    fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.buf.extend_from_slice(&bs);
+        Ok(())
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.mark_aborted();
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.store();
+        Ok(())
+    }
+}
+
+/// MockPager returns all entries in a single page.
+pub struct MockPager {
+    entries: Option<Vec<oio::Entry>>,
+}
+
+impl MockPager {
+    fn new(entries: Vec<oio::Entry>) -> Self {
+        Self {
+            entries: Some(entries),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Page for MockPager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        Ok(self.entries.take())
+    }
+}
+
+impl oio::BlockingPage for MockPager {
+    fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        Ok(self.entries.take())
+    }
+}
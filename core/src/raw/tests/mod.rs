@@ -0,0 +1,396 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Behavior tests that every service should pass.
+//!
+//! Built-in services run these tests under `tests/behavior`, third-party
+//! [`Accessor`][crate::raw::Accessor] implementations can run the same
+//! tests against their own [`Operator`] via [`behavior_test`] and
+//! [`blocking_behavior_test`].
+//!
+//! Tests are picked by the capabilities declared in [`OperatorInfo`], so
+//! operations that are not supported will be skipped instead of failed.
+//!
+//! Layers can be tested without a real service via [`MockAccessor`], whose
+//! replies, errors and delays are scripted by tests.
+//!
+//! # Notes
+//!
+//! - Only available with feature `tests` enabled.
+//! - Tests will write and delete random files under the operator's root,
+//!   please make sure the root is empty and not shared with others.
+//! - Tests panic while assertions failed, just like `#[test]` functions.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use anyhow::Result;
+//! use opendal::raw::tests::behavior_test;
+//! use opendal::services::Memory;
+//! use opendal::Operator;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let op = Operator::new(Memory::default())?.finish();
+//!
+//!     behavior_test(op).await
+//! }
+//! ```
+
+use anyhow::Context;
+use anyhow::Result;
+use log::debug;
+use log::warn;
+
+use crate::Operator;
+use crate::OperatorInfo;
+
+mod utils;
+pub use utils::*;
+
+mod mock;
+pub use mock::*;
+
+/// Behavior tests for services that can read, write, copy and blocking.
+pub mod blocking_copy;
+/// Behavior tests for services that can read, write, list and blocking.
+pub mod blocking_list;
+/// Behavior tests for read-only services that can blocking.
+pub mod blocking_read;
+/// Behavior tests for services that can read, write, rename and blocking.
+pub mod blocking_rename;
+/// Behavior tests for services that can read, write and blocking.
+pub mod blocking_write;
+/// Behavior tests for services that can read, write and copy.
+pub mod copy;
+/// Behavior tests for services that can read, write and list.
+pub mod list;
+/// Behavior tests for list-only services.
+pub mod list_only;
+/// Behavior tests for services that can read, write and presign.
+pub mod presign;
+/// Behavior tests for read-only services.
+pub mod read_only;
+/// Behavior tests for services that can read, write and rename.
+pub mod rename;
+/// Behavior tests for services that can read and write.
+pub mod write;
+
+/// Run tests of `$module` one by one, stop at the first failed test.
+macro_rules! run_tests {
+    ($op:expr, $module:ident, $($test:ident),* $(,)?) => {
+        $(
+            debug!(
+                "run behavior test {}::{}",
+                stringify!($module),
+                stringify!($test)
+            );
+            $module::$test($op.clone())
+                .await
+                .with_context(|| {
+                    format!(
+                        "behavior test {}::{} failed",
+                        stringify!($module),
+                        stringify!($test)
+                    )
+                })?;
+        )*
+    };
+}
+
+/// Blocking version of `run_tests`.
+macro_rules! blocking_run_tests {
+    ($op:expr, $module:ident, $($test:ident),* $(,)?) => {
+        $(
+            debug!(
+                "run behavior test {}::{}",
+                stringify!($module),
+                stringify!($test)
+            );
+            $module::$test($op.clone())
+                .with_context(|| {
+                    format!(
+                        "behavior test {}::{} failed",
+                        stringify!($module),
+                        stringify!($test)
+                    )
+                })?;
+        )*
+    };
+}
+
+/// Run all async behavior tests that the operator supports.
+///
+/// Tests for unsupported capabilities will be skipped with a warning log.
+pub async fn behavior_test(op: Operator) -> Result<()> {
+    let info = op.info();
+
+    if info.can_read() && !info.can_write() {
+        run_tests!(
+            op,
+            read_only,
+            test_stat,
+            test_stat_special_chars,
+            test_stat_not_cleaned_path,
+            test_stat_not_exist,
+            test_stat_root,
+            test_read_full,
+            test_read_full_with_special_chars,
+            test_read_range,
+            test_reader_range,
+            test_reader_from,
+            test_reader_tail,
+            test_read_not_exist,
+            test_read_with_dir_path,
+        );
+    } else {
+        skip(&info, "read_only");
+    }
+
+    if info.can_read() && info.can_write() {
+        run_tests!(
+            op,
+            write,
+            test_create_dir,
+            test_create_dir_existing,
+            test_write,
+            test_write_with_dir_path,
+            test_write_with_special_chars,
+            test_stat,
+            test_stat_dir,
+            test_stat_with_special_chars,
+            test_stat_not_cleaned_path,
+            test_stat_not_exist,
+            test_stat_file_with_dir_path,
+            test_stat_dir_with_file_path,
+            test_stat_nested_parent_dir,
+            test_stat_root,
+            test_read_with_progress,
+            test_read_full,
+            test_read_into,
+            test_download,
+            test_download_empty,
+            test_checksum,
+            test_read_range,
+            test_read_large_range,
+            test_reader_range,
+            test_reader_from,
+            test_reader_tail,
+            test_read_not_exist,
+            test_fuzz_range_reader,
+            test_fuzz_offset_reader,
+            test_fuzz_part_reader,
+            test_read_with_dir_path,
+            test_read_dir_with_file_path,
+            test_read_with_special_chars,
+            test_delete,
+            test_delete_empty_dir,
+            test_delete_with_special_chars,
+            test_delete_file_with_dir_path,
+            test_delete_dir_with_file_path,
+            test_writer_with_progress,
+            test_delete_not_existing,
+            test_delete_stream,
+            test_append,
+            test_abort_writer,
+        );
+    } else {
+        skip(&info, "write");
+    }
+
+    if info.can_read() && info.can_write() && info.can_copy() {
+        run_tests!(
+            op,
+            copy,
+            test_copy,
+            test_copy_non_existing_source,
+            test_copy_source_dir,
+            test_copy_target_dir,
+            test_copy_self,
+            test_copy_nested,
+            test_copy_overwrite,
+        );
+    } else {
+        skip(&info, "copy");
+    }
+
+    if info.can_read() && info.can_write() && info.can_rename() {
+        run_tests!(
+            op,
+            rename,
+            test_rename,
+            test_rename_non_existing_source,
+            test_rename_source_dir,
+            test_rename_target_dir,
+            test_rename_self,
+            test_rename_nested,
+            test_rename_overwrite,
+        );
+    } else {
+        skip(&info, "rename");
+    }
+
+    if info.can_read() && info.can_write() && (info.can_list() || info.can_scan()) {
+        run_tests!(
+            op,
+            list,
+            test_check,
+            test_list_dir,
+            test_list_rich_dir,
+            test_list_empty_dir,
+            test_list_non_exist_dir,
+            test_list_sub_dir,
+            test_list_nested_dir,
+            test_list_dir_with_file_path,
+            test_list_file_with_dir_path,
+            test_scan,
+            test_scan_root,
+            test_remove_all,
+            test_remove_all_with_progress,
+            test_remove_all_with_wide_dirs,
+            test_remove_all_without_trailing_slash,
+        );
+    } else {
+        skip(&info, "list");
+    }
+
+    if info.can_read() && info.can_write() && info.can_presign() {
+        run_tests!(
+            op,
+            presign,
+            test_presign_write,
+            test_presign_read,
+            test_presign_stat,
+        );
+    } else {
+        skip(&info, "presign");
+    }
+
+    if info.can_list() && !info.can_write() {
+        run_tests!(op, list_only, test_list);
+    } else {
+        skip(&info, "list_only");
+    }
+
+    Ok(())
+}
+
+/// Run all blocking behavior tests that the operator supports.
+///
+/// This function must not be called inside an async runtime, since
+/// blocking operations may block on async ones.
+///
+/// Tests for unsupported capabilities will be skipped with a warning log.
+pub fn blocking_behavior_test(op: Operator) -> Result<()> {
+    let info = op.info();
+    if !info.can_blocking() {
+        skip(&info, "blocking");
+        return Ok(());
+    }
+    let op = op.blocking();
+
+    if info.can_read() && !info.can_write() {
+        blocking_run_tests!(
+            op,
+            blocking_read,
+            test_stat,
+            test_stat_special_chars,
+            test_stat_not_exist,
+            test_read_full,
+            test_read_range,
+            test_read_not_exist,
+        );
+    } else {
+        skip(&info, "blocking_read");
+    }
+
+    if info.can_read() && info.can_write() {
+        blocking_run_tests!(
+            op,
+            blocking_write,
+            test_create_dir,
+            test_create_dir_existing,
+            test_write,
+            test_write_with_dir_path,
+            test_write_with_special_chars,
+            test_stat,
+            test_stat_dir,
+            test_stat_with_special_chars,
+            test_stat_not_exist,
+            test_read_full,
+            test_read_range,
+            test_read_large_range,
+            test_read_not_exist,
+            test_fuzz_range_reader,
+            test_fuzz_offset_reader,
+            test_fuzz_part_reader,
+            test_delete,
+        );
+    } else {
+        skip(&info, "blocking_write");
+    }
+
+    if info.can_read() && info.can_write() && info.can_copy() {
+        blocking_run_tests!(
+            op,
+            blocking_copy,
+            test_copy,
+            test_copy_non_existing_source,
+            test_copy_source_dir,
+            test_copy_target_dir,
+            test_copy_self,
+            test_copy_nested,
+            test_copy_overwrite,
+        );
+    } else {
+        skip(&info, "blocking_copy");
+    }
+
+    if info.can_read() && info.can_write() && info.can_rename() {
+        blocking_run_tests!(
+            op,
+            blocking_rename,
+            test_rename,
+            test_rename_non_existing_source,
+            test_rename_source_dir,
+            test_rename_target_dir,
+            test_rename_self,
+            test_rename_nested,
+            test_rename_overwrite,
+        );
+    } else {
+        skip(&info, "blocking_rename");
+    }
+
+    if info.can_read() && info.can_write() && (info.can_list() || info.can_scan()) {
+        blocking_run_tests!(
+            op,
+            blocking_list,
+            test_list_dir,
+            test_list_non_exist_dir,
+            test_scan,
+        );
+    } else {
+        skip(&info, "blocking_list");
+    }
+
+    Ok(())
+}
+
+fn skip(info: &OperatorInfo, tests: &str) {
+    warn!("service {} doesn't support {tests}, ignored", info.scheme());
+}
@@ -29,6 +29,7 @@ use reqsign::AzureStorageLoader;
 use reqsign::AzureStorageSigner;
 
 use super::batch::parse_batch_delete_response;
+use super::core::parse_into_azblob_metadata;
 use super::error::parse_error;
 use super::pager::AzblobPager;
 use super::writer::AzblobWriter;
@@ -50,6 +51,9 @@ const KNOWN_AZBLOB_ENDPOINT_SUFFIX: &[&str] = &[
 
 const AZBLOB_BATCH_LIMIT: usize = 256;
 
+/// Default size of blocks staged by writer.
+const DEFAULT_BLOCK_SIZE: usize = 8 * 1024 * 1024;
+
 /// Azure Storage Blob services support.
 ///
 /// # Capabilities
@@ -58,10 +62,11 @@ const AZBLOB_BATCH_LIMIT: usize = 256;
 ///
 /// - [x] read
 /// - [x] write
+/// - [x] append
 /// - [x] copy
 /// - [x] list
 /// - [x] scan
-/// - [ ] presign
+/// - [x] presign
 /// - [ ] blocking
 ///
 /// # Configuration
@@ -71,9 +76,43 @@ const AZBLOB_BATCH_LIMIT: usize = 256;
 /// - `endpoint`: Set the endpoint for backend.
 /// - `account_name`: Set the account_name for backend.
 /// - `account_key`: Set the account_key for backend.
+/// - `sas_token`: Set the sas_token for backend, conflicts with `account_key`.
+/// - `block_size`: Set the size of blocks staged by writer, default to 8 MiB.
+/// - `block_concurrency`: Set the concurrency of staging blocks, default to 1.
+/// - `enable_append_blob`: Use append blob for writer instead of block blob.
 ///
 /// Refer to public API docs for more information.
 ///
+/// # Writer
+///
+/// Data appended via [`Writer`] will be staged in blocks of `block_size`
+/// via `Put Block` (up to `block_concurrency` blocks in parallel), and
+/// committed via `Put Block List` while closing. Aborting the writer will
+/// simply not commit the staged blocks, which will be expired by azblob.
+/// Small writes that fit in one block still use `Put Blob` directly.
+///
+/// If `enable_append_blob` is set, writer will create an append blob at the
+/// first append and commit following data via the `Append Block` API
+/// instead, which is suitable for log shipping. Appending to an existing
+/// block blob concurrently will fail with a clear error.
+///
+/// # Presign
+///
+/// Presigned requests are signed via service SAS with `account_key`, so
+/// they can be sent by any http client without credential until expired.
+/// Presign with `sas_token` or user delegation SAS is not supported yet.
+///
+/// # Versions and snapshots
+///
+/// `read`, `stat` and `delete` accept a version via `with_version`, which
+/// will be sent as `versionid`. Use `snapshot:<timestamp>` to address a blob
+/// snapshot instead. Deleting with a version only removes that version (or
+/// snapshot) and keeps the base blob. `x-ms-version-id` returned by `read`
+/// and `stat` will be exposed as [`Metadata::version`].
+///
+/// Writes are committed while closing the writer, so the version id
+/// returned by azblob for writes can't be exposed in `RpWrite` yet.
+///
 /// # Example
 ///
 /// This example works on [Azurite](https://github.com/Azure/Azurite) for local developments.
@@ -134,6 +173,9 @@ pub struct AzblobBuilder {
     account_name: Option<String>,
     account_key: Option<String>,
     sas_token: Option<String>,
+    block_size: Option<usize>,
+    block_concurrency: Option<usize>,
+    enable_append_blob: bool,
     http_client: Option<HttpClient>,
 }
 
@@ -154,6 +196,9 @@ impl Debug for AzblobBuilder {
         if self.sas_token.is_some() {
             ds.field("sas_token", &"<redacted>");
         }
+        ds.field("block_size", &self.block_size);
+        ds.field("block_concurrency", &self.block_concurrency);
+        ds.field("enable_append_blob", &self.enable_append_blob);
 
         ds.finish()
     }
@@ -222,9 +267,16 @@ impl AzblobBuilder {
     /// - If sas_token is set, we will take user's input first.
     /// - If not, we will try to load it from environment.
     ///
+    /// SharedKey signing will be skipped while sas_token is set, and the
+    /// SAS query parameters will be appended to every request. So
+    /// sas_token can't be used together with `account_key`.
+    ///
     /// See [Grant limited access to Azure Storage resources using shared access signatures (SAS)](https://learn.microsoft.com/en-us/azure/storage/common/storage-sas-overview)
     /// for more info.
     pub fn sas_token(&mut self, sas_token: &str) -> &mut Self {
+        // Allow users to input the query string copied from azure portal
+        // directly, like `?sv=2021-06-08&...`
+        let sas_token = sas_token.trim_start_matches('?');
         if !sas_token.is_empty() {
             self.sas_token = Some(sas_token.to_string());
         }
@@ -232,6 +284,35 @@ impl AzblobBuilder {
         self
     }
 
+    /// Set the size of blocks staged by writer.
+    ///
+    /// Default to 8 MiB, azblob allows up to 4000 MiB for a block.
+    pub fn block_size(&mut self, block_size: usize) -> &mut Self {
+        if block_size > 0 {
+            self.block_size = Some(block_size);
+        }
+
+        self
+    }
+
+    /// Set the max number of in-flight `Put Block` requests of a writer.
+    ///
+    /// Default to 1. Please note that writer will buffer up to
+    /// `block_size * block_concurrency` bytes in memory.
+    pub fn block_concurrency(&mut self, block_concurrency: usize) -> &mut Self {
+        if block_concurrency > 0 {
+            self.block_concurrency = Some(block_concurrency);
+        }
+
+        self
+    }
+
+    /// Use append blob for writer instead of block blob.
+    pub fn enable_append_blob(&mut self) -> &mut Self {
+        self.enable_append_blob = true;
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -341,6 +422,15 @@ impl Builder for AzblobBuilder {
         map.get("account_name").map(|v| builder.account_name(v));
         map.get("account_key").map(|v| builder.account_key(v));
         map.get("sas_token").map(|v| builder.sas_token(v));
+        map.get("block_size")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.block_size(v));
+        map.get("block_concurrency")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.block_concurrency(v));
+        map.get("enable_append_blob")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_append_blob());
 
         builder
     }
@@ -368,6 +458,15 @@ impl Builder for AzblobBuilder {
         }?;
         debug!("backend use endpoint {}", &container);
 
+        if self.account_key.is_some() && self.sas_token.is_some() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "account_key and sas_token can't be set at the same time",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Azblob));
+        }
+
         let client = if let Some(client) = self.http_client.take() {
             client
         } else {
@@ -397,6 +496,9 @@ impl Builder for AzblobBuilder {
                 root,
                 endpoint,
                 container: self.container.clone(),
+                block_size: self.block_size.unwrap_or(DEFAULT_BLOCK_SIZE),
+                block_concurrency: self.block_concurrency.unwrap_or(1),
+                enable_append_blob: self.enable_append_blob,
 
                 client,
                 loader: cred_loader,
@@ -453,10 +555,24 @@ impl Accessor for AzblobBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Azblob)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.container)
             .set_max_batch_operations(AZBLOB_BATCH_LIMIT)
-            .set_capabilities(Read | Write | List | Scan | Batch | Copy)
-            .set_hints(ReadStreamable);
+            .set_capabilities(
+                Read | Write
+                    | Append
+                    | List
+                    | Scan
+                    | Presign
+                    | Batch
+                    | Copy
+                    | WriteWithContentType
+                    | ListWithLimit
+                    | ReadWithVersion
+                    | StatWithVersion
+                    | DeleteWithVersion,
+            )
+            .set_hints(ReadStreamable | ImplicitDir);
 
         am
     }
@@ -482,13 +598,13 @@ impl Accessor for AzblobBackend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let resp = self.core.azblob_get_blob(path, args.range()).await?;
+        let resp = self.core.azblob_get_blob(path, &args).await?;
 
         let status = resp.status();
 
         match status {
             StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
-                let meta = parse_into_metadata(path, resp.headers())?;
+                let meta = parse_into_azblob_metadata(path, resp.headers())?;
 
                 Ok((RpRead::with_metadata(meta), resp.into_body()))
             }
@@ -497,13 +613,6 @@ impl Accessor for AzblobBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        if args.append() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "append write is not supported",
-            ));
-        }
-
         Ok((
             RpWrite::default(),
             AzblobWriter::new(self.core.clone(), args, path.to_string()),
@@ -524,27 +633,27 @@ impl Accessor for AzblobBackend {
         }
     }
 
-    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
         // Stat root always returns a DIR.
         if path == "/" {
             return Ok(RpStat::new(Metadata::new(EntryMode::DIR)));
         }
 
-        let resp = self.core.azblob_get_blob_properties(path).await?;
+        let resp = self
+            .core
+            .azblob_get_blob_properties(path, args.version())
+            .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
-            }
+            StatusCode::OK => parse_into_azblob_metadata(path, resp.headers()).map(RpStat::new),
             _ => Err(parse_error(resp).await?),
         }
     }
 
-    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
-        let resp = self.core.azblob_delete_blob(path).await?;
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let resp = self.core.azblob_delete_blob(path, args.version()).await?;
 
         let status = resp.status();
 
@@ -554,6 +663,31 @@ impl Accessor for AzblobBackend {
         }
     }
 
+    async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        // We will not send this request out, just for signing.
+        let mut req = match args.operation() {
+            PresignOperation::Stat(v) => self
+                .core
+                .azblob_get_blob_properties_request(path, v.version())?,
+            PresignOperation::Read(v) => self.core.azblob_get_blob_request(path, v)?,
+            PresignOperation::Write(v) => {
+                self.core
+                    .azblob_put_blob_request(path, None, v.content_type(), AsyncBody::Empty)?
+            }
+        };
+
+        self.core.sign_query(&mut req, path, args.expire()).await?;
+
+        // We don't need this request anymore, consume it directly.
+        let (parts, _) = req.into_parts();
+
+        Ok(RpPresign::new(PresignedRequest::new(
+            parts.method,
+            parts.uri,
+            parts.headers,
+        )))
+    }
+
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
         let op = AzblobPager::new(
             self.core.clone(),
@@ -577,16 +711,39 @@ impl Accessor for AzblobBackend {
     }
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
-        let ops = args.into_operation();
-        let paths = ops.into_iter().map(|(p, _)| p).collect::<Vec<_>>();
-        if paths.len() > AZBLOB_BATCH_LIMIT {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "batch delete limit exceeded",
-            ));
+        let ops = args
+            .into_operation()
+            .into_iter()
+            .map(|(p, op)| match op {
+                BatchOperation::Delete(op) => (p, op),
+            })
+            .collect::<Vec<_>>();
+
+        // Azblob accepts at most `AZBLOB_BATCH_LIMIT` sub requests in one
+        // batch, split larger inputs into multiple batch calls.
+        let mut results = Vec::with_capacity(ops.len());
+        for chunk in ops.chunks(AZBLOB_BATCH_LIMIT) {
+            let rps = self.batch_delete(chunk).await?;
+            results.extend(
+                rps.into_iter()
+                    .map(|(path, rp)| (path, rp.map(|v| v.into()))),
+            );
         }
+        Ok(RpBatch::new(results))
+    }
+}
+
+impl AzblobBackend {
+    /// Delete given paths via one blob batch call, `ops` must not exceed
+    /// `AZBLOB_BATCH_LIMIT`.
+    async fn batch_delete(
+        &self,
+        ops: &[(String, OpDelete)],
+    ) -> Result<Vec<(String, Result<RpDelete>)>> {
+        debug_assert!(ops.len() <= AZBLOB_BATCH_LIMIT);
+
         // construct and complete batch request
-        let resp = self.core.azblob_batch_delete(&paths).await?;
+        let resp = self.core.azblob_batch_delete(ops).await?;
 
         // check response status
         if resp.status() != StatusCode::ACCEPTED {
@@ -625,19 +782,100 @@ impl Accessor for AzblobBackend {
             )
         })?;
 
-        let results = parse_batch_delete_response(boundary, body, paths)?
-            .into_iter()
-            .map(|(path, rp)| (path, rp.map(|v| v.into())))
-            .collect();
-        Ok(RpBatch::new(results))
+        let paths = ops.iter().map(|(p, _)| p.clone()).collect();
+        parse_batch_delete_response(boundary, body, paths)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use chrono::DateTime;
+    use chrono::Utc;
+    use futures::TryStreamExt;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::matchers::query_param_is_missing;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
     use super::AzblobBuilder;
+    use crate::ops::OpBatch;
+    use crate::ops::OpDelete;
+    use crate::ops::OpRead;
+    use crate::ops::OpStat;
+    use crate::ops::OpWrite;
+    use crate::raw::Accessor;
     use crate::services::azblob::backend::infer_storage_name_from_endpoint;
     use crate::Builder;
+    use crate::ErrorKind;
+    use crate::Operator;
+    use crate::Result;
+
+    #[tokio::test]
+    async fn test_append() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/container/log"))
+            .and(query_param_is_missing("comp"))
+            .and(header("x-ms-blob-type", "AppendBlob"))
+            .and(header("content-length", "0"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        for (pos, len) in [("0", "5"), ("5", "7")] {
+            Mock::given(method("PUT"))
+                .and(path("/container/log"))
+                .and(query_param("comp", "appendblock"))
+                .and(header("x-ms-blob-condition-appendpos", pos))
+                .and(header("content-length", len))
+                .respond_with(
+                    ResponseTemplate::new(201).insert_header("x-ms-blob-append-offset", pos),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("PUT"))
+            .and(path("/container/block"))
+            .and(query_param("comp", "appendblock"))
+            .respond_with(
+                ResponseTemplate::new(409).insert_header("x-ms-error-code", "InvalidBlobType"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/container/block"))
+            .and(query_param_is_missing("comp"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("sv=2021-06-08&sig=test")
+            .enable_append_blob();
+        let op = Operator::new(builder)?.finish();
+
+        let mut w = op.writer("log").await?;
+        w.append("Hello").await?;
+        w.append(", Azure").await?;
+        w.close().await?;
+
+        let mut w = op.writer("block").await?;
+        let err = w.append("Hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.to_string().contains("not an append blob"));
+
+        Ok(())
+    }
 
     #[test]
     fn test_infer_storage_name_from_endpoint() {
@@ -701,8 +939,7 @@ mod tests {
         azblob_builder.endpoint("https://storagesample.blob.core.usgovcloudapi.net");
         azblob_builder.container("container");
         azblob_builder.account_name("storagesample");
-        azblob_builder.account_key("account-key");
-        azblob_builder.sas_token("sas");
+        azblob_builder.sas_token("?sas");
         let azblob = azblob_builder
             .build()
             .expect("build azblob should be succeeded.");
@@ -714,14 +951,358 @@ mod tests {
 
         assert_eq!(azblob.core.container, "container".to_string());
 
-        assert_eq!(
-            azblob_builder.account_key.unwrap(),
-            "account-key".to_string()
-        );
-
         assert_eq!(azblob_builder.sas_token.unwrap(), "sas".to_string());
     }
 
+    #[test]
+    fn test_builder_with_both_key_and_sas() {
+        let mut azblob_builder = AzblobBuilder::default();
+        azblob_builder.endpoint("https://storagesample.blob.core.usgovcloudapi.net");
+        azblob_builder.container("container");
+        azblob_builder.account_name("storagesample");
+        azblob_builder.account_key("account-key");
+        azblob_builder.sas_token("sas");
+
+        let err = azblob_builder.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        let debug = format!("{azblob_builder:?}");
+        assert!(!debug.contains("account-key"));
+        assert!(!debug.contains("sas\""));
+    }
+
+    #[tokio::test]
+    async fn test_block_upload() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/container/large"))
+            .and(query_param("comp", "block"))
+            .and(header("content-length", "4"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/container/large"))
+            .and(query_param("comp", "block"))
+            .and(header("content-length", "1"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/container/large"))
+            .and(query_param("comp", "blocklist"))
+            .and(header("x-ms-blob-content-type", "text/plain"))
+            .and(|req: &wiremock::Request| {
+                String::from_utf8_lossy(&req.body)
+                    .matches("<Latest>")
+                    .count()
+                    == 5
+            })
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/container/small"))
+            .and(query_param_is_missing("comp"))
+            .and(header("x-ms-blob-type", "BlockBlob"))
+            .and(header("content-length", "3"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("sv=2021-06-08&sig=test")
+            .block_size(4)
+            .block_concurrency(2);
+        let op = Operator::new(builder)?.finish();
+
+        let mut w = op
+            .writer_with("large", OpWrite::new().with_content_type("text/plain"))
+            .await?;
+        w.append("Hello, ").await?;
+        w.append("Azure!").await?;
+        w.append(" Bye").await?;
+        w.close().await?;
+
+        let mut w = op.writer("small").await?;
+        w.append("Hi!").await?;
+        w.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_presign() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        let no_credential = |req: &wiremock::Request| {
+            req.headers.keys().all(|k| {
+                !k.as_str().eq_ignore_ascii_case("authorization")
+                    && !k.as_str().eq_ignore_ascii_case("x-ms-version")
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/container/path/to/file"))
+            .and(query_param("sv", "2020-12-06"))
+            .and(query_param("sr", "b"))
+            .and(query_param("sp", "r"))
+            .and(no_credential)
+            .respond_with(ResponseTemplate::new(200).set_body_string("Hello"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/container/path/to/file"))
+            .and(query_param("sp", "cw"))
+            .and(header("x-ms-blob-type", "BlockBlob"))
+            .and(no_credential)
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .account_name("account")
+            .account_key("YWNjb3VudC1rZXk=");
+        let op = Operator::new(builder)?.finish();
+
+        let expire = Duration::from_secs(3600);
+        let req = op.presign_read("path/to/file", expire).await?;
+        let query = req.uri().query().expect("query must exist").to_string();
+        assert!(query.contains("sig="));
+
+        // Expiry must be formatted as ISO 8601 in UTC and within expire.
+        let se = query
+            .split('&')
+            .find_map(|kv| kv.strip_prefix("se="))
+            .expect("se must exist")
+            .replace("%3A", ":");
+        let se = DateTime::parse_from_rfc3339(&se).expect("se must be valid");
+        let delta = se.with_timezone(&Utc) - Utc::now();
+        assert!(delta <= chrono::Duration::seconds(3600));
+        assert!(delta > chrono::Duration::seconds(3500));
+
+        // Fetch presigned url with plain http client.
+        let client = reqwest::Client::new();
+        let resp = client.get(req.uri().to_string()).send().await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().await.unwrap(), "Hello");
+
+        let req = op.presign_write("path/to/file", expire).await?;
+        let resp = client
+            .put(req.uri().to_string())
+            .headers(req.header().clone())
+            .body("Hello")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+
+        // Presign with sas token is not supported.
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("sv=2021-06-08&sig=test");
+        let op = Operator::new(builder)?.finish();
+        let err = op
+            .presign_read("path/to/file", expire)
+            .await
+            .expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sas_token_query() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/container"))
+            .and(query_param("restype", "container"))
+            .and(query_param("comp", "list"))
+            .and(query_param("sv", "2021-06-08"))
+            .and(query_param("sig", "test"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<EnumerationResults><Blobs><Blob><Name>dir/file</Name><Properties><Content-Length>1</Content-Length><Last-Modified>Sun, 20 Mar 2022 11:29:03 GMT</Last-Modified><Etag>0x8DA0A64D66790C3</Etag></Properties></Blob></Blobs><NextMarker /></EnumerationResults>"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/container/dir/file"))
+            .and(query_param("sv", "2021-06-08"))
+            .and(query_param("sig", "test"))
+            .and(|req: &wiremock::Request| {
+                req.headers
+                    .keys()
+                    .all(|k| !k.as_str().eq_ignore_ascii_case("authorization"))
+            })
+            .respond_with(ResponseTemplate::new(200).insert_header("content-length", "1"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("?sv=2021-06-08&sig=test");
+        let op = Operator::new(builder)?.finish();
+
+        let entries: Vec<_> = op.list("dir/").await?.try_collect().await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(op.stat("dir/file").await?.content_length(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_version() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        for (m, resp) in [
+            (
+                "HEAD",
+                ResponseTemplate::new(200)
+                    .insert_header("content-length", "13")
+                    .insert_header("x-ms-version-id", "v1")
+                    .insert_header("x-ms-is-current-version", "false"),
+            ),
+            (
+                "GET",
+                ResponseTemplate::new(200)
+                    .insert_header("x-ms-version-id", "v1")
+                    .set_body_string("Hello, World!"),
+            ),
+            ("DELETE", ResponseTemplate::new(202)),
+        ] {
+            Mock::given(method(m))
+                .and(path("/container/file"))
+                .and(query_param("versionid", "v1"))
+                .respond_with(resp)
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/container/file"))
+            .and(query_param("snapshot", "2023-01-01T00:00:00.0000000Z"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("snapshot"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/container/file"))
+            .and(query_param_is_missing("versionid"))
+            .and(query_param_is_missing("snapshot"))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("sv=2021-06-08&sig=test");
+        let op = Operator::new(builder)?.finish();
+
+        let meta = op
+            .stat_with("file", OpStat::new().with_version("v1"))
+            .await?;
+        assert_eq!(meta.version(), Some("v1"));
+        assert_eq!(meta.is_current(), Some(false));
+        let bs = op
+            .read_with("file", OpRead::new().with_version("v1"))
+            .await?;
+        assert_eq!(bs, b"Hello, World!");
+        let bs = op
+            .read_with(
+                "file",
+                OpRead::new().with_version("snapshot:2023-01-01T00:00:00.0000000Z"),
+            )
+            .await?;
+        assert_eq!(bs, b"snapshot");
+
+        // Delete the given version only.
+        op.delete_with("file", OpDelete::new().with_version("v1"))
+            .await?;
+        // Delete the base blob.
+        op.delete("file").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_delete_in_chunks() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/container"))
+            .and(query_param("restype", "container"))
+            .and(query_param("comp", "batch"))
+            .respond_with(|req: &wiremock::Request| {
+                let body = String::from_utf8_lossy(&req.body);
+                let mut resp = String::new();
+                for (idx, line) in body
+                    .lines()
+                    .filter(|v| v.starts_with("DELETE "))
+                    .enumerate()
+                {
+                    let status = if line.contains("/denied") {
+                        "403 Forbidden"
+                    } else {
+                        "202 Accepted"
+                    };
+                    resp.push_str(&format!(
+                        "--batchresponse_1\r\nContent-Type: application/http\r\nContent-ID: {idx}\r\n\r\nHTTP/1.1 {status}\r\n\r\n"
+                    ));
+                }
+                resp.push_str("--batchresponse_1--");
+                ResponseTemplate::new(202)
+                    .set_body_raw(resp, "multipart/mixed; boundary=batchresponse_1")
+            })
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = AzblobBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .container("container")
+            .sas_token("sv=2021-06-08&sig=test");
+        let op = Operator::new(builder)?.finish();
+
+        let mut ops: Vec<_> = (0..300)
+            .map(|i| (format!("file-{i}"), OpDelete::new().into()))
+            .collect();
+        ops.push(("denied".to_string(), OpDelete::new().into()));
+        let rp = op.inner().batch(OpBatch::new(ops)).await?;
+
+        let results = rp.into_results();
+        assert_eq!(results.len(), 301);
+        for (path, result) in results {
+            if path == "denied" {
+                assert_eq!(
+                    result.err().map(|e| e.kind()),
+                    Some(ErrorKind::PermissionDenied)
+                );
+            } else {
+                assert!(result.is_ok(), "{path} must be deleted");
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_builder_from_connection_string() {
         let builder = AzblobBuilder::from_connection_string(
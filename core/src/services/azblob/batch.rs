@@ -109,15 +109,21 @@ Content-ID: {idx}
     }
 }
 
+/// Parse batch delete response into per-path results.
+///
+/// Sub-responses are mapped back to `expect` via their `Content-ID`, which
+/// is the index of sub-request in batch. Sub-responses without `Content-ID`
+/// will fallback to their position.
 pub(super) fn parse_batch_delete_response(
     boundary: &str,
     body: String,
     expect: Vec<String>,
 ) -> Result<Vec<(String, Result<RpDelete>)>> {
-    let mut reps = Vec::with_capacity(expect.len());
+    let mut names: Vec<Option<String>> = expect.into_iter().map(Some).collect();
+    let mut reps = Vec::with_capacity(names.len());
 
     let mut resp_packs: Vec<&str> = body.trim().split(&format!("--{boundary}")).collect();
-    if resp_packs.len() != (expect.len() + 2) {
+    if resp_packs.len() != (names.len() + 2) {
         return Err(Error::new(
             ErrorKind::Unexpected,
             "invalid batch delete response",
@@ -125,10 +131,29 @@ pub(super) fn parse_batch_delete_response(
     }
     // drop the tail
     resp_packs.pop();
-    for (resp_pack, name) in resp_packs[1..].iter().zip(expect.into_iter()) {
+    for (pos, resp_pack) in resp_packs[1..].iter().enumerate() {
         // the http body use CRLF (\r\n) instead of LF (\n)
         // split the body at double CRLF
-        let split: Vec<&str> = resp_pack.split("\r\n\r\n").collect();
+        let split: Vec<&str> = resp_pack.splitn(3, "\r\n\r\n").collect();
+
+        let idx = split[0]
+            .lines()
+            .find_map(|line| {
+                let (k, v) = line.split_once(':')?;
+                if k.trim().eq_ignore_ascii_case("content-id") {
+                    v.trim().parse::<usize>().ok()
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(pos);
+        let name = names.get_mut(idx).and_then(|v| v.take()).ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "batch response item doesn't match any request",
+            )
+            .with_context("content-id", idx.to_string())
+        })?;
 
         let header: Vec<&str> = split
             .get(1)
@@ -163,10 +188,12 @@ pub(super) fn parse_batch_delete_response(
         let rep = match status_code {
             StatusCode::ACCEPTED | StatusCode::NOT_FOUND => (name, Ok(RpDelete::default())),
             s => {
-                let body = split.get(1).ok_or_else(|| {
-                    Error::new(ErrorKind::Unexpected, "Empty HTTP error response")
-                })?;
-                let err = parse_http_error(s, body)?;
+                // Use the status line as error message if body is empty.
+                let body = match split.get(2).map(|v| v.trim()) {
+                    Some(body) if !body.is_empty() => body,
+                    _ => split[1].trim(),
+                };
+                let err = parse_http_error(s, body)?.with_context("path", &name);
                 (name, Err(err))
             }
         };
@@ -186,6 +213,7 @@ mod test {
     use super::BatchDeleteRequestBuilder;
     use crate::raw::AsyncBody;
     use crate::services::azblob::batch::parse_batch_delete_response;
+    use crate::ErrorKind;
 
     #[test]
     fn batch_delete_req_builder_test() -> Result<()> {
@@ -311,4 +339,34 @@ Time:2018-06-14T16:46:54.6040685Z</Message></Error>
             }
         }
     }
+
+    #[test]
+    fn test_batch_response_out_of_order() {
+        let body = r#"--batchresponse_1
+Content-Type: application/http
+Content-ID: 1
+
+HTTP/1.1 403 Request to blob forbidden
+x-ms-error-code: AuthorizationPermissionMismatch
+
+--batchresponse_1
+Content-Type: application/http
+Content-ID: 0
+
+HTTP/1.1 202 Accepted
+x-ms-delete-type-permanent: true
+
+--batchresponse_1--"#
+            .replace('\n', "\r\n");
+
+        let expected = vec!["/to-del/ok".to_string(), "/to-del/denied".to_string()];
+        let p =
+            parse_batch_delete_response("batchresponse_1", body, expected).expect("must success");
+        assert_eq!(p.len(), 2);
+        assert_eq!(p[0].0, "/to-del/denied");
+        let err = p[0].1.as_ref().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(p[1].0, "/to-del/ok");
+        assert!(p[1].1.is_ok());
+    }
 }
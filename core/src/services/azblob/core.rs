@@ -20,28 +20,49 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
 use std::str::FromStr;
-
+use std::time::Duration;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
 use http::header::HeaderName;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
+use http::HeaderMap;
+use http::Method;
 use http::Request;
 use http::Response;
 use http::Uri;
 use reqsign::AzureStorageCredential;
 use reqsign::AzureStorageLoader;
 use reqsign::AzureStorageSigner;
+use serde::Serialize;
+use sha2::Sha256;
 
 use super::batch::BatchDeleteRequestBuilder;
+use crate::ops::*;
 use crate::raw::*;
 use crate::*;
 
 const X_MS_BLOB_TYPE: &str = "x-ms-blob-type";
 const X_MS_COPY_SOURCE: &str = "x-ms-copy-source";
+const X_MS_BLOB_CONTENT_TYPE: &str = "x-ms-blob-content-type";
+const X_MS_VERSION_ID: &str = "x-ms-version-id";
+const X_MS_IS_CURRENT_VERSION: &str = "x-ms-is-current-version";
+const X_MS_BLOB_CONDITION_APPENDPOS: &str = "x-ms-blob-condition-appendpos";
+pub const X_MS_BLOB_APPEND_OFFSET: &str = "x-ms-blob-append-offset";
 
 pub struct AzblobCore {
     pub container: String,
     pub root: String,
     pub endpoint: String,
+    pub block_size: usize,
+    pub block_concurrency: usize,
+    pub enable_append_blob: bool,
 
     pub client: HttpClient,
     pub loader: AzureStorageLoader,
@@ -55,6 +76,9 @@ impl Debug for AzblobCore {
             .field("container", &self.container)
             .field("root", &self.root)
             .field("endpoint", &self.endpoint)
+            .field("block_size", &self.block_size)
+            .field("block_concurrency", &self.block_concurrency)
+            .field("enable_append_blob", &self.enable_append_blob)
             .finish_non_exhaustive()
     }
 }
@@ -89,6 +113,64 @@ impl AzblobCore {
             .map_err(new_request_sign_error)
     }
 
+    /// Sign request via service SAS so that it can be sent without any
+    /// credential before expired.
+    ///
+    /// Only account key is supported to sign service SAS for now.
+    pub async fn sign_query(
+        &self,
+        req: &mut Request<AsyncBody>,
+        path: &str,
+        expire: Duration,
+    ) -> Result<()> {
+        let (account_name, account_key) = match self.load_credential().await? {
+            AzureStorageCredential::SharedKey(account_name, account_key) => {
+                (account_name, account_key)
+            }
+            AzureStorageCredential::SharedAccessSignature(_) => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "presign requires account key, existing sas token can't be used to sign",
+                ));
+            }
+        };
+
+        // Read and stat share the same permission.
+        let permissions = if req.method() == Method::PUT {
+            "cw"
+        } else {
+            "r"
+        };
+        let expiry = Utc::now()
+            + chrono::Duration::from_std(expire).map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "presign expire is out of range").set_source(err)
+            })?;
+
+        let resource = format!(
+            "/blob/{}/{}/{}",
+            account_name,
+            self.container,
+            build_abs_path(&self.root, path)
+        );
+        let query = build_service_sas(&account_key, &resource, permissions, expiry)?;
+
+        let uri = format!(
+            "{}{}{}",
+            req.uri(),
+            if req.uri().query().is_some() {
+                "&"
+            } else {
+                "?"
+            },
+            query
+        );
+        *req.uri_mut() = Uri::from_str(&uri).map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "presigned uri is invalid").set_source(err)
+        })?;
+
+        Ok(())
+    }
+
     #[inline]
     pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
         self.client.send(req).await
@@ -96,22 +178,23 @@ impl AzblobCore {
 }
 
 impl AzblobCore {
-    pub async fn azblob_get_blob(
-        &self,
-        path: &str,
-        range: BytesRange,
-    ) -> Result<Response<IncomingAsyncBody>> {
+    pub fn azblob_get_blob_request(&self, path: &str, args: &OpRead) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/{}/{}",
             self.endpoint,
             self.container,
             percent_encode_path(&p)
         );
+        if let Some(version) = args.version() {
+            write!(url, "?{}", build_version_query(version))
+                .expect("write into string must succeed");
+        }
 
         let mut req = Request::get(&url);
 
+        let range = args.range();
         if !range.is_full() {
             // azblob doesn't support read with suffix range.
             //
@@ -126,10 +209,20 @@ impl AzblobCore {
             req = req.header(http::header::RANGE, range.to_header());
         }
 
-        let mut req = req
+        let req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
+        Ok(req)
+    }
+
+    pub async fn azblob_get_blob(
+        &self,
+        path: &str,
+        args: &OpRead,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.azblob_get_blob_request(path, args)?;
+
         self.sign(&mut req).await?;
 
         self.send(req).await
@@ -169,38 +262,201 @@ impl AzblobCore {
         Ok(req)
     }
 
-    pub async fn azblob_get_blob_properties(
+    /// Stage a block to be committed by `Put Block List` later.
+    ///
+    /// `block_id` must be base64 encoded and all blocks of the same blob
+    /// must have the same length of block id.
+    pub fn azblob_put_block_request(
         &self,
         path: &str,
+        block_id: &str,
+        size: u64,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}?comp=block&blockid={}",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p),
+            percent_encode_path(block_id)
+        );
+
+        let mut req = Request::put(&url);
+
+        req = req.header(CONTENT_LENGTH, size);
+
+        let req = req.body(body).map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Commit staged blocks as the content of blob.
+    pub async fn azblob_put_block_list(
+        &self,
+        path: &str,
+        block_ids: &[String],
+        content_type: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
         let url = format!(
+            "{}/{}/{}?comp=blocklist",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let mut req = Request::put(&url);
+
+        if let Some(ty) = content_type {
+            req = req.header(HeaderName::from_static(X_MS_BLOB_CONTENT_TYPE), ty)
+        }
+
+        let content = quick_xml::se::to_string(&PutBlockListRequest {
+            latest: block_ids.to_vec(),
+        })
+        .map_err(new_xml_deserialize_error)?;
+
+        // Make sure content length has been set to avoid put with chunked encoding.
+        req = req.header(CONTENT_LENGTH, content.len());
+
+        let mut req = req
+            .body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
+    /// Create an empty append blob, existing blob will be overwritten.
+    pub fn azblob_init_appendable_blob_request(
+        &self,
+        path: &str,
+        content_type: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let mut req = Request::put(&url);
+
+        // The content-length of append blob creation must be 0.
+        req = req.header(CONTENT_LENGTH, 0);
+
+        if let Some(ty) = content_type {
+            req = req.header(CONTENT_TYPE, ty)
+        }
+
+        req = req.header(HeaderName::from_static(X_MS_BLOB_TYPE), "AppendBlob");
+
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    /// Append a block to the end of an append blob.
+    ///
+    /// If `position` is set, azblob will reject this request with
+    /// `412 Precondition Failed` if the blob's length doesn't match it.
+    pub fn azblob_append_blob_request(
+        &self,
+        path: &str,
+        position: Option<u64>,
+        size: u64,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}?comp=appendblock",
+            self.endpoint,
+            self.container,
+            percent_encode_path(&p)
+        );
+
+        let mut req = Request::put(&url);
+
+        req = req.header(CONTENT_LENGTH, size);
+
+        if let Some(position) = position {
+            req = req.header(
+                HeaderName::from_static(X_MS_BLOB_CONDITION_APPENDPOS),
+                position,
+            );
+        }
+
+        let req = req.body(body).map_err(new_request_build_error)?;
+
+        Ok(req)
+    }
+
+    pub fn azblob_get_blob_properties_request(
+        &self,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut url = format!(
             "{}/{}/{}",
             self.endpoint,
             self.container,
             percent_encode_path(&p)
         );
+        if let Some(version) = version {
+            write!(url, "?{}", build_version_query(version))
+                .expect("write into string must succeed");
+        }
 
         let req = Request::head(&url);
 
-        let mut req = req
+        let req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
+        Ok(req)
+    }
+
+    pub async fn azblob_get_blob_properties(
+        &self,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = self.azblob_get_blob_properties_request(path, version)?;
+
         self.sign(&mut req).await?;
         self.send(req).await
     }
 
-    pub async fn azblob_delete_blob(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+    /// Delete blob, only the given version or snapshot will be deleted
+    /// if `version` is set.
+    pub async fn azblob_delete_blob(
+        &self,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/{}/{}",
             self.endpoint,
             self.container,
             percent_encode_path(&p)
         );
+        if let Some(version) = version {
+            write!(url, "?{}", build_version_query(version))
+                .expect("write into string must succeed");
+        }
 
         let req = Request::delete(&url);
 
@@ -280,7 +536,7 @@ impl AzblobCore {
 
     pub async fn azblob_batch_delete(
         &self,
-        paths: &[String],
+        paths: &[(String, OpDelete)],
     ) -> Result<Response<IncomingAsyncBody>> {
         // init batch request
         let url = format!(
@@ -289,16 +545,17 @@ impl AzblobCore {
         );
         let mut batch_delete_req_builder = BatchDeleteRequestBuilder::new(&url);
 
-        for path in paths.iter() {
+        for (path, op) in paths.iter() {
             // build sub requests
             let p = build_abs_path(&self.root, path);
             let encoded_path = percent_encode_path(&p);
 
-            let url = Uri::from_str(&format!(
-                "{}/{}/{}",
-                self.endpoint, self.container, encoded_path
-            ))
-            .unwrap();
+            let mut url = format!("{}/{}/{}", self.endpoint, self.container, encoded_path);
+            if let Some(version) = op.version() {
+                write!(url, "?{}", build_version_query(version))
+                    .expect("write into string must succeed");
+            }
+            let url = Uri::from_str(&url).unwrap();
 
             let mut sub_req = Request::delete(&url.to_string())
                 .header(CONTENT_LENGTH, 0)
@@ -316,3 +573,165 @@ impl AzblobCore {
         self.send(req).await
     }
 }
+
+/// Prefix of version to address a snapshot instead of a version.
+const SNAPSHOT_PREFIX: &str = "snapshot:";
+
+/// Build query to address a specific version or snapshot of blob.
+///
+/// - `snapshot:<timestamp>` will be sent as `snapshot=<timestamp>`.
+/// - Others will be sent as `versionid=<version>`.
+fn build_version_query(version: &str) -> String {
+    match version.strip_prefix(SNAPSHOT_PREFIX) {
+        Some(snapshot) => format!("snapshot={}", percent_encode_path(snapshot)),
+        None => format!("versionid={}", percent_encode_path(version)),
+    }
+}
+
+/// Parse azblob metadata from headers, besides the standard http headers,
+/// `x-ms-version-id` and `x-ms-is-current-version` will be parsed as
+/// version and is_current.
+pub fn parse_into_azblob_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata> {
+    let mut m = parse_into_metadata(path, headers)?;
+
+    if let Some(v) = headers.get(X_MS_VERSION_ID) {
+        let v = v.to_str().map_err(|e| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header value is not valid utf-8 string",
+            )
+            .with_operation("parse_into_azblob_metadata")
+            .set_source(e)
+        })?;
+        m.set_version(v);
+    }
+    if let Some(v) = headers.get(X_MS_IS_CURRENT_VERSION) {
+        m.set_is_current(v == "true");
+    }
+
+    Ok(m)
+}
+
+/// The version of service SAS we used.
+const SAS_VERSION: &str = "2020-12-06";
+
+/// Build service SAS query string for blob.
+///
+/// Reference: [Create a service SAS](https://learn.microsoft.com/en-us/rest/api/storageservices/create-service-sas)
+pub fn build_service_sas(
+    account_key: &str,
+    resource: &str,
+    permissions: &str,
+    expiry: DateTime<Utc>,
+) -> Result<String> {
+    let expiry = expiry.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+    // Fields of string to sign in order:
+    //
+    // signedPermissions, signedStart, signedExpiry, canonicalizedResource,
+    // signedIdentifier, signedIP, signedProtocol, signedVersion,
+    // signedResource, signedSnapshotTime, signedEncryptionScope,
+    // rscc, rscd, rsce, rscl, rsct
+    let string_to_sign = [
+        permissions,
+        "",
+        &expiry,
+        resource,
+        "",
+        "",
+        "",
+        SAS_VERSION,
+        "b",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+    ]
+    .join("\n");
+
+    let key = BASE64_STANDARD.decode(account_key).map_err(|err| {
+        Error::new(ErrorKind::ConfigInvalid, "account key is not valid base64").set_source(err)
+    })?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).map_err(|err| {
+        Error::new(
+            ErrorKind::ConfigInvalid,
+            "account key is not valid hmac key",
+        )
+        .set_source(err)
+    })?;
+    mac.update(string_to_sign.as_bytes());
+    let signature = BASE64_STANDARD.encode(mac.finalize().into_bytes());
+
+    Ok(format!(
+        "sv={}&se={}&sr=b&sp={}&sig={}",
+        SAS_VERSION,
+        percent_encode_path(&expiry),
+        permissions,
+        percent_encode_path(&signature)
+    ))
+}
+
+/// Request of Put Block List.
+#[derive(Default, Debug, Serialize)]
+#[serde(default, rename = "BlockList", rename_all = "PascalCase")]
+pub struct PutBlockListRequest {
+    pub latest: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_service_sas() {
+        let expiry = DateTime::parse_from_rfc3339("2023-01-02T03:04:05Z")
+            .expect("must be valid")
+            .with_timezone(&Utc);
+
+        let query = build_service_sas(
+            "YWNjb3VudC1rZXk=",
+            "/blob/account/container/path/to/file",
+            "r",
+            expiry,
+        )
+        .expect("must succeed");
+
+        assert!(query.starts_with("sv=2020-12-06&se=2023-01-02T03%3A04%3A05Z&sr=b&sp=r&sig="));
+
+        // Signature must be stable for the same input.
+        let again = build_service_sas(
+            "YWNjb3VudC1rZXk=",
+            "/blob/account/container/path/to/file",
+            "r",
+            expiry,
+        )
+        .expect("must succeed");
+        assert_eq!(query, again);
+
+        let err =
+            build_service_sas("not base64!", "/blob/a/c/p", "r", expiry).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_serialize_put_block_list_request() {
+        let req = PutBlockListRequest {
+            latest: vec!["AAAAAA==".to_string(), "AQAAAA==".to_string()],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<BlockList>
+             <Latest>AAAAAA==</Latest>
+             <Latest>AQAAAA==</Latest>
+             </BlockList>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        )
+    }
+}
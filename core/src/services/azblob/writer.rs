@@ -18,10 +18,16 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use bytes::Bytes;
+use bytes::BytesMut;
+use futures::future::try_join_all;
 use http::StatusCode;
+use uuid::Uuid;
 
 use super::core::AzblobCore;
+use super::core::X_MS_BLOB_APPEND_OFFSET;
 use super::error::parse_error;
 use crate::ops::OpWrite;
 use crate::raw::*;
@@ -32,17 +38,32 @@ pub struct AzblobWriter {
 
     op: OpWrite,
     path: String,
+
+    /// The offset to append next block, `None` means the append blob
+    /// has not been created yet.
+    ///
+    /// Only used while `enable_append_blob` is set.
+    append_offset: Option<u64>,
+
+    /// Data that has not been staged as blocks yet.
+    buffer: BytesMut,
+    /// Ids of staged blocks in order.
+    block_ids: Vec<String>,
 }
 
 impl AzblobWriter {
     pub fn new(core: Arc<AzblobCore>, op: OpWrite, path: String) -> Self {
-        AzblobWriter { core, op, path }
+        AzblobWriter {
+            core,
+            op,
+            path,
+            append_offset: None,
+            buffer: BytesMut::new(),
+            block_ids: vec![],
+        }
     }
-}
 
-#[async_trait]
-impl oio::Write for AzblobWriter {
-    async fn write(&mut self, bs: Bytes) -> Result<()> {
+    async fn put_blob(&self, bs: Bytes) -> Result<()> {
         let mut req = self.core.azblob_put_blob_request(
             &self.path,
             Some(bs.len()),
@@ -65,20 +86,203 @@ impl oio::Write for AzblobWriter {
         }
     }
 
+    async fn put_block(&self, block_id: &str, bs: Bytes) -> Result<()> {
+        let mut req = self.core.azblob_put_block_request(
+            &self.path,
+            block_id,
+            bs.len() as u64,
+            AsyncBody::Bytes(bs),
+        )?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    /// Stage given blocks concurrently.
+    async fn stage_blocks(&mut self, blocks: Vec<Bytes>) -> Result<()> {
+        // Block ids must be base64 encoded and have the same length
+        // for the same blob, uuid fits well.
+        let block_ids: Vec<String> = blocks
+            .iter()
+            .map(|_| BASE64_STANDARD.encode(Uuid::new_v4().as_bytes()))
+            .collect();
+
+        try_join_all(
+            block_ids
+                .iter()
+                .zip(blocks)
+                .map(|(block_id, bs)| self.put_block(block_id, bs)),
+        )
+        .await?;
+
+        self.block_ids.extend(block_ids);
+        Ok(())
+    }
+
+    /// Split at most `block_concurrency` blocks from buffer.
+    fn split_blocks(&mut self) -> Vec<Bytes> {
+        let mut blocks = Vec::with_capacity(self.core.block_concurrency);
+        while !self.buffer.is_empty() && blocks.len() < self.core.block_concurrency {
+            let size = self.core.block_size.min(self.buffer.len());
+            blocks.push(self.buffer.split_to(size).freeze());
+        }
+        blocks
+    }
+
+    async fn create_append_blob(&self) -> Result<()> {
+        let mut req = self
+            .core
+            .azblob_init_appendable_blob_request(&self.path, self.op.content_type())?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Write for AzblobWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.put_blob(bs).await
+    }
+
     async fn append(&mut self, bs: Bytes) -> Result<()> {
-        let _ = bs;
+        if !self.core.enable_append_blob {
+            self.buffer.extend_from_slice(&bs);
+
+            // Only stage blocks while we have enough data to fill all
+            // in-flight requests.
+            while self.buffer.len() >= self.core.block_size * self.core.block_concurrency {
+                let blocks = self.split_blocks();
+                self.stage_blocks(blocks).await?;
+            }
+            return Ok(());
+        }
+
+        let offset = match self.append_offset {
+            Some(offset) => offset,
+            None => {
+                self.create_append_blob().await?;
+                self.append_offset = Some(0);
+                0
+            }
+        };
+
+        let size = bs.len() as u64;
+        let mut req = self.core.azblob_append_blob_request(
+            &self.path,
+            Some(offset),
+            size,
+            AsyncBody::Bytes(bs),
+        )?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                // Azblob returns the offset at which the block was committed.
+                let committed = match resp.headers().get(X_MS_BLOB_APPEND_OFFSET) {
+                    Some(v) => v
+                        .to_str()
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .ok_or_else(|| {
+                            Error::new(
+                                ErrorKind::Unexpected,
+                                "header x-ms-blob-append-offset is not a valid integer",
+                            )
+                            .with_context("path", &self.path)
+                        })?,
+                    None => offset,
+                };
+                self.append_offset = Some(committed + size);
 
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support append",
-        ))
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            StatusCode::CONFLICT
+                if resp
+                    .headers()
+                    .get("x-ms-error-code")
+                    .map(|v| v == "InvalidBlobType")
+                    .unwrap_or_default() =>
+            {
+                let err = parse_error(resp).await?;
+                Err(Error::new(
+                    ErrorKind::Unexpected,
+                    "append to a blob which is not an append blob",
+                )
+                .with_operation("Writer::append")
+                .with_context("path", &self.path)
+                .set_source(err))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
     }
 
     async fn abort(&mut self) -> Result<()> {
+        // Staged blocks will be garbage collected by azblob if they
+        // are not committed.
+        self.buffer.clear();
+        self.block_ids.clear();
         Ok(())
     }
 
     async fn close(&mut self) -> Result<()> {
-        Ok(())
+        if self.core.enable_append_blob {
+            return Ok(());
+        }
+
+        // Use put blob directly for small writes.
+        if self.block_ids.is_empty() && self.buffer.len() <= self.core.block_size {
+            let bs = self.buffer.split().freeze();
+            return self.put_blob(bs).await;
+        }
+
+        while !self.buffer.is_empty() {
+            let blocks = self.split_blocks();
+            self.stage_blocks(blocks).await?;
+        }
+
+        let resp = self
+            .core
+            .azblob_put_block_list(&self.path, &self.block_ids, self.op.content_type())
+            .await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                self.block_ids.clear();
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
     }
 }
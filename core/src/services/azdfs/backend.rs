@@ -307,14 +307,18 @@ impl Accessor for AzdfsBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Azdfs)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.filesystem)
             .set_capabilities(
                 AccessorCapability::Read
                     | AccessorCapability::Write
                     | AccessorCapability::Rename
-                    | AccessorCapability::List,
+                    | AccessorCapability::List
+                    | AccessorCapability::WriteWithContentType
+                    | AccessorCapability::WriteWithContentDisposition
+                    | AccessorCapability::ListWithLimit,
             )
-            .set_hints(AccessorHint::ReadStreamable);
+            .set_hints(AccessorHint::ReadStreamable | AccessorHint::ImplicitDir);
 
         am
     }
@@ -403,9 +407,6 @@ impl Accessor for AzdfsBackend {
 
         match status {
             StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
-            }
             _ => Err(parse_error(resp).await?),
         }
     }
@@ -0,0 +1,522 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http::StatusCode;
+use log::debug;
+use reqsign::AzureStorageConfig;
+use reqsign::AzureStorageLoader;
+use reqsign::AzureStorageSigner;
+
+use super::core::AzfileCore;
+use super::error::parse_error;
+use super::pager::AzfilePager;
+use super::writer::AzfileWriter;
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// Known endpoint suffix Azure File Storage URI syntax.
+/// Azure public cloud: https://accountname.file.core.windows.net
+/// Azure US Government: https://accountname.file.core.usgovcloudapi.net
+/// Azure China: https://accountname.file.core.chinacloudapi.cn
+const KNOWN_AZFILE_ENDPOINT_SUFFIX: &[&str] = &[
+    "file.core.windows.net",
+    "file.core.usgovcloudapi.net",
+    "file.core.chinacloudapi.cn",
+];
+
+/// Azure File Storage Support.
+///
+/// This service will visit the file shares of [Azure Files](https://learn.microsoft.com/en-us/azure/storage/files/storage-files-introduction)
+/// via its [REST API](https://learn.microsoft.com/en-us/rest/api/storageservices/file-service-rest-api).
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] rename
+/// - [x] list
+/// - [ ] ~~scan~~
+/// - [ ] presign
+/// - [ ] blocking
+///
+/// # Notes
+///
+/// Unlike blob storage, dirs in azfile are real: the parent dirs will be
+/// created before writing files, and only empty dirs could be deleted.
+///
+/// Files in azfile must be sized while creating. Writer will create the
+/// file with its final size for `write`, and resize the file before putting
+/// every range for `append`.
+///
+/// # Configuration
+///
+/// - `root`: Set the work dir for backend.
+/// - `share_name`: Set the share name for backend.
+/// - `endpoint`: Set the endpoint for backend.
+/// - `account_name`: Set the account_name for backend.
+/// - `account_key`: Set the account_key for backend.
+/// - `sas_token`: Set the sas_token for backend, conflicts with `account_key`.
+///
+/// Refer to public API docs for more information.
+///
+/// # Example
+///
+/// ## Init OpenDAL Operator
+///
+/// ### Via Builder
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use anyhow::Result;
+/// use opendal::services::Azfile;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     // Create azfile backend builder.
+///     let mut builder = Azfile::default();
+///     // Set the root for azfile, all operations will happen under this root.
+///     //
+///     // NOTE: the root must be absolute path.
+///     builder.root("/path/to/dir");
+///     // Set the share name, this is required.
+///     builder.share_name("test");
+///     // Set the endpoint, this is required.
+///     //
+///     // For examples:
+///     // - "https://accountname.file.core.windows.net"
+///     builder.endpoint("https://accountname.file.core.windows.net");
+///     // Set the account_name and account_key.
+///     //
+///     // OpenDAL will try load credential from the env.
+///     // If credential not set and no valid credential in env, OpenDAL will
+///     // send request without signing like anonymous user.
+///     builder.account_name("account_name");
+///     builder.account_key("account_key");
+///
+///     // `Accessor` provides the low level APIs, we will use `Operator` normally.
+///     let op: Operator = Operator::new(builder)?.finish();
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct AzfileBuilder {
+    root: Option<String>,
+    share_name: String,
+    endpoint: Option<String>,
+    account_name: Option<String>,
+    account_key: Option<String>,
+    sas_token: Option<String>,
+    http_client: Option<HttpClient>,
+}
+
+impl Debug for AzfileBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Builder");
+
+        ds.field("root", &self.root);
+        ds.field("share_name", &self.share_name);
+        ds.field("endpoint", &self.endpoint);
+
+        if self.account_name.is_some() {
+            ds.field("account_name", &"<redacted>");
+        }
+        if self.account_key.is_some() {
+            ds.field("account_key", &"<redacted>");
+        }
+        if self.sas_token.is_some() {
+            ds.field("sas_token", &"<redacted>");
+        }
+
+        ds.finish()
+    }
+}
+
+impl AzfileBuilder {
+    /// Set root of this backend.
+    ///
+    /// All operations will happen under this root.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string())
+        }
+
+        self
+    }
+
+    /// Set share name of this backend.
+    pub fn share_name(&mut self, share_name: &str) -> &mut Self {
+        self.share_name = share_name.to_string();
+
+        self
+    }
+
+    /// Set endpoint of this backend.
+    ///
+    /// Endpoint must be full uri, e.g.
+    ///
+    /// - Azfile: `https://accountname.file.core.windows.net`
+    /// - Azurite: `http://127.0.0.1:10004/devstoreaccount1`
+    pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
+        if !endpoint.is_empty() {
+            // Trim trailing `/` so that we can accept `http://127.0.0.1:9000/`
+            self.endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        }
+
+        self
+    }
+
+    /// Set account_name of this backend.
+    ///
+    /// - If account_name is set, we will take user's input first.
+    /// - If not, we will try to load it from environment.
+    pub fn account_name(&mut self, account_name: &str) -> &mut Self {
+        if !account_name.is_empty() {
+            self.account_name = Some(account_name.to_string());
+        }
+
+        self
+    }
+
+    /// Set account_key of this backend.
+    ///
+    /// - If account_key is set, we will take user's input first.
+    /// - If not, we will try to load it from environment.
+    pub fn account_key(&mut self, account_key: &str) -> &mut Self {
+        if !account_key.is_empty() {
+            self.account_key = Some(account_key.to_string());
+        }
+
+        self
+    }
+
+    /// Set sas_token of this backend.
+    ///
+    /// - If sas_token is set, we will take user's input first.
+    /// - If not, we will try to load it from environment.
+    ///
+    /// The SAS query parameters will be appended to every request, so
+    /// sas_token can't be used together with `account_key`.
+    pub fn sas_token(&mut self, sas_token: &str) -> &mut Self {
+        // Allow users to input the query string copied from azure portal
+        // directly, like `?sv=2021-06-08&...`
+        let sas_token = sas_token.trim_start_matches('?');
+        if !sas_token.is_empty() {
+            self.sas_token = Some(sas_token.to_string());
+        }
+
+        self
+    }
+
+    /// Specify the http client that used by this service.
+    ///
+    /// # Notes
+    ///
+    /// This API is part of OpenDAL's Raw API. `HttpClient` could be changed
+    /// during minor updates.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl Builder for AzfileBuilder {
+    type Accessor = AzfileBackend;
+    const SCHEME: Scheme = Scheme::Azfile;
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        debug!("backend build started: {:?}", &self);
+
+        let root = normalize_root(&self.root.take().unwrap_or_default());
+        debug!("backend use root {}", root);
+
+        let share_name = match self.share_name.is_empty() {
+            false => Ok(&self.share_name),
+            true => Err(Error::new(ErrorKind::ConfigInvalid, "share_name is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Azfile)),
+        }?;
+        debug!("backend use share_name {}", &share_name);
+
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => Ok(endpoint.clone()),
+            None => Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Azfile)),
+        }?;
+        debug!("backend use endpoint {}", &endpoint);
+
+        if self.account_key.is_some() && self.sas_token.is_some() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "account_key and sas_token can't be set at the same time",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Azfile));
+        }
+
+        let client = if let Some(client) = self.http_client.take() {
+            client
+        } else {
+            HttpClient::new().map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::Azfile)
+            })?
+        };
+
+        let config_loader = AzureStorageConfig {
+            account_name: self
+                .account_name
+                .clone()
+                .or_else(|| infer_storage_name_from_endpoint(endpoint.as_str())),
+            account_key: self.account_key.clone(),
+            sas_token: self.sas_token.clone(),
+        };
+
+        let cred_loader = AzureStorageLoader::new(config_loader);
+        // File service requires a newer service version than the default
+        // one, which will be set by `AzfileCore::sign`.
+        let signer = AzureStorageSigner::new().omit_service_version();
+
+        debug!("backend build finished: {:?}", &self);
+        Ok(AzfileBackend {
+            core: Arc::new(AzfileCore {
+                share_name: self.share_name.clone(),
+                root,
+                endpoint,
+                client,
+                loader: cred_loader,
+                signer,
+            }),
+        })
+    }
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = AzfileBuilder::default();
+
+        map.get("root").map(|v| builder.root(v));
+        map.get("share_name").map(|v| builder.share_name(v));
+        map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("account_name").map(|v| builder.account_name(v));
+        map.get("account_key").map(|v| builder.account_key(v));
+        map.get("sas_token").map(|v| builder.sas_token(v));
+
+        builder
+    }
+}
+
+/// Backend for azfile services.
+#[derive(Debug, Clone)]
+pub struct AzfileBackend {
+    core: Arc<AzfileCore>,
+}
+
+#[async_trait]
+impl Accessor for AzfileBackend {
+    type Reader = IncomingAsyncBody;
+    type BlockingReader = ();
+    type Writer = AzfileWriter;
+    type BlockingWriter = ();
+    type Pager = AzfilePager;
+    type BlockingPager = ();
+
+    fn info(&self) -> AccessorInfo {
+        let mut am = AccessorInfo::default();
+        am.set_scheme(Scheme::Azfile)
+            .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
+            .set_name(&self.core.share_name)
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Append
+                    | AccessorCapability::Rename
+                    | AccessorCapability::List
+                    | AccessorCapability::WriteWithContentType
+                    | AccessorCapability::WriteWithContentDisposition
+                    | AccessorCapability::ListWithLimit,
+            )
+            .set_hints(AccessorHint::ReadStreamable);
+
+        am
+    }
+
+    async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        self.core.azfile_ensure_parent_dirs(path).await?;
+        self.core.azfile_create_dir_if_not_exists(path).await?;
+
+        Ok(RpCreate::default())
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let resp = self.core.azfile_read(path, args.range()).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
+                let meta = parse_into_metadata(path, resp.headers())?;
+                Ok((RpRead::with_metadata(meta), resp.into_body()))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        Ok((
+            RpWrite::default(),
+            AzfileWriter::new(self.core.clone(), args, path.to_string()),
+        ))
+    }
+
+    async fn rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
+        self.core.azfile_ensure_parent_dirs(to).await?;
+
+        let resp = self.core.azfile_rename(from, to).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpRename::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        // Stat root always returns a DIR.
+        if path == "/" {
+            return Ok(RpStat::new(Metadata::new(EntryMode::DIR)));
+        }
+
+        let resp = if path.ends_with('/') {
+            self.core.azfile_get_directory_properties(path).await?
+        } else {
+            self.core.azfile_get_file_properties(path).await?
+        };
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        let resp = if path.ends_with('/') {
+            self.core.azfile_delete_directory(path).await?
+        } else {
+            self.core.azfile_delete_file(path).await?
+        };
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::ACCEPTED | StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(RpDelete::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let op = AzfilePager::new(self.core.clone(), path.to_string(), args.limit());
+
+        Ok((RpList::default(), op))
+    }
+}
+
+fn infer_storage_name_from_endpoint(endpoint: &str) -> Option<String> {
+    let endpoint: &str = endpoint
+        .strip_prefix("http://")
+        .or_else(|| endpoint.strip_prefix("https://"))
+        .unwrap_or(endpoint);
+
+    let mut parts = endpoint.splitn(2, '.');
+    let storage_name = parts.next();
+    let endpoint_suffix = parts
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .to_lowercase();
+
+    if KNOWN_AZFILE_ENDPOINT_SUFFIX
+        .iter()
+        .any(|s| *s == endpoint_suffix.as_str())
+    {
+        storage_name.map(|s| s.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_storage_name_from_endpoint() {
+        let endpoint = "https://account.file.core.windows.net";
+        let storage_name = infer_storage_name_from_endpoint(endpoint);
+        assert_eq!(storage_name, Some("account".to_string()));
+
+        let endpoint = "https://account.file.core.chinacloudapi.cn/";
+        let storage_name = infer_storage_name_from_endpoint(endpoint);
+        assert_eq!(storage_name, Some("account".to_string()));
+
+        let endpoint = "http://127.0.0.1:10004/devstoreaccount1";
+        let storage_name = infer_storage_name_from_endpoint(endpoint);
+        assert_eq!(storage_name, None);
+    }
+
+    #[test]
+    fn test_builder_sas_token_conflicts_with_account_key() {
+        let mut builder = AzfileBuilder::default();
+        builder
+            .endpoint("https://account.file.core.windows.net")
+            .share_name("share")
+            .account_key("account-key")
+            .sas_token("?sv=2021-06-08&sig=xxx");
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_builder_requires_share_name() {
+        let mut builder = AzfileBuilder::default();
+        builder.endpoint("https://account.file.core.windows.net");
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
@@ -0,0 +1,394 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Write;
+
+use bytes::Bytes;
+use http::header::CONTENT_LENGTH;
+use http::header::RANGE;
+use http::HeaderValue;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use reqsign::AzureStorageCredential;
+use reqsign::AzureStorageLoader;
+use reqsign::AzureStorageSigner;
+
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+/// Rename File API requires `2021-04-10` and later.
+const AZFILE_VERSION: &str = "2021-12-02";
+
+const X_MS_VERSION: &str = "x-ms-version";
+const X_MS_TYPE: &str = "x-ms-type";
+const X_MS_CONTENT_LENGTH: &str = "x-ms-content-length";
+const X_MS_CONTENT_TYPE: &str = "x-ms-content-type";
+const X_MS_CONTENT_DISPOSITION: &str = "x-ms-content-disposition";
+const X_MS_RANGE: &str = "x-ms-range";
+const X_MS_WRITE: &str = "x-ms-write";
+const X_MS_FILE_RENAME_SOURCE: &str = "x-ms-file-rename-source";
+const X_MS_FILE_RENAME_REPLACE_IF_EXISTS: &str = "x-ms-file-rename-replace-if-exists";
+
+pub struct AzfileCore {
+    pub share_name: String,
+    pub root: String,
+    pub endpoint: String,
+
+    pub client: HttpClient,
+    pub loader: AzureStorageLoader,
+    pub signer: AzureStorageSigner,
+}
+
+impl Debug for AzfileCore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzfileCore")
+            .field("share_name", &self.share_name)
+            .field("root", &self.root)
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzfileCore {
+    async fn load_credential(&self) -> Result<AzureStorageCredential> {
+        let cred = self
+            .loader
+            .load()
+            .await
+            .map_err(new_request_credential_error)?;
+
+        if let Some(cred) = cred {
+            Ok(cred)
+        } else {
+            Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "no valid credential found",
+            ))
+        }
+    }
+
+    /// Signer has been built with `omit_service_version`, so that we can
+    /// use the service version required by file service here.
+    pub async fn sign<T>(&self, req: &mut Request<T>) -> Result<()> {
+        req.headers_mut()
+            .insert(X_MS_VERSION, HeaderValue::from_static(AZFILE_VERSION));
+
+        let cred = self.load_credential().await?;
+        self.signer.sign(req, &cred).map_err(new_request_sign_error)
+    }
+
+    #[inline]
+    pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
+        self.client.send(req).await
+    }
+
+    /// Build the url of given path, trailing `/` of dirs will be trimmed.
+    fn url(&self, path: &str) -> String {
+        let p = build_abs_path(&self.root, path);
+        let p = p.trim_end_matches('/');
+
+        if p.is_empty() {
+            format!("{}/{}", self.endpoint, self.share_name)
+        } else {
+            format!(
+                "{}/{}/{}",
+                self.endpoint,
+                self.share_name,
+                percent_encode_path(p)
+            )
+        }
+    }
+}
+
+impl AzfileCore {
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/get-file
+    pub async fn azfile_read(
+        &self,
+        path: &str,
+        range: BytesRange,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::get(&self.url(path));
+
+        if !range.is_full() {
+            // azfile doesn't support read with suffix range.
+            if range.offset().is_none() && range.size().is_some() {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "azfile doesn't support read with suffix range",
+                ));
+            }
+
+            req = req.header(RANGE, range.to_header());
+        }
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Create a new file or replace the existing one.
+    ///
+    /// File in azfile must be sized while creating, and the content is
+    /// initialized to zero bytes.
+    ///
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/create-file
+    pub async fn azfile_create_file(
+        &self,
+        path: &str,
+        size: u64,
+        content_type: Option<&str>,
+        content_disposition: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::put(&self.url(path))
+            .header(X_MS_TYPE, "file")
+            .header(X_MS_CONTENT_LENGTH, size)
+            .header(CONTENT_LENGTH, 0);
+
+        if let Some(ty) = content_type {
+            req = req.header(X_MS_CONTENT_TYPE, ty)
+        }
+        if let Some(pos) = content_disposition {
+            req = req.header(X_MS_CONTENT_DISPOSITION, pos)
+        }
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Resize the file via `Set File Properties`.
+    ///
+    /// HTTP headers of the file will be cleared if not specified, so we
+    /// need to carry `content_type` and `content_disposition` again.
+    ///
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/set-file-properties
+    pub async fn azfile_resize_file(
+        &self,
+        path: &str,
+        size: u64,
+        content_type: Option<&str>,
+        content_disposition: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?comp=properties", self.url(path));
+
+        let mut req = Request::put(&url)
+            .header(X_MS_CONTENT_LENGTH, size)
+            .header(CONTENT_LENGTH, 0);
+
+        if let Some(ty) = content_type {
+            req = req.header(X_MS_CONTENT_TYPE, ty)
+        }
+        if let Some(pos) = content_disposition {
+            req = req.header(X_MS_CONTENT_DISPOSITION, pos)
+        }
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Write bytes into the range starting at `offset`, the range must
+    /// be inside current file size and no larger than 4 MiB.
+    ///
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/put-range
+    pub async fn azfile_put_range(
+        &self,
+        path: &str,
+        offset: u64,
+        bs: Bytes,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        debug_assert!(!bs.is_empty(), "range to put must not be empty");
+
+        let url = format!("{}?comp=range", self.url(path));
+
+        let mut req = Request::put(&url)
+            .header(X_MS_WRITE, "update")
+            .header(
+                X_MS_RANGE,
+                BytesRange::new(Some(offset), Some(bs.len() as u64)).to_header(),
+            )
+            .header(CONTENT_LENGTH, bs.len())
+            .body(AsyncBody::Bytes(bs))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/get-file-properties
+    pub async fn azfile_get_file_properties(
+        &self,
+        path: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::head(&self.url(path))
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/get-directory-properties
+    pub async fn azfile_get_directory_properties(
+        &self,
+        path: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?restype=directory", self.url(path));
+
+        let mut req = Request::head(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/delete-file2
+    pub async fn azfile_delete_file(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::delete(&self.url(path))
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Only empty directory could be deleted.
+    ///
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/delete-directory
+    pub async fn azfile_delete_directory(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?restype=directory", self.url(path));
+
+        let mut req = Request::delete(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/create-directory
+    pub async fn azfile_create_directory(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?restype=directory", self.url(path));
+
+        let mut req = Request::put(&url)
+            .header(CONTENT_LENGTH, 0)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Rename file and replace the target if exists.
+    ///
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/rename-file
+    pub async fn azfile_rename(&self, from: &str, to: &str) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?comp=rename", self.url(to));
+
+        let mut req = Request::put(&url)
+            .header(X_MS_FILE_RENAME_SOURCE, self.url(from))
+            .header(X_MS_FILE_RENAME_REPLACE_IF_EXISTS, "true")
+            .header(CONTENT_LENGTH, 0)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/list-directories-and-files
+    pub async fn azfile_list(
+        &self,
+        path: &str,
+        marker: &str,
+        limit: Option<usize>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut url = format!(
+            "{}?restype=directory&comp=list&include=Timestamps,ETag",
+            self.url(path)
+        );
+        if let Some(limit) = limit {
+            write!(url, "&maxresults={limit}").expect("write into string must succeed");
+        }
+        if !marker.is_empty() {
+            write!(url, "&marker={}", percent_encode_path(marker))
+                .expect("write into string must succeed");
+        }
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
+    /// Create all parent dirs of given path, azfile requires the parent
+    /// dir exists before creating files or dirs under it.
+    pub async fn azfile_ensure_parent_dirs(&self, path: &str) -> Result<()> {
+        let parts: Vec<&str> = path
+            .trim_end_matches('/')
+            .split('/')
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        for idx in 1..parts.len() {
+            let dir = format!("{}/", parts[..idx].join("/"));
+            self.azfile_create_dir_if_not_exists(&dir).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn azfile_create_dir_if_not_exists(&self, path: &str) -> Result<()> {
+        let resp = self.azfile_create_directory(path).await?;
+
+        match resp.status() {
+            StatusCode::CREATED => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            StatusCode::CONFLICT if is_resource_already_exists(&resp) => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
+fn is_resource_already_exists(resp: &Response<IncomingAsyncBody>) -> bool {
+    resp.headers()
+        .get("x-ms-error-code")
+        .map(|v| v == "ResourceAlreadyExists")
+        .unwrap_or_default()
+}
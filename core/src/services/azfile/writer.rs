@@ -0,0 +1,188 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::StatusCode;
+
+use super::core::AzfileCore;
+use super::error::parse_error;
+use crate::ops::OpWrite;
+use crate::raw::*;
+use crate::*;
+
+/// Put Range allows up to 4 MiB for a range.
+const MAX_RANGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// AzfileWriter writes files via `Create File` and `Put Range`.
+///
+/// File in azfile must be sized while creating:
+///
+/// - `write` knows the whole content, so the file will be created with
+///   its final size directly.
+/// - `append` creates an empty file at first, and resizes the file before
+///   putting every range.
+pub struct AzfileWriter {
+    core: Arc<AzfileCore>,
+
+    op: OpWrite,
+    path: String,
+
+    /// The size of file on server, `None` means file is not created yet.
+    size: Option<u64>,
+}
+
+impl AzfileWriter {
+    pub fn new(core: Arc<AzfileCore>, op: OpWrite, path: String) -> Self {
+        AzfileWriter {
+            core,
+            op,
+            path,
+            size: None,
+        }
+    }
+
+    async fn create_file(&mut self, size: u64) -> Result<()> {
+        self.core.azfile_ensure_parent_dirs(&self.path).await?;
+
+        let resp = self
+            .core
+            .azfile_create_file(
+                &self.path,
+                size,
+                self.op.content_type(),
+                self.op.content_disposition(),
+            )
+            .await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::CREATED => {
+                resp.into_body().consume().await?;
+                self.size = Some(size);
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::azfile_create_file")),
+        }
+    }
+
+    async fn resize_file(&mut self, size: u64) -> Result<()> {
+        let resp = self
+            .core
+            .azfile_resize_file(
+                &self.path,
+                size,
+                self.op.content_type(),
+                self.op.content_disposition(),
+            )
+            .await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                self.size = Some(size);
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::azfile_resize_file")),
+        }
+    }
+
+    async fn put_ranges(&self, offset: u64, bs: Bytes) -> Result<()> {
+        let mut pos = 0;
+        while pos < bs.len() {
+            let size = MAX_RANGE_SIZE.min(bs.len() - pos);
+
+            let resp = self
+                .core
+                .azfile_put_range(&self.path, offset + pos as u64, bs.slice(pos..pos + size))
+                .await?;
+
+            let status = resp.status();
+            match status {
+                StatusCode::CREATED => {
+                    resp.into_body().consume().await?;
+                }
+                _ => {
+                    return Err(parse_error(resp)
+                        .await?
+                        .with_operation("Backend::azfile_put_range"))
+                }
+            }
+
+            pos += size;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl oio::Write for AzfileWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.create_file(bs.len() as u64).await?;
+        self.put_ranges(0, bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        let offset = match self.size {
+            Some(size) => size,
+            None => {
+                self.create_file(0).await?;
+                0
+            }
+        };
+        if bs.is_empty() {
+            return Ok(());
+        }
+
+        self.resize_file(offset + bs.len() as u64).await?;
+        self.put_ranges(offset, bs).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        if self.size.take().is_none() {
+            return Ok(());
+        }
+
+        let resp = self.core.azfile_delete_file(&self.path).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::ACCEPTED | StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Make sure the file exists even if nothing has been written.
+        if self.size.is_none() {
+            self.create_file(0).await?;
+        }
+
+        Ok(())
+    }
+}
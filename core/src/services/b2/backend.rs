@@ -0,0 +1,461 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http::StatusCode;
+use log::debug;
+
+use super::core::constants;
+use super::core::B2Core;
+use super::core::DEFAULT_AUTH_ENDPOINT;
+use super::error::parse_error;
+use super::pager::B2Pager;
+use super::writer::B2Writer;
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// The recommended part size of B2: 100 MB.
+const DEFAULT_PART_SIZE: usize = 100 * 1000 * 1000;
+/// All parts except the last one must be at least 5 MB.
+const MIN_PART_SIZE: usize = 5 * 1000 * 1000;
+/// Files and parts are limited to 5 GB.
+const MAX_PART_SIZE: u64 = 5 * 1000 * 1000 * 1000;
+
+/// [Backblaze B2](https://www.backblaze.com/b2/cloud-storage.html) services
+/// support via its [native API](https://www.backblaze.com/b2/docs/).
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [x] scan
+/// - [ ] presign
+/// - [ ] blocking
+///
+/// # Notes
+///
+/// - Content larger than `part_size` will be uploaded via the large file API.
+/// - B2 keeps all versions of a file, `delete` will delete all of them
+///   (and cancel unfinished large files) so that the file will not be
+///   visible again.
+///
+/// # Configuration
+///
+/// - `root`: Set the work dir for backend.
+/// - `bucket`: Set the bucket name for backend.
+/// - `bucket_id`: Set the bucket id for backend.
+/// - `application_key_id`: Set the application key id for backend.
+/// - `application_key`: Set the application key for backend.
+/// - `part_size`: Set the part size of large files, default to 100 MB.
+///
+/// Refer to public API docs for more information.
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::B2;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     // Create b2 backend builder.
+///     let mut builder = B2::default();
+///
+///     // Set the root for b2, all operations will happen under this root.
+///     //
+///     // NOTE: the root must be absolute path.
+///     builder.root("/path/to/dir");
+///     // Set the bucket name and bucket id, both are required.
+///     builder.bucket("opendal");
+///     builder.bucket_id("e73ede9969c64427a54b0c1d");
+///     // Set the application key id and application key.
+///     builder.application_key_id("application_key_id");
+///     builder.application_key("application_key");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct B2Builder {
+    root: Option<String>,
+    bucket: Option<String>,
+    bucket_id: Option<String>,
+    application_key_id: Option<String>,
+    application_key: Option<String>,
+    part_size: Option<usize>,
+    http_client: Option<HttpClient>,
+}
+
+impl Debug for B2Builder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Builder");
+
+        ds.field("root", &self.root);
+        ds.field("bucket", &self.bucket);
+        ds.field("bucket_id", &self.bucket_id);
+        ds.field("part_size", &self.part_size);
+
+        if self.application_key_id.is_some() {
+            ds.field("application_key_id", &"<redacted>");
+        }
+        if self.application_key.is_some() {
+            ds.field("application_key", &"<redacted>");
+        }
+
+        ds.finish()
+    }
+}
+
+impl B2Builder {
+    /// Set root of this backend.
+    ///
+    /// All operations will happen under this root.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string())
+        }
+
+        self
+    }
+
+    /// Set bucket name of this backend.
+    pub fn bucket(&mut self, bucket: &str) -> &mut Self {
+        if !bucket.is_empty() {
+            self.bucket = Some(bucket.to_string());
+        }
+
+        self
+    }
+
+    /// Set bucket id of this backend.
+    ///
+    /// Bucket id is required by the native API, which could be found in the
+    /// bucket list of B2 web console.
+    pub fn bucket_id(&mut self, bucket_id: &str) -> &mut Self {
+        if !bucket_id.is_empty() {
+            self.bucket_id = Some(bucket_id.to_string());
+        }
+
+        self
+    }
+
+    /// Set application key id of this backend.
+    pub fn application_key_id(&mut self, application_key_id: &str) -> &mut Self {
+        if !application_key_id.is_empty() {
+            self.application_key_id = Some(application_key_id.to_string());
+        }
+
+        self
+    }
+
+    /// Set application key of this backend.
+    pub fn application_key(&mut self, application_key: &str) -> &mut Self {
+        if !application_key.is_empty() {
+            self.application_key = Some(application_key.to_string());
+        }
+
+        self
+    }
+
+    /// Set the part size of large files.
+    ///
+    /// Content larger than part size will be uploaded via the large file
+    /// API. Part size must be in range `[5 MB, 5 GB]`, default to 100 MB.
+    pub fn part_size(&mut self, part_size: usize) -> &mut Self {
+        self.part_size = Some(part_size);
+
+        self
+    }
+
+    /// Specify the http client that used by this service.
+    ///
+    /// # Notes
+    ///
+    /// This API is part of OpenDAL's Raw API. `HttpClient` could be changed
+    /// during minor updates.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl Builder for B2Builder {
+    type Accessor = B2Backend;
+    const SCHEME: Scheme = Scheme::B2;
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        debug!("backend build started: {:?}", &self);
+
+        let root = normalize_root(&self.root.take().unwrap_or_default());
+        debug!("backend use root {}", root);
+
+        let bucket = match &self.bucket {
+            Some(v) => Ok(v.clone()),
+            None => Err(Error::new(ErrorKind::ConfigInvalid, "bucket is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::B2)),
+        }?;
+        debug!("backend use bucket {}", &bucket);
+
+        let bucket_id = match &self.bucket_id {
+            Some(v) => Ok(v.clone()),
+            None => Err(Error::new(ErrorKind::ConfigInvalid, "bucket_id is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::B2)),
+        }?;
+        debug!("backend use bucket_id {}", &bucket_id);
+
+        let (application_key_id, application_key) =
+            match (&self.application_key_id, &self.application_key) {
+                (Some(id), Some(key)) => Ok((id.clone(), key.clone())),
+                _ => Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "application_key_id and application_key are required",
+                )
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::B2)),
+            }?;
+
+        let part_size = self.part_size.unwrap_or(DEFAULT_PART_SIZE);
+        if part_size < MIN_PART_SIZE || part_size as u64 > MAX_PART_SIZE {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "part_size must be in range [5 MB, 5 GB]",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::B2)
+            .with_context("part_size", part_size.to_string()));
+        }
+
+        let client = if let Some(client) = self.http_client.take() {
+            client
+        } else {
+            HttpClient::new().map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::B2)
+            })?
+        };
+
+        debug!("backend build finished: {:?}", &self);
+        Ok(B2Backend {
+            core: Arc::new(B2Core {
+                root,
+                bucket,
+                bucket_id,
+                application_key_id,
+                application_key,
+                part_size,
+                auth_endpoint: DEFAULT_AUTH_ENDPOINT.to_string(),
+                client,
+                auth: Default::default(),
+                upload_urls: Default::default(),
+            }),
+        })
+    }
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = B2Builder::default();
+
+        map.get("root").map(|v| builder.root(v));
+        map.get("bucket").map(|v| builder.bucket(v));
+        map.get("bucket_id").map(|v| builder.bucket_id(v));
+        map.get("application_key_id")
+            .map(|v| builder.application_key_id(v));
+        map.get("application_key")
+            .map(|v| builder.application_key(v));
+        map.get("part_size")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.part_size(v));
+
+        builder
+    }
+}
+
+/// Backend for b2 services.
+#[derive(Debug, Clone)]
+pub struct B2Backend {
+    core: Arc<B2Core>,
+}
+
+#[async_trait]
+impl Accessor for B2Backend {
+    type Reader = IncomingAsyncBody;
+    type BlockingReader = ();
+    type Writer = B2Writer;
+    type BlockingWriter = ();
+    type Pager = B2Pager;
+    type BlockingPager = ();
+
+    fn info(&self) -> AccessorInfo {
+        let mut am = AccessorInfo::default();
+        am.set_scheme(Scheme::B2)
+            .set_root(&self.core.root)
+            .set_name(&self.core.bucket)
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Append
+                    | AccessorCapability::List
+                    | AccessorCapability::Scan
+                    | AccessorCapability::WriteWithContentType
+                    | AccessorCapability::WriteWithContentDisposition
+                    | AccessorCapability::ListWithLimit,
+            )
+            .set_hints(AccessorHint::ReadStreamable | AccessorHint::ImplicitDir);
+
+        am
+    }
+
+    async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        let resp = self
+            .core
+            .upload_file(path, &OpWrite::default(), bytes::Bytes::new())
+            .await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpCreate::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let resp = self.core.download_file_by_name(path, args.range()).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
+                let meta = parse_into_metadata(path, resp.headers())?;
+                Ok((RpRead::with_metadata(meta), resp.into_body()))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        Ok((
+            RpWrite::default(),
+            B2Writer::new(self.core.clone(), args, path.to_string()),
+        ))
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        // Stat root always returns a DIR.
+        if path == "/" {
+            return Ok(RpStat::new(Metadata::new(EntryMode::DIR)));
+        }
+
+        let resp = self.core.head_file_by_name(path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let mut meta = parse_into_metadata(path, resp.headers())?;
+                if meta.last_modified().is_none() {
+                    if let Some(v) = resp.headers().get(constants::X_BZ_UPLOAD_TIMESTAMP) {
+                        let ts = v
+                            .to_str()
+                            .ok()
+                            .and_then(|v| v.parse::<i64>().ok())
+                            .ok_or_else(|| {
+                                Error::new(
+                                    ErrorKind::Unexpected,
+                                    "header value of x-bz-upload-timestamp is not valid integer",
+                                )
+                            })?;
+                        meta.set_last_modified(parse_datetime_from_from_timestamp_millis(ts)?);
+                    }
+                }
+                Ok(RpStat::new(meta))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        self.core.delete_file(path).await?;
+
+        Ok(RpDelete::default())
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let op = B2Pager::new(self.core.clone(), path, Some("/"), args.limit());
+
+        Ok((RpList::default(), op))
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        let op = B2Pager::new(self.core.clone(), path, None, args.limit());
+
+        Ok((RpScan::default(), op))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_from_map() {
+        let mut map = HashMap::new();
+        map.insert("bucket".to_string(), "opendal".to_string());
+        map.insert(
+            "bucket_id".to_string(),
+            "e73ede9969c64427a54b0c1d".to_string(),
+        );
+        map.insert("application_key_id".to_string(), "key_id".to_string());
+        map.insert("application_key".to_string(), "key".to_string());
+        map.insert("part_size".to_string(), "5000000".to_string());
+
+        let mut builder = B2Builder::from_map(map);
+        assert_eq!(builder.part_size, Some(5000000));
+
+        builder.build().expect("build must succeed");
+    }
+
+    #[test]
+    fn test_builder_requires_application_key() {
+        let mut builder = B2Builder::default();
+        builder
+            .bucket("opendal")
+            .bucket_id("e73ede9969c64427a54b0c1d")
+            .application_key_id("key_id");
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
@@ -0,0 +1,828 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_TYPE;
+use http::header::RANGE;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use log::debug;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+
+use super::error::parse_error;
+use crate::ops::OpWrite;
+use crate::raw::*;
+use crate::*;
+
+pub mod constants {
+    pub const X_BZ_FILE_NAME: &str = "x-bz-file-name";
+    pub const X_BZ_CONTENT_SHA1: &str = "x-bz-content-sha1";
+    pub const X_BZ_PART_NUMBER: &str = "x-bz-part-number";
+    pub const X_BZ_UPLOAD_TIMESTAMP: &str = "x-bz-upload-timestamp";
+    pub const X_BZ_INFO_CONTENT_DISPOSITION: &str = "x-bz-info-b2-content-disposition";
+}
+
+/// The endpoint to authorize account.
+pub const DEFAULT_AUTH_ENDPOINT: &str = "https://api.backblazeb2.com";
+
+/// Auth token is valid for at most 24 hours, we will refresh it before
+/// it's going to expire.
+const AUTH_TOKEN_TTL_HOURS: i64 = 23;
+
+pub struct B2Core {
+    pub root: String,
+    pub bucket: String,
+    pub bucket_id: String,
+    pub application_key_id: String,
+    pub application_key: String,
+    /// Content larger than part size will be uploaded as large file.
+    pub part_size: usize,
+    /// Always [`DEFAULT_AUTH_ENDPOINT`] except in tests.
+    pub auth_endpoint: String,
+
+    pub client: HttpClient,
+
+    /// Cached result of `b2_authorize_account`.
+    pub auth: Mutex<Option<AuthInfo>>,
+    /// Upload urls that are not in use.
+    ///
+    /// An upload url can only be used by one upload at the same time, so
+    /// it will be taken out of the pool while uploading and put back after
+    /// the upload succeeded.
+    pub upload_urls: Mutex<Vec<UploadUrl>>,
+}
+
+impl Debug for B2Core {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("B2Core")
+            .field("root", &self.root)
+            .field("bucket", &self.bucket)
+            .field("bucket_id", &self.bucket_id)
+            .field("part_size", &self.part_size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthInfo {
+    pub authorization_token: String,
+    pub api_url: String,
+    pub download_url: String,
+    pub expire_at: DateTime<Utc>,
+}
+
+/// The url and token returned by `b2_get_upload_url` and `b2_get_upload_part_url`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadUrl {
+    pub upload_url: String,
+    pub authorization_token: String,
+}
+
+/// Whether we should fetch a new upload url and try again.
+///
+/// Upload urls could expire or be too busy, B2 requires clients to fetch a
+/// new upload url for these errors.
+///
+/// ref: https://www.backblaze.com/b2/docs/uploading.html
+pub fn should_refetch_upload_url(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
+
+impl B2Core {
+    /// Get the auth info, `b2_authorize_account` will be called if there is
+    /// no valid auth info.
+    pub async fn auth_info(&self) -> Result<AuthInfo> {
+        if let Some(info) = self.auth.lock().expect("lock poisoned").as_ref() {
+            if Utc::now() < info.expire_at {
+                return Ok(info.clone());
+            }
+        }
+
+        let url = format!("{}/b2api/v2/b2_authorize_account", self.auth_endpoint);
+        let req = Request::get(&url)
+            .header(
+                AUTHORIZATION,
+                format_authorization_by_basic(&self.application_key_id, &self.application_key)?,
+            )
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp = self.client.send(req).await?;
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_authorize_account"));
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        let output: AuthorizeAccountResponse =
+            serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+        let info = AuthInfo {
+            authorization_token: output.authorization_token,
+            api_url: output.api_url,
+            download_url: output.download_url,
+            expire_at: Utc::now() + Duration::hours(AUTH_TOKEN_TTL_HOURS),
+        };
+        *self.auth.lock().expect("lock poisoned") = Some(info.clone());
+
+        Ok(info)
+    }
+
+    /// Send request and drop the cached auth info if it's rejected, so that
+    /// the next request (retried by `RetryLayer` maybe) will authorize again.
+    #[inline]
+    pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
+        let resp = self.client.send(req).await?;
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            self.auth.lock().expect("lock poisoned").take();
+        }
+
+        Ok(resp)
+    }
+
+    /// Call b2 native API via `POST <api_url>/b2api/v2/<name>` with json body.
+    async fn call_api<T: Serialize>(
+        &self,
+        name: &str,
+        body: &T,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let auth = self.auth_info().await?;
+
+        let bs = serde_json::to_vec(body).map_err(new_json_serialize_error)?;
+
+        let req = Request::post(format!("{}/b2api/v2/{name}", auth.api_url))
+            .header(AUTHORIZATION, auth.authorization_token)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, bs.len())
+            .body(AsyncBody::Bytes(Bytes::from(bs)))
+            .map_err(new_request_build_error)?;
+
+        self.send(req).await
+    }
+
+    fn download_url(&self, auth: &AuthInfo, path: &str) -> String {
+        let p = build_abs_path(&self.root, path);
+
+        format!(
+            "{}/file/{}/{}",
+            auth.download_url,
+            self.bucket,
+            percent_encode_path(&p)
+        )
+    }
+}
+
+impl B2Core {
+    pub async fn download_file_by_name(
+        &self,
+        path: &str,
+        range: BytesRange,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let auth = self.auth_info().await?;
+
+        let mut req = Request::get(self.download_url(&auth, path))
+            .header(AUTHORIZATION, auth.authorization_token);
+
+        if !range.is_full() {
+            req = req.header(RANGE, range.to_header());
+        }
+
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.send(req).await
+    }
+
+    pub async fn head_file_by_name(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let auth = self.auth_info().await?;
+
+        let req = Request::head(self.download_url(&auth, path))
+            .header(AUTHORIZATION, auth.authorization_token)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.send(req).await
+    }
+
+    /// Take an upload url from the pool, or fetch a new one via `b2_get_upload_url`.
+    pub async fn get_upload_url(&self) -> Result<UploadUrl> {
+        if let Some(url) = self.upload_urls.lock().expect("lock poisoned").pop() {
+            return Ok(url);
+        }
+
+        let resp = self
+            .call_api(
+                "b2_get_upload_url",
+                &GetUploadUrlRequest {
+                    bucket_id: &self.bucket_id,
+                },
+            )
+            .await?;
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_get_upload_url"));
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        serde_json::from_slice(&bs).map_err(new_json_deserialize_error)
+    }
+
+    /// Upload file via `b2_upload_file`.
+    ///
+    /// The upload url will be dropped if upload failed, and we will fetch a
+    /// new one and try again once if B2 asks us to do so.
+    pub async fn upload_file(
+        &self,
+        path: &str,
+        args: &OpWrite,
+        bs: Bytes,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let sha1 = format!("{:x}", Sha1::digest(&bs));
+
+        let mut retried = false;
+        loop {
+            let url = self.get_upload_url().await?;
+
+            let mut req = Request::post(&url.upload_url)
+                .header(AUTHORIZATION, &url.authorization_token)
+                .header(constants::X_BZ_FILE_NAME, percent_encode_path(&p))
+                .header(CONTENT_LENGTH, bs.len())
+                .header(
+                    CONTENT_TYPE,
+                    args.content_type().unwrap_or("b2/x-auto").to_string(),
+                )
+                .header(constants::X_BZ_CONTENT_SHA1, &sha1);
+
+            if let Some(pos) = args.content_disposition() {
+                req = req.header(
+                    constants::X_BZ_INFO_CONTENT_DISPOSITION,
+                    percent_encode_path(pos),
+                );
+            }
+
+            let req = req
+                .body(AsyncBody::Bytes(bs.clone()))
+                .map_err(new_request_build_error)?;
+
+            let resp = match self.client.send(req).await {
+                Ok(resp) => resp,
+                Err(err) if !retried => {
+                    debug!("upload file via {} failed: {err:?}", url.upload_url);
+                    retried = true;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let status = resp.status();
+            if status == StatusCode::OK {
+                self.upload_urls.lock().expect("lock poisoned").push(url);
+                return Ok(resp);
+            }
+            if !retried && should_refetch_upload_url(status) {
+                resp.into_body().consume().await?;
+                retried = true;
+                continue;
+            }
+
+            return Ok(resp);
+        }
+    }
+
+    pub async fn start_large_file(
+        &self,
+        path: &str,
+        args: &OpWrite,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        self.call_api(
+            "b2_start_large_file",
+            &StartLargeFileRequest {
+                bucket_id: &self.bucket_id,
+                file_name: &p,
+                content_type: args.content_type().unwrap_or("b2/x-auto"),
+                file_info: args.content_disposition().map(|v| FileInfo {
+                    b2_content_disposition: v,
+                }),
+            },
+        )
+        .await
+    }
+
+    pub async fn get_upload_part_url(&self, file_id: &str) -> Result<UploadUrl> {
+        let resp = self
+            .call_api("b2_get_upload_part_url", &FileIdRequest { file_id })
+            .await?;
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_get_upload_part_url"));
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        serde_json::from_slice(&bs).map_err(new_json_deserialize_error)
+    }
+
+    /// Upload part via `b2_upload_part`, part number starts from 1.
+    pub async fn upload_part(
+        &self,
+        url: &UploadUrl,
+        part_number: usize,
+        sha1: &str,
+        bs: Bytes,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let req = Request::post(&url.upload_url)
+            .header(AUTHORIZATION, &url.authorization_token)
+            .header(constants::X_BZ_PART_NUMBER, part_number)
+            .header(CONTENT_LENGTH, bs.len())
+            .header(constants::X_BZ_CONTENT_SHA1, sha1)
+            .body(AsyncBody::Bytes(bs))
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
+    pub async fn finish_large_file(
+        &self,
+        file_id: &str,
+        part_sha1_array: &[String],
+    ) -> Result<Response<IncomingAsyncBody>> {
+        self.call_api(
+            "b2_finish_large_file",
+            &FinishLargeFileRequest {
+                file_id,
+                part_sha1_array,
+            },
+        )
+        .await
+    }
+
+    pub async fn cancel_large_file(&self, file_id: &str) -> Result<Response<IncomingAsyncBody>> {
+        self.call_api("b2_cancel_large_file", &FileIdRequest { file_id })
+            .await
+    }
+
+    pub async fn list_file_names(
+        &self,
+        path: &str,
+        delimiter: Option<&str>,
+        start_file_name: Option<&str>,
+        max_file_count: Option<usize>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        self.call_api(
+            "b2_list_file_names",
+            &ListFileNamesRequest {
+                bucket_id: &self.bucket_id,
+                prefix: &p,
+                delimiter,
+                start_file_name,
+                max_file_count,
+            },
+        )
+        .await
+    }
+
+    pub async fn list_file_versions(
+        &self,
+        file_name: &str,
+        start_file_id: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        self.call_api(
+            "b2_list_file_versions",
+            &ListFileVersionsRequest {
+                bucket_id: &self.bucket_id,
+                prefix: file_name,
+                start_file_name: file_name,
+                start_file_id,
+            },
+        )
+        .await
+    }
+
+    pub async fn delete_file_version(
+        &self,
+        file_name: &str,
+        file_id: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        self.call_api(
+            "b2_delete_file_version",
+            &DeleteFileVersionRequest { file_name, file_id },
+        )
+        .await
+    }
+
+    /// Delete all versions of the file.
+    ///
+    /// B2 keeps every version of a file, `b2_delete_file_version` only
+    /// deletes one of them, and the previous version will be visible again.
+    /// So we have to list all versions of the file and delete them one by
+    /// one. Unfinished large files will be canceled.
+    pub async fn delete_file(&self, path: &str) -> Result<()> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut start_file_id: Option<String> = None;
+        loop {
+            let resp = self
+                .list_file_versions(&p, start_file_id.as_deref())
+                .await?;
+            if resp.status() != StatusCode::OK {
+                return Err(parse_error(resp)
+                    .await?
+                    .with_operation("Backend::b2_list_file_versions"));
+            }
+
+            let bs = resp.into_body().bytes().await?;
+            let output: ListFilesResponse =
+                serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+            // Versions of other files sharing the same prefix will be
+            // listed too.
+            for file in output.files.iter().filter(|v| v.file_name == p) {
+                let file_id = match &file.file_id {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let (resp, op) = if file.action == "start" {
+                    (
+                        self.cancel_large_file(file_id).await?,
+                        "Backend::b2_cancel_large_file",
+                    )
+                } else {
+                    (
+                        self.delete_file_version(&file.file_name, file_id).await?,
+                        "Backend::b2_delete_file_version",
+                    )
+                };
+
+                match resp.status() {
+                    StatusCode::OK => resp.into_body().consume().await?,
+                    _ => {
+                        let err = parse_error(resp).await?;
+                        // The version could be deleted by others.
+                        if err.kind() != ErrorKind::NotFound {
+                            return Err(err.with_operation(op));
+                        }
+                    }
+                }
+            }
+
+            match (output.next_file_name, output.next_file_id) {
+                (Some(name), Some(id)) if name == p => start_file_id = Some(id),
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorizeAccountResponse {
+    authorization_token: String,
+    api_url: String,
+    download_url: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GetUploadUrlRequest<'a> {
+    bucket_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartLargeFileRequest<'a> {
+    bucket_id: &'a str,
+    file_name: &'a str,
+    content_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_info: Option<FileInfo<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileInfo<'a> {
+    #[serde(rename = "b2-content-disposition")]
+    b2_content_disposition: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileIdRequest<'a> {
+    file_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FinishLargeFileRequest<'a> {
+    file_id: &'a str,
+    part_sha1_array: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListFileNamesRequest<'a> {
+    bucket_id: &'a str,
+    prefix: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delimiter: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_file_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_file_count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListFileVersionsRequest<'a> {
+    bucket_id: &'a str,
+    prefix: &'a str,
+    start_file_name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_file_id: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeleteFileVersionRequest<'a> {
+    file_name: &'a str,
+    file_id: &'a str,
+}
+
+/// The response of `b2_list_file_names` and `b2_list_file_versions`.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ListFilesResponse {
+    pub files: Vec<File>,
+    pub next_file_name: Option<String>,
+    pub next_file_id: Option<String>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct File {
+    /// `null` for `folder`.
+    pub file_id: Option<String>,
+    pub file_name: String,
+    /// One of `upload`, `start`, `hide` and `folder`.
+    pub action: String,
+    pub content_length: u64,
+    pub content_type: Option<String>,
+    pub content_sha1: Option<String>,
+    pub upload_timestamp: i64,
+}
+
+/// The response of `b2_start_large_file`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartLargeFileResponse {
+    pub file_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::body_partial_json;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    async fn new_core(server: &MockServer) -> B2Core {
+        Mock::given(method("GET"))
+            .and(path("/b2api/v2/b2_authorize_account"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "accountId": "12f634bf3cbe",
+                "authorizationToken": "auth-token",
+                "apiUrl": server.uri(),
+                "downloadUrl": server.uri(),
+                "recommendedPartSize": 100000000,
+                "absoluteMinimumPartSize": 5000000
+            })))
+            // Auth info should be cached.
+            .expect(1)
+            .mount(server)
+            .await;
+
+        B2Core {
+            root: "/".to_string(),
+            bucket: "opendal".to_string(),
+            bucket_id: "bucket-id".to_string(),
+            application_key_id: "key-id".to_string(),
+            application_key: "key".to_string(),
+            part_size: 5 * 1000 * 1000,
+            auth_endpoint: server.uri(),
+            client: HttpClient::new().expect("client must be created"),
+            auth: Default::default(),
+            upload_urls: Default::default(),
+        }
+    }
+
+    fn upload_url_response(server: &MockServer, id: usize) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "bucketId": "bucket-id",
+            "uploadUrl": format!("{}/upload/{id}", server.uri()),
+            "authorizationToken": format!("upload-token-{id}")
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_refetch_expired_upload_url() -> Result<()> {
+        let server = MockServer::start().await;
+        let core = new_core(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_get_upload_url"))
+            .and(header("authorization", "auth-token"))
+            .respond_with(upload_url_response(&server, 1))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_get_upload_url"))
+            .respond_with(upload_url_response(&server, 2))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The first upload url has been expired.
+        Mock::given(method("POST"))
+            .and(path("/upload/1"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+                "status": 401,
+                "code": "expired_auth_token",
+                "message": "Authorization token has expired"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/upload/2"))
+            .and(header("authorization", "upload-token-2"))
+            .and(header(constants::X_BZ_FILE_NAME, "dir/hello.txt"))
+            .and(header(
+                constants::X_BZ_CONTENT_SHA1,
+                "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            // The second upload url should be reused.
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        for _ in 0..2 {
+            let resp = core
+                .upload_file("dir/hello.txt", &OpWrite::default(), Bytes::from("hello"))
+                .await?;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        let urls = core.upload_urls.lock().unwrap().clone();
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].authorization_token, "upload-token-2");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_gives_up_after_refetch() -> Result<()> {
+        let server = MockServer::start().await;
+        let core = new_core(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_get_upload_url"))
+            .respond_with(upload_url_response(&server, 1))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/upload/1"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let resp = core
+            .upload_file("hello.txt", &OpWrite::default(), Bytes::from("hello"))
+            .await?;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        // Failed upload urls should not be reused.
+        assert!(core.upload_urls.lock().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_file_deletes_all_versions() -> Result<()> {
+        let server = MockServer::start().await;
+        let core = new_core(&server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_list_file_versions"))
+            .and(body_partial_json(json!({
+                "bucketId": "bucket-id",
+                "prefix": "a",
+                "startFileName": "a"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {"fileName": "a", "fileId": "3", "action": "hide", "contentLength": 0, "uploadTimestamp": 3},
+                    {"fileName": "a", "fileId": "2", "action": "start", "contentLength": 0, "uploadTimestamp": 2}
+                ],
+                "nextFileName": "a",
+                "nextFileId": "1"
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_list_file_versions"))
+            .and(body_partial_json(json!({"startFileId": "1"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "files": [
+                    {"fileName": "a", "fileId": "1", "action": "upload", "contentLength": 5, "uploadTimestamp": 1},
+                    {"fileName": "ab", "fileId": "0", "action": "upload", "contentLength": 5, "uploadTimestamp": 0}
+                ],
+                "nextFileName": null,
+                "nextFileId": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_delete_file_version"))
+            .and(body_partial_json(json!({"fileName": "a", "fileId": "3"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The version has been deleted by others.
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_delete_file_version"))
+            .and(body_partial_json(json!({"fileName": "a", "fileId": "1"})))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "status": 400,
+                "code": "file_not_present",
+                "message": "File not present: a 1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Unfinished large file should be canceled.
+        Mock::given(method("POST"))
+            .and(path("/b2api/v2/b2_cancel_large_file"))
+            .and(body_partial_json(json!({"fileId": "2"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        core.delete_file("a").await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use http::Response;
+use http::StatusCode;
+use serde::Deserialize;
+use serde_json::de;
+
+use crate::raw::*;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+
+/// The error response of b2 native API.
+///
+/// ref: https://www.backblaze.com/b2/docs/calling.html#error_handling
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct B2Error {
+    pub status: u16,
+    pub code: String,
+    pub message: String,
+}
+
+/// Parse error response into Error.
+pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
+    let (parts, body) = resp.into_parts();
+    let bs = body.bytes().await?;
+
+    let b2_err = de::from_slice::<B2Error>(&bs).ok();
+    let code = b2_err.as_ref().map(|v| v.code.as_str()).unwrap_or_default();
+
+    let (kind, retryable) = match (parts.status, code) {
+        // `b2_delete_file_version` returns `400 file_not_present` for
+        // versions that have been deleted.
+        (StatusCode::NOT_FOUND, _) | (_, "not_found" | "file_not_present") => {
+            (ErrorKind::NotFound, false)
+        }
+        // Auth token is valid for at most 24 hours, it will be refreshed
+        // by the next request.
+        (StatusCode::UNAUTHORIZED, "expired_auth_token") => (ErrorKind::Unexpected, true),
+        (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, _) => {
+            (ErrorKind::PermissionDenied, false)
+        }
+        (
+            StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT,
+            _,
+        ) => (ErrorKind::Unexpected, true),
+        _ => (ErrorKind::Unexpected, false),
+    };
+
+    let message = match b2_err {
+        Some(b2_err) => format!("{b2_err:?}"),
+        None => String::from_utf8_lossy(&bs).into_owned(),
+    };
+
+    let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
+
+    if retryable {
+        err = err.set_temporary();
+    }
+
+    Ok(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    async fn parse(status: u16, body: &str) -> Error {
+        let bs = bytes::Bytes::from(body.to_string());
+        let body = IncomingAsyncBody::new(Box::new(stream::iter(vec![Ok(bs)])), None);
+        let resp = Response::builder().status(status).body(body).unwrap();
+
+        parse_error(resp).await.expect("parse error must succeed")
+    }
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let err = parse(
+            400,
+            r#"{"status": 400, "code": "file_not_present", "message": "File not present: a 4_z"}"#,
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        let err = parse(
+            401,
+            r#"{"status": 401, "code": "expired_auth_token", "message": "Authorization token has expired"}"#,
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+
+        let err = parse(
+            401,
+            r#"{"status": 401, "code": "bad_auth_token", "message": "Invalid authorization token"}"#,
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        let err = parse(503, "service unavailable").await;
+        assert!(err.is_temporary());
+    }
+}
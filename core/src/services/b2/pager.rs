@@ -0,0 +1,167 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http::StatusCode;
+
+use super::core::B2Core;
+use super::core::ListFilesResponse;
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+pub struct B2Pager {
+    core: Arc<B2Core>,
+
+    path: String,
+    delimiter: Option<&'static str>,
+    limit: Option<usize>,
+
+    start_file_name: Option<String>,
+    done: bool,
+}
+
+impl B2Pager {
+    pub fn new(
+        core: Arc<B2Core>,
+        path: &str,
+        delimiter: Option<&'static str>,
+        limit: Option<usize>,
+    ) -> Self {
+        Self {
+            core,
+            path: path.to_string(),
+            delimiter,
+            limit,
+
+            start_file_name: None,
+            done: false,
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Page for B2Pager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let resp = self
+            .core
+            .list_file_names(
+                &self.path,
+                self.delimiter,
+                self.start_file_name.as_deref(),
+                self.limit,
+            )
+            .await?;
+
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        let output: ListFilesResponse =
+            serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+        self.start_file_name = output.next_file_name;
+        self.done = self.start_file_name.is_none();
+
+        let prefix = build_abs_path(&self.core.root, &self.path);
+        let mut entries = Vec::with_capacity(output.files.len());
+
+        for file in output.files {
+            // Skip the dir itself.
+            if file.file_name == prefix {
+                continue;
+            }
+
+            let meta = if file.action == "folder" || file.file_name.ends_with('/') {
+                Metadata::new(EntryMode::DIR)
+            } else {
+                let mut meta =
+                    Metadata::new(EntryMode::FILE).with_content_length(file.content_length);
+                if let Some(v) = &file.content_type {
+                    meta.set_content_type(v);
+                }
+                if file.upload_timestamp > 0 {
+                    meta.set_last_modified(parse_datetime_from_from_timestamp_millis(
+                        file.upload_timestamp,
+                    )?);
+                }
+                meta
+            };
+
+            let path = build_rel_path(&self.core.root, &file.file_name);
+            entries.push(oio::Entry::new(&path, meta));
+        }
+
+        Ok(Some(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_output() {
+        let bs = r#"{
+            "files": [
+                {
+                    "accountId": "12f634bf3cbe",
+                    "action": "folder",
+                    "bucketId": "e73ede9969c64427a54b0c1d",
+                    "contentLength": 0,
+                    "contentSha1": null,
+                    "contentType": null,
+                    "fileId": null,
+                    "fileInfo": {},
+                    "fileName": "dir/sub/",
+                    "uploadTimestamp": 0
+                },
+                {
+                    "accountId": "12f634bf3cbe",
+                    "action": "upload",
+                    "bucketId": "e73ede9969c64427a54b0c1d",
+                    "contentLength": 7,
+                    "contentSha1": "dc724af18fbdd4e59189f5fe768a5f8311527050",
+                    "contentType": "text/plain",
+                    "fileId": "4_ze73ede9969c64427a54b0c1d_f1013bc1e0cb2f65e_d20230520_m145321_c002_v0001018_t0009",
+                    "fileInfo": {},
+                    "fileName": "dir/hello.txt",
+                    "uploadTimestamp": 1684594401000
+                }
+            ],
+            "nextFileName": "dir/world.txt"
+        }"#;
+
+        let out: ListFilesResponse = serde_json::from_str(bs).expect("must success");
+        assert_eq!(out.files.len(), 2);
+        assert_eq!(out.files[0].action, "folder");
+        assert_eq!(out.files[0].file_name, "dir/sub/");
+        assert_eq!(out.files[1].action, "upload");
+        assert_eq!(out.files[1].content_length, 7);
+        assert_eq!(out.files[1].content_type.as_deref(), Some("text/plain"));
+        assert_eq!(out.files[1].upload_timestamp, 1684594401000);
+        assert_eq!(out.next_file_name.as_deref(), Some("dir/world.txt"));
+        assert_eq!(out.next_file_id, None);
+    }
+}
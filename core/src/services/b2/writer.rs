@@ -0,0 +1,260 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use bytes::BytesMut;
+use http::StatusCode;
+use log::debug;
+use sha1::Digest;
+use sha1::Sha1;
+
+use super::core::should_refetch_upload_url;
+use super::core::B2Core;
+use super::core::StartLargeFileResponse;
+use super::core::UploadUrl;
+use super::error::parse_error;
+use crate::ops::OpWrite;
+use crate::raw::*;
+use crate::*;
+
+/// B2Writer uploads content larger than `part_size` via the
+/// [large file API](https://www.backblaze.com/b2/docs/large_files.html).
+///
+/// A large file must have at least 2 parts, and all parts except the last
+/// one must be at least 5 MB.
+pub struct B2Writer {
+    core: Arc<B2Core>,
+
+    op: OpWrite,
+    path: String,
+
+    /// The file id of the large file, `None` means it's not started yet.
+    file_id: Option<String>,
+    /// The upload url of parts, which is bound to the large file.
+    part_url: Option<UploadUrl>,
+    part_sha1_array: Vec<String>,
+    buffer: BytesMut,
+    /// Whether the file has been written by `write`.
+    written: bool,
+}
+
+impl B2Writer {
+    pub fn new(core: Arc<B2Core>, op: OpWrite, path: String) -> Self {
+        B2Writer {
+            core,
+            op,
+            path,
+
+            file_id: None,
+            part_url: None,
+            part_sha1_array: Vec::new(),
+            buffer: BytesMut::new(),
+            written: false,
+        }
+    }
+
+    async fn upload_file(&self, bs: Bytes) -> Result<()> {
+        let resp = self.core.upload_file(&self.path, &self.op, bs).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_upload_file")),
+        }
+    }
+
+    async fn start_large_file(&mut self) -> Result<String> {
+        if let Some(file_id) = &self.file_id {
+            return Ok(file_id.clone());
+        }
+
+        let resp = self.core.start_large_file(&self.path, &self.op).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let output: StartLargeFileResponse =
+                    serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+                self.file_id = Some(output.file_id.clone());
+                Ok(output.file_id)
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_start_large_file")),
+        }
+    }
+
+    /// Upload a part, the part url will be fetched again and retried once
+    /// if it's expired or too busy.
+    async fn upload_part(&mut self, bs: Bytes) -> Result<()> {
+        let file_id = self.start_large_file().await?;
+        let part_number = self.part_sha1_array.len() + 1;
+        let sha1 = format!("{:x}", Sha1::digest(&bs));
+
+        let mut retried = false;
+        loop {
+            let url = match self.part_url.take() {
+                Some(url) => url,
+                None => self.core.get_upload_part_url(&file_id).await?,
+            };
+
+            let resp = match self
+                .core
+                .upload_part(&url, part_number, &sha1, bs.clone())
+                .await
+            {
+                Ok(resp) => resp,
+                Err(err) if !retried => {
+                    debug!("upload part via {} failed: {err:?}", url.upload_url);
+                    retried = true;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let status = resp.status();
+            if status == StatusCode::OK {
+                resp.into_body().consume().await?;
+                self.part_url = Some(url);
+                self.part_sha1_array.push(sha1);
+                return Ok(());
+            }
+            if !retried && should_refetch_upload_url(status) {
+                resp.into_body().consume().await?;
+                retried = true;
+                continue;
+            }
+
+            return Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_upload_part"));
+        }
+    }
+
+    async fn finish_large_file(&mut self) -> Result<()> {
+        let file_id = self.start_large_file().await?;
+
+        let resp = self
+            .core
+            .finish_large_file(&file_id, &self.part_sha1_array)
+            .await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                self.file_id = None;
+                self.part_url = None;
+                self.part_sha1_array.clear();
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_finish_large_file")),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Write for B2Writer {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        let part_size = self.core.part_size;
+
+        if bs.len() <= part_size {
+            self.upload_file(bs).await?;
+        } else {
+            let mut pos = 0;
+            while pos < bs.len() {
+                let size = part_size.min(bs.len() - pos);
+                self.upload_part(bs.slice(pos..pos + size)).await?;
+                pos += size;
+            }
+            self.finish_large_file().await?;
+        }
+
+        self.written = true;
+        Ok(())
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.buffer.extend_from_slice(&bs);
+
+        // Keep at least one byte in buffer so that the last part uploaded
+        // in `close` is never empty, and the large file always has at least
+        // 2 parts.
+        while self.buffer.len() > self.core.part_size {
+            let bs = self.buffer.split_to(self.core.part_size).freeze();
+            self.upload_part(bs).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.buffer.clear();
+        self.part_url = None;
+        self.part_sha1_array.clear();
+
+        let file_id = match self.file_id.take() {
+            Some(file_id) => file_id,
+            None => return Ok(()),
+        };
+
+        let resp = self.core.cancel_large_file(&file_id).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::b2_cancel_large_file")),
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Content has been written by `write` and nothing appended.
+        if self.written && self.buffer.is_empty() && self.file_id.is_none() {
+            return Ok(());
+        }
+
+        let bs = self.buffer.split().freeze();
+
+        // Small enough to be uploaded as a normal file.
+        if self.file_id.is_none() {
+            self.upload_file(bs).await?;
+        } else {
+            self.upload_part(bs).await?;
+            self.finish_large_file().await?;
+        }
+
+        self.written = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,673 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::header::AUTHORIZATION;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_TYPE;
+use http::Request;
+use http::Response;
+use http::StatusCode;
+use log::debug;
+use percent_encoding::utf8_percent_encode;
+use percent_encoding::AsciiSet;
+use percent_encoding::NON_ALPHANUMERIC;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::error::parse_error;
+use crate::raw::adapters::kv;
+use crate::raw::*;
+use crate::*;
+
+const DEFAULT_ENDPOINT: &str = "https://api.cloudflare.com/client/v4";
+/// Values in cloudflare kv are limited to 25 MiB.
+const MAX_VALUE_SIZE: usize = 25 * 1024 * 1024;
+/// Expiration ttl must be at least 60 seconds.
+const MIN_TTL: Duration = Duration::from_secs(60);
+/// The max count of keys that could be listed in one request.
+const SCAN_LIMIT: usize = 1000;
+
+/// Keys could contain `/`, so they must be encoded as a whole path segment
+/// like `encodeURIComponent`.
+static KEY_ENCODE_SET: AsciiSet = NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// [Cloudflare Workers KV](https://developers.cloudflare.com/workers/runtime-apis/kv/)
+/// services support via its [REST API](https://developers.cloudflare.com/api/operations/workers-kv-namespace-list-a-namespace'-s-keys).
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [x] scan
+/// - [ ] ~~presign~~
+/// - [ ] blocking
+///
+/// # Notes
+///
+/// - Values are limited to 25 MiB, writing larger values will fail with
+///   [`ErrorKind::Unsupported`].
+/// - Workers KV is eventually consistent: changes may take up to 60 seconds
+///   to be visible in other locations, and keys listed by `list` and `scan`
+///   could be stale.
+/// - Rate limited requests will fail with [`ErrorKind::RateLimited`], which
+///   is temporary and could be retried by `RetryLayer`.
+///
+/// # Configuration
+///
+/// - `root`: Set the working directory of `OpenDAL`
+/// - `account_id`: Set the account id of cloudflare
+/// - `namespace_id`: Set the namespace id of workers kv
+/// - `api_token`: Set the api token which has the permission to edit workers kv
+/// - `default_ttl`: Set the default ttl (in seconds) for written keys, at least 60 seconds
+///
+/// You can refer to [`CloudflareKvBuilder`]'s docs for more information
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::CloudflareKv;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mut builder = CloudflareKv::default();
+///
+///     builder.account_id("account_id");
+///     builder.namespace_id("namespace_id");
+///     builder.api_token("api_token");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct CloudflareKvBuilder {
+    /// The account id of cloudflare.
+    account_id: Option<String>,
+    /// The namespace id of workers kv.
+    namespace_id: Option<String>,
+    /// The api token to access cloudflare API.
+    api_token: Option<String>,
+    /// The endpoint of cloudflare API.
+    ///
+    /// default is "https://api.cloudflare.com/client/v4"
+    endpoint: Option<String>,
+    /// the working directory of the service. Can be "/path/to/dir"
+    ///
+    /// default is "/"
+    root: Option<String>,
+    /// The default ttl for put operations.
+    default_ttl: Option<Duration>,
+    http_client: Option<HttpClient>,
+}
+
+impl Debug for CloudflareKvBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Builder");
+
+        ds.field("account_id", &self.account_id);
+        ds.field("namespace_id", &self.namespace_id);
+        ds.field("endpoint", &self.endpoint);
+        ds.field("root", &self.root);
+        ds.field("default_ttl", &self.default_ttl);
+
+        if self.api_token.is_some() {
+            ds.field("api_token", &"<redacted>");
+        }
+
+        ds.finish()
+    }
+}
+
+impl CloudflareKvBuilder {
+    /// Set the account id of cloudflare.
+    pub fn account_id(&mut self, account_id: &str) -> &mut Self {
+        if !account_id.is_empty() {
+            self.account_id = Some(account_id.to_string());
+        }
+        self
+    }
+
+    /// Set the namespace id of workers kv.
+    pub fn namespace_id(&mut self, namespace_id: &str) -> &mut Self {
+        if !namespace_id.is_empty() {
+            self.namespace_id = Some(namespace_id.to_string());
+        }
+        self
+    }
+
+    /// Set the api token, which will be sent as bearer token.
+    pub fn api_token(&mut self, api_token: &str) -> &mut Self {
+        if !api_token.is_empty() {
+            self.api_token = Some(api_token.to_string());
+        }
+        self
+    }
+
+    /// Set the endpoint of cloudflare API.
+    ///
+    /// default: "https://api.cloudflare.com/client/v4"
+    pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
+        if !endpoint.is_empty() {
+            self.endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        }
+        self
+    }
+
+    /// Set the working directory, all operations will be performed under it.
+    ///
+    /// default: "/"
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string());
+        }
+        self
+    }
+
+    /// Set the default ttl for written keys.
+    ///
+    /// If set, keys will expire after ttl. ttl must be at least 60 seconds,
+    /// and will be truncated to whole seconds.
+    pub fn default_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Specify the http client that used by this service.
+    ///
+    /// # Notes
+    ///
+    /// This API is part of OpenDAL's Raw API. `HttpClient` could be changed
+    /// during minor updates.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl Builder for CloudflareKvBuilder {
+    const SCHEME: Scheme = Scheme::CloudflareKv;
+    type Accessor = CloudflareKvBackend;
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = CloudflareKvBuilder::default();
+
+        map.get("account_id").map(|v| builder.account_id(v));
+        map.get("namespace_id").map(|v| builder.namespace_id(v));
+        map.get("api_token").map(|v| builder.api_token(v));
+        map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("root").map(|v| builder.root(v));
+        map.get("default_ttl").map(|v| {
+            v.parse::<u64>()
+                .map(|v| builder.default_ttl(Duration::from_secs(v)))
+        });
+
+        builder
+    }
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        debug!("backend build started: {:?}", &self);
+
+        let account_id = self.account_id.clone().ok_or_else(|| {
+            Error::new(ErrorKind::ConfigInvalid, "account_id is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::CloudflareKv)
+        })?;
+        let namespace_id = self.namespace_id.clone().ok_or_else(|| {
+            Error::new(ErrorKind::ConfigInvalid, "namespace_id is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::CloudflareKv)
+        })?;
+        let api_token = self.api_token.clone().ok_or_else(|| {
+            Error::new(ErrorKind::ConfigInvalid, "api_token is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::CloudflareKv)
+        })?;
+
+        if let Some(ttl) = self.default_ttl {
+            if ttl < MIN_TTL {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "default_ttl must be at least 60 seconds",
+                )
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::CloudflareKv)
+                .with_context("default_ttl", format!("{ttl:?}")));
+            }
+        }
+
+        let root = normalize_root(&self.root.take().unwrap_or_default());
+        debug!("backend use root {}", root);
+
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+
+        let client = if let Some(client) = self.http_client.take() {
+            client
+        } else {
+            HttpClient::new().map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::CloudflareKv)
+            })?
+        };
+
+        debug!("backend build finished: {:?}", &self);
+        Ok(CloudflareKvBackend::new(Adapter {
+            url: format!("{endpoint}/accounts/{account_id}/storage/kv/namespaces/{namespace_id}"),
+            namespace_id,
+            authorization: format_authorization_by_bearer(&api_token)?,
+            default_ttl: self.default_ttl,
+            client,
+        })
+        .with_root(&root))
+    }
+}
+
+/// Backend for cloudflare kv services.
+pub type CloudflareKvBackend = kv::Backend<Adapter>;
+
+#[derive(Clone)]
+pub struct Adapter {
+    /// The url of namespace, like `<endpoint>/accounts/<account_id>/storage/kv/namespaces/<namespace_id>`
+    url: String,
+    namespace_id: String,
+    authorization: String,
+    default_ttl: Option<Duration>,
+    client: HttpClient,
+}
+
+// implement `Debug` manually, or api token may be leaked.
+impl Debug for Adapter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Adapter");
+
+        ds.field("url", &self.url);
+        ds.field("default_ttl", &self.default_ttl);
+        ds.finish()
+    }
+}
+
+/// The metadata stored along with values, which is used by `stat`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ValueMetadata {
+    content_length: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataResponse {
+    result: Option<ValueMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListKeysResponse {
+    result: Vec<ListKey>,
+    result_info: Option<ListKeysResultInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListKey {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ListKeysResultInfo {
+    cursor: String,
+}
+
+impl Adapter {
+    fn key_url(&self, kind: &str, key: &str) -> String {
+        format!(
+            "{}/{kind}/{}",
+            self.url,
+            utf8_percent_encode(key, &KEY_ENCODE_SET)
+        )
+    }
+
+    async fn send(
+        &self,
+        req: http::request::Builder,
+        body: AsyncBody,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let req = req
+            .header(AUTHORIZATION, &self.authorization)
+            .body(body)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+}
+
+#[async_trait]
+impl kv::Adapter for Adapter {
+    fn metadata(&self) -> kv::Metadata {
+        kv::Metadata::new(
+            Scheme::CloudflareKv,
+            &self.namespace_id,
+            AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::Scan,
+        )
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let resp = self
+            .send(Request::get(self.key_url("values", path)), AsyncBody::Empty)
+            .await?;
+
+        match resp.status() {
+            StatusCode::OK => Ok(Some(resp.into_body().bytes().await?.to_vec())),
+            StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(None)
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn get_length(&self, path: &str) -> Result<Option<u64>> {
+        let resp = self
+            .send(
+                Request::get(self.key_url("metadata", path)),
+                AsyncBody::Empty,
+            )
+            .await?;
+
+        let meta = match resp.status() {
+            StatusCode::OK => {
+                let bs = resp.into_body().bytes().await?;
+                let output: MetadataResponse =
+                    serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+                output.result.unwrap_or_default()
+            }
+            StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                return Ok(None);
+            }
+            _ => return Err(parse_error(resp).await?),
+        };
+
+        match meta.content_length {
+            Some(length) => Ok(Some(length)),
+            // Values written by others may not have metadata.
+            None => Ok(self.get(path).await?.map(|bs| bs.len() as u64)),
+        }
+    }
+
+    async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+        if value.len() > MAX_VALUE_SIZE {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "value exceeds the 25 MiB limit of cloudflare kv",
+            )
+            .with_operation("kv::Adapter::set")
+            .with_context("service", Scheme::CloudflareKv)
+            .with_context("size", value.len().to_string()));
+        }
+
+        let mut url = self.key_url("values", path);
+        if let Some(ttl) = self.default_ttl {
+            url.push_str(&format!("?expiration_ttl={}", ttl.as_secs()));
+        }
+
+        let metadata = serde_json::to_vec(&ValueMetadata {
+            content_length: Some(value.len() as u64),
+        })
+        .map_err(new_json_serialize_error)?;
+
+        let boundary = format!("opendal-{}", uuid::Uuid::new_v4());
+        let body = build_multipart(&boundary, value, &metadata);
+
+        let resp = self
+            .send(
+                Request::put(url)
+                    .header(
+                        CONTENT_TYPE,
+                        format!("multipart/form-data; boundary={boundary}"),
+                    )
+                    .header(CONTENT_LENGTH, body.len()),
+                AsyncBody::Bytes(body),
+            )
+            .await?;
+
+        match resp.status() {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let resp = self
+            .send(
+                Request::delete(self.key_url("values", path)),
+                AsyncBody::Empty,
+            )
+            .await?;
+
+        match resp.status() {
+            StatusCode::OK | StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut cursor = String::new();
+
+        loop {
+            let mut url = format!("{}/keys?limit={SCAN_LIMIT}", self.url);
+            if !path.is_empty() {
+                url.push_str(&format!(
+                    "&prefix={}",
+                    utf8_percent_encode(path, &KEY_ENCODE_SET)
+                ));
+            }
+            if !cursor.is_empty() {
+                url.push_str(&format!(
+                    "&cursor={}",
+                    utf8_percent_encode(&cursor, &KEY_ENCODE_SET)
+                ));
+            }
+
+            let resp = self.send(Request::get(url), AsyncBody::Empty).await?;
+            if resp.status() != StatusCode::OK {
+                return Err(parse_error(resp).await?);
+            }
+
+            let bs = resp.into_body().bytes().await?;
+            let output: ListKeysResponse =
+                serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+            keys.extend(output.result.into_iter().map(|v| v.name));
+
+            cursor = output.result_info.unwrap_or_default().cursor;
+            if cursor.is_empty() {
+                return Ok(keys);
+            }
+        }
+    }
+}
+
+/// Build `multipart/form-data` body with `value` and `metadata` fields.
+fn build_multipart(boundary: &str, value: &[u8], metadata: &[u8]) -> Bytes {
+    let mut bs = Vec::with_capacity(value.len() + metadata.len() + 256);
+
+    bs.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    bs.extend_from_slice(b"Content-Disposition: form-data; name=\"value\"\r\n");
+    bs.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+    bs.extend_from_slice(value);
+    bs.extend_from_slice(format!("\r\n--{boundary}\r\n").as_bytes());
+    bs.extend_from_slice(b"Content-Disposition: form-data; name=\"metadata\"\r\n\r\n");
+    bs.extend_from_slice(metadata);
+    bs.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    Bytes::from(bs)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::body_string_contains;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+    use crate::raw::adapters::kv::Adapter as _;
+
+    const NAMESPACE_PATH: &str = "/accounts/account/storage/kv/namespaces/namespace";
+
+    fn new_adapter(server: &MockServer) -> Adapter {
+        Adapter {
+            url: format!("{}{NAMESPACE_PATH}", server.uri()),
+            namespace_id: "namespace".to_string(),
+            authorization: "Bearer token".to_string(),
+            default_ttl: Some(Duration::from_secs(120)),
+            client: HttpClient::new().expect("client must be created"),
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_small_ttl() {
+        let mut builder = CloudflareKvBuilder::default();
+        builder
+            .account_id("account")
+            .namespace_id("namespace")
+            .api_token("token")
+            .default_ttl(Duration::from_secs(10));
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[tokio::test]
+    async fn test_set_and_get_length() -> Result<()> {
+        let server = MockServer::start().await;
+        let adapter = new_adapter(&server);
+
+        Mock::given(method("PUT"))
+            .and(path(format!("{NAMESPACE_PATH}/values/dir%2Ffile")))
+            .and(query_param("expiration_ttl", "120"))
+            .and(header("authorization", "Bearer token"))
+            .and(body_string_contains("hello"))
+            .and(body_string_contains(r#"{"content_length":5}"#))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true, "errors": [], "messages": [], "result": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{NAMESPACE_PATH}/metadata/dir%2Ffile")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true, "errors": [], "messages": [], "result": {"content_length": 5}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{NAMESPACE_PATH}/metadata/not_exist")))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "success": false, "errors": [{"code": 10009, "message": "get: 'key not found'"}],
+                "messages": [], "result": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        adapter.set("dir/file", b"hello").await?;
+        assert_eq!(adapter.get_length("dir/file").await?, Some(5));
+        assert_eq!(adapter.get_length("not_exist").await?, None);
+
+        let err = adapter
+            .set("large", &vec![0; MAX_VALUE_SIZE + 1])
+            .await
+            .expect_err("set must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_cursor() -> Result<()> {
+        let server = MockServer::start().await;
+        let adapter = new_adapter(&server);
+
+        Mock::given(method("GET"))
+            .and(path(format!("{NAMESPACE_PATH}/keys")))
+            .and(query_param("prefix", "dir/"))
+            .and(query_param("cursor", "next"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true, "errors": [], "messages": [],
+                "result": [{"name": "dir/c"}],
+                "result_info": {"count": 1, "cursor": ""}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{NAMESPACE_PATH}/keys")))
+            .and(query_param("prefix", "dir/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true, "errors": [], "messages": [],
+                "result": [{"name": "dir/a"}, {"name": "dir/b", "expiration": 1577836800}],
+                "result_info": {"count": 2, "cursor": "next"}
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let keys = adapter.scan("dir/").await?;
+        assert_eq!(keys, vec!["dir/a", "dir/b", "dir/c"]);
+
+        Ok(())
+    }
+}
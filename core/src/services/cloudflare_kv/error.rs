@@ -0,0 +1,115 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use http::Response;
+use http::StatusCode;
+use serde::Deserialize;
+use serde_json::de;
+
+use crate::raw::*;
+use crate::Error;
+use crate::ErrorKind;
+use crate::Result;
+
+/// The error response of cloudflare API.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct CloudflareKvError {
+    pub success: bool,
+    pub errors: Vec<CloudflareKvErrorDetail>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+pub struct CloudflareKvErrorDetail {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Parse error response into Error.
+pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
+    let (parts, body) = resp.into_parts();
+    let bs = body.bytes().await?;
+
+    let message = match de::from_slice::<CloudflareKvError>(&bs) {
+        Ok(v) if !v.errors.is_empty() => format!("{:?}", v.errors),
+        _ => String::from_utf8_lossy(&bs).into_owned(),
+    };
+
+    let mut err = match parts.status {
+        StatusCode::NOT_FOUND => Error::new(ErrorKind::NotFound, &message),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Error::new(ErrorKind::PermissionDenied, &message)
+        }
+        // Values are limited to 25 MiB, and keys are limited to 512 bytes.
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::URI_TOO_LONG => Error::new(
+            ErrorKind::Unsupported,
+            "key or value exceeds the limit of cloudflare kv",
+        )
+        .with_context("message", message),
+        StatusCode::TOO_MANY_REQUESTS => {
+            Error::new(ErrorKind::RateLimited, &message).set_temporary()
+        }
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => {
+            Error::new(ErrorKind::Unexpected, &message).set_temporary()
+        }
+        _ => Error::new(ErrorKind::Unexpected, &message),
+    };
+    err = err.with_context("response", format!("{parts:?}"));
+
+    Ok(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    async fn parse(status: StatusCode, body: &str) -> Error {
+        let bs = bytes::Bytes::from(body.to_string());
+        let body = IncomingAsyncBody::new(Box::new(stream::iter(vec![Ok(bs)])), None);
+        let resp = Response::builder().status(status).body(body).unwrap();
+
+        parse_error(resp).await.expect("parse error must succeed")
+    }
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let err = parse(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"success":false,"errors":[{"code":10013,"message":"Too many requests"}],"messages":[],"result":null}"#,
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert!(err.is_temporary());
+
+        let err = parse(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            r#"{"success":false,"errors":[{"code":10011,"message":"Value too large"}],"messages":[],"result":null}"#,
+        )
+        .await;
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert!(!err.is_temporary());
+
+        let err = parse(StatusCode::NOT_FOUND, "key not found").await;
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,646 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Typed configs for services.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::Scheme;
+
+/// Secret value in service configs, like passwords and access keys.
+///
+/// `Secret` will never be leaked by `Debug` or `Serialize`, both of them
+/// output `<redacted>` instead.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Create a new secret.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Expose the inner value of this secret.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("<redacted>")
+    }
+}
+
+/// Value that could be converted into the entries of builder's map.
+trait ConfigValue {
+    fn insert_into(self, key: &str, map: &mut HashMap<String, String>);
+
+    /// Check if the raw value in builder's map could be parsed as `Self`.
+    fn is_valid(value: &str) -> bool;
+}
+
+macro_rules! impl_config_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ConfigValue for $ty {
+                fn insert_into(self, key: &str, map: &mut HashMap<String, String>) {
+                    map.insert(key.to_string(), self.to_string());
+                }
+
+                fn is_valid(value: &str) -> bool {
+                    value.parse::<$ty>().is_ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_config_value!(String, u32, u64, usize, i64);
+
+impl ConfigValue for bool {
+    fn insert_into(self, key: &str, map: &mut HashMap<String, String>) {
+        map.insert(key.to_string(), self.to_string());
+    }
+
+    /// Builders accept `on` and `off` as boolean too.
+    fn is_valid(value: &str) -> bool {
+        matches!(value, "true" | "false" | "on" | "off")
+    }
+}
+
+impl ConfigValue for Secret {
+    fn insert_into(self, key: &str, map: &mut HashMap<String, String>) {
+        map.insert(key.to_string(), self.0);
+    }
+
+    fn is_valid(_: &str) -> bool {
+        true
+    }
+}
+
+/// Nested values will be inserted as `key.name`, like `header.x-foo`.
+impl ConfigValue for HashMap<String, String> {
+    fn insert_into(self, key: &str, map: &mut HashMap<String, String>) {
+        for (k, v) in self {
+            map.insert(format!("{key}.{k}"), v);
+        }
+    }
+
+    fn is_valid(_: &str) -> bool {
+        true
+    }
+}
+
+/// Generate config structs for services and the [`ServiceConfig`] over them.
+macro_rules! service_config {
+    ($($feature:literal => $service:ident, $config:ident { $($field:ident: $ty:ty,)* })*) => {
+        $(
+            #[doc = concat!("Typed config for [`", stringify!($service), "`](crate::services::", stringify!($service), ") service.")]
+            ///
+            /// All fields are optional and have the same meaning as the keys
+            /// accepted by `Builder::from_map`.
+            #[cfg(feature = $feature)]
+            #[allow(missing_docs)]
+            #[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+            #[serde(default, deny_unknown_fields)]
+            #[non_exhaustive]
+            pub struct $config {
+                $(pub $field: Option<$ty>,)*
+            }
+
+            #[cfg(feature = $feature)]
+            impl $config {
+                /// Convert into the map accepted by `Builder::from_map`.
+                pub fn into_map(self) -> HashMap<String, String> {
+                    let mut map = HashMap::new();
+                    $(
+                        if let Some(v) = self.$field {
+                            v.insert_into(stringify!($field), &mut map);
+                        }
+                    )*
+                    map
+                }
+
+                /// Check if given entry of builder's map is valid.
+                ///
+                /// Returns `None` if the key is unknown.
+                fn is_valid_entry(key: &str, value: &str) -> Option<bool> {
+                    // Nested values like `header.x-foo` are checked by their field.
+                    let field = key.split('.').next().unwrap_or(key);
+                    match field {
+                        $(stringify!($field) => Some(<$ty as ConfigValue>::is_valid(value)),)*
+                        _ => None,
+                    }
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl From<$config> for ServiceConfig {
+                fn from(v: $config) -> Self {
+                    ServiceConfig::$service(v)
+                }
+            }
+        )*
+
+        /// Typed config for all enabled services, tagged by `scheme`.
+        ///
+        /// # Notes
+        ///
+        /// - Unknown fields or mismatched types will be rejected while
+        ///   deserializing.
+        /// - Secrets like passwords will be output as `<redacted>` by both
+        ///   `Debug` and `Serialize`.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use opendal::services::ServiceConfig;
+        ///
+        /// let cfg: ServiceConfig = serde_json::from_str(
+        ///     r#"{"scheme": "memory", "root": "/tmp"}"#,
+        /// ).expect("must be valid config");
+        /// ```
+        #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(tag = "scheme", rename_all = "snake_case")]
+        #[non_exhaustive]
+        pub enum ServiceConfig {
+            $(
+                #[doc = concat!("Config for [`", stringify!($service), "`](crate::services::", stringify!($service), ") service.")]
+                #[cfg(feature = $feature)]
+                $service($config),
+            )*
+        }
+
+        impl ServiceConfig {
+            /// Get the scheme of this config.
+            pub fn scheme(&self) -> Scheme {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        ServiceConfig::$service(_) => Scheme::$service,
+                    )*
+                }
+            }
+
+            /// Convert into the map accepted by `Builder::from_map`.
+            pub fn into_map(self) -> HashMap<String, String> {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        ServiceConfig::$service(v) => v.into_map(),
+                    )*
+                }
+            }
+
+            /// Check if given entry of builder's map is valid for `scheme`.
+            ///
+            /// Returns `None` if the scheme or key is unknown.
+            pub(crate) fn is_valid_entry(scheme: Scheme, key: &str, value: &str) -> Option<bool> {
+                match scheme {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Scheme::$service => $config::is_valid_entry(key, value),
+                    )*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+service_config! {
+    "services-azblob" => Azblob, AzblobConfig {
+        root: String,
+        container: String,
+        endpoint: String,
+        account_name: String,
+        account_key: Secret,
+        sas_token: Secret,
+        block_size: usize,
+        block_concurrency: usize,
+        enable_append_blob: bool,
+    }
+
+    "services-azdfs" => Azdfs, AzdfsConfig {
+        root: String,
+        filesystem: String,
+        endpoint: String,
+        account_name: String,
+        account_key: Secret,
+    }
+
+    "services-azfile" => Azfile, AzfileConfig {
+        root: String,
+        share_name: String,
+        endpoint: String,
+        account_name: String,
+        account_key: Secret,
+        sas_token: Secret,
+    }
+
+    "services-b2" => B2, B2Config {
+        root: String,
+        bucket: String,
+        bucket_id: String,
+        application_key_id: String,
+        application_key: Secret,
+        part_size: usize,
+    }
+
+    "services-cloudflare-kv" => CloudflareKv, CloudflareKvConfig {
+        root: String,
+        account_id: String,
+        namespace_id: String,
+        api_token: Secret,
+        endpoint: String,
+        default_ttl: u64,
+    }
+
+    "services-dashmap" => Dashmap, DashmapConfig {
+        root: String,
+    }
+
+    "services-etcd" => Etcd, EtcdConfig {
+        root: String,
+        endpoints: String,
+        username: String,
+        password: Secret,
+        ca_path: String,
+        cert_path: String,
+        key_path: String,
+        default_ttl: u64,
+    }
+
+    "services-fs" => Fs, FsConfig {
+        root: String,
+        atomic_write: bool,
+        atomic_write_dir: String,
+        symlink_mode: String,
+        preallocate: bool,
+    }
+
+    "services-ftp" => Ftp, FtpConfig {
+        root: String,
+        endpoint: String,
+        user: String,
+        password: Secret,
+        enable_secure: bool,
+        danger_accept_invalid_certs: bool,
+    }
+
+    "services-gcs" => Gcs, GcsConfig {
+        root: String,
+        bucket: String,
+        endpoint: String,
+        credential: Secret,
+        scope: String,
+        predefined_acl: String,
+        default_storage_class: String,
+        write_chunk_size: usize,
+        encryption_key: Secret,
+        encryption_key_sha256: String,
+        disable_vm_metadata: bool,
+        enable_compose_append: bool,
+    }
+
+    "services-ghac" => Ghac, GhacConfig {
+        root: String,
+        version: String,
+        enable_create_simulation: bool,
+    }
+
+    "services-gridfs" => Gridfs, GridfsConfig {
+        root: String,
+        connection_string: Secret,
+        database: String,
+        bucket: String,
+        chunk_size: u32,
+    }
+
+    "services-hdfs" => Hdfs, HdfsConfig {
+        root: String,
+        name_node: String,
+        enable_atomic_write: bool,
+    }
+
+    "services-http" => Http, HttpConfig {
+        root: String,
+        endpoint: String,
+        username: String,
+        password: Secret,
+        token: Secret,
+        enable_write: bool,
+        header: HashMap<String, String>,
+    }
+
+    "services-ipfs" => Ipfs, IpfsConfig {
+        root: String,
+        endpoint: String,
+    }
+
+    "services-ipmfs" => Ipmfs, IpmfsConfig {
+        root: String,
+        endpoint: String,
+    }
+
+    "services-memcached" => Memcached, MemcachedConfig {
+        root: String,
+        endpoint: String,
+        default_ttl: u64,
+    }
+
+    "services-memory" => Memory, MemoryConfig {
+        root: String,
+    }
+
+    "services-moka" => Moka, MokaConfig {
+        name: String,
+        max_capacity: u64,
+        time_to_live: u64,
+        time_to_idle: u64,
+        num_segments: usize,
+        thread_pool_enabled: bool,
+    }
+
+    "services-mysql" => Mysql, MysqlConfig {
+        root: String,
+        connection_string: Secret,
+        table: String,
+        key_field: String,
+        value_field: String,
+        connect_timeout: u64,
+    }
+
+    "services-obs" => Obs, ObsConfig {
+        root: String,
+        bucket: String,
+        endpoint: String,
+        access_key_id: String,
+        secret_access_key: Secret,
+    }
+
+    "services-oss" => Oss, OssConfig {
+        root: String,
+        bucket: String,
+        endpoint: String,
+        presign_endpoint: String,
+        access_key_id: String,
+        access_key_secret: Secret,
+        security_token: Secret,
+        enable_append_object: bool,
+    }
+
+    "services-postgres" => Postgres, PostgresConfig {
+        root: String,
+        connection_string: Secret,
+        table: String,
+        key_field: String,
+        value_field: String,
+        enable_create_table: bool,
+    }
+
+    "services-redis" => Redis, RedisConfig {
+        root: String,
+        endpoint: String,
+        cluster_endpoints: String,
+        username: String,
+        password: Secret,
+        db: i64,
+        chunk_size: usize,
+        default_ttl: u64,
+    }
+
+    "services-rocksdb" => Rocksdb, RocksdbConfig {
+        root: String,
+        datadir: String,
+        column_family: String,
+        create_missing_column_family: bool,
+    }
+
+    "services-s3" => S3, S3Config {
+        root: String,
+        bucket: String,
+        endpoint: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: Secret,
+        security_token: Secret,
+        role_arn: String,
+        external_id: String,
+        role_session_name: String,
+        web_identity_token_file: String,
+        server_side_encryption: String,
+        server_side_encryption_aws_kms_key_id: String,
+        server_side_encryption_customer_algorithm: String,
+        server_side_encryption_customer_key: Secret,
+        server_side_encryption_customer_key_md5: String,
+        disable_config_load: bool,
+        disable_ec2_metadata: bool,
+        disable_web_identity: bool,
+        enable_virtual_host_style: bool,
+        enable_accelerate: bool,
+        enable_dualstack: bool,
+        enable_request_payer: bool,
+        enable_list_objects_v1: bool,
+        default_storage_class: String,
+        checksum_algorithm: String,
+    }
+
+    "services-sftp" => Sftp, SftpConfig {
+        root: String,
+        endpoint: String,
+        user: String,
+        key: String,
+        known_hosts_strategy: String,
+    }
+
+    "services-sled" => Sled, SledConfig {
+        root: String,
+        datadir: String,
+    }
+
+    "services-sqlite" => Sqlite, SqliteConfig {
+        root: String,
+        path: String,
+        table: String,
+    }
+
+    "services-swift" => Swift, SwiftConfig {
+        root: String,
+        endpoint: String,
+        container: String,
+        token: Secret,
+        segment_size: usize,
+    }
+
+    "services-tikv" => Tikv, TikvConfig {
+        root: String,
+        endpoints: String,
+        ca_path: String,
+        cert_path: String,
+        key_path: String,
+        chunk_size: usize,
+    }
+
+    "services-wasabi" => Wasabi, WasabiConfig {
+        root: String,
+        bucket: String,
+        endpoint: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: Secret,
+        security_token: Secret,
+        role_arn: String,
+        external_id: String,
+        server_side_encryption: String,
+        server_side_encryption_aws_kms_key_id: String,
+        server_side_encryption_customer_algorithm: String,
+        server_side_encryption_customer_key: Secret,
+        server_side_encryption_customer_key_md5: String,
+        disable_config_load: bool,
+        disable_ec2_metadata: bool,
+        enable_virtual_host_style: bool,
+        default_storage_class: String,
+    }
+
+    "services-webdav" => Webdav, WebdavConfig {
+        root: String,
+        endpoint: String,
+        username: String,
+        password: Secret,
+        token: Secret,
+        disable_overwrite: bool,
+    }
+
+    "services-webhdfs" => Webhdfs, WebhdfsConfig {
+        root: String,
+        endpoint: String,
+        delegation: Secret,
+        user_name: String,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_deserialize_config() {
+        let cfg: ServiceConfig = serde_json::from_str(
+            r#"{
+                "scheme": "s3",
+                "bucket": "test",
+                "secret_access_key": "secret",
+                "enable_virtual_host_style": true
+            }"#,
+        )
+        .expect("config must be valid");
+        assert_eq!(cfg.scheme(), Scheme::S3);
+
+        let map = cfg.into_map();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["bucket"], "test");
+        assert_eq!(map["secret_access_key"], "secret");
+        assert_eq!(map["enable_virtual_host_style"], "true");
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_deserialize_invalid_config() {
+        // Unknown field.
+        let result =
+            serde_json::from_str::<ServiceConfig>(r#"{"scheme": "s3", "buckett": "test"}"#);
+        assert!(result.is_err());
+
+        // Mismatched type.
+        let result = serde_json::from_str::<ServiceConfig>(
+            r#"{"scheme": "s3", "enable_virtual_host_style": "yes"}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_redact_secret() {
+        let cfg = ServiceConfig::from(S3Config {
+            bucket: Some("test".to_string()),
+            secret_access_key: Some(Secret::new("secret")),
+            ..Default::default()
+        });
+
+        assert!(!format!("{cfg:?}").contains(r#""secret""#));
+        let output = serde_json::to_string(&cfg).expect("serialize must succeed");
+        assert!(output.contains(r#""scheme":"s3""#));
+        assert!(output.contains(r#""secret_access_key":"<redacted>""#));
+        assert!(!output.contains(r#""secret""#));
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_is_valid_entry() {
+        let cases = [
+            ("bucket", "test", Some(true)),
+            ("enable_virtual_host_style", "on", Some(true)),
+            ("enable_virtual_host_style", "yes", Some(false)),
+            ("unknown", "test", None),
+        ];
+
+        for (key, value, expected) in cases {
+            let actual = ServiceConfig::is_valid_entry(Scheme::S3, key, value);
+            assert_eq!(actual, expected, "{key}={value}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "services-http")]
+    fn test_nested_config() {
+        let cfg: ServiceConfig = serde_json::from_str(
+            r#"{"scheme": "http", "endpoint": "https://example.com", "header": {"x-foo": "bar"}}"#,
+        )
+        .expect("config must be valid");
+
+        let map = cfg.into_map();
+        assert_eq!(map["header.x-foo"], "bar");
+    }
+}
@@ -0,0 +1,461 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use etcd_client::Certificate;
+use etcd_client::Client;
+use etcd_client::ConnectOptions;
+use etcd_client::GetOptions;
+use etcd_client::Identity;
+use etcd_client::PutOptions;
+use etcd_client::TlsOptions;
+use tokio::sync::OnceCell;
+use tonic::Code;
+
+use crate::raw::adapters::kv;
+use crate::raw::*;
+use crate::*;
+
+const DEFAULT_ETCD_ENDPOINTS: &str = "http://127.0.0.1:2379";
+
+/// Etcd service support.
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [x] scan
+/// - [ ] ~~presign~~
+/// - [ ] ~~blocking~~
+///
+/// # Notes
+///
+/// Requests will be balanced between all endpoints, and unavailable
+/// endpoints will be skipped.
+///
+/// etcd limits the size of a request (1.5 MiB by default, configured by
+/// `--max-request-bytes`), writing larger values will return
+/// [`ErrorKind::Unsupported`].
+///
+/// # Configuration
+///
+/// - `root`: Set the working directory of `OpenDAL`
+/// - `endpoints`: Set the endpoints of etcd, separated by `,`
+/// - `username`: Set the username of etcd
+/// - `password`: Set the password of etcd
+/// - `ca_path`: Set the ca certificate path for tls
+/// - `cert_path`: Set the client certificate path for tls
+/// - `key_path`: Set the client key path for tls
+/// - `default_ttl`: Set the ttl in seconds of written keys via lease
+///
+/// You can refer to [`EtcdBuilder`]'s docs for more information
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::Etcd;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mut builder = Etcd::default();
+///     builder.endpoints("http://127.0.0.1:2379,http://127.0.0.1:22379");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct EtcdBuilder {
+    endpoints: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    ca_path: Option<String>,
+    cert_path: Option<String>,
+    key_path: Option<String>,
+    default_ttl: Option<Duration>,
+    root: Option<String>,
+}
+
+impl Debug for EtcdBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("EtcdBuilder");
+        ds.field("endpoints", &self.endpoints);
+        ds.field("username", &self.username);
+        if self.password.is_some() {
+            ds.field("password", &"<redacted>");
+        }
+        ds.field("ca_path", &self.ca_path);
+        ds.field("cert_path", &self.cert_path);
+        ds.field("key_path", &self.key_path);
+        ds.field("default_ttl", &self.default_ttl);
+        ds.field("root", &self.root);
+        ds.finish()
+    }
+}
+
+impl EtcdBuilder {
+    /// Set the endpoints of etcd, multiple endpoints are separated by `,`.
+    ///
+    /// default: "http://127.0.0.1:2379"
+    pub fn endpoints(&mut self, endpoints: &str) -> &mut Self {
+        if !endpoints.is_empty() {
+            self.endpoints = Some(endpoints.to_string());
+        }
+        self
+    }
+
+    /// Set the username of etcd.
+    pub fn username(&mut self, username: &str) -> &mut Self {
+        if !username.is_empty() {
+            self.username = Some(username.to_string());
+        }
+        self
+    }
+
+    /// Set the password of etcd.
+    pub fn password(&mut self, password: &str) -> &mut Self {
+        if !password.is_empty() {
+            self.password = Some(password.to_string());
+        }
+        self
+    }
+
+    /// Set the path of ca certificate in PEM format to enable tls.
+    pub fn ca_path(&mut self, ca_path: &str) -> &mut Self {
+        if !ca_path.is_empty() {
+            self.ca_path = Some(ca_path.to_string());
+        }
+        self
+    }
+
+    /// Set the path of client certificate in PEM format.
+    ///
+    /// Must be set together with `key_path`.
+    pub fn cert_path(&mut self, cert_path: &str) -> &mut Self {
+        if !cert_path.is_empty() {
+            self.cert_path = Some(cert_path.to_string());
+        }
+        self
+    }
+
+    /// Set the path of client key in PEM format.
+    ///
+    /// Must be set together with `cert_path`.
+    pub fn key_path(&mut self, key_path: &str) -> &mut Self {
+        if !key_path.is_empty() {
+            self.key_path = Some(key_path.to_string());
+        }
+        self
+    }
+
+    /// Set the default ttl of written keys.
+    ///
+    /// A lease with this ttl will be granted for every write, keys will
+    /// be removed by etcd after the lease expired. The ttl will be rounded
+    /// up to seconds.
+    pub fn default_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Set the root for etcd.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string());
+        }
+        self
+    }
+}
+
+impl Builder for EtcdBuilder {
+    const SCHEME: Scheme = Scheme::Etcd;
+    type Accessor = EtcdBackend;
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = EtcdBuilder::default();
+
+        map.get("endpoints").map(|v| builder.endpoints(v));
+        map.get("username").map(|v| builder.username(v));
+        map.get("password").map(|v| builder.password(v));
+        map.get("ca_path").map(|v| builder.ca_path(v));
+        map.get("cert_path").map(|v| builder.cert_path(v));
+        map.get("key_path").map(|v| builder.key_path(v));
+        map.get("default_ttl").map(|v| {
+            v.parse::<u64>()
+                .map(|v| builder.default_ttl(Duration::from_secs(v)))
+        });
+        map.get("root").map(|v| builder.root(v));
+
+        builder
+    }
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        let endpoints: Vec<String> = self
+            .endpoints
+            .as_deref()
+            .unwrap_or(DEFAULT_ETCD_ENDPOINTS)
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if endpoints.is_empty() {
+            return Err(Error::new(ErrorKind::ConfigInvalid, "endpoints is empty")
+                .with_context("service", Scheme::Etcd));
+        }
+
+        let mut options = ConnectOptions::new();
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            options = options.with_user(username, password);
+        }
+        if let Some(tls) = self.tls_options()? {
+            options = options.with_tls(tls);
+        }
+
+        let default_ttl = match self.default_ttl {
+            None => None,
+            Some(ttl) if ttl.is_zero() => {
+                return Err(
+                    Error::new(ErrorKind::ConfigInvalid, "default_ttl must be positive")
+                        .with_context("service", Scheme::Etcd),
+                )
+            }
+            // Lease ttl is in seconds, round up to make sure keys
+            // live at least as long as required.
+            Some(ttl) => Some(ttl.as_secs() as i64 + i64::from(ttl.subsec_nanos() > 0)),
+        };
+
+        Ok(EtcdBackend::new(Adapter {
+            endpoints,
+            options,
+            client: Arc::new(OnceCell::new()),
+            default_ttl,
+        })
+        .with_root(self.root.as_deref().unwrap_or_default()))
+    }
+}
+
+impl EtcdBuilder {
+    fn tls_options(&self) -> Result<Option<TlsOptions>> {
+        let read = |path: &str| {
+            std::fs::read(path).map_err(|err| {
+                Error::new(ErrorKind::ConfigInvalid, "read tls file")
+                    .with_context("service", Scheme::Etcd)
+                    .with_context("path", path)
+                    .set_source(err)
+            })
+        };
+
+        let mut tls = match &self.ca_path {
+            Some(path) => TlsOptions::new().ca_certificate(Certificate::from_pem(read(path)?)),
+            None => return Ok(None),
+        };
+
+        match (&self.cert_path, &self.key_path) {
+            (Some(cert), Some(key)) => {
+                tls = tls.identity(Identity::from_pem(read(cert)?, read(key)?));
+            }
+            (None, None) => {}
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "cert_path and key_path must be set together",
+                )
+                .with_context("service", Scheme::Etcd))
+            }
+        }
+
+        Ok(Some(tls))
+    }
+}
+
+/// Backend for etcd services.
+pub type EtcdBackend = kv::Backend<Adapter>;
+
+#[derive(Clone)]
+pub struct Adapter {
+    endpoints: Vec<String>,
+    options: ConnectOptions,
+    client: Arc<OnceCell<Client>>,
+    /// The lease ttl in seconds.
+    default_ttl: Option<i64>,
+}
+
+impl Debug for Adapter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Adapter");
+        ds.field("endpoints", &self.endpoints);
+        ds.field("default_ttl", &self.default_ttl);
+        ds.finish()
+    }
+}
+
+impl Adapter {
+    /// Client is cheap to clone, all clones share the same connections.
+    async fn get_client(&self) -> Result<Client> {
+        let client = self
+            .client
+            .get_or_try_init(|| async {
+                Client::connect(&self.endpoints, Some(self.options.clone()))
+                    .await
+                    .map_err(parse_error)
+            })
+            .await?;
+
+        Ok(client.clone())
+    }
+}
+
+#[async_trait]
+impl kv::Adapter for Adapter {
+    fn metadata(&self) -> kv::Metadata {
+        use AccessorCapability::*;
+        kv::Metadata::new(Scheme::Etcd, &self.endpoints.join(","), Read | Write | Scan)
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let mut client = self.get_client().await?;
+        let resp = client.get(path, None).await.map_err(parse_error)?;
+
+        Ok(resp.kvs().first().map(|kv| kv.value().to_vec()))
+    }
+
+    async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+        let mut client = self.get_client().await?;
+
+        let options = match self.default_ttl {
+            Some(ttl) => {
+                let lease = client.lease_grant(ttl, None).await.map_err(parse_error)?;
+                Some(PutOptions::new().with_lease(lease.id()))
+            }
+            None => None,
+        };
+
+        client
+            .put(path, value, options)
+            .await
+            .map_err(parse_error)?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let mut client = self.get_client().await?;
+        client.delete(path, None).await.map_err(parse_error)?;
+
+        Ok(())
+    }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .get(path, Some(GetOptions::new().with_prefix().with_keys_only()))
+            .await
+            .map_err(parse_error)?;
+
+        let mut res = Vec::with_capacity(resp.kvs().len());
+        for kv in resp.kvs() {
+            let key = kv.key_str().map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "store key is not valid utf-8 string")
+                    .set_source(err)
+            })?;
+            res.push(key.to_string());
+        }
+
+        Ok(res)
+    }
+}
+
+fn parse_error(err: etcd_client::Error) -> Error {
+    let (kind, message, retryable) = match &err {
+        etcd_client::Error::GRpcStatus(status) => match status.code() {
+            _ if status.message().contains("request is too large") => (
+                ErrorKind::Unsupported,
+                "value exceeds the request size limit of etcd",
+                false,
+            ),
+            Code::PermissionDenied | Code::Unauthenticated => {
+                (ErrorKind::PermissionDenied, "error from etcd", false)
+            }
+            Code::Unavailable | Code::DeadlineExceeded | Code::Aborted => {
+                (ErrorKind::Unexpected, "error from etcd", true)
+            }
+            _ => (ErrorKind::Unexpected, "error from etcd", false),
+        },
+        etcd_client::Error::TransportError(_) | etcd_client::Error::IoError(_) => {
+            (ErrorKind::Unexpected, "connect to etcd", true)
+        }
+        etcd_client::Error::InvalidArgs(_) | etcd_client::Error::InvalidUri(_) => {
+            (ErrorKind::ConfigInvalid, "invalid etcd config", false)
+        }
+        _ => (ErrorKind::Unexpected, "error from etcd", false),
+    };
+
+    let mut err = Error::new(kind, message).set_source(err);
+    if retryable {
+        err = err.set_temporary();
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Status;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_error(etcd_client::Error::GRpcStatus(Status::invalid_argument(
+            "etcdserver: request is too large",
+        )));
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        let err = parse_error(etcd_client::Error::GRpcStatus(Status::unavailable(
+            "etcdserver: leader changed",
+        )));
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+
+        let err = parse_error(etcd_client::Error::GRpcStatus(Status::unauthenticated(
+            "etcdserver: invalid auth token",
+        )));
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_tls_options() {
+        let mut builder = EtcdBuilder::default();
+        assert!(builder.tls_options().unwrap().is_none());
+
+        builder.ca_path("/not/exist/ca.pem");
+        let err = builder.tls_options().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
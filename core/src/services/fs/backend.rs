@@ -25,12 +25,16 @@ use std::path::PathBuf;
 use async_compat::Compat;
 use async_trait::async_trait;
 use chrono::DateTime;
+use filetime::FileTime;
 use log::debug;
 use tokio::fs;
 use uuid::Uuid;
 
+use super::copy::copy_file;
 use super::error::parse_io_error;
 use super::pager::FsPager;
+use super::pager::SymlinkMode;
+use super::writer::set_last_modified;
 use super::writer::FsWriter;
 use crate::ops::*;
 use crate::raw::*;
@@ -54,9 +58,53 @@ use crate::*;
 /// # Configuration
 ///
 /// - `root`: Set the work dir for backend.
+/// - `atomic_write`: Set to `false` to disable atomic write, default to `true`.
+/// - `atomic_write_dir`: Set the temp dir for atomic write.
+/// - `symlink_mode`: Set how to handle symlinks, default to `follow`.
+/// - `preallocate`: Set to `true` to preallocate files while writing, default
+///   to `true` on linux and `false` on other platforms.
 ///
 /// Refer to [`FsBuilder`]'s public API docs for more information.
 ///
+/// # Atomic write
+///
+/// Data will be written into a temp file (`.{name}.tmp.{uuid}` in the same
+/// dir of target file by default) and renamed to the target path while
+/// closing, so that readers will never observe partial written content and
+/// a failed write leaves the original file untouched. The temp file will be
+/// removed if the writer is aborted or dropped without close. Temp files
+/// will be skipped while listing.
+///
+/// # Modification time
+///
+/// Writes with `last_modified` set in [`OpWrite`] will set the modification
+/// time of the file, and copy will preserve the modification time of the
+/// source file. Failures of setting times will be logged as warnings instead
+/// of failing the operation.
+///
+/// # Preallocate
+///
+/// If the content length is known while writing (for example,
+/// [`OpWrite::with_content_length`] is set), the file will be preallocated
+/// with `fallocate` on linux (or `set_len` on other platforms) to avoid
+/// fragmentation and fail fast with [`ErrorKind::InsufficientStorage`] if
+/// there is no enough space. The file will be shrunk to the actual written
+/// size while closing.
+///
+/// # Symlinks
+///
+/// Symlinks are handled according to `symlink_mode`:
+///
+/// - `follow`: Follow symlinks and report them as their targets. Symlinks
+///   whose targets are missing will be listed as unknown entries.
+/// - `skip_missing`: Same as `follow` but symlinks whose targets are missing
+///   will be skipped while listing.
+/// - `report`: Don't follow symlinks, they will be reported as unknown
+///   entries with their own metadata.
+///
+/// Stat on a symlink whose target is missing returns `NotFound` unless
+/// `symlink_mode` is `report`.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -86,8 +134,10 @@ use crate::*;
 #[derive(Default, Debug)]
 pub struct FsBuilder {
     root: Option<PathBuf>,
+    disable_atomic_write: bool,
     atomic_write_dir: Option<PathBuf>,
-    enable_path_check: bool,
+    symlink_mode: Option<String>,
+    preallocate: Option<bool>,
 }
 
 impl FsBuilder {
@@ -102,7 +152,21 @@ impl FsBuilder {
         self
     }
 
+    /// Enable or disable atomic write, default to enabled.
+    ///
+    /// Disable it for filesystems that rename is not atomic or not
+    /// supported, data will be written to the target file directly.
+    pub fn atomic_write(&mut self, enabled: bool) -> &mut Self {
+        self.disable_atomic_write = !enabled;
+
+        self
+    }
+
     /// Set temp dir for atomic write.
+    ///
+    /// Temp files will be created in the same dir of target file by
+    /// default. The temp dir must be on the same filesystem as root so that
+    /// temp files can be renamed.
     pub fn atomic_write_dir(&mut self, dir: &str) -> &mut Self {
         self.atomic_write_dir = if dir.is_empty() {
             None
@@ -113,15 +177,35 @@ impl FsBuilder {
         self
     }
 
+    /// Set how to handle symlinks, available values are `follow`,
+    /// `skip_missing` and `report`, default to `follow`.
+    ///
+    /// Refer to [`FsBackend`]'s symlinks section for more information.
+    pub fn symlink_mode(&mut self, mode: &str) -> &mut Self {
+        self.symlink_mode = if mode.is_empty() {
+            None
+        } else {
+            Some(mode.to_string())
+        };
+
+        self
+    }
+
+    /// Enable or disable preallocating files while writing with known
+    /// content length, default to enabled on linux and disabled on other
+    /// platforms.
+    pub fn preallocate(&mut self, enabled: bool) -> &mut Self {
+        self.preallocate = Some(enabled);
+
+        self
+    }
+
     /// OpenDAL requires all input path are normalized to make sure the
-    /// behavior is consistent. By enable path check, we can make sure
-    /// fs will behave the same as other services.
+    /// behavior is consistent.
     ///
-    /// Enabling this feature will lead to extra metadata call in all
-    /// operations.
+    /// Path will always be checked now, this function is kept for
+    /// compatibility and does nothing.
     pub fn enable_path_check(&mut self) -> &mut Self {
-        self.enable_path_check = true;
-
         self
     }
 }
@@ -134,8 +218,14 @@ impl Builder for FsBuilder {
         let mut builder = FsBuilder::default();
 
         map.get("root").map(|v| builder.root(v));
+        map.get("atomic_write")
+            .filter(|v| *v == "off" || *v == "false")
+            .map(|_| builder.atomic_write(false));
         map.get("atomic_write_dir")
             .map(|v| builder.atomic_write_dir(v));
+        map.get("symlink_mode").map(|v| builder.symlink_mode(v));
+        map.get("preallocate")
+            .map(|v| builder.preallocate(v == "on" || v == "true"));
 
         builder
     }
@@ -208,11 +298,27 @@ impl Builder for FsBuilder {
             })
             .unwrap_or(Ok(None))?;
 
+        let symlink_mode = match self.symlink_mode.as_deref() {
+            None | Some("follow") => SymlinkMode::Follow,
+            Some("skip_missing") => SymlinkMode::SkipMissing,
+            Some("report") => SymlinkMode::Report,
+            Some(v) => {
+                return Err(
+                    Error::new(ErrorKind::ConfigInvalid, "symlink_mode is invalid")
+                        .with_operation("Builder::build")
+                        .with_context("service", Scheme::Fs)
+                        .with_context("symlink_mode", v),
+                )
+            }
+        };
+
         debug!("backend build finished: {:?}", &self);
         Ok(FsBackend {
             root,
+            atomic_write: !self.disable_atomic_write,
             atomic_write_dir,
-            enable_path_check: self.enable_path_check,
+            symlink_mode,
+            preallocate: self.preallocate.unwrap_or(cfg!(target_os = "linux")),
         })
     }
 }
@@ -221,8 +327,10 @@ impl Builder for FsBuilder {
 #[derive(Debug, Clone)]
 pub struct FsBackend {
     root: PathBuf,
+    atomic_write: bool,
     atomic_write_dir: Option<PathBuf>,
-    enable_path_check: bool,
+    symlink_mode: SymlinkMode,
+    preallocate: bool,
 }
 
 #[inline]
@@ -230,7 +338,22 @@ fn tmp_file_of(path: &str) -> String {
     let name = get_basename(path);
     let uuid = Uuid::new_v4().to_string();
 
-    format!("{name}.{uuid}")
+    format!(".{name}.tmp.{uuid}")
+}
+
+/// Check if given file name is a temp file created by [`tmp_file_of`].
+///
+/// Temp files of in-progress writes should not be visible while listing.
+#[inline]
+pub(super) fn is_tmp_file(name: &str) -> bool {
+    if !name.starts_with('.') {
+        return false;
+    }
+
+    match name.rsplit_once(".tmp.") {
+        Some((_, uuid)) => Uuid::parse_str(uuid).is_ok(),
+        None => false,
+    }
 }
 
 impl FsBackend {
@@ -306,7 +429,9 @@ impl Accessor for FsBackend {
                     | AccessorCapability::Copy
                     | AccessorCapability::Rename
                     | AccessorCapability::List
-                    | AccessorCapability::Blocking,
+                    | AccessorCapability::Blocking
+                    | AccessorCapability::ListWithLimit
+                    | AccessorCapability::WriteWithLastModified,
             )
             .set_hints(AccessorHint::ReadSeekable);
 
@@ -335,34 +460,29 @@ impl Accessor for FsBackend {
 
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let mut f = fs::OpenOptions::new()
+        let f = fs::OpenOptions::new()
             .read(true)
             .open(&p)
             .await
             .map_err(parse_io_error)?;
 
-        let total_length = if self.enable_path_check {
-            // Get fs metadata of file at given path, ensuring it is not a false-positive due to slash normalization.
-            let meta = f.metadata().await.map_err(parse_io_error)?;
-            if meta.is_dir() != path.ends_with('/') {
-                return Err(Error::new(
-                    ErrorKind::NotFound,
-                    "file mode is not match with its path",
-                ));
-            }
+        // Get fs metadata of file at given path, ensuring it is not a false-positive due to slash normalization.
+        let meta = f.metadata().await.map_err(parse_io_error)?;
+        check_path_mode(
+            path,
             if meta.is_dir() {
-                return Err(Error::new(
-                    ErrorKind::IsADirectory,
-                    "given path is a directory",
-                ));
-            }
-
-            meta.len()
-        } else {
-            use tokio::io::AsyncSeekExt;
-
-            f.seek(SeekFrom::End(0)).await.map_err(parse_io_error)?
-        };
+                EntryMode::DIR
+            } else {
+                EntryMode::FILE
+            },
+        )?;
+        if meta.is_dir() {
+            return Err(Error::new(
+                ErrorKind::IsADirectory,
+                "given path is a directory",
+            ));
+        }
+        let total_length = meta.len();
 
         let f = Compat::new(f);
 
@@ -393,16 +513,14 @@ impl Accessor for FsBackend {
         Ok((RpRead::new(end - start), r))
     }
 
-    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let (target_path, tmp_path) = if let Some(atomic_write_dir) = &self.atomic_write_dir {
-            let target_path = Self::ensure_write_abs_path(&self.root, path).await?;
-            let tmp_path =
-                Self::ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path)).await?;
-            (target_path, Some(tmp_path))
-        } else {
-            let p = Self::ensure_write_abs_path(&self.root, path).await?;
-
-            (p, None)
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let target_path = Self::ensure_write_abs_path(&self.root, path).await?;
+        let tmp_path = match (self.atomic_write, &self.atomic_write_dir) {
+            (false, _) => None,
+            (true, Some(atomic_write_dir)) => {
+                Some(Self::ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path)).await?)
+            }
+            (true, None) => Some(target_path.with_file_name(tmp_file_of(path))),
         };
 
         let f = tokio::fs::OpenOptions::new()
@@ -413,18 +531,32 @@ impl Accessor for FsBackend {
             .await
             .map_err(parse_io_error)?;
 
-        Ok((RpWrite::new(), FsWriter::new(target_path, tmp_path, f)))
+        let mut w = FsWriter::new(target_path, tmp_path, f, &args);
+        match args.content_length() {
+            Some(size) if self.preallocate && size > 0 => w.preallocate(size).await?,
+            _ => {}
+        }
+
+        Ok((RpWrite::new(), w))
     }
 
     async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
         let from = self.root.join(from.trim_end_matches('/'));
 
         // try to get the metadata of the source file to ensure it exists
-        tokio::fs::metadata(&from).await.map_err(parse_io_error)?;
+        let meta = tokio::fs::metadata(&from).await.map_err(parse_io_error)?;
 
         let to = Self::ensure_write_abs_path(&self.root, to.trim_end_matches('/')).await?;
 
-        tokio::fs::copy(from, to).await.map_err(parse_io_error)?;
+        let copy_to = to.clone();
+        tokio::task::spawn_blocking(move || copy_file(&from, &copy_to))
+            .await
+            .map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "copy task failed to complete").set_source(err)
+            })?
+            .map_err(parse_io_error)?;
+        // Destination inherits the modification time of source.
+        set_last_modified(&to, FileTime::from_last_modification_time(&meta));
 
         Ok(RpCopy::default())
     }
@@ -445,14 +577,12 @@ impl Accessor for FsBackend {
     async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let meta = tokio::fs::metadata(&p).await.map_err(parse_io_error)?;
-
-        if self.enable_path_check && meta.is_dir() != path.ends_with('/') {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "file mode is not match with its path",
-            ));
+        let meta = if self.symlink_mode == SymlinkMode::Report {
+            tokio::fs::symlink_metadata(&p).await
+        } else {
+            tokio::fs::metadata(&p).await
         }
+        .map_err(parse_io_error)?;
 
         let mode = if meta.is_dir() {
             EntryMode::DIR
@@ -478,6 +608,9 @@ impl Accessor for FsBackend {
         let meta = tokio::fs::metadata(&p).await;
 
         match meta {
+            // Path is not match with the entry mode, which means the
+            // entry doesn't exist.
+            Ok(meta) if meta.is_dir() != path.ends_with('/') => Ok(RpDelete::default()),
             Ok(meta) => {
                 if meta.is_dir() {
                     fs::remove_dir(&p).await.map_err(parse_io_error)?;
@@ -506,7 +639,7 @@ impl Accessor for FsBackend {
             }
         };
 
-        let rd = FsPager::new(&self.root, f, args.limit());
+        let rd = FsPager::new(&self.root, f, args.limit(), self.symlink_mode);
 
         Ok((RpList::default(), Some(rd)))
     }
@@ -524,33 +657,28 @@ impl Accessor for FsBackend {
 
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let mut f = std::fs::OpenOptions::new()
+        let f = std::fs::OpenOptions::new()
             .read(true)
             .open(p)
             .map_err(parse_io_error)?;
 
-        let total_length = if self.enable_path_check {
-            // Get fs metadata of file at given path, ensuring it is not a false-positive due to slash normalization.
-            let meta = f.metadata().map_err(parse_io_error)?;
-            if meta.is_dir() != path.ends_with('/') {
-                return Err(Error::new(
-                    ErrorKind::NotFound,
-                    "file mode is not match with its path",
-                ));
-            }
+        // Get fs metadata of file at given path, ensuring it is not a false-positive due to slash normalization.
+        let meta = f.metadata().map_err(parse_io_error)?;
+        check_path_mode(
+            path,
             if meta.is_dir() {
-                return Err(Error::new(
-                    ErrorKind::IsADirectory,
-                    "given path is a directory",
-                ));
-            }
-
-            meta.len()
-        } else {
-            use std::io::Seek;
-
-            f.seek(SeekFrom::End(0)).map_err(parse_io_error)?
-        };
+                EntryMode::DIR
+            } else {
+                EntryMode::FILE
+            },
+        )?;
+        if meta.is_dir() {
+            return Err(Error::new(
+                ErrorKind::IsADirectory,
+                "given path is a directory",
+            ));
+        }
+        let total_length = meta.len();
 
         let br = args.range();
         let (start, end) = match (br.offset(), br.size()) {
@@ -579,16 +707,15 @@ impl Accessor for FsBackend {
         Ok((RpRead::new(end - start), r))
     }
 
-    fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        let (target_path, tmp_path) = if let Some(atomic_write_dir) = &self.atomic_write_dir {
-            let target_path = Self::blocking_ensure_write_abs_path(&self.root, path)?;
-            let tmp_path =
-                Self::blocking_ensure_write_abs_path(atomic_write_dir, &tmp_file_of(path))?;
-            (target_path, Some(tmp_path))
-        } else {
-            let p = Self::blocking_ensure_write_abs_path(&self.root, path)?;
-
-            (p, None)
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        let target_path = Self::blocking_ensure_write_abs_path(&self.root, path)?;
+        let tmp_path = match (self.atomic_write, &self.atomic_write_dir) {
+            (false, _) => None,
+            (true, Some(atomic_write_dir)) => Some(Self::blocking_ensure_write_abs_path(
+                atomic_write_dir,
+                &tmp_file_of(path),
+            )?),
+            (true, None) => Some(target_path.with_file_name(tmp_file_of(path))),
         };
 
         let f = std::fs::OpenOptions::new()
@@ -598,18 +725,26 @@ impl Accessor for FsBackend {
             .open(tmp_path.as_ref().unwrap_or(&target_path))
             .map_err(parse_io_error)?;
 
-        Ok((RpWrite::new(), FsWriter::new(target_path, tmp_path, f)))
+        let mut w = FsWriter::new(target_path, tmp_path, f, &args);
+        match args.content_length() {
+            Some(size) if self.preallocate && size > 0 => w.preallocate(size)?,
+            _ => {}
+        }
+
+        Ok((RpWrite::new(), w))
     }
 
     fn blocking_copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
         let from = self.root.join(from.trim_end_matches('/'));
 
         // try to get the metadata of the source file to ensure it exists
-        std::fs::metadata(&from).map_err(parse_io_error)?;
+        let meta = std::fs::metadata(&from).map_err(parse_io_error)?;
 
         let to = Self::blocking_ensure_write_abs_path(&self.root, to.trim_end_matches('/'))?;
 
-        std::fs::copy(from, to).map_err(parse_io_error)?;
+        copy_file(&from, &to).map_err(parse_io_error)?;
+        // Destination inherits the modification time of source.
+        set_last_modified(&to, FileTime::from_last_modification_time(&meta));
 
         Ok(RpCopy::default())
     }
@@ -630,14 +765,12 @@ impl Accessor for FsBackend {
     fn blocking_stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
         let p = self.root.join(path.trim_end_matches('/'));
 
-        let meta = std::fs::metadata(p).map_err(parse_io_error)?;
-
-        if self.enable_path_check && meta.is_dir() != path.ends_with('/') {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                "file mode is not match with its path",
-            ));
+        let meta = if self.symlink_mode == SymlinkMode::Report {
+            std::fs::symlink_metadata(p)
+        } else {
+            std::fs::metadata(p)
         }
+        .map_err(parse_io_error)?;
 
         let mode = if meta.is_dir() {
             EntryMode::DIR
@@ -663,6 +796,9 @@ impl Accessor for FsBackend {
         let meta = std::fs::metadata(&p);
 
         match meta {
+            // Path is not match with the entry mode, which means the
+            // entry doesn't exist.
+            Ok(meta) if meta.is_dir() != path.ends_with('/') => Ok(RpDelete::default()),
             Ok(meta) => {
                 if meta.is_dir() {
                     std::fs::remove_dir(&p).map_err(parse_io_error)?;
@@ -691,7 +827,7 @@ impl Accessor for FsBackend {
             }
         };
 
-        let rd = FsPager::new(&self.root, f, args.limit());
+        let rd = FsPager::new(&self.root, f, args.limit(), self.symlink_mode);
 
         Ok((RpList::default(), Some(rd)))
     }
@@ -699,20 +835,318 @@ impl Accessor for FsBackend {
 
 #[cfg(test)]
 mod tests {
+    use chrono::Utc;
+    use futures::TryStreamExt;
+
     use super::*;
 
     #[test]
     fn test_tmp_file_of() {
         let cases = vec![
-            ("hello.txt", "hello.txt"),
-            ("/tmp/opendal.log", "opendal.log"),
-            ("/abc/def/hello.parquet", "hello.parquet"),
+            ("hello.txt", ".hello.txt.tmp."),
+            ("/tmp/opendal.log", ".opendal.log.tmp."),
+            ("/abc/def/hello.parquet", ".hello.parquet.tmp."),
         ];
 
         for (path, expected_prefix) in cases {
             let tmp_file = tmp_file_of(path);
             assert!(tmp_file.len() > expected_prefix.len());
             assert!(tmp_file.starts_with(expected_prefix));
+            assert!(is_tmp_file(&tmp_file), "{tmp_file}");
+        }
+    }
+
+    #[test]
+    fn test_is_tmp_file() {
+        let cases = vec![
+            ("hello.txt", false),
+            (".hello.txt", false),
+            (".hello.txt.tmp.", false),
+            (".hello.txt.tmp.not-a-uuid", false),
+            ("hello.txt.tmp.9fd8b3a6-5d4f-4c4e-8d5a-2f1b6b2c7e10", false),
+            (".hello.txt.tmp.9fd8b3a6-5d4f-4c4e-8d5a-2f1b6b2c7e10", true),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(is_tmp_file(name), expected, "{name}");
+        }
+    }
+
+    fn test_root() -> PathBuf {
+        std::env::temp_dir().join(format!("opendal-fs-{}", Uuid::new_v4()))
+    }
+
+    /// Return names of all files in the dir.
+    fn list_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .expect("read dir must succeed")
+            .map(|v| {
+                v.expect("entry must be valid")
+                    .file_name()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write() -> Result<()> {
+        let root = test_root();
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        op.write("dir/file", "old content").await?;
+        assert_eq!(list_names(&root.join("dir")), vec!["file"]);
+
+        let mut w = op.writer("dir/file").await?;
+        w.append("new").await?;
+        // Readers will not observe the partial written content.
+        assert_eq!(op.read("dir/file").await?, b"old content");
+        // Listers will not observe the temp file.
+        assert_eq!(list_names(&root.join("dir")).len(), 2);
+        let entries: Vec<_> = op.list("dir/").await?.try_collect().await?;
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["dir/file"]);
+        let paths = op
+            .blocking()
+            .list("dir/")?
+            .map(|e| e.map(|e| e.path().to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(paths, vec!["dir/file"]);
+        w.append(" content").await?;
+        w.close().await?;
+        assert_eq!(op.read("dir/file").await?, b"new content");
+        assert_eq!(list_names(&root.join("dir")), vec!["file"]);
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_original_file() -> Result<()> {
+        let root = test_root();
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "old content").await?;
+
+        // Abort the writer.
+        let mut w = op.writer("file").await?;
+        w.append("partial").await?;
+        w.abort().await?;
+        assert_eq!(op.read("file").await?, b"old content");
+        assert_eq!(list_names(&root), vec!["file"]);
+
+        // Drop the writer without close, it will be aborted in background.
+        let mut w = op.writer("file").await?;
+        w.append("partial").await?;
+        drop(w);
+        assert_eq!(op.read("file").await?, b"old content");
+        for _ in 0..100 {
+            if list_names(&root) == vec!["file"] {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         }
+        assert_eq!(list_names(&root), vec!["file"]);
+
+        // Drop the blocking writer without close.
+        let mut w = op.blocking().writer("file")?;
+        w.append("partial")?;
+        drop(w);
+        assert_eq!(op.read("file").await?, b"old content");
+        assert_eq!(list_names(&root), vec!["file"]);
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disable_atomic_write() -> Result<()> {
+        let root = test_root();
+        let builder = FsBuilder::from_map(HashMap::from([
+            ("root".to_string(), root.to_string_lossy().to_string()),
+            ("atomic_write".to_string(), "false".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "old content").await?;
+
+        // Data will be written to the target file directly.
+        let mut w = op.writer("file").await?;
+        w.append("new").await?;
+        assert_eq!(op.read("file").await?, b"new");
+        let err = w.abort().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        w.close().await?;
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_last_modified() -> Result<()> {
+        let root = test_root();
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder)?.finish();
+
+        let last_modified = DateTime::parse_from_rfc3339("2022-03-04T05:06:07.123456789Z")
+            .expect("parse time must succeed")
+            .with_timezone(&Utc);
+        let args = OpWrite::new().with_last_modified(last_modified);
+
+        op.write_with("file", args.clone(), "content").await?;
+        // Modification time should be reported in full precision.
+        assert_eq!(op.stat("file").await?.last_modified(), Some(last_modified));
+
+        op.copy("file", "dir/copied").await?;
+        assert_eq!(
+            op.stat("dir/copied").await?.last_modified(),
+            Some(last_modified)
+        );
+
+        op.blocking().write_with("blocking_file", args, "content")?;
+        op.blocking().copy("blocking_file", "blocking_copied")?;
+        assert_eq!(
+            op.blocking().stat("blocking_copied")?.last_modified(),
+            Some(last_modified)
+        );
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_preallocate() -> Result<()> {
+        let root = test_root();
+        let mut builder = FsBuilder::default();
+        builder.root(&root.to_string_lossy()).preallocate(true);
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "content").await?;
+        assert_eq!(op.read("file").await?, b"content");
+        op.blocking().write("blocking_file", "content")?;
+        assert_eq!(op.read("blocking_file").await?, b"content");
+
+        // File should be shrunk to the actual written size.
+        let mut w = op
+            .writer_with("shrunk_file", OpWrite::new().with_content_length(1024))
+            .await?;
+        w.append("abc").await?;
+        w.close().await?;
+        assert_eq!(op.read("shrunk_file").await?, b"abc");
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    /// Return path and mode of entries under root.
+    #[cfg(unix)]
+    fn list_entries(op: &Operator) -> Result<Vec<(String, EntryMode)>> {
+        let op = op.blocking();
+        let mut entries = op
+            .list("/")?
+            .map(|e| {
+                let e = e?;
+                let meta = op.metadata(&e, Metakey::Mode)?;
+                Ok((e.path().to_string(), meta.mode()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_mode() -> Result<()> {
+        let root = test_root();
+        std::fs::create_dir_all(root.join("dir")).expect("create dir must succeed");
+        std::fs::write(root.join("file"), "content").expect("write file must succeed");
+        std::os::unix::fs::symlink(root.join("dir"), root.join("link_dir"))
+            .expect("create symlink must succeed");
+        std::os::unix::fs::symlink(root.join("file"), root.join("link_file"))
+            .expect("create symlink must succeed");
+        std::os::unix::fs::symlink(root.join("missing"), root.join("link_missing"))
+            .expect("create symlink must succeed");
+
+        let cases = vec![
+            (
+                "follow",
+                vec![
+                    ("dir/", EntryMode::DIR),
+                    ("file", EntryMode::FILE),
+                    ("link_dir/", EntryMode::DIR),
+                    ("link_file", EntryMode::FILE),
+                    ("link_missing", EntryMode::Unknown),
+                ],
+            ),
+            (
+                "skip_missing",
+                vec![
+                    ("dir/", EntryMode::DIR),
+                    ("file", EntryMode::FILE),
+                    ("link_dir/", EntryMode::DIR),
+                    ("link_file", EntryMode::FILE),
+                ],
+            ),
+            (
+                "report",
+                vec![
+                    ("dir/", EntryMode::DIR),
+                    ("file", EntryMode::FILE),
+                    ("link_dir", EntryMode::Unknown),
+                    ("link_file", EntryMode::Unknown),
+                    ("link_missing", EntryMode::Unknown),
+                ],
+            ),
+        ];
+
+        for (mode, expected) in cases {
+            let builder = FsBuilder::from_map(HashMap::from([
+                ("root".to_string(), root.to_string_lossy().to_string()),
+                ("symlink_mode".to_string(), mode.to_string()),
+            ]));
+            let op = Operator::new(builder)?.finish();
+
+            let expected: Vec<_> = expected
+                .into_iter()
+                .map(|(p, m)| (p.to_string(), m))
+                .collect();
+            assert_eq!(list_entries(&op)?, expected, "symlink mode: {mode}");
+
+            let entries: Vec<_> = op.list("/").await?.try_collect().await?;
+            assert_eq!(entries.len(), expected.len(), "symlink mode: {mode}");
+
+            let meta = op.stat("link_missing").await;
+            if mode == "report" {
+                assert_eq!(meta?.mode(), EntryMode::Unknown);
+            } else {
+                let err = meta.expect_err("stat dangling symlink must fail");
+                assert_eq!(err.kind(), ErrorKind::NotFound, "symlink mode: {mode}");
+                let err = op
+                    .blocking()
+                    .stat("link_missing")
+                    .expect_err("stat dangling symlink must fail");
+                assert_eq!(err.kind(), ErrorKind::NotFound, "symlink mode: {mode}");
+            }
+        }
+
+        std::fs::remove_dir_all(root).expect("remove root must succeed");
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_symlink_mode() {
+        let mut builder = FsBuilder::default();
+        builder
+            .root(&std::env::temp_dir().to_string_lossy())
+            .symlink_mode("invalid");
+        let err = builder.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
     }
 }
@@ -0,0 +1,221 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+use std::path::Path;
+
+/// Copy the content and permissions of `from` to `to`, `to` will be
+/// overwritten if exists.
+///
+/// On linux, we will try `copy_file_range` first, and then fallback to
+/// reflink (`FICLONE` ioctl) and the read/write loop if it's not supported
+/// (for example, cross filesystem copy on old kernels).
+///
+/// On other platforms, we will use [`std::fs::copy`] directly which will
+/// use `fclonefileat` and `fcopyfile` on macOS.
+pub fn copy_file(from: &Path, to: &Path) -> io::Result<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::copy_file(from, to)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        std::fs::copy(from, to)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::File;
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+    use std::ptr;
+
+    /// Max bytes to copy in one `copy_file_range` call.
+    const MAX_COPY_SIZE: u64 = 1 << 30;
+
+    pub fn copy_file(from: &Path, to: &Path) -> io::Result<u64> {
+        let mut reader = File::open(from)?;
+        let meta = reader.metadata()?;
+        if !meta.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the source path is not an existing regular file",
+            ));
+        }
+
+        let mut writer = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(to)?;
+        writer.set_permissions(meta.permissions())?;
+
+        let len = meta.len();
+        // Files like procfs report zero length, fallback to the generic way
+        // to make sure all content has been copied.
+        if len == 0 {
+            return io::copy(&mut reader, &mut writer);
+        }
+
+        if let Some(n) = copy_file_range(&reader, &writer, len)? {
+            return Ok(n);
+        }
+        if reflink(&reader, &writer)? {
+            return Ok(len);
+        }
+
+        reader.seek(SeekFrom::Start(0))?;
+        writer.seek(SeekFrom::Start(0))?;
+        io::copy(&mut reader, &mut writer)
+    }
+
+    /// Returns `None` if `copy_file_range` is not supported between given files.
+    fn copy_file_range(reader: &File, writer: &File, len: u64) -> io::Result<Option<u64>> {
+        let mut written = 0;
+        while written < len {
+            let size = (len - written).min(MAX_COPY_SIZE) as usize;
+            // SAFETY: fds are valid during the call, and null offsets means
+            // using and updating the file offsets.
+            let n = unsafe {
+                libc::copy_file_range(
+                    reader.as_raw_fd(),
+                    ptr::null_mut(),
+                    writer.as_raw_fd(),
+                    ptr::null_mut(),
+                    size,
+                    0,
+                )
+            };
+
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                match err.raw_os_error() {
+                    Some(libc::EINTR) => continue,
+                    Some(
+                        libc::EXDEV | libc::ENOSYS | libc::EOPNOTSUPP | libc::EINVAL | libc::EPERM,
+                    ) if written == 0 => return Ok(None),
+                    _ => return Err(err),
+                }
+            }
+            // Source file has been truncated during copy.
+            if n == 0 {
+                break;
+            }
+            written += n as u64;
+        }
+
+        Ok(Some(written))
+    }
+
+    /// Returns `false` if reflink is not supported between given files.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    fn reflink(reader: &File, writer: &File) -> io::Result<bool> {
+        // SAFETY: fds are valid during the call.
+        let ret =
+            unsafe { libc::ioctl(writer.as_raw_fd(), libc::FICLONE as _, reader.as_raw_fd()) };
+        if ret == 0 {
+            return Ok(true);
+        }
+
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EXDEV | libc::ENOTTY | libc::EOPNOTSUPP | libc::EINVAL | libc::EPERM) => {
+                Ok(false)
+            }
+            _ => Err(err),
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    fn reflink(_: &File, _: &File) -> io::Result<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rand::prelude::*;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn test_dir(parent: &Path) -> PathBuf {
+        let dir = parent.join(format!("opendal-fs-copy-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create dir must succeed");
+        dir
+    }
+
+    fn check_copy(from_dir: &Path, to_dir: &Path) {
+        let mut rng = thread_rng();
+        for size in [0, 1, 4096, 3 * 1024 * 1024 + 7] {
+            let mut content = vec![0; size];
+            rng.fill_bytes(&mut content);
+
+            let from = from_dir.join(format!("from-{size}"));
+            let to = to_dir.join(format!("to-{size}"));
+            std::fs::write(&from, &content).expect("write must succeed");
+            // Destination should be overwritten.
+            std::fs::write(&to, vec![1; size + 1024]).expect("write must succeed");
+
+            let n = copy_file(&from, &to).expect("copy must succeed");
+            assert_eq!(n, size as u64);
+            assert_eq!(std::fs::read(&to).expect("read must succeed"), content);
+        }
+    }
+
+    #[test]
+    fn test_copy_file() {
+        let dir = test_dir(&std::env::temp_dir());
+        check_copy(&dir, &dir);
+
+        std::fs::remove_dir_all(dir).expect("remove dir must succeed");
+    }
+
+    /// Copy across filesystems should fallback transparently.
+    #[test]
+    fn test_copy_file_across_filesystems() {
+        let shm = Path::new("/dev/shm");
+        if !shm.is_dir() {
+            return;
+        }
+
+        let from_dir = test_dir(shm);
+        let to_dir = test_dir(&std::env::temp_dir());
+        check_copy(&from_dir, &to_dir);
+        check_copy(&to_dir, &from_dir);
+
+        std::fs::remove_dir_all(from_dir).expect("remove dir must succeed");
+        std::fs::remove_dir_all(to_dir).expect("remove dir must succeed");
+    }
+
+    #[test]
+    fn test_copy_not_found() {
+        let dir = test_dir(&std::env::temp_dir());
+        let err = copy_file(&dir.join("not_exist"), &dir.join("to")).expect_err("must fail");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        std::fs::remove_dir_all(dir).expect("remove dir must succeed");
+    }
+}
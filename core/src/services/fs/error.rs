@@ -24,6 +24,11 @@ use crate::ErrorKind;
 pub fn parse_io_error(err: io::Error) -> Error {
     use io::ErrorKind::*;
 
+    if is_no_space(&err) {
+        return Error::new(ErrorKind::InsufficientStorage, "no space left on device")
+            .set_source(err);
+    }
+
     let (kind, retryable) = match err.kind() {
         NotFound => (ErrorKind::NotFound, false),
         PermissionDenied => (ErrorKind::PermissionDenied, false),
@@ -39,3 +44,41 @@ pub fn parse_io_error(err: io::Error) -> Error {
 
     err
 }
+
+/// Check if the error is caused by no space left or quota exceeded.
+fn is_no_space(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        matches!(err.raw_os_error(), Some(libc::ENOSPC | libc::EDQUOT))
+    }
+
+    #[cfg(windows)]
+    {
+        // ERROR_HANDLE_DISK_FULL and ERROR_DISK_FULL
+        matches!(err.raw_os_error(), Some(39 | 112))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = err;
+        false
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_no_space_error() {
+        for code in [libc::ENOSPC, libc::EDQUOT] {
+            let err = parse_io_error(io::Error::from_raw_os_error(code));
+            assert_eq!(err.kind(), ErrorKind::InsufficientStorage);
+            assert!(!err.is_temporary());
+        }
+
+        let err = parse_io_error(io::Error::from_raw_os_error(libc::ENOENT));
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+}
@@ -18,6 +18,7 @@
 mod backend;
 pub use backend::FsBuilder as Fs;
 
+mod copy;
 mod error;
 mod pager;
 mod writer;
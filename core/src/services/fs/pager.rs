@@ -19,41 +19,75 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use log::warn;
 
+use super::backend::is_tmp_file;
 use super::error::parse_io_error;
 use crate::raw::*;
 use crate::EntryMode;
 use crate::Metadata;
 use crate::Result;
 
+/// How to handle symlinks while listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkMode {
+    /// Follow symlinks and report the type of their targets, symlinks whose
+    /// targets are missing will be reported as unknown entries.
+    Follow,
+    /// Follow symlinks but skip the ones whose targets are missing.
+    SkipMissing,
+    /// Report symlinks themselves as unknown entries without following.
+    Report,
+}
+
 pub struct FsPager<P> {
     root: PathBuf,
+    symlink_mode: SymlinkMode,
 
     size: usize,
     rd: P,
 }
 
 impl<P> FsPager<P> {
-    pub fn new(root: &Path, rd: P, limit: Option<usize>) -> Self {
+    pub fn new(root: &Path, rd: P, limit: Option<usize>, symlink_mode: SymlinkMode) -> Self {
         Self {
             root: root.to_owned(),
+            symlink_mode,
             size: limit.unwrap_or(1000),
             rd,
         }
     }
 }
 
+/// Build entry via the file type of given path, `None` means the file type
+/// can't be detected.
+fn new_entry(rel_path: &str, file_type: Option<std::fs::FileType>) -> oio::Entry {
+    match file_type {
+        Some(ft) if ft.is_file() => oio::Entry::new(rel_path, Metadata::new(EntryMode::FILE)),
+        // Make sure we are returning the correct path.
+        Some(ft) if ft.is_dir() => {
+            oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
+        }
+        _ => oio::Entry::new(rel_path, Metadata::new(EntryMode::Unknown)),
+    }
+}
+
 #[async_trait]
 impl oio::Page for FsPager<tokio::fs::ReadDir> {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
         let mut oes: Vec<oio::Entry> = Vec::with_capacity(self.size);
 
-        for _ in 0..self.size {
+        while oes.len() < self.size {
             let de = match self.rd.next_entry().await.map_err(parse_io_error)? {
                 Some(de) => de,
                 None => break,
             };
 
+            // Skip temp files of in-progress writes.
+            if is_tmp_file(&de.file_name().to_string_lossy()) {
+                continue;
+            }
+
             let entry_path = de.path();
             let rel_path = normalize_path(
                 &entry_path
@@ -67,18 +101,32 @@ impl oio::Page for FsPager<tokio::fs::ReadDir> {
             // (no extra system calls needed), but some Unix platforms may
             // require the equivalent call to symlink_metadata to learn about
             // the target file type.
-            let file_type = de.file_type().await.map_err(parse_io_error)?;
-
-            let d = if file_type.is_file() {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::FILE))
-            } else if file_type.is_dir() {
-                // Make sure we are returning the correct path.
-                oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
-            } else {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::Unknown))
+            //
+            // Errors of a single entry will not abort the listing.
+            let file_type = match de.file_type().await {
+                Ok(ft) if ft.is_symlink() && self.symlink_mode != SymlinkMode::Report => {
+                    match tokio::fs::metadata(&entry_path).await {
+                        Ok(meta) => Some(meta.file_type()),
+                        Err(err)
+                            if err.kind() == std::io::ErrorKind::NotFound
+                                && self.symlink_mode == SymlinkMode::SkipMissing =>
+                        {
+                            continue
+                        }
+                        Err(err) => {
+                            warn!("follow symlink {} failed: {err:?}", entry_path.display());
+                            None
+                        }
+                    }
+                }
+                Ok(ft) => Some(ft),
+                Err(err) => {
+                    warn!("get file type of {} failed: {err:?}", entry_path.display());
+                    None
+                }
             };
 
-            oes.push(d)
+            oes.push(new_entry(&rel_path, file_type))
         }
 
         Ok(if oes.is_empty() { None } else { Some(oes) })
@@ -89,12 +137,17 @@ impl oio::BlockingPage for FsPager<std::fs::ReadDir> {
     fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
         let mut oes: Vec<oio::Entry> = Vec::with_capacity(self.size);
 
-        for _ in 0..self.size {
+        while oes.len() < self.size {
             let de = match self.rd.next() {
                 Some(de) => de.map_err(parse_io_error)?,
                 None => break,
             };
 
+            // Skip temp files of in-progress writes.
+            if is_tmp_file(&de.file_name().to_string_lossy()) {
+                continue;
+            }
+
             let entry_path = de.path();
             let rel_path = normalize_path(
                 &entry_path
@@ -108,18 +161,32 @@ impl oio::BlockingPage for FsPager<std::fs::ReadDir> {
             // (no extra system calls needed), but some Unix platforms may
             // require the equivalent call to symlink_metadata to learn about
             // the target file type.
-            let file_type = de.file_type().map_err(parse_io_error)?;
-
-            let d = if file_type.is_file() {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::FILE))
-            } else if file_type.is_dir() {
-                // Make sure we are returning the correct path.
-                oio::Entry::new(&format!("{rel_path}/"), Metadata::new(EntryMode::DIR))
-            } else {
-                oio::Entry::new(&rel_path, Metadata::new(EntryMode::Unknown))
+            //
+            // Errors of a single entry will not abort the listing.
+            let file_type = match de.file_type() {
+                Ok(ft) if ft.is_symlink() && self.symlink_mode != SymlinkMode::Report => {
+                    match std::fs::metadata(&entry_path) {
+                        Ok(meta) => Some(meta.file_type()),
+                        Err(err)
+                            if err.kind() == std::io::ErrorKind::NotFound
+                                && self.symlink_mode == SymlinkMode::SkipMissing =>
+                        {
+                            continue
+                        }
+                        Err(err) => {
+                            warn!("follow symlink {} failed: {err:?}", entry_path.display());
+                            None
+                        }
+                    }
+                }
+                Ok(ft) => Some(ft),
+                Err(err) => {
+                    warn!("get file type of {} failed: {err:?}", entry_path.display());
+                    None
+                }
             };
 
-            oes.push(d)
+            oes.push(new_entry(&rel_path, file_type))
         }
 
         Ok(if oes.is_empty() { None } else { Some(oes) })
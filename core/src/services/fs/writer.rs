@@ -15,36 +15,151 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::io;
 use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use chrono::DateTime;
+use chrono::Utc;
+use filetime::FileTime;
+use log::warn;
 use tokio::io::AsyncSeekExt;
 use tokio::io::AsyncWriteExt;
 
 use super::error::parse_io_error;
+use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
 pub struct FsWriter<F> {
     target_path: PathBuf,
     tmp_path: Option<PathBuf>,
+    last_modified: Option<DateTime<Utc>>,
     f: F,
     pos: u64,
+    /// The preallocated size of file, which will be shrunk to the actual
+    /// written size while closing.
+    preallocated: Option<u64>,
 }
 
 impl<F> FsWriter<F> {
-    pub fn new(target_path: PathBuf, tmp_path: Option<PathBuf>, f: F) -> Self {
+    pub fn new(target_path: PathBuf, tmp_path: Option<PathBuf>, f: F, args: &OpWrite) -> Self {
         Self {
             target_path,
             tmp_path,
+            last_modified: args.last_modified(),
             f,
             pos: 0,
+            preallocated: None,
         }
     }
+
+    fn set_last_modified(&self) {
+        if let Some(last_modified) = self.last_modified {
+            let mtime = FileTime::from_unix_time(
+                last_modified.timestamp(),
+                last_modified.timestamp_subsec_nanos(),
+            );
+            set_last_modified(&self.target_path, mtime);
+        }
+    }
+}
+
+impl FsWriter<tokio::fs::File> {
+    /// Preallocate `size` bytes for the file.
+    pub async fn preallocate(&mut self, size: u64) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        let preallocated = fallocate(&self.f, size).map_err(parse_io_error)?;
+        #[cfg(not(target_os = "linux"))]
+        let preallocated = {
+            self.f.set_len(size).await.map_err(parse_io_error)?;
+            true
+        };
+
+        if preallocated {
+            self.preallocated = Some(size);
+        }
+        Ok(())
+    }
+
+    /// Shrink the file to the actual written size if it's preallocated.
+    async fn shrink(&mut self) -> Result<()> {
+        if let Some(size) = self.preallocated {
+            let written = self.f.stream_position().await.map_err(parse_io_error)?;
+            if written < size {
+                self.f.set_len(written).await.map_err(parse_io_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FsWriter<std::fs::File> {
+    /// Preallocate `size` bytes for the file.
+    pub fn preallocate(&mut self, size: u64) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        let preallocated = fallocate(&self.f, size).map_err(parse_io_error)?;
+        #[cfg(not(target_os = "linux"))]
+        let preallocated = {
+            self.f.set_len(size).map_err(parse_io_error)?;
+            true
+        };
+
+        if preallocated {
+            self.preallocated = Some(size);
+        }
+        Ok(())
+    }
+
+    /// Shrink the file to the actual written size if it's preallocated.
+    fn shrink(&mut self) -> Result<()> {
+        if let Some(size) = self.preallocated {
+            let written = self.f.stream_position().map_err(parse_io_error)?;
+            if written < size {
+                self.f.set_len(written).map_err(parse_io_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Allocate `size` bytes for the file via `fallocate`, so that we can fail
+/// fast if there is no enough space.
+///
+/// Returns `false` if `fallocate` is not supported by the filesystem.
+#[cfg(target_os = "linux")]
+fn fallocate(f: &impl AsRawFd, size: u64) -> io::Result<bool> {
+    // SAFETY: fd is valid during the call.
+    let ret = unsafe { libc::fallocate(f.as_raw_fd(), 0, 0, size as libc::off_t) };
+    if ret == 0 {
+        return Ok(true);
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EOPNOTSUPP | libc::ENOSYS) => Ok(false),
+        _ => Err(err),
+    }
+}
+
+/// Set the modification time of given path.
+///
+/// Setting times could fail on some filesystems, we only log a warning
+/// instead of failing the whole operation.
+pub fn set_last_modified(path: &Path, mtime: FileTime) {
+    if let Err(err) = filetime::set_file_mtime(path, mtime) {
+        warn!(
+            "set modification time of {} failed: {err:?}",
+            path.display()
+        );
+    }
 }
 
 #[async_trait]
@@ -75,21 +190,39 @@ impl oio::Write for FsWriter<tokio::fs::File> {
         Ok(())
     }
 
+    /// # Notes
+    ///
+    /// Only atomic write supports abort, the target file will be untouched
+    /// since the temp file will be removed.
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        let tmp_path = match self.tmp_path.take() {
+            Some(tmp_path) => tmp_path,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "output writer doesn't support abort without atomic write",
+                ))
+            }
+        };
+
+        match tokio::fs::remove_file(&tmp_path).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(parse_io_error(err)),
+        }
     }
 
     async fn close(&mut self) -> Result<()> {
+        self.shrink().await?;
         self.f.sync_all().await.map_err(parse_io_error)?;
 
         if let Some(tmp_path) = &self.tmp_path {
             tokio::fs::rename(tmp_path, &self.target_path)
                 .await
                 .map_err(parse_io_error)?;
+            self.tmp_path = None;
         }
+        self.set_last_modified();
 
         Ok(())
     }
@@ -121,13 +254,48 @@ impl oio::BlockingWrite for FsWriter<std::fs::File> {
         Ok(())
     }
 
+    /// # Notes
+    ///
+    /// Only atomic write supports abort, the target file will be untouched
+    /// since the temp file will be removed.
+    fn abort(&mut self) -> Result<()> {
+        let tmp_path = match self.tmp_path.take() {
+            Some(tmp_path) => tmp_path,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "output writer doesn't support abort without atomic write",
+                ))
+            }
+        };
+
+        match std::fs::remove_file(tmp_path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(parse_io_error(err)),
+        }
+    }
+
     fn close(&mut self) -> Result<()> {
+        self.shrink()?;
         self.f.sync_all().map_err(parse_io_error)?;
 
         if let Some(tmp_path) = &self.tmp_path {
             std::fs::rename(tmp_path, &self.target_path).map_err(parse_io_error)?;
+            self.tmp_path = None;
         }
+        self.set_last_modified();
 
         Ok(())
     }
 }
+
+/// Remove the temp file if writer is dropped without close, so that
+/// partial written content will not be left.
+impl<F> Drop for FsWriter<F> {
+    fn drop(&mut self) {
+        if let Some(tmp_path) = self.tmp_path.take() {
+            let _ = std::fs::remove_file(tmp_path);
+        }
+    }
+}
@@ -21,6 +21,7 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::str;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use async_tls::TlsConnector;
 use async_trait::async_trait;
@@ -30,6 +31,12 @@ use futures::AsyncRead;
 use futures::AsyncReadExt;
 use http::Uri;
 use log::debug;
+use rustls::Certificate;
+use rustls::ClientConfig;
+use rustls::RootCertStore;
+use rustls::ServerCertVerified;
+use rustls::ServerCertVerifier;
+use rustls::TLSError;
 use suppaftp::list::File;
 use suppaftp::types::FileType;
 use suppaftp::types::Response;
@@ -37,6 +44,7 @@ use suppaftp::FtpError;
 use suppaftp::FtpStream;
 use suppaftp::Status;
 use tokio::sync::OnceCell;
+use webpki::DNSNameRef;
 
 use super::pager::FtpPager;
 use super::util::FtpReader;
@@ -63,10 +71,22 @@ use crate::*;
 /// - `endpoint`: set the endpoint for connection
 /// - `root`: Set the work directory for backend
 /// - `credential`:  login credentials
-/// - `tls`: tls mode
+/// - `enable_secure`: force to use FTPS even if endpoint's scheme is `ftp`
+/// - `danger_accept_invalid_certs`: skip the certificate verification of FTPS
 ///
 /// You can refer to [`FtpBuilder`]'s docs for more information
 ///
+/// # FTPS
+///
+/// FTPS is enabled by endpoint scheme `ftps` (or endpoint without scheme),
+/// or by [`FtpBuilder::enable_secure`]. We will use explicit TLS: send
+/// `AUTH TLS` after connected, then switch both control and data channels
+/// to TLS via `PBSZ 0` and `PROT P`.
+///
+/// Server's certificate will be verified against the webpki roots. Use
+/// [`FtpBuilder::danger_accept_invalid_certs`] to skip the verification
+/// for servers using self-signed certificates.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -95,6 +115,8 @@ pub struct FtpBuilder {
     root: Option<String>,
     user: Option<String>,
     password: Option<String>,
+    enable_secure: bool,
+    danger_accept_invalid_certs: bool,
 }
 
 impl Debug for FtpBuilder {
@@ -102,6 +124,11 @@ impl Debug for FtpBuilder {
         f.debug_struct("Builder")
             .field("endpoint", &self.endpoint)
             .field("root", &self.root)
+            .field("enable_secure", &self.enable_secure)
+            .field(
+                "danger_accept_invalid_certs",
+                &self.danger_accept_invalid_certs,
+            )
             .finish()
     }
 }
@@ -150,6 +177,25 @@ impl FtpBuilder {
 
         self
     }
+
+    /// Enable FTPS (explicit TLS) even if endpoint's scheme is `ftp`.
+    pub fn enable_secure(&mut self) -> &mut Self {
+        self.enable_secure = true;
+        self
+    }
+
+    /// Accept invalid certificates while connecting to FTPS servers.
+    ///
+    /// # Warning
+    ///
+    /// This is dangerous: any certificate will be trusted, including
+    /// expired ones and ones issued for other hosts, which makes
+    /// connections vulnerable to man-in-the-middle attacks. Only use it
+    /// for servers with self-signed certificates in a trusted network.
+    pub fn danger_accept_invalid_certs(&mut self) -> &mut Self {
+        self.danger_accept_invalid_certs = true;
+        self
+    }
 }
 
 impl Builder for FtpBuilder {
@@ -175,10 +221,11 @@ impl Builder for FtpBuilder {
         let host = endpoint_uri.host().unwrap_or("127.0.0.1");
         let port = endpoint_uri.port_u16().unwrap_or(21);
 
+        let domain = host.to_string();
         let endpoint = format!("{host}:{port}");
 
         let enable_secure = match endpoint_uri.scheme_str() {
-            Some("ftp") => false,
+            Some("ftp") => self.enable_secure,
             // if the user forgot to add a scheme prefix
             // treat it as using secured scheme
             Some("ftps") | None => true,
@@ -208,10 +255,12 @@ impl Builder for FtpBuilder {
 
         Ok(FtpBackend {
             endpoint,
+            domain,
             root,
             user,
             password,
             enable_secure,
+            danger_accept_invalid_certs: self.danger_accept_invalid_certs,
             pool: OnceCell::new(),
         })
     }
@@ -223,6 +272,12 @@ impl Builder for FtpBuilder {
         map.get("endpoint").map(|v| builder.endpoint(v));
         map.get("user").map(|v| builder.user(v));
         map.get("password").map(|v| builder.password(v));
+        map.get("enable_secure")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_secure());
+        map.get("danger_accept_invalid_certs")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.danger_accept_invalid_certs());
 
         builder
     }
@@ -230,10 +285,43 @@ impl Builder for FtpBuilder {
 
 pub struct Manager {
     endpoint: String,
+    domain: String,
     root: String,
     user: String,
     password: String,
     enable_secure: bool,
+    danger_accept_invalid_certs: bool,
+}
+
+impl Manager {
+    fn tls_connector(&self) -> TlsConnector {
+        if self.danger_accept_invalid_certs {
+            let mut config = ClientConfig::new();
+            config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(NoCertificateVerification));
+            config.into()
+        } else {
+            TlsConnector::default()
+        }
+    }
+}
+
+/// NoCertificateVerification will accept all server certificates.
+///
+/// Only used when `danger_accept_invalid_certs` is enabled.
+struct NoCertificateVerification;
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _: &RootCertStore,
+        _: &[Certificate],
+        _: DNSNameRef,
+        _: &[u8],
+    ) -> std::result::Result<ServerCertVerified, TLSError> {
+        Ok(ServerCertVerified::assertion())
+    }
 }
 
 #[async_trait]
@@ -245,10 +333,18 @@ impl bb8::ManageConnection for Manager {
         let stream = FtpStream::connect(&self.endpoint).await?;
 
         // switch to secure mode if ssl/tls is on.
+        //
+        // Any failure during `AUTH TLS`, tls handshake, `PBSZ` and `PROT`
+        // will be reported as `SecureError` so that users can know it's
+        // the negotiation of FTPS that failed.
         let mut ftp_stream = if self.enable_secure {
             stream
-                .into_secure(TlsConnector::default().into(), &self.endpoint)
-                .await?
+                .into_secure(self.tls_connector().into(), &self.domain)
+                .await
+                .map_err(|err| match err {
+                    FtpError::SecureError(_) => err,
+                    err => FtpError::SecureError(err.to_string()),
+                })?
         } else {
             stream
         };
@@ -290,10 +386,12 @@ impl bb8::ManageConnection for Manager {
 #[derive(Clone)]
 pub struct FtpBackend {
     endpoint: String,
+    domain: String,
     root: String,
     user: String,
     password: String,
     enable_secure: bool,
+    danger_accept_invalid_certs: bool,
     pool: OnceCell<bb8::Pool<Manager>>,
 }
 
@@ -317,7 +415,10 @@ impl Accessor for FtpBackend {
         am.set_scheme(Scheme::Ftp)
             .set_root(&self.root)
             .set_capabilities(
-                AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::List,
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::List
+                    | AccessorCapability::ListWithLimit,
             );
 
         am
@@ -468,10 +569,12 @@ impl FtpBackend {
                     .max_size(64)
                     .build(Manager {
                         endpoint: self.endpoint.to_string(),
+                        domain: self.domain.to_string(),
                         root: self.root.to_string(),
                         user: self.user.to_string(),
                         password: self.password.to_string(),
                         enable_secure: self.enable_secure,
+                        danger_accept_invalid_certs: self.danger_accept_invalid_certs,
                     })
                     .await
             })
@@ -537,6 +640,15 @@ mod build_test {
         let b = builder.build();
         assert!(b.is_ok());
 
+        // ftp scheme with secure enabled
+        let mut builder = FtpBuilder::default();
+        builder
+            .endpoint("ftp://ftp_server.local:1234")
+            .enable_secure()
+            .danger_accept_invalid_certs();
+        let b = builder.build();
+        assert!(b.is_ok());
+
         // invalid scheme
         let mut builder = FtpBuilder::default();
         builder.endpoint("invalidscheme://ftp_server.local:8765");
@@ -35,6 +35,13 @@ impl From<FtpError> for Error {
             }
             // Allow retry bad response.
             FtpError::BadResponse => (ErrorKind::Unexpected, true),
+            // Failed to negotiate FTPS, it's most likely that server doesn't
+            // support explicit TLS or the certificate is not trusted.
+            FtpError::SecureError(_) => {
+                return Error::new(ErrorKind::ConfigInvalid, "ftps negotiation failed")
+                    .with_context("protocol", "FTPS (explicit TLS via AUTH TLS)")
+                    .set_source(e);
+            }
             _ => (ErrorKind::Unexpected, false),
         };
 
@@ -47,3 +54,17 @@ impl From<FtpError> for Error {
         err
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secure_error() {
+        let err: Error = FtpError::SecureError("invalid certificate".to_string()).into();
+
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(!err.is_temporary());
+        assert!(err.to_string().contains("AUTH TLS"));
+    }
+}
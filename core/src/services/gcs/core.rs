@@ -18,24 +18,52 @@
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::time::Duration;
 
 use backon::ExponentialBuilder;
 use backon::Retryable;
-use bytes::{Bytes, BytesMut};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::BytesMut;
+use chrono::DateTime;
+use chrono::Utc;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_RANGE;
 use http::header::CONTENT_TYPE;
-use http::header::{CONTENT_LENGTH, CONTENT_RANGE};
+use http::header::HOST;
+use http::header::RANGE;
+use http::HeaderMap;
+use http::HeaderValue;
+use http::Method;
 use http::Request;
 use http::Response;
+use http::StatusCode;
 use once_cell::sync::Lazy;
+use percent_encoding::percent_decode_str;
 use reqsign::GoogleCredentialLoader;
 use reqsign::GoogleSigner;
 use reqsign::GoogleToken;
 use reqsign::GoogleTokenLoader;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
 
+use super::error::parse_error;
 use super::uri::percent_encode_path;
+use super::uri::percent_encode_v4;
+use super::uri::percent_encode_v4_path;
 use crate::raw::*;
 use crate::*;
 
+const X_UPLOAD_CONTENT_TYPE: &str = "x-upload-content-type";
+const X_GOOG_ENCRYPTION_ALGORITHM: &str = "x-goog-encryption-algorithm";
+const X_GOOG_ENCRYPTION_KEY: &str = "x-goog-encryption-key";
+const X_GOOG_ENCRYPTION_KEY_SHA256: &str = "x-goog-encryption-key-sha256";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_ALGORITHM: &str = "x-goog-copy-source-encryption-algorithm";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_KEY: &str = "x-goog-copy-source-encryption-key";
+const X_GOOG_COPY_SOURCE_ENCRYPTION_KEY_SHA256: &str = "x-goog-copy-source-encryption-key-sha256";
+
 pub struct GcsCore {
     pub endpoint: String,
     pub bucket: String,
@@ -48,6 +76,15 @@ pub struct GcsCore {
 
     pub predefined_acl: Option<String>,
     pub default_storage_class: Option<String>,
+    pub write_chunk_size: usize,
+    pub service_account: Option<String>,
+    pub iam_endpoint: String,
+    pub enable_compose_append: bool,
+
+    /// Base64 encoded customer-supplied AES-256 encryption key.
+    pub encryption_key: Option<HeaderValue>,
+    /// Base64 encoded SHA-256 of the decoded encryption key.
+    pub encryption_key_sha256: Option<HeaderValue>,
 }
 
 impl Debug for GcsCore {
@@ -86,10 +123,85 @@ impl GcsCore {
         self.signer.sign(req, &cred).map_err(new_request_sign_error)
     }
 
+    /// Sign the request via V4 signed url.
+    ///
+    /// Service account key will be used if loaded, otherwise the string to
+    /// sign will be signed via IAM `signBlob` API as `service_account`.
+    pub async fn sign_query<T>(&self, req: &mut Request<T>, expire: Duration) -> Result<()> {
+        let cred = self
+            .credential_loader
+            .load()
+            .map_err(new_request_credential_error)?;
+        if let Some(cred) = cred {
+            return self
+                .signer
+                .sign_query(req, expire, &cred)
+                .map_err(new_request_sign_error);
+        }
+
+        let account = self.service_account.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::ConfigInvalid,
+                "presign requires either service account key or service_account",
+            )
+        })?;
+
+        let (query, string_to_sign) = build_v4_string_to_sign(req, account, Utc::now(), expire)?;
+        let signature = self
+            .gcs_sign_blob(account, string_to_sign.as_bytes())
+            .await?;
+        let signature: String = signature.iter().map(|b| format!("{b:02x}")).collect();
+
+        let uri = req.uri();
+        let url = format!(
+            "{}://{}{}?{}&X-Goog-Signature={}",
+            uri.scheme_str().unwrap_or("https"),
+            uri.authority().map(|v| v.as_str()).unwrap_or_default(),
+            uri.path(),
+            query,
+            signature
+        );
+        *req.uri_mut() = url.parse().map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "signed uri is invalid").set_source(err)
+        })?;
+
+        Ok(())
+    }
+
     #[inline]
     pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
         self.client.send(req).await
     }
+
+    /// Insert customer-supplied encryption key headers if `encryption_key`
+    /// is set.
+    pub fn insert_encryption_headers(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if let (Some(key), Some(sha256)) = (&self.encryption_key, &self.encryption_key_sha256) {
+            req = req
+                .header(X_GOOG_ENCRYPTION_ALGORITHM, "AES256")
+                .header(X_GOOG_ENCRYPTION_KEY, key)
+                .header(X_GOOG_ENCRYPTION_KEY_SHA256, sha256);
+        }
+        req
+    }
+
+    /// Insert customer-supplied encryption key headers for copy source, which
+    /// is required to rewrite objects encrypted with `encryption_key`.
+    pub fn insert_copy_source_encryption_headers(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if let (Some(key), Some(sha256)) = (&self.encryption_key, &self.encryption_key_sha256) {
+            req = req
+                .header(X_GOOG_COPY_SOURCE_ENCRYPTION_ALGORITHM, "AES256")
+                .header(X_GOOG_COPY_SOURCE_ENCRYPTION_KEY, key)
+                .header(X_GOOG_COPY_SOURCE_ENCRYPTION_KEY_SHA256, sha256);
+        }
+        req
+    }
 }
 
 impl GcsCore {
@@ -110,9 +222,11 @@ impl GcsCore {
         let mut req = Request::get(&url);
 
         if !range.is_full() {
-            req = req.header(http::header::RANGE, range.to_header());
+            req = req.header(RANGE, range.to_header());
         }
 
+        req = self.insert_encryption_headers(req);
+
         let req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -120,6 +234,38 @@ impl GcsCore {
         Ok(req)
     }
 
+    /// Build object request against the XML API, which is required by V4
+    /// signed urls.
+    pub fn gcs_xml_object_request(
+        &self,
+        method: Method,
+        path: &str,
+        range: BytesRange,
+        content_type: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.bucket,
+            percent_encode_v4_path(&p)
+        );
+
+        let mut req = Request::builder().method(method).uri(&url);
+
+        if !range.is_full() {
+            req = req.header(RANGE, range.to_header());
+        }
+        // Content type will be signed, so that it can't be changed by the
+        // uploader.
+        if let Some(content_type) = content_type {
+            req = req.header(CONTENT_TYPE, content_type);
+        }
+
+        req.body(AsyncBody::Empty).map_err(new_request_build_error)
+    }
+
     pub async fn gcs_get_object(
         &self,
         path: &str,
@@ -162,6 +308,8 @@ impl GcsCore {
             req = req.header(CONTENT_LENGTH, size)
         }
 
+        req = self.insert_encryption_headers(req);
+
         if let Some(storage_class) = &self.default_storage_class {
             req = req.header(CONTENT_TYPE, "multipart/related; boundary=my-boundary");
 
@@ -208,6 +356,8 @@ impl GcsCore {
 
         let req = Request::get(&url);
 
+        let req = self.insert_encryption_headers(req);
+
         let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -236,24 +386,37 @@ impl GcsCore {
         self.send(req).await
     }
 
-    pub async fn gcs_copy_object(
+    /// Rewrite object via the [Rewrite API](https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite).
+    ///
+    /// Large objects may not be rewritten in one call, use `rewriteToken`
+    /// in response to continue the rewrite until `done` is `true`.
+    pub async fn gcs_rewrite_object(
         &self,
         from: &str,
         to: &str,
+        rewrite_token: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let source = build_abs_path(&self.root, from);
         let dest = build_abs_path(&self.root, to);
 
-        let req_uri = format!(
-            "{}/storage/v1/b/{}/o/{}/copyTo/b/{}/o/{}",
+        let mut url = format!(
+            "{}/storage/v1/b/{}/o/{}/rewriteTo/b/{}/o/{}",
             self.endpoint,
             self.bucket,
             percent_encode_path(&source),
             self.bucket,
             percent_encode_path(&dest)
         );
+        if let Some(token) = rewrite_token {
+            write!(url, "?rewriteToken={}", percent_encode_path(token))
+                .expect("write into string must succeed");
+        }
 
-        let mut req = Request::post(req_uri)
+        let mut req = Request::post(&url).header(CONTENT_LENGTH, 0);
+        req = self.insert_encryption_headers(req);
+        req = self.insert_copy_source_encryption_headers(req);
+
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
@@ -261,6 +424,50 @@ impl GcsCore {
         self.send(req).await
     }
 
+    /// Compose at most 32 source objects into `dest` via the
+    /// [Compose API](https://cloud.google.com/storage/docs/json_api/v1/objects/compose).
+    pub async fn gcs_compose_object(
+        &self,
+        dest: &str,
+        sources: &[String],
+        content_type: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, dest);
+
+        let url = format!(
+            "{}/storage/v1/b/{}/o/{}/compose",
+            self.endpoint,
+            self.bucket,
+            percent_encode_path(&p)
+        );
+
+        let body = ComposeRequest {
+            source_objects: sources
+                .iter()
+                .map(|v| ComposeSourceObject {
+                    name: build_abs_path(&self.root, v),
+                })
+                .collect(),
+            destination: ComposeDestination {
+                content_type: content_type.map(|v| v.to_string()),
+            },
+        };
+        let body = serde_json::to_vec(&body).map_err(new_json_serialize_error)?;
+
+        let mut req = Request::post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body.len());
+        // Sources must be encrypted with the same key as destination.
+        req = self.insert_encryption_headers(req);
+
+        let mut req = req
+            .body(AsyncBody::Bytes(body.into()))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+
     pub async fn gcs_list_objects(
         &self,
         path: &str,
@@ -305,19 +512,35 @@ impl GcsCore {
     pub async fn gcs_initiate_resumable_upload(
         &self,
         path: &str,
+        content_type: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
-        let url = format!(
+        let mut url = format!(
             "{}/upload/storage/v1/b/{}/o?uploadType=resumable&name={}",
-            self.endpoint, self.bucket, p
+            self.endpoint,
+            self.bucket,
+            percent_encode_path(&p)
         );
-        let mut req = Request::post(&url)
+        if let Some(acl) = &self.predefined_acl {
+            write!(&mut url, "&predefinedAcl={}", acl).unwrap();
+        }
+
+        let mut req = Request::post(&url).header(CONTENT_LENGTH, 0);
+        if let Some(content_type) = content_type {
+            req = req.header(X_UPLOAD_CONTENT_TYPE, content_type);
+        }
+        req = self.insert_encryption_headers(req);
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
         self.sign(&mut req).await?;
         self.send(req).await
     }
 
+    /// Upload a chunk to the resumable upload session at `written_bytes`.
+    ///
+    /// The chunk must be a multiple of 256 KiB unless it's the last part,
+    /// in which case the total size will be sent to finalize the upload.
     pub fn gcs_upload_in_resumable_upload(
         &self,
         location: &str,
@@ -328,15 +551,16 @@ impl GcsCore {
     ) -> Result<Request<AsyncBody>> {
         let mut req = Request::put(location);
 
-        let range_header = if is_last_part {
-            format!(
+        let range_header = match (is_last_part, size) {
+            // Finalize the upload without any more data.
+            (true, 0) => format!("bytes */{}", written_bytes),
+            (true, _) => format!(
                 "bytes {}-{}/{}",
                 written_bytes,
                 written_bytes + size - 1,
                 written_bytes + size
-            )
-        } else {
-            format!("bytes {}-{}/*", written_bytes, written_bytes + size - 1)
+            ),
+            (false, _) => format!("bytes {}-{}/*", written_bytes, written_bytes + size - 1),
         };
 
         req = req
@@ -349,29 +573,291 @@ impl GcsCore {
         Ok(req)
     }
 
-    pub async fn gcs_complete_resumable_upload(
+    /// Query the status of resumable upload session, the committed offset
+    /// will be returned in the `Range` header of `308` response.
+    pub async fn gcs_query_resumable_upload_status(
         &self,
         location: &str,
-        written_bytes: u64,
-        bs: Bytes,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let size = bs.len() as u64;
-        let mut req = Request::post(location)
-            .header(CONTENT_LENGTH, size)
-            .header(
-                CONTENT_RANGE,
-                format!(
-                    "bytes {}-{}/{}",
-                    written_bytes,
-                    written_bytes + size - 1,
-                    written_bytes + size
-                ),
-            )
-            .body(AsyncBody::Bytes(bs))
+        let mut req = Request::put(location)
+            .header(CONTENT_LENGTH, 0)
+            .header(CONTENT_RANGE, "bytes */*")
+            .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
         self.sign(&mut req).await?;
 
         self.send(req).await
     }
+
+    /// Sign payload via the IAM `signBlob` API as given service account.
+    pub async fn gcs_sign_blob(&self, account: &str, payload: &[u8]) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}/v1/projects/-/serviceAccounts/{}:signBlob",
+            self.iam_endpoint,
+            percent_encode_path(account)
+        );
+
+        let body = serde_json::to_vec(&SignBlobRequest {
+            payload: BASE64_STANDARD.encode(payload),
+        })
+        .map_err(new_json_serialize_error)?;
+
+        let mut req = Request::post(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body.len())
+            .body(AsyncBody::Bytes(body.into()))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        let resp = self.send(req).await?;
+
+        if resp.status() != StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        let resp: SignBlobResponse =
+            serde_json::from_slice(&bs).map_err(new_json_deserialize_error)?;
+
+        BASE64_STANDARD.decode(resp.signed_blob).map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "signed blob is not valid base64")
+                .with_operation("gcs_sign_blob")
+                .set_source(err)
+        })
+    }
+
+    pub async fn gcs_cancel_resumable_upload(
+        &self,
+        location: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::delete(location)
+            .header(CONTENT_LENGTH, 0)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+}
+
+/// The response of [Rewrite API](https://cloud.google.com/storage/docs/json_api/v1/objects/rewrite).
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RewriteResponse {
+    pub done: bool,
+    pub rewrite_token: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeRequest {
+    source_objects: Vec<ComposeSourceObject>,
+    destination: ComposeDestination,
+}
+
+#[derive(Serialize)]
+struct ComposeSourceObject {
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComposeDestination {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignBlobRequest {
+    payload: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignBlobResponse {
+    signed_blob: String,
+}
+
+/// Build the V4 string to sign for request which expires after `expire`.
+///
+/// `Host` header will be inserted into request, and the canonical query
+/// (without `X-Goog-Signature`) will be returned along with string to sign.
+fn build_v4_string_to_sign<T>(
+    req: &mut Request<T>,
+    account: &str,
+    now: DateTime<Utc>,
+    expire: Duration,
+) -> Result<(String, String)> {
+    let authority = req
+        .uri()
+        .authority()
+        .ok_or_else(|| Error::new(ErrorKind::Unexpected, "request uri has no host"))?
+        .to_string();
+    req.headers_mut().insert(
+        HOST,
+        authority.parse().map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "host is not a valid header value").set_source(err)
+        })?,
+    );
+
+    let mut headers = Vec::with_capacity(req.headers().len());
+    for (k, v) in req.headers() {
+        let v = v.to_str().map_err(|err| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header value is not valid utf-8 string",
+            )
+            .set_source(err)
+        })?;
+        headers.push((k.as_str().to_lowercase(), v.trim().to_string()));
+    }
+    headers.sort();
+    let signed_headers = headers
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_headers: String = headers.iter().map(|(k, v)| format!("{k}:{v}\n")).collect();
+
+    let scope = format!("{}/auto/storage/goog4_request", now.format("%Y%m%d"));
+    let datetime = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut query: Vec<(String, String)> = req
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            let (k, v) = v.split_once('=').unwrap_or((v, ""));
+            (
+                percent_decode_str(k).decode_utf8_lossy().to_string(),
+                percent_decode_str(v).decode_utf8_lossy().to_string(),
+            )
+        })
+        .collect();
+    query.push((
+        "X-Goog-Algorithm".to_string(),
+        "GOOG4-RSA-SHA256".to_string(),
+    ));
+    query.push((
+        "X-Goog-Credential".to_string(),
+        format!("{account}/{scope}"),
+    ));
+    query.push(("X-Goog-Date".to_string(), datetime.clone()));
+    query.push(("X-Goog-Expires".to_string(), expire.as_secs().to_string()));
+    query.push(("X-Goog-SignedHeaders".to_string(), signed_headers.clone()));
+    let mut query: Vec<String> = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode_v4(k), percent_encode_v4(v)))
+        .collect();
+    query.sort();
+    let query = query.join("&");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\nUNSIGNED-PAYLOAD",
+        req.method(),
+        req.uri().path(),
+        query,
+        canonical_headers,
+        signed_headers
+    );
+    let string_to_sign = format!(
+        "GOOG4-RSA-SHA256\n{}\n{}\n{:x}",
+        datetime,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+
+    Ok((query, string_to_sign))
+}
+
+/// Parse the committed offset of resumable upload from the `Range` header
+/// like `bytes=0-262143`, no `Range` header means nothing committed.
+pub fn parse_resumable_upload_offset(headers: &HeaderMap) -> Result<u64> {
+    let range = match headers.get(RANGE) {
+        Some(v) => v,
+        None => return Ok(0),
+    };
+
+    range
+        .to_str()
+        .ok()
+        .and_then(|v| v.strip_prefix("bytes=0-"))
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|v| v + 1)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header range of resumable upload is invalid",
+            )
+            .with_operation("parse_resumable_upload_offset")
+            .with_context("range", format!("{range:?}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_build_v4_string_to_sign() -> Result<()> {
+        let mut req = Request::put("https://storage.googleapis.com/bucket/dir/a%20b.txt")
+            .header(CONTENT_TYPE, "text/plain")
+            .body(())
+            .expect("request must be valid");
+        let now = Utc.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap();
+
+        let (query, string_to_sign) = build_v4_string_to_sign(
+            &mut req,
+            "test@example.iam.gserviceaccount.com",
+            now,
+            Duration::from_secs(3600),
+        )?;
+
+        assert_eq!(req.headers()[HOST], "storage.googleapis.com");
+        assert_eq!(
+            query,
+            "X-Goog-Algorithm=GOOG4-RSA-SHA256\
+             &X-Goog-Credential=test%40example.iam.gserviceaccount.com%2F20230102%2Fauto%2Fstorage%2Fgoog4_request\
+             &X-Goog-Date=20230102T030405Z\
+             &X-Goog-Expires=3600\
+             &X-Goog-SignedHeaders=content-type%3Bhost"
+        );
+        assert_eq!(
+            string_to_sign,
+            "GOOG4-RSA-SHA256\n\
+             20230102T030405Z\n\
+             20230102/auto/storage/goog4_request\n\
+             9e67f82975dd5b81511e24b27f1c86520c77e7d49b1eb7b18b0e0943a615d980"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_compose_request() {
+        let req = ComposeRequest {
+            source_objects: vec![
+                ComposeSourceObject {
+                    name: "dir/file".to_string(),
+                },
+                ComposeSourceObject {
+                    name: "dir/file.part".to_string(),
+                },
+            ],
+            destination: ComposeDestination {
+                content_type: Some("text/plain".to_string()),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_string(&req).expect("must succeed"),
+            r#"{"sourceObjects":[{"name":"dir/file"},{"name":"dir/file.part"}],"destination":{"contentType":"text/plain"}}"#
+        );
+    }
 }
@@ -49,6 +49,15 @@ struct GcsErrorDetail {
     reason: String,
 }
 
+/// Reasons returned by GCS while the customer-supplied encryption key is
+/// missing or mismatched.
+const ENCRYPTION_KEY_ERROR_REASONS: &[&str] = &[
+    "resourceIsEncryptedWithCustomerEncryptionKey",
+    "customerEncryptionKeySha256IsInvalid",
+    "customerEncryptionKeyIsIncorrect",
+    "resourceNotEncryptedWithCustomerEncryptionKey",
+];
+
 /// Parse error response into Error.
 pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
@@ -65,12 +74,35 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
 
-    let message = match de::from_slice::<GcsErrorResponse>(&bs) {
-        Ok(gcs_err) => format!("{gcs_err:?}"),
-        Err(_) => String::from_utf8_lossy(&bs).into_owned(),
+    let gcs_err = de::from_slice::<GcsErrorResponse>(&bs).ok();
+
+    let is_encryption_key_error = parts.status == StatusCode::BAD_REQUEST
+        && gcs_err
+            .as_ref()
+            .map(|v| {
+                v.error
+                    .errors
+                    .iter()
+                    .any(|e| ENCRYPTION_KEY_ERROR_REASONS.contains(&e.reason.as_str()))
+            })
+            .unwrap_or_default();
+
+    let message = match gcs_err {
+        Some(gcs_err) => format!("{gcs_err:?}"),
+        None => String::from_utf8_lossy(&bs).into_owned(),
     };
 
-    let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
+    let mut err = if is_encryption_key_error {
+        Error::new(
+            ErrorKind::PermissionDenied,
+            "object is encrypted with customer-supplied encryption key, \
+             but encryption_key is not set or mismatched",
+        )
+        .with_context("message", message)
+    } else {
+        Error::new(kind, &message)
+    }
+    .with_context("response", format!("{parts:?}"));
 
     if retryable {
         err = err.set_temporary();
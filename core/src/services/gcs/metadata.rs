@@ -0,0 +1,216 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use log::warn;
+use reqsign::GoogleToken;
+use reqsign::GoogleTokenLoad;
+use serde::Deserialize;
+
+/// Env to override the host of metadata server, which is also respected by
+/// google cloud sdks.
+pub const GCE_METADATA_HOST: &str = "GCE_METADATA_HOST";
+const DEFAULT_GCE_METADATA_HOST: &str = "metadata.google.internal";
+
+/// Token will be refreshed before it's going to expire in this duration.
+const REFRESH_BEFORE_EXPIRY_SECS: i64 = 5 * 60;
+
+/// Load token from the metadata server, which serves tokens of the attached
+/// service account on GCE and the workload identity on GKE.
+///
+/// Token will be cached and refreshed before expiry. If refreshing failed,
+/// the last valid token will be returned until it's expired.
+#[derive(Debug)]
+pub struct MetadataTokenLoader {
+    endpoint: String,
+    scope: String,
+    service_account: String,
+
+    token: Mutex<Option<CachedToken>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expire_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+impl MetadataTokenLoader {
+    /// Create a new loader, `service_account` defaults to `default`.
+    pub fn new(host: &str, scope: &str, service_account: Option<&str>) -> Self {
+        let endpoint = if host.starts_with("http://") || host.starts_with("https://") {
+            host.trim_end_matches('/').to_string()
+        } else {
+            format!("http://{host}")
+        };
+
+        Self {
+            endpoint,
+            scope: scope.to_string(),
+            service_account: service_account.unwrap_or("default").to_string(),
+            token: Mutex::default(),
+        }
+    }
+
+    /// Create a new loader with the host from `GCE_METADATA_HOST` env.
+    pub fn from_env(scope: &str, service_account: Option<&str>) -> Self {
+        let host = std::env::var(GCE_METADATA_HOST)
+            .unwrap_or_else(|_| DEFAULT_GCE_METADATA_HOST.to_string());
+        Self::new(&host, scope, service_account)
+    }
+
+    async fn fetch(&self, client: reqwest::Client) -> anyhow::Result<CachedToken> {
+        let url = format!(
+            "{}/computeMetadata/v1/instance/service-accounts/{}/token?scopes={}",
+            self.endpoint, self.service_account, self.scope
+        );
+
+        let resp = client
+            .get(&url)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "fetch token from metadata server failed: {}",
+                resp.status()
+            ));
+        }
+
+        let token: MetadataTokenResponse = serde_json::from_slice(&resp.bytes().await?)?;
+        Ok(CachedToken {
+            access_token: token.access_token,
+            expire_at: Utc::now() + Duration::seconds(token.expires_in),
+        })
+    }
+
+    fn to_google_token(&self, token: &CachedToken) -> GoogleToken {
+        let expires_in = (token.expire_at - Utc::now()).num_seconds().max(0);
+        GoogleToken::new(&token.access_token, expires_in as usize, &self.scope)
+    }
+}
+
+#[async_trait]
+impl GoogleTokenLoad for MetadataTokenLoader {
+    async fn load(&self, client: reqwest::Client) -> anyhow::Result<Option<GoogleToken>> {
+        let cached = self.token.lock().expect("lock poisoned").clone();
+        if let Some(token) = &cached {
+            if Utc::now() + Duration::seconds(REFRESH_BEFORE_EXPIRY_SECS) < token.expire_at {
+                return Ok(Some(self.to_google_token(token)));
+            }
+        }
+
+        match self.fetch(client).await {
+            Ok(token) => {
+                let google_token = self.to_google_token(&token);
+                *self.token.lock().expect("lock poisoned") = Some(token);
+                Ok(Some(google_token))
+            }
+            // Fallback to the last valid token if refresh failed.
+            Err(err) => match cached {
+                Some(token) if Utc::now() < token.expire_at => {
+                    warn!("refresh token from metadata server failed, use the last valid token: {err:?}");
+                    Ok(Some(self.to_google_token(&token)))
+                }
+                _ => Err(err),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_load_token() -> anyhow::Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/computeMetadata/v1/instance/service-accounts/default/token",
+            ))
+            .and(query_param("scopes", "test-scope"))
+            .and(header("Metadata-Flavor", "Google"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"access_token": "token-1", "expires_in": 3599, "token_type": "Bearer"}"#,
+            ))
+            // Token should be cached.
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let loader = MetadataTokenLoader::new(&mock_server.uri(), "test-scope", None);
+        for _ in 0..2 {
+            let token = loader.load(reqwest::Client::new()).await?;
+            assert!(token.is_some());
+        }
+        let cached = loader.token.lock().unwrap().clone().unwrap();
+        assert_eq!(cached.access_token, "token-1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback_to_last_valid_token() -> anyhow::Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/computeMetadata/v1/instance/service-accounts/test/token",
+            ))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let loader = MetadataTokenLoader::new(&mock_server.uri(), "test-scope", Some("test"));
+
+        // Token is going to expire, refresh failed but still valid.
+        *loader.token.lock().unwrap() = Some(CachedToken {
+            access_token: "token-1".to_string(),
+            expire_at: Utc::now() + Duration::seconds(60),
+        });
+        assert!(loader.load(reqwest::Client::new()).await?.is_some());
+
+        // Token has expired.
+        *loader.token.lock().unwrap() = Some(CachedToken {
+            access_token: "token-1".to_string(),
+            expire_at: Utc::now() - Duration::seconds(1),
+        });
+        assert!(loader.load(reqwest::Client::new()).await.is_err());
+
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ pub use backend::GcsBuilder as Gcs;
 
 mod core;
 mod error;
+mod metadata;
 mod pager;
 mod uri;
 mod writer;
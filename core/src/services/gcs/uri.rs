@@ -40,6 +40,30 @@ pub(super) fn percent_encode_path(path: &str) -> String {
     utf8_percent_encode(path, &GCS_PATH_ENCODE_SET).to_string()
 }
 
+/// GCS_V4_ENCODE_SET is the encode set used by V4 signing, which will encode
+/// all characters except `A-Z a-z 0-9 - _ . ~`.
+static GCS_V4_ENCODE_SET: AsciiSet = NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// percent_encode_v4_path will do percent encoding for the path of XML API,
+/// which keeps `/` as is. The encoded path can be used as the canonical URI
+/// of V4 signing directly.
+pub(super) fn percent_encode_v4_path(path: &str) -> String {
+    path.split('/')
+        .map(percent_encode_v4)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// percent_encode_v4 will do percent encoding for query keys and values of
+/// V4 signing.
+pub(super) fn percent_encode_v4(s: &str) -> String {
+    utf8_percent_encode(s, &GCS_V4_ENCODE_SET).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
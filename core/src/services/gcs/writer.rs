@@ -19,49 +19,61 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use bytes::BytesMut;
+use http::Response;
 use http::StatusCode;
+use uuid::Uuid;
 
+use super::core::parse_resumable_upload_offset;
 use super::core::GcsCore;
 use super::error::parse_error;
 use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
+/// Max times to resume a chunk upload from committed offset.
+const MAX_RESUME_RETRIES: usize = 3;
+/// Max source objects of one compose request.
+const MAX_COMPOSE_SOURCES: usize = 32;
+
 pub struct GcsWriter {
     core: Arc<GcsCore>,
 
     op: OpWrite,
     path: String,
+
+    /// The location of resumable upload session, `None` means the
+    /// session has not been initiated yet.
     location: Option<String>,
+    /// Bytes that have been committed by the session.
     written_bytes: u64,
-    is_last_part_written: bool,
-    last: Option<Bytes>,
+    /// Data that has not been uploaded yet.
+    buffer: BytesMut,
+
+    /// Whether the object has been created, only used while
+    /// `enable_compose_append` is set.
+    created: bool,
+    /// Temporary objects that have not been composed onto the object yet.
+    parts: Vec<String>,
 }
 
 impl GcsWriter {
-    pub fn new(
-        core: Arc<GcsCore>,
-        op: OpWrite,
-        path: String,
-        upload_location: Option<String>,
-    ) -> Self {
+    pub fn new(core: Arc<GcsCore>, op: OpWrite, path: String) -> Self {
         GcsWriter {
             core,
             op,
             path,
-            location: upload_location,
+            location: None,
             written_bytes: 0,
-            is_last_part_written: false,
-            last: None,
+            buffer: BytesMut::new(),
+            created: false,
+            parts: vec![],
         }
     }
-}
 
-#[async_trait]
-impl oio::Write for GcsWriter {
-    async fn write(&mut self, bs: Bytes) -> Result<()> {
+    async fn insert_object(&self, path: &str, bs: Bytes) -> Result<()> {
         let mut req = self.core.gcs_insert_object_request(
-            &percent_encode_path(&self.path),
+            path,
             Some(bs.len()),
             self.op.content_type(),
             AsyncBody::Bytes(bs),
@@ -82,83 +94,273 @@ impl oio::Write for GcsWriter {
         }
     }
 
-    async fn append(&mut self, bs: Bytes) -> Result<()> {
-        let location = if let Some(location) = &self.location {
-            location
-        } else {
-            return Ok(());
-        };
+    /// Compose all temporary objects onto the object and remove them.
+    async fn compose_parts(&mut self) -> Result<()> {
+        let mut sources = Vec::with_capacity(self.parts.len() + 1);
+        sources.push(self.path.clone());
+        sources.extend(self.parts.iter().cloned());
+
+        let resp = self
+            .core
+            .gcs_compose_object(&self.path, &sources, self.op.content_type())
+            .await?;
+
+        let status = resp.status();
 
-        let result = if let Some(last) = &self.last {
-            let bytes_to_upload = last.slice(0..last.len());
-            let part_size = bytes_to_upload.len() as u64;
-            let is_last_part = part_size % (256 * 1024) != 0;
-            let mut req = self.core.gcs_upload_in_resumable_upload(
-                location,
-                part_size,
-                self.written_bytes,
-                is_last_part,
-                AsyncBody::Bytes(bytes_to_upload),
-            )?;
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+            }
+            _ => return Err(parse_error(resp).await?),
+        }
 
-            self.core.sign(&mut req).await?;
+        self.delete_parts().await
+    }
 
-            let resp = self.core.send(req).await?;
+    async fn delete_parts(&mut self) -> Result<()> {
+        while let Some(part) = self.parts.pop() {
+            let resp = self.core.gcs_delete_object(&part).await?;
 
             let status = resp.status();
 
             match status {
-                StatusCode::OK | StatusCode::PERMANENT_REDIRECT => {
-                    if is_last_part {
-                        self.is_last_part_written = true
-                    } else {
-                        self.written_bytes += part_size;
-                    }
-                    Ok(())
+                StatusCode::NO_CONTENT | StatusCode::OK | StatusCode::NOT_FOUND => {
+                    resp.into_body().consume().await?;
+                }
+                _ => {
+                    self.parts.push(part);
+                    return Err(parse_error(resp).await?);
                 }
-                _ => Err(parse_error(resp).await?),
             }
-        } else {
-            Ok(())
-        };
-
-        self.last = Some(bs.slice(0..bs.len()));
-        return result;
-    }
+        }
 
-    async fn abort(&mut self) -> Result<()> {
         Ok(())
     }
 
-    async fn close(&mut self) -> Result<()> {
-        if self.is_last_part_written {
+    async fn compose_append(&mut self, bs: Bytes) -> Result<()> {
+        if !self.created {
+            self.insert_object(&self.path, bs).await?;
+            self.created = true;
             return Ok(());
         }
 
-        let location = if let Some(location) = &self.location {
-            location
-        } else {
-            return Ok(());
-        };
+        let part = format!("{}.opendal-append-{}", self.path, Uuid::new_v4());
+        self.insert_object(&part, bs).await?;
+        self.parts.push(part);
+
+        // Compose all temporary objects along with the object itself.
+        if self.parts.len() + 1 >= MAX_COMPOSE_SOURCES {
+            // Data has been uploaded, retry append again will duplicate it.
+            self.compose_parts()
+                .await
+                .map_err(|err| err.set_persistent())?;
+        }
 
-        let bs = self
-            .last
-            .as_ref()
-            .expect("failed to get the previously uploaded part");
+        Ok(())
+    }
+
+    async fn initiate_upload(&mut self) -> Result<String> {
+        if let Some(location) = &self.location {
+            return Ok(location.clone());
+        }
 
         let resp = self
             .core
-            .gcs_complete_resumable_upload(location, self.written_bytes, bs.slice(0..bs.len()))
+            .gcs_initiate_resumable_upload(&self.path, self.op.content_type())
             .await?;
 
         let status = resp.status();
 
         match status {
             StatusCode::OK => {
+                let location = parse_location(resp.headers())?
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::Unexpected,
+                            "location is not in the response header",
+                        )
+                        .with_context("path", &self.path)
+                    })?
+                    .to_string();
+                resp.into_body().consume().await?;
+
+                self.location = Some(location.clone());
+                Ok(location)
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    /// Put a chunk into session, returns the committed offset, or `None`
+    /// if the upload has been finalized.
+    async fn put_chunk(
+        &self,
+        location: &str,
+        bs: Bytes,
+        is_last_part: bool,
+    ) -> Result<Option<u64>> {
+        let mut req = self.core.gcs_upload_in_resumable_upload(
+            location,
+            bs.len() as u64,
+            self.written_bytes,
+            is_last_part,
+            AsyncBody::Bytes(bs),
+        )?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        parse_upload_response(resp).await
+    }
+
+    async fn query_status(&self, location: &str) -> Result<Option<u64>> {
+        let resp = self
+            .core
+            .gcs_query_resumable_upload_status(location)
+            .await?;
+
+        parse_upload_response(resp).await
+    }
+
+    /// Upload the whole chunk into session.
+    ///
+    /// Data that has not been committed will be sent again, and the
+    /// committed offset will be queried to resume the upload on transient
+    /// failures.
+    async fn upload(&mut self, location: &str, mut bs: Bytes, is_last_part: bool) -> Result<()> {
+        let mut retries = 0;
+
+        loop {
+            let size = bs.len() as u64;
+            let committed = match self.put_chunk(location, bs.clone(), is_last_part).await {
+                Ok(Some(committed)) => committed,
+                Ok(None) => return Ok(()),
+                Err(err) if err.is_temporary() && retries < MAX_RESUME_RETRIES => {
+                    retries += 1;
+                    match self.query_status(location).await? {
+                        Some(committed) => committed,
+                        None => return Ok(()),
+                    }
+                }
+                Err(err) => return Err(err),
+            };
+
+            if committed < self.written_bytes || committed > self.written_bytes + size {
+                return Err(Error::new(
+                    ErrorKind::Unexpected,
+                    "committed offset of resumable upload is out of range",
+                )
+                .with_context("path", &self.path)
+                .with_context("written_bytes", self.written_bytes.to_string())
+                .with_context("committed", committed.to_string()));
+            }
+
+            bs = bs.slice((committed - self.written_bytes) as usize..);
+            self.written_bytes = committed;
+
+            // The last part must be sent even it's empty to finalize the upload.
+            if bs.is_empty() && !is_last_part {
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn parse_upload_response(resp: Response<IncomingAsyncBody>) -> Result<Option<u64>> {
+    let status = resp.status();
+
+    match status {
+        StatusCode::PERMANENT_REDIRECT => {
+            let committed = parse_resumable_upload_offset(resp.headers())?;
+            resp.into_body().consume().await?;
+            Ok(Some(committed))
+        }
+        StatusCode::CREATED | StatusCode::OK => {
+            resp.into_body().consume().await?;
+            Ok(None)
+        }
+        _ => Err(parse_error(resp).await?),
+    }
+}
+
+#[async_trait]
+impl oio::Write for GcsWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        self.insert_object(&self.path, bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        if self.core.enable_compose_append {
+            return self.compose_append(bs).await;
+        }
+
+        self.buffer.extend_from_slice(&bs);
+
+        // Always keep the last chunk in buffer so that it can be used to
+        // finalize the upload.
+        while self.buffer.len() > self.core.write_chunk_size {
+            let location = self.initiate_upload().await?;
+            let chunk = self.buffer.split_to(self.core.write_chunk_size).freeze();
+            // Data has been buffered, retry append again will duplicate it.
+            self.upload(&location, chunk, false)
+                .await
+                .map_err(|err| err.set_persistent())?;
+        }
+
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        if self.core.enable_compose_append {
+            return self.delete_parts().await;
+        }
+
+        self.buffer.clear();
+
+        let location = match self.location.take() {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+
+        let resp = self.core.gcs_cancel_resumable_upload(&location).await?;
+
+        // GCS returns `499 Client Closed Request` for cancelled upload.
+        match resp.status().as_u16() {
+            499 | 204 => {
                 resp.into_body().consume().await?;
                 Ok(())
             }
             _ => Err(parse_error(resp).await?),
         }
     }
+
+    async fn close(&mut self) -> Result<()> {
+        if self.core.enable_compose_append {
+            if !self.parts.is_empty() {
+                self.compose_parts().await?;
+            }
+            return Ok(());
+        }
+
+        let location = match &self.location {
+            Some(location) => location.clone(),
+            // Upload small objects directly.
+            None if !self.buffer.is_empty() => {
+                let bs = Bytes::copy_from_slice(&self.buffer);
+                self.write(bs).await?;
+                self.buffer.clear();
+                return Ok(());
+            }
+            None => return Ok(()),
+        };
+
+        let bs = self.buffer.split().freeze();
+        // Data has been taken from buffer, retry close again will lose it.
+        self.upload(&location, bs, true)
+            .await
+            .map_err(|err| err.set_persistent())?;
+        self.location = None;
+        Ok(())
+    }
 }
@@ -60,6 +60,8 @@ const GITHUB_API_URL: &str = "GITHUB_API_URL";
 const GITHUB_REPOSITORY: &str = "GITHUB_REPOSITORY";
 /// The github API version that used by OpenDAL.
 const GITHUB_API_VERSION: &str = "2022-11-28";
+/// The max page size that supported by github list caches API.
+const GITHUB_API_PAGE_SIZE: usize = 100;
 
 /// GitHub Action Cache Services support.
 ///
@@ -104,6 +106,9 @@ const GITHUB_API_VERSION: &str = "2022-11-28";
 ///   GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
 /// ```
 ///
+/// `delete` will only remove caches that created with the same `version`,
+/// caches of other versions under the same key will be kept.
+///
 /// # Limitations
 ///
 /// Unlike other services, ghac doesn't support create empty files.
@@ -115,6 +120,9 @@ const GITHUB_API_VERSION: &str = "2022-11-28";
 /// # Configuration
 ///
 /// - `root`: Set the work dir for backend.
+/// - `version`: Set the version of caches, caches with different versions
+///   are isolated from each other.
+/// - `enable_create_simulation`: Enable create empty file simulation.
 ///
 /// Refer to [`GhacBuilder`]'s public API docs for more information.
 ///
@@ -167,6 +175,10 @@ impl GhacBuilder {
     /// The version is the unique value that provides namespacing.
     /// It's better to make sure this value is only used by this backend.
     ///
+    /// Caches written with different versions won't be visible to each
+    /// other even with the same path, so it's useful to isolate caches
+    /// between different toolchains.
+    ///
     /// If not set, we will use `opendal` as default.
     pub fn version(&mut self, version: &str) -> &mut Self {
         if !version.is_empty() {
@@ -464,13 +476,47 @@ impl Accessor for GhacBackend {
             ));
         }
 
-        let resp = self.ghac_delete(path).await?;
+        let p = build_abs_path(&self.root, path);
 
-        // deleting not existing objects is ok
-        if resp.status().is_success() || resp.status() == StatusCode::NOT_FOUND {
-            Ok(RpDelete::default())
-        } else {
-            Err(parse_error(resp).await?)
+        // Github only allows deleting caches by key which will remove
+        // caches of all versions, so we need to find the caches of our
+        // version and delete them by id instead.
+        let mut page = 1;
+        loop {
+            let resp = self.ghac_list_caches(&p, page).await?;
+            let caches = if resp.status() == StatusCode::OK {
+                let slc = resp.into_body().bytes().await?;
+                let list_resp: GhacListCachesResponse =
+                    serde_json::from_slice(&slc).map_err(new_json_deserialize_error)?;
+                list_resp.actions_caches
+            } else {
+                return Err(parse_error(resp)
+                    .await
+                    .map(|err| err.with_operation("Backend::ghac_list_caches"))?);
+            };
+
+            for cache in caches.iter() {
+                // list caches will match caches with key prefix, so we
+                // need to filter them.
+                if cache.key != p || cache.version != self.version {
+                    continue;
+                }
+
+                let resp = self.ghac_delete_cache(cache.id).await?;
+                // deleting not existing objects is ok
+                if resp.status().is_success() || resp.status() == StatusCode::NOT_FOUND {
+                    resp.into_body().consume().await?;
+                } else {
+                    return Err(parse_error(resp)
+                        .await
+                        .map(|err| err.with_operation("Backend::ghac_delete_cache"))?);
+                }
+            }
+
+            if caches.len() < GITHUB_API_PAGE_SIZE {
+                return Ok(RpDelete::default());
+            }
+            page += 1;
         }
     }
 }
@@ -596,18 +642,40 @@ impl GhacBackend {
         Ok(req)
     }
 
-    async fn ghac_delete(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
-        let p = build_abs_path(&self.root, path);
-
+    async fn ghac_list_caches(
+        &self,
+        key: &str,
+        page: usize,
+    ) -> Result<Response<IncomingAsyncBody>> {
         let url = format!(
-            "{}/repos/{}/actions/caches?key={}",
+            "{}/repos/{}/actions/caches?key={}&per_page={GITHUB_API_PAGE_SIZE}&page={page}",
             self.api_url,
             self.repo,
-            percent_encode_path(&p)
+            percent_encode_path(key)
+        );
+
+        let mut req = Request::get(&url);
+        req = req.header(AUTHORIZATION, format!("Bearer {}", self.api_token));
+        req = req.header(ACCEPT, "application/vnd.github+json");
+        req = req.header(USER_AGENT, format!("opendal/{VERSION} (service ghac)"));
+        req = req.header("X-GitHub-Api-Version", GITHUB_API_VERSION);
+
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
+    async fn ghac_delete_cache(&self, cache_id: i64) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!(
+            "{}/repos/{}/actions/caches/{cache_id}",
+            self.api_url, self.repo,
         );
 
         let mut req = Request::delete(&url);
         req = req.header(AUTHORIZATION, format!("Bearer {}", self.api_token));
+        req = req.header(ACCEPT, "application/vnd.github+json");
         req = req.header(USER_AGENT, format!("opendal/{VERSION} (service ghac)"));
         req = req.header("X-GitHub-Api-Version", GITHUB_API_VERSION);
 
@@ -644,3 +712,17 @@ struct GhacReserveResponse {
 struct GhacCommitRequest {
     size: u64,
 }
+
+#[derive(Deserialize)]
+struct GhacListCachesResponse {
+    // Not used fields.
+    // total_count: u64,
+    actions_caches: Vec<GhacCacheEntry>,
+}
+
+#[derive(Deserialize)]
+struct GhacCacheEntry {
+    id: i64,
+    key: String,
+    version: String,
+}
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use http::HeaderMap;
 use http::Response;
 use http::StatusCode;
 
@@ -30,6 +31,8 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (kind, retryable) = match parts.status {
         StatusCode::NOT_FOUND | StatusCode::NO_CONTENT => (ErrorKind::NotFound, false),
         StatusCode::CONFLICT => (ErrorKind::AlreadyExists, false),
+        // Github API returns 403 while reaching the primary rate limit.
+        StatusCode::FORBIDDEN if is_rate_limited(&parts.headers) => (ErrorKind::RateLimited, true),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
         StatusCode::TOO_MANY_REQUESTS => (ErrorKind::RateLimited, true),
         StatusCode::INTERNAL_SERVER_ERROR
@@ -49,3 +52,30 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
 
     Ok(err)
 }
+
+fn is_rate_limited(headers: &HeaderMap) -> bool {
+    headers
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v == "0")
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_is_rate_limited() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_rate_limited(&headers));
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("10"));
+        assert!(!is_rate_limited(&headers));
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        assert!(is_rate_limited(&headers));
+    }
+}
@@ -0,0 +1,71 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use mongodb::error::ErrorKind as MongoErrorKind;
+use mongodb::error::RETRYABLE_WRITE_ERROR;
+use mongodb::error::TRANSIENT_TRANSACTION_ERROR;
+
+use crate::Error;
+use crate::ErrorKind;
+
+/// Parse error from mongodb into Error.
+pub fn parse_error(err: mongodb::error::Error) -> Error {
+    let (kind, retryable) = match err.kind.as_ref() {
+        MongoErrorKind::Authentication { .. } => (ErrorKind::PermissionDenied, false),
+        MongoErrorKind::InvalidArgument { .. } | MongoErrorKind::InvalidTlsConfig { .. } => {
+            (ErrorKind::ConfigInvalid, false)
+        }
+        // Network is broken or no server available for now, allow retry
+        // so that driver could pick up a new connection.
+        MongoErrorKind::Io(_)
+        | MongoErrorKind::ConnectionPoolCleared { .. }
+        | MongoErrorKind::ServerSelection { .. }
+        | MongoErrorKind::DnsResolve { .. } => (ErrorKind::Unexpected, true),
+        // Unauthorized
+        MongoErrorKind::Command(e) if e.code == 13 => (ErrorKind::PermissionDenied, false),
+        _ => (
+            ErrorKind::Unexpected,
+            err.contains_label(RETRYABLE_WRITE_ERROR)
+                || err.contains_label(TRANSIENT_TRANSACTION_ERROR),
+        ),
+    };
+
+    let mut err = Error::new(kind, "error from mongodb").set_source(err);
+    if retryable {
+        err = err.set_temporary();
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let err =
+            parse_error(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset").into());
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+
+        let err = parse_error(mongodb::error::Error::custom("custom error"));
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(!err.is_temporary());
+    }
+}
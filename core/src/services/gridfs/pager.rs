@@ -0,0 +1,46 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::vec::IntoIter;
+
+use async_trait::async_trait;
+
+use crate::raw::*;
+use crate::*;
+
+pub struct GridfsPager {
+    size: usize,
+    entries: IntoIter<oio::Entry>,
+}
+
+impl GridfsPager {
+    pub fn new(entries: Vec<oio::Entry>, limit: Option<usize>) -> Self {
+        Self {
+            size: limit.unwrap_or(1000),
+            entries: entries.into_iter(),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Page for GridfsPager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let oes: Vec<oio::Entry> = self.entries.by_ref().take(self.size).collect();
+
+        Ok(if oes.is_empty() { None } else { Some(oes) })
+    }
+}
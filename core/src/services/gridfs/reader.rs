@@ -0,0 +1,155 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io;
+use std::task::Context;
+use std::task::Poll;
+
+use bytes::Buf;
+use bytes::Bytes;
+use futures::ready;
+use futures::StreamExt;
+use mongodb::bson::Bson;
+use mongodb::bson::Document;
+use mongodb::Cursor;
+
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+/// GridfsReader streams the content of a file from its chunks.
+///
+/// Chunks must be returned in the order of `n`, the first chunk's `n`
+/// should be `first`.
+pub struct GridfsReader {
+    chunks: Option<Cursor<Document>>,
+    n: i64,
+    skip: usize,
+    remaining: u64,
+    buf: Bytes,
+}
+
+/// Chunks cursor is only accessed via `&mut self`.
+unsafe impl Sync for GridfsReader {}
+
+impl GridfsReader {
+    /// Create a reader that skips `skip` bytes of the first chunk and
+    /// returns `size` bytes in total.
+    pub fn new(chunks: Option<Cursor<Document>>, first: u64, skip: u64, size: u64) -> Self {
+        Self {
+            chunks,
+            n: first as i64,
+            skip: skip as usize,
+            remaining: size,
+            buf: Bytes::new(),
+        }
+    }
+
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Result<Bytes>> {
+        let cursor = match self.chunks.as_mut() {
+            Some(cursor) => cursor,
+            None => return Poll::Ready(Err(missing_chunk_error(self.n))),
+        };
+
+        let mut chunk = match ready!(cursor.poll_next_unpin(cx)) {
+            Some(chunk) => chunk.map_err(parse_error)?,
+            None => return Poll::Ready(Err(missing_chunk_error(self.n))),
+        };
+
+        let n = match chunk.get("n") {
+            Some(Bson::Int32(v)) => *v as i64,
+            Some(Bson::Int64(v)) => *v,
+            _ => -1,
+        };
+        if n != self.n {
+            return Poll::Ready(Err(missing_chunk_error(self.n)));
+        }
+        self.n += 1;
+
+        let mut bs = match chunk.remove("data") {
+            Some(Bson::Binary(v)) => Bytes::from(v.bytes),
+            _ => {
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::Unexpected,
+                    "gridfs chunk doesn't have valid data",
+                )
+                .with_context("n", n.to_string())))
+            }
+        };
+
+        if self.skip > 0 {
+            bs.advance(self.skip.min(bs.len()));
+            self.skip = 0;
+        }
+        if bs.len() as u64 > self.remaining {
+            bs.truncate(self.remaining as usize);
+        }
+
+        Poll::Ready(Ok(bs))
+    }
+}
+
+fn missing_chunk_error(n: i64) -> Error {
+    Error::new(ErrorKind::Unexpected, "gridfs chunk is missing").with_context("n", n.to_string())
+}
+
+impl oio::Read for GridfsReader {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        while self.buf.is_empty() {
+            if self.remaining == 0 {
+                return Poll::Ready(Ok(0));
+            }
+            self.buf = ready!(self.poll_chunk(cx))?;
+        }
+
+        let size = self.buf.len().min(buf.len());
+        buf[..size].copy_from_slice(&self.buf[..size]);
+        self.buf.advance(size);
+        self.remaining -= size as u64;
+
+        Poll::Ready(Ok(size))
+    }
+
+    fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
+        let (_, _) = (cx, pos);
+
+        Poll::Ready(Err(Error::new(
+            ErrorKind::Unsupported,
+            "output reader doesn't support seeking",
+        )))
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
+        if !self.buf.is_empty() {
+            let bs = self.buf.split_off(0);
+            self.remaining -= bs.len() as u64;
+            return Poll::Ready(Some(Ok(bs)));
+        }
+
+        loop {
+            if self.remaining == 0 {
+                return Poll::Ready(None);
+            }
+
+            let bs = ready!(self.poll_chunk(cx))?;
+            if !bs.is_empty() {
+                self.remaining -= bs.len() as u64;
+                return Poll::Ready(Some(Ok(bs)));
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::AsyncWriteExt;
+use mongodb::gridfs::GridFsBucket;
+use mongodb::gridfs::GridFsUploadStream;
+use mongodb::options::GridFsUploadOptions;
+
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+pub struct GridfsWriter {
+    bucket: GridFsBucket,
+    filename: String,
+    options: GridFsUploadOptions,
+    stream: Option<GridFsUploadStream>,
+}
+
+/// Upload stream is only accessed via `&mut self`.
+unsafe impl Sync for GridfsWriter {}
+
+impl GridfsWriter {
+    pub fn new(bucket: GridFsBucket, filename: String, options: GridFsUploadOptions) -> Self {
+        Self {
+            bucket,
+            filename,
+            options,
+            stream: None,
+        }
+    }
+
+    fn stream(&mut self) -> &mut GridFsUploadStream {
+        let (bucket, filename, options) = (&self.bucket, &self.filename, &self.options);
+
+        self.stream
+            .get_or_insert_with(|| bucket.open_upload_stream(filename, options.clone()))
+    }
+}
+
+#[async_trait]
+impl oio::Write for GridfsWriter {
+    /// # Notes
+    ///
+    /// `write` carries the whole content, so we will discard the chunks
+    /// that have been uploaded by former calls.
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            stream.abort().await.map_err(parse_error)?;
+        }
+
+        self.append(bs).await
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.stream().write_all(&bs).await.map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "write into gridfs upload stream").set_source(err)
+        })
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            stream.abort().await.map_err(parse_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// The files collection document will only be inserted while closing,
+    /// so the file is not visible before `close`.
+    async fn close(&mut self) -> Result<()> {
+        self.stream().close().await.map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "close gridfs upload stream").set_source(err)
+        })?;
+        self.stream = None;
+
+        Ok(())
+    }
+}
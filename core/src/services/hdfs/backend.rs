@@ -25,6 +25,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use log::debug;
+use uuid::Uuid;
 
 use super::error::parse_io_error;
 use super::pager::HdfsPager;
@@ -43,6 +44,7 @@ use crate::*;
 ///
 /// - [x] read
 /// - [x] write
+/// - [x] append
 /// - [x] list
 /// - [ ] ~~scan~~
 /// - [ ] ~~presign~~
@@ -60,9 +62,24 @@ use crate::*;
 ///
 /// - `root`: Set the work dir for backend.
 /// - `name_node`: Set the name node for backend.
+/// - `enable_atomic_write`: Write into a temp file and rename it into place on close.
 ///
 /// Refer to [`HdfsBuilder`]'s public API docs for more information.
 ///
+/// # Append
+///
+/// Data written by [`Writer::append`][crate::Writer::append] will be appended
+/// to the end of the existing file, the file will be created if it doesn't
+/// exist yet.
+///
+/// # Atomic Write
+///
+/// With `enable_atomic_write`, data will be written into a temp file
+/// (`.{name}.{uuid}._tmp` in the same dir) which will be renamed into place
+/// on close. The temp file starts with `.` so that it will be ignored by
+/// hadoop's input formats. Writers will always replace the whole file in
+/// this mode, and the temp file will be removed on abort.
+///
 /// # Environment
 ///
 /// HDFS needs some environment set correctly.
@@ -130,6 +147,7 @@ use crate::*;
 pub struct HdfsBuilder {
     root: Option<String>,
     name_node: Option<String>,
+    enable_atomic_write: bool,
 }
 
 impl HdfsBuilder {
@@ -160,6 +178,12 @@ impl HdfsBuilder {
 
         self
     }
+
+    /// Write into a temp file and rename it into place on close.
+    pub fn enable_atomic_write(&mut self) -> &mut Self {
+        self.enable_atomic_write = true;
+        self
+    }
 }
 
 impl Builder for HdfsBuilder {
@@ -171,6 +195,9 @@ impl Builder for HdfsBuilder {
 
         map.get("root").map(|v| builder.root(v));
         map.get("name_node").map(|v| builder.name_node(v));
+        map.get("enable_atomic_write")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_atomic_write());
 
         builder
     }
@@ -204,6 +231,7 @@ impl Builder for HdfsBuilder {
         Ok(HdfsBackend {
             root,
             client: Arc::new(client),
+            atomic_write: self.enable_atomic_write,
         })
     }
 }
@@ -213,12 +241,53 @@ impl Builder for HdfsBuilder {
 pub struct HdfsBackend {
     root: String,
     client: Arc<hdrs::Client>,
+    atomic_write: bool,
 }
 
 /// hdrs::Client is thread-safe.
 unsafe impl Send for HdfsBackend {}
 unsafe impl Sync for HdfsBackend {}
 
+impl HdfsBackend {
+    /// Create the parent dir of given path, returns the target path and
+    /// the temp path to write if atomic write is enabled.
+    fn prepare_write(&self, path: &str) -> Result<(String, Option<String>)> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let parent = PathBuf::from(&p)
+            .parent()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "path should have parent but not, it must be malformed",
+                )
+                .with_context("input", &p)
+            })?
+            .to_path_buf();
+
+        self.client
+            .create_dir(&parent.to_string_lossy())
+            .map_err(parse_io_error)?;
+
+        let tmp_path = if self.atomic_write {
+            Some(parent.join(tmp_file_of(path)).to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        Ok((p, tmp_path))
+    }
+}
+
+/// Build the temp file name of given path, which starts with `.` and ends
+/// with `._tmp`.
+fn tmp_file_of(path: &str) -> String {
+    let name = get_basename(path);
+    let uuid = Uuid::new_v4().to_string();
+
+    format!(".{name}.{uuid}._tmp")
+}
+
 #[async_trait]
 impl Accessor for HdfsBackend {
     type Reader = oio::into_reader::FdReader<hdrs::AsyncFile>;
@@ -235,8 +304,10 @@ impl Accessor for HdfsBackend {
             .set_capabilities(
                 AccessorCapability::Read
                     | AccessorCapability::Write
+                    | AccessorCapability::Append
                     | AccessorCapability::List
-                    | AccessorCapability::Blocking,
+                    | AccessorCapability::Blocking
+                    | AccessorCapability::ListWithLimit,
             )
             .set_hints(AccessorHint::ReadSeekable);
 
@@ -286,34 +357,43 @@ impl Accessor for HdfsBackend {
         Ok((RpRead::new(end - start), r))
     }
 
-    async fn write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let p = build_rooted_abs_path(&self.root, path);
-
-        let parent = PathBuf::from(&p)
-            .parent()
-            .ok_or_else(|| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    "path should have parent but not, it must be malformed",
-                )
-                .with_context("input", &p)
-            })?
-            .to_path_buf();
-
-        self.client
-            .create_dir(&parent.to_string_lossy())
-            .map_err(parse_io_error)?;
-
-        let f = self
-            .client
-            .open_file()
-            .create(true)
-            .write(true)
-            .async_open(&p)
-            .await
-            .map_err(parse_io_error)?;
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let (target_path, tmp_path) = self.prepare_write(path)?;
+
+        let f = if args.append() && tmp_path.is_none() {
+            match self
+                .client
+                .open_file()
+                .append(true)
+                .async_open(&target_path)
+                .await
+            {
+                Ok(f) => f,
+                // File is not exist, create it instead.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => self
+                    .client
+                    .open_file()
+                    .create(true)
+                    .write(true)
+                    .async_open(&target_path)
+                    .await
+                    .map_err(parse_io_error)?,
+                Err(err) => return Err(parse_io_error(err)),
+            }
+        } else {
+            self.client
+                .open_file()
+                .create(true)
+                .write(true)
+                .async_open(tmp_path.as_ref().unwrap_or(&target_path))
+                .await
+                .map_err(parse_io_error)?
+        };
 
-        Ok((RpWrite::new(), HdfsWriter::new(f)))
+        Ok((
+            RpWrite::new(),
+            HdfsWriter::new(self.client.clone(), target_path, tmp_path, f),
+        ))
     }
 
     async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
@@ -351,6 +431,12 @@ impl Accessor for HdfsBackend {
         // Safety: Err branch has been checked, it's OK to unwrap.
         let meta = meta.ok().unwrap();
 
+        // Path is not match with the entry mode, which means the entry
+        // doesn't exist.
+        if meta.is_dir() != path.ends_with('/') {
+            return Ok(RpDelete::default());
+        }
+
         let result = if meta.is_dir() {
             self.client.remove_dir(&p)
         } else {
@@ -423,33 +509,35 @@ impl Accessor for HdfsBackend {
         Ok((RpRead::new(end - start), r))
     }
 
-    fn blocking_write(&self, path: &str, _: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
-        let p = build_rooted_abs_path(&self.root, path);
-
-        let parent = PathBuf::from(&p)
-            .parent()
-            .ok_or_else(|| {
-                Error::new(
-                    ErrorKind::Unexpected,
-                    "path should have parent but not, it must be malformed",
-                )
-                .with_context("input", &p)
-            })?
-            .to_path_buf();
-
-        self.client
-            .create_dir(&parent.to_string_lossy())
-            .map_err(parse_io_error)?;
-
-        let f = self
-            .client
-            .open_file()
-            .create(true)
-            .write(true)
-            .open(&p)
-            .map_err(parse_io_error)?;
+    fn blocking_write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::BlockingWriter)> {
+        let (target_path, tmp_path) = self.prepare_write(path)?;
+
+        let f = if args.append() && tmp_path.is_none() {
+            match self.client.open_file().append(true).open(&target_path) {
+                Ok(f) => f,
+                // File is not exist, create it instead.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => self
+                    .client
+                    .open_file()
+                    .create(true)
+                    .write(true)
+                    .open(&target_path)
+                    .map_err(parse_io_error)?,
+                Err(err) => return Err(parse_io_error(err)),
+            }
+        } else {
+            self.client
+                .open_file()
+                .create(true)
+                .write(true)
+                .open(tmp_path.as_ref().unwrap_or(&target_path))
+                .map_err(parse_io_error)?
+        };
 
-        Ok((RpWrite::new(), HdfsWriter::new(f)))
+        Ok((
+            RpWrite::new(),
+            HdfsWriter::new(self.client.clone(), target_path, tmp_path, f),
+        ))
     }
 
     fn blocking_stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
@@ -487,6 +575,12 @@ impl Accessor for HdfsBackend {
         // Safety: Err branch has been checked, it's OK to unwrap.
         let meta = meta.ok().unwrap();
 
+        // Path is not match with the entry mode, which means the entry
+        // doesn't exist.
+        if meta.is_dir() != path.ends_with('/') {
+            return Ok(RpDelete::default());
+        }
+
         let result = if meta.is_dir() {
             self.client.remove_dir(&p)
         } else {
@@ -517,3 +611,23 @@ impl Accessor for HdfsBackend {
         Ok((RpList::default(), Some(rd)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tmp_file_of() {
+        let cases = vec![
+            ("hello.txt", ".hello.txt."),
+            ("/tmp/opendal.log", ".opendal.log."),
+            ("/abc/def/hello.parquet", ".hello.parquet."),
+        ];
+
+        for (path, expected_prefix) in cases {
+            let tmp_file = tmp_file_of(path);
+            assert!(tmp_file.starts_with(expected_prefix));
+            assert!(tmp_file.ends_with("._tmp"));
+        }
+    }
+}
@@ -22,20 +22,33 @@ use crate::ErrorKind;
 
 /// Parse all path related errors.
 ///
+/// libhdfs maps java exceptions thrown by the JNI layer into errno, for
+/// example `FileAlreadyExistsException` into `EEXIST` and
+/// `DSQuotaExceededException` into `EDQUOT`. We will convert them back
+/// into the proper error kinds.
+///
 /// ## Notes
 ///
 /// Skip utf-8 check to allow invalid path input.
 pub fn parse_io_error(err: io::Error) -> Error {
     use io::ErrorKind::*;
 
-    let (kind, retryable) = match err.kind() {
-        NotFound => (ErrorKind::NotFound, false),
-        PermissionDenied => (ErrorKind::PermissionDenied, false),
-        Interrupted | UnexpectedEof | TimedOut | WouldBlock => (ErrorKind::Unexpected, true),
-        _ => (ErrorKind::Unexpected, true),
+    let (kind, retryable) = match err.raw_os_error() {
+        Some(libc::EISDIR) => (ErrorKind::IsADirectory, false),
+        Some(libc::ENOTDIR) => (ErrorKind::NotADirectory, false),
+        Some(libc::ENOSPC | libc::EDQUOT) => (ErrorKind::InsufficientStorage, false),
+        Some(libc::ENOTSUP | libc::ENOSYS) => (ErrorKind::Unsupported, false),
+        _ => match err.kind() {
+            NotFound => (ErrorKind::NotFound, false),
+            PermissionDenied => (ErrorKind::PermissionDenied, false),
+            AlreadyExists => (ErrorKind::AlreadyExists, false),
+            InvalidInput => (ErrorKind::Unexpected, false),
+            Interrupted | UnexpectedEof | TimedOut | WouldBlock => (ErrorKind::Unexpected, true),
+            _ => (ErrorKind::Unexpected, true),
+        },
     };
 
-    let mut err = Error::new(kind, &err.kind().to_string()).set_source(err);
+    let mut err = Error::new(kind, &err.to_string()).set_source(err);
 
     if retryable {
         err = err.set_temporary();
@@ -43,3 +56,31 @@ pub fn parse_io_error(err: io::Error) -> Error {
 
     err
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_io_error() {
+        let cases = [
+            (libc::ENOENT, ErrorKind::NotFound),
+            (libc::EACCES, ErrorKind::PermissionDenied),
+            (libc::EEXIST, ErrorKind::AlreadyExists),
+            (libc::EISDIR, ErrorKind::IsADirectory),
+            (libc::ENOTDIR, ErrorKind::NotADirectory),
+            (libc::EDQUOT, ErrorKind::InsufficientStorage),
+            (libc::ENOTSUP, ErrorKind::Unsupported),
+        ];
+
+        for (code, kind) in cases {
+            let err = parse_io_error(io::Error::from_raw_os_error(code));
+            assert_eq!(err.kind(), kind, "errno {code}");
+            assert!(!err.is_temporary(), "errno {code}");
+        }
+
+        let err = parse_io_error(io::Error::from_raw_os_error(libc::EINTR));
+        assert_eq!(err.kind(), ErrorKind::Unexpected);
+        assert!(err.is_temporary());
+    }
+}
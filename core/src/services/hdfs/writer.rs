@@ -15,9 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::io;
 use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -29,13 +31,63 @@ use crate::raw::*;
 use crate::*;
 
 pub struct HdfsWriter<F> {
+    client: Arc<hdrs::Client>,
+    target_path: String,
+    tmp_path: Option<String>,
     f: F,
-    pos: u64,
 }
 
+/// hdrs::Client is thread-safe.
+unsafe impl<F: Send> Send for HdfsWriter<F> {}
+unsafe impl<F: Sync> Sync for HdfsWriter<F> {}
+
 impl<F> HdfsWriter<F> {
-    pub fn new(f: F) -> Self {
-        Self { f, pos: 0 }
+    pub fn new(
+        client: Arc<hdrs::Client>,
+        target_path: String,
+        tmp_path: Option<String>,
+        f: F,
+    ) -> Self {
+        Self {
+            client,
+            target_path,
+            tmp_path,
+            f,
+        }
+    }
+
+    /// Rename the temp file into place if atomic write is enabled.
+    ///
+    /// HDFS doesn't allow renaming into an existing file, so we will remove
+    /// the target first.
+    fn rename_tmp_file(&mut self) -> Result<()> {
+        if let Some(tmp_path) = &self.tmp_path {
+            match self.client.remove_file(&self.target_path) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => return Err(parse_io_error(err)),
+            }
+            self.client
+                .rename_file(tmp_path, &self.target_path)
+                .map_err(parse_io_error)?;
+            self.tmp_path = None;
+        }
+
+        Ok(())
+    }
+
+    fn remove_tmp_file(&mut self) -> Result<()> {
+        match &self.tmp_path {
+            Some(tmp_path) => {
+                self.client.remove_file(tmp_path).map_err(parse_io_error)?;
+                self.tmp_path = None;
+                Ok(())
+            }
+            None => Err(Error::new(
+                ErrorKind::Unsupported,
+                "output writer doesn't support abort",
+            )),
+        }
     }
 }
 
@@ -57,30 +109,21 @@ impl oio::Write for HdfsWriter<hdrs::AsyncFile> {
 
     /// # Notes
     ///
-    /// File could be partial written, so we will seek to start to make sure
-    /// we write the same content.
+    /// HDFS output stream is append only, so we will write the content to
+    /// the end of file directly.
     async fn append(&mut self, bs: Bytes) -> Result<()> {
-        self.f
-            .seek(SeekFrom::Start(self.pos))
-            .await
-            .map_err(parse_io_error)?;
         self.f.write_all(&bs).await.map_err(parse_io_error)?;
-        self.pos += bs.len() as u64;
 
         Ok(())
     }
 
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        self.remove_tmp_file()
     }
 
     async fn close(&mut self) -> Result<()> {
         self.f.close().await.map_err(parse_io_error)?;
-
-        Ok(())
+        self.rename_tmp_file()
     }
 }
 
@@ -98,21 +141,20 @@ impl oio::BlockingWrite for HdfsWriter<hdrs::File> {
 
     /// # Notes
     ///
-    /// File could be partial written, so we will seek to start to make sure
-    /// we write the same content.
+    /// HDFS output stream is append only, so we will write the content to
+    /// the end of file directly.
     fn append(&mut self, bs: Bytes) -> Result<()> {
-        self.f
-            .seek(SeekFrom::Start(self.pos))
-            .map_err(parse_io_error)?;
         self.f.write_all(&bs).map_err(parse_io_error)?;
-        self.pos += bs.len() as u64;
 
         Ok(())
     }
 
+    fn abort(&mut self) -> Result<()> {
+        self.remove_tmp_file()
+    }
+
     fn close(&mut self) -> Result<()> {
         self.f.flush().map_err(parse_io_error)?;
-
-        Ok(())
+        self.rename_tmp_file()
     }
 }
@@ -21,25 +21,29 @@ use std::fmt::Formatter;
 
 use async_trait::async_trait;
 use http::header;
+use http::header::HeaderName;
 use http::header::IF_NONE_MATCH;
+use http::HeaderMap;
+use http::HeaderValue;
 use http::Request;
 use http::Response;
 use http::StatusCode;
 use log::debug;
 
 use super::error::parse_error;
+use super::writer::HttpWriter;
 use crate::ops::*;
 use crate::raw::*;
 use crate::*;
 
-/// HTTP Read-only service support like Nginx and Caddy.
+/// HTTP service support like Nginx and Caddy.
 ///
 /// # Capabilities
 ///
 /// This service can be used to:
 ///
 /// - [x] read
-/// - [ ] ~~write~~
+/// - [x] write (requires `enable_write`)
 /// - [ ] ~~list~~
 /// - [ ] ~~scan~~
 /// - [ ] ~~presign~~
@@ -47,13 +51,23 @@ use crate::*;
 ///
 /// # Notes
 ///
-/// Only `read` ans `stat` are supported. We can use this service to visit any
-/// HTTP Server like nginx, caddy.
+/// Only `read` ans `stat` are supported by default. We can use this service
+/// to visit any HTTP Server like nginx, caddy.
+///
+/// With `enable_write`, `write` and `delete` will be supported via `PUT`
+/// and `DELETE` on the same urls, which is useful for servers like artifact
+/// servers. `405 Method Not Allowed` and `501 Not Implemented` responses
+/// will be returned as `Unsupported` errors.
 ///
 /// # Configuration
 ///
 /// - `endpoint`: set the endpoint for http
 /// - `root`: Set the work directory for backend
+/// - `enable_write`: Set to `true` to enable write and delete
+/// - `username`, `password`: Set the basic auth for every request
+/// - `token`: Set the bearer token for every request
+/// - `header.<name>`: Set a static header for every request, for example
+///   `header.X-Api-Key`
 ///
 /// You can refer to [`HttpBuilder`]'s docs for more information
 ///
@@ -84,6 +98,8 @@ pub struct HttpBuilder {
     password: Option<String>,
     token: Option<String>,
     root: Option<String>,
+    enable_write: bool,
+    headers: Vec<(String, String)>,
     http_client: Option<HttpClient>,
 }
 
@@ -92,6 +108,22 @@ impl Debug for HttpBuilder {
         let mut de = f.debug_struct("Builder");
         de.field("endpoint", &self.endpoint);
         de.field("root", &self.root);
+        de.field("enable_write", &self.enable_write);
+        de.field("username", &self.username);
+        if self.password.is_some() {
+            de.field("password", &"<redacted>");
+        }
+        if self.token.is_some() {
+            de.field("token", &"<redacted>");
+        }
+        de.field(
+            "headers",
+            &self
+                .headers
+                .iter()
+                .map(|(k, _)| (k.as_str(), "<redacted>"))
+                .collect::<Vec<_>>(),
+        );
 
         de.finish()
     }
@@ -152,6 +184,24 @@ impl HttpBuilder {
         self
     }
 
+    /// Add a static header which will be attached to every request.
+    ///
+    /// Headers with the same name will be all sent.
+    pub fn header(&mut self, key: &str, value: &str) -> &mut Self {
+        if !key.is_empty() {
+            self.headers.push((key.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    /// Enable or disable write and delete via `PUT` and `DELETE`.
+    ///
+    /// default: disabled
+    pub fn enable_write(&mut self, enabled: bool) -> &mut Self {
+        self.enable_write = enabled;
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -176,6 +226,17 @@ impl Builder for HttpBuilder {
         map.get("username").map(|v| builder.username(v));
         map.get("password").map(|v| builder.password(v));
         map.get("token").map(|v| builder.token(v));
+        map.get("enable_write")
+            .map(|v| builder.enable_write(v == "on" || v == "true"));
+        // Sort headers to make sure they are sent in a stable order.
+        let mut headers: Vec<_> = map
+            .iter()
+            .filter_map(|(k, v)| k.strip_prefix("header.").map(|k| (k, v)))
+            .collect();
+        headers.sort();
+        for (k, v) in headers {
+            builder.header(k, v);
+        }
 
         builder
     }
@@ -214,11 +275,30 @@ impl Builder for HttpBuilder {
             auth = Some(format_authorization_by_bearer(token)?)
         }
 
+        let mut headers = HeaderMap::new();
+        for (k, v) in &self.headers {
+            let name = HeaderName::from_bytes(k.as_bytes()).map_err(|err| {
+                Error::new(ErrorKind::ConfigInvalid, "header name is invalid")
+                    .with_context("service", Scheme::Http)
+                    .with_context("header", k)
+                    .set_source(err)
+            })?;
+            let value = HeaderValue::from_str(v).map_err(|err| {
+                Error::new(ErrorKind::ConfigInvalid, "header value is invalid")
+                    .with_context("service", Scheme::Http)
+                    .with_context("header", k)
+                    .set_source(err)
+            })?;
+            headers.append(name, value);
+        }
+
         debug!("backend build finished: {:?}", &self);
         Ok(HttpBackend {
             endpoint: endpoint.to_string(),
             authorization: auth,
+            headers,
             root,
+            enable_write: self.enable_write,
             client,
         })
     }
@@ -229,9 +309,11 @@ impl Builder for HttpBuilder {
 pub struct HttpBackend {
     endpoint: String,
     root: String,
+    enable_write: bool,
     client: HttpClient,
 
     authorization: Option<String>,
+    headers: HeaderMap,
 }
 
 impl Debug for HttpBackend {
@@ -239,6 +321,7 @@ impl Debug for HttpBackend {
         f.debug_struct("Backend")
             .field("endpoint", &self.endpoint)
             .field("root", &self.root)
+            .field("enable_write", &self.enable_write)
             .field("client", &self.client)
             .finish()
     }
@@ -248,16 +331,24 @@ impl Debug for HttpBackend {
 impl Accessor for HttpBackend {
     type Reader = IncomingAsyncBody;
     type BlockingReader = ();
-    type Writer = ();
+    type Writer = HttpWriter;
     type BlockingWriter = ();
     type Pager = ();
     type BlockingPager = ();
 
     fn info(&self) -> AccessorInfo {
+        let mut cap = AccessorCapability::Read
+            | AccessorCapability::ReadWithIfNoneMatch
+            | AccessorCapability::StatWithIfNoneMatch;
+        if self.enable_write {
+            cap |= AccessorCapability::Write | AccessorCapability::WriteWithContentType;
+        }
+
         let mut ma = AccessorInfo::default();
         ma.set_scheme(Scheme::Http)
             .set_root(&self.root)
-            .set_capabilities(AccessorCapability::Read)
+            .set_endpoint(&self.endpoint)
+            .set_capabilities(cap)
             .set_hints(AccessorHint::ReadStreamable);
 
         ma
@@ -299,9 +390,70 @@ impl Accessor for HttpBackend {
             _ => Err(parse_error(resp).await?),
         }
     }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        self.check_write_enabled()?;
+
+        if args.append() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "append write is not supported",
+            ));
+        }
+
+        Ok((
+            RpWrite::default(),
+            HttpWriter::new(self.clone(), args, path.to_string()),
+        ))
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        self.check_write_enabled()?;
+
+        let resp = self.http_delete(path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK
+            | StatusCode::ACCEPTED
+            | StatusCode::NO_CONTENT
+            | StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(RpDelete::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
 }
 
 impl HttpBackend {
+    /// Attach authorization and static headers to the request.
+    fn apply_headers(&self, mut req: http::request::Builder) -> http::request::Builder {
+        if let Some(auth) = &self.authorization {
+            req = req.header(header::AUTHORIZATION, auth.clone())
+        }
+        if let Some(headers) = req.headers_mut() {
+            for (k, v) in &self.headers {
+                headers.append(k, v.clone());
+            }
+        }
+
+        req
+    }
+
+    fn check_write_enabled(&self) -> Result<()> {
+        if self.enable_write {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "write is not enabled, please set enable_write to true",
+            )
+            .with_context("service", Scheme::Http))
+        }
+    }
+
     async fn http_get(
         &self,
         path: &str,
@@ -318,9 +470,7 @@ impl HttpBackend {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
-        if let Some(auth) = &self.authorization {
-            req = req.header(header::AUTHORIZATION, auth.clone())
-        }
+        req = self.apply_headers(req);
 
         if !range.is_full() {
             req = req.header(header::RANGE, range.to_header());
@@ -348,10 +498,52 @@ impl HttpBackend {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
-        if let Some(auth) = &self.authorization {
-            req = req.header(header::AUTHORIZATION, auth.clone())
+        req = self.apply_headers(req);
+
+        let req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
+    pub async fn http_put(
+        &self,
+        path: &str,
+        size: Option<usize>,
+        content_type: Option<&str>,
+        body: AsyncBody,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::put(&url);
+
+        req = self.apply_headers(req);
+
+        if let Some(size) = size {
+            req = req.header(header::CONTENT_LENGTH, size)
+        }
+
+        if let Some(mime) = content_type {
+            req = req.header(header::CONTENT_TYPE, mime)
         }
 
+        let req = req.body(body).map_err(new_request_build_error)?;
+
+        self.client.send(req).await
+    }
+
+    async fn http_delete(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let url = format!("{}{}", self.endpoint, percent_encode_path(&p));
+
+        let mut req = Request::delete(&url);
+
+        req = self.apply_headers(req);
+
         let req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
@@ -365,6 +557,7 @@ mod tests {
     use anyhow::Result;
     use wiremock::matchers::basic_auth;
     use wiremock::matchers::bearer_token;
+    use wiremock::matchers::body_string;
     use wiremock::matchers::headers;
     use wiremock::matchers::method;
     use wiremock::matchers::path;
@@ -536,4 +729,127 @@ mod tests {
         assert_eq!(bs.content_length(), 128);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_write_and_delete() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/dir/hello"))
+            .and(headers("content-type", vec!["text/plain"]))
+            .and(body_string("Hello, World!"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/dir/hello"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/dir/not_exist"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let builder = HttpBuilder::from_map(HashMap::from([
+            ("endpoint".to_string(), mock_server.uri()),
+            ("root".to_string(), "/dir".to_string()),
+            ("enable_write".to_string(), "true".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+        assert!(op.info().can_write());
+
+        op.write_with(
+            "hello",
+            OpWrite::new().with_content_type("text/plain"),
+            "Hello, World!",
+        )
+        .await?;
+        op.delete("hello").await?;
+        op.delete("not_exist").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_unsupported() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&mock_server)
+            .await;
+
+        // Write is disabled by default.
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+        assert!(!op.info().can_write());
+        let err = op.write("hello", "Hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        // Server doesn't allow PUT.
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri()).enable_write(true);
+        let op = Operator::new(builder)?.finish();
+        let err = op.write("hello", "Hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_with_static_headers() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            .and(headers("x-api-key", vec!["secret-key"]))
+            .and(headers("x-tenant", vec!["opendal"]))
+            .respond_with(ResponseTemplate::new(200).set_body_string("Hello, World!"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let builder = HttpBuilder::from_map(HashMap::from([
+            ("endpoint".to_string(), mock_server.uri()),
+            ("password".to_string(), "password".to_string()),
+            ("header.X-Api-Key".to_string(), "secret-key".to_string()),
+            ("header.X-Tenant".to_string(), "opendal".to_string()),
+        ]));
+        let debug = format!("{builder:?}");
+        assert!(!debug.contains("secret-key"));
+        assert!(!debug.contains("\"password\""));
+        let op = Operator::new(builder)?.finish();
+        assert_eq!(op.read("hello").await?, b"Hello, World!");
+
+        // Requests without required headers will be rejected.
+        let mut builder = HttpBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+        let err = op.read("hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut builder = HttpBuilder::default();
+        builder
+            .endpoint("http://127.0.0.1")
+            .header("invalid header", "value");
+        let err = builder.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
 }
@@ -19,3 +19,4 @@ mod backend;
 pub use backend::HttpBuilder as Http;
 
 mod error;
+mod writer;
@@ -219,6 +219,7 @@ impl Accessor for IpfsBackend {
         let mut ma = AccessorInfo::default();
         ma.set_scheme(Scheme::Ipfs)
             .set_root(&self.root)
+            .set_endpoint(&self.endpoint)
             .set_capabilities(AccessorCapability::Read | AccessorCapability::List)
             .set_hints(AccessorHint::ReadStreamable);
 
@@ -17,6 +17,8 @@
 
 use std::collections::HashMap;
 use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use async_compat::Compat;
 use async_trait::async_trait;
@@ -107,6 +109,9 @@ impl MemcachedBuilder {
     }
 
     /// Set the default ttl for memcached services.
+    ///
+    /// memcached only supports ttl in seconds, ttl that is not whole seconds
+    /// will be rounded up.
     pub fn default_ttl(&mut self, ttl: Duration) -> &mut Self {
         self.default_ttl = Some(ttl);
         self
@@ -122,6 +127,10 @@ impl Builder for MemcachedBuilder {
 
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("default_ttl").map(|v| {
+            v.parse::<u64>()
+                .map(|v| builder.default_ttl(Duration::from_secs(v)))
+        });
 
         builder
     }
@@ -248,16 +257,9 @@ impl kv::Adapter for Adapter {
     async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
         let mut conn = self.conn().await?;
 
-        conn.set(
-            &percent_encode_path(key),
-            value,
-            // Set expiration to 0 if ttl not set.
-            self.default_ttl
-                .map(|v| v.as_secs() as u32)
-                .unwrap_or_default(),
-        )
-        .await
-        .map_err(parse_io_error)?;
+        conn.set(&percent_encode_path(key), value, exptime(self.default_ttl))
+            .await
+            .map_err(parse_io_error)?;
 
         Ok(())
     }
@@ -328,3 +330,54 @@ impl bb8::ManageConnection for MemcacheConnectionManager {
         false
     }
 }
+
+/// Max relative expiration time memcached accepts, larger values will be
+/// treated as unix timestamp.
+const MAX_RELATIVE_EXPTIME: u64 = 60 * 60 * 24 * 30;
+
+/// Convert ttl into memcached's exptime.
+///
+/// - `0` means never expire, so ttl less than 1 second will be rounded up.
+/// - ttl longer than 30 days must be sent as an absolute unix timestamp.
+fn exptime(ttl: Option<Duration>) -> u32 {
+    let ttl = match ttl {
+        None => return 0,
+        Some(ttl) => ttl,
+    };
+
+    let mut secs = ttl.as_secs();
+    if ttl.subsec_nanos() > 0 {
+        secs += 1;
+    }
+
+    if secs > MAX_RELATIVE_EXPTIME {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        secs += now;
+    }
+
+    secs as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exptime() {
+        assert_eq!(exptime(None), 0);
+        assert_eq!(exptime(Some(Duration::from_secs(60))), 60);
+        assert_eq!(exptime(Some(Duration::from_millis(100))), 1);
+        assert_eq!(exptime(Some(Duration::from_millis(1500))), 2);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let ttl = 60 * 60 * 24 * 31;
+        let actual = exptime(Some(Duration::from_secs(ttl))) as u64;
+        assert!(actual >= now + ttl && actual <= now + ttl + 5);
+    }
+}
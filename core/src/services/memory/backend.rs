@@ -23,6 +23,7 @@ use async_trait::async_trait;
 use parking_lot::Mutex;
 
 use crate::raw::adapters::kv;
+use crate::raw::adapters::typed_kv;
 use crate::raw::*;
 use crate::*;
 
@@ -38,6 +39,13 @@ use crate::*;
 /// - [x] scan
 /// - [ ] ~~presign~~
 /// - [x] blocking
+///
+/// # Metadata
+///
+/// Values are stored along with their metadata, so that `stat`, `read` and
+/// `scan` will return `content_length`, `last_modified` (the time of write)
+/// and `content_type` / `content_disposition` (if set in `OpWrite`) like
+/// object storage services.
 #[derive(Default)]
 pub struct MemoryBuilder {
     root: Option<String>,
@@ -73,40 +81,41 @@ impl Builder for MemoryBuilder {
 }
 
 /// Backend is used to serve `Accessor` support in memory.
-pub type MemoryBackend = kv::Backend<Adapter>;
+pub type MemoryBackend = typed_kv::Backend<Adapter>;
 
 #[derive(Debug, Clone)]
 pub struct Adapter {
-    inner: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    inner: Arc<Mutex<BTreeMap<String, typed_kv::Value>>>,
 }
 
 #[async_trait]
-impl kv::Adapter for Adapter {
+impl typed_kv::Adapter for Adapter {
     fn metadata(&self) -> kv::Metadata {
         kv::Metadata::new(
             Scheme::Memory,
             &format!("{:?}", &self.inner as *const _),
-            AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::Scan,
+            AccessorCapability::Read
+                | AccessorCapability::Write
+                | AccessorCapability::Scan
+                | AccessorCapability::WriteWithContentType
+                | AccessorCapability::WriteWithContentDisposition,
         )
     }
 
-    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+    async fn get(&self, path: &str) -> Result<Option<typed_kv::Value>> {
         self.blocking_get(path)
     }
 
-    fn blocking_get(&self, path: &str) -> Result<Option<Vec<u8>>> {
-        match self.inner.lock().get(path) {
-            None => Ok(None),
-            Some(bs) => Ok(Some(bs.to_vec())),
-        }
+    fn blocking_get(&self, path: &str) -> Result<Option<typed_kv::Value>> {
+        Ok(self.inner.lock().get(path).cloned())
     }
 
-    async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+    async fn set(&self, path: &str, value: typed_kv::Value) -> Result<()> {
         self.blocking_set(path, value)
     }
 
-    fn blocking_set(&self, path: &str, value: &[u8]) -> Result<()> {
-        self.inner.lock().insert(path.to_string(), value.to_vec());
+    fn blocking_set(&self, path: &str, value: typed_kv::Value) -> Result<()> {
+        self.inner.lock().insert(path.to_string(), value);
 
         Ok(())
     }
@@ -121,29 +130,35 @@ impl kv::Adapter for Adapter {
         Ok(())
     }
 
-    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+    async fn scan(&self, path: &str) -> Result<Vec<(String, Metadata)>> {
         self.blocking_scan(path)
     }
 
-    fn blocking_scan(&self, path: &str) -> Result<Vec<String>> {
+    fn blocking_scan(&self, path: &str) -> Result<Vec<(String, Metadata)>> {
         let inner = self.inner.lock();
-        let keys: Vec<_> = if path.is_empty() {
-            inner.keys().cloned().collect()
+        let entries: Vec<_> = if path.is_empty() {
+            inner
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.metadata.clone()))
+                .collect()
         } else {
             let right_range = format!("{}0", &path[..path.len() - 1]);
             inner
                 .range(path.to_string()..right_range)
-                .map(|(k, _)| k.to_string())
+                .map(|(k, v)| (k.to_string(), v.metadata.clone()))
                 .collect()
         };
 
-        Ok(keys)
+        Ok(entries)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use futures::TryStreamExt;
+
     use super::*;
+    use crate::ops::*;
 
     #[test]
     fn test_accessor_metadata_name() {
@@ -153,4 +168,39 @@ mod tests {
         let b2 = MemoryBuilder::default().build().unwrap();
         assert_ne!(b1.info().name(), b2.info().name())
     }
+
+    #[tokio::test]
+    async fn test_metadata() -> Result<()> {
+        let op = Operator::new(MemoryBuilder::default())?.finish();
+
+        let before = chrono::Utc::now();
+        op.write_with(
+            "dir/file",
+            OpWrite::new().with_content_type("text/plain"),
+            "content",
+        )
+        .await?;
+
+        let meta = op.stat("dir/file").await?;
+        assert_eq!(meta.mode(), EntryMode::FILE);
+        assert_eq!(meta.content_length(), 7);
+        assert_eq!(meta.content_type(), Some("text/plain"));
+        assert!(meta.last_modified().expect("last_modified must be set") >= before);
+
+        let (rp, _) = op.inner().read("dir/file", OpRead::new()).await?;
+        assert_eq!(rp.metadata().content_type(), Some("text/plain"));
+        let (rp, _) = op
+            .inner()
+            .read("dir/file", OpRead::new().with_range((1..3).into()))
+            .await?;
+        assert_eq!(rp.metadata().content_length(), 2);
+
+        // Content length is available without extra stat.
+        let entries: Vec<_> = op.scan("dir/").await?.try_collect().await?;
+        assert_eq!(entries.len(), 1);
+        let meta = op.metadata(&entries[0], Metakey::ContentLength).await?;
+        assert_eq!(meta.content_length(), 7);
+
+        Ok(())
+    }
 }
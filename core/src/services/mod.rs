@@ -29,11 +29,31 @@ mod azdfs;
 #[cfg(feature = "services-azdfs")]
 pub use azdfs::Azdfs;
 
+#[cfg(feature = "services-azfile")]
+mod azfile;
+#[cfg(feature = "services-azfile")]
+pub use azfile::Azfile;
+
+#[cfg(feature = "services-b2")]
+mod b2;
+#[cfg(feature = "services-b2")]
+pub use b2::B2;
+
+#[cfg(feature = "services-cloudflare-kv")]
+mod cloudflare_kv;
+#[cfg(feature = "services-cloudflare-kv")]
+pub use self::cloudflare_kv::CloudflareKv;
+
 #[cfg(feature = "services-dashmap")]
 mod dashmap;
 #[cfg(feature = "services-dashmap")]
 pub use self::dashmap::Dashmap;
 
+#[cfg(feature = "services-etcd")]
+mod etcd;
+#[cfg(feature = "services-etcd")]
+pub use self::etcd::Etcd;
+
 #[cfg(feature = "services-fs")]
 mod fs;
 #[cfg(feature = "services-fs")]
@@ -54,6 +74,11 @@ mod ghac;
 #[cfg(feature = "services-ghac")]
 pub use ghac::Ghac;
 
+#[cfg(feature = "services-gridfs")]
+mod gridfs;
+#[cfg(feature = "services-gridfs")]
+pub use self::gridfs::Gridfs;
+
 #[cfg(feature = "services-hdfs")]
 mod hdfs;
 #[cfg(feature = "services-hdfs")]
@@ -89,6 +114,11 @@ mod moka;
 #[cfg(feature = "services-moka")]
 pub use self::moka::Moka;
 
+#[cfg(feature = "services-mysql")]
+mod mysql;
+#[cfg(feature = "services-mysql")]
+pub use self::mysql::Mysql;
+
 #[cfg(feature = "services-obs")]
 mod obs;
 #[cfg(feature = "services-obs")]
@@ -98,6 +128,13 @@ pub use obs::Obs;
 mod oss;
 #[cfg(feature = "services-oss")]
 pub use oss::Oss;
+#[cfg(feature = "services-oss")]
+pub use oss::OssCredentialLoad;
+
+#[cfg(feature = "services-postgres")]
+mod postgres;
+#[cfg(feature = "services-postgres")]
+pub use self::postgres::Postgres;
 
 #[cfg(feature = "services-redis")]
 mod redis;
@@ -114,11 +151,31 @@ mod s3;
 #[cfg(feature = "services-s3")]
 pub use s3::S3;
 
+#[cfg(feature = "services-sftp")]
+mod sftp;
+#[cfg(feature = "services-sftp")]
+pub use sftp::Sftp;
+
 #[cfg(feature = "services-sled")]
 mod sled;
 #[cfg(feature = "services-sled")]
 pub use self::sled::Sled;
 
+#[cfg(feature = "services-sqlite")]
+mod sqlite;
+#[cfg(feature = "services-sqlite")]
+pub use self::sqlite::Sqlite;
+
+#[cfg(feature = "services-swift")]
+mod swift;
+#[cfg(feature = "services-swift")]
+pub use swift::Swift;
+
+#[cfg(feature = "services-tikv")]
+mod tikv;
+#[cfg(feature = "services-tikv")]
+pub use self::tikv::Tikv;
+
 #[cfg(feature = "services-wasabi")]
 mod wasabi;
 #[cfg(feature = "services-wasabi")]
@@ -133,3 +190,8 @@ pub use webdav::Webdav;
 mod webhdfs;
 #[cfg(feature = "services-webhdfs")]
 pub use webhdfs::Webhdfs;
+#[cfg(feature = "services-webhdfs")]
+pub use webhdfs::WebhdfsDelegationLoad;
+
+mod config;
+pub use config::*;
@@ -37,7 +37,7 @@ use crate::*;
 /// - [x] read
 /// - [x] write
 /// - [ ] ~~list~~
-/// - [ ] ~~scan~~
+/// - [x] scan
 /// - [ ] ~~presign~~
 /// - [x] blocking
 #[derive(Default, Debug)]
@@ -200,7 +200,7 @@ impl kv::Adapter for Adapter {
         kv::Metadata::new(
             Scheme::Moka,
             self.inner.name().unwrap_or("moka"),
-            AccessorCapability::Read | AccessorCapability::Write,
+            AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::Scan,
         )
     }
 
@@ -234,4 +234,18 @@ impl kv::Adapter for Adapter {
 
         Ok(())
     }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        self.blocking_scan(path)
+    }
+
+    /// moka doesn't keep keys in order, so we have to iterate all entries.
+    fn blocking_scan(&self, path: &str) -> Result<Vec<String>> {
+        Ok(self
+            .inner
+            .iter()
+            .map(|(k, _)| k.to_string())
+            .filter(|k| k.starts_with(path))
+            .collect())
+    }
 }
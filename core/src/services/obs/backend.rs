@@ -27,6 +27,7 @@ use reqsign::HuaweicloudObsConfig;
 use reqsign::HuaweicloudObsCredentialLoader;
 use reqsign::HuaweicloudObsSigner;
 
+use super::core::parse_into_obs_metadata;
 use super::core::ObsCore;
 use super::error::parse_error;
 use super::pager::ObsPager;
@@ -307,9 +308,20 @@ impl Accessor for ObsBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Obs)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.bucket)
-            .set_capabilities(Read | Write | Copy | List | Scan)
-            .set_hints(ReadStreamable);
+            .set_capabilities(
+                Read | Write
+                    | Copy
+                    | List
+                    | Scan
+                    | WriteWithContentType
+                    | ReadWithIfMatch
+                    | StatWithIfMatch
+                    | WriteWithIfMatch
+                    | ListWithLimit,
+            )
+            .set_hints(ReadStreamable | ImplicitDir);
 
         am
     }
@@ -391,10 +403,7 @@ impl Accessor for ObsBackend {
 
         // The response is very similar to azblob.
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
-            }
+            StatusCode::OK => parse_into_obs_metadata(path, resp.headers()).map(RpStat::new),
             _ => Err(parse_error(resp).await?),
         }
     }
@@ -21,6 +21,7 @@ use std::fmt::Formatter;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::header::IF_MATCH;
+use http::HeaderMap;
 use http::Request;
 use http::Response;
 use reqsign::HuaweicloudObsCredential;
@@ -30,6 +31,11 @@ use reqsign::HuaweicloudObsSigner;
 use crate::raw::*;
 use crate::*;
 
+pub const X_OBS_OBJECT_TYPE: &str = "x-obs-object-type";
+pub const X_OBS_SERVER_SIDE_ENCRYPTION: &str = "x-obs-server-side-encryption";
+pub const X_OBS_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM: &str =
+    "x-obs-server-side-encryption-customer-algorithm";
+
 pub struct ObsCore {
     pub bucket: String,
     pub root: String,
@@ -244,3 +250,20 @@ impl ObsCore {
         self.send(req).await
     }
 }
+
+/// Parse obs response headers into metadata.
+pub fn parse_into_obs_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata> {
+    let mut m = parse_into_metadata(path, headers)?;
+
+    // Etags of appendable objects and objects encrypted by KMS or SSE-C
+    // are not md5 of content.
+    let header = |k: &str| headers.get(k).and_then(|v| v.to_str().ok());
+    if header(X_OBS_OBJECT_TYPE) == Some("Appendable")
+        || header(X_OBS_SERVER_SIDE_ENCRYPTION) == Some("kms")
+        || headers.contains_key(X_OBS_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM)
+    {
+        m.set_etag_opaque();
+    }
+
+    Ok(m)
+}
@@ -75,6 +75,8 @@ impl oio::Write for ObsWriter {
         ))
     }
 
+    /// Content is uploaded by a single put in `write`, there is no
+    /// initiated upload or staged part to clean up.
     async fn abort(&mut self) -> Result<()> {
         Ok(())
     }
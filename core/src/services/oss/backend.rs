@@ -16,10 +16,10 @@
 // under the License.
 
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 use bytes::Buf;
@@ -31,6 +31,8 @@ use reqsign::AliyunLoader;
 use reqsign::AliyunOssSigner;
 
 use super::core::*;
+use super::credential::OssCredentialLoad;
+use super::error::parse_delete_objects_result_error;
 use super::error::parse_error;
 use super::pager::OssPager;
 use super::writer::OssWriter;
@@ -38,6 +40,9 @@ use crate::ops::*;
 use crate::raw::*;
 use crate::*;
 
+/// The maximum number of keys in one DeleteMultipleObjects request.
+const OSS_BATCH_LIMIT: usize = 1000;
+
 /// Aliyun Object Storage Service (OSS) support
 ///
 /// # Capabilities
@@ -49,7 +54,7 @@ use crate::*;
 /// - [x] copy
 /// - [x] list
 /// - [x] scan
-/// - [ ] presign
+/// - [x] presign
 /// - [ ] blocking
 ///
 /// # Configuration
@@ -60,12 +65,43 @@ use crate::*;
 /// - `presign_endpoint`: Set the endpoint for presign.
 /// - `access_key_id`: Set the access_key_id for backend.
 /// - `access_key_secret`: Set the access_key_secret for backend.
+/// - `security_token`: Set the security_token of STS temporary credentials for backend.
 /// - `role_arn`: Set the role of backend.
 /// - `oidc_token`: Set the oidc_token for backend.
 /// - `allow_anonymous`: Set the backend access OSS in anonymous way.
+/// - `enable_append_object`: Use AppendObject for writer instead of multipart upload.
 ///
 /// Refer to [`OssBuilder`]'s public API docs for more information.
 ///
+/// # Credentials
+///
+/// STS temporary credentials can be set via `access_key_id`,
+/// `access_key_secret` and `security_token`. Static credentials can't be
+/// refreshed, use [`OssBuilder::customed_credential_loader`] instead for
+/// long-running operators so that credentials can be refreshed before
+/// they expire.
+///
+/// # Presign
+///
+/// Presigned requests are signed via query with `Expires`, including the
+/// `security-token` for STS credentials, so they can be used by browsers
+/// or curl directly until expired. Presigned read supports response header
+/// overrides via `override_content_disposition` and `override_cache_control`,
+/// which are ignored by normal reads.
+///
+/// # Writer
+///
+/// Data appended via [`Writer`] will be uploaded via multipart upload and
+/// completed while closing.
+///
+/// If `enable_append_object` is set, writer will append data via the
+/// `AppendObject` API instead, which is suitable for log shipping. Writer
+/// will continue from the end of the existing appendable object, or create
+/// a new one if not exist. Appending to a normal object will fail with
+/// [`ErrorKind::Unsupported`]. If the object has been appended by others,
+/// the append will fail with a temporary [`ErrorKind::PreconditionFailed`]
+/// and could be retried at the refreshed position.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -117,6 +153,10 @@ pub struct OssBuilder {
     // authenticate options
     access_key_id: Option<String>,
     access_key_secret: Option<String>,
+    security_token: Option<String>,
+    customed_credential_loader: Option<Box<dyn OssCredentialLoad>>,
+
+    enable_append_object: bool,
 
     http_client: Option<HttpClient>,
 }
@@ -126,7 +166,14 @@ impl Debug for OssBuilder {
         let mut d = f.debug_struct("Builder");
         d.field("root", &self.root)
             .field("bucket", &self.bucket)
-            .field("endpoint", &self.endpoint);
+            .field("endpoint", &self.endpoint)
+            .field("enable_append_object", &self.enable_append_object);
+        if self.security_token.is_some() {
+            d.field("security_token", &"<redacted>");
+        }
+        if self.customed_credential_loader.is_some() {
+            d.field("customed_credential_loader", &"<customed>");
+        }
 
         d.finish_non_exhaustive()
     }
@@ -204,6 +251,34 @@ impl OssBuilder {
         self
     }
 
+    /// Set security_token of this backend.
+    ///
+    /// The security token of STS temporary credentials, which will be sent
+    /// via the `x-oss-security-token` header (or `security-token` query for
+    /// presigned urls).
+    pub fn security_token(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.security_token = Some(v.to_string())
+        }
+
+        self
+    }
+
+    /// Specify the customed credential loader used by this service.
+    ///
+    /// Credentials loaded by it take precedence over static and env
+    /// credentials, and will be refreshed while they are going to expire.
+    pub fn customed_credential_loader(&mut self, loader: Box<dyn OssCredentialLoad>) -> &mut Self {
+        self.customed_credential_loader = Some(loader);
+        self
+    }
+
+    /// Use AppendObject for writer instead of multipart upload.
+    pub fn enable_append_object(&mut self) -> &mut Self {
+        self.enable_append_object = true;
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -270,6 +345,10 @@ impl Builder for OssBuilder {
         map.get("access_key_id").map(|v| builder.access_key_id(v));
         map.get("access_key_secret")
             .map(|v| builder.access_key_secret(v));
+        map.get("security_token").map(|v| builder.security_token(v));
+        map.get("enable_append_object")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_append_object());
 
         builder
     }
@@ -322,6 +401,10 @@ impl Builder for OssBuilder {
             cfg.access_key_secret = Some(v);
         }
 
+        if let Some(v) = self.security_token.take() {
+            cfg.security_token = Some(v);
+        }
+
         let loader = AliyunLoader::new(client.client(), cfg);
 
         let signer = AliyunOssSigner::new(bucket);
@@ -335,8 +418,11 @@ impl Builder for OssBuilder {
                 endpoint,
                 host,
                 presign_endpoint,
+                enable_append_object: self.enable_append_object,
                 signer,
                 loader,
+                customed_credential_loader: self.customed_credential_loader.take(),
+                customed_credential: Mutex::default(),
                 client,
             }),
         })
@@ -365,10 +451,27 @@ impl Accessor for OssBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Oss)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.bucket)
-            .set_max_batch_operations(1000)
-            .set_capabilities(Read | Write | Copy | List | Scan | Presign | Batch)
-            .set_hints(ReadStreamable);
+            .set_max_batch_operations(OSS_BATCH_LIMIT)
+            .set_capabilities(
+                Read | Write
+                    | Copy
+                    | List
+                    | Scan
+                    | Presign
+                    | Batch
+                    | WriteWithContentType
+                    | ReadWithIfNoneMatch
+                    | StatWithIfNoneMatch
+                    | WriteWithContentDisposition
+                    | WriteWithCacheControl
+                    | ReadWithOverrideCacheControl
+                    | ReadWithOverrideContentDisposition
+                    | ListWithLimit
+                    | Append,
+            )
+            .set_hints(ReadStreamable | ImplicitDir);
 
         am
     }
@@ -390,10 +493,7 @@ impl Accessor for OssBackend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let resp = self
-            .core
-            .oss_get_object(path, args.range(), args.if_none_match())
-            .await?;
+        let resp = self.core.oss_get_object(path, &args).await?;
 
         let status = resp.status();
 
@@ -407,7 +507,7 @@ impl Accessor for OssBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        let upload_id = if args.append() {
+        let upload_id = if args.append() && !self.core.enable_append_object {
             let resp = self.core.oss_initiate_upload(path, &args).await?;
             match resp.status() {
                 StatusCode::OK => {
@@ -456,12 +556,7 @@ impl Accessor for OssBackend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                let m = Metadata::new(EntryMode::DIR);
-                Ok(RpStat::new(m))
-            }
-
+            StatusCode::OK => parse_into_oss_metadata(path, resp.headers()).map(RpStat::new),
             _ => Err(parse_error(resp).await?),
         }
     }
@@ -496,10 +591,7 @@ impl Accessor for OssBackend {
         // We will not send this request out, just for signing.
         let mut req = match args.operation() {
             PresignOperation::Stat(_) => self.core.oss_head_object_request(path, true, None)?,
-            PresignOperation::Read(v) => {
-                self.core
-                    .oss_get_object_request(path, v.range(), true, None)?
-            }
+            PresignOperation::Read(v) => self.core.oss_get_object_request(path, true, v)?,
             PresignOperation::Write(v) => self.core.oss_put_object_request(
                 path,
                 None,
@@ -524,58 +616,614 @@ impl Accessor for OssBackend {
     }
 
     async fn batch(&self, args: OpBatch) -> Result<RpBatch> {
-        let ops = args.into_operation();
-        // Sadly, OSS will not return failed keys, so we will build
-        // a set to calculate the failed keys.
-        let mut keys = HashSet::new();
-
-        let ops_len = ops.len();
-        if ops_len > 1000 {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "oss services only allow delete up to 1000 keys at once",
-            )
-            .with_context("length", ops_len.to_string()));
-        }
-
-        let paths = ops
+        let paths = args
+            .into_operation()
             .into_iter()
-            .map(|(p, _)| {
-                keys.insert(p.clone());
-                p
+            .map(|(p, op)| match op {
+                BatchOperation::Delete(_) => p,
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        // OSS accepts at most `OSS_BATCH_LIMIT` keys in one request, split
+        // larger inputs into multiple requests.
+        let mut results = Vec::with_capacity(paths.len());
+        for chunk in paths.chunks(OSS_BATCH_LIMIT) {
+            let rps = self.batch_delete(chunk).await?;
+            results.extend(
+                rps.into_iter()
+                    .map(|(path, rp)| (path, rp.map(|v| v.into()))),
+            );
+        }
+        Ok(RpBatch::new(results))
+    }
+}
+
+impl OssBackend {
+    /// Delete given paths via one DeleteMultipleObjects call, `paths` must
+    /// not exceed `OSS_BATCH_LIMIT`.
+    async fn batch_delete(&self, paths: &[String]) -> Result<Vec<(String, Result<RpDelete>)>> {
+        debug_assert!(paths.len() <= OSS_BATCH_LIMIT);
 
         let resp = self.core.oss_delete_objects(paths).await?;
 
         let status = resp.status();
 
-        if let StatusCode::OK = status {
-            let bs = resp.into_body().bytes().await?;
+        if status != StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+        // Quiet mode returns nothing if all keys have been deleted.
+        let result: DeleteObjectsResult = if bs.is_empty() {
+            DeleteObjectsResult::default()
+        } else {
+            quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?
+        };
 
-            let result: DeleteObjectsResult =
-                quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+        let mut errors: HashMap<String, DeleteObjectsResultError> = result
+            .error
+            .into_iter()
+            .map(|v| (build_rel_path(&self.core.root, &v.key), v))
+            .collect();
 
-            let mut batched_result = Vec::with_capacity(ops_len);
-            for i in result.deleted {
-                let path = build_rel_path(&self.core.root, &i.key);
-                keys.remove(&path);
-                batched_result.push((path, Ok(RpDelete::default().into())));
-            }
-            // TODO: we should handle those errors with code.
-            for i in keys {
-                batched_result.push((
-                    i,
-                    Err(Error::new(
-                        ErrorKind::Unexpected,
-                        "oss delete this key failed for reason we don't know",
-                    )),
-                ));
+        Ok(paths
+            .iter()
+            .map(|path| match errors.remove(path) {
+                // Deleting a not existing key is fine.
+                Some(err) if err.code != "NoSuchKey" => {
+                    (path.clone(), Err(parse_delete_objects_result_error(err)))
+                }
+                _ => (path.clone(), Ok(RpDelete::default())),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration as StdDuration;
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use chrono::Utc;
+    use percent_encoding::percent_decode_str;
+    use reqsign::AliyunConfig;
+    use reqsign::AliyunCredential;
+    use reqsign::AliyunLoader;
+    use reqsign::AliyunOssSigner;
+    use wiremock::matchers::body_string;
+    use wiremock::matchers::header;
+    use wiremock::matchers::header_exists;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::OssBackend;
+    use super::OssBuilder;
+    use super::OssCore;
+    use super::OssCredentialLoad;
+    use crate::layers::RetryLayer;
+    use crate::ops::OpBatch;
+    use crate::ops::OpDelete;
+    use crate::ops::OpRead;
+    use crate::ops::OpWrite;
+    use crate::raw::Accessor;
+    use crate::raw::HttpClient;
+    use crate::Builder;
+    use crate::ChecksumAlgorithm;
+    use crate::ChecksumStrategy;
+    use crate::ErrorKind;
+    use crate::Operator;
+    use crate::OperatorBuilder;
+    use crate::Result;
+
+    /// Build operator directly since the bucket will be prepended to the
+    /// endpoint host by builder.
+    fn test_operator(endpoint: &str) -> Result<Operator> {
+        test_operator_with_credential(endpoint, AliyunConfig::default(), None)
+    }
+
+    fn test_operator_with_credential(
+        endpoint: &str,
+        cfg: AliyunConfig,
+        customed_credential_loader: Option<Box<dyn OssCredentialLoad>>,
+    ) -> Result<Operator> {
+        let backend = test_backend(endpoint, cfg, customed_credential_loader)?;
+        Ok(OperatorBuilder::new(backend).finish())
+    }
+
+    fn test_backend(
+        endpoint: &str,
+        cfg: AliyunConfig,
+        customed_credential_loader: Option<Box<dyn OssCredentialLoad>>,
+    ) -> Result<OssBackend> {
+        let client = HttpClient::new()?;
+        Ok(OssBackend {
+            core: Arc::new(OssCore {
+                root: "/".to_string(),
+                bucket: "bucket".to_string(),
+                host: "bucket.oss".to_string(),
+                endpoint: endpoint.to_string(),
+                presign_endpoint: endpoint.to_string(),
+                enable_append_object: true,
+                loader: AliyunLoader::new(client.client(), cfg),
+                signer: AliyunOssSigner::new("bucket"),
+                customed_credential_loader,
+                customed_credential: Mutex::default(),
+                client,
+            }),
+        })
+    }
+
+    fn sts_config() -> AliyunConfig {
+        AliyunConfig {
+            access_key_id: Some("STS.id".to_string()),
+            access_key_secret: Some("secret".to_string()),
+            security_token: Some("sts+token/with=".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_object() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/log"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/log"))
+            .and(query_param("position", "0"))
+            .and(header("content-type", "text/plain"))
+            .and(body_string("Hello"))
+            .respond_with(
+                ResponseTemplate::new(200).insert_header("x-oss-next-append-position", "5"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/log"))
+            .and(query_param("position", "5"))
+            .and(body_string(", OSS"))
+            .respond_with(
+                ResponseTemplate::new(200).insert_header("x-oss-next-append-position", "10"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator(&mock_server.uri())?;
+        let mut w = op
+            .writer_with("log", OpWrite::new().with_content_type("text/plain"))
+            .await?;
+        w.append("Hello").await?;
+        w.append(", OSS").await?;
+        w.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append_existing_object() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/log"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-oss-object-type", "Appendable")
+                    .insert_header("content-length", "5"),
+            )
+            .mount(&mock_server)
+            .await;
+        // Object has been appended by others.
+        Mock::given(method("POST"))
+            .and(path("/log"))
+            .and(query_param("position", "5"))
+            .respond_with(
+                ResponseTemplate::new(409)
+                    .insert_header("x-oss-next-append-position", "8")
+                    .set_body_string(
+                        "<Error><Code>PositionNotEqualToLength</Code><Message>Position is not equal to file length</Message></Error>",
+                    ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/log"))
+            .and(query_param("position", "8"))
+            .respond_with(
+                ResponseTemplate::new(200).insert_header("x-oss-next-append-position", "13"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator(&mock_server.uri())?;
+        let mut w = op.writer("log").await?;
+        let err = w.append("Hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PreconditionFailed);
+        assert!(err.is_temporary());
+        // Retry at the refreshed position.
+        w.append("Hello").await?;
+        w.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append_not_appendable_object() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/normal"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-oss-object-type", "Normal")
+                    .insert_header("content-length", "5"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/normal"))
+            .and(query_param("position", "5"))
+            .respond_with(ResponseTemplate::new(409).set_body_string(
+                "<Error><Code>ObjectNotAppendable</Code><Message>The object is not appendable</Message></Error>",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator(&mock_server.uri())?;
+        let mut w = op.writer("normal").await?;
+        let err = w.append("Hello").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert!(!err.is_temporary());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_abort_multipart_upload_retry() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/file"))
+            .and(query_param("uploads", ""))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<InitiateMultipartUploadResult><Bucket>bucket</Bucket><Key>file</Key><UploadId>upload-id</UploadId></InitiateMultipartUploadResult>",
+            ))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/file"))
+            .and(query_param("partNumber", "1"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"etag\""))
+            .mount(&mock_server)
+            .await;
+        // The first abort fails with a temporary error.
+        Mock::given(method("DELETE"))
+            .and(path("/file"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+        // The retried abort must still carry the upload id.
+        Mock::given(method("DELETE"))
+            .and(path("/file"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut backend = test_backend(&mock_server.uri(), AliyunConfig::default(), None)?;
+        Arc::get_mut(&mut backend.core)
+            .expect("core must not be shared")
+            .enable_append_object = false;
+        let op = OperatorBuilder::new(backend)
+            .layer(RetryLayer::new().with_min_delay(StdDuration::from_millis(1)))
+            .finish();
+
+        let mut w = op.writer("file").await?;
+        w.append("Hello").await?;
+        w.abort().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checksum_of_appendable_object() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        // Etag of appendable object looks like md5 but is not.
+        Mock::given(method("HEAD"))
+            .and(path("/log"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"fc7704075bcfc00e9e4cfab1b9ab1772\"")
+                    .insert_header("content-length", "13")
+                    .insert_header("x-oss-object-type", "Appendable"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/log"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("Hello, World!"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator(&mock_server.uri())?;
+        let checksum = op.checksum("log", ChecksumAlgorithm::Md5).await?;
+        assert_eq!(checksum.strategy(), ChecksumStrategy::Computed);
+        assert_eq!(checksum.to_hex(), "65a8e27d8879283831b664bd8b7f0ad4");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_security_token_config() {
+        let builder = OssBuilder::from_map(HashMap::from([
+            ("bucket".to_string(), "bucket".to_string()),
+            ("access_key_id".to_string(), "STS.id".to_string()),
+            ("access_key_secret".to_string(), "secret".to_string()),
+            ("security_token".to_string(), "sts-token".to_string()),
+        ]));
+        assert_eq!(builder.security_token.as_deref(), Some("sts-token"));
+
+        let debug = format!("{builder:?}");
+        assert!(debug.contains("security_token"));
+        assert!(!debug.contains("sts-token"));
+    }
+
+    #[tokio::test]
+    async fn test_security_token() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/file"))
+            .and(header("x-oss-security-token", "sts-token"))
+            .respond_with(ResponseTemplate::new(200).insert_header("content-length", "5"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let cfg = AliyunConfig {
+            access_key_id: Some("STS.id".to_string()),
+            access_key_secret: Some("secret".to_string()),
+            security_token: Some("sts-token".to_string()),
+            ..Default::default()
+        };
+        let op = test_operator_with_credential(&mock_server.uri(), cfg, None)?;
+        let meta = op.stat("file").await?;
+        assert_eq!(meta.content_length(), 5);
+
+        Ok(())
+    }
+
+    /// Credential loader which issues a new token that is going to expire
+    /// at every load.
+    #[derive(Debug, Default)]
+    struct RotatingCredentialLoader {
+        loaded: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl OssCredentialLoad for RotatingCredentialLoader {
+        async fn load_credential(
+            &self,
+            _: reqwest::Client,
+        ) -> anyhow::Result<Option<AliyunCredential>> {
+            let n = self.loaded.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(Some(AliyunCredential {
+                access_key_id: "STS.id".to_string(),
+                access_key_secret: "secret".to_string(),
+                security_token: Some(format!("sts-token-{n}")),
+                expires_in: Some(Utc::now() + Duration::minutes(1)),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_customed_credential_loader() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        for token in ["sts-token-1", "sts-token-2"] {
+            Mock::given(method("HEAD"))
+                .and(path("/file"))
+                .and(header("x-oss-security-token", token))
+                .respond_with(ResponseTemplate::new(200).insert_header("content-length", "5"))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let op = test_operator_with_credential(
+            &mock_server.uri(),
+            AliyunConfig::default(),
+            Some(Box::<RotatingCredentialLoader>::default()),
+        )?;
+        // Credential will be refreshed since it's going to expire.
+        op.stat("file").await?;
+        op.stat("file").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_presign_signature() -> Result<()> {
+        // Token without special chars so that reqsign could sign it.
+        let cfg = AliyunConfig {
+            security_token: Some("token".to_string()),
+            ..sts_config()
+        };
+        let backend = test_backend("https://bucket.oss", cfg, None)?;
+        let signer = AliyunOssSigner::new("bucket");
+        let cred = AliyunCredential {
+            access_key_id: "STS.id".to_string(),
+            access_key_secret: "secret".to_string(),
+            security_token: Some("token".to_string()),
+            expires_in: None,
+        };
+
+        // Retry in case of crossing the second boundary.
+        for _ in 0..3 {
+            let mut expected = http::Request::get("https://bucket.oss/path/to/file")
+                .body(())
+                .expect("must be valid");
+            signer
+                .sign_query(&mut expected, StdDuration::from_secs(3600), &cred)
+                .expect("sign must succeed");
+
+            let mut actual = http::Request::get("https://bucket.oss/path/to/file")
+                .body(())
+                .expect("must be valid");
+            backend
+                .core
+                .sign_query(&mut actual, StdDuration::from_secs(3600))
+                .await?;
+
+            let query = |req: &http::Request<()>| {
+                req.uri()
+                    .query()
+                    .unwrap_or_default()
+                    .split('&')
+                    .map(|v| percent_decode_str(v).decode_utf8_lossy().into_owned())
+                    .collect::<HashSet<_>>()
+            };
+            if query(&expected) == query(&actual) {
+                return Ok(());
             }
+        }
 
-            Ok(RpBatch::new(batched_result))
-        } else {
-            Err(parse_error(resp).await?)
+        panic!("signature mismatched with reqsign")
+    }
+
+    #[tokio::test]
+    async fn test_presign_read() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/file"))
+            .and(query_param("security-token", "sts+token/with="))
+            .and(query_param(
+                "response-content-disposition",
+                "attachment; filename=\"a.txt\"",
+            ))
+            .and(query_param("OSSAccessKeyId", "STS.id"))
+            .and(|req: &wiremock::Request| {
+                let pairs: HashMap<_, _> = req.url.query_pairs().collect();
+                pairs.contains_key("Expires") && pairs.contains_key("Signature")
+            })
+            .respond_with(ResponseTemplate::new(200).set_body_string("Hello, OSS"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator_with_credential(&mock_server.uri(), sts_config(), None)?;
+        let req = op
+            .presign_read_with(
+                "file",
+                OpRead::new().with_override_content_disposition("attachment; filename=\"a.txt\""),
+                StdDuration::from_secs(3600),
+            )
+            .await?;
+        assert!(req.header().get("content-type").is_none());
+
+        // Presigned request could be sent without any credential.
+        let resp = reqwest::get(req.uri().to_string())
+            .await
+            .expect("request must succeed");
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(resp.text().await.expect("must succeed"), "Hello, OSS");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_presign_write() -> Result<()> {
+        let op = test_operator_with_credential("https://bucket.oss", sts_config(), None)?;
+        let req = op
+            .presign_write_with(
+                "file",
+                OpWrite::new().with_content_type("text/plain"),
+                StdDuration::from_secs(3600),
+            )
+            .await?;
+        assert_eq!(req.method(), http::Method::PUT);
+        assert_eq!(req.header()["content-type"], "text/plain");
+        assert!(req.header().get("content-length").is_none());
+        assert!(req
+            .uri()
+            .to_string()
+            .contains("security-token=sts%2Btoken/with%3D"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_delete_in_chunks() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(query_param("delete", ""))
+            .and(header_exists("content-md5"))
+            .and(|req: &wiremock::Request| {
+                String::from_utf8_lossy(&req.body).contains("<Quiet>true</Quiet>")
+            })
+            .respond_with(|req: &wiremock::Request| {
+                let body = String::from_utf8_lossy(&req.body);
+                // Quiet mode only returns the failed keys.
+                let mut resp = String::from("<DeleteResult>");
+                for key in ["denied", "a&amp;b"] {
+                    if body.contains(&format!("<Key>{key}</Key>")) {
+                        resp.push_str(&format!(
+                            "<Error><Key>{key}</Key><Code>AccessDenied</Code><Message>Access denied</Message></Error>"
+                        ));
+                    }
+                }
+                if body.contains("<Key>missing</Key>") {
+                    resp.push_str("<Error><Key>missing</Key><Code>NoSuchKey</Code><Message>Not found</Message></Error>");
+                }
+                resp.push_str("</DeleteResult>");
+                ResponseTemplate::new(200).set_body_raw(resp, "application/xml")
+            })
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let op = test_operator(&mock_server.uri())?;
+        let mut ops: Vec<_> = (0..1000)
+            .map(|i| (format!("file-{i}"), OpDelete::new().into()))
+            .collect();
+        ops.push(("denied".to_string(), OpDelete::new().into()));
+        ops.push(("a&b".to_string(), OpDelete::new().into()));
+        ops.push(("missing".to_string(), OpDelete::new().into()));
+        let rp = op.inner().batch(OpBatch::new(ops)).await?;
+
+        let results = rp.into_results();
+        assert_eq!(results.len(), 1003);
+        for (path, result) in results {
+            if path == "denied" || path == "a&b" {
+                assert_eq!(
+                    result.err().map(|e| e.kind()),
+                    Some(ErrorKind::PermissionDenied),
+                    "{path}"
+                );
+            } else {
+                assert!(result.is_ok(), "{path}");
+            }
         }
+
+        Ok(())
     }
 }
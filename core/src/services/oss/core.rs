@@ -17,27 +17,45 @@
 
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::Duration;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use bytes::Bytes;
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
 use http::header::CACHE_CONTROL;
 use http::header::CONTENT_DISPOSITION;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::header::IF_NONE_MATCH;
 use http::header::RANGE;
+use http::HeaderMap;
+use http::Method;
 use http::Request;
 use http::Response;
+use http::Uri;
+use percent_encoding::percent_decode_str;
 use reqsign::AliyunCredential;
 use reqsign::AliyunLoader;
 use reqsign::AliyunOssSigner;
 use serde::Deserialize;
 use serde::Serialize;
+use sha1::Sha1;
 
+use super::credential::OssCredentialLoad;
+use crate::ops::OpRead;
 use crate::ops::OpWrite;
 use crate::raw::*;
 use crate::*;
 
+pub const X_OSS_NEXT_APPEND_POSITION: &str = "x-oss-next-append-position";
+pub const X_OSS_OBJECT_TYPE: &str = "x-oss-object-type";
+pub const X_OSS_SERVER_SIDE_ENCRYPTION: &str = "x-oss-server-side-encryption";
+
 pub struct OssCore {
     pub root: String,
     pub bucket: String,
@@ -47,9 +65,13 @@ pub struct OssCore {
     pub host: String,
     pub endpoint: String,
     pub presign_endpoint: String,
+    pub enable_append_object: bool,
 
     pub client: HttpClient,
     pub loader: AliyunLoader,
+    pub customed_credential_loader: Option<Box<dyn OssCredentialLoad>>,
+    /// Credential cached from `customed_credential_loader`.
+    pub customed_credential: Mutex<Option<AliyunCredential>>,
     pub signer: AliyunOssSigner,
 }
 
@@ -66,6 +88,28 @@ impl Debug for OssCore {
 
 impl OssCore {
     async fn load_credential(&self) -> Result<Option<AliyunCredential>> {
+        if let Some(loader) = &self.customed_credential_loader {
+            // Return cached credential if it's valid.
+            match self
+                .customed_credential
+                .lock()
+                .expect("lock poisoned")
+                .clone()
+            {
+                Some(cred) if cred.is_valid() => return Ok(Some(cred)),
+                _ => (),
+            }
+
+            let cred = loader
+                .load_credential(self.client.client())
+                .await
+                .map_err(new_request_credential_error)?;
+            if let Some(cred) = cred {
+                *self.customed_credential.lock().expect("lock poisoned") = Some(cred.clone());
+                return Ok(Some(cred));
+            }
+        }
+
         let cred = self
             .loader
             .load()
@@ -89,6 +133,11 @@ impl OssCore {
         self.signer.sign(req, &cred).map_err(new_request_sign_error)
     }
 
+    /// Sign request via query so that it can be sent by any http client
+    /// without credential before expired.
+    ///
+    /// We don't use reqsign here since it doesn't encode query values like
+    /// the security token and response header overrides after signing.
     pub async fn sign_query<T>(&self, req: &mut Request<T>, duration: Duration) -> Result<()> {
         let cred = if let Some(cred) = self.load_credential().await? {
             cred
@@ -96,9 +145,78 @@ impl OssCore {
             return Ok(());
         };
 
-        self.signer
-            .sign_query(req, duration, &cred)
-            .map_err(new_request_sign_error)
+        let expires = Utc::now()
+            + chrono::Duration::from_std(duration).map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "presign expire is out of range").set_source(err)
+            })?;
+        let expires = expires.timestamp().to_string();
+
+        let mut query: Vec<(String, String)> = req
+            .uri()
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                let (k, v) = v.split_once('=').unwrap_or((v, ""));
+                (
+                    percent_decode_str(k).decode_utf8_lossy().into_owned(),
+                    percent_decode_str(v).decode_utf8_lossy().into_owned(),
+                )
+            })
+            .collect();
+        if let Some(token) = &cred.security_token {
+            query.push(("security-token".to_string(), token.clone()));
+        }
+
+        let path = percent_decode_str(req.uri().path())
+            .decode_utf8_lossy()
+            .into_owned();
+        let string_to_sign = build_string_to_sign(
+            req.method(),
+            req.headers(),
+            &expires,
+            &format!("/{}{}", self.bucket, path),
+            &query,
+        );
+
+        let mut mac =
+            Hmac::<Sha1>::new_from_slice(cred.access_key_secret.as_bytes()).map_err(|err| {
+                Error::new(ErrorKind::ConfigInvalid, "access key secret is invalid").set_source(err)
+            })?;
+        mac.update(string_to_sign.as_bytes());
+        let signature = BASE64_STANDARD.encode(mac.finalize().into_bytes());
+
+        query.push(("OSSAccessKeyId".to_string(), cred.access_key_id));
+        query.push(("Expires".to_string(), expires));
+        query.push(("Signature".to_string(), signature));
+
+        let query = query
+            .iter()
+            .map(|(k, v)| {
+                if v.is_empty() {
+                    percent_encode_path(k)
+                } else {
+                    format!("{}={}", percent_encode_path(k), percent_encode_path(v))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        let uri = format!(
+            "{}://{}{}?{}",
+            req.uri().scheme_str().unwrap_or("https"),
+            req.uri()
+                .authority()
+                .map(|v| v.as_str())
+                .unwrap_or_default(),
+            req.uri().path(),
+            query
+        );
+        *req.uri_mut() = Uri::from_str(&uri).map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "presigned uri is invalid").set_source(err)
+        })?;
+
+        Ok(())
     }
 
     #[inline]
@@ -125,7 +243,53 @@ impl OssCore {
 
         let mut req = Request::put(&url);
 
-        req = req.header(CONTENT_LENGTH, size.unwrap_or_default());
+        // Content length of presigned requests will be set by the sender.
+        if !is_presign {
+            req = req.header(CONTENT_LENGTH, size.unwrap_or_default());
+        }
+
+        if let Some(mime) = content_type {
+            req = req.header(CONTENT_TYPE, mime);
+        }
+
+        if let Some(pos) = content_disposition {
+            req = req.header(CONTENT_DISPOSITION, pos);
+        }
+
+        if let Some(cache_control) = cache_control {
+            req = req.header(CACHE_CONTROL, cache_control)
+        }
+
+        let req = req.body(body).map_err(new_request_build_error)?;
+        Ok(req)
+    }
+
+    /// Creates a request that appends data at `position` via AppendObject.
+    ///
+    /// The object will be created as an appendable object if `position` is 0.
+    #[allow(clippy::too_many_arguments)]
+    pub fn oss_append_object_request(
+        &self,
+        path: &str,
+        position: u64,
+        size: usize,
+        content_type: Option<&str>,
+        content_disposition: Option<&str>,
+        cache_control: Option<&str>,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let endpoint = self.get_endpoint(false);
+        let url = format!(
+            "{}/{}?append&position={}",
+            endpoint,
+            percent_encode_path(&p),
+            position
+        );
+
+        let mut req = Request::post(&url);
+
+        req = req.header(CONTENT_LENGTH, size);
 
         if let Some(mime) = content_type {
             req = req.header(CONTENT_TYPE, mime);
@@ -146,17 +310,41 @@ impl OssCore {
     pub fn oss_get_object_request(
         &self,
         path: &str,
-        range: BytesRange,
         is_presign: bool,
-        if_none_match: Option<&str>,
+        args: &OpRead,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
         let endpoint = self.get_endpoint(is_presign);
-        let url = format!("{}/{}", endpoint, percent_encode_path(&p));
+        let mut url = format!("{}/{}", endpoint, percent_encode_path(&p));
+
+        // Response header overrides are only used by presigned requests
+        // since they only make sense for browsers.
+        if is_presign {
+            let mut query_args = Vec::new();
+            if let Some(override_content_disposition) = args.override_content_disposition() {
+                query_args.push(format!(
+                    "response-content-disposition={}",
+                    percent_encode_path(override_content_disposition)
+                ))
+            }
+            if let Some(override_cache_control) = args.override_cache_control() {
+                query_args.push(format!(
+                    "response-cache-control={}",
+                    percent_encode_path(override_cache_control)
+                ))
+            }
+            if !query_args.is_empty() {
+                url.push_str(&format!("?{}", query_args.join("&")));
+            }
+        }
 
         let mut req = Request::get(&url);
-        req = req.header(CONTENT_TYPE, "application/octet-stream");
+        // Presigned requests could be sent by browsers without content type.
+        if !is_presign {
+            req = req.header(CONTENT_TYPE, "application/octet-stream");
+        }
 
+        let range = args.range();
         if !range.is_full() {
             req = req.header(RANGE, range.to_header());
             // Adding `x-oss-range-behavior` header to use standard behavior.
@@ -164,7 +352,7 @@ impl OssCore {
             req = req.header("x-oss-range-behavior", "standard");
         }
 
-        if let Some(if_none_match) = if_none_match {
+        if let Some(if_none_match) = args.if_none_match() {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
@@ -238,10 +426,9 @@ impl OssCore {
     pub async fn oss_get_object(
         &self,
         path: &str,
-        range: BytesRange,
-        if_none_match: Option<&str>,
+        args: &OpRead,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.oss_get_object_request(path, range, false, if_none_match)?;
+        let mut req = self.oss_get_object_request(path, false, args)?;
 
         self.sign(&mut req).await?;
         self.send(req).await
@@ -324,19 +511,24 @@ impl OssCore {
         self.send(req).await
     }
 
+    /// Delete objects via DeleteMultipleObjects in quiet mode, so that
+    /// only the failed keys will be returned.
+    ///
+    /// At most 1000 keys could be deleted at once.
     pub async fn oss_delete_objects(
         &self,
-        paths: Vec<String>,
+        paths: &[String],
     ) -> Result<Response<IncomingAsyncBody>> {
         let url = format!("{}/?delete", self.endpoint);
 
         let req = Request::post(&url);
 
         let content = quick_xml::se::to_string(&DeleteObjectsRequest {
+            quiet: true,
             object: paths
-                .into_iter()
+                .iter()
                 .map(|path| DeleteObjectsRequestObject {
-                    key: build_abs_path(&self.root, &path),
+                    key: build_abs_path(&self.root, path),
                 })
                 .collect(),
         })
@@ -472,12 +664,120 @@ impl OssCore {
         self.sign(&mut req).await?;
         self.send(req).await
     }
+
+    /// Abort an ongoing multipart upload, parts that have been uploaded
+    /// will be removed.
+    pub async fn oss_abort_multipart_upload(
+        &self,
+        path: &str,
+        upload_id: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let endpoint = self.get_endpoint(false);
+        let url = format!(
+            "{}/{}?uploadId={}",
+            endpoint,
+            percent_encode_path(&p),
+            percent_encode_path(upload_id)
+        );
+
+        let mut req = Request::delete(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+        self.send(req).await
+    }
+}
+
+/// Sub resources that must be included in the canonicalized resource.
+///
+/// Only the ones used by us are listed here.
+const SUB_RESOURCES: &[&str] = &[
+    "append",
+    "position",
+    "response-cache-control",
+    "response-content-disposition",
+    "response-content-type",
+    "security-token",
+    "uploadId",
+    "partNumber",
+    "versionId",
+];
+
+/// Build string to sign of the OSS V1 signature.
+///
+/// Reference: [Add signatures to URLs](https://www.alibabacloud.com/help/en/object-storage-service/latest/add-signatures-to-urls)
+pub fn build_string_to_sign(
+    method: &Method,
+    headers: &HeaderMap,
+    expires: &str,
+    resource: &str,
+    query: &[(String, String)],
+) -> String {
+    let header = |k: &str| {
+        headers
+            .get(k)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    };
+
+    let mut oss_headers: Vec<(String, &str)> = headers
+        .iter()
+        .filter(|(k, _)| k.as_str().starts_with("x-oss-"))
+        .map(|(k, v)| (k.as_str().to_lowercase(), v.to_str().unwrap_or_default()))
+        .collect();
+    oss_headers.sort();
+
+    let mut sub_resources: Vec<&(String, String)> = query
+        .iter()
+        .filter(|(k, _)| SUB_RESOURCES.contains(&k.as_str()))
+        .collect();
+    sub_resources.sort();
+
+    let mut s = format!(
+        "{}\n{}\n{}\n{}\n",
+        method,
+        header("content-md5"),
+        header("content-type"),
+        expires
+    );
+    for (k, v) in oss_headers {
+        s.push_str(&format!("{k}:{v}\n"));
+    }
+    s.push_str(resource);
+    for (i, (k, v)) in sub_resources.into_iter().enumerate() {
+        s.push(if i == 0 { '?' } else { '&' });
+        s.push_str(k);
+        if !v.is_empty() {
+            s.push('=');
+            s.push_str(v);
+        }
+    }
+    s
+}
+
+/// Parse oss response headers into metadata.
+pub fn parse_into_oss_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata> {
+    let mut m = parse_into_metadata(path, headers)?;
+
+    // Etags of appendable objects and objects encrypted by KMS are not md5
+    // of content.
+    let header = |k: &str| headers.get(k).and_then(|v| v.to_str().ok());
+    if header(X_OSS_OBJECT_TYPE) == Some("Appendable")
+        || header(X_OSS_SERVER_SIDE_ENCRYPTION) == Some("KMS")
+    {
+        m.set_etag_opaque();
+    }
+
+    Ok(m)
 }
 
 /// Request of DeleteObjects.
 #[derive(Default, Debug, Serialize)]
 #[serde(default, rename = "Delete", rename_all = "PascalCase")]
 pub struct DeleteObjectsRequest {
+    pub quiet: bool,
     pub object: Vec<DeleteObjectsRequestObject>,
 }
 
@@ -492,6 +792,7 @@ pub struct DeleteObjectsRequestObject {
 #[serde(default, rename = "DeleteResult", rename_all = "PascalCase")]
 pub struct DeleteObjectsResult {
     pub deleted: Vec<DeleteObjectsResultDeleted>,
+    pub error: Vec<DeleteObjectsResultError>,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -554,6 +855,7 @@ mod tests {
     #[test]
     fn test_serialize_delete_objects_request() {
         let req = DeleteObjectsRequest {
+            quiet: true,
             object: vec![
                 DeleteObjectsRequestObject {
                     key: "multipart.data".to_string(),
@@ -572,6 +874,7 @@ mod tests {
         pretty_assertions::assert_eq!(
             actual,
             r#"<Delete>
+  <Quiet>true</Quiet>
   <Object>
     <Key>multipart.data</Key>
   </Object>
@@ -587,6 +890,45 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_serialize_delete_objects_request_with_escaped_key() {
+        let req = DeleteObjectsRequest {
+            quiet: true,
+            object: vec![DeleteObjectsRequestObject {
+                key: "a&b<c>.txt".to_string(),
+            }],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            "<Delete><Quiet>true</Quiet><Object><Key>a&amp;b&lt;c&gt;.txt</Key></Object></Delete>"
+        )
+    }
+
+    #[test]
+    fn test_deserialize_delete_objects_result_with_error() {
+        let bs = Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<DeleteResult>
+    <Error>
+       <Key>a&amp;b.txt</Key>
+       <Code>AccessDenied</Code>
+       <Message>Access denied</Message>
+    </Error>
+</DeleteResult>"#,
+        );
+
+        let out: DeleteObjectsResult =
+            quick_xml::de::from_reader(bs.reader()).expect("must success");
+
+        assert!(out.deleted.is_empty());
+        assert_eq!(out.error.len(), 1);
+        assert_eq!(out.error[0].key, "a&b.txt");
+        assert_eq!(out.error[0].code, "AccessDenied");
+    }
+
     /// This example is from https://www.alibabacloud.com/help/zh/object-storage-service/latest/deletemultipleobjects
     #[test]
     fn test_deserialize_delete_objects_result() {
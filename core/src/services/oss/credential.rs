@@ -0,0 +1,37 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use reqsign::AliyunCredential;
+
+/// Customized credential loader for OSS.
+///
+/// Implement it to provide temporary credentials (like STS tokens) that
+/// need to be refreshed. The returned credential will be cached until it's
+/// going to expire according to its `expires_in`, and `load_credential`
+/// will be called again to refresh it.
+#[async_trait]
+pub trait OssCredentialLoad: 'static + Send + Sync + Debug {
+    /// Load credential, returning `None` to fallback to the static and
+    /// env credentials.
+    async fn load_credential(
+        &self,
+        client: reqwest::Client,
+    ) -> anyhow::Result<Option<AliyunCredential>>;
+}
@@ -21,6 +21,7 @@ use http::StatusCode;
 use quick_xml::de;
 use serde::Deserialize;
 
+use super::core::DeleteObjectsResultError;
 use crate::raw::*;
 use crate::Error;
 use crate::ErrorKind;
@@ -41,10 +42,23 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (parts, body) = resp.into_parts();
     let bs = body.bytes().await?;
 
+    let oss_err = de::from_reader::<_, OssError>(bs.clone().reader()).ok();
+
     let (kind, retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED => (ErrorKind::PreconditionFailed, false),
+        // Conditional reads like `If-None-Match` will get `304 Not Modified`.
+        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
+            (ErrorKind::PreconditionFailed, false)
+        }
+        StatusCode::CONFLICT => match oss_err.as_ref().map(|v| v.code.as_str()) {
+            // Append to an object which is not an appendable object.
+            Some("ObjectNotAppendable") => (ErrorKind::Unsupported, false),
+            // Append position is not equal to the object length, it's
+            // retryable after refreshing the position.
+            Some("PositionNotEqualToLength") => (ErrorKind::PreconditionFailed, true),
+            _ => (ErrorKind::Unexpected, false),
+        },
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -52,9 +66,9 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
         _ => (ErrorKind::Unexpected, false),
     };
 
-    let message = match de::from_reader::<_, OssError>(bs.clone().reader()) {
-        Ok(oss_err) => format!("{oss_err:?}"),
-        Err(_) => String::from_utf8_lossy(&bs).into_owned(),
+    let message = match oss_err {
+        Some(oss_err) => format!("{oss_err:?}"),
+        None => String::from_utf8_lossy(&bs).into_owned(),
     };
 
     let mut err = Error::new(kind, &message).with_context("response", format!("{parts:?}"));
@@ -66,6 +80,16 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     Ok(err)
 }
 
+/// Parse error of a key in the DeleteMultipleObjects response into Error.
+pub fn parse_delete_objects_result_error(err: DeleteObjectsResultError) -> Error {
+    let kind = match err.code.as_str() {
+        "AccessDenied" => ErrorKind::PermissionDenied,
+        _ => ErrorKind::Unexpected,
+    };
+
+    Error::new(kind, &format!("{err:?}")).with_context("key", &err.key)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
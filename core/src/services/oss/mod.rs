@@ -19,6 +19,8 @@ mod backend;
 pub use backend::OssBuilder as Oss;
 
 mod core;
+mod credential;
+pub use credential::OssCredentialLoad;
 mod error;
 mod pager;
 mod writer;
@@ -19,6 +19,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use http::HeaderMap;
 use http::StatusCode;
 
 use super::core::*;
@@ -34,6 +35,12 @@ pub struct OssWriter {
     path: String,
     upload_id: Option<String>,
     parts: Vec<MultipartUploadPart>,
+
+    /// The position to append next data, `None` means the position has
+    /// not been loaded yet.
+    ///
+    /// Only used while `enable_append_object` is set.
+    position: Option<u64>,
 }
 
 impl OssWriter {
@@ -44,10 +51,115 @@ impl OssWriter {
             path,
             upload_id,
             parts: vec![],
+            position: None,
+        }
+    }
+
+    /// Load the position to append from the existing object.
+    ///
+    /// Appending to a not existing object starts from 0 and will create
+    /// an appendable object.
+    async fn load_position(&self) -> Result<u64> {
+        let resp = self.core.oss_head_object(&self.path, None).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let size = parse_content_length(resp.headers())?.unwrap_or_default();
+                resp.into_body().consume().await?;
+                Ok(size)
+            }
+            StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(0)
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn append_object(&mut self, bs: Bytes) -> Result<()> {
+        let position = match self.position {
+            Some(position) => position,
+            None => {
+                let position = self.load_position().await?;
+                self.position = Some(position);
+                position
+            }
+        };
+
+        // Object metadata can only be set while creating the object.
+        let (content_type, content_disposition, cache_control) = if position == 0 {
+            (
+                self.op.content_type(),
+                self.op.content_disposition(),
+                self.op.cache_control(),
+            )
+        } else {
+            (None, None, None)
+        };
+
+        let size = bs.len();
+        let mut req = self.core.oss_append_object_request(
+            &self.path,
+            position,
+            size,
+            content_type,
+            content_disposition,
+            cache_control,
+            AsyncBody::Bytes(bs),
+        )?;
+
+        self.core.sign(&mut req).await?;
+
+        let resp = self.core.send(req).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => {
+                let next =
+                    parse_next_append_position(resp.headers())?.unwrap_or(position + size as u64);
+                self.position = Some(next);
+
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => {
+                // OSS returns the current length of object while position
+                // is not equal to it, refresh the position so that the
+                // append can be retried.
+                if let Some(next) = parse_next_append_position(resp.headers())? {
+                    self.position = Some(next);
+                }
+
+                Err(parse_error(resp)
+                    .await?
+                    .with_operation("Writer::append")
+                    .with_context("path", &self.path)
+                    .with_context("position", position.to_string()))
+            }
         }
     }
 }
 
+fn parse_next_append_position(headers: &HeaderMap) -> Result<Option<u64>> {
+    match headers.get(X_OSS_NEXT_APPEND_POSITION) {
+        Some(v) => v
+            .to_str()
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Some)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unexpected,
+                    "header x-oss-next-append-position is not a valid integer",
+                )
+            }),
+        None => Ok(None),
+    }
+}
+
 #[async_trait]
 impl oio::Write for OssWriter {
     async fn write(&mut self, bs: Bytes) -> Result<()> {
@@ -77,6 +189,10 @@ impl oio::Write for OssWriter {
     }
 
     async fn append(&mut self, bs: Bytes) -> Result<()> {
+        if self.core.enable_append_object {
+            return self.append_object(bs).await;
+        }
+
         let upload_id = self.upload_id.as_ref().expect(
             "Writer doesn't have upload id, but users trying to call append, must be buggy",
         );
@@ -116,10 +232,27 @@ impl oio::Write for OssWriter {
     }
 
     async fn abort(&mut self) -> Result<()> {
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support abort",
-        ))
+        // Keep the upload id until abort succeeds so that a retried abort
+        // will send the request again.
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id,
+            None => return Ok(()),
+        };
+
+        let resp = self
+            .core
+            .oss_abort_multipart_upload(&self.path, upload_id)
+            .await?;
+        match resp.status() {
+            // oss returns code 204 if abort succeeds.
+            StatusCode::NO_CONTENT => {
+                resp.into_body().consume().await?;
+                self.upload_id = None;
+                self.parts.clear();
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
     }
 
     async fn close(&mut self) -> Result<()> {
@@ -23,13 +23,20 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use http::Uri;
+use redis::aio::ConnectionLike;
 use redis::aio::ConnectionManager;
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
 use redis::AsyncCommands;
 use redis::Client;
+use redis::Cmd;
 use redis::ConnectionAddr;
 use redis::ConnectionInfo;
+use redis::Pipeline;
 use redis::RedisConnectionInfo;
 use redis::RedisError;
+use redis::RedisFuture;
+use redis::Value;
 use tokio::sync::OnceCell;
 
 use crate::raw::adapters::kv;
@@ -48,7 +55,7 @@ const DEFAULT_REDIS_PORT: u16 = 6379;
 /// - [x] read
 /// - [x] write
 /// - [ ] ~~list~~
-/// - [ ] scan
+/// - [x] scan
 /// - [ ] ~~presign~~
 /// - [ ] blocking
 ///
@@ -56,12 +63,24 @@ const DEFAULT_REDIS_PORT: u16 = 6379;
 ///
 /// - `root`: Set the working directory of `OpenDAL`
 /// - `endpoint`: Set the network address of redis server
+/// - `cluster_endpoints`: Set the network addresses of redis cluster nodes, separated by `,`
 /// - `username`: Set the username of Redis
 /// - `password`: Set the password for authentication
 /// - `db`: Set the DB of redis
+/// - `default_ttl`: Set the default ttl (in seconds) for written keys
+/// - `chunk_size`: Split values larger than this size (in bytes) into chunks
 ///
 /// You can refer to [`RedisBuilder`]'s docs for more information
 ///
+/// # Cluster
+///
+/// By default, redis service talks to a single node. Set `cluster_endpoints`
+/// to enable cluster mode, in which `MOVED` and `ASK` redirections will be
+/// followed automatically. `SCAN` will be sent to every master node and the
+/// results will be merged.
+///
+/// Redis cluster only supports db `0`.
+///
 /// # Example
 ///
 /// ## Via Builder
@@ -88,6 +107,10 @@ pub struct RedisBuilder {
     ///
     /// default is "tcp://127.0.0.1:6379"
     endpoint: Option<String>,
+    /// network addresses of the Redis cluster nodes, e.g. ["tcp://127.0.0.1:6379", "tcp://127.0.0.1:6380"]
+    ///
+    /// default is empty, which means cluster mode is disabled.
+    cluster_endpoints: Vec<String>,
     /// the username to connect redis service.
     ///
     /// default is None
@@ -106,6 +129,10 @@ pub struct RedisBuilder {
     db: i64,
     /// The default ttl for put operations.
     default_ttl: Option<Duration>,
+    /// The chunk size of large values.
+    ///
+    /// default is None, which means values will never be chunked.
+    chunk_size: Option<usize>,
 }
 
 impl Debug for RedisBuilder {
@@ -116,6 +143,9 @@ impl Debug for RedisBuilder {
         if let Some(endpoint) = self.endpoint.clone() {
             ds.field("endpoint", &endpoint);
         }
+        if !self.cluster_endpoints.is_empty() {
+            ds.field("cluster_endpoints", &self.cluster_endpoints);
+        }
         if let Some(username) = self.username.clone() {
             ds.field("username", &username);
         }
@@ -140,6 +170,23 @@ impl RedisBuilder {
         self
     }
 
+    /// set the network addresses of redis cluster nodes, separated by `,`.
+    ///
+    /// Cluster mode will be enabled once any endpoint is set. This function
+    /// can be called multiple times to add more endpoints.
+    ///
+    /// The supported schemes are the same as [`RedisBuilder::endpoint`].
+    pub fn cluster_endpoints(&mut self, cluster_endpoints: &str) -> &mut Self {
+        self.cluster_endpoints.extend(
+            cluster_endpoints
+                .split(',')
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string()),
+        );
+        self
+    }
+
     /// set the username for redis
     ///
     /// default: no username
@@ -170,12 +217,26 @@ impl RedisBuilder {
 
     /// Set the default ttl for redis services.
     ///
-    /// If set, we will specify `EX` for write operations.
+    /// If set, we will specify `EX` (or `PX` if ttl is not whole seconds)
+    /// for write operations, and keys will be removed by redis after
+    /// expired. Reading or stating an expired key will return `NotFound`.
+    ///
+    /// ttl must be at least 1 millisecond.
     pub fn default_ttl(&mut self, ttl: Duration) -> &mut Self {
         self.default_ttl = Some(ttl);
         self
     }
 
+    /// Set the chunk size for redis services.
+    ///
+    /// Values larger than chunk size will be split into `key//part/N` keys
+    /// with a small manifest stored at `key`, so that we don't create huge
+    /// values in redis.
+    pub fn chunk_size(&mut self, chunk_size: usize) -> &mut Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
     /// set the working directory, all operations will be performed under it.
     ///
     /// default: "/"
@@ -196,49 +257,39 @@ impl Builder for RedisBuilder {
 
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("cluster_endpoints")
+            .map(|v| builder.cluster_endpoints(v));
         map.get("username").map(|v| builder.username(v));
         map.get("password").map(|v| builder.password(v));
         map.get("db")
             .map(|v| v.parse::<i64>().map(|v| builder.db(v)));
+        map.get("chunk_size")
+            .map(|v| v.parse::<usize>().map(|v| builder.chunk_size(v)));
+        map.get("default_ttl").map(|v| {
+            v.parse::<u64>()
+                .map(|v| builder.default_ttl(Duration::from_secs(v)))
+        });
 
         builder
     }
 
     fn build(&mut self) -> Result<Self::Accessor> {
-        let endpoint = self
-            .endpoint
-            .clone()
-            .unwrap_or_else(|| DEFAULT_REDIS_ENDPOINT.to_string());
-
-        let ep_url = endpoint.parse::<Uri>().map_err(|e| {
-            Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
-                .with_context("service", Scheme::Redis)
-                .with_context("endpoint", endpoint)
-                .set_source(e)
-        })?;
-
-        let con_addr = match ep_url.scheme_str() {
-            Some("tcp") | Some("redis") | None => {
-                let host = ep_url
-                    .host()
-                    .map(|h| h.to_string())
-                    .unwrap_or_else(|| "127.0.0.1".to_string());
-                let port = ep_url.port_u16().unwrap_or(DEFAULT_REDIS_PORT);
-                ConnectionAddr::Tcp(host, port)
-            }
-            // TODO: wait for upstream to support `rustls` based TLS connection.
-            Some("unix") | Some("redis+unix") => {
-                let path = PathBuf::from(ep_url.path());
-                ConnectionAddr::Unix(path)
-            }
-            Some(s) => {
+        if let Some(ttl) = self.default_ttl {
+            if ttl.as_millis() == 0 {
                 return Err(
-                    Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
+                    Error::new(ErrorKind::ConfigInvalid, "default_ttl is too small")
                         .with_context("service", Scheme::Redis)
-                        .with_context("scheme", s),
-                )
+                        .with_context("default_ttl", format!("{ttl:?}")),
+                );
             }
-        };
+        }
+
+        let root = normalize_root(
+            self.root
+                .clone()
+                .unwrap_or_else(|| "/".to_string())
+                .as_str(),
+        );
 
         let redis_info = RedisConnectionInfo {
             db: self.db,
@@ -246,43 +297,166 @@ impl Builder for RedisBuilder {
             password: self.password.clone(),
         };
 
+        if !self.cluster_endpoints.is_empty() {
+            if self.endpoint.is_some() {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "endpoint and cluster_endpoints can't be set at the same time",
+                )
+                .with_context("service", Scheme::Redis));
+            }
+            if self.db != 0 {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "redis cluster only supports db 0",
+                )
+                .with_context("service", Scheme::Redis)
+                .with_context("db", self.db.to_string()));
+            }
+
+            let nodes = self
+                .cluster_endpoints
+                .iter()
+                .map(|endpoint| {
+                    Ok(ConnectionInfo {
+                        addr: parse_endpoint(endpoint)?,
+                        redis: redis_info.clone(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let client = ClusterClient::new(nodes).map_err(|e| {
+                Error::new(ErrorKind::ConfigInvalid, "invalid cluster endpoints")
+                    .with_context("service", Scheme::Redis)
+                    .with_context("cluster_endpoints", self.cluster_endpoints.join(","))
+                    .set_source(e)
+            })?;
+
+            return Ok(RedisBackend::new(Adapter {
+                addr: self.cluster_endpoints.join(","),
+                redis_info,
+                client: RedisClient::Cluster(client),
+                conn: OnceCell::new(),
+                default_ttl: self.default_ttl,
+            })
+            .with_root(&root)
+            .with_chunk_size(self.chunk_size.unwrap_or_default()));
+        }
+
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REDIS_ENDPOINT.to_string());
+
         let con_info = ConnectionInfo {
-            addr: con_addr,
-            redis: redis_info,
+            addr: parse_endpoint(&endpoint)?,
+            redis: redis_info.clone(),
         };
 
         let client = Client::open(con_info).map_err(|e| {
             Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
                 .with_context("service", Scheme::Redis)
-                .with_context("endpoint", self.endpoint.as_ref().unwrap())
+                .with_context("endpoint", &endpoint)
                 .with_context("db", self.db.to_string())
                 .set_source(e)
         })?;
 
-        let root = normalize_root(
-            self.root
-                .clone()
-                .unwrap_or_else(|| "/".to_string())
-                .as_str(),
-        );
-
-        let conn = OnceCell::new();
         Ok(RedisBackend::new(Adapter {
-            client,
-            conn,
+            addr: client.get_connection_info().addr.to_string(),
+            redis_info,
+            client: RedisClient::Single(client),
+            conn: OnceCell::new(),
             default_ttl: self.default_ttl,
         })
-        .with_root(&root))
+        .with_root(&root)
+        .with_chunk_size(self.chunk_size.unwrap_or_default()))
+    }
+}
+
+/// Parse endpoint into redis connection address.
+fn parse_endpoint(endpoint: &str) -> Result<ConnectionAddr> {
+    let ep_url = endpoint.parse::<Uri>().map_err(|e| {
+        Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
+            .with_context("service", Scheme::Redis)
+            .with_context("endpoint", endpoint)
+            .set_source(e)
+    })?;
+
+    match ep_url.scheme_str() {
+        Some("tcp") | Some("redis") | None => {
+            let host = ep_url
+                .host()
+                .map(|h| h.to_string())
+                .unwrap_or_else(|| "127.0.0.1".to_string());
+            let port = ep_url.port_u16().unwrap_or(DEFAULT_REDIS_PORT);
+            Ok(ConnectionAddr::Tcp(host, port))
+        }
+        // TODO: wait for upstream to support `rustls` based TLS connection.
+        Some("unix") | Some("redis+unix") => {
+            let path = PathBuf::from(ep_url.path());
+            Ok(ConnectionAddr::Unix(path))
+        }
+        Some(s) => Err(
+            Error::new(ErrorKind::ConfigInvalid, "invalid or unsupported scheme")
+                .with_context("service", Scheme::Redis)
+                .with_context("scheme", s),
+        ),
     }
 }
 
 /// Backend for redis services.
 pub type RedisBackend = kv::Backend<Adapter>;
 
+#[derive(Clone)]
+enum RedisClient {
+    Single(Client),
+    Cluster(ClusterClient),
+}
+
+/// RedisConnection is the connection of single node or cluster.
+///
+/// Both of them implement [`ConnectionLike`] so that we can send the same
+/// commands via them.
+#[derive(Clone)]
+enum RedisConnection {
+    Single(ConnectionManager),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Adapter {
-    client: Client,
-    conn: OnceCell<ConnectionManager>,
+    addr: String,
+    redis_info: RedisConnectionInfo,
+    client: RedisClient,
+    conn: OnceCell<RedisConnection>,
 
     default_ttl: Option<Duration>,
 }
@@ -292,22 +466,110 @@ impl Debug for Adapter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut ds = f.debug_struct("Adapter");
 
-        let info = self.client.get_connection_info();
-        ds.field("addr", &info.addr);
-        ds.field("db", &info.redis.db);
-        ds.field("user", &info.redis.username);
+        ds.field("addr", &self.addr);
+        ds.field("db", &self.redis_info.db);
+        ds.field("user", &self.redis_info.username);
+        ds.field("cluster", &matches!(self.client, RedisClient::Cluster(_)));
         ds.finish()
     }
 }
 
 impl Adapter {
-    async fn conn(&self) -> Result<ConnectionManager> {
+    async fn conn(&self) -> Result<RedisConnection> {
         Ok(self
             .conn
-            .get_or_try_init(|| async { ConnectionManager::new(self.client.clone()).await })
+            .get_or_try_init(|| async {
+                match &self.client {
+                    RedisClient::Single(client) => ConnectionManager::new(client.clone())
+                        .await
+                        .map(RedisConnection::Single),
+                    RedisClient::Cluster(client) => client
+                        .get_async_connection()
+                        .await
+                        .map(RedisConnection::Cluster),
+                }
+            })
             .await?
             .clone())
     }
+
+    /// Scan all keys that match given pattern on all master nodes of the
+    /// cluster.
+    ///
+    /// `SCAN` only iterates keys of the node that receives it, so we fetch
+    /// the masters via `CLUSTER SLOTS` and scan them one by one.
+    async fn cluster_scan(&self, pattern: &str) -> Result<Vec<String>> {
+        let mut conn = self.conn().await?;
+        let slots: Value = redis::cmd("CLUSTER")
+            .arg("SLOTS")
+            .query_async(&mut conn)
+            .await?;
+
+        let mut keys = Vec::new();
+        for addr in parse_cluster_masters(slots)? {
+            let client = Client::open(ConnectionInfo {
+                addr,
+                redis: self.redis_info.clone(),
+            })?;
+            let mut node_conn = client.get_multiplexed_async_connection().await?;
+
+            let mut iter = node_conn.scan_match::<_, String>(pattern).await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+/// Parse the response of `CLUSTER SLOTS` into addresses of master nodes.
+///
+/// Every slot range is returned as `[start, end, [ip, port, id], replicas...]`.
+fn parse_cluster_masters(slots: Value) -> Result<Vec<ConnectionAddr>> {
+    let invalid = || Error::new(ErrorKind::Unexpected, "invalid response of CLUSTER SLOTS");
+
+    let ranges = match slots {
+        Value::Bulk(v) => v,
+        _ => return Err(invalid()),
+    };
+
+    let mut masters = Vec::new();
+    for range in ranges {
+        let master = match range {
+            Value::Bulk(mut v) if v.len() >= 3 => v.swap_remove(2),
+            _ => return Err(invalid()),
+        };
+        let (ip, port) = match master {
+            Value::Bulk(v) => match (v.get(0), v.get(1)) {
+                (Some(Value::Data(ip)), Some(Value::Int(port))) => {
+                    (String::from_utf8_lossy(ip).to_string(), *port as u16)
+                }
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        };
+
+        let addr = ConnectionAddr::Tcp(ip, port);
+        if !masters.contains(&addr) {
+            masters.push(addr);
+        }
+    }
+
+    Ok(masters)
+}
+
+/// Escape the glob-style special chars in key so that we can use it as
+/// the prefix of `MATCH` pattern.
+fn escape_pattern(key: &str) -> String {
+    let mut s = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
 }
 
 #[async_trait]
@@ -315,8 +577,8 @@ impl kv::Adapter for Adapter {
     fn metadata(&self) -> kv::Metadata {
         kv::Metadata::new(
             Scheme::Redis,
-            &self.client.get_connection_info().addr.to_string(),
-            AccessorCapability::Read | AccessorCapability::Write,
+            &self.addr,
+            AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::Scan,
         )
     }
 
@@ -329,7 +591,10 @@ impl kv::Adapter for Adapter {
     async fn set(&self, key: &str, value: &[u8]) -> Result<()> {
         let mut conn = self.conn().await?;
         match self.default_ttl {
-            Some(ttl) => conn.set_ex(key, value, ttl.as_secs() as usize).await?,
+            Some(ttl) if ttl.subsec_nanos() == 0 => {
+                conn.set_ex(key, value, ttl.as_secs() as usize).await?
+            }
+            Some(ttl) => conn.pset_ex(key, value, ttl.as_millis() as usize).await?,
             None => conn.set(key, value).await?,
         }
         Ok(())
@@ -346,6 +611,23 @@ impl kv::Adapter for Adapter {
         conn.append(key, value).await?;
         Ok(())
     }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        let pattern = format!("{}*", escape_pattern(path));
+
+        if let RedisClient::Cluster(_) = self.client {
+            return self.cluster_scan(&pattern).await;
+        }
+
+        let mut conn = self.conn().await?;
+        let mut iter = conn.scan_match::<_, String>(pattern).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+
+        Ok(keys)
+    }
 }
 
 impl From<RedisError> for Error {
@@ -353,3 +635,100 @@ impl From<RedisError> for Error {
         Error::new(ErrorKind::Unexpected, e.category()).set_source(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_pattern() {
+        assert_eq!(escape_pattern("abc/def/"), "abc/def/");
+        assert_eq!(escape_pattern("a*b?c[d]e\\"), "a\\*b\\?c\\[d\\]e\\\\");
+    }
+
+    #[test]
+    fn test_parse_cluster_masters() {
+        let node = |ip: &str, port: i64| {
+            Value::Bulk(vec![
+                Value::Data(ip.as_bytes().to_vec()),
+                Value::Int(port),
+                Value::Data(b"id".to_vec()),
+            ])
+        };
+        let slots = Value::Bulk(vec![
+            Value::Bulk(vec![
+                Value::Int(0),
+                Value::Int(5460),
+                node("127.0.0.1", 7000),
+                node("127.0.0.1", 7003),
+            ]),
+            Value::Bulk(vec![
+                Value::Int(5461),
+                Value::Int(10922),
+                node("127.0.0.1", 7001),
+            ]),
+            Value::Bulk(vec![
+                Value::Int(10923),
+                Value::Int(16383),
+                node("127.0.0.1", 7000),
+            ]),
+        ]);
+
+        let masters = parse_cluster_masters(slots).expect("must succeed");
+        assert_eq!(
+            masters,
+            vec![
+                ConnectionAddr::Tcp("127.0.0.1".to_string(), 7000),
+                ConnectionAddr::Tcp("127.0.0.1".to_string(), 7001),
+            ]
+        );
+
+        assert!(parse_cluster_masters(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_build_cluster() {
+        let mut builder = RedisBuilder::default();
+        builder
+            .cluster_endpoints("tcp://127.0.0.1:7000,tcp://127.0.0.1:7001")
+            .cluster_endpoints("127.0.0.1:7002");
+        assert_eq!(builder.cluster_endpoints.len(), 3);
+        assert!(builder.build().is_ok());
+
+        let mut builder = RedisBuilder::default();
+        builder.cluster_endpoints("tcp://127.0.0.1:7000").db(1);
+        assert_eq!(
+            builder.build().unwrap_err().kind(),
+            ErrorKind::ConfigInvalid
+        );
+
+        let mut builder = RedisBuilder::default();
+        builder
+            .endpoint("tcp://127.0.0.1:6379")
+            .cluster_endpoints("tcp://127.0.0.1:7000");
+        assert_eq!(
+            builder.build().unwrap_err().kind(),
+            ErrorKind::ConfigInvalid
+        );
+    }
+
+    #[test]
+    fn test_build_default_ttl() {
+        let mut builder = RedisBuilder::default();
+        builder.default_ttl(Duration::from_millis(1500));
+        assert!(builder.build().is_ok());
+
+        let mut builder = RedisBuilder::default();
+        builder.default_ttl(Duration::from_micros(10));
+        assert_eq!(
+            builder.build().unwrap_err().kind(),
+            ErrorKind::ConfigInvalid
+        );
+
+        let builder = RedisBuilder::from_map(HashMap::from([(
+            "default_ttl".to_string(),
+            "60".to_string(),
+        )]));
+        assert_eq!(builder.default_ttl, Some(Duration::from_secs(60)));
+    }
+}
@@ -18,10 +18,19 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
 
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use rocksdb::BoundColumnFamily;
+use rocksdb::Direction;
+use rocksdb::IteratorMode;
+use rocksdb::Options;
 use rocksdb::DB;
+use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
 
 use crate::raw::adapters::kv;
 use crate::raw::*;
@@ -36,13 +45,20 @@ use crate::*;
 ///
 /// - [x] read
 /// - [x] write
-/// - [ ] ~~list~~
-/// - [ ] scan
+/// - [x] list
+/// - [x] scan
 /// - [ ] ~~presign~~
 /// - [x] blocking
 ///
 /// # Note
 ///
+/// Operators that open the same `datadir` in one process will share the
+/// same rocksdb instance. Use [`RocksdbBuilder::column_family`] to isolate
+/// their namespaces.
+///
+/// Rocksdb only allows one process to open the db at the same time, open
+/// a db that used by other process will return [`ErrorKind::ConfigInvalid`].
+///
 /// OpenDAL will build rocksdb from source by default.
 ///
 /// To link with existing rocksdb lib, please set one of the following:
@@ -59,6 +75,8 @@ use crate::*;
 ///
 /// - `root`: Set the working directory of `OpenDAL`
 /// - `datadir`: Set the path to the rocksdb data directory
+/// - `column_family`: Set the column family to use, default to `default`
+/// - `create_missing_column_family`: Create the column family if it's missing
 ///
 /// You can refer to [`RocksdbBuilder`]'s docs for more information
 ///
@@ -90,6 +108,12 @@ pub struct RocksdbBuilder {
     ///
     /// default is "/"
     root: Option<String>,
+    /// The column family to use.
+    ///
+    /// default is "default"
+    column_family: Option<String>,
+    /// Create the column family if it's missing.
+    create_missing_column_family: bool,
 }
 
 impl RocksdbBuilder {
@@ -108,6 +132,24 @@ impl RocksdbBuilder {
         }
         self
     }
+
+    /// Set the column family to use, all keys will be stored in it.
+    ///
+    /// default: "default"
+    pub fn column_family(&mut self, column_family: &str) -> &mut Self {
+        if !column_family.is_empty() {
+            self.column_family = Some(column_family.to_owned());
+        }
+        self
+    }
+
+    /// Create the column family if it doesn't exist.
+    ///
+    /// Building will fail on missing column family if this is not enabled.
+    pub fn create_missing_column_family(&mut self) -> &mut Self {
+        self.create_missing_column_family = true;
+        self
+    }
 }
 
 impl Builder for RocksdbBuilder {
@@ -118,6 +160,11 @@ impl Builder for RocksdbBuilder {
         let mut builder = RocksdbBuilder::default();
 
         map.get("datadir").map(|v| builder.datadir(v));
+        map.get("root").map(|v| builder.root(v));
+        map.get("column_family").map(|v| builder.column_family(v));
+        map.get("create_missing_column_family")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.create_missing_column_family());
 
         builder
     }
@@ -127,15 +174,77 @@ impl Builder for RocksdbBuilder {
             Error::new(ErrorKind::ConfigInvalid, "datadir is required but not set")
                 .with_context("service", Scheme::Rocksdb)
         })?;
-        let db = DB::open_default(&path).map_err(|e| {
-            Error::new(ErrorKind::ConfigInvalid, "open default transaction db")
-                .with_context("service", Scheme::Rocksdb)
-                .with_context("datadir", path)
-                .set_source(e)
-        })?;
+        let db = open_db(&path)?;
+
+        let cf = self
+            .column_family
+            .take()
+            .unwrap_or_else(|| DEFAULT_COLUMN_FAMILY_NAME.to_string());
+        if db.cf_handle(&cf).is_none() {
+            if !self.create_missing_column_family {
+                return Err(
+                    Error::new(ErrorKind::ConfigInvalid, "column family doesn't exist")
+                        .with_context("service", Scheme::Rocksdb)
+                        .with_context("datadir", path)
+                        .with_context("column_family", cf),
+                );
+            }
+
+            db.create_cf(&cf, &Options::default()).map_err(|e| {
+                Error::new(ErrorKind::Unexpected, "create column family")
+                    .with_context("service", Scheme::Rocksdb)
+                    .with_context("datadir", &path)
+                    .with_context("column_family", &cf)
+                    .set_source(e)
+            })?;
+        }
+
+        Ok(RocksdbBackend::new(Adapter { db, cf })
+            .with_root(self.root.as_deref().unwrap_or_default()))
+    }
+}
 
-        Ok(RocksdbBackend::new(Adapter { db: Arc::new(db) }))
+/// Opened rocksdb instances in this process, indexed by their canonical path.
+///
+/// Rocksdb holds a lock on the datadir, so we must share the same instance
+/// instead of opening it again.
+static DBS: Lazy<Mutex<HashMap<PathBuf, Weak<DB>>>> = Lazy::new(Mutex::default);
+
+fn open_db(path: &str) -> Result<Arc<DB>> {
+    let mut dbs = DBS.lock().expect("lock must succeed");
+
+    if let Ok(key) = std::fs::canonicalize(path) {
+        if let Some(db) = dbs.get(&key).and_then(|db| db.upgrade()) {
+            return Ok(db);
+        }
     }
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    // Column families must be opened all together, default cf will be
+    // added by rocksdb.
+    let cfs = DB::list_cf(&opts, path).unwrap_or_default();
+    let db = DB::open_cf(&opts, path, cfs).map_err(|e| {
+        Error::new(
+            ErrorKind::ConfigInvalid,
+            "open rocksdb failed, is it opened by another process?",
+        )
+        .with_context("service", Scheme::Rocksdb)
+        .with_context("datadir", path)
+        .set_source(e)
+    })?;
+    let db = Arc::new(db);
+
+    let key = std::fs::canonicalize(path).map_err(|e| {
+        Error::new(ErrorKind::Unexpected, "canonicalize datadir")
+            .with_context("service", Scheme::Rocksdb)
+            .with_context("datadir", path)
+            .set_source(e)
+    })?;
+    dbs.retain(|_, db| db.strong_count() > 0);
+    dbs.insert(key, Arc::downgrade(&db));
+
+    Ok(db)
 }
 
 /// Backend for rocksdb services.
@@ -144,12 +253,24 @@ pub type RocksdbBackend = kv::Backend<Adapter>;
 #[derive(Clone)]
 pub struct Adapter {
     db: Arc<DB>,
+    cf: String,
+}
+
+impl Adapter {
+    fn cf_handle(&self) -> Result<Arc<BoundColumnFamily<'_>>> {
+        self.db.cf_handle(&self.cf).ok_or_else(|| {
+            Error::new(ErrorKind::Unexpected, "column family has been dropped")
+                .with_context("service", Scheme::Rocksdb)
+                .with_context("column_family", &self.cf)
+        })
+    }
 }
 
 impl Debug for Adapter {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut ds = f.debug_struct("Adapter");
         ds.field("path", &self.db.path());
+        ds.field("column_family", &self.cf);
         ds.finish()
     }
 }
@@ -160,7 +281,10 @@ impl kv::Adapter for Adapter {
         kv::Metadata::new(
             Scheme::Rocksdb,
             &self.db.path().to_string_lossy(),
-            AccessorCapability::Read | AccessorCapability::Write,
+            AccessorCapability::Read
+                | AccessorCapability::Write
+                | AccessorCapability::Scan
+                | AccessorCapability::Blocking,
         )
     }
 
@@ -169,7 +293,7 @@ impl kv::Adapter for Adapter {
     }
 
     fn blocking_get(&self, path: &str) -> Result<Option<Vec<u8>>> {
-        Ok(self.db.get(path)?)
+        Ok(self.db.get_cf(&self.cf_handle()?, path)?)
     }
 
     async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
@@ -177,7 +301,7 @@ impl kv::Adapter for Adapter {
     }
 
     fn blocking_set(&self, path: &str, value: &[u8]) -> Result<()> {
-        Ok(self.db.put(path, value)?)
+        Ok(self.db.put_cf(&self.cf_handle()?, path, value)?)
     }
 
     async fn delete(&self, path: &str) -> Result<()> {
@@ -185,7 +309,35 @@ impl kv::Adapter for Adapter {
     }
 
     fn blocking_delete(&self, path: &str) -> Result<()> {
-        Ok(self.db.delete(path)?)
+        Ok(self.db.delete_cf(&self.cf_handle()?, path)?)
+    }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        self.blocking_scan(path)
+    }
+
+    /// Keys in rocksdb are sorted, so we seek to the prefix and stop at
+    /// the first key that doesn't start with it.
+    fn blocking_scan(&self, path: &str) -> Result<Vec<String>> {
+        let cf = self.cf_handle()?;
+        let it = self
+            .db
+            .iterator_cf(&cf, IteratorMode::From(path.as_bytes(), Direction::Forward));
+        let mut res = Vec::default();
+
+        for i in it {
+            let (key, _) = i?;
+            if !key.starts_with(path.as_bytes()) {
+                break;
+            }
+
+            res.push(String::from_utf8(key.to_vec()).map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "store key is not valid utf-8 string")
+                    .set_source(err)
+            })?);
+        }
+
+        Ok(res)
     }
 }
 
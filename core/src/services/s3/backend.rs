@@ -19,6 +19,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -35,6 +36,7 @@ use reqsign::AwsCredentialLoad;
 use reqsign::AwsLoader;
 use reqsign::AwsV4Signer;
 
+use super::core::ChecksumAlgorithm;
 use super::core::*;
 use super::error::parse_error;
 use super::pager::S3Pager;
@@ -54,6 +56,17 @@ static ENDPOINT_TEMPLATES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new
     m
 });
 
+/// Allow constructing correct dualstack region endpoint if user gives a global endpoint.
+static DUALSTACK_ENDPOINT_TEMPLATES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut m = HashMap::new();
+    // AWS S3 Service.
+    m.insert(
+        "https://s3.amazonaws.com",
+        "https://s3.dualstack.{region}.amazonaws.com",
+    );
+    m
+});
+
 /// Aws S3 and compatible services (including minio, digitalocean space and so on) support
 ///
 /// # Capabilities
@@ -85,9 +98,65 @@ static ENDPOINT_TEMPLATES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new
 /// - `server_side_encryption_customer_key_md5`: Set the server_side_encryption_customer_key_md5 for backend.
 /// - `disable_config_load`: Disable aws config load from env
 /// - `enable_virtual_host_style`: Enable virtual host style.
+/// - `enable_accelerate`: Enable transfer acceleration via `s3-accelerate` endpoint.
+/// - `enable_dualstack`: Enable dualstack (IPv4 and IPv6) endpoint.
+/// - `enable_request_payer`: Enable requester pays for requests.
+/// - `enable_list_objects_v1`: Use ListObjects (v1) instead of ListObjectsV2 for listing.
+/// - `checksum_algorithm`: Set the checksum algorithm (`none`, `crc32c`, `sha1` or `sha256`) for uploads.
+/// - `role_arn`: Set the role_arn to assume for backend.
+/// - `external_id`: Set the external_id used while assuming role.
+/// - `role_session_name`: Set the role_session_name used while assuming role.
+/// - `web_identity_token_file`: Set the web identity token file for backend.
+/// - `disable_web_identity`: Disable loading credential via web identity.
 ///
 /// Refer to [`S3Builder`]'s public API docs for more information.
 ///
+/// # Credentials
+///
+/// OpenDAL will try to load credential from the following sources in order,
+/// the first one that returns a valid credential wins:
+///
+/// 1. Explicit keys: `access_key_id`, `secret_access_key` and `security_token`.
+///    Envs like `AWS_ACCESS_KEY_ID` and profiles in `~/.aws/config` are loaded
+///    into the same place unless `disable_config_load` is set.
+/// 2. Web identity: `AssumeRoleWithWebIdentity` with `role_arn` and
+///    `web_identity_token_file` (`AWS_ROLE_ARN` and `AWS_WEB_IDENTITY_TOKEN_FILE`
+///    for IRSA on EKS). Disable it via `disable_web_identity`.
+/// 3. Assume role: `AssumeRole` with `role_arn`, `external_id` and
+///    `role_session_name`.
+/// 4. EC2 instance metadata (IMDSv2). Disable it via `disable_ec2_metadata`.
+///
+/// Credentials returned by STS and IMDS will be refreshed automatically
+/// before they expire.
+///
+/// # Archived objects
+///
+/// `stat` returns the object's storage class via [`Metadata::storage_class`]
+/// and the `x-amz-restore` status via [`Metadata::restore_status`].
+///
+/// Reading an archived object that has not been restored returns
+/// [`ErrorKind::Archived`]. Users can restore it via `S3Backend::restore`
+/// on the backend built by [`S3Builder`]:
+///
+/// ```no_run
+/// # use anyhow::Result;
+/// use opendal::services::S3;
+/// use opendal::Builder;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let mut builder = S3::default();
+/// builder.bucket("test");
+///
+/// let backend = builder.build()?;
+/// backend.restore("path/to/file", 7, "Standard").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Storage class of written objects can be set via `default_storage_class`
+/// or per write via [`OpWrite::with_storage_class`].
+///
 /// # Temporary security credentials
 ///
 /// OpenDAL now provides support for S3 temporary security credentials in IAM.
@@ -285,6 +354,8 @@ pub struct S3Builder {
     region: Option<String>,
     role_arn: Option<String>,
     external_id: Option<String>,
+    role_session_name: Option<String>,
+    web_identity_token_file: Option<String>,
     access_key_id: Option<String>,
     secret_access_key: Option<String>,
     server_side_encryption: Option<String>,
@@ -299,7 +370,13 @@ pub struct S3Builder {
 
     disable_config_load: bool,
     disable_ec2_metadata: bool,
+    disable_web_identity: bool,
     enable_virtual_host_style: bool,
+    enable_accelerate: bool,
+    enable_dualstack: bool,
+    enable_request_payer: bool,
+    enable_list_objects_v1: bool,
+    checksum_algorithm: Option<String>,
 
     http_client: Option<HttpClient>,
     customed_credential_load: Option<Box<dyn AwsCredentialLoad>>,
@@ -312,7 +389,8 @@ impl Debug for S3Builder {
         d.field("root", &self.root)
             .field("bucket", &self.bucket)
             .field("endpoint", &self.endpoint)
-            .field("region", &self.region);
+            .field("region", &self.region)
+            .field("enable_request_payer", &self.enable_request_payer);
 
         d.finish_non_exhaustive()
     }
@@ -396,6 +474,10 @@ impl S3Builder {
     }
 
     /// Set role_arn for this backend.
+    ///
+    /// If `role_arn` is set, OpenDAL will assume this role via STS
+    /// (or via web identity if `web_identity_token_file` is available)
+    /// to get temporary credentials.
     pub fn role_arn(&mut self, v: &str) -> &mut Self {
         if !v.is_empty() {
             self.role_arn = Some(v.to_string())
@@ -413,6 +495,29 @@ impl S3Builder {
         self
     }
 
+    /// Set role_session_name used while assuming role.
+    ///
+    /// Default to `reqsign`.
+    pub fn role_session_name(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.role_session_name = Some(v.to_string())
+        }
+
+        self
+    }
+
+    /// Set web identity token file for this backend.
+    ///
+    /// The token in this file will be exchanged with `role_arn` for
+    /// temporary credentials via `AssumeRoleWithWebIdentity`.
+    pub fn web_identity_token_file(&mut self, v: &str) -> &mut Self {
+        if !v.is_empty() {
+            self.web_identity_token_file = Some(v.to_string())
+        }
+
+        self
+    }
+
     /// Set default storage_class for this backend.
     ///
     /// Available values:
@@ -501,6 +606,9 @@ impl S3Builder {
     /// `v`: base64 encoded key that matches algorithm specified in
     /// `server_side_encryption_customer_algorithm`.
     ///
+    /// The key md5 will be calculated from this key while building if
+    /// `server_side_encryption_customer_key_md5` is not set.
+    ///
     /// # Note
     ///
     /// This function is the low-level setting for SSE related features.
@@ -619,6 +727,16 @@ impl S3Builder {
         self
     }
 
+    /// Disable load credential via web identity.
+    ///
+    /// This option is used to ignore `web_identity_token_file` (including
+    /// the one loaded from env `AWS_WEB_IDENTITY_TOKEN_FILE`) so that
+    /// opendal will not call `AssumeRoleWithWebIdentity`.
+    pub fn disable_web_identity(&mut self) -> &mut Self {
+        self.disable_web_identity = true;
+        self
+    }
+
     /// Enable virtual host style so that opendal will send API requests
     /// in virtual host style instead of path style.
     ///
@@ -629,6 +747,74 @@ impl S3Builder {
         self
     }
 
+    /// Enable requester pays so that opendal will send
+    /// `x-amz-request-payer: requester` with every request.
+    ///
+    /// This is required to access buckets that have requester pays enabled,
+    /// otherwise S3 will reject requests with `403 Forbidden`.
+    pub fn enable_request_payer(&mut self) -> &mut Self {
+        self.enable_request_payer = true;
+        self
+    }
+
+    /// Enable transfer acceleration so that opendal will send requests to
+    /// `{bucket}.s3-accelerate.amazonaws.com`.
+    ///
+    /// - Acceleration must be enabled on the bucket first.
+    /// - Acceleration always uses virtual host style, so bucket name can't
+    ///   contain dot(.) character.
+    /// - Acceleration can't be used with a custom `endpoint`.
+    /// - If `enable_dualstack` is also set, opendal will use
+    ///   `{bucket}.s3-accelerate.dualstack.amazonaws.com` instead.
+    ///
+    /// `region` is still used for signing, so please make sure it's the
+    /// bucket's region.
+    pub fn enable_accelerate(&mut self) -> &mut Self {
+        self.enable_accelerate = true;
+        self
+    }
+
+    /// Enable dualstack endpoint so that opendal can access s3 via both
+    /// IPv4 and IPv6, for example `s3.dualstack.{region}.amazonaws.com`.
+    ///
+    /// Only works for aws s3's global endpoint and has no effect on other
+    /// custom endpoints.
+    pub fn enable_dualstack(&mut self) -> &mut Self {
+        self.enable_dualstack = true;
+        self
+    }
+
+    /// Enable ListObjects (v1) so that opendal will list objects via
+    /// the legacy API which paginates by `marker`.
+    ///
+    /// Some s3 compatible services (like older ceph rgw) don't implement
+    /// ListObjectsV2, enable this option for them.
+    pub fn enable_list_objects_v1(&mut self) -> &mut Self {
+        self.enable_list_objects_v1 = true;
+        self
+    }
+
+    /// Set checksum algorithm for this backend.
+    ///
+    /// Available values:
+    /// - `none`
+    /// - `crc32c`
+    /// - `sha1`
+    /// - `sha256`
+    ///
+    /// If set, opendal will calculate the checksum for every `PutObject`
+    /// and `UploadPart`, and s3 will validate them server-side. `stat`
+    /// and `read` will also return the checksum in metadata.
+    pub fn checksum_algorithm(&mut self, v: &str) -> &mut Self {
+        self.checksum_algorithm = if v.is_empty() || v.eq_ignore_ascii_case("none") {
+            None
+        } else {
+            Some(v.to_string())
+        };
+
+        self
+    }
+
     /// Adding a customed credential load for service.
     pub fn customed_credential_load(&mut self, cred: Box<dyn AwsCredentialLoad>) -> &mut Self {
         self.customed_credential_load = Some(cred);
@@ -656,7 +842,7 @@ impl S3Builder {
         // If enable virtual host style, `bucket` will reside in domain part,
         // for example `https://bucket_name.s3.us-east-1.amazonaws.com`,
         // so `bucket` with dot can't be recognized correctly for this format.
-        if self.enable_virtual_host_style && self.bucket.contains('.') {
+        if (self.enable_virtual_host_style || self.enable_accelerate) && self.bucket.contains('.') {
             return false;
         }
         true
@@ -670,6 +856,15 @@ impl S3Builder {
             self.bucket.as_str()
         };
 
+        // Transfer acceleration only supports virtual host style.
+        if self.enable_accelerate {
+            return if self.enable_dualstack {
+                format!("https://{bucket}.s3-accelerate.dualstack.amazonaws.com")
+            } else {
+                format!("https://{bucket}.s3-accelerate.amazonaws.com")
+            };
+        }
+
         let mut endpoint = match &self.endpoint {
             Some(endpoint) => {
                 if endpoint.starts_with("http") {
@@ -686,7 +881,12 @@ impl S3Builder {
         endpoint = endpoint.replace(&format!("//{bucket}."), "//");
 
         // Update with endpoint templates.
-        endpoint = if let Some(template) = ENDPOINT_TEMPLATES.get(endpoint.as_str()) {
+        let templates = if self.enable_dualstack {
+            &DUALSTACK_ENDPOINT_TEMPLATES
+        } else {
+            &ENDPOINT_TEMPLATES
+        };
+        endpoint = if let Some(template) = templates.get(endpoint.as_str()) {
             template.replace("{region}", region)
         } else {
             // If we don't know where about this endpoint, just leave
@@ -722,6 +922,10 @@ impl Builder for S3Builder {
         map.get("security_token").map(|v| builder.security_token(v));
         map.get("role_arn").map(|v| builder.role_arn(v));
         map.get("external_id").map(|v| builder.external_id(v));
+        map.get("role_session_name")
+            .map(|v| builder.role_session_name(v));
+        map.get("web_identity_token_file")
+            .map(|v| builder.web_identity_token_file(v));
         map.get("server_side_encryption")
             .map(|v| builder.server_side_encryption(v));
         map.get("server_side_encryption_aws_kms_key_id")
@@ -738,11 +942,28 @@ impl Builder for S3Builder {
         map.get("disable_ec2_metadata")
             .filter(|v| *v == "on" || *v == "true")
             .map(|_| builder.disable_ec2_metadata());
+        map.get("disable_web_identity")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_web_identity());
         map.get("enable_virtual_host_style")
             .filter(|v| *v == "on" || *v == "true")
             .map(|_| builder.enable_virtual_host_style());
+        map.get("enable_accelerate")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_accelerate());
+        map.get("enable_dualstack")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_dualstack());
+        map.get("enable_request_payer")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_request_payer());
+        map.get("enable_list_objects_v1")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.enable_list_objects_v1());
         map.get("default_storage_class")
             .map(|v| builder.default_storage_class(v));
+        map.get("checksum_algorithm")
+            .map(|v| builder.checksum_algorithm(v));
 
         builder
     }
@@ -764,6 +985,15 @@ impl Builder for S3Builder {
         }?;
         debug!("backend use bucket {}", &bucket);
 
+        if self.enable_accelerate && self.endpoint.is_some() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "enable_accelerate can't be used with a custom endpoint",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::S3));
+        }
+
         let default_storage_class = match &self.default_storage_class {
             None => None,
             Some(v) => Some(
@@ -771,6 +1001,14 @@ impl Builder for S3Builder {
             ),
         };
 
+        let checksum_algorithm = match &self.checksum_algorithm {
+            None => None,
+            Some(v) => Some(
+                ChecksumAlgorithm::from_str(v)
+                    .map_err(|err| err.with_context("service", Scheme::S3))?,
+            ),
+        };
+
         let server_side_encryption = match &self.server_side_encryption {
             None => None,
             Some(v) => Some(
@@ -803,6 +1041,23 @@ impl Builder for S3Builder {
                 })?),
             };
 
+        // Calculate the key md5 from the base64 encoded key if it's not set,
+        // S3 will reject requests that carry key without key md5.
+        if self.server_side_encryption_customer_key_md5.is_none() {
+            if let Some(key) = &self.server_side_encryption_customer_key {
+                let key = BASE64_STANDARD.decode(key).map_err(|err| {
+                    Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "server_side_encryption_customer_key is not valid base64",
+                    )
+                    .with_context("service", Scheme::S3)
+                    .set_source(err)
+                })?;
+                self.server_side_encryption_customer_key_md5 =
+                    Some(BASE64_STANDARD.encode(Md5::digest(key).as_slice()));
+            }
+        }
+
         let server_side_encryption_customer_key_md5 =
             match &self.server_side_encryption_customer_key_md5 {
                 None => None,
@@ -845,6 +1100,15 @@ impl Builder for S3Builder {
         if let Some(v) = self.external_id.take() {
             cfg.external_id = Some(v)
         }
+        if let Some(v) = self.role_session_name.take() {
+            cfg.role_session_name = v
+        }
+        if let Some(v) = self.web_identity_token_file.take() {
+            cfg.web_identity_token_file = Some(v)
+        }
+        if self.disable_web_identity {
+            cfg.web_identity_token_file = None
+        }
 
         if cfg.region.is_none() {
             // region is required to make signer work.
@@ -883,6 +1147,9 @@ impl Builder for S3Builder {
                 server_side_encryption_customer_key,
                 server_side_encryption_customer_key_md5,
                 default_storage_class,
+                enable_request_payer: self.enable_request_payer,
+                enable_list_objects_v1: self.enable_list_objects_v1,
+                checksum_algorithm,
                 signer,
                 loader,
                 client,
@@ -897,6 +1164,31 @@ pub struct S3Backend {
     core: Arc<S3Core>,
 }
 
+impl S3Backend {
+    /// Restore an archived object (for example, in `GLACIER` or
+    /// `DEEP_ARCHIVE` storage class) so that it can be read for `days` days.
+    ///
+    /// `tier` is the retrieval tier like `Expedited`, `Standard` or `Bulk`.
+    ///
+    /// The restore status can be checked via [`Metadata::restore_status`]
+    /// returned by `stat`.
+    pub async fn restore(&self, path: &str, days: u32, tier: &str) -> Result<()> {
+        let resp = self.core.s3_restore_object(path, days, tier).await?;
+
+        let status = resp.status();
+
+        match status {
+            // 202 means the restore request is accepted, while 200 means
+            // the object has been restored already.
+            StatusCode::OK | StatusCode::ACCEPTED => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+}
+
 #[async_trait]
 impl Accessor for S3Backend {
     type Reader = IncomingAsyncBody;
@@ -913,18 +1205,44 @@ impl Accessor for S3Backend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::S3)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.bucket)
             .set_max_batch_operations(1000)
-            .set_capabilities(Read | Write | List | Scan | Presign | Batch | Copy)
-            .set_hints(ReadStreamable);
+            .set_capabilities(
+                Read | Write
+                    | List
+                    | Scan
+                    | Presign
+                    | Batch
+                    | Copy
+                    | WriteWithContentType
+                    | ReadWithIfMatch
+                    | ReadWithIfNoneMatch
+                    | ReadWithOverrideCacheControl
+                    | ReadWithOverrideContentDisposition
+                    | StatWithIfMatch
+                    | StatWithIfNoneMatch
+                    | WriteWithContentDisposition
+                    | WriteWithCacheControl
+                    | ListWithLimit
+                    | ReadWithVersion
+                    | StatWithVersion
+                    | DeleteWithVersion
+                    | ListWithVersions
+                    | WriteWithStorageClass,
+            )
+            .set_hints(ReadStreamable | ImplicitDir);
 
         am
     }
 
     async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
-        let mut req =
-            self.core
-                .s3_put_object_request(path, Some(0), None, None, None, AsyncBody::Empty)?;
+        let mut req = self.core.s3_put_object_request(
+            path,
+            Some(0),
+            &OpWrite::default(),
+            AsyncBody::Empty,
+        )?;
 
         self.core.sign(&mut req).await?;
 
@@ -942,16 +1260,13 @@ impl Accessor for S3Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
-        let resp = self
-            .core
-            .s3_get_object(path, args.range(), args.if_none_match(), args.if_match())
-            .await?;
+        let resp = self.core.s3_get_object(path, &args).await?;
 
         let status = resp.status();
 
         match status {
             StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
-                let meta = parse_into_metadata(path, resp.headers())?;
+                let meta = parse_into_s3_metadata(path, resp.headers())?;
                 Ok((RpRead::with_metadata(meta), resp.into_body()))
             }
             _ => Err(parse_error(resp).await?),
@@ -960,16 +1275,7 @@ impl Accessor for S3Backend {
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
         let upload_id = if args.append() {
-            let resp = self
-                .core
-                .s3_initiate_multipart_upload(
-                    path,
-                    args.content_type(),
-                    args.content_disposition(),
-                    args.cache_control(),
-                    args.if_match(),
-                )
-                .await?;
+            let resp = self.core.s3_initiate_multipart_upload(path, &args).await?;
 
             let status = resp.status();
 
@@ -1020,22 +1326,19 @@ impl Accessor for S3Backend {
 
         let resp = self
             .core
-            .s3_head_object(path, args.if_none_match(), args.if_match())
+            .s3_head_object(path, args.if_none_match(), args.if_match(), args.version())
             .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
-            }
+            StatusCode::OK => parse_into_s3_metadata(path, resp.headers()).map(RpStat::new),
             _ => Err(parse_error(resp).await?),
         }
     }
 
-    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
-        let resp = self.core.s3_delete_object(path).await?;
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        let resp = self.core.s3_delete_object(path, args.version()).await?;
 
         let status = resp.status();
 
@@ -1046,10 +1349,12 @@ impl Accessor for S3Backend {
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
-        Ok((
-            RpList::default(),
-            S3Pager::new(self.core.clone(), path, "/", args.limit()),
-        ))
+        let mut pager = S3Pager::new(self.core.clone(), path, "/", args.limit());
+        if args.versions() {
+            pager = pager.with_versions();
+        }
+
+        Ok((RpList::default(), pager))
     }
 
     async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
@@ -1062,21 +1367,16 @@ impl Accessor for S3Backend {
     async fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
         // We will not send this request out, just for signing.
         let mut req = match args.operation() {
-            PresignOperation::Stat(v) => {
-                self.core
-                    .s3_head_object_request(path, v.if_none_match(), v.if_match())?
-            }
-            PresignOperation::Read(v) => self.core.s3_get_object_request(
+            PresignOperation::Stat(v) => self.core.s3_head_object_request(
                 path,
-                v.range(),
-                v.override_content_disposition(),
-                v.override_cache_control(),
                 v.if_none_match(),
                 v.if_match(),
+                v.version(),
             )?,
-            PresignOperation::Write(_) => {
+            PresignOperation::Read(v) => self.core.s3_get_object_request(path, v)?,
+            PresignOperation::Write(v) => {
                 self.core
-                    .s3_put_object_request(path, None, None, None, None, AsyncBody::Empty)?
+                    .s3_put_object_request(path, None, v, AsyncBody::Empty)?
             }
         };
 
@@ -1102,7 +1402,12 @@ impl Accessor for S3Backend {
             .with_context("length", ops.len().to_string()));
         }
 
-        let paths = ops.into_iter().map(|(p, _)| p).collect();
+        let paths = ops
+            .into_iter()
+            .map(|(p, op)| match op {
+                BatchOperation::Delete(op) => (p, op),
+            })
+            .collect();
 
         let resp = self.core.s3_delete_objects(paths).await?;
 
@@ -1138,7 +1443,23 @@ impl Accessor for S3Backend {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use futures::TryStreamExt;
+    use http::HeaderValue;
+    use wiremock::matchers::any;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::matchers::query_param_is_missing;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
     use super::*;
+    use crate::layers::RetryLayer;
+    use crate::Operator;
 
     #[test]
     fn test_is_valid_bucket() {
@@ -1161,6 +1482,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_map_credential_options() {
+        let b = S3Builder::from_map(HashMap::from([
+            (
+                "role_arn".to_string(),
+                "arn:aws:iam::123:role/test".to_string(),
+            ),
+            ("external_id".to_string(), "external".to_string()),
+            ("role_session_name".to_string(), "opendal".to_string()),
+            (
+                "web_identity_token_file".to_string(),
+                "/var/run/token".to_string(),
+            ),
+            ("disable_web_identity".to_string(), "true".to_string()),
+        ]));
+
+        assert_eq!(b.role_arn.as_deref(), Some("arn:aws:iam::123:role/test"));
+        assert_eq!(b.external_id.as_deref(), Some("external"));
+        assert_eq!(b.role_session_name.as_deref(), Some("opendal"));
+        assert_eq!(b.web_identity_token_file.as_deref(), Some("/var/run/token"));
+        assert!(b.disable_web_identity);
+    }
+
     #[test]
     fn test_build_endpoint() {
         let _ = env_logger::try_init();
@@ -1194,5 +1538,635 @@ mod tests {
             let endpoint = b.build_endpoint("us-east-2");
             assert_eq!(endpoint, "https://test.s3.us-east-2.amazonaws.com");
         }
+
+        let mut b = S3Builder::default();
+        b.bucket("test").enable_dualstack();
+        let endpoint = b.build_endpoint("us-east-2");
+        assert_eq!(
+            endpoint,
+            "https://s3.dualstack.us-east-2.amazonaws.com/test"
+        );
+    }
+
+    #[test]
+    fn test_accelerate() {
+        let mut b = S3Builder::default();
+        b.bucket("test").enable_accelerate();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://test.s3-accelerate.amazonaws.com"
+        );
+
+        b.enable_dualstack();
+        assert_eq!(
+            b.build_endpoint("us-east-2"),
+            "https://test.s3-accelerate.dualstack.amazonaws.com"
+        );
+
+        let mut b = S3Builder::default();
+        b.bucket("test.with.dot").enable_accelerate();
+        assert!(!b.is_bucket_valid());
+
+        let mut b = S3Builder::default();
+        b.bucket("test")
+            .endpoint("https://s3.us-east-2.amazonaws.com")
+            .enable_accelerate();
+        let err = b.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        let b = S3Builder::from_map(
+            [
+                ("bucket".to_string(), "test".to_string()),
+                ("enable_accelerate".to_string(), "true".to_string()),
+                ("enable_dualstack".to_string(), "on".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert!(b.enable_accelerate);
+        assert!(b.enable_dualstack);
+    }
+
+    #[tokio::test]
+    async fn test_request_payer() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        let mocks = [
+            ("HEAD", "/test/file", ResponseTemplate::new(200)),
+            (
+                "GET",
+                "/test/file",
+                ResponseTemplate::new(200).set_body_string("Hello, World!"),
+            ),
+            (
+                "GET",
+                "/test",
+                ResponseTemplate::new(200).set_body_string("<ListBucketResult></ListBucketResult>"),
+            ),
+            ("PUT", "/test/file", ResponseTemplate::new(200)),
+            ("PUT", "/test/copied", ResponseTemplate::new(200)),
+            ("DELETE", "/test/file", ResponseTemplate::new(204)),
+        ];
+        for (m, p, resp) in mocks {
+            Mock::given(method(m))
+                .and(path(p))
+                .and(header("x-amz-request-payer", "requester"))
+                .respond_with(resp)
+                .mount(&mock_server)
+                .await;
+        }
+        // Requester pays buckets reject all requests without the header.
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(403))
+            .with_priority(10)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .disable_config_load()
+            .disable_ec2_metadata()
+            .enable_request_payer();
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "Hello, World!").await?;
+        op.stat("file").await?;
+        assert_eq!(op.read("file").await?, b"Hello, World!");
+        let entries: Vec<_> = op.list("/").await?.try_collect().await?;
+        assert!(entries.is_empty());
+        op.copy("file", "copied").await?;
+        op.delete("file").await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_side_encryption_customer_key_md5() {
+        let key = [0u8; 32];
+
+        let mut builder = S3Builder::default();
+        builder
+            .bucket("test")
+            .region("us-east-1")
+            .server_side_encryption_customer_algorithm("AES256")
+            .server_side_encryption_customer_key(&BASE64_STANDARD.encode(key));
+        let backend = builder.build().expect("build must succeed");
+
+        assert_eq!(
+            backend.core.server_side_encryption_customer_key_md5,
+            Some(
+                HeaderValue::from_str(&BASE64_STANDARD.encode(Md5::digest(key).as_slice()))
+                    .unwrap()
+            )
+        );
+
+        let mut builder = S3Builder::default();
+        builder
+            .bucket("test")
+            .region("us-east-1")
+            .server_side_encryption_customer_key("not base64!");
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[tokio::test]
+    async fn test_server_side_encryption_customer_key_headers() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let key = [1u8; 32];
+        let key_base64 = BASE64_STANDARD.encode(key);
+        let key_md5 = BASE64_STANDARD.encode(Md5::digest(key).as_slice());
+
+        let mock_server = MockServer::start().await;
+        let mocks = [
+            ("HEAD", "/test/file", ResponseTemplate::new(200)),
+            (
+                "GET",
+                "/test/file",
+                ResponseTemplate::new(200).set_body_string("Hello, World!"),
+            ),
+            ("PUT", "/test/file", ResponseTemplate::new(200)),
+        ];
+        for (m, p, resp) in mocks {
+            Mock::given(method(m))
+                .and(path(p))
+                .and(header(
+                    "x-amz-server-side-encryption-customer-algorithm",
+                    "AES256",
+                ))
+                .and(header(
+                    "x-amz-server-side-encryption-customer-key",
+                    key_base64.as_str(),
+                ))
+                .and(header(
+                    "x-amz-server-side-encryption-customer-key-md5",
+                    key_md5.as_str(),
+                ))
+                .respond_with(resp)
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("PUT"))
+            .and(path("/test/copied"))
+            .and(header(
+                "x-amz-copy-source-server-side-encryption-customer-key",
+                key_base64.as_str(),
+            ))
+            .and(header(
+                "x-amz-copy-source-server-side-encryption-customer-key-md5",
+                key_md5.as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+        // S3 returns an opaque 400 if SSE-C headers are missing.
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(400))
+            .with_priority(10)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .disable_config_load()
+            .disable_ec2_metadata()
+            .server_side_encryption_customer_algorithm("AES256")
+            .server_side_encryption_customer_key(&key_base64);
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "Hello, World!").await?;
+        op.stat("file").await?;
+        assert_eq!(op.read("file").await?, b"Hello, World!");
+        op.copy("file", "copied").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_abort_multipart_upload_retry() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/test/file"))
+            .and(query_param("uploads", ""))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "<InitiateMultipartUploadResult><Bucket>test</Bucket><Key>file</Key><UploadId>upload-id</UploadId></InitiateMultipartUploadResult>",
+            ))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/test/file"))
+            .and(query_param("partNumber", "1"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"etag\""))
+            .mount(&mock_server)
+            .await;
+        // The first abort fails with a temporary error.
+        Mock::given(method("DELETE"))
+            .and(path("/test/file"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+        // The retried abort must still carry the upload id.
+        Mock::given(method("DELETE"))
+            .and(path("/test/file"))
+            .and(query_param("uploadId", "upload-id"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .disable_config_load()
+            .disable_ec2_metadata();
+        let op = Operator::new(builder)?
+            .layer(RetryLayer::new().with_min_delay(Duration::from_millis(1)))
+            .finish();
+
+        let mut w = op.writer("file").await?;
+        w.append("Hello, World!").await?;
+        w.abort().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checksum_of_encrypted_object() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        for (name, value) in [
+            ("x-amz-server-side-encryption", "aws:kms"),
+            ("x-amz-server-side-encryption-customer-algorithm", "AES256"),
+        ] {
+            let mock_server = MockServer::start().await;
+            // Etag of encrypted object looks like md5 but is not.
+            Mock::given(method("HEAD"))
+                .and(path("/test/file"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .insert_header("etag", "\"fc7704075bcfc00e9e4cfab1b9ab1772\"")
+                        .insert_header("content-length", "13")
+                        .insert_header(name, value),
+                )
+                .mount(&mock_server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/test/file"))
+                .respond_with(ResponseTemplate::new(200).set_body_string("Hello, World!"))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            let mut builder = S3Builder::default();
+            builder
+                .endpoint(&mock_server.uri())
+                .bucket("test")
+                .region("us-east-1")
+                .disable_config_load()
+                .disable_ec2_metadata();
+            let op = Operator::new(builder)?.finish();
+
+            let checksum = op.checksum("file", crate::ChecksumAlgorithm::Md5).await?;
+            assert_eq!(checksum.strategy(), ChecksumStrategy::Computed, "{name}");
+            assert_eq!(checksum.to_hex(), "65a8e27d8879283831b664bd8b7f0ad4");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_version() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        let mocks = [
+            (
+                "HEAD",
+                ResponseTemplate::new(200).insert_header("x-amz-version-id", "v1"),
+            ),
+            (
+                "GET",
+                ResponseTemplate::new(200)
+                    .insert_header("x-amz-version-id", "v1")
+                    .set_body_string("Hello, World!"),
+            ),
+            ("DELETE", ResponseTemplate::new(204)),
+        ];
+        for (m, resp) in mocks {
+            Mock::given(method(m))
+                .and(path("/test/file"))
+                .and(query_param("versionId", "v1"))
+                .respond_with(resp)
+                .mount(&mock_server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/test"))
+            .and(query_param("versions", ""))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<ListVersionsResult>
+  <IsTruncated>false</IsTruncated>
+  <Version>
+    <Key>file</Key>
+    <VersionId>v2</VersionId>
+    <IsLatest>false</IsLatest>
+    <LastModified>2009-10-12T17:50:30.000Z</LastModified>
+    <ETag>"fba9dede5f27731c9771645a39863328"</ETag>
+    <Size>13</Size>
+  </Version>
+  <DeleteMarker>
+    <Key>file</Key>
+    <VersionId>v3</VersionId>
+    <IsLatest>true</IsLatest>
+    <LastModified>2009-11-12T17:50:30.000Z</LastModified>
+  </DeleteMarker>
+</ListVersionsResult>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .disable_config_load()
+            .disable_ec2_metadata();
+        let op = Operator::new(builder)?.finish();
+
+        let meta = op
+            .stat_with("file", OpStat::new().with_version("v1"))
+            .await?;
+        assert_eq!(meta.version(), Some("v1"));
+        let bs = op
+            .read_with("file", OpRead::new().with_version("v1"))
+            .await?;
+        assert_eq!(bs, b"Hello, World!");
+        op.delete_with("file", OpDelete::new().with_version("v1"))
+            .await?;
+
+        let entries: Vec<_> = op
+            .list_with("/", OpList::new().with_versions())
+            .await?
+            .try_collect()
+            .await?;
+        let mut versions = Vec::new();
+        for e in entries {
+            let meta = op
+                .metadata(
+                    &e,
+                    Metakey::Version | Metakey::IsCurrent | Metakey::IsDeleted,
+                )
+                .await?;
+            versions.push((
+                e.path().to_string(),
+                meta.version().map(|v| v.to_string()),
+                meta.is_current(),
+                meta.is_deleted(),
+            ));
+        }
+        assert_eq!(
+            versions,
+            vec![
+                (
+                    "file".to_string(),
+                    Some("v2".to_string()),
+                    Some(false),
+                    false
+                ),
+                ("file".to_string(), Some("v3".to_string()), Some(true), true),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archived_object() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/test/file"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-amz-storage-class", "GLACIER")
+                    .insert_header("x-amz-restore", r#"ongoing-request="true""#),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/test/file"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(
+                r#"<Error>
+  <Code>InvalidObjectState</Code>
+  <Message>The operation is not valid for the object's storage class</Message>
+</Error>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/test/file"))
+            .and(query_param("restore", ""))
+            .and(wiremock::matchers::body_string(
+                "<RestoreRequest><Days>7</Days><GlacierJobParameters><Tier>Bulk</Tier></GlacierJobParameters></RestoreRequest>",
+            ))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/test/cold"))
+            .and(header("x-amz-storage-class", "DEEP_ARCHIVE"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/test/warm"))
+            .and(header("x-amz-storage-class", "STANDARD_IA"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .default_storage_class("STANDARD_IA")
+            .disable_config_load()
+            .disable_ec2_metadata();
+        let backend = builder.build()?;
+        backend.restore("file", 7, "Bulk").await?;
+        let op = Operator::new(builder)?.finish();
+
+        let meta = op.stat("file").await?;
+        assert_eq!(meta.storage_class(), Some("GLACIER"));
+        assert_eq!(meta.restore_status(), Some(r#"ongoing-request="true""#));
+
+        let err = op.read("file").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Archived);
+
+        op.write_with(
+            "cold",
+            OpWrite::new().with_storage_class("DEEP_ARCHIVE"),
+            "Hello",
+        )
+        .await?;
+        op.write("warm", "Hello").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checksum_algorithm() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        // crc32c of `123456789` is `0xE3069283`.
+        Mock::given(method("PUT"))
+            .and(path("/test/file"))
+            .and(header("x-amz-sdk-checksum-algorithm", "CRC32C"))
+            .and(header("x-amz-checksum-crc32c", "4waSgw=="))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("HEAD"))
+            .and(path("/test/file"))
+            .and(header("x-amz-checksum-mode", "ENABLED"))
+            .respond_with(
+                ResponseTemplate::new(200).insert_header("x-amz-checksum-crc32c", "4waSgw=="),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/test/multipart"))
+            .and(query_param("uploads", ""))
+            .and(header("x-amz-checksum-algorithm", "CRC32C"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<InitiateMultipartUploadResult><UploadId>upload</UploadId></InitiateMultipartUploadResult>"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/test/multipart"))
+            .and(query_param("partNumber", "1"))
+            .and(header("x-amz-checksum-crc32c", "4waSgw=="))
+            .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"etag1\""))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/test/multipart"))
+            .and(query_param("uploadId", "upload"))
+            .and(wiremock::matchers::body_string_contains(
+                "<ChecksumCRC32C>4waSgw==</ChecksumCRC32C>",
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .checksum_algorithm("crc32c")
+            .disable_config_load()
+            .disable_ec2_metadata();
+        let op = Operator::new(builder)?.finish();
+
+        op.write("file", "123456789").await?;
+        let meta = op.stat("file").await?;
+        assert_eq!(meta.checksum_algorithm(), Some("CRC32C"));
+        assert_eq!(meta.checksum(), Some("4waSgw=="));
+
+        let mut w = op.writer("multipart").await?;
+        w.append("123456789").await?;
+        w.close().await?;
+
+        let mut builder = S3Builder::default();
+        builder.bucket("test").checksum_algorithm("md5");
+        let err = builder.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_objects_v1() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/test"))
+            .and(query_param_is_missing("list-type"))
+            .and(query_param_is_missing("marker"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<ListBucketResult>
+  <IsTruncated>true</IsTruncated>
+  <Contents>
+    <Key>dir/a</Key>
+    <LastModified>2009-10-12T17:50:30.000Z</LastModified>
+    <ETag>"fba9dede5f27731c9771645a39863328"</ETag>
+    <Size>1</Size>
+  </Contents>
+</ListBucketResult>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/test"))
+            .and(query_param_is_missing("list-type"))
+            .and(query_param("marker", "dir/a"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<ListBucketResult>
+  <IsTruncated>false</IsTruncated>
+  <Contents>
+    <Key>dir/b</Key>
+    <LastModified>2009-10-12T17:50:30.000Z</LastModified>
+    <ETag>"fba9dede5f27731c9771645a39863328"</ETag>
+    <Size>2</Size>
+  </Contents>
+</ListBucketResult>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = S3Builder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .bucket("test")
+            .region("us-east-1")
+            .disable_config_load()
+            .disable_ec2_metadata()
+            .enable_list_objects_v1();
+        let op = Operator::new(builder)?.finish();
+
+        let entries: Vec<_> = op.scan("dir/").await?.try_collect().await?;
+        let paths: Vec<_> = entries.iter().map(|e| e.path()).collect();
+        assert_eq!(paths, vec!["dir/a", "dir/b"]);
+
+        Ok(())
     }
 }
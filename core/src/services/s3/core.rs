@@ -19,10 +19,13 @@ use std::fmt;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write;
+use std::str::FromStr;
 use std::time::Duration;
 
 use backon::ExponentialBuilder;
 use backon::Retryable;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use bytes::Bytes;
 use http::header::HeaderName;
 use http::header::CACHE_CONTROL;
@@ -31,6 +34,7 @@ use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::header::IF_MATCH;
 use http::header::IF_NONE_MATCH;
+use http::HeaderMap;
 use http::HeaderValue;
 use http::Request;
 use http::Response;
@@ -40,7 +44,9 @@ use reqsign::AwsLoader;
 use reqsign::AwsV4Signer;
 use serde::Deserialize;
 use serde::Serialize;
+use sha1::Digest;
 
+use crate::ops::*;
 use crate::raw::*;
 use crate::*;
 
@@ -57,6 +63,16 @@ mod constants {
     pub const X_AMZ_SERVER_SIDE_ENCRYPTION_AWS_KMS_KEY_ID: &str =
         "x-amz-server-side-encryption-aws-kms-key-id";
     pub const X_AMZ_STORAGE_CLASS: &str = "x-amz-storage-class";
+    pub const X_AMZ_REQUEST_PAYER: &str = "x-amz-request-payer";
+    pub const X_AMZ_VERSION_ID: &str = "x-amz-version-id";
+    pub const X_AMZ_RESTORE: &str = "x-amz-restore";
+    pub const X_AMZ_CHECKSUM_ALGORITHM: &str = "x-amz-checksum-algorithm";
+    pub const X_AMZ_SDK_CHECKSUM_ALGORITHM: &str = "x-amz-sdk-checksum-algorithm";
+    pub const X_AMZ_CHECKSUM_MODE: &str = "x-amz-checksum-mode";
+    pub const X_AMZ_CHECKSUM_CRC32: &str = "x-amz-checksum-crc32";
+    pub const X_AMZ_CHECKSUM_CRC32C: &str = "x-amz-checksum-crc32c";
+    pub const X_AMZ_CHECKSUM_SHA1: &str = "x-amz-checksum-sha1";
+    pub const X_AMZ_CHECKSUM_SHA256: &str = "x-amz-checksum-sha256";
 
     pub const X_AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM: &str =
         "x-amz-copy-source-server-side-encryption-customer-algorithm";
@@ -67,6 +83,65 @@ mod constants {
 
     pub const RESPONSE_CONTENT_DISPOSITION: &str = "response-content-disposition";
     pub const RESPONSE_CACHE_CONTROL: &str = "response-cache-control";
+    pub const VERSION_ID: &str = "versionId";
+}
+
+/// Checksum algorithms supported by s3.
+///
+/// Reference: [Checking object integrity](https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Crc32c,
+    Sha1,
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    /// Algorithm name used in `x-amz-checksum-algorithm`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32c => "CRC32C",
+            ChecksumAlgorithm::Sha1 => "SHA1",
+            ChecksumAlgorithm::Sha256 => "SHA256",
+        }
+    }
+
+    /// Header name that carries the checksum value.
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Crc32c => constants::X_AMZ_CHECKSUM_CRC32C,
+            ChecksumAlgorithm::Sha1 => constants::X_AMZ_CHECKSUM_SHA1,
+            ChecksumAlgorithm::Sha256 => constants::X_AMZ_CHECKSUM_SHA256,
+        }
+    }
+
+    /// Calculate the base64 encoded checksum of content.
+    pub fn checksum(&self, content: &[u8]) -> String {
+        match self {
+            ChecksumAlgorithm::Crc32c => {
+                BASE64_STANDARD.encode(crc32c::crc32c(content).to_be_bytes())
+            }
+            ChecksumAlgorithm::Sha1 => BASE64_STANDARD.encode(sha1::Sha1::digest(content)),
+            ChecksumAlgorithm::Sha256 => BASE64_STANDARD.encode(sha2::Sha256::digest(content)),
+        }
+    }
+}
+
+impl FromStr for ChecksumAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "crc32c" => Ok(ChecksumAlgorithm::Crc32c),
+            "sha1" => Ok(ChecksumAlgorithm::Sha1),
+            "sha256" => Ok(ChecksumAlgorithm::Sha256),
+            v => Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "checksum_algorithm is not supported",
+            )
+            .with_context("checksum_algorithm", v)),
+        }
+    }
 }
 
 static BACKOFF: Lazy<ExponentialBuilder> =
@@ -82,6 +157,9 @@ pub struct S3Core {
     pub server_side_encryption_customer_key: Option<HeaderValue>,
     pub server_side_encryption_customer_key_md5: Option<HeaderValue>,
     pub default_storage_class: Option<HeaderValue>,
+    pub enable_request_payer: bool,
+    pub enable_list_objects_v1: bool,
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
 
     pub signer: AwsV4Signer,
     pub loader: AwsLoader,
@@ -94,6 +172,9 @@ impl Debug for S3Core {
             .field("bucket", &self.bucket)
             .field("endpoint", &self.endpoint)
             .field("root", &self.root)
+            .field("enable_request_payer", &self.enable_request_payer)
+            .field("enable_list_objects_v1", &self.enable_list_objects_v1)
+            .field("checksum_algorithm", &self.checksum_algorithm)
             .finish_non_exhaustive()
     }
 }
@@ -200,22 +281,105 @@ impl S3Core {
 
         req
     }
+
+    /// Insert `x-amz-request-payer: requester` if request payer is enabled,
+    /// which is required by all requests to requester pays buckets.
+    pub fn insert_request_payer_header(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if self.enable_request_payer {
+            req = req.header(
+                HeaderName::from_static(constants::X_AMZ_REQUEST_PAYER),
+                "requester",
+            );
+        }
+
+        req
+    }
 }
 
 impl S3Core {
+    /// Set storage class header, the storage class in `OpWrite` will
+    /// override the default one.
+    pub fn insert_storage_class_header(
+        &self,
+        mut req: http::request::Builder,
+        args: &OpWrite,
+    ) -> http::request::Builder {
+        if let Some(v) = args.storage_class() {
+            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        } else if let Some(v) = &self.default_storage_class {
+            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        }
+
+        req
+    }
+
+    /// Insert `x-amz-checksum-mode: ENABLED` if checksum algorithm is set,
+    /// so that s3 will return checksums of the object.
+    pub fn insert_checksum_mode_header(
+        &self,
+        mut req: http::request::Builder,
+    ) -> http::request::Builder {
+        if self.checksum_algorithm.is_some() {
+            req = req.header(
+                HeaderName::from_static(constants::X_AMZ_CHECKSUM_MODE),
+                "ENABLED",
+            );
+        }
+
+        req
+    }
+
+    /// Calculate the checksum of content if checksum algorithm is set.
+    pub fn calculate_checksum(&self, content: &[u8]) -> Option<String> {
+        self.checksum_algorithm.map(|v| v.checksum(content))
+    }
+
+    /// Insert the given checksum with its algorithm.
+    pub fn insert_checksum_header(
+        &self,
+        mut req: http::request::Builder,
+        checksum: Option<&str>,
+    ) -> http::request::Builder {
+        if let (Some(algorithm), Some(checksum)) = (self.checksum_algorithm, checksum) {
+            req = req
+                .header(
+                    HeaderName::from_static(constants::X_AMZ_SDK_CHECKSUM_ALGORITHM),
+                    algorithm.as_str(),
+                )
+                .header(HeaderName::from_static(algorithm.header_name()), checksum);
+        }
+
+        req
+    }
+
     pub fn s3_head_object_request(
         &self,
         path: &str,
         if_none_match: Option<&str>,
         if_match: Option<&str>,
+        version: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!("{}/{}", self.endpoint, percent_encode_path(&p));
+        let mut url = format!("{}/{}", self.endpoint, percent_encode_path(&p));
+        if let Some(version) = version {
+            write!(
+                url,
+                "?{}={}",
+                constants::VERSION_ID,
+                percent_encode_path(version)
+            )
+            .expect("write into string must succeed");
+        }
 
         let mut req = Request::head(&url);
 
         req = self.insert_sse_headers(req, false);
+        req = self.insert_request_payer_header(req);
+        req = self.insert_checksum_mode_header(req);
 
         if let Some(if_none_match) = if_none_match {
             req = req.header(IF_NONE_MATCH, if_none_match);
@@ -232,15 +396,7 @@ impl S3Core {
         Ok(req)
     }
 
-    pub fn s3_get_object_request(
-        &self,
-        path: &str,
-        range: BytesRange,
-        override_content_disposition: Option<&str>,
-        override_cache_control: Option<&str>,
-        if_none_match: Option<&str>,
-        if_match: Option<&str>,
-    ) -> Result<Request<AsyncBody>> {
+    pub fn s3_get_object_request(&self, path: &str, args: &OpRead) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
         // Construct headers to add to the request
@@ -248,40 +404,50 @@ impl S3Core {
 
         // Add query arguments to the URL based on response overrides
         let mut query_args = Vec::new();
-        if let Some(override_content_disposition) = override_content_disposition {
+        if let Some(override_content_disposition) = args.override_content_disposition() {
             query_args.push(format!(
                 "{}={}",
                 constants::RESPONSE_CONTENT_DISPOSITION,
                 percent_encode_path(override_content_disposition)
             ))
         }
-        if let Some(override_cache_control) = override_cache_control {
+        if let Some(override_cache_control) = args.override_cache_control() {
             query_args.push(format!(
                 "{}={}",
                 constants::RESPONSE_CACHE_CONTROL,
                 percent_encode_path(override_cache_control)
             ))
         }
+        if let Some(version) = args.version() {
+            query_args.push(format!(
+                "{}={}",
+                constants::VERSION_ID,
+                percent_encode_path(version)
+            ))
+        }
         if !query_args.is_empty() {
             url.push_str(&format!("?{}", query_args.join("&")));
         }
 
         let mut req = Request::get(&url);
 
+        let range = args.range();
         if !range.is_full() {
             req = req.header(http::header::RANGE, range.to_header());
         }
 
-        if let Some(if_none_match) = if_none_match {
+        if let Some(if_none_match) = args.if_none_match() {
             req = req.header(IF_NONE_MATCH, if_none_match);
         }
 
-        if let Some(if_match) = if_match {
+        if let Some(if_match) = args.if_match() {
             req = req.header(IF_MATCH, if_match);
         }
         // Set SSE headers.
         // TODO: how will this work with presign?
         req = self.insert_sse_headers(req, false);
+        req = self.insert_request_payer_header(req);
+        req = self.insert_checksum_mode_header(req);
 
         let req = req
             .body(AsyncBody::Empty)
@@ -293,12 +459,9 @@ impl S3Core {
     pub async fn s3_get_object(
         &self,
         path: &str,
-        range: BytesRange,
-        if_none_match: Option<&str>,
-        if_match: Option<&str>,
+        args: &OpRead,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req =
-            self.s3_get_object_request(path, range, None, None, if_none_match, if_match)?;
+        let mut req = self.s3_get_object_request(path, args)?;
 
         self.sign(&mut req).await?;
 
@@ -309,9 +472,7 @@ impl S3Core {
         &self,
         path: &str,
         size: Option<usize>,
-        content_type: Option<&str>,
-        content_disposition: Option<&str>,
-        cache_control: Option<&str>,
+        args: &OpWrite,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -324,25 +485,28 @@ impl S3Core {
             req = req.header(CONTENT_LENGTH, size)
         }
 
-        if let Some(mime) = content_type {
+        if let Some(mime) = args.content_type() {
             req = req.header(CONTENT_TYPE, mime)
         }
 
-        if let Some(pos) = content_disposition {
+        if let Some(pos) = args.content_disposition() {
             req = req.header(CONTENT_DISPOSITION, pos)
         }
 
-        if let Some(cache_control) = cache_control {
+        if let Some(cache_control) = args.cache_control() {
             req = req.header(CACHE_CONTROL, cache_control)
         }
 
-        // Set storage class header
-        if let Some(v) = &self.default_storage_class {
-            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        req = self.insert_storage_class_header(req, args);
+
+        // Set checksum header, we can only calculate it for in-memory body.
+        if let AsyncBody::Bytes(bs) = &body {
+            req = self.insert_checksum_header(req, self.calculate_checksum(bs).as_deref());
         }
 
         // Set SSE headers.
         req = self.insert_sse_headers(req, true);
+        req = self.insert_request_payer_header(req);
 
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
@@ -355,20 +519,37 @@ impl S3Core {
         path: &str,
         if_none_match: Option<&str>,
         if_match: Option<&str>,
+        version: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.s3_head_object_request(path, if_none_match, if_match)?;
+        let mut req = self.s3_head_object_request(path, if_none_match, if_match, version)?;
 
         self.sign(&mut req).await?;
 
         self.send(req).await
     }
 
-    pub async fn s3_delete_object(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+    pub async fn s3_delete_object(
+        &self,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!("{}/{}", self.endpoint, percent_encode_path(&p));
+        let mut url = format!("{}/{}", self.endpoint, percent_encode_path(&p));
+        if let Some(version) = version {
+            write!(
+                url,
+                "?{}={}",
+                constants::VERSION_ID,
+                percent_encode_path(version)
+            )
+            .expect("write into string must succeed");
+        }
+
+        let req = Request::delete(&url);
+        let req = self.insert_request_payer_header(req);
 
-        let mut req = Request::delete(&url)
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
@@ -429,6 +610,8 @@ impl S3Core {
             )
         }
 
+        req = self.insert_request_payer_header(req);
+
         let mut req = req
             .header(constants::X_AMZ_COPY_SOURCE, percent_encode_path(&source))
             .body(AsyncBody::Empty)
@@ -474,7 +657,45 @@ impl S3Core {
             .expect("write into string must succeed");
         }
 
-        let mut req = Request::get(&url)
+        let req = Request::get(&url);
+        let req = self.insert_request_payer_header(req);
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
+    /// List objects via ListObjects (v1) which uses `marker` instead of
+    /// `continuation-token` for pagination.
+    pub async fn s3_list_objects_v1(
+        &self,
+        path: &str,
+        marker: &str,
+        delimiter: &str,
+        limit: Option<usize>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut url = format!("{}?prefix={}", self.endpoint, percent_encode_path(&p));
+        if !delimiter.is_empty() {
+            write!(url, "&delimiter={delimiter}").expect("write into string must succeed");
+        }
+        if let Some(limit) = limit {
+            write!(url, "&max-keys={limit}").expect("write into string must succeed");
+        }
+        if !marker.is_empty() {
+            write!(url, "&marker={}", percent_encode_path(marker))
+                .expect("write into string must succeed");
+        }
+
+        let req = Request::get(&url);
+        let req = self.insert_request_payer_header(req);
+
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
 
@@ -486,10 +707,7 @@ impl S3Core {
     pub async fn s3_initiate_multipart_upload(
         &self,
         path: &str,
-        content_type: Option<&str>,
-        content_disposition: Option<&str>,
-        cache_control: Option<&str>,
-        if_match: Option<&str>,
+        args: &OpWrite,
     ) -> Result<Response<IncomingAsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
@@ -497,29 +715,34 @@ impl S3Core {
 
         let mut req = Request::post(&url);
 
-        if let Some(mime) = content_type {
+        if let Some(mime) = args.content_type() {
             req = req.header(CONTENT_TYPE, mime)
         }
 
-        if let Some(content_disposition) = content_disposition {
+        if let Some(content_disposition) = args.content_disposition() {
             req = req.header(CONTENT_DISPOSITION, content_disposition)
         }
 
-        if let Some(cache_control) = cache_control {
+        if let Some(cache_control) = args.cache_control() {
             req = req.header(CACHE_CONTROL, cache_control)
         }
 
-        if let Some(if_match) = if_match {
+        if let Some(if_match) = args.if_match() {
             req = req.header(IF_MATCH, if_match)
         }
 
-        // Set storage class header
-        if let Some(v) = &self.default_storage_class {
-            req = req.header(HeaderName::from_static(constants::X_AMZ_STORAGE_CLASS), v);
+        let mut req = self.insert_storage_class_header(req, args);
+
+        if let Some(algorithm) = self.checksum_algorithm {
+            req = req.header(
+                HeaderName::from_static(constants::X_AMZ_CHECKSUM_ALGORITHM),
+                algorithm.as_str(),
+            );
         }
 
         // Set SSE headers.
         let req = self.insert_sse_headers(req, true);
+        let req = self.insert_request_payer_header(req);
 
         let mut req = req
             .body(AsyncBody::Empty)
@@ -536,6 +759,7 @@ impl S3Core {
         upload_id: &str,
         part_number: usize,
         size: Option<u64>,
+        checksum: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -554,8 +778,11 @@ impl S3Core {
             req = req.header(CONTENT_LENGTH, size);
         }
 
+        req = self.insert_checksum_header(req, checksum);
+
         // Set SSE headers.
         req = self.insert_sse_headers(req, true);
+        req = self.insert_request_payer_header(req);
 
         // Set body
         let req = req.body(body).map_err(new_request_build_error)?;
@@ -582,6 +809,7 @@ impl S3Core {
 
         // Set SSE headers.
         let req = self.insert_sse_headers(req, true);
+        let req = self.insert_request_payer_header(req);
 
         let content = quick_xml::se::to_string(&CompleteMultipartUploadRequest {
             part: parts.to_vec(),
@@ -616,26 +844,115 @@ impl S3Core {
             percent_encode_path(upload_id)
         );
 
-        let mut req = Request::delete(&url)
+        let req = Request::delete(&url);
+        let req = self.insert_request_payer_header(req);
+
+        let mut req = req
             .body(AsyncBody::Empty)
             .map_err(new_request_build_error)?;
         self.sign(&mut req).await?;
         self.send(req).await
     }
 
+    /// Restore an archived object for `days` days with given retrieval tier.
+    pub async fn s3_restore_object(
+        &self,
+        path: &str,
+        days: u32,
+        tier: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let url = format!("{}/{}?restore", self.endpoint, percent_encode_path(&p));
+
+        let req = Request::post(&url);
+        let req = self.insert_request_payer_header(req);
+
+        let content = quick_xml::se::to_string(&RestoreRequest {
+            days,
+            glacier_job_parameters: RestoreRequestGlacierJobParameters {
+                tier: tier.to_string(),
+            },
+        })
+        .map_err(new_xml_deserialize_error)?;
+
+        // Make sure content length has been set to avoid post with chunked encoding.
+        let req = req.header(CONTENT_LENGTH, content.len());
+        // Set content-type to `application/xml` to avoid mixed with form post.
+        let req = req.header(CONTENT_TYPE, "application/xml");
+        // Set content-md5 as required by API.
+        let req = req.header("CONTENT-MD5", format_content_md5(content.as_bytes()));
+
+        let mut req = req
+            .body(AsyncBody::Bytes(Bytes::from(content)))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
+    pub async fn s3_list_object_versions(
+        &self,
+        path: &str,
+        key_marker: &str,
+        version_id_marker: &str,
+        delimiter: &str,
+        limit: Option<usize>,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut url = format!(
+            "{}?versions&prefix={}",
+            self.endpoint,
+            percent_encode_path(&p)
+        );
+        if !delimiter.is_empty() {
+            write!(url, "&delimiter={delimiter}").expect("write into string must succeed");
+        }
+        if let Some(limit) = limit {
+            write!(url, "&max-keys={limit}").expect("write into string must succeed");
+        }
+        if !key_marker.is_empty() {
+            write!(url, "&key-marker={}", percent_encode_path(key_marker))
+                .expect("write into string must succeed");
+        }
+        if !version_id_marker.is_empty() {
+            write!(
+                url,
+                "&version-id-marker={}",
+                percent_encode_path(version_id_marker)
+            )
+            .expect("write into string must succeed");
+        }
+
+        let req = Request::get(&url);
+        let req = self.insert_request_payer_header(req);
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req).await?;
+
+        self.send(req).await
+    }
+
     pub async fn s3_delete_objects(
         &self,
-        paths: Vec<String>,
+        paths: Vec<(String, OpDelete)>,
     ) -> Result<Response<IncomingAsyncBody>> {
         let url = format!("{}/?delete", self.endpoint);
 
         let req = Request::post(&url);
+        let req = self.insert_request_payer_header(req);
 
         let content = quick_xml::se::to_string(&DeleteObjectsRequest {
             object: paths
                 .into_iter()
-                .map(|path| DeleteObjectsRequestObject {
+                .map(|(path, op)| DeleteObjectsRequestObject {
                     key: build_abs_path(&self.root, &path),
+                    version_id: op.version().map(|v| v.to_string()),
                 })
                 .collect(),
         })
@@ -658,6 +975,72 @@ impl S3Core {
     }
 }
 
+/// Parse s3 object metadata from headers, besides the standard http
+/// headers, `x-amz-version-id`, `x-amz-storage-class` and `x-amz-restore`
+/// will be parsed as version, storage class and restore status.
+pub fn parse_into_s3_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata> {
+    let mut m = parse_into_metadata(path, headers)?;
+
+    if let Some(v) = parse_s3_header(headers, constants::X_AMZ_VERSION_ID)? {
+        m.set_version(v);
+    }
+    // S3 will not return `x-amz-storage-class` for `STANDARD` objects.
+    m.set_storage_class(
+        parse_s3_header(headers, constants::X_AMZ_STORAGE_CLASS)?.unwrap_or("STANDARD"),
+    );
+    if let Some(v) = parse_s3_header(headers, constants::X_AMZ_RESTORE)? {
+        m.set_restore_status(v);
+    }
+    for (algorithm, name) in [
+        ("CRC32", constants::X_AMZ_CHECKSUM_CRC32),
+        ("CRC32C", constants::X_AMZ_CHECKSUM_CRC32C),
+        ("SHA1", constants::X_AMZ_CHECKSUM_SHA1),
+        ("SHA256", constants::X_AMZ_CHECKSUM_SHA256),
+    ] {
+        if let Some(v) = parse_s3_header(headers, name)? {
+            m.set_checksum(algorithm, v);
+            break;
+        }
+    }
+    // Etags of objects encrypted by SSE-KMS or SSE-C are not md5 of content.
+    let kms = parse_s3_header(headers, constants::X_AMZ_SERVER_SIDE_ENCRYPTION)?
+        .map_or(false, |v| v.starts_with("aws:kms"));
+    if kms || headers.contains_key(constants::X_AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM) {
+        m.set_etag_opaque();
+    }
+
+    Ok(m)
+}
+
+fn parse_s3_header<'a>(headers: &'a HeaderMap, name: &str) -> Result<Option<&'a str>> {
+    match headers.get(name) {
+        None => Ok(None),
+        Some(v) => Ok(Some(v.to_str().map_err(|e| {
+            Error::new(
+                ErrorKind::Unexpected,
+                "header value is not valid utf-8 string",
+            )
+            .with_operation("parse_into_s3_metadata")
+            .with_context("header", name)
+            .set_source(e)
+        })?)),
+    }
+}
+
+/// Request of RestoreObject
+#[derive(Default, Debug, Serialize)]
+#[serde(default, rename = "RestoreRequest", rename_all = "PascalCase")]
+pub struct RestoreRequest {
+    pub days: u32,
+    pub glacier_job_parameters: RestoreRequestGlacierJobParameters,
+}
+
+#[derive(Default, Debug, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct RestoreRequestGlacierJobParameters {
+    pub tier: String,
+}
+
 /// Result of CreateMultipartUpload
 #[derive(Default, Debug, Deserialize)]
 #[serde(default, rename_all = "PascalCase")]
@@ -707,6 +1090,39 @@ pub struct CompleteMultipartUploadRequestPart {
     /// ref: <https://github.com/tafia/quick-xml/issues/362>
     #[serde(rename = "ETag")]
     pub etag: String,
+    #[serde(rename = "ChecksumCRC32C", skip_serializing_if = "Option::is_none")]
+    pub checksum_crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1", skip_serializing_if = "Option::is_none")]
+    pub checksum_sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256", skip_serializing_if = "Option::is_none")]
+    pub checksum_sha256: Option<String>,
+}
+
+impl CompleteMultipartUploadRequestPart {
+    /// Create a new part with its checksum.
+    ///
+    /// S3 requires the checksum of every part while completing the upload,
+    /// and calculates the checksum-of-checksums of the whole object from
+    /// them.
+    pub fn new(
+        part_number: usize,
+        etag: String,
+        algorithm: Option<ChecksumAlgorithm>,
+        checksum: Option<String>,
+    ) -> Self {
+        let mut part = CompleteMultipartUploadRequestPart {
+            part_number,
+            etag,
+            ..Default::default()
+        };
+        match algorithm {
+            Some(ChecksumAlgorithm::Crc32c) => part.checksum_crc32c = checksum,
+            Some(ChecksumAlgorithm::Sha1) => part.checksum_sha1 = checksum,
+            Some(ChecksumAlgorithm::Sha256) => part.checksum_sha256 = checksum,
+            None => {}
+        }
+        part
+    }
 }
 
 /// Request of DeleteObjects.
@@ -720,6 +1136,8 @@ pub struct DeleteObjectsRequest {
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteObjectsRequestObject {
     pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
 }
 
 /// Result of DeleteObjects.
@@ -780,14 +1198,17 @@ mod tests {
                 CompleteMultipartUploadRequestPart {
                     part_number: 1,
                     etag: "\"a54357aff0632cce46d942af68356b38\"".to_string(),
+                    ..Default::default()
                 },
                 CompleteMultipartUploadRequestPart {
                     part_number: 2,
                     etag: "\"0c78aef83f66abc1fa1e8477f296d394\"".to_string(),
+                    ..Default::default()
                 },
                 CompleteMultipartUploadRequestPart {
                     part_number: 3,
                     etag: "\"acbd18db4cc2f85cedef654fccc4a4d8\"".to_string(),
+                    ..Default::default()
                 },
             ],
         };
@@ -824,9 +1245,11 @@ mod tests {
             object: vec![
                 DeleteObjectsRequestObject {
                     key: "sample1.txt".to_string(),
+                    version_id: None,
                 },
                 DeleteObjectsRequestObject {
                     key: "sample2.txt".to_string(),
+                    version_id: Some("OYcLXagmS.WaD..oyH4KRguB95_YhLs7".to_string()),
                 },
             ],
         };
@@ -841,6 +1264,7 @@ mod tests {
              </Object>
              <Object>
                <Key>sample2.txt</Key>
+               <VersionId>OYcLXagmS.WaD..oyH4KRguB95_YhLs7</VersionId>
              </Object>
              </Delete>"#
                 // Cleanup space and new line
@@ -848,6 +1272,85 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_serialize_complete_multipart_upload_request_with_checksum() {
+        let req = CompleteMultipartUploadRequest {
+            part: vec![
+                CompleteMultipartUploadRequestPart::new(
+                    1,
+                    "etag1".to_string(),
+                    Some(ChecksumAlgorithm::Crc32c),
+                    Some("4waSgw==".to_string()),
+                ),
+                CompleteMultipartUploadRequestPart::new(2, "etag2".to_string(), None, None),
+            ],
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<CompleteMultipartUpload>
+             <Part>
+               <PartNumber>1</PartNumber>
+               <ETag>etag1</ETag>
+               <ChecksumCRC32C>4waSgw==</ChecksumCRC32C>
+             </Part>
+             <Part>
+               <PartNumber>2</PartNumber>
+               <ETag>etag2</ETag>
+             </Part>
+            </CompleteMultipartUpload>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        )
+    }
+
+    #[test]
+    fn test_checksum_algorithm() {
+        let cases = vec![
+            ("crc32c", b"123456789".as_slice(), "4waSgw=="),
+            ("SHA1", b"".as_slice(), "2jmj7l5rSw0yVb/vlWAYkK/YBwk="),
+            (
+                "sha256",
+                b"".as_slice(),
+                "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=",
+            ),
+        ];
+
+        for (name, content, expected) in cases {
+            let algorithm = ChecksumAlgorithm::from_str(name).expect("must be valid");
+            assert_eq!(algorithm.checksum(content), expected, "{name}");
+        }
+
+        assert!(ChecksumAlgorithm::from_str("md5").is_err());
+    }
+
+    /// This example is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_RestoreObject.html#API_RestoreObject_Examples
+    #[test]
+    fn test_serialize_restore_request() {
+        let req = RestoreRequest {
+            days: 2,
+            glacier_job_parameters: RestoreRequestGlacierJobParameters {
+                tier: "Expedited".to_string(),
+            },
+        };
+
+        let actual = quick_xml::se::to_string(&req).expect("must succeed");
+
+        pretty_assertions::assert_eq!(
+            actual,
+            r#"<RestoreRequest>
+             <Days>2</Days>
+             <GlacierJobParameters>
+               <Tier>Expedited</Tier>
+             </GlacierJobParameters>
+             </RestoreRequest>"#
+                // Cleanup space and new line
+                .replace([' ', '\n'], "")
+        )
+    }
+
     /// This example is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_DeleteObjects.html#API_DeleteObjects_Examples
     #[test]
     fn test_deserialize_delete_objects_result() {
@@ -44,7 +44,10 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
     let (mut kind, mut retryable) = match parts.status {
         StatusCode::NOT_FOUND => (ErrorKind::NotFound, false),
         StatusCode::FORBIDDEN => (ErrorKind::PermissionDenied, false),
-        StatusCode::PRECONDITION_FAILED => (ErrorKind::PreconditionFailed, false),
+        // Conditional reads like `If-None-Match` will get `304 Not Modified`.
+        StatusCode::PRECONDITION_FAILED | StatusCode::NOT_MODIFIED => {
+            (ErrorKind::PreconditionFailed, false)
+        }
         StatusCode::INTERNAL_SERVER_ERROR
         | StatusCode::BAD_GATEWAY
         | StatusCode::SERVICE_UNAVAILABLE
@@ -64,6 +67,10 @@ pub async fn parse_error(resp: Response<IncomingAsyncBody>) -> Result<Error> {
             //
             // It's Ok for us to retry it again.
             "RequestTimeout" => (ErrorKind::Unexpected, true),
+            // > The operation is not valid for the object's storage class.
+            //
+            // Returned while reading archived objects that are not restored.
+            "InvalidObjectState" => (ErrorKind::Archived, false),
             _ => (kind, retryable),
         }
     }
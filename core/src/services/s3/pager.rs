@@ -35,8 +35,13 @@ pub struct S3Pager {
     path: String,
     delimiter: String,
     limit: Option<usize>,
+    /// List object versions instead of objects.
+    versions: bool,
 
     token: String,
+    /// Markers used by list object versions.
+    key_marker: String,
+    version_id_marker: String,
     done: bool,
 }
 
@@ -48,24 +53,32 @@ impl S3Pager {
             path: path.to_string(),
             delimiter: delimiter.to_string(),
             limit,
+            versions: false,
 
             token: "".to_string(),
+            key_marker: "".to_string(),
+            version_id_marker: "".to_string(),
             done: false,
         }
     }
-}
 
-#[async_trait]
-impl oio::Page for S3Pager {
-    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        if self.done {
-            return Ok(None);
-        }
+    /// List all versions of objects (including delete markers) instead of
+    /// the latest objects via `ListObjectVersions`.
+    pub fn with_versions(mut self) -> Self {
+        self.versions = true;
+        self
+    }
 
-        let resp = self
-            .core
-            .s3_list_objects(&self.path, &self.token, &self.delimiter, self.limit)
-            .await?;
+    async fn next_objects(&mut self) -> Result<Vec<oio::Entry>> {
+        let resp = if self.core.enable_list_objects_v1 {
+            self.core
+                .s3_list_objects_v1(&self.path, &self.token, &self.delimiter, self.limit)
+                .await?
+        } else {
+            self.core
+                .s3_list_objects(&self.path, &self.token, &self.delimiter, self.limit)
+                .await?
+        };
 
         if resp.status() != http::StatusCode::OK {
             return Err(parse_error(resp).await?);
@@ -87,7 +100,18 @@ impl oio::Page for S3Pager {
         } else {
             output.common_prefixes.is_empty() && output.contents.is_empty()
         };
-        self.token = output.next_continuation_token.clone().unwrap_or_default();
+        self.token = if self.core.enable_list_objects_v1 {
+            // ListObjects (v1) only returns `NextMarker` while delimiter is
+            // specified, use the last key as marker instead.
+            output
+                .next_marker
+                .clone()
+                .or_else(|| output.contents.last().map(|v| v.key.clone()))
+                .or_else(|| output.common_prefixes.last().map(|v| v.prefix.clone()))
+                .unwrap_or_default()
+        } else {
+            output.next_continuation_token.clone().unwrap_or_default()
+        };
 
         let mut entries = Vec::with_capacity(output.common_prefixes.len() + output.contents.len());
 
@@ -123,6 +147,105 @@ impl oio::Page for S3Pager {
             entries.push(de);
         }
 
+        Ok(entries)
+    }
+
+    async fn next_versions(&mut self) -> Result<Vec<oio::Entry>> {
+        let resp = self
+            .core
+            .s3_list_object_versions(
+                &self.path,
+                &self.key_marker,
+                &self.version_id_marker,
+                &self.delimiter,
+                self.limit,
+            )
+            .await?;
+
+        if resp.status() != http::StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+
+        let output: VersionsOutput =
+            de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
+
+        self.done = if let Some(is_truncated) = output.is_truncated {
+            !is_truncated
+        } else {
+            output.next_key_marker.is_none()
+        };
+        self.key_marker = output.next_key_marker.clone().unwrap_or_default();
+        self.version_id_marker = output.next_version_id_marker.clone().unwrap_or_default();
+
+        let mut entries = Vec::with_capacity(
+            output.common_prefixes.len() + output.version.len() + output.delete_marker.len(),
+        );
+
+        for prefix in output.common_prefixes {
+            let de = oio::Entry::new(
+                &build_rel_path(&self.core.root, &prefix.prefix),
+                Metadata::new(EntryMode::DIR),
+            );
+
+            entries.push(de);
+        }
+
+        for version in output.version {
+            if version.key.ends_with('/') {
+                continue;
+            }
+
+            let mut meta = Metadata::new(EntryMode::FILE);
+
+            meta.set_version(&version.version_id);
+            meta.set_is_current(version.is_latest);
+            meta.set_is_deleted(false);
+            meta.set_etag(&version.etag);
+            meta.set_content_md5(version.etag.trim_matches('"'));
+            meta.set_content_length(version.size);
+            meta.set_last_modified(parse_datetime_from_rfc3339(version.last_modified.as_str())?);
+
+            let de = oio::Entry::new(&build_rel_path(&self.core.root, &version.key), meta);
+
+            entries.push(de);
+        }
+
+        for marker in output.delete_marker {
+            if marker.key.ends_with('/') {
+                continue;
+            }
+
+            let mut meta = Metadata::new(EntryMode::FILE);
+
+            meta.set_version(&marker.version_id);
+            meta.set_is_current(marker.is_latest);
+            meta.set_is_deleted(true);
+            meta.set_last_modified(parse_datetime_from_rfc3339(marker.last_modified.as_str())?);
+
+            let de = oio::Entry::new(&build_rel_path(&self.core.root, &marker.key), meta);
+
+            entries.push(de);
+        }
+
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl oio::Page for S3Pager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let entries = if self.versions {
+            self.next_versions().await?
+        } else {
+            self.next_objects().await?
+        };
+
         Ok(Some(entries))
     }
 }
@@ -134,6 +257,8 @@ impl oio::Page for S3Pager {
 /// Use `Option` in `is_truncated` and `next_continuation_token` to make
 /// the behavior more clear so that we can be compatible to more s3 services.
 ///
+/// `next_marker` is only returned by ListObjects (v1).
+///
 /// And enable `serde(default)` so that we can keep going even when some field
 /// is not exist.
 #[derive(Default, Debug, Deserialize)]
@@ -141,6 +266,7 @@ impl oio::Page for S3Pager {
 struct Output {
     is_truncated: Option<bool>,
     next_continuation_token: Option<String>,
+    next_marker: Option<String>,
     common_prefixes: Vec<OutputCommonPrefix>,
     contents: Vec<OutputContent>,
 }
@@ -161,6 +287,39 @@ struct OutputCommonPrefix {
     prefix: String,
 }
 
+/// Output of ListObjectVersions.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct VersionsOutput {
+    is_truncated: Option<bool>,
+    next_key_marker: Option<String>,
+    next_version_id_marker: Option<String>,
+    common_prefixes: Vec<OutputCommonPrefix>,
+    version: Vec<OutputVersion>,
+    delete_marker: Vec<OutputDeleteMarker>,
+}
+
+#[derive(Default, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OutputVersion {
+    key: String,
+    version_id: String,
+    is_latest: bool,
+    size: u64,
+    last_modified: String,
+    #[serde(rename = "ETag")]
+    etag: String,
+}
+
+#[derive(Default, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OutputDeleteMarker {
+    key: String,
+    version_id: String,
+    is_latest: bool,
+    last_modified: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,4 +387,85 @@ mod tests {
             ]
         )
     }
+
+    /// This example is from https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectVersions.html#API_ListObjectVersions_Examples
+    #[test]
+    fn test_parse_list_versions_output() {
+        let bs = bytes::Bytes::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListVersionsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01">
+  <Name>bucket</Name>
+  <Prefix>my</Prefix>
+  <KeyMarker/>
+  <VersionIdMarker/>
+  <MaxKeys>5</MaxKeys>
+  <IsTruncated>true</IsTruncated>
+  <NextKeyMarker>my-third-image.jpg</NextKeyMarker>
+  <NextVersionIdMarker>03jpff543dhffds434rfdsFDN943fdsFkdmqnh892</NextVersionIdMarker>
+  <Version>
+    <Key>my-image.jpg</Key>
+    <VersionId>3/L4kqtJl40Nr8X8gdRQBpUMLUo</VersionId>
+    <IsLatest>true</IsLatest>
+    <LastModified>2009-10-12T17:50:30.000Z</LastModified>
+    <ETag>"fba9dede5f27731c9771645a39863328"</ETag>
+    <Size>434234</Size>
+    <StorageClass>STANDARD</StorageClass>
+  </Version>
+  <DeleteMarker>
+    <Key>my-second-image.jpg</Key>
+    <VersionId>03jpff543dhffds434rfdsFDN943fdsFkdmqnh892</VersionId>
+    <IsLatest>true</IsLatest>
+    <LastModified>2009-11-12T17:50:30.000Z</LastModified>
+  </DeleteMarker>
+  <Version>
+    <Key>my-second-image.jpg</Key>
+    <VersionId>QUpfdndhfd8438MNFDN93jdnJFkdmqnh893</VersionId>
+    <IsLatest>false</IsLatest>
+    <LastModified>2009-10-10T17:50:30.000Z</LastModified>
+    <ETag>"9b2cf535f27731c974343645a3985328"</ETag>
+    <Size>166434</Size>
+    <StorageClass>STANDARD</StorageClass>
+  </Version>
+</ListVersionsResult>"#,
+        );
+
+        let out: VersionsOutput = de::from_reader(bs.reader()).expect("must success");
+
+        assert!(out.is_truncated.unwrap());
+        assert_eq!(out.next_key_marker.as_deref(), Some("my-third-image.jpg"));
+        assert_eq!(
+            out.next_version_id_marker.as_deref(),
+            Some("03jpff543dhffds434rfdsFDN943fdsFkdmqnh892")
+        );
+        assert_eq!(
+            out.version,
+            vec![
+                OutputVersion {
+                    key: "my-image.jpg".to_string(),
+                    version_id: "3/L4kqtJl40Nr8X8gdRQBpUMLUo".to_string(),
+                    is_latest: true,
+                    size: 434234,
+                    last_modified: "2009-10-12T17:50:30.000Z".to_string(),
+                    etag: "\"fba9dede5f27731c9771645a39863328\"".to_string(),
+                },
+                OutputVersion {
+                    key: "my-second-image.jpg".to_string(),
+                    version_id: "QUpfdndhfd8438MNFDN93jdnJFkdmqnh893".to_string(),
+                    is_latest: false,
+                    size: 166434,
+                    last_modified: "2009-10-10T17:50:30.000Z".to_string(),
+                    etag: "\"9b2cf535f27731c974343645a3985328\"".to_string(),
+                }
+            ]
+        );
+        assert_eq!(
+            out.delete_marker,
+            vec![OutputDeleteMarker {
+                key: "my-second-image.jpg".to_string(),
+                version_id: "03jpff543dhffds434rfdsFDN943fdsFkdmqnh892".to_string(),
+                is_latest: true,
+                last_modified: "2009-11-12T17:50:30.000Z".to_string(),
+            }]
+        );
+    }
 }
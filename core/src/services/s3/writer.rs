@@ -61,9 +61,7 @@ impl oio::Write for S3Writer {
         let mut req = self.core.s3_put_object_request(
             &self.path,
             Some(bs.len()),
-            self.op.content_type(),
-            self.op.content_disposition(),
-            self.op.cache_control(),
+            &self.op,
             AsyncBody::Bytes(bs),
         )?;
 
@@ -88,12 +86,14 @@ impl oio::Write for S3Writer {
         );
         // AWS S3 requires part number must between [1..=10000]
         let part_number = self.parts.len() + 1;
+        let checksum = self.core.calculate_checksum(&bs);
 
         let mut req = self.core.s3_upload_part_request(
             &self.path,
             upload_id,
             part_number,
             Some(bs.len() as u64),
+            checksum.as_deref(),
             AsyncBody::Bytes(bs),
         )?;
 
@@ -116,8 +116,12 @@ impl oio::Write for S3Writer {
 
                 resp.into_body().consume().await?;
 
-                self.parts
-                    .push(CompleteMultipartUploadRequestPart { part_number, etag });
+                self.parts.push(CompleteMultipartUploadRequestPart::new(
+                    part_number,
+                    etag,
+                    self.core.checksum_algorithm,
+                    checksum,
+                ));
 
                 Ok(())
             }
@@ -126,10 +130,11 @@ impl oio::Write for S3Writer {
     }
 
     async fn abort(&mut self) -> Result<()> {
-        let upload_id = if let Some(upload_id) = &self.upload_id {
-            upload_id
-        } else {
-            return Ok(());
+        // Keep the upload id until abort succeeds so that a retried abort
+        // will send the request again.
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id,
+            None => return Ok(()),
         };
 
         let resp = self
@@ -140,6 +145,8 @@ impl oio::Write for S3Writer {
             // s3 returns code 204 if abort succeeds.
             StatusCode::NO_CONTENT => {
                 resp.into_body().consume().await?;
+                self.upload_id = None;
+                self.parts.clear();
                 Ok(())
             }
             _ => Err(parse_error(resp).await?),
@@ -0,0 +1,654 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::cmp::min;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io::SeekFrom;
+use std::pin::Pin;
+
+use async_compat::Compat;
+use async_trait::async_trait;
+use bb8::PooledConnection;
+use bb8::RunError;
+use chrono::DateTime;
+use chrono::Utc;
+use futures::StreamExt;
+use http::Uri;
+use log::debug;
+use openssh::KnownHosts;
+use openssh::SessionBuilder;
+use openssh_sftp_client::file::TokioCompatFile;
+use openssh_sftp_client::fs::DirEntry;
+use openssh_sftp_client::fs::Fs;
+use openssh_sftp_client::metadata::MetaData;
+use openssh_sftp_client::Sftp;
+use openssh_sftp_client::SftpOptions;
+use tokio::io::AsyncSeekExt;
+use tokio::sync::OnceCell;
+
+use super::pager::SftpPager;
+use super::writer::SftpWriter;
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// SFTP services support.
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [ ] ~~scan~~
+/// - [ ] ~~presign~~
+/// - [ ] blocking
+///
+/// # Configuration
+///
+/// - `endpoint`: set the endpoint for connection, like `ssh://127.0.0.1:22`
+/// - `root`: Set the work directory for backend
+/// - `user`: set the login user
+/// - `key`: set the path of private key for authentication
+/// - `known_hosts_strategy`: set the known hosts checking strategy, could be
+///   `strict` (default), `accept` or `add`
+///
+/// You can refer to [`SftpBuilder`]'s docs for more information
+///
+/// # Authentication
+///
+/// This service talks to the server via the system's `ssh` binary, and
+/// authenticates with private keys. Keys protected by passphrase should
+/// be loaded into `ssh-agent` first. Password authentication is not
+/// supported.
+///
+/// # Connection Reuse
+///
+/// Creating a SFTP session requires a full ssh handshake, so connections
+/// are kept in a pool and reused by all operations. Requests issued on the
+/// same connection are multiplexed over the single sftp channel.
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::Sftp;
+/// use opendal::Object;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     // create backend builder
+///     let mut builder = Sftp::default();
+///
+///     builder.endpoint("ssh://127.0.0.1:22");
+///     builder.user("test");
+///     builder.key("/home/test/.ssh/id_rsa");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///     let _obj: Object = op.object("test_file");
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct SftpBuilder {
+    endpoint: Option<String>,
+    root: Option<String>,
+    user: Option<String>,
+    key: Option<String>,
+    known_hosts_strategy: Option<String>,
+}
+
+impl Debug for SftpBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Builder")
+            .field("endpoint", &self.endpoint)
+            .field("root", &self.root)
+            .field("user", &self.user)
+            .field("known_hosts_strategy", &self.known_hosts_strategy)
+            .finish()
+    }
+}
+
+impl SftpBuilder {
+    /// set endpoint for sftp backend.
+    ///
+    /// The format is `ssh://host:port`, port will be `22` if not set.
+    pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
+        self.endpoint = if endpoint.is_empty() {
+            None
+        } else {
+            Some(endpoint.to_string())
+        };
+
+        self
+    }
+
+    /// set root path for sftp backend.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        self.root = if root.is_empty() {
+            None
+        } else {
+            Some(root.to_string())
+        };
+
+        self
+    }
+
+    /// set user for sftp backend.
+    pub fn user(&mut self, user: &str) -> &mut Self {
+        self.user = if user.is_empty() {
+            None
+        } else {
+            Some(user.to_string())
+        };
+
+        self
+    }
+
+    /// set the path of private key for sftp backend.
+    pub fn key(&mut self, key: &str) -> &mut Self {
+        self.key = if key.is_empty() {
+            None
+        } else {
+            Some(key.to_string())
+        };
+
+        self
+    }
+
+    /// set known hosts strategy for sftp backend.
+    ///
+    /// - `strict`: the host key must be in known hosts file already.
+    /// - `add`: add the host key if it's not known, but reject changed keys.
+    /// - `accept`: accept any host key and add it to known hosts file.
+    ///
+    /// Default to `strict`.
+    pub fn known_hosts_strategy(&mut self, strategy: &str) -> &mut Self {
+        self.known_hosts_strategy = if strategy.is_empty() {
+            None
+        } else {
+            Some(strategy.to_string())
+        };
+
+        self
+    }
+}
+
+impl Builder for SftpBuilder {
+    const SCHEME: Scheme = Scheme::Sftp;
+    type Accessor = SftpBackend;
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        debug!("sftp backend build started: {:?}", &self);
+        let endpoint = match &self.endpoint {
+            None => return Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")),
+            Some(v) => v,
+        };
+
+        let endpoint_uri = match endpoint.parse::<Uri>() {
+            Err(e) => {
+                return Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is invalid")
+                    .with_context("endpoint", endpoint)
+                    .set_source(e));
+            }
+            Ok(uri) => uri,
+        };
+
+        match endpoint_uri.scheme_str() {
+            Some("ssh") | Some("sftp") | None => (),
+            Some(s) => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "endpoint is unsupported or invalid",
+                )
+                .with_context("endpoint", s));
+            }
+        }
+
+        // `Uri` will treat a bare hostname like `localhost` as path.
+        let host = endpoint_uri.host().unwrap_or(endpoint).to_string();
+        let port = endpoint_uri.port_u16().unwrap_or(22);
+
+        let known_hosts = match self.known_hosts_strategy.as_deref() {
+            None => KnownHosts::Strict,
+            Some(v) if v.eq_ignore_ascii_case("strict") => KnownHosts::Strict,
+            Some(v) if v.eq_ignore_ascii_case("add") => KnownHosts::Add,
+            Some(v) if v.eq_ignore_ascii_case("accept") => KnownHosts::Accept,
+            Some(v) => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "known_hosts_strategy is invalid",
+                )
+                .with_context("known_hosts_strategy", v));
+            }
+        };
+
+        let root = normalize_root(&self.root.take().unwrap_or_default());
+
+        debug!("sftp backend finished: {:?}", &self);
+
+        Ok(SftpBackend {
+            host,
+            port,
+            root,
+            user: self.user.take(),
+            key: self.key.take(),
+            known_hosts,
+            pool: OnceCell::new(),
+        })
+    }
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = SftpBuilder::default();
+
+        map.get("root").map(|v| builder.root(v));
+        map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("user").map(|v| builder.user(v));
+        map.get("key").map(|v| builder.key(v));
+        map.get("known_hosts_strategy")
+            .map(|v| builder.known_hosts_strategy(v));
+
+        builder
+    }
+}
+
+pub struct Manager {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    key: Option<String>,
+    known_hosts: KnownHosts,
+}
+
+#[async_trait]
+impl bb8::ManageConnection for Manager {
+    type Connection = Sftp;
+    type Error = Error;
+
+    async fn connect(&self) -> std::result::Result<Self::Connection, Self::Error> {
+        let mut builder = SessionBuilder::default();
+        builder
+            .port(self.port)
+            .known_hosts_check(self.known_hosts.clone());
+        if let Some(user) = &self.user {
+            builder.user(user.clone());
+        }
+        if let Some(key) = &self.key {
+            builder.keyfile(key);
+        }
+
+        let session = builder.connect(&self.host).await?;
+        let sftp = Sftp::from_session(session, SftpOptions::default()).await?;
+
+        Ok(sftp)
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> std::result::Result<(), Self::Error> {
+        conn.fs().metadata(".").await?;
+
+        Ok(())
+    }
+
+    /// Always allow reuse conn.
+    fn has_broken(&self, _: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+/// Backend is used to serve `Accessor` support for sftp.
+#[derive(Clone)]
+pub struct SftpBackend {
+    host: String,
+    port: u16,
+    root: String,
+    user: Option<String>,
+    key: Option<String>,
+    known_hosts: KnownHosts,
+    pool: OnceCell<bb8::Pool<Manager>>,
+}
+
+impl Debug for SftpBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Backend")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Accessor for SftpBackend {
+    type Reader = oio::into_reader::FdReader<Compat<Pin<Box<TokioCompatFile>>>>;
+    type BlockingReader = ();
+    type Writer = SftpWriter;
+    type BlockingWriter = ();
+    type Pager = SftpPager;
+    type BlockingPager = ();
+
+    fn info(&self) -> AccessorInfo {
+        let mut am = AccessorInfo::default();
+        am.set_scheme(Scheme::Sftp)
+            .set_root(&self.root)
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Append
+                    | AccessorCapability::List
+                    | AccessorCapability::ListWithLimit
+                    | AccessorCapability::Rename,
+            );
+
+        am
+    }
+
+    async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        sftp_create_dir_all(&mut sftp.fs(), &p).await?;
+
+        Ok(RpCreate::default())
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        use oio::ReadExt;
+
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let mut f = sftp.open(&p).await?;
+        let meta = parse_metadata(f.metadata().await?);
+        if meta.mode().is_dir() {
+            return Err(Error::new(
+                ErrorKind::IsADirectory,
+                "given path is a directory",
+            ));
+        }
+        let total_length = meta.content_length();
+
+        let br = args.range();
+        let (start, end) = match (br.offset(), br.size()) {
+            // Read a specific range.
+            (Some(offset), Some(size)) => (offset, min(offset + size, total_length)),
+            // Read from offset.
+            (Some(offset), None) => (offset, total_length),
+            // Read the last size bytes.
+            (None, Some(size)) => (
+                if total_length > size {
+                    total_length - size
+                } else {
+                    0
+                },
+                total_length,
+            ),
+            // Read the whole file.
+            (None, None) => (0, total_length),
+        };
+
+        let f = Compat::new(Box::pin(TokioCompatFile::from(f)));
+        let mut r = oio::into_reader::from_fd(f, start, end);
+
+        // Rewind to make sure we are on the correct offset.
+        r.seek(SeekFrom::Start(0)).await?;
+
+        Ok((RpRead::new(end - start), r))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let parent = get_parent(&p);
+        if parent != "/" {
+            sftp_create_dir_all(&mut sftp.fs(), parent).await?;
+        }
+
+        let f = if args.append() {
+            let mut f = sftp.options().write(true).create(true).open(&p).await?;
+            // SFTP has no portable append mode, so we seek to the end of
+            // the existing file instead.
+            let size = f.metadata().await?.len().unwrap_or_default();
+            f.seek(SeekFrom::Start(size)).await.map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "seek sftp file").set_source(err)
+            })?;
+            f
+        } else {
+            sftp.options()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&p)
+                .await?
+        };
+
+        Ok((RpWrite::new(), SftpWriter::new(f)))
+    }
+
+    async fn rename(&self, from: &str, to: &str, _: OpRename) -> Result<RpRename> {
+        let sftp = self.sftp_connect().await?;
+        let from = build_rooted_abs_path(&self.root, from);
+        let to = build_rooted_abs_path(&self.root, to);
+
+        let mut fs = sftp.fs();
+        let parent = get_parent(&to);
+        if parent != "/" {
+            sftp_create_dir_all(&mut fs, parent).await?;
+        }
+
+        // SFTP v3 rename fails if target exists, remove it first.
+        match fs.remove_file(&to).await.map_err(Error::from) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::NotFound => (),
+            Err(err) => return Err(err),
+        }
+        fs.rename(&from, &to).await?;
+
+        Ok(RpRename::default())
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let meta = parse_metadata(sftp.fs().metadata(&p).await?);
+
+        // Make sure the mode matches the path, as `stat("file/")` will
+        // succeed on some servers.
+        check_path_mode(path, meta.mode())?;
+
+        Ok(RpStat::new(meta))
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let mut fs = sftp.fs();
+
+        // Path is not match with the entry mode, which means the entry
+        // doesn't exist.
+        match fs.metadata(&p).await.map_err(Error::from) {
+            Ok(meta) if check_path_mode(path, parse_metadata(meta).mode()).is_err() => {
+                return Ok(RpDelete::default())
+            }
+            Ok(_) => (),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(RpDelete::default()),
+            Err(err) => return Err(err),
+        }
+
+        let result = if path.ends_with('/') {
+            fs.remove_dir(&p).await
+        } else {
+            fs.remove_file(&p).await
+        };
+
+        match result.map_err(Error::from) {
+            Ok(()) => Ok(RpDelete::default()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(RpDelete::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let sftp = self.sftp_connect().await?;
+        let p = build_rooted_abs_path(&self.root, path);
+
+        let mut fs = sftp.fs();
+        let dir = match fs.open_dir(&p).await.map_err(Error::from) {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Ok((
+                    RpList::default(),
+                    SftpPager::new(path, vec![], args.limit()),
+                ))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut stream = Box::pin(dir.read_dir());
+        let mut entries: Vec<DirEntry> = Vec::new();
+        while let Some(de) = stream.next().await {
+            entries.push(de?);
+        }
+
+        let path = if path == "/" { "" } else { path };
+        Ok((
+            RpList::default(),
+            SftpPager::new(path, entries, args.limit()),
+        ))
+    }
+}
+
+impl SftpBackend {
+    pub async fn sftp_connect(&self) -> Result<PooledConnection<'static, Manager>> {
+        let pool = self
+            .pool
+            .get_or_try_init(|| async {
+                bb8::Pool::builder()
+                    .max_size(16)
+                    .build(Manager {
+                        host: self.host.clone(),
+                        port: self.port,
+                        user: self.user.clone(),
+                        key: self.key.clone(),
+                        known_hosts: self.known_hosts.clone(),
+                    })
+                    .await
+            })
+            .await?;
+
+        pool.get_owned().await.map_err(|err| match err {
+            RunError::User(err) => err,
+            RunError::TimedOut => {
+                Error::new(ErrorKind::Unexpected, "connection request: timeout").set_temporary()
+            }
+        })
+    }
+}
+
+/// Create dir and all its parents.
+///
+/// SFTP v3 returns a generic failure while creating an existing dir, so
+/// we will check the dir via stat before treat it as an error.
+async fn sftp_create_dir_all(fs: &mut Fs, path: &str) -> Result<()> {
+    let mut curr_path = String::new();
+
+    for p in path.split_inclusive('/') {
+        curr_path.push_str(p);
+        if curr_path == "/" {
+            continue;
+        }
+
+        if let Err(err) = fs.create_dir(&curr_path).await {
+            match fs.metadata(&curr_path).await {
+                Ok(meta) if meta.file_type().map(|v| v.is_dir()).unwrap_or_default() => (),
+                _ => return Err(err.into()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Translate sftp file attrs into [`Metadata`].
+pub(super) fn parse_metadata(meta: MetaData) -> Metadata {
+    let mode = match meta.file_type() {
+        Some(t) if t.is_dir() => EntryMode::DIR,
+        Some(t) if t.is_file() => EntryMode::FILE,
+        _ => EntryMode::Unknown,
+    };
+
+    let mut m = Metadata::new(mode);
+    if let Some(size) = meta.len() {
+        m.set_content_length(size);
+    }
+    if let Some(modified) = meta.modified() {
+        m.set_last_modified(DateTime::<Utc>::from(modified.as_system_time()));
+    }
+
+    m
+}
+
+#[cfg(test)]
+mod build_test {
+    use super::SftpBuilder;
+    use crate::*;
+
+    #[test]
+    fn test_build() {
+        // ssh scheme, should suffix with default port 22
+        let mut builder = SftpBuilder::default();
+        builder.endpoint("ssh://sftp_server.local");
+        let b = builder.build();
+        assert!(b.is_ok());
+
+        // no scheme
+        let mut builder = SftpBuilder::default();
+        builder.endpoint("sftp_server.local:2222");
+        let b = builder.build();
+        assert!(b.is_ok());
+
+        // bare hostname
+        let mut builder = SftpBuilder::default();
+        builder.endpoint("localhost");
+        let b = builder.build();
+        assert!(b.is_ok());
+
+        // invalid scheme
+        let mut builder = SftpBuilder::default();
+        builder.endpoint("ftp://sftp_server.local:8765");
+        let b = builder.build();
+        assert!(b.is_err());
+        assert_eq!(b.unwrap_err().kind(), ErrorKind::ConfigInvalid);
+
+        // invalid known hosts strategy
+        let mut builder = SftpBuilder::default();
+        builder
+            .endpoint("ssh://sftp_server.local")
+            .known_hosts_strategy("unknown");
+        let b = builder.build();
+        assert!(b.is_err());
+        assert_eq!(b.unwrap_err().kind(), ErrorKind::ConfigInvalid);
+    }
+}
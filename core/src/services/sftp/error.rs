@@ -0,0 +1,58 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use openssh::Error as SshError;
+use openssh_sftp_client::error::SftpErrorKind;
+use openssh_sftp_client::Error as SftpClientError;
+
+use crate::Error;
+use crate::ErrorKind;
+
+impl From<SftpClientError> for Error {
+    fn from(e: SftpClientError) -> Self {
+        let (kind, retryable) = match &e {
+            SftpClientError::SftpError(kind, _) => match kind {
+                SftpErrorKind::NoSuchFile => (ErrorKind::NotFound, false),
+                SftpErrorKind::PermDenied => (ErrorKind::PermissionDenied, false),
+                SftpErrorKind::OpUnsupported => (ErrorKind::Unsupported, false),
+                _ => (ErrorKind::Unexpected, false),
+            },
+            // The underlying ssh process or channel is broken, allow retry
+            // so that we can get a fresh connection from pool.
+            SftpClientError::IOError(_)
+            | SftpClientError::BackgroundTaskFailure(_)
+            | SftpClientError::RemoteChildSpawnError(_) => (ErrorKind::Unexpected, true),
+            _ => (ErrorKind::Unexpected, false),
+        };
+
+        let mut err = Error::new(kind, "sftp error").set_source(e);
+
+        if retryable {
+            err = err.set_temporary();
+        }
+
+        err
+    }
+}
+
+impl From<SshError> for Error {
+    fn from(e: SshError) -> Self {
+        Error::new(ErrorKind::Unexpected, "ssh error")
+            .set_source(e)
+            .set_temporary()
+    }
+}
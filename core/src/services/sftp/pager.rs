@@ -0,0 +1,71 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::vec::IntoIter;
+
+use async_trait::async_trait;
+use openssh_sftp_client::fs::DirEntry;
+
+use super::backend::parse_metadata;
+use crate::raw::*;
+use crate::*;
+
+pub struct SftpPager {
+    path: String,
+    size: usize,
+    entries: IntoIter<DirEntry>,
+}
+
+impl SftpPager {
+    pub fn new(path: &str, entries: Vec<DirEntry>, limit: Option<usize>) -> Self {
+        Self {
+            path: path.to_string(),
+            size: limit.unwrap_or(1000),
+            entries: entries.into_iter(),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Page for SftpPager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        let mut oes: Vec<oio::Entry> = Vec::with_capacity(self.size);
+
+        while oes.len() < self.size {
+            let de = match self.entries.next() {
+                Some(de) => de,
+                None => break,
+            };
+
+            let name = de.filename().to_string_lossy();
+            if name == "." || name == ".." {
+                continue;
+            }
+
+            let meta = parse_metadata(de.metadata());
+            let path = if meta.mode().is_dir() {
+                format!("{}{}/", self.path, name)
+            } else {
+                format!("{}{}", self.path, name)
+            };
+
+            oes.push(oio::Entry::new(&path, meta))
+        }
+
+        Ok(if oes.is_empty() { None } else { Some(oes) })
+    }
+}
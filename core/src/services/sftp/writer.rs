@@ -0,0 +1,80 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::SeekFrom;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use openssh_sftp_client::file::File;
+use tokio::io::AsyncSeekExt;
+
+use crate::raw::*;
+use crate::*;
+
+pub struct SftpWriter {
+    f: Option<File>,
+}
+
+impl SftpWriter {
+    pub fn new(f: File) -> Self {
+        Self { f: Some(f) }
+    }
+
+    fn file(&mut self) -> Result<&mut File> {
+        self.f
+            .as_mut()
+            .ok_or_else(|| Error::new(ErrorKind::Unexpected, "sftp writer has been closed"))
+    }
+}
+
+#[async_trait]
+impl oio::Write for SftpWriter {
+    /// # Notes
+    ///
+    /// File could be partial written, so we will seek to start to make sure
+    /// we write the same content.
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        let f = self.file()?;
+        f.seek(SeekFrom::Start(0))
+            .await
+            .map_err(|err| Error::new(ErrorKind::Unexpected, "seek sftp file").set_source(err))?;
+        f.write_all(&bs).await?;
+
+        Ok(())
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.file()?.write_all(&bs).await?;
+
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "output writer doesn't support abort",
+        ))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if let Some(f) = self.f.take() {
+            f.close().await?;
+        }
+
+        Ok(())
+    }
+}
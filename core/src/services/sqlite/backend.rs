@@ -0,0 +1,416 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io::Read;
+use std::io::Write;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::DatabaseName;
+use rusqlite::OptionalExtension;
+
+use crate::raw::adapters::kv;
+use crate::raw::*;
+use crate::*;
+
+/// The default table name used to store entries.
+const DEFAULT_TABLE: &str = "opendal";
+
+/// Sqlite service support.
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [x] scan
+/// - [ ] ~~presign~~
+/// - [x] blocking
+///
+/// # Notes
+///
+/// Entries are stored in a table like:
+///
+/// ```sql
+/// CREATE TABLE IF NOT EXISTS opendal (
+///     key TEXT PRIMARY KEY,
+///     value BLOB,
+///     last_modified INTEGER,
+///     content_type TEXT
+/// )
+/// ```
+///
+/// The table will be created while building if not exists. `last_modified`
+/// is the unix timestamp in milliseconds of the last write.
+///
+/// The database is opened in WAL mode so that readers won't be blocked by
+/// writers. Values are read and written via sqlite's incremental blob I/O.
+///
+/// # Configuration
+///
+/// - `root`: Set the working directory of `OpenDAL`
+/// - `path`: Set the path to the sqlite database file
+/// - `table`: Set the table to store entries, default to `opendal`
+///
+/// You can refer to [`SqliteBuilder`]'s docs for more information
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::Sqlite;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mut builder = Sqlite::default();
+///     builder.path("/tmp/opendal/sqlite.db");
+///     builder.table("data");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///     Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct SqliteBuilder {
+    /// The path to the sqlite database file.
+    path: Option<String>,
+    /// The table to store entries.
+    table: Option<String>,
+    root: Option<String>,
+}
+
+impl Debug for SqliteBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("SqliteBuilder");
+        ds.field("path", &self.path);
+        ds.field("table", &self.table);
+        ds.field("root", &self.root);
+        ds.finish()
+    }
+}
+
+impl SqliteBuilder {
+    /// Set the path to the sqlite database file. Will create if not exists.
+    pub fn path(&mut self, path: &str) -> &mut Self {
+        if !path.is_empty() {
+            self.path = Some(path.to_string());
+        }
+        self
+    }
+
+    /// Set the table to store entries.
+    ///
+    /// Only ASCII letters, digits and `_` are allowed.
+    ///
+    /// default: "opendal"
+    pub fn table(&mut self, table: &str) -> &mut Self {
+        if !table.is_empty() {
+            self.table = Some(table.to_string());
+        }
+        self
+    }
+
+    /// Set the root for sqlite.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string());
+        }
+        self
+    }
+}
+
+impl Builder for SqliteBuilder {
+    const SCHEME: Scheme = Scheme::Sqlite;
+    type Accessor = SqliteBackend;
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = SqliteBuilder::default();
+
+        map.get("path").map(|v| builder.path(v));
+        map.get("table").map(|v| builder.table(v));
+        map.get("root").map(|v| builder.root(v));
+
+        builder
+    }
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        let path = self.path.take().ok_or_else(|| {
+            Error::new(ErrorKind::ConfigInvalid, "path is required but not set")
+                .with_context("service", Scheme::Sqlite)
+        })?;
+        let table = self
+            .table
+            .take()
+            .unwrap_or_else(|| DEFAULT_TABLE.to_string());
+        if !is_valid_table_name(&table) {
+            return Err(
+                Error::new(ErrorKind::ConfigInvalid, "table name is invalid")
+                    .with_context("service", Scheme::Sqlite)
+                    .with_context("table", table),
+            );
+        }
+
+        let pool = r2d2::Pool::builder()
+            .build(Manager { path: path.clone() })
+            .map_err(|err| {
+                Error::new(ErrorKind::ConfigInvalid, "open sqlite database")
+                    .with_context("service", Scheme::Sqlite)
+                    .with_context("path", &path)
+                    .set_source(err)
+            })?;
+
+        let adapter = Adapter { path, table, pool };
+        adapter.create_table().map_err(|err| {
+            err.with_operation("Builder::build")
+                .with_context("service", Scheme::Sqlite)
+        })?;
+
+        Ok(SqliteBackend::new(adapter).with_root(self.root.as_deref().unwrap_or_default()))
+    }
+}
+
+/// Backend for sqlite services.
+pub type SqliteBackend = kv::Backend<Adapter>;
+
+/// Manager is used to open sqlite connections for the pool.
+struct Manager {
+    path: String,
+}
+
+impl r2d2::ManageConnection for Manager {
+    type Connection = Connection;
+    type Error = rusqlite::Error;
+
+    fn connect(&self) -> std::result::Result<Connection, rusqlite::Error> {
+        let conn = Connection::open(&self.path)?;
+        // WAL allows readers to work concurrently with the writer.
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        // Wait for other writers instead of failing immediately.
+        conn.busy_timeout(Duration::from_secs(5))?;
+
+        Ok(conn)
+    }
+
+    fn is_valid(&self, conn: &mut Connection) -> std::result::Result<(), rusqlite::Error> {
+        conn.execute_batch("")
+    }
+
+    fn has_broken(&self, _: &mut Connection) -> bool {
+        false
+    }
+}
+
+#[derive(Clone)]
+pub struct Adapter {
+    path: String,
+    table: String,
+    pool: r2d2::Pool<Manager>,
+}
+
+impl Debug for Adapter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Adapter");
+        ds.field("path", &self.path);
+        ds.field("table", &self.table);
+        ds.finish()
+    }
+}
+
+impl Adapter {
+    fn get_connection(&self) -> Result<r2d2::PooledConnection<Manager>> {
+        self.pool.get().map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "get connection from pool")
+                .with_context("path", &self.path)
+                .set_source(err)
+                .set_temporary()
+        })
+    }
+
+    fn create_table(&self) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                key TEXT PRIMARY KEY,
+                value BLOB,
+                last_modified INTEGER,
+                content_type TEXT
+            )",
+            self.table
+        ))
+        .map_err(parse_error)
+    }
+
+    fn row_id(conn: &Connection, table: &str, path: &str) -> Result<Option<i64>> {
+        conn.query_row(
+            &format!("SELECT rowid FROM {table} WHERE key = ?1"),
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(parse_error)
+    }
+}
+
+#[async_trait]
+impl kv::Adapter for Adapter {
+    fn metadata(&self) -> kv::Metadata {
+        use AccessorCapability::*;
+        kv::Metadata::new(Scheme::Sqlite, &self.path, Read | Write | Scan | Blocking)
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        self.blocking_get(path)
+    }
+
+    fn blocking_get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let conn = self.get_connection()?;
+
+        let row_id = match Self::row_id(&conn, &self.table, path)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let mut blob = conn
+            .blob_open(DatabaseName::Main, &self.table, "value", row_id, true)
+            .map_err(parse_error)?;
+        let mut bs = Vec::with_capacity(blob.len());
+        blob.read_to_end(&mut bs).map_err(parse_io_error)?;
+
+        Ok(Some(bs))
+    }
+
+    async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+        self.blocking_set(path, value)
+    }
+
+    fn blocking_set(&self, path: &str, value: &[u8]) -> Result<()> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction().map_err(parse_error)?;
+
+        let last_modified = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        // Allocate the blob first, then write the value incrementally.
+        tx.execute(
+            &format!(
+                "INSERT INTO {} (key, value, last_modified) VALUES (?1, zeroblob(?2), ?3)
+                ON CONFLICT(key) DO UPDATE SET
+                    value = excluded.value,
+                    last_modified = excluded.last_modified",
+                self.table
+            ),
+            params![path, value.len() as i64, last_modified],
+        )
+        .map_err(parse_error)?;
+
+        let row_id = Self::row_id(&tx, &self.table, path)?
+            .ok_or_else(|| Error::new(ErrorKind::Unexpected, "inserted row is missing"))?;
+        let mut blob = tx
+            .blob_open(DatabaseName::Main, &self.table, "value", row_id, false)
+            .map_err(parse_error)?;
+        blob.write_all(value).map_err(parse_io_error)?;
+        drop(blob);
+
+        tx.commit().map_err(parse_error)
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        self.blocking_delete(path)
+    }
+
+    fn blocking_delete(&self, path: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            &format!("DELETE FROM {} WHERE key = ?1", self.table),
+            params![path],
+        )
+        .map_err(parse_error)?;
+
+        Ok(())
+    }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        self.blocking_scan(path)
+    }
+
+    /// Keys are indexed by primary key, so we seek to the prefix and stop
+    /// at the first key that doesn't start with it.
+    fn blocking_scan(&self, path: &str) -> Result<Vec<String>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT key FROM {} WHERE key >= ?1 ORDER BY key",
+                self.table
+            ))
+            .map_err(parse_error)?;
+        let mut rows = stmt.query(params![path]).map_err(parse_error)?;
+
+        let mut res = Vec::default();
+        while let Some(row) = rows.next().map_err(parse_error)? {
+            let key: String = row.get(0).map_err(parse_error)?;
+            if !key.starts_with(path) {
+                break;
+            }
+            res.push(key);
+        }
+
+        Ok(res)
+    }
+}
+
+/// Table name will be used in sql directly, so we only allow a safe subset.
+fn is_valid_table_name(table: &str) -> bool {
+    !table.is_empty()
+        && !table.starts_with(|c: char| c.is_ascii_digit())
+        && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_error(err: rusqlite::Error) -> Error {
+    Error::new(ErrorKind::Unexpected, "error from sqlite").set_source(err)
+}
+
+fn parse_io_error(err: std::io::Error) -> Error {
+    Error::new(ErrorKind::Unexpected, "read or write sqlite blob").set_source(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_table_name() {
+        assert!(is_valid_table_name("opendal"));
+        assert!(is_valid_table_name("data_v2"));
+        assert!(!is_valid_table_name(""));
+        assert!(!is_valid_table_name("2data"));
+        assert!(!is_valid_table_name("data; DROP TABLE x"));
+        assert!(!is_valid_table_name("\"data\""));
+    }
+}
@@ -0,0 +1,451 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http::StatusCode;
+use log::debug;
+
+use super::core::SwiftCore;
+use super::error::parse_error;
+use super::pager::SwiftPager;
+use super::writer::SwiftWriter;
+use crate::ops::*;
+use crate::raw::*;
+use crate::*;
+
+/// The default segment size for large objects: 64 MiB.
+const DEFAULT_SEGMENT_SIZE: usize = 64 * 1024 * 1024;
+/// Swift requires segments of SLO (except the last one) to be at least 1 MiB by default.
+const MIN_SEGMENT_SIZE: usize = 1024 * 1024;
+/// Swift limits the size of single object to 5 GiB by default.
+const MAX_SEGMENT_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// [OpenStack Swift](https://docs.openstack.org/swift/latest/) object storage support.
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] copy
+/// - [x] list
+/// - [x] scan
+/// - [ ] presign
+/// - [ ] blocking
+///
+/// # Notes
+///
+/// Content larger than `segment_size` will be uploaded as a
+/// [Static Large Object](https://docs.openstack.org/swift/latest/overview_large_objects.html#static-large-objects),
+/// whose segments are stored in container `<container>_segments`. Deleting
+/// the object will delete its segments too.
+///
+/// # Configuration
+///
+/// - `endpoint`: Set the storage url of the account, like `https://127.0.0.1:8080/v1/AUTH_test`.
+/// - `container`: Set the container for backend.
+/// - `token`: Set the auth token which will be sent in `X-Auth-Token`.
+/// - `root`: Set the work dir for backend.
+/// - `segment_size`: Set the segment size of large objects, default to 64 MiB.
+///
+/// Refer to public API docs for more information.
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::Swift;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     // Create swift backend builder.
+///     let mut builder = Swift::default();
+///
+///     // Set the storage url of the account, this is required.
+///     builder.endpoint("https://127.0.0.1:8080/v1/AUTH_test");
+///     // Set the container name, this is required.
+///     builder.container("test");
+///     // Set the auth token, which could be fetched from keystone.
+///     builder.token("token");
+///     // Set the root for swift, all operations will happen under this root.
+///     //
+///     // NOTE: the root must be absolute path.
+///     builder.root("/path/to/dir");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct SwiftBuilder {
+    endpoint: Option<String>,
+    container: Option<String>,
+    token: Option<String>,
+    root: Option<String>,
+    segment_size: Option<usize>,
+    http_client: Option<HttpClient>,
+}
+
+impl Debug for SwiftBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Builder");
+
+        ds.field("endpoint", &self.endpoint);
+        ds.field("container", &self.container);
+        ds.field("root", &self.root);
+        ds.field("segment_size", &self.segment_size);
+
+        if self.token.is_some() {
+            ds.field("token", &"<redacted>");
+        }
+
+        ds.finish()
+    }
+}
+
+impl SwiftBuilder {
+    /// Set the storage url of the account.
+    ///
+    /// Storage url is returned by the auth service, e.g.
+    ///
+    /// - `https://127.0.0.1:8080/v1/AUTH_test`
+    pub fn endpoint(&mut self, endpoint: &str) -> &mut Self {
+        if !endpoint.is_empty() {
+            // Trim trailing `/` so that we can accept `http://127.0.0.1:8080/v1/AUTH_test/`
+            self.endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        }
+
+        self
+    }
+
+    /// Set container of this backend.
+    pub fn container(&mut self, container: &str) -> &mut Self {
+        if !container.is_empty() {
+            self.container = Some(container.to_string());
+        }
+
+        self
+    }
+
+    /// Set the auth token of this backend.
+    ///
+    /// Token will be sent in `X-Auth-Token` header, requests will be sent
+    /// without token if not set.
+    pub fn token(&mut self, token: &str) -> &mut Self {
+        if !token.is_empty() {
+            self.token = Some(token.to_string());
+        }
+
+        self
+    }
+
+    /// Set root of this backend.
+    ///
+    /// All operations will happen under this root.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string())
+        }
+
+        self
+    }
+
+    /// Set the segment size of large objects.
+    ///
+    /// Content larger than segment size will be uploaded as Static Large
+    /// Object. Segment size must be in range `[1 MiB, 5 GiB]`, default to
+    /// 64 MiB.
+    pub fn segment_size(&mut self, segment_size: usize) -> &mut Self {
+        self.segment_size = Some(segment_size);
+
+        self
+    }
+
+    /// Specify the http client that used by this service.
+    ///
+    /// # Notes
+    ///
+    /// This API is part of OpenDAL's Raw API. `HttpClient` could be changed
+    /// during minor updates.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+}
+
+impl Builder for SwiftBuilder {
+    type Accessor = SwiftBackend;
+    const SCHEME: Scheme = Scheme::Swift;
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        debug!("backend build started: {:?}", &self);
+
+        let root = normalize_root(&self.root.take().unwrap_or_default());
+        debug!("backend use root {}", root);
+
+        let endpoint = match &self.endpoint {
+            Some(endpoint) => Ok(endpoint.clone()),
+            None => Err(Error::new(ErrorKind::ConfigInvalid, "endpoint is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Swift)),
+        }?;
+        debug!("backend use endpoint {}", &endpoint);
+
+        let container = match &self.container {
+            Some(container) => Ok(container.clone()),
+            None => Err(Error::new(ErrorKind::ConfigInvalid, "container is empty")
+                .with_operation("Builder::build")
+                .with_context("service", Scheme::Swift)),
+        }?;
+        debug!("backend use container {}", &container);
+
+        let segment_size = self.segment_size.unwrap_or(DEFAULT_SEGMENT_SIZE);
+        if segment_size < MIN_SEGMENT_SIZE || segment_size as u64 > MAX_SEGMENT_SIZE {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "segment_size must be in range [1 MiB, 5 GiB]",
+            )
+            .with_operation("Builder::build")
+            .with_context("service", Scheme::Swift)
+            .with_context("segment_size", segment_size.to_string()));
+        }
+
+        let client = if let Some(client) = self.http_client.take() {
+            client
+        } else {
+            HttpClient::new().map_err(|err| {
+                err.with_operation("Builder::build")
+                    .with_context("service", Scheme::Swift)
+            })?
+        };
+
+        debug!("backend build finished: {:?}", &self);
+        Ok(SwiftBackend {
+            core: Arc::new(SwiftCore {
+                endpoint,
+                container,
+                root,
+                token: self.token.clone(),
+                segment_size,
+                client,
+            }),
+        })
+    }
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = SwiftBuilder::default();
+
+        map.get("endpoint").map(|v| builder.endpoint(v));
+        map.get("container").map(|v| builder.container(v));
+        map.get("token").map(|v| builder.token(v));
+        map.get("root").map(|v| builder.root(v));
+        map.get("segment_size")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(|v| builder.segment_size(v));
+
+        builder
+    }
+}
+
+/// Backend for swift services.
+#[derive(Debug, Clone)]
+pub struct SwiftBackend {
+    core: Arc<SwiftCore>,
+}
+
+#[async_trait]
+impl Accessor for SwiftBackend {
+    type Reader = IncomingAsyncBody;
+    type BlockingReader = ();
+    type Writer = SwiftWriter;
+    type BlockingWriter = ();
+    type Pager = SwiftPager;
+    type BlockingPager = ();
+
+    fn info(&self) -> AccessorInfo {
+        let mut am = AccessorInfo::default();
+        am.set_scheme(Scheme::Swift)
+            .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
+            .set_name(&self.core.container)
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Append
+                    | AccessorCapability::Copy
+                    | AccessorCapability::List
+                    | AccessorCapability::Scan
+                    | AccessorCapability::WriteWithContentType
+                    | AccessorCapability::WriteWithContentDisposition
+                    | AccessorCapability::ListWithLimit,
+            )
+            .set_hints(AccessorHint::ReadStreamable | AccessorHint::ImplicitDir);
+
+        am
+    }
+
+    async fn create_dir(&self, path: &str, _: OpCreate) -> Result<RpCreate> {
+        let resp = self
+            .core
+            .swift_put_object(path, Some(0), &OpWrite::default(), AsyncBody::Empty)
+            .await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpCreate::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        let resp = self.core.swift_get_object(path, args.range()).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => {
+                let meta = parse_into_metadata(path, resp.headers())?;
+                Ok((RpRead::with_metadata(meta), resp.into_body()))
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        Ok((
+            RpWrite::default(),
+            SwiftWriter::new(self.core.clone(), args, path.to_string()),
+        ))
+    }
+
+    async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
+        let resp = self.core.swift_copy_object(from, to).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(RpCopy::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
+        // Stat root always returns a DIR.
+        if path == "/" {
+            return Ok(RpStat::new(Metadata::new(EntryMode::DIR)));
+        }
+
+        let resp = self.core.swift_head_object(path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<RpDelete> {
+        let resp = self.core.swift_delete_object(path).await?;
+
+        let status = resp.status();
+
+        match status {
+            StatusCode::NO_CONTENT | StatusCode::OK | StatusCode::NOT_FOUND => {
+                resp.into_body().consume().await?;
+                Ok(RpDelete::default())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Pager)> {
+        let op = SwiftPager::new(self.core.clone(), path, "/", args.limit());
+
+        Ok((RpList::default(), op))
+    }
+
+    async fn scan(&self, path: &str, args: OpScan) -> Result<(RpScan, Self::Pager)> {
+        let op = SwiftPager::new(self.core.clone(), path, "", args.limit());
+
+        Ok((RpScan::default(), op))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_from_map() {
+        let mut map = HashMap::new();
+        map.insert(
+            "endpoint".to_string(),
+            "http://127.0.0.1:8080/v1/AUTH_test/".to_string(),
+        );
+        map.insert("container".to_string(), "test".to_string());
+        map.insert("token".to_string(), "token".to_string());
+        map.insert("segment_size".to_string(), "1048576".to_string());
+
+        let mut builder = SwiftBuilder::from_map(map);
+        assert_eq!(
+            builder.endpoint.as_deref(),
+            Some("http://127.0.0.1:8080/v1/AUTH_test")
+        );
+        assert_eq!(builder.segment_size, Some(1048576));
+
+        builder.build().expect("build must succeed");
+    }
+
+    #[test]
+    fn test_builder_requires_container() {
+        let mut builder = SwiftBuilder::default();
+        builder.endpoint("http://127.0.0.1:8080/v1/AUTH_test");
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+
+    #[test]
+    fn test_builder_rejects_small_segment_size() {
+        let mut builder = SwiftBuilder::default();
+        builder
+            .endpoint("http://127.0.0.1:8080/v1/AUTH_test")
+            .container("test")
+            .segment_size(1024);
+
+        let err = builder.build().expect_err("build must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
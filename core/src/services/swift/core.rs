@@ -0,0 +1,340 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Write;
+
+use bytes::Bytes;
+use http::header::CONTENT_DISPOSITION;
+use http::header::CONTENT_LENGTH;
+use http::header::CONTENT_TYPE;
+use http::header::RANGE;
+use http::Request;
+use http::Response;
+use serde::Serialize;
+
+use crate::ops::OpWrite;
+use crate::raw::*;
+use crate::*;
+
+const X_AUTH_TOKEN: &str = "x-auth-token";
+const X_COPY_FROM: &str = "x-copy-from";
+
+pub struct SwiftCore {
+    /// The storage url of the account, like `https://127.0.0.1:8080/v1/AUTH_test`.
+    pub endpoint: String,
+    pub container: String,
+    pub root: String,
+    pub token: Option<String>,
+    /// Writes larger than segment size will be uploaded as SLO.
+    pub segment_size: usize,
+
+    pub client: HttpClient,
+}
+
+impl Debug for SwiftCore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwiftCore")
+            .field("endpoint", &self.endpoint)
+            .field("container", &self.container)
+            .field("root", &self.root)
+            .field("segment_size", &self.segment_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A segment in the manifest of Static Large Object.
+#[derive(Debug, Serialize)]
+pub struct SloSegment {
+    /// The path of segment in the form of `/container/object`.
+    pub path: String,
+    pub etag: Option<String>,
+    pub size_bytes: u64,
+}
+
+impl SwiftCore {
+    pub fn sign<T>(&self, req: &mut Request<T>) -> Result<()> {
+        if let Some(token) = &self.token {
+            req.headers_mut().insert(
+                X_AUTH_TOKEN,
+                token.parse().map_err(|err| {
+                    Error::new(
+                        ErrorKind::ConfigInvalid,
+                        "token is not a valid header value",
+                    )
+                    .set_source(err)
+                })?,
+            );
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn send(&self, req: Request<AsyncBody>) -> Result<Response<IncomingAsyncBody>> {
+        self.client.send(req).await
+    }
+
+    /// Segments are stored in the container `<container>_segments`, which
+    /// follows the convention of python-swiftclient.
+    pub fn segment_container(&self) -> String {
+        format!("{}_segments", self.container)
+    }
+
+    fn object_url(&self, container: &str, object: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint,
+            percent_encode_path(container),
+            percent_encode_path(object)
+        )
+    }
+
+    fn url(&self, path: &str) -> String {
+        let p = build_abs_path(&self.root, path);
+
+        self.object_url(&self.container, &p)
+    }
+}
+
+impl SwiftCore {
+    pub async fn swift_get_object(
+        &self,
+        path: &str,
+        range: BytesRange,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::get(&self.url(path));
+
+        if !range.is_full() {
+            req = req.header(RANGE, range.to_header());
+        }
+
+        let mut req = req
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_put_object(
+        &self,
+        path: &str,
+        size: Option<usize>,
+        args: &OpWrite,
+        body: AsyncBody,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::put(&self.url(path));
+
+        if let Some(size) = size {
+            req = req.header(CONTENT_LENGTH, size)
+        }
+        if let Some(ty) = args.content_type() {
+            req = req.header(CONTENT_TYPE, ty)
+        }
+        if let Some(pos) = args.content_disposition() {
+            req = req.header(CONTENT_DISPOSITION, pos)
+        }
+
+        let mut req = req.body(body).map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_head_object(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::head(&self.url(path))
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    /// Delete the object, the segments will be deleted too if the object
+    /// is a Static Large Object.
+    ///
+    /// `multipart-manifest=delete` will be ignored for normal objects.
+    pub async fn swift_delete_object(&self, path: &str) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!("{}?multipart-manifest=delete", self.url(path));
+
+        let mut req = Request::delete(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_copy_object(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let source = build_abs_path(&self.root, from);
+
+        let mut req = Request::put(&self.url(to))
+            .header(
+                X_COPY_FROM,
+                format!(
+                    "/{}/{}",
+                    percent_encode_path(&self.container),
+                    percent_encode_path(&source)
+                ),
+            )
+            .header(CONTENT_LENGTH, 0)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_list(
+        &self,
+        path: &str,
+        delimiter: &str,
+        marker: &str,
+        limit: usize,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+
+        let mut url = format!(
+            "{}/{}?format=json&limit={limit}",
+            self.endpoint,
+            percent_encode_path(&self.container)
+        );
+        if !p.is_empty() {
+            write!(url, "&prefix={}", percent_encode_path(&p))
+                .expect("write into string must succeed");
+        }
+        if !delimiter.is_empty() {
+            write!(url, "&delimiter={delimiter}").expect("write into string must succeed");
+        }
+        if !marker.is_empty() {
+            write!(url, "&marker={}", percent_encode_path(marker))
+                .expect("write into string must succeed");
+        }
+
+        let mut req = Request::get(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    /// Create the segment container, swift returns `202 Accepted` if the
+    /// container exists already.
+    pub async fn swift_create_segment_container(&self) -> Result<Response<IncomingAsyncBody>> {
+        let url = format!(
+            "{}/{}",
+            self.endpoint,
+            percent_encode_path(&self.segment_container())
+        );
+
+        let mut req = Request::put(&url)
+            .header(CONTENT_LENGTH, 0)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_put_segment(
+        &self,
+        segment: &str,
+        bs: Bytes,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::put(&self.object_url(&self.segment_container(), segment))
+            .header(CONTENT_LENGTH, bs.len())
+            .body(AsyncBody::Bytes(bs))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    pub async fn swift_delete_segment(&self, segment: &str) -> Result<Response<IncomingAsyncBody>> {
+        let mut req = Request::delete(&self.object_url(&self.segment_container(), segment))
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+
+    /// ref: https://docs.openstack.org/swift/latest/overview_large_objects.html#static-large-objects
+    pub async fn swift_put_slo_manifest(
+        &self,
+        path: &str,
+        segments: &[SloSegment],
+        args: &OpWrite,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let manifest = serde_json::to_vec(segments).map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "serialize slo manifest").set_source(err)
+        })?;
+
+        let url = format!("{}?multipart-manifest=put", self.url(path));
+
+        let mut req = Request::put(&url).header(CONTENT_LENGTH, manifest.len());
+
+        if let Some(ty) = args.content_type() {
+            req = req.header(CONTENT_TYPE, ty)
+        }
+        if let Some(pos) = args.content_disposition() {
+            req = req.header(CONTENT_DISPOSITION, pos)
+        }
+
+        let mut req = req
+            .body(AsyncBody::Bytes(Bytes::from(manifest)))
+            .map_err(new_request_build_error)?;
+
+        self.sign(&mut req)?;
+        self.send(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_slo_manifest() {
+        let segments = vec![
+            SloSegment {
+                path: "/test_segments/a/b/00000000".to_string(),
+                etag: Some("0228c7926b8b642dfb29554cd1f00963".to_string()),
+                size_bytes: 1048576,
+            },
+            SloSegment {
+                path: "/test_segments/a/b/00000001".to_string(),
+                etag: None,
+                size_bytes: 12,
+            },
+        ];
+
+        let bs = serde_json::to_string(&segments).expect("must success");
+        assert_eq!(
+            bs,
+            r#"[{"path":"/test_segments/a/b/00000000","etag":"0228c7926b8b642dfb29554cd1f00963","size_bytes":1048576},{"path":"/test_segments/a/b/00000001","etag":null,"size_bytes":12}]"#
+        );
+    }
+}
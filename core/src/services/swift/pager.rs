@@ -0,0 +1,224 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use http::StatusCode;
+use serde::Deserialize;
+use serde_json::de;
+
+use super::core::SwiftCore;
+use super::error::parse_error;
+use crate::raw::*;
+use crate::*;
+
+/// Swift returns at most 10000 objects in a page, we use a smaller default.
+const DEFAULT_LIST_LIMIT: usize = 1000;
+
+pub struct SwiftPager {
+    core: Arc<SwiftCore>,
+
+    path: String,
+    delimiter: String,
+    limit: usize,
+
+    marker: String,
+    done: bool,
+}
+
+impl SwiftPager {
+    pub fn new(core: Arc<SwiftCore>, path: &str, delimiter: &str, limit: Option<usize>) -> Self {
+        Self {
+            core,
+            path: path.to_string(),
+            delimiter: delimiter.to_string(),
+            limit: limit.unwrap_or(DEFAULT_LIST_LIMIT),
+
+            marker: "".to_string(),
+            done: false,
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Page for SwiftPager {
+    async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let resp = self
+            .core
+            .swift_list(&self.path, &self.delimiter, &self.marker, self.limit)
+            .await?;
+
+        let status = resp.status();
+        // Swift returns `204 No Content` if there is no objects.
+        if status == StatusCode::NO_CONTENT {
+            resp.into_body().consume().await?;
+            self.done = true;
+            return Ok(None);
+        }
+        if status != StatusCode::OK {
+            return Err(parse_error(resp).await?);
+        }
+
+        let bs = resp.into_body().bytes().await?;
+
+        let output: Vec<ListEntry> = de::from_slice(&bs).map_err(|e| {
+            Error::new(ErrorKind::Unexpected, "deserialize json from response").set_source(e)
+        })?;
+
+        self.done = output.len() < self.limit;
+
+        let prefix = build_abs_path(&self.core.root, &self.path);
+        let mut entries = Vec::with_capacity(output.len());
+
+        for entry in output {
+            let (name, de) = match entry {
+                ListEntry::Subdir { subdir } => {
+                    let de = oio::Entry::new(
+                        &build_rel_path(&self.core.root, &subdir),
+                        Metadata::new(EntryMode::DIR),
+                    );
+                    (subdir, de)
+                }
+                ListEntry::Object {
+                    name,
+                    bytes,
+                    hash,
+                    content_type,
+                    last_modified,
+                } => {
+                    let mode = if name.ends_with('/') {
+                        EntryMode::DIR
+                    } else {
+                        EntryMode::FILE
+                    };
+                    let mut meta = Metadata::new(mode).with_content_length(bytes);
+                    if !hash.is_empty() {
+                        meta.set_etag(&format!("\"{hash}\""));
+                    }
+                    if !content_type.is_empty() {
+                        meta.set_content_type(&content_type);
+                    }
+                    if !last_modified.is_empty() {
+                        meta.set_last_modified(parse_list_datetime(&last_modified)?);
+                    }
+
+                    let de = oio::Entry::new(&build_rel_path(&self.core.root, &name), meta);
+                    (name, de)
+                }
+            };
+
+            // The marker should be updated before skipping the dir itself,
+            // otherwise we will list it again and again.
+            self.marker = name.clone();
+
+            // Skip the dir itself.
+            if name == prefix {
+                continue;
+            }
+
+            entries.push(de);
+        }
+
+        Ok(Some(entries))
+    }
+}
+
+/// Swift returns `last_modified` without timezone, which is always UTC.
+///
+/// For example: `2016-04-28T16:03:52.000000`
+fn parse_list_datetime(s: &str) -> Result<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|v| DateTime::from_utc(v, Utc))
+        .map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "parse datetime from swift list").set_source(err)
+        })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ListEntry {
+    Subdir {
+        subdir: String,
+    },
+    Object {
+        name: String,
+        bytes: u64,
+        #[serde(default)]
+        hash: String,
+        #[serde(default)]
+        content_type: String,
+        #[serde(default)]
+        last_modified: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_output() {
+        let bs = r#"[
+            {"subdir": "dir/sub/"},
+            {
+                "hash": "451e372e48e0f6b1114fa0724aa79fa1",
+                "last_modified": "2014-01-15T16:41:49.390270",
+                "bytes": 14,
+                "name": "dir/goodbye",
+                "content_type": "application/octet-stream"
+            }
+        ]"#;
+
+        let out: Vec<ListEntry> = de::from_str(bs).expect("must success");
+        assert_eq!(out.len(), 2);
+
+        match &out[0] {
+            ListEntry::Subdir { subdir } => assert_eq!(subdir, "dir/sub/"),
+            v => panic!("unexpected entry: {v:?}"),
+        }
+        match &out[1] {
+            ListEntry::Object {
+                name,
+                bytes,
+                hash,
+                content_type,
+                last_modified,
+            } => {
+                assert_eq!(name, "dir/goodbye");
+                assert_eq!(*bytes, 14);
+                assert_eq!(hash, "451e372e48e0f6b1114fa0724aa79fa1");
+                assert_eq!(content_type, "application/octet-stream");
+                assert_eq!(last_modified, "2014-01-15T16:41:49.390270");
+            }
+            v => panic!("unexpected entry: {v:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_list_datetime() {
+        let t = parse_list_datetime("2014-01-15T16:41:49.390270").expect("must success");
+        assert_eq!(t.to_rfc3339(), "2014-01-15T16:41:49.390270+00:00");
+    }
+}
@@ -0,0 +1,233 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use bytes::BytesMut;
+use http::StatusCode;
+
+use super::core::SloSegment;
+use super::core::SwiftCore;
+use super::error::parse_error;
+use crate::ops::OpWrite;
+use crate::raw::*;
+use crate::*;
+
+/// SwiftWriter uploads content larger than `segment_size` as a
+/// [Static Large Object](https://docs.openstack.org/swift/latest/overview_large_objects.html).
+///
+/// Segments are stored in `<container>_segments` with name
+/// `<path>/<uuid>/<index>`, and will be removed while deleting the
+/// object or aborting the writer.
+pub struct SwiftWriter {
+    core: Arc<SwiftCore>,
+
+    op: OpWrite,
+    path: String,
+
+    /// The prefix of segments, generated on first segment uploaded.
+    segment_prefix: Option<String>,
+    segments: Vec<SloSegment>,
+    buffer: BytesMut,
+    /// Whether the object has been written by `write`.
+    written: bool,
+}
+
+impl SwiftWriter {
+    pub fn new(core: Arc<SwiftCore>, op: OpWrite, path: String) -> Self {
+        SwiftWriter {
+            core,
+            op,
+            path,
+
+            segment_prefix: None,
+            segments: Vec::new(),
+            buffer: BytesMut::new(),
+            written: false,
+        }
+    }
+
+    async fn put_object(&self, bs: Bytes) -> Result<()> {
+        let resp = self
+            .core
+            .swift_put_object(&self.path, Some(bs.len()), &self.op, AsyncBody::Bytes(bs))
+            .await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::swift_put_object")),
+        }
+    }
+
+    async fn put_segment(&mut self, bs: Bytes) -> Result<()> {
+        let prefix = match &self.segment_prefix {
+            Some(prefix) => prefix.clone(),
+            None => {
+                let resp = self.core.swift_create_segment_container().await?;
+                match resp.status() {
+                    StatusCode::CREATED | StatusCode::ACCEPTED | StatusCode::NO_CONTENT => {
+                        resp.into_body().consume().await?;
+                    }
+                    _ => {
+                        return Err(parse_error(resp)
+                            .await?
+                            .with_operation("Backend::swift_create_segment_container"))
+                    }
+                }
+
+                let prefix = format!(
+                    "{}/{}",
+                    build_abs_path(&self.core.root, &self.path).trim_end_matches('/'),
+                    uuid::Uuid::new_v4()
+                );
+                self.segment_prefix = Some(prefix.clone());
+                prefix
+            }
+        };
+
+        let name = format!("{prefix}/{:08}", self.segments.len());
+        let size = bs.len() as u64;
+
+        let resp = self.core.swift_put_segment(&name, bs).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                let etag = parse_etag(resp.headers())?.map(|v| v.trim_matches('"').to_string());
+                resp.into_body().consume().await?;
+
+                self.segments.push(SloSegment {
+                    path: format!("/{}/{}", self.core.segment_container(), name),
+                    etag,
+                    size_bytes: size,
+                });
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::swift_put_segment")),
+        }
+    }
+
+    async fn put_manifest(&mut self) -> Result<()> {
+        let resp = self
+            .core
+            .swift_put_slo_manifest(&self.path, &self.segments, &self.op)
+            .await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::CREATED | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                // Segments are owned by the manifest now.
+                self.segments.clear();
+                self.segment_prefix = None;
+                Ok(())
+            }
+            _ => Err(parse_error(resp)
+                .await?
+                .with_operation("Backend::swift_put_slo_manifest")),
+        }
+    }
+}
+
+#[async_trait]
+impl oio::Write for SwiftWriter {
+    async fn write(&mut self, bs: Bytes) -> Result<()> {
+        if bs.len() <= self.core.segment_size {
+            self.put_object(bs).await?;
+        } else {
+            let mut pos = 0;
+            while pos < bs.len() {
+                let size = self.core.segment_size.min(bs.len() - pos);
+                self.put_segment(bs.slice(pos..pos + size)).await?;
+                pos += size;
+            }
+            self.put_manifest().await?;
+        }
+
+        self.written = true;
+        Ok(())
+    }
+
+    async fn append(&mut self, bs: Bytes) -> Result<()> {
+        self.buffer.extend_from_slice(&bs);
+
+        while self.buffer.len() >= self.core.segment_size {
+            let bs = self.buffer.split_to(self.core.segment_size).freeze();
+            self.put_segment(bs).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.buffer.clear();
+
+        for segment in self.segments.drain(..) {
+            let name = segment
+                .path
+                .trim_start_matches(&format!("/{}/", self.core.segment_container()))
+                .to_string();
+
+            let resp = self.core.swift_delete_segment(&name).await?;
+            match resp.status() {
+                StatusCode::NO_CONTENT | StatusCode::OK | StatusCode::NOT_FOUND => {
+                    resp.into_body().consume().await?;
+                }
+                _ => {
+                    return Err(parse_error(resp)
+                        .await?
+                        .with_operation("Backend::swift_delete_segment"))
+                }
+            }
+        }
+        self.segment_prefix = None;
+
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        // Content has been written by `write` and nothing appended.
+        if self.written && self.buffer.is_empty() && self.segments.is_empty() {
+            return Ok(());
+        }
+
+        let bs = self.buffer.split().freeze();
+
+        // Small enough to be uploaded as a normal object.
+        if self.segments.is_empty() {
+            self.put_object(bs).await?;
+        } else {
+            if !bs.is_empty() {
+                self.put_segment(bs).await?;
+            }
+            self.put_manifest().await?;
+        }
+
+        self.written = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tikv_client::Config;
+use tikv_client::Key;
+use tikv_client::RawClient;
+use tokio::sync::OnceCell;
+
+use crate::raw::adapters::kv;
+use crate::raw::*;
+use crate::*;
+
+/// TiKV limits the size of a raft entry (8 MiB by default), so values
+/// larger than this will be split into chunks.
+const DEFAULT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// The max keys that could be returned by a single raw scan.
+const MAX_SCAN_LIMIT: u32 = 10240;
+
+/// TiKV service support.
+///
+/// # Capabilities
+///
+/// This service can be used to:
+///
+/// - [x] read
+/// - [x] write
+/// - [x] list
+/// - [x] scan
+/// - [ ] ~~presign~~
+/// - [ ] ~~blocking~~
+///
+/// # Notes
+///
+/// This service uses TiKV in raw KV mode, please don't mix it with
+/// transactional KV in the same cluster.
+///
+/// Values larger than `chunk_size` will be split into chunks. Blocking
+/// operations could be supported via `BlockingLayer`.
+///
+/// # Configuration
+///
+/// - `root`: Set the working directory of `OpenDAL`
+/// - `endpoints`: Set the PD endpoints of TiKV, separated by `,`
+/// - `ca_path`: Set the ca certificate path for tls
+/// - `cert_path`: Set the client certificate path for tls
+/// - `key_path`: Set the client key path for tls
+/// - `chunk_size`: Set the size of chunks, default to 4 MiB
+///
+/// You can refer to [`TikvBuilder`]'s docs for more information
+///
+/// # Example
+///
+/// ## Via Builder
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::services::Tikv;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let mut builder = Tikv::default();
+///     builder.endpoints("127.0.0.1:2379");
+///
+///     let op: Operator = Operator::new(builder)?.finish();
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct TikvBuilder {
+    endpoints: Option<String>,
+    ca_path: Option<String>,
+    cert_path: Option<String>,
+    key_path: Option<String>,
+    chunk_size: Option<usize>,
+    root: Option<String>,
+}
+
+impl TikvBuilder {
+    /// Set the PD endpoints of TiKV, multiple endpoints are separated by `,`.
+    pub fn endpoints(&mut self, endpoints: &str) -> &mut Self {
+        if !endpoints.is_empty() {
+            self.endpoints = Some(endpoints.to_string());
+        }
+        self
+    }
+
+    /// Set the path of ca certificate in PEM format to enable tls.
+    ///
+    /// Must be set together with `cert_path` and `key_path`.
+    pub fn ca_path(&mut self, ca_path: &str) -> &mut Self {
+        if !ca_path.is_empty() {
+            self.ca_path = Some(ca_path.to_string());
+        }
+        self
+    }
+
+    /// Set the path of client certificate in PEM format.
+    pub fn cert_path(&mut self, cert_path: &str) -> &mut Self {
+        if !cert_path.is_empty() {
+            self.cert_path = Some(cert_path.to_string());
+        }
+        self
+    }
+
+    /// Set the path of client key in PEM format.
+    pub fn key_path(&mut self, key_path: &str) -> &mut Self {
+        if !key_path.is_empty() {
+            self.key_path = Some(key_path.to_string());
+        }
+        self
+    }
+
+    /// Set the size of chunks that large values will be split into.
+    ///
+    /// default: 4 MiB
+    pub fn chunk_size(&mut self, chunk_size: usize) -> &mut Self {
+        if chunk_size > 0 {
+            self.chunk_size = Some(chunk_size);
+        }
+        self
+    }
+
+    /// Set the root for tikv.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        if !root.is_empty() {
+            self.root = Some(root.to_string());
+        }
+        self
+    }
+}
+
+impl Builder for TikvBuilder {
+    const SCHEME: Scheme = Scheme::Tikv;
+    type Accessor = TikvBackend;
+
+    fn from_map(map: HashMap<String, String>) -> Self {
+        let mut builder = TikvBuilder::default();
+
+        map.get("endpoints").map(|v| builder.endpoints(v));
+        map.get("ca_path").map(|v| builder.ca_path(v));
+        map.get("cert_path").map(|v| builder.cert_path(v));
+        map.get("key_path").map(|v| builder.key_path(v));
+        map.get("chunk_size")
+            .map(|v| v.parse::<usize>().map(|v| builder.chunk_size(v)));
+        map.get("root").map(|v| builder.root(v));
+
+        builder
+    }
+
+    fn build(&mut self) -> Result<Self::Accessor> {
+        let endpoints: Vec<String> = self
+            .endpoints
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if endpoints.is_empty() {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "endpoints is required but not set",
+            )
+            .with_context("service", Scheme::Tikv));
+        }
+
+        let config = match (&self.ca_path, &self.cert_path, &self.key_path) {
+            (Some(ca), Some(cert), Some(key)) => Config::default().with_security(ca, cert, key),
+            (None, None, None) => Config::default(),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::ConfigInvalid,
+                    "ca_path, cert_path and key_path must be set together",
+                )
+                .with_context("service", Scheme::Tikv))
+            }
+        };
+
+        Ok(TikvBackend::new(Adapter {
+            endpoints,
+            config,
+            client: Arc::new(OnceCell::new()),
+        })
+        .with_root(self.root.as_deref().unwrap_or_default())
+        .with_chunk_size(self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE)))
+    }
+}
+
+/// Backend for tikv services.
+pub type TikvBackend = kv::Backend<Adapter>;
+
+#[derive(Clone)]
+pub struct Adapter {
+    endpoints: Vec<String>,
+    config: Config,
+    client: Arc<OnceCell<RawClient>>,
+}
+
+impl Debug for Adapter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut ds = f.debug_struct("Adapter");
+        ds.field("endpoints", &self.endpoints);
+        ds.finish()
+    }
+}
+
+impl Adapter {
+    async fn get_client(&self) -> Result<&RawClient> {
+        self.client
+            .get_or_try_init(|| async {
+                RawClient::new_with_config(self.endpoints.clone(), self.config.clone())
+                    .await
+                    .map_err(parse_error)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl kv::Adapter for Adapter {
+    fn metadata(&self) -> kv::Metadata {
+        use AccessorCapability::*;
+        kv::Metadata::new(Scheme::Tikv, &self.endpoints.join(","), Read | Write | Scan)
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let client = self.get_client().await?;
+        client.get(path.to_string()).await.map_err(parse_error)
+    }
+
+    async fn set(&self, path: &str, value: &[u8]) -> Result<()> {
+        let client = self.get_client().await?;
+        client
+            .put(path.to_string(), value.to_vec())
+            .await
+            .map_err(parse_error)
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let client = self.get_client().await?;
+        client.delete(path.to_string()).await.map_err(parse_error)
+    }
+
+    async fn scan(&self, path: &str) -> Result<Vec<String>> {
+        let client = self.get_client().await?;
+        let end = prefix_end(path.as_bytes()).map(Key::from);
+
+        let mut start = path.as_bytes().to_vec();
+        let mut res = Vec::default();
+        loop {
+            let keys = client
+                .scan_keys((Key::from(start), end.clone()), MAX_SCAN_LIMIT)
+                .await
+                .map_err(parse_error)?;
+            let size = keys.len();
+
+            for key in keys {
+                let key = String::from_utf8(key.into()).map_err(|err| {
+                    Error::new(ErrorKind::Unexpected, "store key is not valid utf-8 string")
+                        .set_source(err)
+                })?;
+                res.push(key);
+            }
+
+            if size < MAX_SCAN_LIMIT as usize {
+                return Ok(res);
+            }
+            // Continue from the key right after the last one.
+            start = res.last().expect("must have keys").as_bytes().to_vec();
+            start.push(0);
+        }
+    }
+}
+
+/// Returns the smallest key that larger than all keys with this prefix,
+/// `None` means there is no upper bound.
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+fn parse_error(err: tikv_client::Error) -> Error {
+    use tikv_client::Error::*;
+
+    // Region errors happen while regions are splitting or leaders are
+    // changing, they are expected to be retried.
+    let retryable = matches!(
+        err,
+        RegionError(_)
+            | RegionForKeyNotFound { .. }
+            | RegionNotFoundInResponse { .. }
+            | LeaderNotFound { .. }
+            | NoCurrentRegions
+            | EntryNotFoundInRegionCache
+            | Grpc(_)
+            | GrpcAPI(_)
+            | Io(_)
+    );
+
+    let mut err = Error::new(ErrorKind::Unexpected, "error from tikv").set_source(err);
+    if retryable {
+        err = err.set_temporary();
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_end() {
+        assert_eq!(prefix_end(b""), None);
+        assert_eq!(prefix_end(b"dir/"), Some(b"dir0".to_vec()));
+        assert_eq!(prefix_end(b"a\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_end(b"\xff\xff"), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_error(tikv_client::Error::LeaderNotFound { region_id: 1 });
+        assert!(err.is_temporary());
+
+        let err = parse_error(tikv_client::Error::Unimplemented);
+        assert!(!err.is_temporary());
+    }
+}
@@ -903,10 +903,27 @@ impl Accessor for WasabiBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Wasabi)
             .set_root(&self.core.root)
+            .set_endpoint(&self.core.endpoint)
             .set_name(&self.core.bucket)
             .set_max_batch_operations(1000)
-            .set_capabilities(Read | Write | List | Scan | Presign | Batch | Copy | Rename)
-            .set_hints(ReadStreamable);
+            .set_capabilities(
+                Read | Write
+                    | List
+                    | Scan
+                    | Presign
+                    | Batch
+                    | Copy
+                    | Rename
+                    | WriteWithContentType
+                    | ReadWithIfNoneMatch
+                    | ReadWithOverrideCacheControl
+                    | ReadWithOverrideContentDisposition
+                    | StatWithIfNoneMatch
+                    | WriteWithContentDisposition
+                    | WriteWithCacheControl
+                    | ListWithLimit,
+            )
+            .set_hints(ReadStreamable | ImplicitDir);
 
         am
     }
@@ -934,7 +951,13 @@ impl Accessor for WasabiBackend {
     async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
         let resp = self
             .core
-            .get_object(path, args.range(), args.if_none_match())
+            .get_object(
+                path,
+                args.range(),
+                args.override_content_disposition(),
+                args.override_cache_control(),
+                args.if_none_match(),
+            )
             .await?;
 
         let status = resp.status();
@@ -983,10 +1006,7 @@ impl Accessor for WasabiBackend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK => parse_into_metadata(path, resp.headers()).map(RpStat::new),
-            StatusCode::NOT_FOUND if path.ends_with('/') => {
-                Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
-            }
+            StatusCode::OK => parse_into_wasabi_metadata(path, resp.headers()).map(RpStat::new),
             _ => Err(parse_error(resp).await?),
         }
     }
@@ -29,6 +29,7 @@ use http::header::CACHE_CONTROL;
 use http::header::CONTENT_DISPOSITION;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
+use http::HeaderMap;
 use http::HeaderValue;
 use http::Request;
 use http::Response;
@@ -289,9 +290,17 @@ impl WasabiCore {
         &self,
         path: &str,
         range: BytesRange,
+        override_content_disposition: Option<&str>,
+        override_cache_control: Option<&str>,
         if_none_match: Option<&str>,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.get_object_request(path, range, None, None, if_none_match)?;
+        let mut req = self.get_object_request(
+            path,
+            range,
+            override_content_disposition,
+            override_cache_control,
+            if_none_match,
+        )?;
 
         self.sign(&mut req).await?;
 
@@ -797,6 +806,22 @@ pub struct DeleteObjectsResultError {
     pub message: String,
 }
 
+/// Parse wasabi response headers into metadata.
+pub fn parse_into_wasabi_metadata(path: &str, headers: &HeaderMap) -> Result<Metadata> {
+    let mut m = parse_into_metadata(path, headers)?;
+
+    // Etags of objects encrypted by SSE-KMS or SSE-C are not md5 of content.
+    let kms = headers
+        .get(constants::X_AMZ_SERVER_SIDE_ENCRYPTION)
+        .and_then(|v| v.to_str().ok())
+        .map_or(false, |v| v.starts_with("aws:kms"));
+    if kms || headers.contains_key(constants::X_AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM) {
+        m.set_etag_opaque();
+    }
+
+    Ok(m)
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Buf;
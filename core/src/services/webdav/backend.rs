@@ -23,12 +23,14 @@ use async_trait::async_trait;
 use bytes::Buf;
 use http::header;
 use http::HeaderMap;
+use http::HeaderValue;
 use http::Request;
 use http::Response;
 use http::StatusCode;
 use log::debug;
 
 use super::error::parse_error;
+use super::error::parse_error_with_body;
 use super::list_response::Multistatus;
 use super::pager::WebdavPager;
 use super::writer::WebdavWriter;
@@ -47,7 +49,7 @@ use crate::*;
 /// - [x] copy
 /// - [x] rename
 /// - [x] list
-/// - [ ] ~~scan~~
+/// - [x] scan
 /// - [ ] ~~presign~~
 /// - [ ] blocking
 ///
@@ -60,6 +62,16 @@ use crate::*;
 ///
 /// - `endpoint`: set the endpoint for webdav
 /// - `root`: Set the work directory for backend
+/// - `disable_overwrite`: Set to `true` to forbid copy and rename from
+///   overwriting existing destinations
+///
+/// # Copy and Rename
+///
+/// `copy` and `rename` are implemented via `COPY` and `MOVE` with the
+/// `Destination` header, and directories will be copied recursively via
+/// `Depth: infinity`. Existing destinations will be overwritten unless
+/// `disable_overwrite` is set, in which case `AlreadyExists` will be
+/// returned.
 ///
 /// You can refer to [`WebdavBuilder`]'s docs for more information
 ///
@@ -93,6 +105,7 @@ pub struct WebdavBuilder {
     password: Option<String>,
     token: Option<String>,
     root: Option<String>,
+    disable_overwrite: bool,
     http_client: Option<HttpClient>,
 }
 
@@ -161,6 +174,15 @@ impl WebdavBuilder {
         self
     }
 
+    /// Forbid copy and rename from overwriting existing destinations.
+    ///
+    /// `Overwrite: F` will be sent and `AlreadyExists` will be returned if
+    /// the destination exists.
+    pub fn disable_overwrite(&mut self) -> &mut Self {
+        self.disable_overwrite = true;
+        self
+    }
+
     /// Specify the http client that used by this service.
     ///
     /// # Notes
@@ -185,6 +207,9 @@ impl Builder for WebdavBuilder {
         map.get("username").map(|v| builder.username(v));
         map.get("password").map(|v| builder.password(v));
         map.get("token").map(|v| builder.token(v));
+        map.get("disable_overwrite")
+            .filter(|v| *v == "on" || *v == "true")
+            .map(|_| builder.disable_overwrite());
 
         builder
     }
@@ -225,9 +250,10 @@ impl Builder for WebdavBuilder {
 
         debug!("backend build finished: {:?}", &self);
         Ok(WebdavBackend {
-            endpoint: endpoint.to_string(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
             authorization: auth,
             root,
+            overwrite: !self.disable_overwrite,
             client,
         })
     }
@@ -237,7 +263,8 @@ impl Builder for WebdavBuilder {
 #[derive(Clone)]
 pub struct WebdavBackend {
     endpoint: String,
-    root: String,
+    pub(super) root: String,
+    overwrite: bool,
     client: HttpClient,
 
     authorization: Option<String>,
@@ -248,6 +275,7 @@ impl Debug for WebdavBackend {
         f.debug_struct("Backend")
             .field("endpoint", &self.endpoint)
             .field("root", &self.root)
+            .field("overwrite", &self.overwrite)
             .field("client", &self.client)
             .finish()
     }
@@ -266,12 +294,16 @@ impl Accessor for WebdavBackend {
         let mut ma = AccessorInfo::default();
         ma.set_scheme(Scheme::Webdav)
             .set_root(&self.root)
+            .set_endpoint(&self.endpoint)
             .set_capabilities(
                 AccessorCapability::Read
                     | AccessorCapability::Write
                     | AccessorCapability::Copy
                     | AccessorCapability::Rename
-                    | AccessorCapability::List,
+                    | AccessorCapability::List
+                    | AccessorCapability::Scan
+                    | AccessorCapability::WriteWithContentType
+                    | AccessorCapability::WriteWithContentDisposition,
             )
             .set_hints(AccessorHint::ReadStreamable);
 
@@ -315,27 +347,19 @@ impl Accessor for WebdavBackend {
     async fn copy(&self, from: &str, to: &str, _args: OpCopy) -> Result<RpCopy> {
         self.ensure_parent_path(to).await?;
 
-        let resp = self.webdav_copy(from, to).await?;
-
-        let status = resp.status();
+        let resp = self.webdav_copy_or_move("COPY", from, to).await?;
+        self.parse_copy_or_move_response(resp, from, to).await?;
 
-        match status {
-            StatusCode::CREATED | StatusCode::NO_CONTENT => Ok(RpCopy::default()),
-            _ => Err(parse_error(resp).await?),
-        }
+        Ok(RpCopy::default())
     }
 
     async fn rename(&self, from: &str, to: &str, _args: OpRename) -> Result<RpRename> {
         self.ensure_parent_path(to).await?;
 
-        let resp = self.webdav_move(from, to).await?;
-
-        let status = resp.status();
+        let resp = self.webdav_copy_or_move("MOVE", from, to).await?;
+        self.parse_copy_or_move_response(resp, from, to).await?;
 
-        match status {
-            StatusCode::CREATED | StatusCode::NO_CONTENT => Ok(RpRename::default()),
-            _ => Err(parse_error(resp).await?),
-        }
+        Ok(RpRename::default())
     }
 
     async fn stat(&self, path: &str, _: OpStat) -> Result<RpStat> {
@@ -357,7 +381,7 @@ impl Accessor for WebdavBackend {
             match status {
                 // HTTP Server like nginx could return FORBIDDEN if auto-index
                 // is not enabled, we should ignore them.
-                StatusCode::NOT_FOUND | StatusCode::FORBIDDEN if path.ends_with('/') => {
+                StatusCode::FORBIDDEN if path.ends_with('/') => {
                     Ok(RpStat::new(Metadata::new(EntryMode::DIR)))
                 }
                 _ => Err(parse_error(resp).await?),
@@ -392,35 +416,59 @@ impl Accessor for WebdavBackend {
     }
 
     async fn list(&self, path: &str, _: OpList) -> Result<(RpList, Self::Pager)> {
-        let mut header_map = HeaderMap::new();
-        header_map.insert("Depth", "1".parse().unwrap());
-        header_map.insert(header::CONTENT_TYPE, "application/xml".parse().unwrap());
-        let resp = self.webdav_propfind(path, Some(header_map)).await?;
+        let result = self.webdav_list(path, "1").await?;
+
+        Ok((
+            RpList::default(),
+            WebdavPager::new(&self.root, path, result),
+        ))
+    }
+
+    async fn scan(&self, path: &str, _: OpScan) -> Result<(RpScan, Self::Pager)> {
+        let resp = self.webdav_propfind_with_depth(path, "infinity").await?;
         let status = resp.status();
 
-        match status {
+        let result = match status {
             StatusCode::OK | StatusCode::MULTI_STATUS => {
                 let bs = resp.into_body().bytes().await?;
-                let result: Multistatus =
-                    quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?;
-
-                Ok((
-                    RpList::default(),
-                    WebdavPager::new(&self.root, path, result),
-                ))
+                quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)?
             }
-            StatusCode::NOT_FOUND if path.ends_with('/') => Ok((
-                RpList::default(),
-                WebdavPager::new(
-                    &self.root,
-                    path,
-                    Multistatus {
-                        response: Vec::new(),
-                    },
-                ),
-            )),
-            _ => Err(parse_error(resp).await?),
-        }
+            StatusCode::NOT_FOUND if path.ends_with('/') => Multistatus {
+                response: Vec::new(),
+            },
+            StatusCode::FORBIDDEN => {
+                let (parts, body) = resp.into_parts();
+                let bs = body.bytes().await?;
+
+                // rfc4918 9.1: servers may reject `Depth: infinity` with the
+                // `propfind-finite-depth` precondition, fallback to list
+                // dirs one by one.
+                if String::from_utf8_lossy(&bs).contains("propfind-finite-depth") {
+                    debug!(
+                        "webdav server doesn't allow infinite depth, fallback to recursive list"
+                    );
+                    return Ok((
+                        RpScan::default(),
+                        WebdavPager::new_recursive(self.clone(), path),
+                    ));
+                }
+                return Err(parse_error_with_body(parts, bs));
+            }
+            // nginx's dav ext module rejects `Depth: infinity` as bad request.
+            StatusCode::BAD_REQUEST => {
+                debug!("webdav server doesn't support infinite depth, fallback to recursive list");
+                return Ok((
+                    RpScan::default(),
+                    WebdavPager::new_recursive(self.clone(), path),
+                ));
+            }
+            _ => return Err(parse_error(resp).await?),
+        };
+
+        Ok((
+            RpScan::default(),
+            WebdavPager::new(&self.root, path, result),
+        ))
     }
 }
 
@@ -512,6 +560,34 @@ impl WebdavBackend {
         self.client.send(req).await
     }
 
+    /// List the children of given path with `PROPFIND`.
+    pub(super) async fn webdav_list(&self, path: &str, depth: &'static str) -> Result<Multistatus> {
+        let resp = self.webdav_propfind_with_depth(path, depth).await?;
+        let status = resp.status();
+
+        match status {
+            StatusCode::OK | StatusCode::MULTI_STATUS => {
+                let bs = resp.into_body().bytes().await?;
+                quick_xml::de::from_reader(bs.reader()).map_err(new_xml_deserialize_error)
+            }
+            StatusCode::NOT_FOUND if path.ends_with('/') => Ok(Multistatus {
+                response: Vec::new(),
+            }),
+            _ => Err(parse_error(resp).await?),
+        }
+    }
+
+    async fn webdav_propfind_with_depth(
+        &self,
+        path: &str,
+        depth: &'static str,
+    ) -> Result<Response<IncomingAsyncBody>> {
+        let mut header_map = HeaderMap::new();
+        header_map.insert("Depth", HeaderValue::from_static(depth));
+        header_map.insert(header::CONTENT_TYPE, "application/xml".parse().unwrap());
+        self.webdav_propfind(path, Some(header_map)).await
+    }
+
     async fn webdav_propfind(
         &self,
         path: &str,
@@ -569,23 +645,34 @@ impl WebdavBackend {
         self.client.send(req).await
     }
 
-    async fn webdav_copy(&self, from: &str, to: &str) -> Result<Response<IncomingAsyncBody>> {
+    /// Send `COPY` or `MOVE` request from `from` to `to`.
+    async fn webdav_copy_or_move(
+        &self,
+        method: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Response<IncomingAsyncBody>> {
         let source = build_abs_path(&self.root, from);
         let target = build_abs_path(&self.root, to);
 
         let source = format!("{}/{}", self.endpoint, percent_encode_path(&source));
+        // Destination must be an absolute URI with the same encoding as
+        // request uri.
         let target = format!("{}/{}", self.endpoint, percent_encode_path(&target));
 
-        let mut req = Request::builder().method("COPY").uri(&source);
+        let mut req = Request::builder().method(method).uri(&source);
 
         if let Some(auth) = &self.authorization {
             req = req.header(header::AUTHORIZATION, auth);
         }
 
         req = req.header("Destination", target);
+        req = req.header("Overwrite", if self.overwrite { "T" } else { "F" });
 
-        // We always specific "T" for keeping to overwrite the destination.
-        req = req.header("Overwrite", "T");
+        // Copy or move directories with all their members.
+        if from.ends_with('/') {
+            req = req.header("Depth", "infinity");
+        }
 
         let req = req
             .body(AsyncBody::Empty)
@@ -594,29 +681,36 @@ impl WebdavBackend {
         self.client.send(req).await
     }
 
-    async fn webdav_move(&self, from: &str, to: &str) -> Result<Response<IncomingAsyncBody>> {
-        let source = build_abs_path(&self.root, from);
-        let target = build_abs_path(&self.root, to);
-
-        let source = format!("{}/{}", self.endpoint, percent_encode_path(&source));
-        let target = format!("{}/{}", self.endpoint, percent_encode_path(&target));
-
-        let mut req = Request::builder().method("MOVE").uri(&source);
+    /// Parse the response of `COPY` or `MOVE`.
+    ///
+    /// - `201 Created`: destination has been created.
+    /// - `204 No Content`: existing destination has been overwritten.
+    /// - `200 OK`: returned by some servers instead of `204`.
+    /// - `412 Precondition Failed`: destination exists while overwrite is
+    ///   forbidden.
+    async fn parse_copy_or_move_response(
+        &self,
+        resp: Response<IncomingAsyncBody>,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        let status = resp.status();
 
-        if let Some(auth) = &self.authorization {
-            req = req.header(header::AUTHORIZATION, auth);
+        match status {
+            StatusCode::CREATED | StatusCode::NO_CONTENT | StatusCode::OK => {
+                resp.into_body().consume().await?;
+                Ok(())
+            }
+            StatusCode::PRECONDITION_FAILED if !self.overwrite => {
+                resp.into_body().consume().await?;
+                Err(
+                    Error::new(ErrorKind::AlreadyExists, "destination already exists")
+                        .with_context("from", from)
+                        .with_context("to", to),
+                )
+            }
+            _ => Err(parse_error(resp).await?),
         }
-
-        req = req.header("Destination", target);
-
-        // We always specific "T" for keeping to overwrite the destination.
-        req = req.header("Overwrite", "T");
-
-        let req = req
-            .body(AsyncBody::Empty)
-            .map_err(new_request_build_error)?;
-
-        self.client.send(req).await
     }
 
     async fn create_internal(&self, abs_path: &str) -> Result<RpCreate> {
@@ -665,3 +759,224 @@ impl WebdavBackend {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use futures::TryStreamExt;
+    use wiremock::matchers::header;
+    use wiremock::matchers::header_exists;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+    use crate::Operator;
+
+    async fn mount_mkcol(mock_server: &MockServer) {
+        Mock::given(method("MKCOL"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_copy() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        mount_mkcol(&mock_server).await;
+        Mock::given(method("COPY"))
+            .and(path("/root/src%20file"))
+            .and(header(
+                "Destination",
+                format!("{}/root/dst/dst%20file", mock_server.uri()).as_str(),
+            ))
+            .and(header("Overwrite", "T"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("COPY"))
+            .and(path("/root/src_dir/"))
+            .and(header("Depth", "infinity"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebdavBuilder::default();
+        builder
+            .endpoint(&format!("{}/", mock_server.uri()))
+            .root("/root");
+        let op = Operator::new(builder)?.finish();
+
+        op.copy("src file", "dst/dst file").await?;
+        op.inner()
+            .copy("src_dir/", "dst_dir/", OpCopy::new())
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_without_overwrite() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        mount_mkcol(&mock_server).await;
+        Mock::given(method("MOVE"))
+            .and(path("/src"))
+            .and(header_exists("Destination"))
+            .and(header("Overwrite", "F"))
+            .respond_with(ResponseTemplate::new(412))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let builder = WebdavBuilder::from_map(HashMap::from([
+            ("endpoint".to_string(), mock_server.uri()),
+            ("disable_overwrite".to_string(), "true".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+
+        let err = op.rename("src", "dst").await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+        Ok(())
+    }
+
+    fn multistatus(entries: &[(&str, Option<u64>)]) -> String {
+        let mut body =
+            String::from(r#"<?xml version="1.0" encoding="utf-8"?><D:multistatus xmlns:D="DAV:">"#);
+        for (href, length) in entries {
+            let prop = match length {
+                Some(length) => {
+                    format!("<D:getcontentlength>{length}</D:getcontentlength><D:resourcetype/>")
+                }
+                None => "<D:resourcetype><D:collection/></D:resourcetype>".to_string(),
+            };
+            body.push_str(&format!(
+                "<D:response><D:href>{href}</D:href><D:propstat><D:prop>\
+                 <D:getlastmodified>Tue, 07 Feb 2023 06:39:47 GMT</D:getlastmodified>{prop}\
+                 </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>"
+            ));
+        }
+        body.push_str("</D:multistatus>");
+        body
+    }
+
+    async fn scan_entries(op: &Operator, path: &str) -> Result<Vec<(String, Metadata)>> {
+        let mut entries = Vec::new();
+        let mut lister = op.scan(path).await?;
+        while let Some(de) = lister.try_next().await? {
+            let meta = op
+                .metadata(&de, Metakey::ContentLength | Metakey::LastModified)
+                .await?;
+            entries.push((de.path().to_string(), meta));
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_infinite_depth() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PROPFIND"))
+            .and(path("/root/dir/"))
+            .and(header("Depth", "infinity"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(multistatus(&[
+                ("/root/dir/", None),
+                ("/root/dir/a", Some(1)),
+                ("/root/dir/sub/", None),
+                ("/root/dir/sub/b", Some(2)),
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebdavBuilder::default();
+        builder.endpoint(&mock_server.uri()).root("/root");
+        let op = Operator::new(builder)?.finish();
+
+        let entries = scan_entries(&op, "dir/").await?;
+        let paths: Vec<_> = entries.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, vec!["dir/a", "dir/sub/", "dir/sub/b"]);
+        assert_eq!(entries[2].1.content_length(), 2);
+        assert!(entries[2].1.last_modified().is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_fallback_to_recursive_list() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PROPFIND"))
+            .and(header("Depth", "infinity"))
+            .respond_with(ResponseTemplate::new(403).set_body_string(
+                r#"<?xml version="1.0" encoding="utf-8"?><D:error xmlns:D="DAV:"><D:propfind-finite-depth/></D:error>"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PROPFIND"))
+            .and(path("/dir/"))
+            .and(header("Depth", "1"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(multistatus(&[
+                ("/dir/", None),
+                ("/dir/a", Some(1)),
+                ("/dir/sub/", None),
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PROPFIND"))
+            .and(path("/dir/sub/"))
+            .and(header("Depth", "1"))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_string(multistatus(&[("/dir/sub/", None), ("/dir/sub/b", Some(2))])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebdavBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+
+        let entries = scan_entries(&op, "dir/").await?;
+        let paths: Vec<_> = entries.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, vec!["dir/a", "dir/sub/", "dir/sub/b"]);
+        assert_eq!(entries[0].1.content_length(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_forbidden() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("PROPFIND"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebdavBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+
+        let err = match op.scan("dir/").await {
+            Ok(_) => panic!("scan must fail"),
+            Err(err) => err,
+        };
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        Ok(())
+    }
+}
@@ -15,21 +15,28 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::VecDeque;
 use std::mem;
 
 use async_trait::async_trait;
 
+use super::backend::WebdavBackend;
 use super::list_response::Multistatus;
+use super::list_response::ResourceType;
 use crate::raw::build_rel_path;
 use crate::raw::oio;
 use crate::EntryMode;
-use crate::Metadata;
 use crate::Result;
 
 pub struct WebdavPager {
     root: String,
     path: String,
     multistates: Multistatus,
+
+    /// Backend used to walk the dirs one by one while the server doesn't
+    /// allow `Depth: infinity`.
+    backend: Option<WebdavBackend>,
+    dirs: VecDeque<String>,
 }
 
 impl WebdavPager {
@@ -38,6 +45,21 @@ impl WebdavPager {
             root: root.into(),
             path: path.into(),
             multistates,
+            backend: None,
+            dirs: VecDeque::new(),
+        }
+    }
+
+    /// Create a pager that lists the dirs recursively with `Depth: 1`.
+    pub fn new_recursive(backend: WebdavBackend, path: &str) -> Self {
+        Self {
+            root: backend.root.clone(),
+            path: path.into(),
+            multistates: Multistatus {
+                response: Vec::new(),
+            },
+            backend: Some(backend),
+            dirs: VecDeque::from([path.to_string()]),
         }
     }
 }
@@ -45,38 +67,53 @@ impl WebdavPager {
 #[async_trait]
 impl oio::Page for WebdavPager {
     async fn next(&mut self) -> Result<Option<Vec<oio::Entry>>> {
-        if self.multistates.response.is_empty() {
-            return Ok(None);
-        };
-        let oes = mem::take(&mut self.multistates.response);
+        loop {
+            if self.multistates.response.is_empty() {
+                let backend = match &self.backend {
+                    Some(backend) => backend,
+                    None => return Ok(None),
+                };
+                match self.dirs.pop_front() {
+                    Some(dir) => {
+                        self.multistates = backend.webdav_list(&dir, "1").await?;
+                        self.path = dir;
+                        continue;
+                    }
+                    None => return Ok(None),
+                }
+            };
+            let oes = mem::take(&mut self.multistates.response);
 
-        let oes = oes
-            .into_iter()
-            .filter_map(|de| {
-                let path = de.href;
-                let normalized_path = if self.root != path {
-                    build_rel_path(&self.root, &path)
+            let mut entries = Vec::with_capacity(oes.len());
+            for de in oes {
+                let normalized_path = if self.root != de.href {
+                    build_rel_path(&self.root, &de.href)
                 } else {
-                    path
+                    de.href.clone()
                 };
 
                 if normalized_path == self.path {
                     // WebDav server may return the current path as an entry.
-                    return None;
+                    continue;
                 }
 
-                let entry = if de.propstat.prop.resourcetype.value
-                    == Some(super::list_response::ResourceType::Collection)
-                {
-                    oio::Entry::new(&normalized_path, Metadata::new(EntryMode::DIR))
+                let mut meta = de.parse_into_metadata()?;
+                if de.propstat.prop.resourcetype.value == Some(ResourceType::Collection) {
+                    meta.set_mode(EntryMode::DIR);
+                    if self.backend.is_some() {
+                        self.dirs.push_back(normalized_path.clone());
+                    }
                 } else {
-                    oio::Entry::new(&normalized_path, Metadata::new(EntryMode::FILE))
-                };
+                    meta.set_mode(EntryMode::FILE);
+                }
 
-                Some(entry)
-            })
-            .collect();
+                entries.push(oio::Entry::new(&normalized_path, meta));
+            }
 
-        Ok(Some(oes))
+            if entries.is_empty() && self.backend.is_some() {
+                continue;
+            }
+            return Ok(Some(entries));
+        }
     }
 }
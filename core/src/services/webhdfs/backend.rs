@@ -17,6 +17,7 @@
 
 use core::fmt::Debug;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use http::header::CONTENT_LENGTH;
@@ -27,6 +28,7 @@ use http::StatusCode;
 use log::debug;
 use tokio::sync::OnceCell;
 
+use super::delegation::WebhdfsDelegationLoad;
 use super::error::parse_error;
 use super::message::BooleanResp;
 use super::message::FileStatusType;
@@ -53,6 +55,7 @@ const WEBHDFS_DEFAULT_ENDPOINT: &str = "http://127.0.0.1:9870";
 ///
 /// - [x] read
 /// - [x] write
+/// - [x] append
 /// - [x] list
 /// - [ ] ~~scan~~
 /// - [ ] ~~presign~~
@@ -62,14 +65,30 @@ const WEBHDFS_DEFAULT_ENDPOINT: &str = "http://127.0.0.1:9870";
 ///
 /// [Hdfs][crate::services::Hdfs] is powered by HDFS's native java client. Users need to setup the hdfs services correctly. But webhdfs can access from HTTP API and no extra setup needed.
 ///
+/// # Append
+///
+/// Data written by [`Writer::append`][crate::Writer::append] will be appended
+/// to the end of the file via WebHDFS's `APPEND` operation. The file will be
+/// created if it doesn't exist yet.
+///
 /// # Configurations
 ///
 /// - `root`: The root path of the WebHDFS service.
 /// - `endpoint`: The endpoint of the WebHDFS service.
 /// - `delegation`: The delegation token for WebHDFS.
+/// - `user_name`: The user name for WebHDFS's simple authentication.
 ///
 /// Refer to [`Builder`]'s public API docs for more information
 ///
+/// # Authentication
+///
+/// Requests will carry `delegation=<token>` if delegation token is set,
+/// or `user.name=<user>` for simple authentication. They are mutually
+/// exclusive.
+///
+/// Delegation tokens expire, use [`WebhdfsBuilder::customed_delegation_loader`]
+/// to refresh the token of a long-running operator.
+///
 /// # Examples
 ///
 /// ## Via Builder
@@ -108,14 +127,23 @@ pub struct WebhdfsBuilder {
     root: Option<String>,
     endpoint: Option<String>,
     delegation: Option<String>,
+    user_name: Option<String>,
+    customed_delegation_loader: Option<Arc<dyn WebhdfsDelegationLoad>>,
 }
 
 impl Debug for WebhdfsBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Builder")
-            .field("root", &self.root)
+        let mut d = f.debug_struct("Builder");
+        d.field("root", &self.root)
             .field("endpoint", &self.endpoint)
-            .finish_non_exhaustive()
+            .field("user_name", &self.user_name);
+        if self.delegation.is_some() {
+            d.field("delegation", &"<redacted>");
+        }
+        if self.customed_delegation_loader.is_some() {
+            d.field("customed_delegation_loader", &"<customed>");
+        }
+        d.finish_non_exhaustive()
     }
 }
 
@@ -157,14 +185,38 @@ impl WebhdfsBuilder {
     /// used for authentication
     ///
     /// # Note
-    /// The builder prefers using delegation token over username.
-    /// If both are set, delegation token will be used.
+    ///
+    /// Delegation token can't be used together with `user_name`.
     pub fn delegation(&mut self, delegation: &str) -> &mut Self {
         if !delegation.is_empty() {
             self.delegation = Some(delegation.to_string());
         }
         self
     }
+
+    /// Set the user name of this backend, used for simple authentication
+    /// via `user.name`.
+    ///
+    /// # Note
+    ///
+    /// User name can't be used together with delegation token.
+    pub fn user_name(&mut self, user_name: &str) -> &mut Self {
+        if !user_name.is_empty() {
+            self.user_name = Some(user_name.to_string());
+        }
+        self
+    }
+
+    /// Specify the customed delegation token loader used by this service.
+    ///
+    /// Tokens loaded by it take precedence over the static delegation token.
+    pub fn customed_delegation_loader(
+        &mut self,
+        loader: Box<dyn WebhdfsDelegationLoad>,
+    ) -> &mut Self {
+        self.customed_delegation_loader = Some(Arc::from(loader));
+        self
+    }
 }
 
 impl Builder for WebhdfsBuilder {
@@ -177,6 +229,7 @@ impl Builder for WebhdfsBuilder {
         map.get("root").map(|v| builder.root(v));
         map.get("endpoint").map(|v| builder.endpoint(v));
         map.get("delegation").map(|v| builder.delegation(v));
+        map.get("user_name").map(|v| builder.user_name(v));
 
         builder
     }
@@ -207,17 +260,25 @@ impl Builder for WebhdfsBuilder {
         };
         debug!("backend use endpoint {}", endpoint);
 
-        let auth = self
-            .delegation
-            .take()
-            .map(|dt| format!("delegation_token={dt}"));
+        let delegation = self.delegation.take();
+        let user_name = self.user_name.take();
+        let delegation_loader = self.customed_delegation_loader.take();
+        if user_name.is_some() && (delegation.is_some() || delegation_loader.is_some()) {
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "user_name can't be used together with delegation token",
+            )
+            .with_context("service", Scheme::Webhdfs));
+        }
 
         let client = HttpClient::new()?;
 
         let backend = WebhdfsBackend {
             root,
             endpoint,
-            auth,
+            delegation,
+            user_name,
+            delegation_loader,
             client,
             root_checker: OnceCell::new(),
         };
@@ -227,17 +288,51 @@ impl Builder for WebhdfsBuilder {
 }
 
 /// Backend for WebHDFS service
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WebhdfsBackend {
     root: String,
     endpoint: String,
-    auth: Option<String>,
+    delegation: Option<String>,
+    user_name: Option<String>,
+    delegation_loader: Option<Arc<dyn WebhdfsDelegationLoad>>,
     root_checker: OnceCell<()>,
 
     pub client: HttpClient,
 }
 
+impl Debug for WebhdfsBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Backend")
+            .field("root", &self.root)
+            .field("endpoint", &self.endpoint)
+            .field("user_name", &self.user_name)
+            .finish_non_exhaustive()
+    }
+}
+
 impl WebhdfsBackend {
+    /// Build the auth query of request, which starts with `&` if not empty.
+    async fn auth_query(&self) -> Result<String> {
+        if let Some(loader) = &self.delegation_loader {
+            let token = loader.load_delegation().await.map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "loading webhdfs delegation token")
+                    .set_source(err)
+            })?;
+            if let Some(token) = token {
+                return Ok(format!("&delegation={token}"));
+            }
+        }
+
+        if let Some(token) = &self.delegation {
+            return Ok(format!("&delegation={token}"));
+        }
+        if let Some(user_name) = &self.user_name {
+            return Ok(format!("&user.name={}", percent_encode_path(user_name)));
+        }
+
+        Ok(String::new())
+    }
+
     /// create object or make a directory
     ///
     /// TODO: we should split it into mkdir and create
@@ -260,9 +355,7 @@ impl WebhdfsBackend {
             percent_encode_path(&p),
             op,
         );
-        if let Some(auth) = &self.auth {
-            url += format!("&{auth}").as_str();
-        }
+        url += &self.auth_query().await?;
 
         let req = Request::put(&url)
             .body(AsyncBody::Empty)
@@ -292,6 +385,42 @@ impl WebhdfsBackend {
         re_builder.body(body).map_err(new_request_build_error)
     }
 
+    /// Build the request to append data to datanode.
+    ///
+    /// WebHDFS will redirect us to the datanode which holds the last block
+    /// of this file. Return `NotFound` if the file doesn't exist.
+    pub async fn webhdfs_append_request(
+        &self,
+        path: &str,
+        size: usize,
+        body: AsyncBody,
+    ) -> Result<Request<AsyncBody>> {
+        let p = build_abs_path(&self.root, path);
+        let mut url = format!(
+            "{}/webhdfs/v1/{}?op=APPEND",
+            self.endpoint,
+            percent_encode_path(&p),
+        );
+        url += &self.auth_query().await?;
+
+        let req = Request::post(&url)
+            .body(AsyncBody::Empty)
+            .map_err(new_request_build_error)?;
+
+        let resp = self.client.send(req).await?;
+
+        // should be a 307 TEMPORARY_REDIRECT
+        if resp.status() != StatusCode::TEMPORARY_REDIRECT {
+            return Err(parse_error(resp).await?);
+        }
+        let re_url = self.follow_redirect(resp)?;
+
+        Request::post(re_url)
+            .header(CONTENT_LENGTH, size.to_string())
+            .body(body)
+            .map_err(new_request_build_error)
+    }
+
     async fn webhdfs_open_request(
         &self,
         path: &str,
@@ -303,9 +432,7 @@ impl WebhdfsBackend {
             self.endpoint,
             percent_encode_path(&p),
         );
-        if let Some(auth) = &self.auth {
-            url += &format!("&{auth}");
-        }
+        url += &self.auth_query().await?;
 
         if !range.is_full() {
             // Webhdfs does not support read from end
@@ -331,16 +458,14 @@ impl WebhdfsBackend {
         Ok(req)
     }
 
-    fn webhdfs_list_status_request(&self, path: &str) -> Result<Request<AsyncBody>> {
+    async fn webhdfs_list_status_request(&self, path: &str) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
         let mut url = format!(
             "{}/webhdfs/v1/{}?op=LISTSTATUS",
             self.endpoint,
             percent_encode_path(&p),
         );
-        if let Some(auth) = &self.auth {
-            url += format!("&{auth}").as_str();
-        }
+        url += &self.auth_query().await?;
 
         let req = Request::get(&url)
             .body(AsyncBody::Empty)
@@ -376,9 +501,7 @@ impl WebhdfsBackend {
             percent_encode_path(&p),
         );
 
-        if let Some(auth) = &self.auth {
-            url += format!("&{auth}").as_str();
-        }
+        url += &self.auth_query().await?;
 
         let req = Request::get(&url)
             .body(AsyncBody::Empty)
@@ -394,9 +517,7 @@ impl WebhdfsBackend {
             self.endpoint,
             percent_encode_path(&p),
         );
-        if let Some(auth) = &self.auth {
-            url += format!("&{auth}").as_str();
-        }
+        url += &self.auth_query().await?;
 
         let req = Request::delete(&url)
             .body(AsyncBody::Empty)
@@ -465,8 +586,13 @@ impl Accessor for WebhdfsBackend {
         let mut am = AccessorInfo::default();
         am.set_scheme(Scheme::Webhdfs)
             .set_root(&self.root)
+            .set_endpoint(&self.endpoint)
             .set_capabilities(
-                AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::List,
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::Append
+                    | AccessorCapability::List
+                    | AccessorCapability::WriteWithContentType,
             )
             .set_hints(AccessorHint::ReadStreamable);
         am
@@ -519,13 +645,6 @@ impl Accessor for WebhdfsBackend {
     }
 
     async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
-        if args.append() {
-            return Err(Error::new(
-                ErrorKind::Unsupported,
-                "append write is not supported",
-            ));
-        }
-
         Ok((
             RpWrite::default(),
             WebhdfsWriter::new(self.clone(), args, path.to_string()),
@@ -578,7 +697,7 @@ impl Accessor for WebhdfsBackend {
 
     async fn list(&self, path: &str, _: OpList) -> Result<(RpList, Self::Pager)> {
         let path = path.trim_end_matches('/');
-        let req = self.webhdfs_list_status_request(path)?;
+        let req = self.webhdfs_list_status_request(path).await?;
 
         let resp = self.client.send(req).await?;
         match resp.status() {
@@ -600,3 +719,182 @@ impl Accessor for WebhdfsBackend {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use anyhow::Result;
+    use wiremock::matchers::body_bytes;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+
+    use super::*;
+    use crate::Operator;
+
+    #[tokio::test]
+    async fn test_append() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhdfs/v1/root/log"))
+            .and(query_param("op", "APPEND"))
+            .and(query_param("delegation", "token"))
+            .respond_with(ResponseTemplate::new(307).insert_header(
+                "Location",
+                format!("{}/datanode", mock_server.uri()).as_str(),
+            ))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/datanode"))
+            .and(body_bytes("hello"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/datanode"))
+            .and(body_bytes("world"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebhdfsBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .root("/root")
+            .delegation("token");
+        let op = Operator::new(builder)?.finish();
+
+        let mut w = op.writer("log").await?;
+        w.append("hello").await?;
+        w.append("world").await?;
+        w.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append_not_found() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhdfs/v1/log"))
+            .and(query_param("op", "APPEND"))
+            .respond_with(ResponseTemplate::new(404).set_body_string(
+                r#"{"RemoteException":{"exception":"FileNotFoundException","javaClassName":"java.io.FileNotFoundException","message":"File does not exist: /log"}}"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/webhdfs/v1/log"))
+            .and(query_param("op", "CREATE"))
+            .respond_with(ResponseTemplate::new(307).insert_header(
+                "Location",
+                format!("{}/datanode", mock_server.uri()).as_str(),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/datanode"))
+            .and(body_bytes("hello"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut builder = WebhdfsBuilder::default();
+        builder.endpoint(&mock_server.uri());
+        let op = Operator::new(builder)?.finish();
+
+        let mut w = op.writer("log").await?;
+        w.append("hello").await?;
+        w.close().await?;
+
+        Ok(())
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingLoader {
+        count: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WebhdfsDelegationLoad for CountingLoader {
+        async fn load_delegation(&self) -> Result<Option<String>> {
+            let n = self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(format!("token-{n}")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_customed_delegation_loader() -> Result<()> {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mock_server = MockServer::start().await;
+        for token in ["token-0", "token-1"] {
+            Mock::given(method("DELETE"))
+                .and(path("/webhdfs/v1/file"))
+                .and(query_param("delegation", token))
+                .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"boolean":true}"#))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let mut builder = WebhdfsBuilder::default();
+        builder
+            .endpoint(&mock_server.uri())
+            .delegation("static")
+            .customed_delegation_loader(Box::<CountingLoader>::default());
+        let op = Operator::new(builder)?.finish();
+
+        // Every request should use the latest token.
+        op.delete("file").await?;
+        op.delete("file").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_name() -> Result<()> {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/webhdfs/v1/file"))
+            .and(query_param("user.name", "hadoop"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"boolean":true}"#))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let builder = WebhdfsBuilder::from_map(HashMap::from([
+            ("endpoint".to_string(), mock_server.uri()),
+            ("user_name".to_string(), "hadoop".to_string()),
+        ]));
+        let op = Operator::new(builder)?.finish();
+        op.delete("file").await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_name_with_delegation() {
+        let mut builder = WebhdfsBuilder::default();
+        builder.user_name("hadoop").delegation("token");
+
+        let err = builder.build().expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
@@ -0,0 +1,33 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+/// Customized delegation token loader for WebHDFS.
+///
+/// Delegation tokens expire, implement it to replace the token of a live
+/// operator. `load_delegation` will be called before every request, so
+/// implementations should cache the token and only refresh it while it's
+/// going to expire.
+#[async_trait]
+pub trait WebhdfsDelegationLoad: 'static + Send + Sync + Debug {
+    /// Load delegation token, returning `None` to fallback to the static
+    /// delegation token.
+    async fn load_delegation(&self) -> anyhow::Result<Option<String>>;
+}
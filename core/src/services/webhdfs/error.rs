@@ -61,7 +61,10 @@ fn parse_error_msg(parts: Parts, body: &str) -> Result<Error> {
     };
 
     let message = match serde_json::from_str::<WebHdfsErrorWrapper>(body) {
-        Ok(wh_error) => format!("{:?}", wh_error.remote_exception),
+        Ok(wh_error) => format!(
+            "{}: {}",
+            wh_error.remote_exception.exception, wh_error.remote_exception.message
+        ),
         Err(_) => body.to_owned(),
     };
 
@@ -122,4 +125,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_parse_error_permission_denied() -> Result<()> {
+        let bs = bytes::Bytes::from(
+            r#"{"RemoteException":{"exception":"SecurityException","javaClassName":"java.lang.SecurityException","message":"Failed to obtain user group information: token expired"}}"#,
+        );
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let body = IncomingAsyncBody::new(Box::new(stream::iter(vec![Ok(bs.clone())])), None);
+            let resp = Response::builder().status(status).body(body).unwrap();
+
+            let err = parse_error(resp).await?;
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+            assert!(err
+                .to_string()
+                .contains("SecurityException: Failed to obtain user group information"));
+        }
+
+        Ok(())
+    }
 }
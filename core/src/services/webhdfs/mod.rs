@@ -18,6 +18,8 @@
 mod backend;
 pub use backend::WebhdfsBuilder as Webhdfs;
 
+mod delegation;
+pub use delegation::WebhdfsDelegationLoad;
 mod error;
 mod message;
 mod pager;
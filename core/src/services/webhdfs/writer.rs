@@ -30,11 +30,18 @@ pub struct WebhdfsWriter {
 
     op: OpWrite,
     path: String,
+    /// Whether data has been appended to the file.
+    appended: bool,
 }
 
 impl WebhdfsWriter {
     pub fn new(backend: WebhdfsBackend, op: OpWrite, path: String) -> Self {
-        WebhdfsWriter { backend, op, path }
+        WebhdfsWriter {
+            backend,
+            op,
+            path,
+            appended: false,
+        }
     }
 }
 
@@ -64,15 +71,43 @@ impl oio::Write for WebhdfsWriter {
     }
 
     async fn append(&mut self, bs: Bytes) -> Result<()> {
-        let _ = bs;
+        let req = match self
+            .backend
+            .webhdfs_append_request(&self.path, bs.len(), AsyncBody::Bytes(bs.clone()))
+            .await
+        {
+            Ok(req) => req,
+            // Namenode returns `FileNotFoundException` if file is not exist,
+            // create it with the given content instead.
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.write(bs).await?;
+                self.appended = true;
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
 
-        Err(Error::new(
-            ErrorKind::Unsupported,
-            "output writer doesn't support append",
-        ))
+        let resp = self.backend.client.send(req).await?;
+
+        let status = resp.status();
+        match status {
+            StatusCode::OK => {
+                resp.into_body().consume().await?;
+                self.appended = true;
+                Ok(())
+            }
+            _ => Err(parse_error(resp).await?),
+        }
     }
 
     async fn abort(&mut self) -> Result<()> {
+        if self.appended {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "webhdfs doesn't support abort appended data",
+            ));
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,328 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Write;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use md5::Digest;
+use md5::Md5;
+
+use crate::*;
+
+/// ChecksumAlgorithm is the algorithm used by [`Operator::checksum`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgorithm {
+    /// MD5 digest of content, 16 bytes.
+    Md5,
+    /// CRC32C (Castagnoli) of content, 4 bytes in big endian.
+    Crc32c,
+}
+
+impl ChecksumAlgorithm {
+    /// Size of the digest in bytes.
+    fn size(&self) -> usize {
+        match self {
+            ChecksumAlgorithm::Md5 => 16,
+            ChecksumAlgorithm::Crc32c => 4,
+        }
+    }
+}
+
+/// ChecksumStrategy tells how a [`Checksum`] is got.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumStrategy {
+    /// Checksum is returned by service in metadata, content is not
+    /// downloaded.
+    Metadata,
+    /// Checksum is computed locally by reading the whole content.
+    Computed,
+}
+
+/// Checksum of content returned by [`Operator::checksum`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Checksum {
+    algorithm: ChecksumAlgorithm,
+    strategy: ChecksumStrategy,
+    digest: Vec<u8>,
+}
+
+impl Debug for Checksum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Checksum")
+            .field("algorithm", &self.algorithm)
+            .field("strategy", &self.strategy)
+            .field("digest", &self.to_hex())
+            .finish()
+    }
+}
+
+impl Checksum {
+    /// Algorithm of this checksum.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Strategy used to get this checksum.
+    pub fn strategy(&self) -> ChecksumStrategy {
+        self.strategy
+    }
+
+    /// Raw digest bytes of this checksum.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Format digest in lower case hex, like `fc7704...`.
+    pub fn to_hex(&self) -> String {
+        self.digest
+            .iter()
+            .fold(String::with_capacity(self.digest.len() * 2), |mut s, b| {
+                let _ = write!(s, "{b:02x}");
+                s
+            })
+    }
+
+    /// Format digest in standard base64, like `fHcEH1vPwA6eTPqxuasXcg==`.
+    pub fn to_base64(&self) -> String {
+        BASE64_STANDARD.encode(&self.digest)
+    }
+
+    /// Try to get checksum from metadata returned by stat.
+    ///
+    /// Values that can't be decoded into a digest of expected size will be
+    /// ignored, so we will fallback to compute it locally.
+    pub(crate) fn from_metadata(
+        scheme: Scheme,
+        meta: &Metadata,
+        algorithm: ChecksumAlgorithm,
+    ) -> Option<Self> {
+        let checksum = meta
+            .checksum_algorithm()
+            .zip(meta.checksum())
+            .filter(|(algo, _)| match algorithm {
+                ChecksumAlgorithm::Md5 => algo.eq_ignore_ascii_case("MD5"),
+                ChecksumAlgorithm::Crc32c => algo.eq_ignore_ascii_case("CRC32C"),
+            })
+            .and_then(|(_, v)| decode_base64(v));
+
+        let digest = match algorithm {
+            ChecksumAlgorithm::Md5 => checksum
+                .or_else(|| meta.content_md5().and_then(decode_base64))
+                .or_else(|| {
+                    meta.etag()
+                        .filter(|_| !meta.is_etag_opaque())
+                        .and_then(|v| parse_etag_md5(scheme, v))
+                }),
+            ChecksumAlgorithm::Crc32c => checksum,
+        }?;
+
+        if digest.len() != algorithm.size() {
+            return None;
+        }
+
+        Some(Self {
+            algorithm,
+            strategy: ChecksumStrategy::Metadata,
+            digest,
+        })
+    }
+}
+
+/// ChecksumHasher computes checksum by feeding content.
+pub(crate) enum ChecksumHasher {
+    Md5(Md5),
+    Crc32c(u32),
+}
+
+impl ChecksumHasher {
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Md5 => ChecksumHasher::Md5(Md5::new()),
+            ChecksumAlgorithm::Crc32c => ChecksumHasher::Crc32c(0),
+        }
+    }
+
+    pub fn update(&mut self, bs: &[u8]) {
+        match self {
+            ChecksumHasher::Md5(h) => h.update(bs),
+            ChecksumHasher::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, bs),
+        }
+    }
+
+    pub fn finish(self) -> Checksum {
+        let (algorithm, digest) = match self {
+            ChecksumHasher::Md5(h) => (ChecksumAlgorithm::Md5, h.finalize().to_vec()),
+            ChecksumHasher::Crc32c(crc) => (ChecksumAlgorithm::Crc32c, crc.to_be_bytes().to_vec()),
+        };
+
+        Checksum {
+            algorithm,
+            strategy: ChecksumStrategy::Computed,
+            digest,
+        }
+    }
+}
+
+fn decode_base64(v: &str) -> Option<Vec<u8>> {
+    BASE64_STANDARD.decode(v).ok()
+}
+
+/// Parse etag as md5 digest.
+///
+/// Only services that use content md5 as etag for simple uploads are
+/// trusted. Etags of multipart uploads are like `"<hex>-<parts>"`, and
+/// weak etags like `W/"<hex>"` are not digests of content, both of them
+/// will be rejected.
+///
+/// Objects like encrypted by SSE-KMS or SSE-C on s3 and appended via
+/// AppendObject on oss have etags that look like md5 but are not, services
+/// will mark them via `Metadata::set_etag_opaque` so that they won't be
+/// parsed here.
+fn parse_etag_md5(scheme: Scheme, etag: &str) -> Option<Vec<u8>> {
+    if !matches!(
+        scheme,
+        Scheme::S3 | Scheme::Obs | Scheme::Oss | Scheme::Wasabi
+    ) {
+        return None;
+    }
+
+    if etag.starts_with("W/") {
+        return None;
+    }
+    // Some services return etag without quotes.
+    let etag = etag.trim_matches('"');
+    if etag.len() != 32 || !etag.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..etag.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&etag[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_etag_md5() {
+        let cases = vec![
+            (
+                "quoted",
+                Scheme::S3,
+                r#""fc7704075bcfc00e9e4cfab1b9ab1772""#,
+                Some("fc7704075bcfc00e9e4cfab1b9ab1772"),
+            ),
+            (
+                "unquoted upper case",
+                Scheme::Oss,
+                "FC7704075BCFC00E9E4CFAB1B9AB1772",
+                Some("fc7704075bcfc00e9e4cfab1b9ab1772"),
+            ),
+            (
+                "multipart",
+                Scheme::S3,
+                r#""fc7704075bcfc00e9e4cfab1b9ab1772-3""#,
+                None,
+            ),
+            (
+                "multipart with 32 chars",
+                Scheme::S3,
+                r#""fc7704075bcfc00e9e4cfab1b9ab1-12""#,
+                None,
+            ),
+            (
+                "weak",
+                Scheme::S3,
+                r#"W/"fc7704075bcfc00e9e4cfab1b9ab1772""#,
+                None,
+            ),
+            (
+                "untrusted service",
+                Scheme::Azblob,
+                r#""fc7704075bcfc00e9e4cfab1b9ab1772""#,
+                None,
+            ),
+        ];
+
+        for (name, scheme, etag, expected) in cases {
+            let actual = parse_etag_md5(scheme, etag).map(|v| {
+                Checksum {
+                    algorithm: ChecksumAlgorithm::Md5,
+                    strategy: ChecksumStrategy::Metadata,
+                    digest: v,
+                }
+                .to_hex()
+            });
+            assert_eq!(actual.as_deref(), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_from_metadata() {
+        let meta = Metadata::new(EntryMode::FILE)
+            .with_etag(r#""fc7704075bcfc00e9e4cfab1b9ab1772-2""#.to_string())
+            .with_checksum("CRC32C".to_string(), "4waSgw==".to_string())
+            .with_bit(Metakey::Complete);
+
+        // Multipart etag must not be treated as md5.
+        assert!(Checksum::from_metadata(Scheme::S3, &meta, ChecksumAlgorithm::Md5).is_none());
+
+        let checksum = Checksum::from_metadata(Scheme::S3, &meta, ChecksumAlgorithm::Crc32c)
+            .expect("crc32c must be returned");
+        assert_eq!(checksum.strategy(), ChecksumStrategy::Metadata);
+        assert_eq!(checksum.to_hex(), "e3069283");
+
+        let mut meta = Metadata::new(EntryMode::FILE)
+            .with_etag(r#""fc7704075bcfc00e9e4cfab1b9ab1772""#.to_string())
+            .with_bit(Metakey::Complete);
+        let checksum = Checksum::from_metadata(Scheme::S3, &meta, ChecksumAlgorithm::Md5)
+            .expect("md5 must be returned");
+        assert_eq!(checksum.to_hex(), "fc7704075bcfc00e9e4cfab1b9ab1772");
+
+        // Opaque etag must not be treated as md5.
+        meta.set_etag_opaque();
+        assert!(Checksum::from_metadata(Scheme::S3, &meta, ChecksumAlgorithm::Md5).is_none());
+
+        let meta = Metadata::new(EntryMode::FILE)
+            .with_content_md5("fHcEH1vPwA6eTPqxuasXcg==".to_string())
+            .with_bit(Metakey::Complete);
+        let checksum = Checksum::from_metadata(Scheme::Gcs, &meta, ChecksumAlgorithm::Md5)
+            .expect("md5 must be returned");
+        assert_eq!(checksum.to_hex(), "7c77041f5bcfc00e9e4cfab1b9ab1772");
+    }
+
+    #[test]
+    fn test_checksum_hasher() {
+        let mut h = ChecksumHasher::new(ChecksumAlgorithm::Crc32c);
+        h.update(b"12345");
+        h.update(b"6789");
+        assert_eq!(h.finish().to_base64(), "4waSgw==");
+
+        let mut h = ChecksumHasher::new(ChecksumAlgorithm::Md5);
+        h.update(b"");
+        let checksum = h.finish();
+        assert_eq!(checksum.strategy(), ChecksumStrategy::Computed);
+        assert_eq!(checksum.to_hex(), "d41d8cd98f00b204e9800998ecf8427e");
+    }
+}
@@ -75,6 +75,14 @@ pub enum ErrorKind {
     /// For example, reading a file with If-Match header but the file's ETag
     /// is not match.
     PreconditionFailed,
+    /// The given path is archived and must be restored before reading.
+    ///
+    /// For example, reading an object in s3 `GLACIER` storage class.
+    Archived,
+    /// The underlying storage doesn't have enough space for this operation.
+    ///
+    /// For example, writing to a full disk or exceeding the quota.
+    InsufficientStorage,
 }
 
 impl ErrorKind {
@@ -104,6 +112,8 @@ impl From<ErrorKind> for &'static str {
             ErrorKind::RateLimited => "RateLimited",
             ErrorKind::IsSameFile => "IsSameFile",
             ErrorKind::PreconditionFailed => "PreconditionFailed",
+            ErrorKind::Archived => "Archived",
+            ErrorKind::InsufficientStorage => "InsufficientStorage",
         }
     }
 }
@@ -43,6 +43,23 @@ pub struct Metadata {
     content_type: Option<String>,
     etag: Option<String>,
     last_modified: Option<DateTime<Utc>>,
+    /// Service specific metadata that most entries don't carry, boxed to
+    /// keep every listed entry small.
+    extended: Option<Box<ExtendedMetadata>>,
+}
+
+/// ExtendedMetadata carries the rarely set metadata like versions and
+/// storage classes.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct ExtendedMetadata {
+    version: Option<String>,
+    is_current: Option<bool>,
+    is_deleted: bool,
+    storage_class: Option<String>,
+    restore_status: Option<String>,
+    checksum: Option<(String, String)>,
+    /// Etag is known not to be the md5 of content.
+    opaque_etag: bool,
 }
 
 impl Metadata {
@@ -66,9 +83,15 @@ impl Metadata {
             last_modified: None,
             etag: None,
             content_disposition: None,
+            extended: None,
         }
     }
 
+    /// Get the extended metadata, create it if not exist.
+    fn extended_mut(&mut self) -> &mut ExtendedMetadata {
+        self.extended.get_or_insert_with(Default::default)
+    }
+
     /// Get the bit from metadata.
     pub(crate) fn bit(&self) -> FlagSet<Metakey> {
         self.bit
@@ -330,6 +353,18 @@ impl Metadata {
         self
     }
 
+    /// Check if the etag of this entry is known not to be the md5 of content.
+    pub(crate) fn is_etag_opaque(&self) -> bool {
+        self.extended.as_ref().map_or(false, |v| v.opaque_etag)
+    }
+
+    /// Mark the etag of this entry is not the md5 of content even if it
+    /// looks like one, for example objects encrypted by SSE-KMS on s3.
+    pub(crate) fn set_etag_opaque(&mut self) -> &mut Self {
+        self.extended_mut().opaque_etag = true;
+        self
+    }
+
     /// Content-Disposition of this entry
     ///
     /// `Content-Disposition` is defined by [RFC 2616](https://www.rfc-editor/rfcs/2616) and
@@ -383,6 +418,197 @@ impl Metadata {
         self.bit |= Metakey::ContentDisposition;
         self
     }
+
+    /// Version of this entry.
+    ///
+    /// Version is the identifier of a specific version of the object on
+    /// services that support versioning, like `x-amz-version-id` of s3.
+    pub fn version(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Version) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: version, maybe a bug"
+        );
+
+        self.extended.as_ref().and_then(|v| v.version.as_deref())
+    }
+
+    /// Set version of this entry.
+    pub fn with_version(mut self, version: String) -> Self {
+        self.extended_mut().version = Some(version);
+        self.bit |= Metakey::Version;
+        self
+    }
+
+    /// Set version of this entry.
+    pub fn set_version(&mut self, version: &str) -> &mut Self {
+        self.extended_mut().version = Some(version.to_string());
+        self.bit |= Metakey::Version;
+        self
+    }
+
+    /// Is this entry the current (latest) version of the object.
+    ///
+    /// Only available for entries returned by listing versions.
+    pub fn is_current(&self) -> Option<bool> {
+        debug_assert!(
+            self.bit.contains(Metakey::IsCurrent) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: is_current, maybe a bug"
+        );
+
+        self.extended.as_ref().and_then(|v| v.is_current)
+    }
+
+    /// Set is_current of this entry.
+    pub fn with_is_current(mut self, is_current: bool) -> Self {
+        self.extended_mut().is_current = Some(is_current);
+        self.bit |= Metakey::IsCurrent;
+        self
+    }
+
+    /// Set is_current of this entry.
+    pub fn set_is_current(&mut self, is_current: bool) -> &mut Self {
+        self.extended_mut().is_current = Some(is_current);
+        self.bit |= Metakey::IsCurrent;
+        self
+    }
+
+    /// Is this entry a delete marker of the object.
+    ///
+    /// Only entries returned by listing versions could be delete markers.
+    pub fn is_deleted(&self) -> bool {
+        debug_assert!(
+            self.bit.contains(Metakey::IsDeleted) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: is_deleted, maybe a bug"
+        );
+
+        self.extended.as_ref().map_or(false, |v| v.is_deleted)
+    }
+
+    /// Set is_deleted of this entry.
+    pub fn with_is_deleted(mut self, is_deleted: bool) -> Self {
+        self.extended_mut().is_deleted = is_deleted;
+        self.bit |= Metakey::IsDeleted;
+        self
+    }
+
+    /// Set is_deleted of this entry.
+    pub fn set_is_deleted(&mut self, is_deleted: bool) -> &mut Self {
+        self.extended_mut().is_deleted = is_deleted;
+        self.bit |= Metakey::IsDeleted;
+        self
+    }
+
+    /// Storage class of this entry.
+    ///
+    /// OpenDAL will return this value AS-IS like the following:
+    ///
+    /// - `STANDARD`
+    /// - `GLACIER`
+    /// - `DEEP_ARCHIVE`
+    pub fn storage_class(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::StorageClass) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: storage_class, maybe a bug"
+        );
+
+        self.extended
+            .as_ref()
+            .and_then(|v| v.storage_class.as_deref())
+    }
+
+    /// Set storage class of this entry.
+    pub fn with_storage_class(mut self, storage_class: String) -> Self {
+        self.extended_mut().storage_class = Some(storage_class);
+        self.bit |= Metakey::StorageClass;
+        self
+    }
+
+    /// Set storage class of this entry.
+    pub fn set_storage_class(&mut self, storage_class: &str) -> &mut Self {
+        self.extended_mut().storage_class = Some(storage_class.to_string());
+        self.bit |= Metakey::StorageClass;
+        self
+    }
+
+    /// Restore status of this archived entry.
+    ///
+    /// OpenDAL will return this value AS-IS like the following (from s3's
+    /// `x-amz-restore`):
+    ///
+    /// - `ongoing-request="true"`
+    /// - `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`
+    ///
+    /// `None` means this entry is not archived or the restore has never
+    /// been requested.
+    pub fn restore_status(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::RestoreStatus) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: restore_status, maybe a bug"
+        );
+
+        self.extended
+            .as_ref()
+            .and_then(|v| v.restore_status.as_deref())
+    }
+
+    /// Set restore status of this entry.
+    pub fn with_restore_status(mut self, restore_status: String) -> Self {
+        self.extended_mut().restore_status = Some(restore_status);
+        self.bit |= Metakey::RestoreStatus;
+        self
+    }
+
+    /// Set restore status of this entry.
+    pub fn set_restore_status(&mut self, restore_status: &str) -> &mut Self {
+        self.extended_mut().restore_status = Some(restore_status.to_string());
+        self.bit |= Metakey::RestoreStatus;
+        self
+    }
+
+    /// Algorithm of the checksum returned by service, like `CRC32C`,
+    /// `SHA1` and `SHA256`.
+    pub fn checksum_algorithm(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Checksum) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: checksum, maybe a bug"
+        );
+
+        self.extended
+            .as_ref()
+            .and_then(|v| v.checksum.as_ref())
+            .map(|(algorithm, _)| algorithm.as_str())
+    }
+
+    /// Checksum of this entry returned by service AS-IS.
+    ///
+    /// For example, s3 returns base64 encoded checksum like `yZRlqg==`.
+    ///
+    /// The algorithm could be got via [`Metadata::checksum_algorithm`].
+    pub fn checksum(&self) -> Option<&str> {
+        debug_assert!(
+            self.bit.contains(Metakey::Checksum) || self.bit.contains(Metakey::Complete),
+            "visiting not set metadata: checksum, maybe a bug"
+        );
+
+        self.extended
+            .as_ref()
+            .and_then(|v| v.checksum.as_ref())
+            .map(|(_, checksum)| checksum.as_str())
+    }
+
+    /// Set checksum and its algorithm of this entry.
+    pub fn with_checksum(mut self, algorithm: String, checksum: String) -> Self {
+        self.extended_mut().checksum = Some((algorithm, checksum));
+        self.bit |= Metakey::Checksum;
+        self
+    }
+
+    /// Set checksum and its algorithm of this entry.
+    pub fn set_checksum(&mut self, algorithm: &str, checksum: &str) -> &mut Self {
+        self.extended_mut().checksum = Some((algorithm.to_string(), checksum.to_string()));
+        self.bit |= Metakey::Checksum;
+        self
+    }
 }
 
 flags! {
@@ -420,5 +646,17 @@ flags! {
         Etag,
         /// Key for last last modified.
         LastModified,
+        /// Key for version.
+        Version,
+        /// Key for is_current.
+        IsCurrent,
+        /// Key for is_deleted.
+        IsDeleted,
+        /// Key for storage class.
+        StorageClass,
+        /// Key for restore status.
+        RestoreStatus,
+        /// Key for checksum and checksum algorithm.
+        Checksum,
     }
 }
@@ -54,4 +54,18 @@ pub use error::Result;
 mod scheme;
 pub use scheme::Scheme;
 
+mod profile;
+pub use profile::Profile;
+
+mod checksum;
+pub use checksum::Checksum;
+pub use checksum::ChecksumAlgorithm;
+pub(crate) use checksum::ChecksumHasher;
+pub use checksum::ChecksumStrategy;
+
+mod progress;
+pub(crate) use progress::range_size;
+pub(crate) use progress::Progress;
+pub(crate) use progress::ProgressFn;
+
 pub mod ops;
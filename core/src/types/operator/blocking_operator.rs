@@ -594,8 +594,22 @@ impl BlockingOperator {
             );
         }
 
+        let bs = bs.into();
+        let args = match args.content_length() {
+            Some(_) => args,
+            None => args.with_content_length(bs.len() as u64),
+        };
+
+        let mut progress = args
+            .progress()
+            .map(|f| Progress::new(f, args.content_length()));
+        let size = bs.len();
+
         let (_, mut w) = self.inner().blocking_write(&path, args)?;
-        w.write(bs.into())?;
+        w.write(bs)?;
+        if let Some(p) = &mut progress {
+            p.advance(size);
+        }
         w.close()?;
 
         Ok(())
@@ -625,6 +639,32 @@ impl BlockingOperator {
     /// # }
     /// ```
     pub fn writer(&self, path: &str) -> Result<BlockingWriter> {
+        self.writer_with(path, OpWrite::default())
+    }
+
+    /// Write multiple bytes into given path with extra options.
+    ///
+    /// # Notes
+    ///
+    /// - Write will make sure all bytes has been written, or an error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::BlockingOperator;
+    /// use opendal::ops::OpWrite;
+    ///
+    /// # fn test(op: BlockingOperator) -> Result<()> {
+    /// let args = OpWrite::new().with_abort_on_drop(false);
+    /// let mut w = op.writer_with("path/to/file", args)?;
+    /// w.append(vec![0; 4096])?;
+    /// w.append(vec![1; 4096])?;
+    /// w.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn writer_with(&self, path: &str, args: OpWrite) -> Result<BlockingWriter> {
         let path = normalize_path(path);
 
         if !validate_path(&path, EntryMode::FILE) {
@@ -636,8 +676,7 @@ impl BlockingOperator {
             );
         }
 
-        let op = OpWrite::default().with_append();
-        BlockingWriter::create_dir(self.inner().clone(), &path, op)
+        BlockingWriter::create_dir(self.inner().clone(), &path, args.with_append())
     }
 
     /// Delete given path.
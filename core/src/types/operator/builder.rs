@@ -16,6 +16,7 @@
 // under the License.
 
 use std::collections::HashMap;
+use std::env;
 use std::sync::Arc;
 
 use crate::layers::*;
@@ -110,6 +111,183 @@ impl Operator {
         Ok(OperatorBuilder::new(acc))
     }
 
+    /// Create a new operator via given scheme and map.
+    ///
+    /// Unlike [`Operator::from_map`], the service is decided at runtime,
+    /// which is useful for applications that load services from config.
+    ///
+    /// Returns [`ErrorKind::Unsupported`] if the scheme is unknown or not
+    /// enabled by cargo features, all enabled schemes will be carried in
+    /// the error context.
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// use std::collections::HashMap;
+    ///
+    /// use opendal::Operator;
+    /// use opendal::Scheme;
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let map = HashMap::from([
+    ///         // Set the root for fs, all operations will happen under this root.
+    ///         //
+    ///         // NOTE: the root must be absolute path.
+    ///         ("root".to_string(), "/tmp".to_string()),
+    ///     ]);
+    ///
+    ///     // Build an `Operator` to start operating the storage.
+    ///     let op: Operator = Operator::via_map(Scheme::Fs, map)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn via_map(scheme: Scheme, map: HashMap<String, String>) -> Result<Operator> {
+        let op = match scheme {
+            #[cfg(feature = "services-azblob")]
+            Scheme::Azblob => Self::from_map::<services::Azblob>(map)?.finish(),
+            #[cfg(feature = "services-azdfs")]
+            Scheme::Azdfs => Self::from_map::<services::Azdfs>(map)?.finish(),
+            #[cfg(feature = "services-azfile")]
+            Scheme::Azfile => Self::from_map::<services::Azfile>(map)?.finish(),
+            #[cfg(feature = "services-b2")]
+            Scheme::B2 => Self::from_map::<services::B2>(map)?.finish(),
+            #[cfg(feature = "services-cloudflare-kv")]
+            Scheme::CloudflareKv => Self::from_map::<services::CloudflareKv>(map)?.finish(),
+            #[cfg(feature = "services-dashmap")]
+            Scheme::Dashmap => Self::from_map::<services::Dashmap>(map)?.finish(),
+            #[cfg(feature = "services-etcd")]
+            Scheme::Etcd => Self::from_map::<services::Etcd>(map)?.finish(),
+            #[cfg(feature = "services-fs")]
+            Scheme::Fs => Self::from_map::<services::Fs>(map)?.finish(),
+            #[cfg(feature = "services-gcs")]
+            Scheme::Gcs => Self::from_map::<services::Gcs>(map)?.finish(),
+            #[cfg(feature = "services-ghac")]
+            Scheme::Ghac => Self::from_map::<services::Ghac>(map)?.finish(),
+            #[cfg(feature = "services-gridfs")]
+            Scheme::Gridfs => Self::from_map::<services::Gridfs>(map)?.finish(),
+            #[cfg(feature = "services-hdfs")]
+            Scheme::Hdfs => Self::from_map::<services::Hdfs>(map)?.finish(),
+            #[cfg(feature = "services-http")]
+            Scheme::Http => Self::from_map::<services::Http>(map)?.finish(),
+            #[cfg(feature = "services-ftp")]
+            Scheme::Ftp => Self::from_map::<services::Ftp>(map)?.finish(),
+            #[cfg(feature = "services-ipfs")]
+            Scheme::Ipfs => Self::from_map::<services::Ipfs>(map)?.finish(),
+            #[cfg(feature = "services-ipmfs")]
+            Scheme::Ipmfs => Self::from_map::<services::Ipmfs>(map)?.finish(),
+            #[cfg(feature = "services-memcached")]
+            Scheme::Memcached => Self::from_map::<services::Memcached>(map)?.finish(),
+            #[cfg(feature = "services-memory")]
+            Scheme::Memory => Self::from_map::<services::Memory>(map)?.finish(),
+            #[cfg(feature = "services-moka")]
+            Scheme::Moka => Self::from_map::<services::Moka>(map)?.finish(),
+            #[cfg(feature = "services-mysql")]
+            Scheme::Mysql => Self::from_map::<services::Mysql>(map)?.finish(),
+            #[cfg(feature = "services-obs")]
+            Scheme::Obs => Self::from_map::<services::Obs>(map)?.finish(),
+            #[cfg(feature = "services-oss")]
+            Scheme::Oss => Self::from_map::<services::Oss>(map)?.finish(),
+            #[cfg(feature = "services-postgres")]
+            Scheme::Postgres => Self::from_map::<services::Postgres>(map)?.finish(),
+            #[cfg(feature = "services-redis")]
+            Scheme::Redis => Self::from_map::<services::Redis>(map)?.finish(),
+            #[cfg(feature = "services-rocksdb")]
+            Scheme::Rocksdb => Self::from_map::<services::Rocksdb>(map)?.finish(),
+            #[cfg(feature = "services-s3")]
+            Scheme::S3 => Self::from_map::<services::S3>(map)?.finish(),
+            #[cfg(feature = "services-sftp")]
+            Scheme::Sftp => Self::from_map::<services::Sftp>(map)?.finish(),
+            #[cfg(feature = "services-sled")]
+            Scheme::Sled => Self::from_map::<services::Sled>(map)?.finish(),
+            #[cfg(feature = "services-sqlite")]
+            Scheme::Sqlite => Self::from_map::<services::Sqlite>(map)?.finish(),
+            #[cfg(feature = "services-swift")]
+            Scheme::Swift => Self::from_map::<services::Swift>(map)?.finish(),
+            #[cfg(feature = "services-tikv")]
+            Scheme::Tikv => Self::from_map::<services::Tikv>(map)?.finish(),
+            #[cfg(feature = "services-wasabi")]
+            Scheme::Wasabi => Self::from_map::<services::Wasabi>(map)?.finish(),
+            #[cfg(feature = "services-webdav")]
+            Scheme::Webdav => Self::from_map::<services::Webdav>(map)?.finish(),
+            #[cfg(feature = "services-webhdfs")]
+            Scheme::Webhdfs => Self::from_map::<services::Webhdfs>(map)?.finish(),
+            v => {
+                let enabled = Scheme::enabled()
+                    .into_iter()
+                    .map(|v| v.into_static())
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "scheme is not supported or not enabled by cargo features",
+                )
+                .with_operation("Operator::via_map")
+                .with_context("scheme", v)
+                .with_context("enabled", enabled));
+            }
+        };
+
+        Ok(op)
+    }
+
+    /// Create a new operator from typed [`ServiceConfig`][crate::services::ServiceConfig].
+    ///
+    /// Config could be deserialized from any format supported by serde, so
+    /// that applications can embed services' config in their own config
+    /// files.
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// use opendal::services::ServiceConfig;
+    /// use opendal::Operator;
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let cfg: ServiceConfig = serde_json::from_str(
+    ///         r#"{"scheme": "fs", "root": "/tmp"}"#,
+    ///     )?;
+    ///
+    ///     // Build an `Operator` to start operating the storage.
+    ///     let op: Operator = Operator::from_config(cfg)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_config(cfg: services::ServiceConfig) -> Result<Operator> {
+        let scheme = cfg.scheme();
+        Self::via_map(scheme, cfg.into_map())
+    }
+
+    /// Create a new operator via given scheme from envs like `OPENDAL_S3_BUCKET`.
+    ///
+    /// This is the runtime version of [`Operator::from_env`].
+    pub fn via_env(scheme: Scheme) -> Result<Operator> {
+        let prefix = format!("opendal_{scheme}_");
+        let map = env::vars()
+            .filter_map(|(k, v)| {
+                k.to_lowercase()
+                    .strip_prefix(&prefix)
+                    .map(|k| (k.to_string(), v))
+            })
+            .collect();
+
+        Self::via_map(scheme, map)
+    }
+
+    /// Create a new operator from the profile with given name, which is
+    /// loaded from envs like `OPENDAL_{NAME}_{KEY}`.
+    ///
+    /// Read [`Profile`] for more details.
+    pub fn from_profile(name: &str) -> Result<Operator> {
+        Self::via_profile(Profile::from_env(name)?)
+    }
+
+    /// Create a new operator from given [`Profile`].
+    pub fn via_profile(profile: Profile) -> Result<Operator> {
+        let scheme = profile.scheme();
+        Self::via_map(scheme, profile.into_map())
+    }
+
     /// Create a new operator from iter.
     ///
     /// # WARNING
@@ -218,6 +396,7 @@ impl Operator {
 /// ```
 pub struct OperatorBuilder<A: Accessor> {
     accessor: A,
+    correctness_check: bool,
 }
 
 impl<A: Accessor> OperatorBuilder<A> {
@@ -225,9 +404,12 @@ impl<A: Accessor> OperatorBuilder<A> {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(accessor: A) -> OperatorBuilder<impl Accessor> {
         // Make sure error context layer has been attached.
-        OperatorBuilder { accessor }
-            .layer(ErrorContextLayer)
-            .layer(CompleteLayer)
+        OperatorBuilder {
+            accessor,
+            correctness_check: true,
+        }
+        .layer(ErrorContextLayer)
+        .layer(CompleteLayer)
     }
 
     /// Create a new layer with static dispatch.
@@ -264,13 +446,34 @@ impl<A: Accessor> OperatorBuilder<A> {
     pub fn layer<L: Layer<A>>(self, layer: L) -> OperatorBuilder<L::LayeredAccessor> {
         OperatorBuilder {
             accessor: layer.layer(self.accessor),
+            correctness_check: self.correctness_check,
         }
     }
 
+    /// Disable the correctness check while building the operator.
+    ///
+    /// By default, OpenDAL will check whether the args of operations are
+    /// supported by the underlying service and return an `Unsupported`
+    /// error if not. Users who want to pass the args to services as is
+    /// can disable it.
+    #[must_use]
+    pub fn disable_correctness_check(mut self) -> Self {
+        self.correctness_check = false;
+        self
+    }
+
     /// Finish the building to construct an Operator.
     pub fn finish(self) -> Operator {
-        let ob = self.layer(TypeEraseLayer);
+        let accessor = if self.correctness_check {
+            Arc::new(
+                self.layer(CorrectnessCheckLayer)
+                    .layer(TypeEraseLayer)
+                    .accessor,
+            ) as FusedAccessor
+        } else {
+            Arc::new(self.layer(TypeEraseLayer).accessor) as FusedAccessor
+        };
 
-        Operator::from_inner(Arc::new(ob.accessor) as FusedAccessor)
+        Operator::from_inner(accessor)
     }
 }
@@ -87,6 +87,13 @@ impl OperatorInfo {
         self.0.capabilities().contains(AccessorCapability::Batch)
     }
 
+    /// Check if current backend supports [`Accessor::write`] with content type or not.
+    pub fn can_write_with_content_type(&self) -> bool {
+        self.0
+            .capabilities()
+            .contains(AccessorCapability::WriteWithContentType)
+    }
+
     /// Check if current backend supports blocking operations or not.
     pub fn can_blocking(&self) -> bool {
         self.0.capabilities().contains(AccessorCapability::Blocking)
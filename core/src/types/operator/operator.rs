@@ -16,16 +16,21 @@
 // under the License.
 
 use std::ops::RangeBounds;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
+use bytes::BufMut;
 use bytes::Bytes;
 use flagset::FlagSet;
+use futures::future;
 use futures::stream;
 use futures::AsyncReadExt;
+use futures::AsyncWrite;
+use futures::AsyncWriteExt;
 use futures::Stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
-use tokio::io::ReadBuf;
 
 use super::BlockingOperator;
 use crate::ops::*;
@@ -68,7 +73,7 @@ pub struct Operator {
 
 /// # Operator basic API.
 impl Operator {
-    pub(super) fn inner(&self) -> &FusedAccessor {
+    pub(crate) fn inner(&self) -> &FusedAccessor {
         &self.accessor
     }
 
@@ -161,6 +166,15 @@ impl Operator {
     /// returned by [`Lister`]. It's highly possible that metadata
     /// you want has already been cached.
     ///
+    /// Path ends with `/` always means a directory and path without it
+    /// always means a file: `stat("abc")` on a directory or `stat("abc/")`
+    /// on a file will return `NotFound`. Dirs that are only implied by the
+    /// entries under them will be returned as directories too.
+    ///
+    /// Services that can't list (like `http` and `ghac`) have no way to
+    /// tell whether a dir exists, they may return a directory for any path
+    /// ends with `/`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -494,6 +508,96 @@ impl Operator {
         range: impl RangeBounds<u64>,
         args: OpRead,
     ) -> Result<Vec<u8>> {
+        let br = BytesRange::from(range);
+        let f = args.progress();
+        let (path, length, r) = self.open_read(path, args.with_range(br)).await?;
+
+        let progress = f.map(|f| Progress::new(f, Some(length as u64)));
+        let mut buffer = Vec::with_capacity(length);
+        self.fill_buf(&path, br, r, length, &mut buffer, progress)
+            .await?;
+
+        Ok(buffer)
+    }
+
+    /// Read the whole path into given buffer.
+    ///
+    /// Content will be written into `buf` directly without allocating
+    /// extra bytes. Returns the size of content that has been written.
+    ///
+    /// # Notes
+    ///
+    /// - An error will be returned before reading if `buf` doesn't have
+    ///   enough capacity for the content length returned by service.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut buf = vec![0; 4096];
+    /// let n = op.read_into("path/to/file", &mut buf.as_mut_slice()).await?;
+    /// let content = &buf[..n];
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_into(&self, path: &str, buf: &mut impl BufMut) -> Result<usize> {
+        self.range_read_into(path, .., buf).await
+    }
+
+    /// Read the specified range of path into given buffer.
+    ///
+    /// # Notes
+    ///
+    /// Read [`Operator::read_into`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// use bytes::BytesMut;
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut buf = BytesMut::with_capacity(1024);
+    /// let n = op
+    ///     .range_read_into("path/to/file", 1024..2048, &mut buf)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn range_read_into(
+        &self,
+        path: &str,
+        range: impl RangeBounds<u64>,
+        buf: &mut impl BufMut,
+    ) -> Result<usize> {
+        let br = BytesRange::from(range);
+        let (path, length, r) = self.open_read(path, OpRead::new().with_range(br)).await?;
+
+        if buf.remaining_mut() < length {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                "buffer doesn't have enough capacity for content",
+            )
+            .with_operation("read_into")
+            .with_context("service", self.inner().info().scheme())
+            .with_context("path", &path)
+            .with_context("range", br.to_string())
+            .with_context("content_length", length.to_string())
+            .with_context("capacity", buf.remaining_mut().to_string()));
+        }
+
+        self.fill_buf(&path, br, r, length, buf, None).await?;
+        Ok(length)
+    }
+
+    /// Open a reader for read operations, returns normalized path and
+    /// content length.
+    async fn open_read(&self, path: &str, args: OpRead) -> Result<(String, usize, oio::Reader)> {
         let path = normalize_path(path);
 
         if !validate_path(&path, EntryMode::FILE) {
@@ -505,33 +609,319 @@ impl Operator {
             );
         }
 
-        let br = BytesRange::from(range);
+        let (rp, r) = self.inner().read(&path, args).await?;
+        let length = rp.into_metadata().content_length() as usize;
 
-        let (rp, mut s) = self.inner().read(&path, args.with_range(br)).await?;
+        Ok((path, length, r))
+    }
 
-        let length = rp.into_metadata().content_length() as usize;
-        let mut buffer = Vec::with_capacity(length);
+    /// Fill `buf` with exactly `length` bytes read from `r`.
+    ///
+    /// For services that read in stream like HTTP, chunks of body will be
+    /// copied into `buf` directly. For others like fs, we will read into
+    /// the spare capacity of `buf` to avoid allocating chunks.
+    async fn fill_buf(
+        &self,
+        path: &str,
+        br: BytesRange,
+        mut r: oio::Reader,
+        length: usize,
+        buf: &mut impl BufMut,
+        mut progress: Option<Progress>,
+    ) -> Result<()> {
+        let info = self.inner().info();
+        let new_error = |msg: &'static str| {
+            Error::new(ErrorKind::Unexpected, msg)
+                .with_operation("range_read")
+                .with_context("service", info.scheme().into_static())
+                .with_context("path", path)
+                .with_context("range", br.to_string())
+        };
+
+        let mut n = 0;
+        if info.hints().contains(AccessorHint::ReadStreamable) {
+            while let Some(bs) = oio::ReadExt::next(&mut r).await {
+                let bs = bs.map_err(|err| new_error("read from storage").set_source(err))?;
+                if n + bs.len() > length {
+                    return Err(new_error("read more than content length")
+                        .with_context("content_length", length.to_string()));
+                }
+                buf.put_slice(&bs);
+                n += bs.len();
+                if let Some(p) = &mut progress {
+                    p.advance(bs.len());
+                }
+            }
+        } else {
+            while n < length {
+                let dst = buf.chunk_mut();
+                let size = dst.len().min(length - n);
+                // Safety: `dst` is valid for `size` bytes, and we fill it
+                // with zero before reading so it's always initialized.
+                let dst = unsafe {
+                    std::ptr::write_bytes(dst.as_mut_ptr(), 0, size);
+                    std::slice::from_raw_parts_mut(dst.as_mut_ptr(), size)
+                };
+
+                let read = r
+                    .read(dst)
+                    .await
+                    .map_err(|err| new_error("read from storage").set_source(err))?;
+                if read == 0 {
+                    break;
+                }
+                // Safety: `read` bytes of `dst` have been filled.
+                unsafe { buf.advance_mut(read) };
+                n += read;
+                if let Some(p) = &mut progress {
+                    p.advance(read);
+                }
+            }
+        }
 
-        let dst = buffer.spare_capacity_mut();
-        let mut buf = ReadBuf::uninit(dst);
+        if n != length {
+            return Err(new_error("read less than content length")
+                .with_context("content_length", length.to_string())
+                .with_context("read", n.to_string()));
+        }
 
-        // Safety: the input buffer is created with_capacity(length).
-        unsafe { buf.assume_init(length) };
+        Ok(())
+    }
 
-        // TODO: use native read api
-        s.read_exact(buf.initialized_mut()).await.map_err(|err| {
-            Error::new(ErrorKind::Unexpected, "read from storage")
-                .with_operation("range_read")
-                .with_context("service", self.inner().info().scheme().into_static())
+    /// Download the whole path into a bytes via concurrent range reads.
+    ///
+    /// # Notes
+    ///
+    /// Read [`Operator::download_to`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::ops::OpDownload;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let bs = op
+    ///     .download_with(
+    ///         "path/to/file",
+    ///         OpDownload::new().with_concurrent(8).with_chunk(16 * 1024 * 1024),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_with(&self, path: &str, args: OpDownload) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.download_to(path, args, &mut buf).await?;
+
+        Ok(buf)
+    }
+
+    /// Download the whole path into given writer via concurrent range reads.
+    ///
+    /// The path will be stat first, then split into chunks which are read
+    /// concurrently and written into `w` in order. Returns the size of
+    /// content that has been written.
+    ///
+    /// # Notes
+    ///
+    /// - If the service supports `if_match`, every range read will carry the
+    ///   etag returned by stat, so the download will fail with
+    ///   [`ErrorKind::PreconditionFailed`] if the path has been changed.
+    /// - Small files, `concurrent <= 1` and services that can't read a range
+    ///   without loading the whole content will be downloaded in a single
+    ///   request.
+    /// - At most `concurrent * chunk` bytes will be buffered in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::ops::OpDownload;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut buf = Vec::new();
+    /// let n = op
+    ///     .download_to("path/to/file", OpDownload::new(), &mut buf)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_to(
+        &self,
+        path: &str,
+        args: OpDownload,
+        mut w: impl AsyncWrite + Unpin,
+    ) -> Result<u64> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "download path is a directory")
+                    .with_operation("download")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let info = self.inner().info();
+        let meta = self.stat(&path).await?;
+        let length = meta.content_length();
+
+        let mut op = OpRead::new();
+        if let Some(etag) = meta.etag() {
+            if info
+                .capabilities()
+                .contains(AccessorCapability::ReadWithIfMatch)
+            {
+                op = op.with_if_match(etag);
+            }
+        }
+
+        let chunk = args.chunk().max(1);
+        let ranges = if length == 0 {
+            vec![]
+        } else if args.concurrent() <= 1
+            || length <= chunk
+            || info.hints().contains(AccessorHint::ReadWholeContent)
+        {
+            vec![(0, length)]
+        } else {
+            (0..length)
+                .step_by(chunk as usize)
+                .map(|offset| (offset, chunk.min(length - offset)))
+                .collect()
+        };
+
+        let mut chunks = stream::iter(ranges)
+            .map(|(offset, size)| {
+                let (path, op) = (&path, op.clone());
+                async move {
+                    let bs = self
+                        .range_read_with(path, offset..offset + size, op)
+                        .await?;
+                    Ok::<_, Error>((offset, size, bs))
+                }
+            })
+            .buffered(args.concurrent().max(1));
+
+        let mut written = 0;
+        while let Some((offset, size, bs)) = chunks.try_next().await? {
+            if bs.len() as u64 != size {
+                return Err(Error::new(
+                    ErrorKind::Unexpected,
+                    "downloaded chunk size is not expected",
+                )
+                .with_operation("download")
+                .with_context("service", info.scheme())
+                .with_context("path", &path)
+                .with_context("offset", offset.to_string())
+                .with_context("expect", size.to_string())
+                .with_context("actual", bs.len().to_string()));
+            }
+
+            w.write_all(&bs).await.map_err(|err| {
+                Error::new(ErrorKind::Unexpected, "write into download destination")
+                    .with_operation("download")
+                    .with_context("service", info.scheme())
+                    .with_context("path", &path)
+                    .set_source(err)
+            })?;
+            written += size;
+        }
+
+        if written != length {
+            return Err(Error::new(
+                ErrorKind::Unexpected,
+                "downloaded size is not the same as content length",
+            )
+            .with_operation("download")
+            .with_context("service", info.scheme())
+            .with_context("path", &path)
+            .with_context("expect", length.to_string())
+            .with_context("actual", written.to_string()));
+        }
+
+        w.flush().await.map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "flush download destination")
+                .with_operation("download")
+                .with_context("service", info.scheme())
                 .with_context("path", &path)
-                .with_context("range", br.to_string())
                 .set_source(err)
         })?;
 
-        // Safety: read_exact makes sure this buffer has been filled.
-        unsafe { buffer.set_len(length) }
+        Ok(written)
+    }
 
-        Ok(buffer)
+    /// Get checksum of given path with specified algorithm.
+    ///
+    /// Checksum returned by service in metadata will be used if possible,
+    /// otherwise the whole content will be read and computed locally.
+    /// Use [`Checksum::strategy`] to check which one is used.
+    ///
+    /// # Notes
+    ///
+    /// - Etag will be treated as md5 only for services that use md5 as etag
+    ///   of simple uploads like s3, etags of multipart uploads, encrypted
+    ///   objects (SSE-KMS or SSE-C) and appendable objects will never be
+    ///   used.
+    /// - If the service supports `if_match`, the computing read will carry
+    ///   the etag returned by stat, so it will fail with
+    ///   [`ErrorKind::PreconditionFailed`] if the path has been changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// use opendal::ChecksumAlgorithm;
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let checksum = op
+    ///     .checksum("path/to/file", ChecksumAlgorithm::Md5)
+    ///     .await?;
+    /// println!("{} via {:?}", checksum.to_hex(), checksum.strategy());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn checksum(&self, path: &str, algorithm: ChecksumAlgorithm) -> Result<Checksum> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::FILE) {
+            return Err(
+                Error::new(ErrorKind::IsADirectory, "checksum path is a directory")
+                    .with_operation("checksum")
+                    .with_context("service", self.inner().info().scheme())
+                    .with_context("path", &path),
+            );
+        }
+
+        let info = self.inner().info();
+        let meta = self.stat(&path).await?;
+        if let Some(checksum) = Checksum::from_metadata(info.scheme(), &meta, algorithm) {
+            return Ok(checksum);
+        }
+
+        let mut op = OpRead::new();
+        if let Some(etag) = meta.etag() {
+            if info
+                .capabilities()
+                .contains(AccessorCapability::ReadWithIfMatch)
+            {
+                op = op.with_if_match(etag);
+            }
+        }
+
+        let mut r = self.reader_with(&path, op).await?;
+        let mut hasher = ChecksumHasher::new(algorithm);
+        while let Some(bs) = oio::ReadExt::next(&mut r).await {
+            hasher.update(&bs?);
+        }
+
+        Ok(hasher.finish())
     }
 
     /// Create a new reader which can read the whole path.
@@ -854,8 +1244,22 @@ impl Operator {
             );
         }
 
+        let bs = bs.into();
+        let args = match args.content_length() {
+            Some(_) => args,
+            None => args.with_content_length(bs.len() as u64),
+        };
+
+        let mut progress = args
+            .progress()
+            .map(|f| Progress::new(f, args.content_length()));
+        let size = bs.len();
+
         let (_, mut w) = self.inner().write(&path, args).await?;
-        w.write(bs.into()).await?;
+        w.write(bs).await?;
+        if let Some(p) = &mut progress {
+            p.advance(size);
+        }
         w.close().await?;
 
         Ok(())
@@ -880,9 +1284,34 @@ impl Operator {
     /// # }
     /// ```
     pub async fn delete(&self, path: &str) -> Result<()> {
+        self.delete_with(path, OpDelete::new()).await
+    }
+
+    /// Delete the given path with extra options.
+    ///
+    /// # Notes
+    ///
+    /// - Delete not existing error won't return errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use futures::io;
+    /// # use opendal::Operator;
+    /// use opendal::ops::OpDelete;
+    ///
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.delete_with("test", OpDelete::new().with_version("version_id"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_with(&self, path: &str, args: OpDelete) -> Result<()> {
         let path = normalize_path(path);
 
-        let _ = self.inner().delete(&path, OpDelete::new()).await?;
+        let _ = self.inner().delete(&path, args).await?;
 
         Ok(())
     }
@@ -911,7 +1340,28 @@ impl Operator {
         self.remove_via(stream::iter(paths)).await
     }
 
-    /// remove will given paths.
+    /// Remove given paths with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use opendal::ops::OpRemove;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.remove_with(
+    ///     vec!["abc".to_string(), "def".to_string()],
+    ///     OpRemove::new().with_concurrent(16),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_with(&self, paths: Vec<String>, args: OpRemove) -> Result<()> {
+        self.remove_via_with(stream::iter(paths), args).await
+    }
 
     /// remove_via will remove files via given stream.
     ///
@@ -938,34 +1388,38 @@ impl Operator {
     /// # }
     /// ```
     pub async fn remove_via(&self, input: impl Stream<Item = String> + Unpin) -> Result<()> {
-        if self.info().can_batch() {
-            let mut input = input
-                .map(|v| (v, OpDelete::default().into()))
-                .chunks(self.limit());
-
-            while let Some(batches) = input.next().await {
-                let results = self
-                    .inner()
-                    .batch(OpBatch::new(batches))
-                    .await?
-                    .into_results();
-
-                // TODO: return error here directly seems not a good idea?
-                for (_, result) in results {
-                    let _ = result?;
-                }
-            }
-        } else {
-            input
-                .map(Ok)
-                .try_for_each_concurrent(self.limit, |path| async move {
-                    let _ = self.inner().delete(&path, OpDelete::default()).await?;
-                    Ok::<(), Error>(())
-                })
-                .await?;
-        }
+        self.remove_via_with(input, OpRemove::new()).await
+    }
 
-        Ok(())
+    /// Remove files via given stream with extra options.
+    ///
+    /// # Notes
+    ///
+    /// Read [`Operator::remove_via`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use futures::stream;
+    /// use opendal::ops::OpRemove;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let stream = stream::iter(vec!["abc".to_string(), "def".to_string()]);
+    /// op.remove_via_with(stream, OpRemove::new().with_concurrent(16))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_via_with(
+        &self,
+        input: impl Stream<Item = String> + Unpin,
+        args: OpRemove,
+    ) -> Result<()> {
+        let removed = AtomicU64::new(0);
+        self.remove_paths(input.map(Ok), &args, &removed).await
     }
 
     /// Remove the path and all nested dirs and files recursively.
@@ -975,6 +1429,14 @@ impl Operator {
     /// If underlying services support delete in batch, we will use batch
     /// delete instead.
     ///
+    /// Files will be removed concurrently first, then dirs will be removed
+    /// level by level from the deepest one, so that a dir will never be
+    /// removed before its children.
+    ///
+    /// Path without trailing slash will be removed as a dir if there is no
+    /// file at the path, so `remove_all("dir")` acts the same as
+    /// `remove_all("dir/")`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -989,9 +1451,50 @@ impl Operator {
     /// # }
     /// ```
     pub async fn remove_all(&self, path: &str) -> Result<()> {
-        let meta = match self.stat(path).await {
+        self.remove_all_with(path, OpRemove::new()).await
+    }
+
+    /// Remove the path and all nested dirs and files recursively with
+    /// extra options.
+    ///
+    /// # Notes
+    ///
+    /// Read [`Operator::remove_all`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// use opendal::ops::OpRemove;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// op.remove_all_with(
+    ///     "path/to/dir",
+    ///     OpRemove::new()
+    ///         .with_concurrent(16)
+    ///         .with_progress(|removed| println!("removed {removed} entries")),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remove_all_with(&self, path: &str, args: OpRemove) -> Result<()> {
+        let (path, meta) = match self.stat(path).await {
             // If object exists.
-            Ok(metadata) => metadata,
+            Ok(metadata) => (path.to_string(), metadata),
+
+            // Dirs can only be found with a trailing slash, try it as a dir
+            // so that the dir won't be kept silently.
+            Err(e) if e.kind() == ErrorKind::NotFound && !path.ends_with('/') => {
+                let dir = format!("{path}/");
+                match self.stat(&dir).await {
+                    Ok(metadata) => (dir, metadata),
+                    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+                    Err(e) => return Err(e),
+                }
+            }
 
             // If object not found, return success.
             Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
@@ -999,43 +1502,93 @@ impl Operator {
             // Pass on any other error.
             Err(e) => return Err(e),
         };
+        let path = path.as_str();
+
+        let removed = AtomicU64::new(0);
+        if meta.mode() == EntryMode::DIR {
+            // Remove files first and collect dirs for later.
+            let mut dirs = Vec::new();
+            let obs = self.scan(path).await?;
+            let files = obs.map_ok(|v| v.path().to_string()).try_filter_map(|p| {
+                if p.ends_with('/') {
+                    dirs.push(p);
+                    future::ready(Ok(None))
+                } else {
+                    future::ready(Ok(Some(p)))
+                }
+            });
+            self.remove_paths(files, &args, &removed).await?;
+
+            // Remove dirs from the deepest level, dirs in the same level
+            // could be removed concurrently.
+            let depth = |p: &String| p.matches('/').count();
+            dirs.sort_by_key(depth);
+            while let Some(deepest) = dirs.last().map(depth) {
+                let idx = dirs.partition_point(|p| depth(p) < deepest);
+                let level = dirs.split_off(idx);
+                self.remove_paths(stream::iter(level).map(Ok), &args, &removed)
+                    .await?;
+            }
+        }
 
-        if meta.mode() != EntryMode::DIR {
-            return self.delete(path).await;
+        // Remove the path itself.
+        {
+            let _permit = args.acquire().await?;
+            self.delete(path).await?;
         }
+        args.report(removed.fetch_add(1, Ordering::Relaxed) + 1);
+
+        Ok(())
+    }
 
-        let obs = self.scan(path).await?;
+    /// Remove paths from input concurrently, every in-flight request holds
+    /// a permit of `args`.
+    async fn remove_paths(
+        &self,
+        input: impl Stream<Item = Result<String>>,
+        args: &OpRemove,
+        removed: &AtomicU64,
+    ) -> Result<()> {
+        let report = |n: u64| args.report(removed.fetch_add(n, Ordering::Relaxed) + n);
 
         if self.info().can_batch() {
-            let mut obs = obs.try_chunks(self.limit());
-
-            while let Some(batches) = obs.next().await {
-                let batches = batches
-                    .map_err(|err| err.1)?
-                    .into_iter()
-                    .map(|v| (v.path().to_string(), OpDelete::default().into()))
-                    .collect();
-
-                let results = self
-                    .inner()
-                    .batch(OpBatch::new(batches))
-                    .await?
-                    .into_results();
-
-                // TODO: return error here directly seems not a good idea?
-                for (_, result) in results {
-                    let _ = result?;
-                }
-            }
+            input
+                .try_chunks(self.limit())
+                .map_err(|err| err.1)
+                .try_for_each_concurrent(args.concurrent(), |batches| async move {
+                    let n = batches.len() as u64;
+                    let batches = batches
+                        .into_iter()
+                        .map(|v| (v, OpDelete::default().into()))
+                        .collect();
+
+                    let _permit = args.acquire().await?;
+                    let results = self
+                        .inner()
+                        .batch(OpBatch::new(batches))
+                        .await?
+                        .into_results();
+
+                    // TODO: return error here directly seems not a good idea?
+                    for (_, result) in results {
+                        let _ = result?;
+                    }
+
+                    report(n);
+                    Ok(())
+                })
+                .await
         } else {
-            obs.try_for_each(|v| async move { self.delete(v.path()).await })
-                .await?;
-        }
-
-        // Remove the directory itself.
-        self.delete(path).await?;
+            input
+                .try_for_each_concurrent(args.concurrent(), |path| async move {
+                    let _permit = args.acquire().await?;
+                    let _ = self.inner().delete(&path, OpDelete::default()).await?;
 
-        Ok(())
+                    report(1);
+                    Ok(())
+                })
+                .await
+        }
     }
 
     /// List given path.
@@ -1089,6 +1642,58 @@ impl Operator {
         Ok(Lister::new(pager))
     }
 
+    /// List given path with extra options.
+    ///
+    /// An error will be returned if given path doesn't end with `/`.
+    ///
+    /// # Examples
+    ///
+    /// List all versions of objects under given path:
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use futures::TryStreamExt;
+    /// use opendal::ops::OpList;
+    /// use opendal::Metakey;
+    /// use opendal::Operator;
+    /// # #[tokio::main]
+    /// # async fn test(op: Operator) -> Result<()> {
+    /// let mut ds = op
+    ///     .list_with("path/to/dir/", OpList::new().with_versions())
+    ///     .await?;
+    /// while let Some(de) = ds.try_next().await? {
+    ///     let meta = op
+    ///         .metadata(&de, Metakey::Version | Metakey::IsCurrent | Metakey::IsDeleted)
+    ///         .await?;
+    ///     println!(
+    ///         "{} {:?} {:?} {}",
+    ///         de.path(),
+    ///         meta.version(),
+    ///         meta.is_current(),
+    ///         meta.is_deleted()
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_with(&self, path: &str, args: OpList) -> Result<Lister> {
+        let path = normalize_path(path);
+
+        if !validate_path(&path, EntryMode::DIR) {
+            return Err(Error::new(
+                ErrorKind::NotADirectory,
+                "the path trying to list should end with `/`",
+            )
+            .with_operation("Operator::list_with")
+            .with_context("service", self.info().scheme().into_static())
+            .with_context("path", &path));
+        }
+
+        let (_, pager) = self.inner().list(&path, args).await?;
+
+        Ok(Lister::new(pager))
+    }
+
     /// List dir in flat way.
     ///
     /// Also, this function can be used to list a prefix.
@@ -1174,8 +1779,7 @@ impl Operator {
 
         let op = OpPresign::new(OpStat::new(), expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_with_op(&path, op).await
     }
 
     /// Presign an operation for read.
@@ -1209,8 +1813,7 @@ impl Operator {
 
         let op = OpPresign::new(OpRead::new(), expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_with_op(&path, op).await
     }
 
     /// Presign an operation for read option described in OpenDAL [rfc-1735](../../docs/rfcs/1735_operation_extension.md).
@@ -1244,8 +1847,7 @@ impl Operator {
 
         let op = OpPresign::new(op, expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_with_op(&path, op).await
     }
 
     /// Presign an operation for write.
@@ -1313,7 +1915,25 @@ impl Operator {
 
         let op = OpPresign::new(op, expire);
 
-        let rp = self.inner().presign(&path, op).await?;
-        Ok(rp.into_presigned_request())
+        self.presign_with_op(&path, op).await
+    }
+
+    /// Presign the op and make sure the returned request is valid.
+    async fn presign_with_op(&self, path: &str, op: OpPresign) -> Result<PresignedRequest> {
+        let req = self
+            .inner()
+            .presign(path, op)
+            .await?
+            .into_presigned_request();
+
+        // Reject invalid headers here, so that users will know which service
+        // returns them instead of failing while sending the request.
+        req.check_headers().map_err(|err| {
+            err.with_operation(Operation::Presign)
+                .with_context("service", self.info().scheme())
+                .with_context("path", path)
+        })?;
+
+        Ok(req)
     }
 }
@@ -19,9 +19,18 @@
 //!
 //! By using ops, users can add more context for operation.
 
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::DateTime;
+use chrono::Utc;
+use tokio::sync::Semaphore;
+use tokio::sync::SemaphorePermit;
+
 use crate::raw::*;
+use crate::*;
 
 /// Args for `create` operation.
 ///
@@ -40,12 +49,111 @@ impl OpCreate {
 ///
 /// The path must be normalized.
 #[derive(Debug, Clone, Default)]
-pub struct OpDelete {}
+pub struct OpDelete {
+    version: Option<String>,
+}
 
 impl OpDelete {
     /// Create a new `OpDelete`.
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Set the version of the object to delete.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Get the version of the object to delete.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+/// Args for `remove` operations like `remove_all`.
+///
+/// Every in-flight delete or batch delete request holds a permit of the
+/// semaphore in this args, clones of this args share the same semaphore.
+/// So we can limit the total concurrency of multiple remove operations by
+/// passing the same args to them.
+///
+/// # Notes
+///
+/// Limits of [`ConcurrentLimitLayer`][crate::layers::ConcurrentLimitLayer]
+/// will still apply to every request.
+#[derive(Clone)]
+pub struct OpRemove {
+    concurrent: usize,
+    semaphore: Arc<Semaphore>,
+    progress: Option<Arc<dyn Fn(u64) + Send + Sync>>,
+}
+
+impl Debug for OpRemove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpRemove")
+            .field("concurrent", &self.concurrent)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for OpRemove {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpRemove {
+    /// Create a default `OpRemove` which will send at most 8 requests
+    /// concurrently.
+    pub fn new() -> Self {
+        Self {
+            concurrent: 8,
+            semaphore: Arc::new(Semaphore::new(8)),
+            progress: None,
+        }
+    }
+
+    /// Set the max count of concurrent delete requests.
+    ///
+    /// `0` will be treated as `1`. A new semaphore will be created, so this
+    /// args will not share the limit with its previous clones.
+    pub fn with_concurrent(mut self, concurrent: usize) -> Self {
+        self.concurrent = concurrent.max(1);
+        self.semaphore = Arc::new(Semaphore::new(self.concurrent));
+        self
+    }
+
+    /// Get the max count of concurrent delete requests.
+    pub fn concurrent(&self) -> usize {
+        self.concurrent
+    }
+
+    /// Set the callback of progress, which will be called with the count
+    /// of removed entries so far after every request succeeded.
+    ///
+    /// The callback will be called inside the remove operation, please
+    /// keep it cheap.
+    pub fn with_progress(mut self, f: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(f));
+        self
+    }
+
+    /// Report the count of removed entries.
+    pub(crate) fn report(&self, removed: u64) {
+        if let Some(f) = &self.progress {
+            f(removed)
+        }
+    }
+
+    /// Acquire a permit before sending a request.
+    pub(crate) async fn acquire(&self) -> Result<SemaphorePermit<'_>> {
+        self.semaphore.acquire().await.map_err(|err| {
+            Error::new(ErrorKind::Unexpected, "remove semaphore has been closed")
+                .with_operation("remove")
+                .set_source(err)
+        })
     }
 }
 
@@ -55,6 +163,8 @@ pub struct OpList {
     /// The limit passed to underlying service to specify the max results
     /// that could return.
     limit: Option<usize>,
+    /// Whether to list all versions of objects instead of the latest ones.
+    versions: bool,
 }
 
 impl OpList {
@@ -73,6 +183,18 @@ impl OpList {
     pub fn limit(&self) -> Option<usize> {
         self.limit
     }
+
+    /// List all versions of objects (including delete markers) instead
+    /// of the latest ones.
+    pub fn with_versions(mut self) -> Self {
+        self.versions = true;
+        self
+    }
+
+    /// Check whether this list operation should list all versions.
+    pub fn versions(&self) -> bool {
+        self.versions
+    }
 }
 
 /// Args for `scan` operation.
@@ -216,6 +338,8 @@ pub struct OpRead {
     override_cache_control: Option<String>,
     if_match: Option<String>,
     if_none_match: Option<String>,
+    version: Option<String>,
+    progress: Option<ProgressFn>,
 }
 
 impl OpRead {
@@ -279,6 +403,81 @@ impl OpRead {
     pub fn if_none_match(&self) -> Option<&str> {
         self.if_none_match.as_deref()
     }
+
+    /// Set the version of the option
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Get version from option
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Set the callback of progress, which will be called with the bytes
+    /// read so far and the total bytes to read if known.
+    ///
+    /// The callback will be called at most once per chunk, please keep it
+    /// cheap. Panics in callback will be caught and the callback will not
+    /// be called anymore.
+    pub fn with_progress(mut self, f: impl Fn(u64, Option<u64>) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressFn::new(f));
+        self
+    }
+
+    /// Get the callback of progress.
+    pub(crate) fn progress(&self) -> Option<ProgressFn> {
+        self.progress.clone()
+    }
+}
+
+/// Args for `download` operation.
+#[derive(Debug, Clone)]
+pub struct OpDownload {
+    concurrent: usize,
+    chunk: u64,
+}
+
+impl Default for OpDownload {
+    fn default() -> Self {
+        Self {
+            concurrent: 4,
+            chunk: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl OpDownload {
+    /// Create a default `OpDownload` which will download with 4 concurrent
+    /// requests in 8 MiB chunks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the max count of concurrent range requests.
+    ///
+    /// `0` and `1` means download in a single request.
+    pub fn with_concurrent(mut self, concurrent: usize) -> Self {
+        self.concurrent = concurrent;
+        self
+    }
+
+    /// Get the max count of concurrent range requests.
+    pub fn concurrent(&self) -> usize {
+        self.concurrent
+    }
+
+    /// Set the size of each range request.
+    pub fn with_chunk(mut self, chunk: u64) -> Self {
+        self.chunk = chunk;
+        self
+    }
+
+    /// Get the size of each range request.
+    pub fn chunk(&self) -> u64 {
+        self.chunk
+    }
 }
 
 /// Args for `stat` operation.
@@ -286,6 +485,7 @@ impl OpRead {
 pub struct OpStat {
     if_match: Option<String>,
     if_none_match: Option<String>,
+    version: Option<String>,
 }
 
 impl OpStat {
@@ -315,6 +515,17 @@ impl OpStat {
     pub fn if_none_match(&self) -> Option<&str> {
         self.if_none_match.as_deref()
     }
+
+    /// Set the version of the option
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Get version from option
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
 }
 
 /// Args for `write` operation.
@@ -326,6 +537,11 @@ pub struct OpWrite {
     content_disposition: Option<String>,
     cache_control: Option<String>,
     if_match: Option<String>,
+    storage_class: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+    content_length: Option<u64>,
+    disable_abort_on_drop: bool,
+    progress: Option<ProgressFn>,
 }
 
 impl OpWrite {
@@ -388,6 +604,73 @@ impl OpWrite {
     pub fn if_match(&self) -> Option<&str> {
         self.if_match.as_deref()
     }
+
+    /// Get the storage class from option
+    pub fn storage_class(&self) -> Option<&str> {
+        self.storage_class.as_deref()
+    }
+
+    /// Set the storage class of option
+    pub fn with_storage_class(mut self, storage_class: &str) -> Self {
+        self.storage_class = Some(storage_class.to_string());
+        self
+    }
+
+    /// Get the last modified time from option
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.last_modified
+    }
+
+    /// Set the last modified time of option
+    pub fn with_last_modified(mut self, last_modified: DateTime<Utc>) -> Self {
+        self.last_modified = Some(last_modified);
+        self
+    }
+
+    /// Get the content length from option
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Set the content length of option
+    ///
+    /// Services could use it as a hint of the total size that will be written.
+    pub fn with_content_length(mut self, content_length: u64) -> Self {
+        self.content_length = Some(content_length);
+        self
+    }
+
+    /// Get whether the writer should be aborted while dropped without
+    /// close, default to `true`.
+    pub fn abort_on_drop(&self) -> bool {
+        !self.disable_abort_on_drop
+    }
+
+    /// Set whether the writer should be aborted while dropped without
+    /// close.
+    ///
+    /// Disable it if uploads are cleaned up outside, for example, by
+    /// bucket lifecycle rules.
+    pub fn with_abort_on_drop(mut self, abort_on_drop: bool) -> Self {
+        self.disable_abort_on_drop = !abort_on_drop;
+        self
+    }
+
+    /// Set the callback of progress, which will be called with the bytes
+    /// written so far and the content length if set.
+    ///
+    /// The callback will be called at most once per chunk, please keep it
+    /// cheap. Panics in callback will be caught and the callback will not
+    /// be called anymore.
+    pub fn with_progress(mut self, f: impl Fn(u64, Option<u64>) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressFn::new(f));
+        self
+    }
+
+    /// Get the callback of progress.
+    pub(crate) fn progress(&self) -> Option<ProgressFn> {
+        self.progress.clone()
+    }
 }
 
 /// Args for `copy` operation.
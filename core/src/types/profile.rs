@@ -0,0 +1,295 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+
+use log::debug;
+
+use crate::services::ServiceConfig;
+use crate::*;
+
+/// The env that selects the profile to use.
+const PROFILE_ENV: &str = "OPENDAL_PROFILE";
+
+/// Profile is a named set of service config, which is resolved from
+/// envs like `OPENDAL_{NAME}_{KEY}` or from a section in config file.
+///
+/// Every profile must carry a `type` to decide its service, and all other
+/// keys will be passed to `Builder::from_map`.
+///
+/// Take profile `prod-archive` for example:
+///
+/// ```shell
+/// OPENDAL_PROFILE=prod-archive
+/// OPENDAL_PROD_ARCHIVE_TYPE=obs
+/// OPENDAL_PROD_ARCHIVE_BUCKET=archive
+/// OPENDAL_PROD_ARCHIVE_ENDPOINT=https://obs.cn-north-4.myhuaweicloud.com
+/// ```
+///
+/// # Precedence
+///
+/// Values are resolved in the following order, the former overwrites the
+/// latter:
+///
+/// - Values set via [`Profile::with`].
+/// - Values loaded from profile envs or config file.
+/// - Service specific envs like `AWS_ACCESS_KEY_ID`, which are handled by
+///   credential loaders of services.
+///
+/// # Notes
+///
+/// Profile names are case-insensitive, and `-` or `.` in names will be
+/// treated as `_`. Profiles whose names are prefix of others like `prod`
+/// and `prod_archive` will see each other's envs, unknown keys will be
+/// ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use anyhow::Result;
+/// use opendal::Operator;
+/// use opendal::Profile;
+///
+/// fn main() -> Result<()> {
+///     // Load the profile selected by `OPENDAL_PROFILE`.
+///     let profile = Profile::from_env_default()?.with("root", "/data");
+///
+///     let op: Operator = Operator::via_profile(profile)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Profile {
+    name: String,
+    scheme: Scheme,
+    map: HashMap<String, String>,
+}
+
+/// Profile may carry secrets, so we only output its keys.
+impl std::fmt::Debug for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut keys: Vec<_> = self.map.keys().collect();
+        keys.sort();
+
+        f.debug_struct("Profile")
+            .field("name", &self.name)
+            .field("scheme", &self.scheme)
+            .field("keys", &keys)
+            .finish()
+    }
+}
+
+impl Profile {
+    /// Load the profile selected by env `OPENDAL_PROFILE`.
+    pub fn from_env_default() -> Result<Self> {
+        let name = env::var(PROFILE_ENV).map_err(|err| {
+            Error::new(
+                ErrorKind::ConfigInvalid,
+                "profile env is not set or invalid",
+            )
+            .with_operation("Profile::from_env_default")
+            .with_context("env", PROFILE_ENV)
+            .set_source(err)
+        })?;
+
+        Self::from_env(&name)
+    }
+
+    /// Load the profile with given name from envs `OPENDAL_{NAME}_{KEY}`.
+    pub fn from_env(name: &str) -> Result<Self> {
+        let prefix = env_prefix(name)?;
+        let map = env::vars()
+            .filter_map(|(k, v)| k.strip_prefix(&prefix).map(|k| (k.to_lowercase(), v)))
+            .collect();
+
+        Self::build(name, map, |key| format!("{prefix}{}", key.to_uppercase()))
+    }
+
+    /// Load the profile with given name from map, which is usually a section
+    /// in config file.
+    ///
+    /// The map must contain a `type` key to decide the service.
+    pub fn from_map(name: &str, map: HashMap<String, String>) -> Result<Self> {
+        // Validate the name so that profiles behave the same from everywhere.
+        env_prefix(name)?;
+
+        Self::build(name, map, |key| key.to_string())
+    }
+
+    /// Build the profile, `source` is used to tell users where the key
+    /// comes from in errors.
+    fn build(
+        name: &str,
+        mut map: HashMap<String, String>,
+        source: impl Fn(&str) -> String,
+    ) -> Result<Self> {
+        let ty = map.remove("type").ok_or_else(|| {
+            Error::new(ErrorKind::ConfigInvalid, "profile type is missing")
+                .with_operation("Profile::build")
+                .with_context("profile", name)
+                .with_context("source", source("type"))
+        })?;
+
+        let scheme = Scheme::from_str(&ty)?;
+        if !scheme.is_enabled() {
+            let enabled = Scheme::enabled()
+                .into_iter()
+                .map(|v| v.into_static())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            return Err(Error::new(
+                ErrorKind::ConfigInvalid,
+                "profile type is not supported or not enabled by cargo features",
+            )
+            .with_operation("Profile::build")
+            .with_context("profile", name)
+            .with_context("source", source("type"))
+            .with_context("type", ty)
+            .with_context("enabled", enabled));
+        }
+
+        for (k, v) in map.iter() {
+            match ServiceConfig::is_valid_entry(scheme, k, v) {
+                Some(true) => {}
+                // Don't carry the value in error, it may be a secret.
+                Some(false) => {
+                    return Err(
+                        Error::new(ErrorKind::ConfigInvalid, "profile value is malformed")
+                            .with_operation("Profile::build")
+                            .with_context("profile", name)
+                            .with_context("source", source(k)),
+                    )
+                }
+                None => debug!("profile {name} has unknown key {k}, ignored"),
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            scheme,
+            map,
+        })
+    }
+
+    /// Set a value for this profile, which overwrites the loaded one.
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.map.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Get the name of this profile.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the scheme of this profile.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Consume the profile into the map accepted by `Builder::from_map`.
+    pub fn into_map(self) -> HashMap<String, String> {
+        self.map
+    }
+}
+
+/// Build the env prefix of profile like `OPENDAL_PROD_ARCHIVE_`.
+fn env_prefix(name: &str) -> Result<String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(Error::new(
+            ErrorKind::ConfigInvalid,
+            "profile name must be non-empty ascii alphanumeric, `-`, `_` or `.`",
+        )
+        .with_context("profile", name));
+    }
+
+    Ok(format!(
+        "OPENDAL_{}_",
+        name.to_uppercase().replace(['-', '.'], "_")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_prefix() {
+        assert_eq!(env_prefix("prod-archive").unwrap(), "OPENDAL_PROD_ARCHIVE_");
+        assert_eq!(env_prefix("a.b_c").unwrap(), "OPENDAL_A_B_C_");
+        assert!(env_prefix("").is_err());
+        assert!(env_prefix("prod archive").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_from_env() {
+        env::set_var("OPENDAL_TEST_PROFILE_ENV_TYPE", "s3");
+        env::set_var("OPENDAL_TEST_PROFILE_ENV_BUCKET", "test");
+
+        let profile = Profile::from_env("test-profile-env")
+            .expect("profile must be loaded")
+            .with("root", "/data");
+        assert_eq!(profile.scheme(), Scheme::S3);
+
+        let map = profile.into_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["bucket"], "test");
+        assert_eq!(map["root"], "/data");
+    }
+
+    #[test]
+    #[cfg(feature = "services-s3")]
+    fn test_from_env_malformed() {
+        env::set_var("OPENDAL_TEST_PROFILE_MALFORMED_TYPE", "s3");
+        env::set_var(
+            "OPENDAL_TEST_PROFILE_MALFORMED_ENABLE_VIRTUAL_HOST_STYLE",
+            "yes",
+        );
+
+        let err = Profile::from_env("test-profile-malformed").expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err
+            .to_string()
+            .contains("OPENDAL_TEST_PROFILE_MALFORMED_ENABLE_VIRTUAL_HOST_STYLE"));
+    }
+
+    #[test]
+    fn test_from_map_missing_type() {
+        let map = HashMap::from([("bucket".to_string(), "test".to_string())]);
+
+        let err = Profile::from_map("prod", map).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+        assert!(err.to_string().contains("type"));
+    }
+
+    #[test]
+    fn test_from_map_unknown_type() {
+        let map = HashMap::from([("type".to_string(), "unknown".to_string())]);
+
+        let err = Profile::from_map("prod", map).expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::ConfigInvalid);
+    }
+}
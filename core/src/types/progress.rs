@@ -0,0 +1,161 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+use log::warn;
+
+use crate::raw::*;
+
+/// ProgressFn is the callback set by `with_progress` of [`OpRead`] and
+/// [`OpWrite`].
+///
+/// [`OpRead`]: crate::ops::OpRead
+/// [`OpWrite`]: crate::ops::OpWrite
+#[derive(Clone)]
+pub(crate) struct ProgressFn(Arc<dyn Fn(u64, Option<u64>) + Send + Sync>);
+
+impl ProgressFn {
+    pub fn new(f: impl Fn(u64, Option<u64>) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Debug for ProgressFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressFn")
+    }
+}
+
+/// Progress tracks the bytes transferred by operator level readers and
+/// writers.
+///
+/// Progress is tracked above all layers, so bytes read again by
+/// [`RetryLayer`][crate::layers::RetryLayer] will not be counted twice.
+pub(crate) struct Progress {
+    f: Option<ProgressFn>,
+    transferred: u64,
+    total: Option<u64>,
+}
+
+impl Progress {
+    pub fn new(f: ProgressFn, total: Option<u64>) -> Self {
+        Self {
+            f: Some(f),
+            transferred: 0,
+            total,
+        }
+    }
+
+    /// Report that `n` bytes have been transferred.
+    ///
+    /// Panics in callback will be caught and the callback will be disabled
+    /// for the rest of the transfer, so that they can't abort the transfer.
+    pub fn advance(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let f = match &self.f {
+            Some(f) => f,
+            None => return,
+        };
+
+        self.transferred += n as u64;
+        let (transferred, total) = (self.transferred, self.total);
+        if catch_unwind(AssertUnwindSafe(|| (f.0)(transferred, total))).is_err() {
+            warn!("progress: callback panicked at {transferred} bytes transferred, disabled");
+            self.f = None;
+        }
+    }
+}
+
+/// Calculate the size of given range in content of `total` bytes.
+pub(crate) fn range_size(br: BytesRange, total: u64) -> u64 {
+    match (br.offset(), br.size()) {
+        (Some(offset), Some(size)) => size.min(total.saturating_sub(offset)),
+        (Some(offset), None) => total.saturating_sub(offset),
+        (None, Some(size)) => size.min(total),
+        (None, None) => total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let reported = Arc::new(AtomicU64::new(0));
+        let calls = Arc::new(AtomicU64::new(0));
+        let f = {
+            let (reported, calls) = (reported.clone(), calls.clone());
+            ProgressFn::new(move |transferred, total| {
+                assert_eq!(total, Some(10));
+                reported.store(transferred, Ordering::Relaxed);
+                calls.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+
+        let mut p = Progress::new(f, Some(10));
+        p.advance(4);
+        p.advance(0);
+        p.advance(6);
+        assert_eq!(reported.load(Ordering::Relaxed), 10);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_progress_panic() {
+        let calls = Arc::new(AtomicU64::new(0));
+        let f = {
+            let calls = calls.clone();
+            ProgressFn::new(move |_, _| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                panic!("progress panicked")
+            })
+        };
+
+        let mut p = Progress::new(f, None);
+        p.advance(1);
+        p.advance(1);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_range_size() {
+        let cases = vec![
+            ("full", BytesRange::new(None, None), 10),
+            ("offset", BytesRange::new(Some(4), None), 6),
+            ("offset out of range", BytesRange::new(Some(20), None), 0),
+            ("range", BytesRange::new(Some(4), Some(4)), 4),
+            ("range out of range", BytesRange::new(Some(8), Some(4)), 2),
+            ("suffix", BytesRange::new(None, Some(4)), 4),
+            ("suffix out of range", BytesRange::new(None, Some(20)), 10),
+        ];
+
+        for (name, br, expected) in cases {
+            assert_eq!(range_size(br, 10), expected, "{name}");
+        }
+    }
+}
@@ -27,6 +27,7 @@ use futures::AsyncSeek;
 use futures::Stream;
 
 use crate::ops::OpRead;
+use crate::ops::OpStat;
 use crate::raw::*;
 use crate::*;
 
@@ -57,6 +58,7 @@ use crate::*;
 pub struct Reader {
     inner: oio::Reader,
     seek_state: SeekState,
+    progress: Option<Progress>,
 }
 
 impl Reader {
@@ -68,18 +70,40 @@ impl Reader {
     /// We don't want to expose those details to users so keep this function
     /// in crate only.
     pub(crate) async fn create_dir(acc: FusedAccessor, path: &str, op: OpRead) -> Result<Self> {
-        let (_, r) = acc.read(path, op).await?;
+        let (br, f) = (op.range(), op.progress());
+        let (rp, r) = acc.read(path, op).await?;
+
+        let progress = match f {
+            Some(f) => {
+                // Fallback to stat if content length is not returned.
+                let total = match rp.metadata().content_length_raw() {
+                    Some(v) => Some(v),
+                    None => acc
+                        .stat(path, OpStat::new())
+                        .await
+                        .ok()
+                        .map(|rp| range_size(br, rp.into_metadata().content_length())),
+                };
+                Some(Progress::new(f, total))
+            }
+            None => None,
+        };
 
         Ok(Reader {
             inner: r,
             seek_state: SeekState::Init,
+            progress,
         })
     }
 }
 
 impl oio::Read for Reader {
     fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
-        self.inner.poll_read(cx, buf)
+        let n = ready!(self.inner.poll_read(cx, buf))?;
+        if let Some(p) = &mut self.progress {
+            p.advance(n);
+        }
+        Poll::Ready(Ok(n))
     }
 
     fn poll_seek(&mut self, cx: &mut Context<'_>, pos: io::SeekFrom) -> Poll<Result<u64>> {
@@ -87,7 +111,11 @@ impl oio::Read for Reader {
     }
 
     fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
-        self.inner.poll_next(cx)
+        let res = ready!(self.inner.poll_next(cx));
+        if let (Some(p), Some(Ok(bs))) = (&mut self.progress, &res) {
+            p.advance(bs.len());
+        }
+        Poll::Ready(res)
     }
 }
 
@@ -97,7 +125,8 @@ impl AsyncRead for Reader {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        Pin::new(&mut self.inner).poll_read(cx, buf)
+        oio::Read::poll_read(&mut *self, cx, buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::Interrupted, err))
     }
 }
 
@@ -118,7 +147,7 @@ impl tokio::io::AsyncRead for Reader {
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
         let b = buf.initialize_unfilled();
-        let n = ready!(self.inner.poll_read(cx, b))?;
+        let n = ready!(oio::Read::poll_read(&mut *self, cx, b))?;
         unsafe {
             buf.assume_init(n);
         }
@@ -172,8 +201,7 @@ impl Stream for Reader {
     type Item = io::Result<Bytes>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.inner)
-            .poll_next(cx)
+        oio::Read::poll_next(&mut *self, cx)
             .map_err(|err| io::Error::new(io::ErrorKind::Interrupted, err))
     }
 }
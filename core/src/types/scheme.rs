@@ -35,14 +35,24 @@ pub enum Scheme {
     Azblob,
     /// [azdfs][crate::services::Azdfs]: Azure Data Lake Storage Gen2.
     Azdfs,
+    /// [azfile][crate::services::Azfile]: Azure File Storage.
+    Azfile,
+    /// [b2][crate::services::B2]: Backblaze B2 services.
+    B2,
+    /// [cloudflare_kv][crate::services::CloudflareKv]: Cloudflare Workers KV services.
+    CloudflareKv,
     /// [dashmap][crate::services::Dashmap]: dashmap backend support.
     Dashmap,
+    /// [etcd][crate::services::Etcd]: Etcd services
+    Etcd,
     /// [fs][crate::services::Fs]: POSIX alike file system.
     Fs,
     /// [gcs][crate::services::Gcs]: Google Cloud Storage backend.
     Gcs,
     /// [ghac][crate::services::Ghac]: GitHub Action Cache services.
     Ghac,
+    /// [gridfs][crate::services::Gridfs]: MongoDB GridFS services
+    Gridfs,
     /// [hdfs][crate::services::Hdfs]: Hadoop Distributed File System.
     Hdfs,
     /// [http][crate::services::Http]: HTTP backend.
@@ -59,18 +69,30 @@ pub enum Scheme {
     Memory,
     /// [moka][crate::services::Moka]: moka backend support.
     Moka,
+    /// [mysql][crate::services::Mysql]: Mysql services
+    Mysql,
     /// [obs][crate::services::Obs]: Huawei Cloud OBS services.
     Obs,
     /// [oss][crate::services::Oss]: Aliyun Object Storage Services
     Oss,
+    /// [postgres][crate::services::Postgres]: Postgres services
+    Postgres,
     /// [redis][crate::services::Redis]: Redis services
     Redis,
     /// [rocksdb][crate::services::Rocksdb]: RocksDB services
     Rocksdb,
     /// [s3][crate::services::S3]: AWS S3 alike services.
     S3,
+    /// [sftp][crate::services::Sftp]: SFTP services
+    Sftp,
     /// [sled][crate::services::Sled]: Sled services
     Sled,
+    /// [sqlite][crate::services::Sqlite]: Sqlite services
+    Sqlite,
+    /// [swift][crate::services::Swift]: OpenStack Swift services.
+    Swift,
+    /// [tikv][crate::services::Tikv]: Tikv services
+    Tikv,
     /// [wasabi][crate::services::Wasabi]: Wasabi service
     Wasabi,
     /// [webdav][crate::services::Webdav]: WebDAV support.
@@ -91,6 +113,96 @@ impl Scheme {
     pub fn into_static(self) -> &'static str {
         self.into()
     }
+
+    /// Get all schemes that have been enabled by cargo features.
+    ///
+    /// `Custom` schemes are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use opendal::Scheme;
+    ///
+    /// let schemes = Scheme::enabled();
+    /// assert!(schemes.contains(&Scheme::Memory));
+    /// ```
+    pub fn enabled() -> Vec<Scheme> {
+        vec![
+            #[cfg(feature = "services-azblob")]
+            Scheme::Azblob,
+            #[cfg(feature = "services-azdfs")]
+            Scheme::Azdfs,
+            #[cfg(feature = "services-azfile")]
+            Scheme::Azfile,
+            #[cfg(feature = "services-b2")]
+            Scheme::B2,
+            #[cfg(feature = "services-cloudflare-kv")]
+            Scheme::CloudflareKv,
+            #[cfg(feature = "services-dashmap")]
+            Scheme::Dashmap,
+            #[cfg(feature = "services-etcd")]
+            Scheme::Etcd,
+            #[cfg(feature = "services-fs")]
+            Scheme::Fs,
+            #[cfg(feature = "services-gcs")]
+            Scheme::Gcs,
+            #[cfg(feature = "services-ghac")]
+            Scheme::Ghac,
+            #[cfg(feature = "services-gridfs")]
+            Scheme::Gridfs,
+            #[cfg(feature = "services-hdfs")]
+            Scheme::Hdfs,
+            #[cfg(feature = "services-http")]
+            Scheme::Http,
+            #[cfg(feature = "services-ftp")]
+            Scheme::Ftp,
+            #[cfg(feature = "services-ipfs")]
+            Scheme::Ipfs,
+            #[cfg(feature = "services-ipmfs")]
+            Scheme::Ipmfs,
+            #[cfg(feature = "services-memcached")]
+            Scheme::Memcached,
+            #[cfg(feature = "services-memory")]
+            Scheme::Memory,
+            #[cfg(feature = "services-moka")]
+            Scheme::Moka,
+            #[cfg(feature = "services-mysql")]
+            Scheme::Mysql,
+            #[cfg(feature = "services-obs")]
+            Scheme::Obs,
+            #[cfg(feature = "services-oss")]
+            Scheme::Oss,
+            #[cfg(feature = "services-postgres")]
+            Scheme::Postgres,
+            #[cfg(feature = "services-redis")]
+            Scheme::Redis,
+            #[cfg(feature = "services-rocksdb")]
+            Scheme::Rocksdb,
+            #[cfg(feature = "services-s3")]
+            Scheme::S3,
+            #[cfg(feature = "services-sftp")]
+            Scheme::Sftp,
+            #[cfg(feature = "services-sled")]
+            Scheme::Sled,
+            #[cfg(feature = "services-sqlite")]
+            Scheme::Sqlite,
+            #[cfg(feature = "services-swift")]
+            Scheme::Swift,
+            #[cfg(feature = "services-tikv")]
+            Scheme::Tikv,
+            #[cfg(feature = "services-wasabi")]
+            Scheme::Wasabi,
+            #[cfg(feature = "services-webdav")]
+            Scheme::Webdav,
+            #[cfg(feature = "services-webhdfs")]
+            Scheme::Webhdfs,
+        ]
+    }
+
+    /// Check if this scheme has been enabled by cargo features.
+    pub fn is_enabled(&self) -> bool {
+        Self::enabled().contains(self)
+    }
 }
 
 impl Default for Scheme {
@@ -108,15 +220,24 @@ impl Display for Scheme {
 impl FromStr for Scheme {
     type Err = Error;
 
+    /// Parse scheme from str, common aliases like `s3a`, `gs` and `abfs`
+    /// will be parsed into their services.
+    ///
+    /// Unknown schemes will be parsed into [`Scheme::Custom`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.to_lowercase();
         match s.as_str() {
-            "azblob" => Ok(Scheme::Azblob),
-            "azdfs" => Ok(Scheme::Azdfs),
+            "azblob" | "azb" | "wasb" | "wasbs" => Ok(Scheme::Azblob),
+            "azdfs" | "abfs" | "abfss" => Ok(Scheme::Azdfs),
+            "azfile" => Ok(Scheme::Azfile),
+            "b2" => Ok(Scheme::B2),
+            "cloudflare_kv" | "cloudflare-kv" => Ok(Scheme::CloudflareKv),
             "dashmap" => Ok(Scheme::Dashmap),
-            "fs" => Ok(Scheme::Fs),
-            "gcs" => Ok(Scheme::Gcs),
+            "etcd" => Ok(Scheme::Etcd),
+            "fs" | "file" => Ok(Scheme::Fs),
+            "gcs" | "gs" => Ok(Scheme::Gcs),
             "ghac" => Ok(Scheme::Ghac),
+            "gridfs" => Ok(Scheme::Gridfs),
             "hdfs" => Ok(Scheme::Hdfs),
             "http" | "https" => Ok(Scheme::Http),
             "ftp" | "ftps" => Ok(Scheme::Ftp),
@@ -125,12 +246,18 @@ impl FromStr for Scheme {
             "memcached" => Ok(Scheme::Memcached),
             "memory" => Ok(Scheme::Memory),
             "moka" => Ok(Scheme::Moka),
+            "mysql" => Ok(Scheme::Mysql),
             "obs" => Ok(Scheme::Obs),
+            "postgres" | "postgresql" => Ok(Scheme::Postgres),
             "redis" => Ok(Scheme::Redis),
             "rocksdb" => Ok(Scheme::Rocksdb),
-            "s3" => Ok(Scheme::S3),
+            "s3" | "s3a" | "s3n" | "minio" => Ok(Scheme::S3),
+            "sftp" => Ok(Scheme::Sftp),
             "sled" => Ok(Scheme::Sled),
+            "sqlite" => Ok(Scheme::Sqlite),
+            "swift" => Ok(Scheme::Swift),
             "oss" => Ok(Scheme::Oss),
+            "tikv" => Ok(Scheme::Tikv),
             "wasabi" => Ok(Scheme::Wasabi),
             "webdav" => Ok(Scheme::Webdav),
             "webhdfs" => Ok(Scheme::Webhdfs),
@@ -144,10 +271,15 @@ impl From<Scheme> for &'static str {
         match v {
             Scheme::Azblob => "azblob",
             Scheme::Azdfs => "azdfs",
+            Scheme::Azfile => "azfile",
+            Scheme::B2 => "b2",
+            Scheme::CloudflareKv => "cloudflare_kv",
             Scheme::Dashmap => "dashmap",
+            Scheme::Etcd => "etcd",
             Scheme::Fs => "fs",
             Scheme::Gcs => "gcs",
             Scheme::Ghac => "ghac",
+            Scheme::Gridfs => "gridfs",
             Scheme::Hdfs => "hdfs",
             Scheme::Http => "http",
             Scheme::Ftp => "ftp",
@@ -156,12 +288,18 @@ impl From<Scheme> for &'static str {
             Scheme::Memcached => "memcached",
             Scheme::Memory => "memory",
             Scheme::Moka => "moka",
+            Scheme::Mysql => "mysql",
             Scheme::Obs => "obs",
+            Scheme::Postgres => "postgres",
             Scheme::Redis => "redis",
             Scheme::Rocksdb => "rocksdb",
             Scheme::S3 => "s3",
+            Scheme::Sftp => "sftp",
             Scheme::Sled => "sled",
+            Scheme::Sqlite => "sqlite",
+            Scheme::Swift => "swift",
             Scheme::Oss => "oss",
+            Scheme::Tikv => "tikv",
             Scheme::Wasabi => "wasabi",
             Scheme::Webdav => "webdav",
             Scheme::Webhdfs => "webhdfs",
@@ -175,3 +313,37 @@ impl From<Scheme> for String {
         v.into_static().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alias() {
+        let cases = [
+            ("s3", Scheme::S3),
+            ("S3A", Scheme::S3),
+            ("minio", Scheme::S3),
+            ("gs", Scheme::Gcs),
+            ("azb", Scheme::Azblob),
+            ("abfss", Scheme::Azdfs),
+            ("file", Scheme::Fs),
+            ("cloudflare-kv", Scheme::CloudflareKv),
+            ("unknown", Scheme::Custom("unknown")),
+        ];
+
+        for (input, expected) in cases {
+            let actual = Scheme::from_str(input).expect("parse must succeed");
+            assert_eq!(actual, expected, "{input}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "services-memory")]
+    fn test_enabled() {
+        let schemes = Scheme::enabled();
+        assert!(schemes.contains(&Scheme::Memory));
+        assert!(Scheme::Memory.is_enabled());
+        assert!(!Scheme::Custom("unknown").is_enabled());
+    }
+}
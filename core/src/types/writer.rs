@@ -26,6 +26,8 @@ use futures::future::BoxFuture;
 use futures::ready;
 use futures::AsyncWrite;
 use futures::FutureExt;
+use log::debug;
+use log::warn;
 
 use crate::ops::OpWrite;
 use crate::raw::oio::Write;
@@ -40,8 +42,24 @@ use crate::*;
 /// Writer is designed for appending multiple blocks which could
 /// lead to much requests. If only want to send all data in single chunk,
 /// please use [`Operator::write`] instead.
+///
+/// # Drop
+///
+/// Writer that is dropped without `close` or `abort` will be aborted in
+/// background, so that initiated multipart uploads or temp files will not
+/// be left. Abort is best-effort and requires a tokio runtime, users who
+/// manage cleanup by themselves can disable it via
+/// [`OpWrite::with_abort_on_drop`].
+///
+/// Writer dropped while a write is in progress will not be aborted, since
+/// the pending write is cancelled along with the inner writer.
 pub struct Writer {
     state: State,
+
+    abort_on_drop: bool,
+    /// Set after `close` succeeded or `abort` called.
+    finished: bool,
+    progress: Option<Progress>,
 }
 
 impl Writer {
@@ -53,10 +71,15 @@ impl Writer {
     /// We don't want to expose those details to users so keep this function
     /// in crate only.
     pub(crate) async fn create_dir(acc: FusedAccessor, path: &str, op: OpWrite) -> Result<Self> {
+        let abort_on_drop = op.abort_on_drop();
+        let progress = op.progress().map(|f| Progress::new(f, op.content_length()));
         let (_, w) = acc.write(path, op).await?;
 
         Ok(Writer {
             state: State::Idle(Some(w)),
+            abort_on_drop,
+            finished: false,
+            progress,
         })
     }
 
@@ -67,7 +90,13 @@ impl Writer {
     /// and compatibility.
     pub async fn append(&mut self, bs: impl Into<Bytes>) -> Result<()> {
         if let State::Idle(Some(w)) = &mut self.state {
-            w.append(bs.into()).await
+            let bs = bs.into();
+            let size = bs.len();
+            w.append(bs).await?;
+            if let Some(p) = &mut self.progress {
+                p.advance(size);
+            }
+            Ok(())
         } else {
             unreachable!(
                 "writer state invalid while append, expect Idle, actual {}",
@@ -79,6 +108,7 @@ impl Writer {
     /// Abort inner writer.
     pub async fn abort(&mut self) -> Result<()> {
         if let State::Idle(Some(w)) = &mut self.state {
+            self.finished = true;
             w.abort().await
         } else {
             unreachable!(
@@ -91,7 +121,9 @@ impl Writer {
     /// Close the writer and make sure all data have been stored.
     pub async fn close(&mut self) -> Result<()> {
         if let State::Idle(Some(w)) = &mut self.state {
-            w.close().await
+            w.close().await?;
+            self.finished = true;
+            Ok(())
         } else {
             unreachable!(
                 "writer state invalid while close, expect Idle, actual {}",
@@ -140,6 +172,9 @@ impl AsyncWrite for Writer {
                 State::Write(fut) => match ready!(fut.poll_unpin(cx)) {
                     Ok((size, w)) => {
                         self.state = State::Idle(Some(w));
+                        if let Some(p) = &mut self.progress {
+                            p.advance(size);
+                        }
                         return Poll::Ready(Ok(size));
                     }
                     Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
@@ -175,6 +210,7 @@ impl AsyncWrite for Writer {
                 State::Close(fut) => match ready!(fut.poll_unpin(cx)) {
                     Ok(w) => {
                         self.state = State::Idle(Some(w));
+                        self.finished = true;
                         return Poll::Ready(Ok(()));
                     }
                     Err(err) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
@@ -184,10 +220,51 @@ impl AsyncWrite for Writer {
     }
 }
 
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if self.finished || !self.abort_on_drop {
+            return;
+        }
+
+        // Only idle writer can be aborted. Writer in `Write` or `Close`
+        // state is owned by the in-flight future, which will be dropped
+        // with the writer instead of being driven to completion.
+        let mut w = match std::mem::replace(&mut self.state, State::Idle(None)) {
+            State::Idle(Some(w)) => w,
+            _ => return,
+        };
+        let fut = async move { w.abort().await };
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = fut.await {
+                        debug!("writer: abort on drop failed: {err:?}");
+                    }
+                });
+            }
+            Err(_) => {
+                warn!("writer: dropped without close outside of tokio runtime, abort skipped")
+            }
+        }
+    }
+}
+
 /// BlockingWriter is designed to write data into given path in an blocking
 /// manner.
+///
+/// # Drop
+///
+/// BlockingWriter that is dropped without `close` or `abort` will be
+/// aborted before dropped. Users can disable it via
+/// [`OpWrite::with_abort_on_drop`].
 pub struct BlockingWriter {
     pub(crate) inner: oio::BlockingWriter,
+
+    abort_on_drop: bool,
+    /// Set after `close` succeeded or `abort` called.
+    finished: bool,
+    progress: Option<Progress>,
 }
 
 impl BlockingWriter {
@@ -199,9 +276,16 @@ impl BlockingWriter {
     /// We don't want to expose those details to users so keep this function
     /// in crate only.
     pub(crate) fn create_dir(acc: FusedAccessor, path: &str, op: OpWrite) -> Result<Self> {
+        let abort_on_drop = op.abort_on_drop();
+        let progress = op.progress().map(|f| Progress::new(f, op.content_length()));
         let (_, w) = acc.blocking_write(path, op)?;
 
-        Ok(BlockingWriter { inner: w })
+        Ok(BlockingWriter {
+            inner: w,
+            abort_on_drop,
+            finished: false,
+            progress,
+        })
     }
 
     /// Append data into writer.
@@ -210,12 +294,38 @@ impl BlockingWriter {
     /// into blocks of 4MiB (except the last block) for better performance
     /// and compatibility.
     pub fn append(&mut self, bs: impl Into<Bytes>) -> Result<()> {
-        self.inner.append(bs.into())
+        let bs = bs.into();
+        let size = bs.len();
+        self.inner.append(bs)?;
+        if let Some(p) = &mut self.progress {
+            p.advance(size);
+        }
+        Ok(())
+    }
+
+    /// Abort inner writer.
+    pub fn abort(&mut self) -> Result<()> {
+        self.finished = true;
+        self.inner.abort()
     }
 
     /// Close the writer and make sure all data have been stored.
     pub fn close(&mut self) -> Result<()> {
-        self.inner.close()
+        self.inner.close()?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for BlockingWriter {
+    fn drop(&mut self) {
+        if self.finished || !self.abort_on_drop {
+            return;
+        }
+
+        if let Err(err) = self.inner.abort() {
+            debug!("blocking writer: abort on drop failed: {err:?}");
+        }
     }
 }
 
@@ -231,3 +341,134 @@ impl io::Write for BlockingWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tests")]
+    #[tokio::test]
+    async fn test_writer_abort_on_drop() {
+        use std::time::Duration;
+
+        use crate::ops::OpWrite;
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockReply;
+        use crate::raw::Operation;
+        use crate::OperatorBuilder;
+
+        let mock = MockAccessor::new();
+        let op = OperatorBuilder::new(mock.clone()).finish();
+
+        // Simulate a cancelled task that holds an unclosed writer.
+        mock.push(Operation::Write, MockReply::ok());
+        let task = {
+            let op = op.clone();
+            tokio::spawn(async move {
+                let mut w = op.writer("cancelled").await.unwrap();
+                w.append("Hello, World!").await.unwrap();
+                futures::future::pending::<()>().await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        task.abort();
+        assert!(task.await.unwrap_err().is_cancelled());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(mock.aborted("cancelled"));
+        assert!(mock.written("cancelled").is_none());
+
+        // Closed writer should not be aborted.
+        mock.push(Operation::Write, MockReply::ok());
+        let mut w = op.writer("closed").await.unwrap();
+        w.append("Hello, World!").await.unwrap();
+        w.close().await.unwrap();
+        drop(w);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!mock.aborted("closed"));
+
+        // Abort on drop could be disabled.
+        mock.push(Operation::Write, MockReply::ok());
+        let args = OpWrite::new().with_abort_on_drop(false);
+        let mut w = op.writer_with("disabled", args).await.unwrap();
+        w.append("Hello, World!").await.unwrap();
+        drop(w);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!mock.aborted("disabled"));
+    }
+
+    #[tokio::test]
+    async fn test_writer_drop_with_pending_write() {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::atomic::Ordering;
+        use std::sync::Arc;
+
+        use async_trait::async_trait;
+        use futures::task::noop_waker;
+
+        use super::*;
+
+        /// PendingWriter never finishes append.
+        struct PendingWriter(Arc<AtomicBool>);
+
+        #[async_trait]
+        impl Write for PendingWriter {
+            async fn write(&mut self, _: Bytes) -> Result<()> {
+                futures::future::pending().await
+            }
+
+            async fn append(&mut self, _: Bytes) -> Result<()> {
+                futures::future::pending().await
+            }
+
+            async fn abort(&mut self) -> Result<()> {
+                self.0.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+
+            async fn close(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let aborted = Arc::new(AtomicBool::new(false));
+        let mut w = Writer {
+            state: State::Idle(Some(Box::new(PendingWriter(aborted.clone())))),
+            abort_on_drop: true,
+            finished: false,
+            progress: None,
+        };
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut w)
+            .poll_write(&mut cx, b"Hello, World!")
+            .is_pending());
+        drop(w);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(!aborted.load(Ordering::Relaxed));
+    }
+
+    #[cfg(feature = "tests")]
+    #[test]
+    fn test_blocking_writer_abort_on_drop() {
+        use crate::raw::tests::MockAccessor;
+        use crate::raw::tests::MockReply;
+        use crate::raw::Operation;
+        use crate::OperatorBuilder;
+
+        let mock = MockAccessor::new();
+        let op = OperatorBuilder::new(mock.clone()).finish().blocking();
+
+        mock.push(Operation::Write, MockReply::ok());
+        let mut w = op.writer("dropped").unwrap();
+        w.append("Hello, World!").unwrap();
+        drop(w);
+        assert!(mock.aborted("dropped"));
+        assert!(mock.written("dropped").is_none());
+
+        mock.push(Operation::Write, MockReply::ok());
+        let mut w = op.writer("closed").unwrap();
+        w.append("Hello, World!").unwrap();
+        w.close().unwrap();
+        drop(w);
+        assert!(!mock.aborted("closed"));
+    }
+}
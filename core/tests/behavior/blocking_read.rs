@@ -15,13 +15,6 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use anyhow::Result;
-use opendal::BlockingOperator;
-use opendal::EntryMode;
-use opendal::ErrorKind;
-use sha2::Digest;
-use sha2::Sha256;
-
 /// Test services that meet the following capability:
 ///
 /// - can_read
@@ -41,7 +34,7 @@ macro_rules! behavior_blocking_read_test {
                         match op {
                             Some(op) if op.info().can_read()
                                 && !op.info().can_write()
-                                && op.info().can_blocking() => $crate::blocking_read::$test(op.blocking()),
+                                && op.info().can_blocking() => opendal::raw::tests::blocking_read::$test(op.blocking()),
                             Some(_) => {
                                 log::warn!("service {} doesn't support blocking_read, ignored", opendal::Scheme::$service);
                                 Ok(())
@@ -75,75 +68,3 @@ macro_rules! behavior_blocking_read_tests {
         )*
     };
 }
-
-/// Stat normal file and dir should return metadata
-pub fn test_stat(op: BlockingOperator) -> Result<()> {
-    let meta = op.stat("normal_file")?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), 262144);
-
-    let meta = op.stat("normal_dir/")?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    Ok(())
-}
-
-/// Stat special file and dir should return metadata
-pub fn test_stat_special_chars(op: BlockingOperator) -> Result<()> {
-    let meta = op.stat("special_file  !@#$%^&()_+-=;',")?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), 262144);
-
-    let meta = op.stat("special_dir  !@#$%^&()_+-=;',/")?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    Ok(())
-}
-
-/// Stat not exist file should return NotFound
-pub fn test_stat_not_exist(op: BlockingOperator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let meta = op.stat(&path);
-    assert!(meta.is_err());
-    assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
-/// Read full content should match.
-pub fn test_read_full(op: BlockingOperator) -> Result<()> {
-    let bs = op.read("normal_file")?;
-    assert_eq!(bs.len(), 262144, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        "e7541d0f50d2d5c79dc41f28ccba8e0cdfbbc8c4b1aa1a0110184ef0ef67689f",
-        "read content"
-    );
-
-    Ok(())
-}
-
-/// Read full content should match.
-pub fn test_read_range(op: BlockingOperator) -> Result<()> {
-    let bs = op.range_read("normal_file", 1024..2048)?;
-    assert_eq!(bs.len(), 1024, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        "28786fb63abfe5545479e4f50da853652d1d67b88be5553c265ede4022774913",
-        "read content"
-    );
-
-    Ok(())
-}
-
-/// Read not exist file should return NotFound
-pub fn test_read_not_exist(op: BlockingOperator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let bs = op.read(&path);
-    assert!(bs.is_err());
-    assert_eq!(bs.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
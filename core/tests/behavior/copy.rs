@@ -15,12 +15,6 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use anyhow::Result;
-use opendal::ErrorKind;
-use opendal::Operator;
-
-use super::utils::*;
-
 /// Test services that meet the following capability:
 ///
 /// - can_read
@@ -40,7 +34,7 @@ macro_rules! behavior_copy_test {
                         match op {
                             Some(op) if op.info().can_read()
                               && op.info().can_write()
-                              && op.info().can_copy() => $crate::copy::$test(op).await,
+                              && op.info().can_copy() => opendal::raw::tests::copy::$test(op).await,
                             Some(_) => {
                                 log::warn!("service {} doesn't support copy, ignored", opendal::Scheme::$service);
                                 Ok(())
@@ -76,136 +70,3 @@ macro_rules! behavior_copy_tests {
         )*
     };
 }
-
-/// Copy a file and test with stat.
-pub async fn test_copy(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (source_content, _) = gen_bytes();
-
-    op.write(&source_path, source_content.clone()).await?;
-
-    let target_path = uuid::Uuid::new_v4().to_string();
-
-    op.copy(&source_path, &target_path).await?;
-
-    let target_content = op.read(&target_path).await.expect("read must succeed");
-    assert_eq!(target_content, source_content);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy a nonexistent source should return an error.
-pub async fn test_copy_non_existing_source(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let target_path = uuid::Uuid::new_v4().to_string();
-
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::NotFound);
-    Ok(())
-}
-
-/// Copy a dir as source should return an error.
-pub async fn test_copy_source_dir(op: Operator) -> Result<()> {
-    let source_path = format!("{}/", uuid::Uuid::new_v4());
-    let target_path = uuid::Uuid::new_v4().to_string();
-
-    op.create_dir(&source_path).await?;
-
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsADirectory);
-    Ok(())
-}
-
-/// Copy to a dir should return an error.
-pub async fn test_copy_target_dir(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&source_path, content).await?;
-
-    let target_path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&target_path).await?;
-
-    let err = op
-        .copy(&source_path, &target_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsADirectory);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy a file to self should return an error.
-pub async fn test_copy_self(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&source_path, content).await?;
-
-    let err = op
-        .copy(&source_path, &source_path)
-        .await
-        .expect_err("copy must fail");
-    assert_eq!(err.kind(), ErrorKind::IsSameFile);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy to a nested path, parent path should be created successfully.
-pub async fn test_copy_nested(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (source_content, _) = gen_bytes();
-
-    op.write(&source_path, source_content.clone()).await?;
-
-    let target_path = format!(
-        "{}/{}/{}",
-        uuid::Uuid::new_v4(),
-        uuid::Uuid::new_v4(),
-        uuid::Uuid::new_v4()
-    );
-
-    op.copy(&source_path, &target_path).await?;
-
-    let target_content = op.read(&target_path).await.expect("read must succeed");
-    assert_eq!(target_content, source_content);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Copy to a exist path should overwrite successfully.
-pub async fn test_copy_overwrite(op: Operator) -> Result<()> {
-    let source_path = uuid::Uuid::new_v4().to_string();
-    let (source_content, _) = gen_bytes();
-
-    op.write(&source_path, source_content.clone()).await?;
-
-    let target_path = uuid::Uuid::new_v4().to_string();
-    let (target_content, _) = gen_bytes();
-    assert_ne!(source_content, target_content);
-
-    op.write(&target_path, target_content).await?;
-
-    op.copy(&source_path, &target_path).await?;
-
-    let target_content = op.read(&target_path).await.expect("read must succeed");
-    assert_eq!(target_content, source_content);
-
-    op.delete(&source_path).await.expect("delete must succeed");
-    op.delete(&target_path).await.expect("delete must succeed");
-    Ok(())
-}
@@ -15,13 +15,6 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::collections::HashMap;
-
-use anyhow::Result;
-use futures::TryStreamExt;
-use opendal::EntryMode;
-use opendal::Operator;
-
 /// Test services that meet the following capability:
 ///
 /// - !can_write
@@ -38,7 +31,7 @@ macro_rules! behavior_list_only_test {
                     async fn [< $test >]() -> anyhow::Result<()> {
                         let op = $crate::utils::init_service::<opendal::services::$service>(false);
                         match op {
-                            Some(op) if op.info().can_list() && !op.info().can_write() => $crate::list_only::$test(op).await,
+                            Some(op) if op.info().can_list() && !op.info().can_write() => opendal::raw::tests::list_only::$test(op).await,
                             Some(_) => {
                                 log::warn!("service {} doesn't support list, ignored", opendal::Scheme::$service);
                                 Ok(())
@@ -67,21 +60,3 @@ macro_rules! behavior_list_only_tests {
         )*
     };
 }
-
-/// Stat normal file and dir should return metadata
-pub async fn test_list(op: Operator) -> Result<()> {
-    let mut entries = HashMap::new();
-
-    let mut ds = op.list("/").await?;
-    while let Some(de) = ds.try_next().await? {
-        entries.insert(de.path().to_string(), op.stat(de.path()).await?.mode());
-    }
-
-    assert_eq!(entries["normal_file"], EntryMode::FILE);
-    assert_eq!(entries["special_file  !@#$%^&()_+-=;',"], EntryMode::FILE);
-
-    assert_eq!(entries["normal_dir/"], EntryMode::DIR);
-    assert_eq!(entries["special_dir  !@#$%^&()_+-=;',/"], EntryMode::DIR);
-
-    Ok(())
-}
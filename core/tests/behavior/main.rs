@@ -77,23 +77,34 @@ macro_rules! behavior_tests {
 
 behavior_tests!(Azblob);
 behavior_tests!(Azdfs);
+cfg_if::cfg_if! { if #[cfg(feature = "services-azfile")] { behavior_tests!(Azfile); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-b2")] { behavior_tests!(B2); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-cloudflare-kv")] { behavior_tests!(CloudflareKv); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-dashmap")] { behavior_tests!(Dashmap); }}
 behavior_tests!(Fs);
+cfg_if::cfg_if! { if #[cfg(feature = "services-etcd")] { behavior_tests!(Etcd); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-ftp")] { behavior_tests!(Ftp); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-memcached")] { behavior_tests!(Memcached); }}
 behavior_tests!(Memory);
 cfg_if::cfg_if! { if #[cfg(feature = "services-moka")] { behavior_tests!(Moka); }}
 behavior_tests!(Gcs);
 behavior_tests!(Ghac);
+cfg_if::cfg_if! { if #[cfg(feature = "services-gridfs")] { behavior_tests!(Gridfs); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-mysql")] { behavior_tests!(Mysql); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-ipfs")] { behavior_tests!(Ipfs); }}
 behavior_tests!(Ipmfs);
 cfg_if::cfg_if! { if #[cfg(feature = "services-hdfs")] { behavior_tests!(Hdfs); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-http")] { behavior_tests!(Http); }}
 behavior_tests!(Obs);
+cfg_if::cfg_if! { if #[cfg(feature = "services-postgres")] { behavior_tests!(Postgres); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-redis")] { behavior_tests!(Redis); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-rocksdb")] { behavior_tests!(Rocksdb); }}
 behavior_tests!(Oss);
 behavior_tests!(S3);
+cfg_if::cfg_if! { if #[cfg(feature = "services-sftp")] { behavior_tests!(Sftp); }}
 cfg_if::cfg_if! { if #[cfg(feature = "services-sled")] { behavior_tests!(Sled); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-sqlite")] { behavior_tests!(Sqlite); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-swift")] { behavior_tests!(Swift); }}
+cfg_if::cfg_if! { if #[cfg(feature = "services-tikv")] { behavior_tests!(Tikv); }}
 behavior_tests!(Webdav);
 behavior_tests!(Webhdfs);
@@ -15,20 +15,6 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use anyhow::Result;
-use futures::AsyncReadExt;
-use futures::AsyncSeekExt;
-use futures::StreamExt;
-use log::debug;
-use log::warn;
-use opendal::EntryMode;
-use opendal::ErrorKind;
-use opendal::Operator;
-use sha2::Digest;
-use sha2::Sha256;
-
-use super::utils::*;
-
 /// Test services that meet the following capability:
 ///
 /// - can_read
@@ -45,7 +31,7 @@ macro_rules! behavior_write_test {
                     async fn [< $test >]() -> anyhow::Result<()> {
                         let op = $crate::utils::init_service::<opendal::services::$service>(true);
                         match op {
-                            Some(op) if op.info().can_read() && op.info().can_write() => $crate::write::$test(op).await,
+                            Some(op) if op.info().can_read() && op.info().can_write() => opendal::raw::tests::write::$test(op).await,
                             Some(_) => {
                                 log::warn!("service {} doesn't support write, ignored", opendal::Scheme::$service);
                                 Ok(())
@@ -79,8 +65,16 @@ macro_rules! behavior_write_tests {
                 test_stat_with_special_chars,
                 test_stat_not_cleaned_path,
                 test_stat_not_exist,
+                test_stat_file_with_dir_path,
+                test_stat_dir_with_file_path,
+                test_stat_nested_parent_dir,
                 test_stat_root,
+                test_read_with_progress,
                 test_read_full,
+                test_read_into,
+                test_download,
+                test_download_empty,
+                test_checksum,
                 test_read_range,
                 test_read_large_range,
                 test_reader_range,
@@ -91,10 +85,14 @@ macro_rules! behavior_write_tests {
                 test_fuzz_offset_reader,
                 test_fuzz_part_reader,
                 test_read_with_dir_path,
+                test_read_dir_with_file_path,
                 test_read_with_special_chars,
                 test_delete,
                 test_delete_empty_dir,
                 test_delete_with_special_chars,
+                test_delete_file_with_dir_path,
+                test_delete_dir_with_file_path,
+                test_writer_with_progress,
                 test_delete_not_existing,
                 test_delete_stream,
                 test_append,
@@ -103,623 +101,3 @@ macro_rules! behavior_write_tests {
         )*
     };
 }
-
-/// Create dir with dir path should succeed.
-pub async fn test_create_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await?;
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Create dir on existing dir should succeed.
-pub async fn test_create_dir_existing(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await?;
-
-    op.create_dir(&path).await?;
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Write a single file and test with stat.
-pub async fn test_write(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content).await?;
-
-    let meta = op.stat(&path).await.expect("stat must succeed");
-    assert_eq!(meta.content_length(), size as u64);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Write file with dir path should return an error
-pub async fn test_write_with_dir_path(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-    let (content, _) = gen_bytes();
-
-    let result = op.write(&path, content).await;
-    assert!(result.is_err());
-    assert_eq!(result.unwrap_err().kind(), ErrorKind::IsADirectory);
-
-    Ok(())
-}
-
-/// Write a single file with special chars should succeed.
-pub async fn test_write_with_special_chars(op: Operator) -> Result<()> {
-    let path = format!("{} !@#$%^&()_+-=;',.txt", uuid::Uuid::new_v4());
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content).await?;
-
-    let meta = op.stat(&path).await.expect("stat must succeed");
-    assert_eq!(meta.content_length(), size as u64);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Stat existing file should return metadata
-pub async fn test_stat(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), size as u64);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Stat existing file should return metadata
-pub async fn test_stat_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await.expect("write must succeed");
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Stat existing file with special chars should return metadata
-pub async fn test_stat_with_special_chars(op: Operator) -> Result<()> {
-    let path = format!("{} !@#$%^&()_+-=;',.txt", uuid::Uuid::new_v4());
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    let meta = op.stat(&path).await?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), size as u64);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Stat not cleaned path should also succeed.
-pub async fn test_stat_not_cleaned_path(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    let meta = op.stat(&format!("//{}", &path)).await?;
-    assert_eq!(meta.mode(), EntryMode::FILE);
-    assert_eq!(meta.content_length(), size as u64);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Stat not exist file should return NotFound
-pub async fn test_stat_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let meta = op.stat(&path).await;
-    assert!(meta.is_err());
-    assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
-/// Root should be able to stat and returns DIR.
-pub async fn test_stat_root(op: Operator) -> Result<()> {
-    let meta = op.stat("").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    let meta = op.stat("/").await?;
-    assert_eq!(meta.mode(), EntryMode::DIR);
-
-    Ok(())
-}
-
-/// Read full content should match.
-pub async fn test_read_full(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let bs = op.read(&path).await?;
-    assert_eq!(size, bs.len(), "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content)),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read range content should match.
-pub async fn test_read_range(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (offset, length) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let bs = op.range_read(&path, offset..offset + length).await?;
-    assert_eq!(bs.len() as u64, length, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!(
-            "{:x}",
-            Sha256::digest(&content[offset as usize..(offset + length) as usize])
-        ),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read large range content should match.
-pub async fn test_read_large_range(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (offset, _) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let bs = op.range_read(&path, offset..u32::MAX as u64).await?;
-    assert_eq!(
-        bs.len() as u64,
-        size as u64 - offset,
-        "read size with large range"
-    );
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content[offset as usize..])),
-        "read content with large range"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read range content should match.
-pub async fn test_reader_range(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (offset, length) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut r = op.range_reader(&path, offset..offset + length).await?;
-
-    let mut bs = Vec::new();
-    r.read_to_end(&mut bs).await?;
-
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!(
-            "{:x}",
-            Sha256::digest(&content[offset as usize..(offset + length) as usize])
-        ),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read range from should match.
-pub async fn test_reader_from(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (offset, _) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut r = op.range_reader(&path, offset..).await?;
-
-    let mut bs = Vec::new();
-    r.read_to_end(&mut bs).await?;
-
-    assert_eq!(bs.len(), size - offset as usize, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content[offset as usize..])),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read range tail should match.
-pub async fn test_reader_tail(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (_, length) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut r = match op.range_reader(&path, ..length).await {
-        Ok(r) => r,
-        // Not all services support range with tail range, let's tolerate this.
-        Err(err) if err.kind() == ErrorKind::Unsupported => {
-            warn!("service doesn't support range with tail");
-            return Ok(());
-        }
-        Err(err) => return Err(err.into()),
-    };
-
-    let mut bs = Vec::new();
-    r.read_to_end(&mut bs).await?;
-
-    assert_eq!(bs.len(), length as usize, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content[size - length as usize..])),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read not exist file should return NotFound
-pub async fn test_read_not_exist(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    let bs = op.read(&path).await;
-    assert!(bs.is_err());
-    assert_eq!(bs.unwrap_err().kind(), ErrorKind::NotFound);
-
-    Ok(())
-}
-
-pub async fn test_fuzz_range_reader(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, _) = gen_bytes();
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut fuzzer = ObjectReaderFuzzer::new(&path, content.clone(), 0, content.len());
-    let mut o = op.range_reader(&path, 0..content.len() as u64).await?;
-
-    for _ in 0..100 {
-        match fuzzer.fuzz() {
-            ObjectReaderAction::Read(size) => {
-                let mut bs = vec![0; size];
-                let n = o.read(&mut bs).await?;
-                fuzzer.check_read(n, &bs[..n])
-            }
-            ObjectReaderAction::Seek(input_pos) => {
-                let actual_pos = o.seek(input_pos).await?;
-                fuzzer.check_seek(input_pos, actual_pos)
-            }
-            ObjectReaderAction::Next => {
-                let actual_bs = o
-                    .next()
-                    .await
-                    .map(|v| v.expect("next should not return error"));
-                fuzzer.check_next(actual_bs)
-            }
-        }
-    }
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-pub async fn test_fuzz_offset_reader(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, _) = gen_bytes();
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut fuzzer = ObjectReaderFuzzer::new(&path, content.clone(), 0, content.len());
-    let mut o = op.range_reader(&path, 0..).await?;
-
-    for _ in 0..100 {
-        match fuzzer.fuzz() {
-            ObjectReaderAction::Read(size) => {
-                let mut bs = vec![0; size];
-                let n = o.read(&mut bs).await?;
-                fuzzer.check_read(n, &bs[..n])
-            }
-            ObjectReaderAction::Seek(input_pos) => {
-                let actual_pos = o.seek(input_pos).await?;
-                fuzzer.check_seek(input_pos, actual_pos)
-            }
-            ObjectReaderAction::Next => {
-                let actual_bs = o
-                    .next()
-                    .await
-                    .map(|v| v.expect("next should not return error"));
-                fuzzer.check_next(actual_bs)
-            }
-        }
-    }
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-pub async fn test_fuzz_part_reader(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-    let (offset, length) = gen_offset_length(size);
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let mut fuzzer =
-        ObjectReaderFuzzer::new(&path, content.clone(), offset as usize, length as usize);
-    let mut o = op.range_reader(&path, offset..offset + length).await?;
-
-    for _ in 0..100 {
-        match fuzzer.fuzz() {
-            ObjectReaderAction::Read(size) => {
-                let mut bs = vec![0; size];
-                let n = o.read(&mut bs).await?;
-                fuzzer.check_read(n, &bs[..n])
-            }
-            ObjectReaderAction::Seek(input_pos) => {
-                let actual_pos = o.seek(input_pos).await?;
-                fuzzer.check_seek(input_pos, actual_pos)
-            }
-            ObjectReaderAction::Next => {
-                let actual_bs = o
-                    .next()
-                    .await
-                    .map(|v| v.expect("next should not return error"));
-                fuzzer.check_next(actual_bs)
-            }
-        }
-    }
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read with dir path should return an error.
-pub async fn test_read_with_dir_path(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await.expect("write must succeed");
-
-    let result = op.read(&path).await;
-    assert!(result.is_err());
-    assert_eq!(result.unwrap_err().kind(), ErrorKind::IsADirectory);
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-/// Read file with special chars should succeed.
-pub async fn test_read_with_special_chars(op: Operator) -> Result<()> {
-    let path = format!("{} !@#$%^&()_+-=;',.txt", uuid::Uuid::new_v4());
-    debug!("Generate a random file: {}", &path);
-    let (content, size) = gen_bytes();
-
-    op.write(&path, content.clone())
-        .await
-        .expect("write must succeed");
-
-    let bs = op.read(&path).await?;
-    assert_eq!(size, bs.len(), "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs)),
-        format!("{:x}", Sha256::digest(&content)),
-        "read content"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}
-
-// Delete existing file should succeed.
-pub async fn test_abort_writer(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    let mut writer = match op.writer(&path).await {
-        Ok(writer) => writer,
-        Err(e) => {
-            assert_eq!(e.kind(), ErrorKind::Unsupported);
-            return Ok(());
-        }
-    };
-
-    if let Err(e) = writer.append(content).await {
-        assert_eq!(e.kind(), ErrorKind::Unsupported);
-        return Ok(());
-    }
-
-    if let Err(e) = writer.abort().await {
-        assert_eq!(e.kind(), ErrorKind::Unsupported);
-        return Ok(());
-    }
-
-    // Aborted writer should not write actual file.
-    assert!(!op.is_exist(&path).await?);
-    Ok(())
-}
-
-// Delete existing file should succeed.
-pub async fn test_delete(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let (content, _) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    op.delete(&path).await?;
-
-    // Stat it again to check.
-    assert!(!op.is_exist(&path).await?);
-
-    Ok(())
-}
-
-// Delete empty dir should succeed.
-pub async fn test_delete_empty_dir(op: Operator) -> Result<()> {
-    let path = format!("{}/", uuid::Uuid::new_v4());
-
-    op.create_dir(&path).await.expect("create must succeed");
-
-    op.delete(&path).await?;
-
-    Ok(())
-}
-
-// Delete file with special chars should succeed.
-pub async fn test_delete_with_special_chars(op: Operator) -> Result<()> {
-    let path = format!("{} !@#$%^&()_+-=;',.txt", uuid::Uuid::new_v4());
-    debug!("Generate a random file: {}", &path);
-    let (content, _) = gen_bytes();
-
-    op.write(&path, content).await.expect("write must succeed");
-
-    op.delete(&path).await?;
-
-    // Stat it again to check.
-    assert!(!op.is_exist(&path).await?);
-
-    Ok(())
-}
-
-// Delete not existing file should also succeed.
-pub async fn test_delete_not_existing(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-
-    op.delete(&path).await?;
-
-    Ok(())
-}
-
-// Delete via stream.
-pub async fn test_delete_stream(op: Operator) -> Result<()> {
-    let dir = uuid::Uuid::new_v4().to_string();
-    op.create_dir(&format!("{dir}/"))
-        .await
-        .expect("creat must succeed");
-
-    let expected: Vec<_> = (0..100).collect();
-    for path in expected.iter() {
-        op.write(&format!("{dir}/{path}"), "delete_stream").await?;
-    }
-
-    op.with_limit(30)
-        .remove_via(futures::stream::iter(expected.clone()).map(|v| format!("{dir}/{v}")))
-        .await?;
-
-    // Stat it again to check.
-    for path in expected.iter() {
-        assert!(
-            !op.is_exist(&format!("{dir}/{path}")).await?,
-            "{path} should be removed"
-        )
-    }
-
-    Ok(())
-}
-
-// Append write
-pub async fn test_append(op: Operator) -> Result<()> {
-    let path = uuid::Uuid::new_v4().to_string();
-    let size = 5 * 1024 * 1024; // write file with 5 MiB
-    let content_a = gen_fixed_bytes(size);
-    let content_b = gen_fixed_bytes(size);
-
-    let mut w = match op.writer(&path).await {
-        Ok(w) => w,
-        Err(err) if err.kind() == ErrorKind::Unsupported => {
-            warn!("service doesn't support write with append");
-            return Ok(());
-        }
-        Err(err) => return Err(err.into()),
-    };
-    w.append(content_a.clone()).await?;
-    w.append(content_b.clone()).await?;
-    w.close().await?;
-
-    let meta = op.stat(&path).await.expect("stat must succeed");
-    assert_eq!(meta.content_length(), (size * 2) as u64);
-
-    let bs = op.read(&path).await?;
-    assert_eq!(bs.len(), size * 2, "read size");
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs[..size])),
-        format!("{:x}", Sha256::digest(content_a)),
-        "read content a"
-    );
-    assert_eq!(
-        format!("{:x}", Sha256::digest(&bs[size..])),
-        format!("{:x}", Sha256::digest(content_b)),
-        "read content b"
-    );
-
-    op.delete(&path).await.expect("delete must succeed");
-    Ok(())
-}